@@ -0,0 +1,17 @@
+# Names of the bundled bodies, as body-<id>. Bodies from scenarios without
+# a name here keep the name given in the scenario
+
+body-sun = Sun
+body-mercury = Mercury
+body-venus = Venus
+body-earth = Earth
+body-moon = Moon
+body-mars = Mars
+body-jupiter = Jupiter
+body-saturn = Saturn
+body-uranus = Uranus
+body-neptune = Neptune
+body-pluto = Pluto
+body-136199_eris = 136199 Eris
+body-136108_haumea = 136108 Haumea
+body-136472_makemake = 136472 Makemake
@@ -0,0 +1,45 @@
+# Text shown in the Help window
+
+help-keyboard-controls = Keyboard Controls
+help-move = Move the camera forward/left/right/back
+help-move-vertically = Move the camera up/down
+help-roll = Roll the camera left/right
+help-statistics = Show/hide the frame rate and simulation statistics
+help-quick-save = Quick save/load the slot selected in the Save window
+help-capture = Capture the next frame, when launched from RenderDoc
+help-double-click = Double click
+help-follow = Follow the body under the cursor with the camera
+
+help-gamepad-controls = Gamepad Controls
+help-left-stick = Left stick
+help-right-stick = Right stick
+help-bumpers = Bumpers
+help-triggers = Triggers
+help-turn = Turn the camera
+help-change-speed = Slow down/speed up the camera
+
+help-start-tutorial = Start the Tutorial
+help-start-tutorial-hover = A guided tour of moving around, editing bodies, time and saving
+
+## Tutorial
+
+tutorial-title = Tutorial
+tutorial-step = Step { $step } of { $steps }
+tutorial-step-done = ✔ Well done!
+tutorial-step-waiting = Try it to carry on, or skip this step.
+tutorial-back = Back
+tutorial-next = Next
+tutorial-skip = Skip
+tutorial-finish = Finish
+tutorial-welcome-title = Welcome
+tutorial-welcome = This is a simulation of the Solar System, where every body is pulled by the gravity of every other. This tutorial shows you the basics, and can be started again from the Help window.
+tutorial-camera-title = Moving the Camera
+tutorial-camera = Use W, A, S and D to fly around, Space and Shift to go up and down, and Q and E to roll. Scroll to change how fast the camera moves. Try moving the camera now.
+tutorial-edit-body-title = Editing a Body
+tutorial-edit-body = Open a body's window from the Bodies section of the Global State window, then drag its position, velocity or mass to change it. Try changing a body now.
+tutorial-time-scale-title = Changing the Time Scale
+tutorial-time-scale = The time scale in the Time section sets how much time passes each second. Try changing it now, or pause the simulation to look around.
+tutorial-saving-title = Saving
+tutorial-saving = Save the simulation from the Save Simulation window, or press F5 to quick save and F9 to quick load. Try saving now.
+tutorial-finished-title = All Done
+tutorial-finished = That's everything you need to get started. The Help window lists all the controls.
@@ -0,0 +1,508 @@
+# Text shown in the panels and windows
+
+## Shared
+
+reset = Reset
+dismiss = Dismiss
+choose-a-body = Choose a body
+first-body = First:
+second-body = Second:
+
+## Global State window
+
+global-title = Global State
+new-simulation-title = New Simulation
+save-simulation-title = Save Simulation
+load-simulation-title = Load Simulation
+open-scenario-title = Open Scenario
+dial-link = Longitude Dial
+conjunctions-title = Conjunction Finder
+periods-title = Orbital Periods
+compare-title = Compare Bodies
+experiments-title = Experiments
+sandbox-title = What If
+camera-path-title = Camera Path
+console-title = Script Console
+render-settings-title = Render Settings
+render-stats-title = Render Stats
+logs-title = Log
+layouts-title = Layouts
+help-title = Help
+about-title = About
+
+bodies-section = Bodies
+bodies-go-to = Go to
+bodies-go-to-hover = Fly the camera to this body
+bodies-search = Search
+bodies-sort = Sort by:
+bodies-sort-name = Name
+bodies-sort-mass = Mass
+bodies-sort-distance = Distance from the Sun
+bodies-grouped = Group by type
+bodies-none-found = No bodies match the search
+
+camera-section = Camera
+camera-position = Position:
+camera-speed = Speed:
+camera-controller = Controller:
+camera-controller-free = Free
+camera-controller-orbit = Orbit
+camera-follow = Follow:
+camera-follow-hover = Double click a body to follow it
+camera-offset = Offset:
+camera-smoothing = Smoothing:
+camera-smoothing-hover = How long the camera takes to catch up with the body
+camera-fly-duration = Fly to duration:
+camera-fly-duration-hover = How long the camera takes to fly to a body
+camera-gamepad = Gamepad
+camera-gamepad-hover = Control the camera with a connected gamepad
+camera-dead-zone = Dead zone:
+camera-dead-zone-hover = How far the sticks and triggers move before they do anything
+
+constants-section = Constants
+constants-gravitational-constant = Gravitational Constant:
+
+integrator-section = Integrator
+integrator-analytic-encounters = Step close encounters analytically
+integrator-analytic-encounters-hover =
+    Pairs of bodies too close for the time step are stepped as an exact two body orbit, keeping slingshots accurate
+integrator-minimum-steps = Minimum Steps per Orbit:
+
+time-section = Time
+time-scale = Time Scale:
+time-pause = Pause
+time-resume = Resume
+time-date = Date:
+time-time = Time:
+time-julian-date = Julian Date:
+
+stopwatch = Stopwatch:
+stopwatch-start = Start
+stopwatch-stop = Stop
+stopwatch-lap = Lap
+stopwatch-lap-number = Lap { $number }:
+
+display-section = Display
+display-labels = Body labels
+display-body-size = Body Size:
+display-true-scale = True scale
+display-exaggerated = Exaggerated
+display-exaggeration = Exaggeration:
+display-exaggeration-hover = Stars are exaggerated by the square root of this
+display-grid = Ecliptic grid
+display-axes = Axes at the origin
+display-ruler = Measure distances
+display-ruler-hover = Click two bodies, or points on the ecliptic, to measure the distance between them
+display-arrows = Arrows:
+display-velocity-arrows = Velocity
+display-acceleration-arrows = Acceleration
+display-arrows-hover = Draw each body's velocity and the net pull on it, which bends its path into an orbit. Arrows grow with the logarithm of the vector
+display-field = Gravitational field
+display-field-hover =
+    Arrows across the ecliptic pointing the way gravity pulls, brighter where it is stronger
+display-trails = Trails coloured by
+display-colourmap = Colourmap:
+display-trail-width = Trail width:
+display-trail-width-hover = Trails wider than a pixel are drawn as ribbons facing the camera
+display-cursor = Cursor:
+display-cursor-size = Cursor Size:
+display-reticle = Center reticle
+display-attract-mode = Attract mode after
+display-attract-mode-idle = idle
+display-units = Units:
+units-si = SI
+units-astronomical = Astronomical
+units-imperial = Imperial
+
+trail-colouring-body-colour = Body colour
+trail-colouring-speed = Speed
+trail-colouring-specific-energy = Specific energy
+trail-colouring-acceleration = Acceleration
+
+cursor-system = System
+cursor-crosshair = Crosshair
+cursor-circle = Circle
+cursor-dot = Dot
+
+accessibility-section = Accessibility
+accessibility-narration = Narrate events
+accessibility-high-contrast = High contrast and large text
+accessibility-ui-scale = Interface scale:
+accessibility-palette = Body colours:
+accessibility-palette-hover = Colours for the bodies and their trails which can be told apart with colour blindness
+
+palette-natural = Natural
+palette-okabe-ito = Okabe-Ito
+palette-tol-bright = Tol bright
+
+fonts-section = Fonts
+fonts-interface = Interface:
+fonts-keys = Keys:
+fonts-monospace = Monospace:
+fonts-built-in = Built-in
+fonts-same-as-interface = Same as interface
+
+audio-section = Audio
+audio-no-music = No music is playing
+audio-volume = Volume:
+audio-mute = Mute
+audio-play = Play
+audio-pause = Pause
+audio-skip = Skip
+audio-shuffle = Shuffle
+audio-repeat = Repeat:
+audio-crossfade = Crossfade:
+audio-crossfade-hover = How long each track fades into the next
+audio-playing = Playing: { $track }
+audio-paused = Paused: { $track }
+audio-playlist-ended = Reached the end of the playlist
+audio-user-music-hover = Music added to { $folder } is played after the bundled music
+
+repeat-all = All
+repeat-one = One
+repeat-off = Off
+
+language-section = Language
+
+autosave-section = Autosave
+autosave-enabled = Autosave
+autosave-enabled-hover = Saves are written to { $folder }
+autosave-interval = Every:
+autosave-minutes = { " " }min
+autosave-clock = Of:
+autosave-clock-real = Real time
+autosave-clock-simulated = Simulated time
+autosave-kept = Keep:
+autosave-saves = { " " }saves
+autosave-kept-hover = Older autosaves are removed
+
+diagnostics-section = Diagnostics
+diagnostics-memory = History Memory:
+diagnostics-memory-used = { $used } MiB of
+diagnostics-memory-hover = When recorded history uses more than this, the oldest data is thinned out
+diagnostics-pruned =
+    Pruned { $points ->
+        [one] { $points } trail point
+       *[other] { $points } trail points
+    } over { $prunes ->
+        [one] { $prunes } prune
+       *[other] { $prunes } prunes
+    }, last at { $last }
+diagnostics-nothing-pruned = Nothing has been pruned
+
+## New, Save and Load windows
+
+new-simulation-scenarios = Start a new simulation from a scenario:
+new-simulation-no-scenarios = No scenarios found
+new-simulation-open-file = Open File
+new-simulation-refresh = Refresh
+save-as = Save as { $format }
+load-from = Load from { $format }
+quick-slots = Quick slots:
+quick-slots-hover = F5 saves to the selected slot, F9 loads from it
+quick-slot = Slot { $slot }
+quick-slot-empty = Empty
+quick-slot-save = Save
+quick-slot-load = Load
+simulation-loaded = Simulation loaded
+simulation-saved = Saved the simulation to { $path }
+save-failed = Failed to save
+load-failed = Failed to load
+quick-save-failed = Failed to quick save
+quick-load-failed = Failed to quick load
+scenario-failed = Failed to start the scenario
+dropped-file-title = Load File
+dropped-file-confirm = Load { $file }? The current simulation will be replaced.
+dropped-file-load = Load
+dropped-file-cancel = Cancel
+autosave-failed = Failed to autosave: { $error }
+screenshot-saved = Saved a screenshot to { $path }
+screenshot-failed = Failed to take a screenshot: { $error }
+device-recreated = The graphics device was lost and has been recreated
+
+## About window
+
+about-version = Version { $version }
+about-author = By { $author }
+about-usage-statistics = Usage statistics, these never leave this computer
+about-time-run = Time run:
+about-sessions = Sessions:
+about-bodies-created = Bodies created:
+about-saves-made = Saves made:
+about-features-used = Features Used
+
+## Body windows
+
+planet-name = Name:
+planet-id = ID:
+planet-fly-to = Fly To
+planet-fly-to-hover = Move the camera to this body
+planet-position = Position:
+planet-velocity = Velocity:
+planet-mass = Mass:
+planet-mass-slider-hover = Drag to change the mass on a logarithmic scale
+planet-earth-mass = Earth
+planet-jupiter-mass = Jupiter
+planet-solar-mass = Sun
+planet-surface-gravity = Surface gravity:
+planet-escape-velocity = Escape velocity:
+planet-mass-rate = Mass rate:
+planet-mass-rate-hover = Mass gained each second, negative to lose mass
+planet-light = Light
+planet-colour = Colour:
+planet-intensity = Intensity:
+planet-luminosity = Luminosity:
+planet-luminosity-hover =
+    Brightness compared to the Sun, light gets dimmer with the square of the distance from the star
+planet-atmosphere = Atmosphere
+planet-thickness = Thickness:
+planet-thickness-hover = How far the atmosphere extends above the surface, relative to the radius
+planet-rings = Rings
+planet-rings-inner-radius = Inner radius:
+planet-rings-outer-radius = Outer radius:
+planet-rings-radius-hover = How far the edge of the rings is from the centre of the body, in multiples of its radius
+planet-rings-tilt = Tilt:
+planet-rings-tilt-hover = The angle between the rings, which lie along the equator, and the plane of the orbits
+planet-rings-opacity = Opacity:
+planet-rings-opacity-hover = How much of the light behind the densest parts of the rings is blocked
+planet-appearance = Appearance
+planet-colour-textured-hover = The body is drawn with its texture, so its colour is only used for its trail and label
+planet-trail-colour = Trail colour
+planet-trail-colour-hover = Draw the trail in its own colour rather than the body's, when trails are coloured by body
+planet-label-shown = Show label
+planet-properties = Properties
+planet-info = Info
+facts-radius = Mean radius:
+facts-day-length = Day length:
+facts-retrograde = { $period } (retrograde)
+facts-composition = Composition:
+facts-discovery = Discovered:
+facts-discovered = { $year } by { $by }
+facts-antiquity = Known since antiquity
+material-hydrogen = Hydrogen
+material-helium = Helium
+material-rock = Silicate rock
+material-iron = Iron
+material-water = Water
+material-carbon-dioxide = Carbon dioxide
+material-ices = Water, ammonia and methane ices
+material-nitrogen-ice = Nitrogen ice
+material-methane-ice = Methane ice
+planet-arrows-shown = Show arrows
+planet-arrows-shown-hover = Draw this body's velocity and acceleration arrows, when they are turned on
+planet-lineage = Lineage
+planet-merged-at = Merged at { $time }
+bodies-merged = { $absorbed } merged with { $survivor }
+
+## Heliocentric Longitude window
+
+dial-title = Heliocentric Longitude
+
+## Conjunction Finder window
+
+conjunctions-find = Find Next
+conjunctions-find-hover = Find the next conjunction or opposition as seen from the Sun
+conjunctions-found = { $kind } in { $time }
+conjunctions-jump = Jump to Alignment
+conjunctions-not-found = No alignment in the next 200 years
+conjunctions-jumped =
+    { $kind ->
+        [conjunction] Jumped to the conjunction
+       *[opposition] Jumped to the opposition
+    }
+alignment-conjunction = Conjunction
+alignment-opposition = Opposition
+
+## Orbital Periods window
+
+periods-orbits = Orbits:
+periods-semi-major-axis = Semi-major axis:
+periods-sidereal = Sidereal period:
+periods-sidereal-hover = From the semi-major axis, by Kepler's third law
+periods-measured = Measured period:
+periods-measured-hover = Time taken by the last full orbit in the simulation
+periods-difference = Difference:
+periods-no-synodic = The bodies don't orbit the same body, so have no synodic period
+periods-synodic = Synodic period:
+periods-synodic-hover = Time between the bodies lining up
+periods-synodic-measured = From measured periods:
+periods-unknown-body = Unknown
+
+## Compare Bodies window
+
+compare-mass-ratio = Mass ratio:
+compare-mass-ratio-hover = The mass of the first body over the mass of the second
+compare-distance = Distance:
+compare-relative-velocity = Relative velocity:
+compare-period-ratio = Orbital period ratio:
+compare-period-ratio-hover = The sidereal period of the first body over that of the second, each around the body it orbits
+
+## Experiments window
+
+experiments-description = Change the gravitational constant between groups of bodies
+experiments-overrides = Gravity Overrides
+experiments-and = and
+experiments-add-override = Add Override
+experiments-groups = Groups
+experiments-no-groups = Nothing
+group-stars = Stars
+group-planets = Planets
+group-moons = Moons
+group-a = Group A
+group-b = Group B
+
+## What If window
+
+sandbox-description = Run the simulation alongside a copy with bodies removed or their masses changed, and plot how far the tracked bodies drift apart. Moons move with their planet
+sandbox-body = Body
+sandbox-removed = Removed
+sandbox-mass = Mass
+sandbox-tracked = Tracked
+sandbox-duration = Duration:
+sandbox-run = Branch and Run
+sandbox-run-hover = Copy the simulation as it is now and run both copies, without changing the simulation
+sandbox-stop = Stop
+sandbox-apply = Apply to Simulation
+sandbox-apply-hover = Make the same changes to the simulation itself
+sandbox-reset = Reset
+sandbox-plot = Distance between the branches ({ $unit }) over time (years)
+
+## Camera Path window
+
+camera-path-play = Play
+camera-path-stop = Stop
+camera-path-play-disabled-hover = Place at least two keyframes
+camera-path-loop = Loop
+camera-path-view-hover = Move the camera to this keyframe
+camera-path-add-keyframe = Add Keyframe
+camera-path-add-keyframe-hover = Place a keyframe where the camera is
+camera-path-clear = Clear
+
+## Script Console window
+
+console-run = Run
+console-clear = Clear
+console-reset-hover = Forget the variables, functions and callbacks of every script
+console-callbacks =
+    { $count ->
+        [one] { $count } callback
+       *[other] { $count } callbacks
+    }
+
+## Render Settings window
+
+render-present-mode = Present mode:
+render-present-mode-unsupported = Not supported by this adapter
+render-backend = Backend:
+render-surface-format = Surface format:
+render-automatic = Automatic
+render-reflections = Reflections:
+render-reflections-hover = Metallic surfaces reflect the stars and the Sun
+render-stars = Stars
+render-stars-hover = Draw the brightest real stars at their positions on the sky, so the constellations can be picked out
+render-power-saving = Power saving:
+render-power-saving-hover = Only draw frames while something is moving or changing, rather than continuously, to save battery
+render-next-launch = The backend and surface format are used from the next launch
+render-post-processing = Post-processing
+post-tonemapping = Tonemapping
+post-tonemapping-hover = Compress the brightness of the scene so bright lights keep their colour rather than turning white
+post-vignette = Vignette
+post-vignette-hover = Darken the corners of the window
+post-fxaa = Anti-aliasing (FXAA)
+post-fxaa-hover = Smooth jagged edges by blurring along them
+post-gamma-correction = Gamma correction
+post-gamma-correction-hover = Encode colours for the display, only needed when the surface format isn't sRGB
+render-adapter = Adapter:
+render-adapter-type = Type:
+render-limits = Limits
+render-limit-texture-size = Texture size
+render-limit-texture-layers = Texture layers
+render-limit-bind-groups = Bind groups
+render-limit-vertex-buffers = Vertex buffers
+render-limit-vertex-attributes = Vertex attributes
+render-limit-uniform-binding-size = Uniform binding size
+render-limit-storage-binding-size = Storage binding size
+render-limit-buffer-size = Buffer size
+present-mode-vsync = Vsync
+present-mode-immediate = Immediate
+present-mode-mailbox = Mailbox
+backend-automatic = Automatic
+
+## Render Stats window
+
+render-stats-draw-calls = Draw calls:
+render-stats-triangles = Triangles:
+render-stats-line-segments = Line segments:
+render-stats-culled = Bodies out of view:
+render-stats-untimed = This adapter can't time how long the GPU takes
+render-stats-pass = Pass
+render-stats-gpu-time = GPU time
+render-stats-frame = Whole frame
+render-stats-no-subpasses = This adapter can only time the opaque pass as a whole
+gpu-pass-environment = Reflections
+gpu-pass-opaque = Opaque pass
+gpu-pass-bodies = Bodies
+gpu-pass-lights = Stars
+gpu-pass-transparent = Transparent pass
+gpu-pass-post = Post-processing
+gpu-pass-ui = Interface
+
+## Log window
+
+logs-search = Search
+logs-copy-all = Copy All
+logs-copy-all-hover = Copy the shown records to the clipboard
+logs-copy-hover = Click to copy this record
+logs-shown = { $shown } of { $total } records
+
+## Layouts window
+
+dock = Dock:
+dock-floating = Floating
+dock-left = Left
+dock-right = Right
+layouts-name = Layout name
+layouts-save = Save
+layouts-save-hover = Save the open windows and where they are under this name
+layouts-none = No layouts saved yet
+layouts-apply = Apply
+layouts-delete = Delete
+
+## Time toolbar
+
+toolbar-julian-date = Julian date { $date }
+toolbar-real-time = Real time
+toolbar-day-per-second = 1 day/s
+toolbar-month-per-second = 1 month/s
+toolbar-year-per-second = 1 year/s
+toolbar-jump-to = Jump to:
+toolbar-jump-to-hover = The Julian date to fast-forward or rewind the simulation to
+toolbar-julian-date-prefix = JD{ " " }
+toolbar-jump = Jump
+toolbar-cancel-jump = Cancel
+
+## Ruler
+
+ruler-distance = { $km } km ({ $au } AU)
+ruler-light-time = Light travel time: { $time }
+ruler-pick-second = Click a second body or point
+ruler-dismiss = Dismiss
+
+## Statistics overlay
+
+hud-frame-rate = FPS:
+hud-cpu-time = CPU time:
+hud-gpu-time = GPU time:
+hud-iterations = Iterations:
+hud-unsupported = Unsupported
+
+## Narration
+
+narration-focused = Focused on { $body }
+
+## Validation
+
+invalid-not-finite = Must be a finite number
+invalid-not-positive = Must be greater than zero
+invalid-negative = Must not be negative
+invalid-light-speed = Must be slower than light
@@ -0,0 +1,17 @@
+# Names of the bundled bodies, as body-<id>. Bodies from scenarios without
+# a name here keep the name given in the scenario
+
+body-sun = Soleil
+body-mercury = Mercure
+body-venus = Vénus
+body-earth = Terre
+body-moon = Lune
+body-mars = Mars
+body-jupiter = Jupiter
+body-saturn = Saturne
+body-uranus = Uranus
+body-neptune = Neptune
+body-pluto = Pluton
+body-136199_eris = (136199) Éris
+body-136108_haumea = (136108) Hauméa
+body-136472_makemake = (136472) Makémaké
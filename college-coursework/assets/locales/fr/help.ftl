@@ -0,0 +1,45 @@
+# Text shown in the Help window
+
+help-keyboard-controls = Commandes du clavier
+help-move = Déplacer la caméra en avant/à gauche/à droite/en arrière
+help-move-vertically = Monter/descendre la caméra
+help-roll = Faire pivoter la caméra vers la gauche/la droite
+help-statistics = Afficher/masquer la fréquence d'images et les statistiques de la simulation
+help-quick-save = Sauvegarde/chargement rapide de l'emplacement choisi dans la fenêtre Enregistrer
+help-capture = Capturer l'image suivante, si lancé depuis RenderDoc
+help-double-click = Double-clic
+help-follow = Suivre avec la caméra le corps sous le curseur
+
+help-gamepad-controls = Commandes de la manette
+help-left-stick = Stick gauche
+help-right-stick = Stick droit
+help-bumpers = Gâchettes hautes
+help-triggers = Gâchettes
+help-turn = Tourner la caméra
+help-change-speed = Ralentir/accélérer la caméra
+
+help-start-tutorial = Commencer le tutoriel
+help-start-tutorial-hover = Une visite guidée des déplacements, de la modification des corps, du temps et de la sauvegarde
+
+## Tutorial
+
+tutorial-title = Tutoriel
+tutorial-step = Étape { $step } sur { $steps }
+tutorial-step-done = ✔ Bravo !
+tutorial-step-waiting = Essayez pour continuer, ou passez cette étape.
+tutorial-back = Retour
+tutorial-next = Suivant
+tutorial-skip = Passer
+tutorial-finish = Terminer
+tutorial-welcome-title = Bienvenue
+tutorial-welcome = Ceci est une simulation du Système solaire, où chaque corps est attiré par la gravité de tous les autres. Ce tutoriel présente les bases, et peut être relancé depuis la fenêtre d'aide.
+tutorial-camera-title = Déplacer la caméra
+tutorial-camera = Utilisez W, A, S et D pour vous déplacer, Espace et Maj pour monter et descendre, et Q et E pour pivoter. Faites défiler pour changer la vitesse de la caméra. Essayez de déplacer la caméra.
+tutorial-edit-body-title = Modifier un corps
+tutorial-edit-body = Ouvrez la fenêtre d'un corps depuis la section Corps de la fenêtre État global, puis faites glisser sa position, sa vitesse ou sa masse pour la changer. Essayez de modifier un corps.
+tutorial-time-scale-title = Changer l'échelle de temps
+tutorial-time-scale = L'échelle de temps de la section Temps règle le temps qui passe chaque seconde. Essayez de la changer, ou mettez la simulation en pause pour regarder autour.
+tutorial-saving-title = Sauvegarder
+tutorial-saving = Sauvegardez la simulation depuis la fenêtre Enregistrer la simulation, ou appuyez sur F5 pour une sauvegarde rapide et F9 pour un chargement rapide. Essayez de sauvegarder.
+tutorial-finished-title = Terminé
+tutorial-finished = Vous savez tout ce qu'il faut pour commencer. La fenêtre d'aide liste toutes les commandes.
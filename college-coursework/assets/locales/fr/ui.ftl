@@ -0,0 +1,508 @@
+# Text shown in the panels and windows
+
+## Shared
+
+reset = Réinitialiser
+dismiss = Fermer
+choose-a-body = Choisir un corps
+first-body = Premier :
+second-body = Second :
+
+## Global State window
+
+global-title = État global
+new-simulation-title = Nouvelle simulation
+save-simulation-title = Enregistrer la simulation
+load-simulation-title = Charger une simulation
+open-scenario-title = Ouvrir un scénario
+dial-link = Cadran des longitudes
+conjunctions-title = Recherche de conjonctions
+periods-title = Périodes orbitales
+compare-title = Comparer des corps
+experiments-title = Expériences
+sandbox-title = Et si
+camera-path-title = Trajectoire de la caméra
+console-title = Console de scripts
+render-settings-title = Paramètres de rendu
+render-stats-title = Statistiques de rendu
+logs-title = Journal
+layouts-title = Dispositions
+help-title = Aide
+about-title = À propos
+
+bodies-section = Corps
+bodies-go-to = Aller à
+bodies-go-to-hover = Déplacer la caméra jusqu'à ce corps
+bodies-search = Rechercher
+bodies-sort = Trier par :
+bodies-sort-name = Nom
+bodies-sort-mass = Masse
+bodies-sort-distance = Distance au Soleil
+bodies-grouped = Regrouper par type
+bodies-none-found = Aucun corps ne correspond à la recherche
+
+camera-section = Caméra
+camera-position = Position :
+camera-speed = Vitesse :
+camera-controller = Contrôle :
+camera-controller-free = Libre
+camera-controller-orbit = Orbite
+camera-follow = Suivre :
+camera-follow-hover = Double-cliquez sur un corps pour le suivre
+camera-offset = Décalage :
+camera-smoothing = Lissage :
+camera-smoothing-hover = Le temps que met la caméra à rattraper le corps
+camera-fly-duration = Durée du déplacement :
+camera-fly-duration-hover = Le temps que met la caméra à se déplacer jusqu'à un corps
+camera-gamepad = Manette
+camera-gamepad-hover = Contrôler la caméra avec une manette connectée
+camera-dead-zone = Zone morte :
+camera-dead-zone-hover = La distance que parcourent les sticks et les gâchettes avant d'avoir un effet
+
+constants-section = Constantes
+constants-gravitational-constant = Constante gravitationnelle :
+
+integrator-section = Intégrateur
+integrator-analytic-encounters = Calculer analytiquement les rencontres proches
+integrator-analytic-encounters-hover =
+    Les paires de corps trop proches pour le pas de temps suivent une orbite exacte à deux corps, ce qui garde les assistances gravitationnelles précises
+integrator-minimum-steps = Nombre minimal de pas par orbite :
+
+time-section = Temps
+time-scale = Échelle de temps :
+time-pause = Pause
+time-resume = Reprendre
+time-date = Date :
+time-time = Heure :
+time-julian-date = Date julienne :
+
+stopwatch = Chronomètre :
+stopwatch-start = Démarrer
+stopwatch-stop = Arrêter
+stopwatch-lap = Tour
+stopwatch-lap-number = Tour { $number } :
+
+display-section = Affichage
+display-labels = Noms des corps
+display-body-size = Taille des corps :
+display-true-scale = Échelle réelle
+display-exaggerated = Exagérée
+display-exaggeration = Exagération :
+display-exaggeration-hover = Les étoiles sont exagérées de la racine carrée de cette valeur
+display-grid = Grille de l'écliptique
+display-axes = Axes à l'origine
+display-ruler = Mesurer les distances
+display-ruler-hover = Cliquer sur deux corps, ou deux points de l'écliptique, pour mesurer la distance entre eux
+display-arrows = Flèches :
+display-velocity-arrows = Vitesse
+display-acceleration-arrows = Accélération
+display-arrows-hover = Dessiner la vitesse de chaque corps et l'attraction totale qu'il subit, qui courbe sa trajectoire en orbite. Les flèches grandissent avec le logarithme du vecteur
+display-field = Champ gravitationnel
+display-field-hover =
+    Des flèches sur l'écliptique indiquant la direction de la gravité, plus lumineuses là où elle est plus forte
+display-trails = Traînées colorées selon
+display-colourmap = Palette :
+display-trail-width = Largeur des traînées :
+display-trail-width-hover = Les traînées plus larges qu'un pixel sont dessinées comme des rubans face à la caméra
+display-cursor = Curseur :
+display-cursor-size = Taille du curseur :
+display-reticle = Réticule central
+display-attract-mode = Mode démonstration après
+display-attract-mode-idle = d'inactivité
+display-units = Unités :
+units-si = SI
+units-astronomical = Astronomiques
+units-imperial = Impériales
+
+trail-colouring-body-colour = Couleur du corps
+trail-colouring-speed = Vitesse
+trail-colouring-specific-energy = Énergie spécifique
+trail-colouring-acceleration = Accélération
+
+cursor-system = Système
+cursor-crosshair = Croix
+cursor-circle = Cercle
+cursor-dot = Point
+
+accessibility-section = Accessibilité
+accessibility-narration = Annoncer les événements
+accessibility-high-contrast = Contraste élevé et grand texte
+accessibility-ui-scale = Échelle de l'interface :
+accessibility-palette = Couleurs des corps :
+accessibility-palette-hover = Des couleurs pour les corps et leurs traînées qui se distinguent malgré le daltonisme
+
+palette-natural = Naturelles
+palette-okabe-ito = Okabe-Ito
+palette-tol-bright = Tol vives
+
+fonts-section = Polices
+fonts-interface = Interface :
+fonts-keys = Touches :
+fonts-monospace = Chasse fixe :
+fonts-built-in = Intégrée
+fonts-same-as-interface = Identique à l'interface
+
+audio-section = Audio
+audio-no-music = Aucune musique en cours
+audio-volume = Volume :
+audio-mute = Muet
+audio-play = Lecture
+audio-pause = Pause
+audio-skip = Suivant
+audio-shuffle = Aléatoire
+audio-repeat = Répéter :
+audio-crossfade = Fondu enchaîné :
+audio-crossfade-hover = La durée du fondu entre chaque morceau et le suivant
+audio-playing = Lecture : { $track }
+audio-paused = En pause : { $track }
+audio-playlist-ended = Fin de la liste de lecture
+audio-user-music-hover = La musique ajoutée à { $folder } est jouée après la musique fournie
+
+repeat-all = Tout
+repeat-one = Un
+repeat-off = Non
+
+language-section = Langue
+
+autosave-section = Sauvegarde automatique
+autosave-enabled = Sauvegarde automatique
+autosave-enabled-hover = Les sauvegardes sont écrites dans { $folder }
+autosave-interval = Toutes les :
+autosave-minutes = { " " }min
+autosave-clock = En :
+autosave-clock-real = Temps réel
+autosave-clock-simulated = Temps simulé
+autosave-kept = Garder :
+autosave-saves = { " " }sauvegardes
+autosave-kept-hover = Les sauvegardes automatiques plus anciennes sont supprimées
+
+diagnostics-section = Diagnostics
+diagnostics-memory = Mémoire de l'historique :
+diagnostics-memory-used = { $used } Mio sur
+diagnostics-memory-hover = Quand l'historique enregistré dépasse cette taille, les données les plus anciennes sont allégées
+diagnostics-pruned =
+    { $points ->
+        [one] { $points } point de traînée supprimé
+       *[other] { $points } points de traînée supprimés
+    } en { $prunes ->
+        [one] { $prunes } passe
+       *[other] { $prunes } passes
+    }, la dernière à { $last }
+diagnostics-nothing-pruned = Rien n'a été supprimé
+
+## New, Save and Load windows
+
+new-simulation-scenarios = Commencer une nouvelle simulation à partir d'un scénario :
+new-simulation-no-scenarios = Aucun scénario trouvé
+new-simulation-open-file = Ouvrir un fichier
+new-simulation-refresh = Actualiser
+save-as = Enregistrer en { $format }
+load-from = Charger depuis { $format }
+quick-slots = Emplacements rapides :
+quick-slots-hover = F5 enregistre dans l'emplacement choisi, F9 le charge
+quick-slot = Emplacement { $slot }
+quick-slot-empty = Vide
+quick-slot-save = Enregistrer
+quick-slot-load = Charger
+simulation-loaded = Simulation chargée
+simulation-saved = Simulation enregistrée dans { $path }
+save-failed = Échec de l'enregistrement
+load-failed = Échec du chargement
+quick-save-failed = Échec de l'enregistrement rapide
+quick-load-failed = Échec du chargement rapide
+scenario-failed = Échec du lancement du scénario
+dropped-file-title = Charger le fichier
+dropped-file-confirm = Charger { $file } ? La simulation actuelle sera remplacée.
+dropped-file-load = Charger
+dropped-file-cancel = Annuler
+autosave-failed = Échec de la sauvegarde automatique : { $error }
+screenshot-saved = Capture d'écran enregistrée dans { $path }
+screenshot-failed = Échec de la capture d'écran : { $error }
+device-recreated = Le périphérique graphique a été perdu puis recréé
+
+## About window
+
+about-version = Version { $version }
+about-author = Par { $author }
+about-usage-statistics = Statistiques d'utilisation, elles ne quittent jamais cet ordinateur
+about-time-run = Durée d'utilisation :
+about-sessions = Sessions :
+about-bodies-created = Corps créés :
+about-saves-made = Sauvegardes effectuées :
+about-features-used = Fonctionnalités utilisées
+
+## Body windows
+
+planet-name = Nom :
+planet-id = Identifiant :
+planet-fly-to = Aller à
+planet-fly-to-hover = Déplacer la caméra jusqu'à ce corps
+planet-position = Position :
+planet-velocity = Vitesse :
+planet-mass = Masse :
+planet-mass-slider-hover = Faites glisser pour changer la masse sur une échelle logarithmique
+planet-earth-mass = Terre
+planet-jupiter-mass = Jupiter
+planet-solar-mass = Soleil
+planet-surface-gravity = Gravité de surface :
+planet-escape-velocity = Vitesse de libération :
+planet-mass-rate = Variation de masse :
+planet-mass-rate-hover = Masse gagnée chaque seconde, négative pour perdre de la masse
+planet-light = Lumière
+planet-colour = Couleur :
+planet-intensity = Intensité :
+planet-luminosity = Luminosité :
+planet-luminosity-hover =
+    Luminosité par rapport au Soleil, la lumière diminue avec le carré de la distance à l'étoile
+planet-atmosphere = Atmosphère
+planet-thickness = Épaisseur :
+planet-thickness-hover = Jusqu'où l'atmosphère s'étend au-dessus de la surface, par rapport au rayon
+planet-rings = Anneaux
+planet-rings-inner-radius = Rayon intérieur :
+planet-rings-outer-radius = Rayon extérieur :
+planet-rings-radius-hover = La distance entre le bord des anneaux et le centre du corps, en multiples de son rayon
+planet-rings-tilt = Inclinaison :
+planet-rings-tilt-hover = L'angle entre les anneaux, qui suivent l'équateur, et le plan des orbites
+planet-rings-opacity = Opacité :
+planet-rings-opacity-hover = La part de la lumière bloquée derrière les parties les plus denses des anneaux
+planet-appearance = Apparence
+planet-colour-textured-hover = Le corps est dessiné avec sa texture, sa couleur ne sert donc qu'à sa traînée et à son étiquette
+planet-trail-colour = Couleur de la traînée
+planet-trail-colour-hover = Dessiner la traînée dans sa propre couleur plutôt que celle du corps, quand les traînées sont colorées par corps
+planet-label-shown = Afficher l'étiquette
+planet-properties = Propriétés
+planet-info = Infos
+facts-radius = Rayon moyen :
+facts-day-length = Durée du jour :
+facts-retrograde = { $period } (rétrograde)
+facts-composition = Composition :
+facts-discovery = Découverte :
+facts-discovered = En { $year } par { $by }
+facts-antiquity = Connu depuis l'Antiquité
+material-hydrogen = Hydrogène
+material-helium = Hélium
+material-rock = Roche silicatée
+material-iron = Fer
+material-water = Eau
+material-carbon-dioxide = Dioxyde de carbone
+material-ices = Glaces d'eau, d'ammoniac et de méthane
+material-nitrogen-ice = Glace d'azote
+material-methane-ice = Glace de méthane
+planet-arrows-shown = Afficher les flèches
+planet-arrows-shown-hover = Dessiner les flèches de vitesse et d'accélération de ce corps, quand elles sont activées
+planet-lineage = Origine
+planet-merged-at = Fusionné à { $time }
+bodies-merged = { $absorbed } a fusionné avec { $survivor }
+
+## Heliocentric Longitude window
+
+dial-title = Longitude héliocentrique
+
+## Conjunction Finder window
+
+conjunctions-find = Chercher la suivante
+conjunctions-find-hover = Chercher la prochaine conjonction ou opposition vue depuis le Soleil
+conjunctions-found = { $kind } dans { $time }
+conjunctions-jump = Aller à l'alignement
+conjunctions-not-found = Aucun alignement dans les 200 prochaines années
+conjunctions-jumped =
+    { $kind ->
+        [conjunction] Avancé jusqu'à la conjonction
+       *[opposition] Avancé jusqu'à l'opposition
+    }
+alignment-conjunction = Conjonction
+alignment-opposition = Opposition
+
+## Orbital Periods window
+
+periods-orbits = Orbite autour de :
+periods-semi-major-axis = Demi-grand axe :
+periods-sidereal = Période sidérale :
+periods-sidereal-hover = D'après le demi-grand axe, par la troisième loi de Kepler
+periods-measured = Période mesurée :
+periods-measured-hover = Durée de la dernière orbite complète dans la simulation
+periods-difference = Écart :
+periods-no-synodic = Les corps n'orbitent pas autour du même corps, ils n'ont donc pas de période synodique
+periods-synodic = Période synodique :
+periods-synodic-hover = Durée entre deux alignements des corps
+periods-synodic-measured = D'après les périodes mesurées :
+periods-unknown-body = Inconnu
+
+## Compare Bodies window
+
+compare-mass-ratio = Rapport des masses :
+compare-mass-ratio-hover = La masse du premier corps divisée par celle du second
+compare-distance = Distance :
+compare-relative-velocity = Vitesse relative :
+compare-period-ratio = Rapport des périodes orbitales :
+compare-period-ratio-hover = La période sidérale du premier corps divisée par celle du second, chacun autour du corps qu'il orbite
+
+## Experiments window
+
+experiments-description = Modifier la constante gravitationnelle entre des groupes de corps
+experiments-overrides = Modifications de la gravité
+experiments-and = et
+experiments-add-override = Ajouter une modification
+experiments-groups = Groupes
+experiments-no-groups = Aucun
+group-stars = Étoiles
+group-planets = Planètes
+group-moons = Lunes
+group-a = Groupe A
+group-b = Groupe B
+
+## What If window
+
+sandbox-description = Faire tourner la simulation à côté d'une copie où des corps sont supprimés ou leur masse modifiée, et tracer l'écart entre les corps suivis. Les lunes se déplacent avec leur planète
+sandbox-body = Corps
+sandbox-removed = Supprimé
+sandbox-mass = Masse
+sandbox-tracked = Suivi
+sandbox-duration = Durée :
+sandbox-run = Dupliquer et lancer
+sandbox-run-hover = Copier la simulation telle qu'elle est et faire tourner les deux copies, sans modifier la simulation
+sandbox-stop = Arrêter
+sandbox-apply = Appliquer à la simulation
+sandbox-apply-hover = Faire les mêmes changements à la simulation elle-même
+sandbox-reset = Réinitialiser
+sandbox-plot = Distance entre les copies ({ $unit }) au fil du temps (années)
+
+## Camera Path window
+
+camera-path-play = Lecture
+camera-path-stop = Arrêter
+camera-path-play-disabled-hover = Placez au moins deux images clés
+camera-path-loop = Boucle
+camera-path-view-hover = Déplacer la caméra sur cette image clé
+camera-path-add-keyframe = Ajouter une image clé
+camera-path-add-keyframe-hover = Placer une image clé à la position de la caméra
+camera-path-clear = Effacer
+
+## Script Console window
+
+console-run = Exécuter
+console-clear = Effacer
+console-reset-hover = Oublier les variables, fonctions et rappels de tous les scripts
+console-callbacks =
+    { $count ->
+        [one] { $count } rappel
+       *[other] { $count } rappels
+    }
+
+## Render Settings window
+
+render-present-mode = Mode de présentation :
+render-present-mode-unsupported = Non pris en charge par cet adaptateur
+render-backend = API graphique :
+render-surface-format = Format de surface :
+render-automatic = Automatique
+render-reflections = Reflets :
+render-reflections-hover = Les surfaces métalliques reflètent les étoiles et le Soleil
+render-stars = Étoiles
+render-stars-hover = Dessiner les étoiles réelles les plus brillantes à leur position dans le ciel, pour reconnaître les constellations
+render-power-saving = Économie d'énergie :
+render-power-saving-hover = Ne dessiner des images que lorsque quelque chose bouge ou change, plutôt qu'en continu, pour économiser la batterie
+render-next-launch = L'API graphique et le format de surface s'appliquent au prochain lancement
+render-post-processing = Post-traitement
+post-tonemapping = Mappage des tons
+post-tonemapping-hover = Compresser la luminosité de la scène pour que les lumières vives gardent leur couleur au lieu de devenir blanches
+post-vignette = Vignettage
+post-vignette-hover = Assombrir les coins de la fenêtre
+post-fxaa = Anticrénelage (FXAA)
+post-fxaa-hover = Lisser les bords crénelés en les floutant
+post-gamma-correction = Correction gamma
+post-gamma-correction-hover = Encoder les couleurs pour l'écran, seulement nécessaire lorsque le format de surface n'est pas sRGB
+render-adapter = Adaptateur :
+render-adapter-type = Type :
+render-limits = Limites
+render-limit-texture-size = Taille des textures
+render-limit-texture-layers = Couches de texture
+render-limit-bind-groups = Groupes de liaison
+render-limit-vertex-buffers = Tampons de sommets
+render-limit-vertex-attributes = Attributs de sommets
+render-limit-uniform-binding-size = Taille de liaison uniforme
+render-limit-storage-binding-size = Taille de liaison de stockage
+render-limit-buffer-size = Taille des tampons
+present-mode-vsync = Synchro verticale
+present-mode-immediate = Immédiat
+present-mode-mailbox = Boîte aux lettres
+backend-automatic = Automatique
+
+## Render Stats window
+
+render-stats-draw-calls = Appels de dessin :
+render-stats-triangles = Triangles :
+render-stats-line-segments = Segments de ligne :
+render-stats-culled = Corps hors du champ :
+render-stats-untimed = Cet adaptateur ne peut pas mesurer le temps pris par le GPU
+render-stats-pass = Passe
+render-stats-gpu-time = Temps GPU
+render-stats-frame = Image entière
+render-stats-no-subpasses = Cet adaptateur ne peut mesurer la passe opaque que dans son ensemble
+gpu-pass-environment = Réflexions
+gpu-pass-opaque = Passe opaque
+gpu-pass-bodies = Corps
+gpu-pass-lights = Étoiles
+gpu-pass-transparent = Passe transparente
+gpu-pass-post = Post-traitement
+gpu-pass-ui = Interface
+
+## Log window
+
+logs-search = Rechercher
+logs-copy-all = Tout copier
+logs-copy-all-hover = Copier les entrées affichées dans le presse-papiers
+logs-copy-hover = Cliquez pour copier cette entrée
+logs-shown = { $shown } entrées sur { $total }
+
+## Layouts window
+
+dock = Ancrage :
+dock-floating = Flottante
+dock-left = Gauche
+dock-right = Droite
+layouts-name = Nom de la disposition
+layouts-save = Enregistrer
+layouts-save-hover = Enregistrer les fenêtres ouvertes et leur emplacement sous ce nom
+layouts-none = Aucune disposition enregistrée
+layouts-apply = Appliquer
+layouts-delete = Supprimer
+
+## Time toolbar
+
+toolbar-julian-date = Date julienne { $date }
+toolbar-real-time = Temps réel
+toolbar-day-per-second = 1 jour/s
+toolbar-month-per-second = 1 mois/s
+toolbar-year-per-second = 1 an/s
+toolbar-jump-to = Aller à :
+toolbar-jump-to-hover = La date julienne vers laquelle avancer ou reculer la simulation
+toolbar-julian-date-prefix = JJ{ " " }
+toolbar-jump = Aller
+toolbar-cancel-jump = Annuler
+
+## Ruler
+
+ruler-distance = { $km } km ({ $au } UA)
+ruler-light-time = Temps de trajet de la lumière : { $time }
+ruler-pick-second = Cliquer sur un second corps ou point
+ruler-dismiss = Fermer
+
+## Statistics overlay
+
+hud-frame-rate = IPS :
+hud-cpu-time = Temps CPU :
+hud-gpu-time = Temps GPU :
+hud-iterations = Itérations :
+hud-unsupported = Non pris en charge
+
+## Narration
+
+narration-focused = Centré sur { $body }
+
+## Validation
+
+invalid-not-finite = Doit être un nombre fini
+invalid-not-positive = Doit être supérieur à zéro
+invalid-negative = Ne doit pas être négatif
+invalid-light-speed = Doit être plus lent que la lumière
@@ -0,0 +1,149 @@
+mod narration;
+mod palette;
+
+use std::fs;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::config::CONFIG_DIR;
+
+pub use narration::{Announcements, Narrator};
+pub use palette::ColourPalette;
+
+const ACCESSIBILITY_FILE: &'static str = "accessibility.toml";
+
+/// How much larger text is drawn in high-contrast mode
+const LARGE_TEXT_SCALE: f32 = 1.4;
+
+/// The range the whole UI can be scaled by, on top of the display's own
+/// scale factor
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+/// Accessibility options that can be changed from the UI
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Speak major events and changes of the focused body aloud
+    pub narration: bool,
+    /// Draw larger text, thicker lines and a higher contrast UI
+    pub high_contrast: bool,
+    /// How many times larger the whole UI is drawn
+    pub ui_scale: f32,
+    /// The colours bodies and their trails are drawn in
+    pub palette: ColourPalette,
+}
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            narration: false,
+            high_contrast: false,
+            ui_scale: 1.0,
+            palette: ColourPalette::default(),
+        }
+    }
+}
+impl AccessibilitySettings {
+    pub fn load() -> Self {
+        //! Load the accessibility settings saved by a previous session,
+        //! falling back to the defaults if there are none
+
+        let path = CONFIG_DIR.join(ACCESSIBILITY_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid accessibility settings in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        //! Save the accessibility settings for use by later sessions
+
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize accessibility settings: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(ACCESSIBILITY_FILE), contents))
+        {
+            warn!("Failed to save accessibility settings: {}", err);
+        }
+    }
+
+    pub fn pixels_per_point(&self, scale_factor: f64) -> f32 {
+        //! Returns how many physical pixels each point of the UI covers, on
+        //! a display with the given scale factor
+
+        scale_factor as f32 * self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+    }
+
+    pub fn label_scale(&self) -> f32 {
+        //! Returns the factor to scale the font size of labels by
+
+        if self.high_contrast {
+            LARGE_TEXT_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    pub fn line_width_scale(&self) -> f32 {
+        //! Returns the factor to scale the width of trails and other lines by
+
+        if self.high_contrast {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn marker_outline(&self) -> egui::Stroke {
+        //! Returns the outline to draw around markers drawn over the scene
+
+        if self.high_contrast {
+            egui::Stroke::new(2.0, egui::Color32::BLACK)
+        } else {
+            egui::Stroke::none()
+        }
+    }
+
+    pub fn style(&self) -> egui::Style {
+        //! Returns the UI style matching these settings
+
+        let mut style = egui::Style::default();
+
+        if self.high_contrast {
+            for font_id in style.text_styles.values_mut() {
+                font_id.size *= LARGE_TEXT_SCALE;
+            }
+
+            // Use pure white text on black backgrounds with strong outlines
+            // around every widget
+            let visuals = &mut style.visuals;
+            visuals.override_text_color = Some(egui::Color32::WHITE);
+            visuals.extreme_bg_color = egui::Color32::BLACK;
+            visuals.widgets.noninteractive.bg_fill = egui::Color32::BLACK;
+            visuals.widgets.noninteractive.bg_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+            for widget in [
+                &mut visuals.widgets.inactive,
+                &mut visuals.widgets.hovered,
+                &mut visuals.widgets.active,
+                &mut visuals.widgets.open,
+            ] {
+                widget.bg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+                widget.fg_stroke.color = egui::Color32::WHITE;
+            }
+            visuals.hyperlink_color = egui::Color32::YELLOW;
+        }
+
+        style
+    }
+}
@@ -0,0 +1,99 @@
+use std::thread;
+
+use crossbeam::channel::{self, Sender};
+use log::{info, warn};
+use specs::{Read, ReadExpect, System, Write};
+use tts::Tts;
+
+use crate::{
+    locale::{self, tr},
+    renderer::components::CameraCenter,
+};
+
+use super::AccessibilitySettings;
+
+/// Queue of messages waiting to be spoken by the narrator
+#[derive(Debug, Default)]
+pub struct Announcements(Vec<String>);
+impl Announcements {
+    pub fn push(&mut self, text: impl Into<String>) {
+        //! Queue a message to be announced
+
+        self.0.push(text.into());
+    }
+}
+
+/// System that speaks queued announcements and changes of the focused body
+/// using the platform's text-to-speech engine
+pub struct Narrator {
+    sender: Sender<String>,
+    focused_body: Option<String>,
+}
+impl Narrator {
+    pub fn new() -> Self {
+        //! Create a new narrator, spawning the thread that owns the speech engine
+
+        let (sender, receiver) = channel::unbounded::<String>();
+
+        // The speech engine is created on its own thread as it blocks
+        // while talking to the platform
+        thread::spawn(move || {
+            let mut tts = match Tts::default() {
+                Ok(tts) => tts,
+                Err(err) => {
+                    warn!("Text-to-speech is unavailable: {}", err);
+                    return;
+                }
+            };
+
+            info!("Text-to-speech initialised");
+
+            while let Ok(text) = receiver.recv() {
+                if let Err(err) = tts.speak(text, false) {
+                    warn!("Failed to speak announcement: {}", err);
+                }
+            }
+        });
+
+        Self {
+            sender,
+            focused_body: None,
+        }
+    }
+
+    fn speak(&self, text: String) {
+        // If the speech thread has exited there is nothing to speak to
+        let _ = self.sender.send(text);
+    }
+}
+impl<'a> System<'a> for Narrator {
+    type SystemData = (
+        Write<'a, Announcements>,
+        Read<'a, AccessibilitySettings>,
+        ReadExpect<'a, CameraCenter>,
+    );
+
+    fn run(&mut self, (mut announcements, settings, camera_center): Self::SystemData) {
+        let focused_body = camera_center.get_body().get_name();
+        let focus_changed = self.focused_body.as_deref() != Some(focused_body);
+        self.focused_body = Some(focused_body.to_string());
+
+        // Discard anything queued while narration is turned off so it is not
+        // read out all at once when it is turned back on
+        if !settings.narration {
+            announcements.0.clear();
+            return;
+        }
+
+        if focus_changed {
+            self.speak(tr!(
+                "narration-focused",
+                body = locale::body_name(camera_center.get_body())
+            ));
+        }
+
+        for text in announcements.0.drain(..) {
+            self.speak(text);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use specs::Entity;
+
+use crate::locale::tr;
+
+/// The Okabe-Ito palette, without its black which can't be seen against
+/// space (in sRGB)
+const OKABE_ITO: [[u8; 3]; 7] = [
+    [230, 159, 0],
+    [86, 180, 233],
+    [0, 158, 115],
+    [240, 228, 66],
+    [0, 114, 178],
+    [213, 94, 0],
+    [204, 121, 167],
+];
+
+/// Paul Tol's bright palette (in sRGB)
+const TOL_BRIGHT: [[u8; 3]; 7] = [
+    [68, 119, 170],
+    [102, 204, 238],
+    [34, 136, 51],
+    [204, 187, 68],
+    [238, 102, 119],
+    [170, 51, 119],
+    [187, 187, 187],
+];
+
+/// The colours bodies and their trails are drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColourPalette {
+    /// The colour of each body's surface
+    Natural,
+    /// Distinguishable with any common form of colour blindness
+    OkabeIto,
+    /// Distinguishable with any common form of colour blindness, and in
+    /// greyscale
+    TolBright,
+}
+impl Default for ColourPalette {
+    fn default() -> Self {
+        Self::Natural
+    }
+}
+impl ColourPalette {
+    pub const ALL: [Self; 3] = [Self::Natural, Self::OkabeIto, Self::TolBright];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Natural => tr!("palette-natural"),
+            Self::OkabeIto => tr!("palette-okabe-ito"),
+            Self::TolBright => tr!("palette-tol-bright"),
+        }
+    }
+
+    fn colours(&self) -> &'static [[u8; 3]] {
+        match self {
+            Self::Natural => &[],
+            Self::OkabeIto => &OKABE_ITO,
+            Self::TolBright => &TOL_BRIGHT,
+        }
+    }
+
+    pub fn body_colour(&self, entity: Entity, natural: [f32; 4]) -> [f32; 4] {
+        //! The colour to draw a body in, from its natural colour (in linear
+        //! RGBA). Bodies take the colours of the palette in turn, in the
+        //! order they were made, so the planets next to each other differ
+
+        let colours = self.colours();
+        if colours.is_empty() {
+            return natural;
+        }
+
+        let [r, g, b] = colours[entity.id() as usize % colours.len()];
+        let colour = egui::Rgba::from(egui::Color32::from_rgb(r, g, b));
+        [colour.r(), colour.g(), colour.b(), natural[3]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, World, WorldExt};
+
+    use super::ColourPalette;
+
+    #[test]
+    fn test_palette_colours() {
+        let mut world = World::new();
+        let first = world.create_entity().build();
+        let second = world.create_entity().build();
+        let natural = [0.2, 0.4, 0.6, 0.5];
+
+        assert_eq!(natural, ColourPalette::Natural.body_colour(first, natural));
+
+        // Neighbouring bodies differ, and keep their opacity
+        let palette = ColourPalette::OkabeIto;
+        assert_ne!(
+            palette.body_colour(first, natural),
+            palette.body_colour(second, natural)
+        );
+        assert_eq!(0.5, palette.body_colour(first, natural)[3]);
+    }
+}
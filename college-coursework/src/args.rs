@@ -1,6 +1,79 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use log::LevelFilter;
 
+use crate::renderer::settings::{Backend, PresentMode};
+
 #[derive(Parser, Debug)]
 #[clap(author, version)]
-pub struct Args {}
+pub struct Args {
+    /// Run the simulation on a single thread so that results are exactly reproducible
+    #[clap(long)]
+    pub deterministic: bool,
+
+    /// The number of threads the simulation uses, every core by default
+    #[clap(long)]
+    pub threads: Option<usize>,
+
+    /// Leave one core free for rendering and the UI
+    #[clap(long)]
+    pub reserve_core: bool,
+
+    /// Check the GPU, audio, file dialogs, saving and physics work on this
+    /// machine, printing a report instead of starting the program
+    #[clap(long)]
+    pub self_test: bool,
+
+    /// Start without audio, using the default settings and layout and the
+    /// most widely supported present mode, to recover from a bad
+    /// configuration or a crash
+    #[clap(long)]
+    pub safe_mode: bool,
+
+    /// Simulated seconds for every real second, to start with
+    #[clap(long)]
+    pub time_scale: Option<f64>,
+
+    /// The gravitational constant to start with (in m^3 kg^-1 s^-2)
+    #[clap(long)]
+    pub gravitational_constant: Option<f64>,
+
+    /// A scenario to start with, instead of the solar system, as TOML or RON
+    /// depending on its extension
+    #[clap(long, value_name = "PATH")]
+    pub scenario: Option<PathBuf>,
+
+    /// A saved simulation to start with, as JSON, TOML or binary depending on
+    /// its extension. Takes priority over the scenario
+    #[clap(long, value_name = "PATH")]
+    pub load: Option<PathBuf>,
+
+    /// A Rhai script to run once the simulation has started, such as one
+    /// automating a demonstration
+    #[clap(long, value_name = "PATH")]
+    pub script: Option<PathBuf>,
+
+    /// Volume of the music, from 0 to 1
+    #[clap(long)]
+    pub volume: Option<f32>,
+
+    /// Start a WebSocket server on this port, taking JSON-RPC commands such as
+    /// get_state, set_body, set_time_scale, save and screenshot from this
+    /// machine
+    #[clap(long, value_name = "PORT")]
+    pub remote_port: Option<u16>,
+
+    /// How frames are presented to the window
+    #[clap(long, value_enum)]
+    pub present_mode: Option<PresentMode>,
+
+    /// The graphics API used to render
+    #[clap(long, value_enum)]
+    pub backend: Option<Backend>,
+
+    /// Write a config file listing every startup option to the config
+    /// directory, instead of starting the program
+    #[clap(long)]
+    pub write_default_config: bool,
+}
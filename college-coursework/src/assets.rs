@@ -1,16 +1,29 @@
-use std::io::{BufReader, Cursor, Error};
-
-use wgpu::util::DeviceExt;
-
-use crate::renderer::{model, texture};
+use std::{
+    io::{BufReader, Cursor, Error},
+    path::Path,
+    sync::Arc,
+};
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, SquareMatrix, Vector3};
+use image::{DynamicImage, GrayAlphaImage, GrayImage, Luma, RgbImage, RgbaImage};
+
+use crate::{
+    models::mesh::{calculate_tangents, smooth_normals},
+    renderer::{model, texture},
+};
+
+pub fn asset_path(file_name: &str) -> std::path::PathBuf {
+    //! Gets the path of an asset relative to the assets directory
+    std::path::Path::new(env!("OUT_DIR"))
+        .join("assets")
+        .join(file_name)
+}
 
 pub async fn load_string(file_name: &str) -> Result<String, Error> {
     //! Loads the contents of an asset into a string from the file system
 
     // Get the path relative to the assets directory
-    let path = std::path::Path::new(env!("OUT_DIR"))
-        .join("assets")
-        .join(file_name);
+    let path = asset_path(file_name);
 
     log::info!("Loading {:?} as string", file_name);
 
@@ -23,9 +36,7 @@ pub async fn load_binary(file_name: &str) -> Result<Vec<u8>, Error> {
     //! Loads the contents of an asset into a byte vector from the file syste,
 
     // get the path relative to the assets directory
-    let path = std::path::Path::new(env!("OUT_DIR"))
-        .join("assets")
-        .join(file_name);
+    let path = asset_path(file_name);
 
     log::info!("Loading {:?} as binary data", file_name);
 
@@ -48,6 +59,7 @@ pub async fn load_texture(
     file_name: &str,
     device: &wgpu::Device,
     queue: &wgpu::Queue,
+    is_linear: bool,
 ) -> Result<texture::Texture, LoadTextureError> {
     //! Loads the contents of an asset into a texture object from the file system
 
@@ -58,10 +70,43 @@ pub async fn load_texture(
 
     // Use the binary data to create a texture
     Ok(texture::Texture::from_bytes(
-        device, queue, &data, file_name,
+        device, queue, &data, file_name, is_linear,
+    )?)
+}
+
+pub fn load_texture_blocking(
+    file_name: &str,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    is_linear: bool,
+) -> Result<texture::Texture, LoadTextureError> {
+    //! Loads the contents of an asset into a texture object from the file
+    //! system, for use outside of async code
+
+    log::info!("Loading {:?} as a texture", file_name);
+
+    let data = std::fs::read(asset_path(file_name))?;
+
+    Ok(texture::Texture::from_bytes(
+        device, queue, &data, file_name, is_linear,
     )?)
 }
 
+async fn load_map(
+    file_name: &str,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+) -> Result<Option<texture::Texture>, LoadTextureError> {
+    //! Loads an optional material map, such as a normal or specular map, an
+    //! empty file name meaning the material does not have the map
+
+    if file_name.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(load_texture(file_name, device, queue, true).await?))
+}
+
 /// Possible errors produced by the load_model function
 #[derive(thiserror::Error, Debug)]
 pub enum LoadModelError {
@@ -73,6 +118,12 @@ pub enum LoadModelError {
 
     #[error(transparent)]
     LoadTextureError(#[from] LoadTextureError),
+
+    #[error(transparent)]
+    GltfError(#[from] gltf::Error),
+
+    #[error("Image {0} has an unsupported pixel format {1:?}")]
+    UnsupportedImageFormat(usize, gltf::image::Format),
 }
 
 pub async fn load_model(
@@ -81,7 +132,27 @@ pub async fn load_model(
     queue: &wgpu::Queue,
     layout: &wgpu::BindGroupLayout,
 ) -> Result<model::Model, LoadModelError> {
-    //! Loads the contents of an asset into a model object frmo the file system
+    //! Loads the contents of an asset into a model object from the file
+    //! system, as glTF if it has a .gltf or .glb extension and as OBJ
+    //! otherwise
+
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        Some("gltf" | "glb") => load_gltf_model(file_name, device, queue, layout),
+        _ => load_obj_model(file_name, device, queue, layout).await,
+    }
+}
+
+async fn load_obj_model(
+    file_name: &str,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+) -> Result<model::Model, LoadModelError> {
+    //! Loads an OBJ model and the MTL materials it refers to
 
     log::info!("Loading {:?}, as a Model", file_name);
 
@@ -108,16 +179,31 @@ pub async fn load_model(
     // Iterate through the materials loading the diffuse and normal textures from the file system
     let mut materials = Vec::new();
     for material in obj_materials? {
-        let diffuse_texture = load_texture(&material.diffuse_texture, device, queue).await?;
-        let normal_texture = load_texture(&material.normal_texture, device, queue).await?;
-
-        materials.push(model::Material::new(
+        let diffuse_texture = load_texture(&material.diffuse_texture, device, queue, false).await?;
+
+        // The other maps are optional, the material falls back to defaults
+        // for any that are missing
+        let maps = model::MaterialMaps {
+            normal: load_map(&material.normal_texture, device, queue).await?,
+            specular: load_map(&material.specular_texture, device, queue).await?,
+            roughness: match material.unknown_param.get("map_Pr") {
+                Some(file_name) => load_map(file_name, device, queue).await?,
+                None => None,
+            },
+            metallic: match material.unknown_param.get("map_Pm") {
+                Some(file_name) => load_map(file_name, device, queue).await?,
+                None => None,
+            },
+        };
+
+        materials.push(Arc::new(model::Material::new(
             device,
+            queue,
             &material.name,
             diffuse_texture,
-            normal_texture,
+            maps,
             layout,
-        ))
+        )))
     }
 
     // iterate through the meshes, converting them into the Mesh object
@@ -145,91 +231,344 @@ pub async fn load_model(
                 })
                 .collect::<Vec<_>>();
 
-            // Get the vertices and indices of the model loaded by tobj
-            let indices = &model.mesh.indices;
-            let mut triangles_included = vec![0; vertices.len()];
-
-            //
-            for c in indices.chunks(3) {
-                // Get the vertices specified at the point of each index
-                let v0 = vertices[c[0] as usize];
-                let v1 = vertices[c[1] as usize];
-                let v2 = vertices[c[2] as usize];
-
-                // Convert the vertices into vectors
-                let pos0: cgmath::Vector3<_> = v0.position.into();
-                let pos1: cgmath::Vector3<_> = v1.position.into();
-                let pos2: cgmath::Vector3<_> = v2.position.into();
-
-                let uv0: cgmath::Vector2<_> = v0.tex_coords.into();
-                let uv1: cgmath::Vector2<_> = v1.tex_coords.into();
-                let uv2: cgmath::Vector2<_> = v2.tex_coords.into();
-
-                // Get the difference between the 0th position and 1st and 2nd position
-                let delta_pos1 = pos1 - pos0;
-                let delta_pos2 = pos2 - pos0;
-
-                let delta_uv1 = uv1 - uv0;
-                let delta_uv2 = uv2 - uv0;
-
-                // Caluclate the tangent and bitangent of the face
-                let r = 1.0 / (delta_uv1.x * delta_uv2.y - delta_uv1.y * delta_uv2.x);
-                let tangent = (delta_pos1 * delta_uv2.y - delta_pos2 * delta_uv1.y) * r;
-                let bitangent = (delta_pos2 * delta_uv1.x - delta_pos1 * delta_uv2.x) * -r;
-
-                // Set the tangent and bitangent of each vertex
-                vertices[c[0] as usize].tangent =
-                    (tangent + cgmath::Vector3::from(vertices[c[0] as usize].tangent)).into();
-                vertices[c[1] as usize].tangent =
-                    (tangent + cgmath::Vector3::from(vertices[c[1] as usize].tangent)).into();
-                vertices[c[2] as usize].tangent =
-                    (tangent + cgmath::Vector3::from(vertices[c[2] as usize].tangent)).into();
-                vertices[c[0] as usize].bitangent =
-                    (bitangent + cgmath::Vector3::from(vertices[c[0] as usize].bitangent)).into();
-                vertices[c[1] as usize].bitangent =
-                    (bitangent + cgmath::Vector3::from(vertices[c[1] as usize].bitangent)).into();
-                vertices[c[2] as usize].bitangent =
-                    (bitangent + cgmath::Vector3::from(vertices[c[2] as usize].bitangent)).into();
-
-                // Increase the count of each index used to average the tangent and bitangent
-                triangles_included[c[0] as usize] += 1;
-                triangles_included[c[1] as usize] += 1;
-                triangles_included[c[2] as usize] += 1;
+            calculate_tangents(&mut vertices, &model.mesh.indices);
+
+            // Create the mesh
+            Arc::new(model::Mesh::new(
+                device,
+                file_name.to_string(),
+                vertices,
+                model.mesh.indices,
+                model.mesh.material_id.unwrap_or(0),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    // Bundle the meshes and materials together into one object
+    Ok(model::Model::new(meshes, materials))
+}
+
+fn load_gltf_model(
+    file_name: &str,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+) -> Result<model::Model, LoadModelError> {
+    //! Loads a glTF 2.0 model, either a .gltf file with its buffers and
+    //! images embedded or alongside it, or a binary .glb file. Each triangle
+    //! primitive of each mesh in the scene becomes a mesh, moved into place
+    //! by the transforms of the nodes above it
+
+    log::info!("Loading {:?}, as a glTF Model", file_name);
+
+    // The importer reads the buffers and images the file refers to, relative
+    // to the file, and decodes the images
+    let (document, buffers, images) = gltf::import(asset_path(file_name))?;
+    let images = images
+        .into_iter()
+        .enumerate()
+        .map(|(i, data)| gltf_image(i, data))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut materials = document
+        .materials()
+        .map(|material| Arc::new(gltf_material(&material, &images, device, queue, layout)))
+        .collect::<Vec<_>>();
+    // glTF's default material is only added if a primitive uses it
+    let mut default_material = None;
+
+    // Find where each mesh is placed by walking down the node hierarchy of
+    // the scene, files without a scene have their meshes drawn as they are
+    let mut placements: Vec<(gltf::Mesh, Matrix4<f32>)> = Vec::new();
+    match document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        Some(scene) => {
+            let mut nodes = scene
+                .nodes()
+                .map(|node| (node, Matrix4::identity()))
+                .collect::<Vec<_>>();
+            while let Some((node, parent)) = nodes.pop() {
+                let transform = parent * Matrix4::from(node.transform().matrix());
+                if let Some(mesh) = node.mesh() {
+                    placements.push((mesh, transform));
+                }
+                nodes.extend(node.children().map(|child| (child, transform)));
             }
+        }
+        None => placements.extend(document.meshes().map(|mesh| (mesh, Matrix4::identity()))),
+    }
 
-            // average the tangent and bitangent vectors
-            for (i, n) in triangles_included.into_iter().enumerate() {
-                let denom = 1.0 / n as f32;
-                let mut v = &mut vertices[i];
-                v.tangent = (cgmath::Vector3::from(v.tangent) * denom).into();
-                v.bitangent = (cgmath::Vector3::from(v.bitangent) * denom).into();
+    let mut meshes = Vec::new();
+    for (mesh, transform) in placements {
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                log::warn!(
+                    "Skipping a primitive of {:?} in {:?} drawn as {:?} rather than triangles",
+                    mesh.name(),
+                    file_name,
+                    primitive.mode()
+                );
+                continue;
             }
 
-            // Create a vertex buffer from the mesh
-            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some(&format!("{:?} Vertex Buffer", file_name)),
-                contents: bytemuck::cast_slice(&vertices),
-                usage: wgpu::BufferUsages::VERTEX,
-            });
+            let (vertices, indices) = match gltf_vertices(&primitive, &buffers, transform) {
+                Some(vertices) => vertices,
+                None => {
+                    log::warn!(
+                        "Skipping a primitive of {:?} in {:?} without positions",
+                        mesh.name(),
+                        file_name
+                    );
+                    continue;
+                }
+            };
+
+            let material = match primitive.material().index() {
+                Some(index) => index,
+                None => *default_material.get_or_insert_with(|| {
+                    materials.push(Arc::new(gltf_material(
+                        &primitive.material(),
+                        &images,
+                        device,
+                        queue,
+                        layout,
+                    )));
+                    materials.len() - 1
+                }),
+            };
+
+            meshes.push(Arc::new(model::Mesh::new(
+                device,
+                mesh.name().unwrap_or(file_name).to_string(),
+                vertices,
+                indices,
+                material,
+            )));
+        }
+    }
 
-            // Create an index buffer from the mesh
-            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some(&format!("{:?} Index Buffer", file_name)),
-                contents: bytemuck::cast_slice(&model.mesh.indices),
-                usage: wgpu::BufferUsages::INDEX,
-            });
+    Ok(model::Model::new(meshes, materials))
+}
 
-            // Create the mesh
-            model::Mesh {
-                name: file_name.to_string(),
-                vertex_buffer,
-                index_buffer,
-                num_elements: model.mesh.indices.len() as u32,
-                material: model.mesh.material_id.unwrap_or(0),
-            }
+fn gltf_vertices(
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+    transform: Matrix4<f32>,
+) -> Option<(Vec<model::ModelVertex>, Vec<u32>)> {
+    //! The vertices and indices of a primitive, moved into place by the
+    //! transform of the node it belongs to, or None if it has no positions
+
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions = reader.read_positions()?.collect::<Vec<_>>();
+    let mut indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect::<Vec<_>>(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    let tex_coords = match reader.read_tex_coords(0) {
+        Some(tex_coords) => tex_coords.into_f32().collect::<Vec<_>>(),
+        None => vec![[0.0; 2]; positions.len()],
+    };
+    let normals = match reader.read_normals() {
+        Some(normals) => normals.collect::<Vec<_>>(),
+        None => smooth_normals(&positions, &indices),
+    };
+
+    let mut vertices = positions
+        .iter()
+        .zip(tex_coords)
+        .zip(normals)
+        .map(|((&position, tex_coords), normal)| model::ModelVertex {
+            position,
+            tex_coords,
+            normal,
+            tangent: [0.0; 3],
+            bitangent: [0.0; 3],
         })
         .collect::<Vec<_>>();
 
-    // Bundle the meshes and materials together into one object
-    Ok(model::Model { meshes, materials })
+    // Tangents exported with the model match its normal maps best, their
+    // last component is the handedness of the bitangent
+    match reader.read_tangents() {
+        Some(tangents) => {
+            for (vertex, [x, y, z, w]) in vertices.iter_mut().zip(tangents) {
+                let tangent = Vector3::new(x, y, z);
+                vertex.tangent = tangent.into();
+                vertex.bitangent = (Vector3::from(vertex.normal).cross(tangent) * w).into();
+            }
+        }
+        None => calculate_tangents(&mut vertices, &indices),
+    }
+
+    // Normals are transformed by the inverse transpose, so they stay
+    // perpendicular to the surface under non-uniform scaling
+    let linear = Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    );
+    let normal_matrix = linear
+        .invert()
+        .map(|inverse| inverse.transpose())
+        .unwrap_or(linear);
+
+    for vertex in vertices.iter_mut() {
+        let position = transform * Vector3::from(vertex.position).extend(1.0);
+        vertex.position = position.truncate().into();
+        vertex.normal = (normal_matrix * Vector3::from(vertex.normal))
+            .normalize()
+            .into();
+        vertex.tangent = (linear * Vector3::from(vertex.tangent)).into();
+        vertex.bitangent = (linear * Vector3::from(vertex.bitangent)).into();
+    }
+
+    // A mirroring transform turns the triangles inside out, so their winding
+    // is reversed to keep them facing outwards
+    if linear.determinant() < 0.0 {
+        for triangle in indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+
+    Some((vertices, indices))
+}
+
+fn gltf_image(index: usize, data: gltf::image::Data) -> Result<DynamicImage, LoadModelError> {
+    //! Converts an image decoded by the glTF importer into an image that can
+    //! be made into a texture
+
+    use gltf::image::Format;
+
+    let (width, height, pixels) = (data.width, data.height, data.pixels);
+    let image = match data.format {
+        Format::R8 => GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8),
+        Format::R8G8 => {
+            GrayAlphaImage::from_raw(width, height, pixels).map(DynamicImage::ImageLumaA8)
+        }
+        Format::R8G8B8 => RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8),
+        Format::R8G8B8A8 => {
+            RgbaImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8)
+        }
+        _ => None,
+    };
+
+    image.ok_or(LoadModelError::UnsupportedImageFormat(index, data.format))
+}
+
+fn linear_to_srgb(colour: [f32; 4]) -> [f32; 4] {
+    //! Encodes a linear colour, as glTF's factors are, for an sRGB texture.
+    //! The alpha is always linear
+    let [r, g, b, a] = colour;
+    let encode = |c: f32| {
+        if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    };
+    [encode(r), encode(g), encode(b), a]
+}
+
+fn channel_map(source: &DynamicImage, channel: usize, factor: f32) -> DynamicImage {
+    //! A single channel of an image as a greyscale image, scaled by a factor
+    let rgba = source.to_rgba8();
+    DynamicImage::ImageLuma8(GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let value = rgba.get_pixel(x, y).0[channel] as f32 * factor;
+        Luma([value.round().clamp(0.0, 255.0) as u8])
+    }))
+}
+
+fn gltf_material(
+    material: &gltf::Material,
+    images: &[DynamicImage],
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+) -> model::Material {
+    //! Converts a glTF metallic-roughness material into a material, with
+    //! flat colours for any textures it is missing
+
+    let name = material.name().unwrap_or("glTF Default Material");
+    let pbr = material.pbr_metallic_roughness();
+    let label = |map: &str| format!("{:?} {} Texture", name, map);
+
+    // Exporters leave the base colour factor white when there is a texture,
+    // so it is only used without one
+    let diffuse_texture = match pbr.base_color_texture() {
+        Some(info) => texture::Texture::from_image(
+            device,
+            queue,
+            &images[info.texture().source().index()],
+            Some(&label("Diffuse")),
+            false,
+        ),
+        None => texture::Texture::from_colour(
+            device,
+            queue,
+            linear_to_srgb(pbr.base_color_factor()),
+            Some(&label("Diffuse")),
+            false,
+        ),
+    };
+
+    // glTF packs the roughness into the green channel of one texture and the
+    // metalness into its blue channel, they are split into separate maps
+    let (roughness, metallic) = match pbr.metallic_roughness_texture() {
+        Some(info) => {
+            let image = &images[info.texture().source().index()];
+            (
+                channel_map(image, 1, pbr.roughness_factor()),
+                channel_map(image, 2, pbr.metallic_factor()),
+            )
+        }
+        None => {
+            let flat = |factor: f32| {
+                DynamicImage::ImageLuma8(GrayImage::from_pixel(
+                    1,
+                    1,
+                    Luma([(factor.clamp(0.0, 1.0) * 255.0).round() as u8]),
+                ))
+            };
+            (flat(pbr.roughness_factor()), flat(pbr.metallic_factor()))
+        }
+    };
+
+    let maps = model::MaterialMaps {
+        normal: material.normal_texture().map(|normal| {
+            texture::Texture::from_image(
+                device,
+                queue,
+                &images[normal.texture().source().index()],
+                Some(&label("Normal")),
+                true,
+            )
+        }),
+        // Every metallic-roughness material reflects, the roughness decides
+        // how sharply
+        specular: Some(texture::Texture::from_colour(
+            device,
+            queue,
+            [1.0; 4],
+            Some(&label("Specular")),
+            true,
+        )),
+        roughness: Some(texture::Texture::from_image(
+            device,
+            queue,
+            &roughness,
+            Some(&label("Roughness")),
+            true,
+        )),
+        metallic: Some(texture::Texture::from_image(
+            device,
+            queue,
+            &metallic,
+            Some(&label("Metallic")),
+            true,
+        )),
+    };
+
+    model::Material::new(device, queue, name, diffuse_texture, maps, layout)
 }
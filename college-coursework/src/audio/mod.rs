@@ -0,0 +1,443 @@
+mod playlist;
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam::channel::{self, Receiver, RecvTimeoutError, Sender};
+use log::{info, warn};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source, StreamError};
+
+use playlist::{find_tracks, Playlist, Track};
+pub use playlist::{RepeatMode, USER_MUSIC_DIR};
+
+/// How often the music thread updates crossfades and checks whether a track
+/// is ending, while waiting for commands
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The longest crossfade that can be chosen (in seconds)
+pub const MAX_CROSSFADE: f32 = 10.0;
+
+/// Shared with the player when no track is playing, after the end of the
+/// playlist
+const NO_TRACK: usize = usize::MAX;
+
+/// Changes sent to the thread playing the music
+enum MusicCommand {
+    /// The volume to play at, from 0 to 1, already taking muting into account
+    Volume(f32),
+    Paused(bool),
+    /// Move on to the next track
+    Skip,
+    Shuffled(bool),
+    Repeat(RepeatMode),
+    Crossfade(Duration),
+}
+
+/// Controls the background music, which is played on its own thread
+pub struct MusicPlayer {
+    /// None when audio is turned off
+    sender: Option<Sender<MusicCommand>>,
+    tracks: Arc<Vec<Track>>,
+    /// The index of the track playing, updated by the music thread
+    track: Arc<AtomicUsize>,
+    /// From 0 to 1
+    volume: f32,
+    muted: bool,
+    paused: bool,
+    shuffled: bool,
+    repeat: RepeatMode,
+    /// How long one track fades into the next (in seconds)
+    crossfade: f32,
+}
+impl Default for MusicPlayer {
+    fn default() -> Self {
+        Self {
+            sender: None,
+            tracks: Arc::new(Vec::new()),
+            track: Arc::new(AtomicUsize::new(0)),
+            volume: 1.0,
+            muted: false,
+            paused: false,
+            shuffled: false,
+            repeat: RepeatMode::All,
+            crossfade: 2.0,
+        }
+    }
+}
+impl MusicPlayer {
+    pub fn start(volume: f32) -> Result<(OutputStream, Self), StreamError> {
+        //! Start playing the music found on disk on another thread, streaming
+        //! each track as it is played. The stream has to be kept alive for
+        //! the music to play
+
+        let (stream, stream_handle) = OutputStream::try_default()?;
+
+        let tracks = find_tracks();
+        if tracks.is_empty() {
+            warn!("No music was found to play");
+            return Ok((stream, Self::default()));
+        }
+        info!("Found {} music tracks", tracks.len());
+
+        let (sender, receiver) = channel::unbounded();
+        let player = Self {
+            sender: Some(sender),
+            tracks: Arc::new(tracks),
+            volume: volume.clamp(0.0, 1.0),
+            ..Self::default()
+        };
+
+        let mut music_thread = MusicThread {
+            stream_handle,
+            commands: receiver,
+            playlist: Playlist::new(player.tracks.len()),
+            tracks: player.tracks.clone(),
+            track: player.track.clone(),
+            volume: player.volume,
+            paused: false,
+            crossfade: Duration::from_secs_f32(player.crossfade),
+            playing: None,
+            fading: None,
+            failures: 0,
+        };
+        thread::spawn(move || music_thread.run());
+
+        Ok((stream, player))
+    }
+
+    pub fn enabled(&self) -> bool {
+        //! Whether there is any music to control
+        self.sender.is_some()
+    }
+
+    fn send(&self, command: MusicCommand) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(command);
+        }
+    }
+
+    fn send_volume(&self) {
+        self.send(MusicCommand::Volume(if self.muted {
+            0.0
+        } else {
+            self.volume
+        }));
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        self.send_volume();
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.send_volume();
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.send(MusicCommand::Paused(paused));
+    }
+
+    pub fn skip(&self) {
+        self.send(MusicCommand::Skip);
+    }
+
+    pub fn shuffled(&self) -> bool {
+        self.shuffled
+    }
+
+    pub fn set_shuffled(&mut self, shuffled: bool) {
+        self.shuffled = shuffled;
+        self.send(MusicCommand::Shuffled(shuffled));
+    }
+
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+        self.send(MusicCommand::Repeat(repeat));
+    }
+
+    pub fn crossfade(&self) -> f32 {
+        self.crossfade
+    }
+
+    pub fn set_crossfade(&mut self, crossfade: f32) {
+        self.crossfade = crossfade.clamp(0.0, MAX_CROSSFADE);
+        self.send(MusicCommand::Crossfade(Duration::from_secs_f32(
+            self.crossfade,
+        )));
+    }
+
+    pub fn current_track(&self) -> Option<&str> {
+        //! The name of the track playing, or none if no music is playing
+
+        self.tracks
+            .get(self.track.load(Ordering::Relaxed))
+            .filter(|_| self.enabled())
+            .map(|track| track.name.as_str())
+    }
+}
+
+/// A track being played on its own sink
+struct PlayingTrack {
+    sink: Sink,
+    /// How much of the track has been played
+    elapsed: Duration,
+    /// The length of the track, once it is known
+    duration: Option<Duration>,
+    /// Sent the length of the track once it has been measured
+    measured: Option<Receiver<Duration>>,
+}
+impl PlayingTrack {
+    fn ending(&self, crossfade: Duration) -> bool {
+        //! Whether the track has finished, or is close enough to the end to
+        //! start fading into the next one
+        self.sink.empty()
+            || self
+                .duration
+                .map_or(false, |duration| self.elapsed + crossfade >= duration)
+    }
+}
+
+fn measure_duration(path: &Path) -> Option<Duration> {
+    //! Find the length of a track, decoding all of it when the format doesn't
+    //! say
+
+    let decoder = Decoder::new(BufReader::new(File::open(path).ok()?)).ok()?;
+    if let Some(duration) = decoder.total_duration() {
+        return Some(duration);
+    }
+
+    let samples_per_second = decoder.channels() as f64 * decoder.sample_rate() as f64;
+    Some(Duration::from_secs_f64(
+        decoder.count() as f64 / samples_per_second,
+    ))
+}
+
+/// Plays the playlist, carrying out the commands from the player
+struct MusicThread {
+    stream_handle: OutputStreamHandle,
+    commands: Receiver<MusicCommand>,
+    playlist: Playlist,
+    tracks: Arc<Vec<Track>>,
+    /// Shared with the player, to show which track is playing
+    track: Arc<AtomicUsize>,
+    volume: f32,
+    paused: bool,
+    crossfade: Duration,
+    /// None after the end of the playlist
+    playing: Option<PlayingTrack>,
+    /// The previous track fading out, with how far through the fade it is
+    fading: Option<(PlayingTrack, Duration)>,
+    /// Tracks in a row which couldn't be played
+    failures: usize,
+}
+impl MusicThread {
+    fn run(&mut self) {
+        //! Play each track in turn until the player is dropped
+
+        self.play(self.playlist.current(), false);
+        let mut last_update = Instant::now();
+
+        loop {
+            match self.commands.recv_timeout(POLL_INTERVAL) {
+                Ok(command) => self.command(command),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let elapsed = last_update.elapsed();
+            last_update = Instant::now();
+            if !self.paused {
+                self.advance(elapsed);
+            }
+
+            // Move on once the track is ending, unless still fading into it
+            let ending = self
+                .playing
+                .as_ref()
+                .map_or(false, |playing| playing.ending(self.crossfade));
+            if ending && self.fading.is_none() {
+                match self.playlist.next(false) {
+                    Some(index) => self.play(index, true),
+                    None => self.stop(),
+                }
+            }
+
+            // Every track has been tried without any playing
+            if self.failures >= self.tracks.len() {
+                warn!("None of the music could be played");
+                return;
+            }
+        }
+    }
+
+    fn command(&mut self, command: MusicCommand) {
+        match command {
+            MusicCommand::Volume(volume) => {
+                self.volume = volume;
+                self.update_volumes();
+            }
+            MusicCommand::Paused(paused) => {
+                self.paused = paused;
+                let sinks = self
+                    .playing
+                    .iter()
+                    .chain(self.fading.iter().map(|(fading, _)| fading))
+                    .map(|track| &track.sink);
+                for sink in sinks {
+                    if paused {
+                        sink.pause();
+                    } else {
+                        sink.play();
+                    }
+                }
+            }
+            MusicCommand::Skip => {
+                // Skipping always gives another track
+                let index = self.playlist.next(true).unwrap();
+                self.play(index, true);
+            }
+            MusicCommand::Shuffled(shuffled) => self.playlist.set_shuffled(shuffled),
+            MusicCommand::Repeat(repeat) => self.playlist.repeat = repeat,
+            MusicCommand::Crossfade(crossfade) => {
+                self.crossfade = crossfade;
+                if crossfade.is_zero() {
+                    self.fading = None;
+                }
+            }
+        }
+    }
+
+    fn play(&mut self, index: usize, fade: bool) {
+        //! Start playing a track, fading out the one playing if there is a
+        //! crossfade
+
+        self.track.store(index, Ordering::Relaxed);
+
+        // Only one track fades out at a time, any other is stopped
+        let previous = self.playing.take();
+        self.fading = previous
+            .filter(|_| fade && !self.crossfade.is_zero())
+            .map(|previous| (previous, Duration::ZERO));
+
+        let sink = match Sink::try_new(&self.stream_handle) {
+            Ok(sink) => sink,
+            Err(err) => {
+                warn!("Failed to play music: {}", err);
+                self.failures = self.tracks.len();
+                return;
+            }
+        };
+        if self.paused {
+            sink.pause();
+        }
+
+        let path = self.tracks[index].path.clone();
+        match File::open(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|err| err.to_string()))
+        {
+            Ok(source) => {
+                sink.append(source);
+                self.failures = 0;
+            }
+            // The empty sink is moved on from straight away
+            Err(err) => {
+                warn!("Failed to play the music {:?}: {}", path, err);
+                self.failures += 1;
+            }
+        }
+
+        // Measured without holding up the music
+        let (sender, measured) = channel::bounded(1);
+        thread::spawn(move || {
+            if let Some(duration) = measure_duration(&path) {
+                let _ = sender.send(duration);
+            }
+        });
+
+        self.playing = Some(PlayingTrack {
+            sink,
+            elapsed: Duration::ZERO,
+            duration: None,
+            measured: Some(measured),
+        });
+        self.update_volumes();
+    }
+
+    fn stop(&mut self) {
+        //! Stop once the playlist has finished, fading out the last track
+
+        self.track.store(NO_TRACK, Ordering::Relaxed);
+        let last = self.playing.take();
+        if self.fading.is_none() && !self.crossfade.is_zero() {
+            self.fading = last.map(|last| (last, Duration::ZERO));
+        }
+    }
+
+    fn advance(&mut self, elapsed: Duration) {
+        //! Keep track of how much has been played, and continue any crossfade
+
+        if let Some(playing) = &mut self.playing {
+            playing.elapsed += elapsed;
+            if let Some(duration) = playing
+                .measured
+                .as_ref()
+                .and_then(|measured| measured.try_recv().ok())
+            {
+                playing.duration = Some(duration);
+                playing.measured = None;
+            }
+        }
+
+        if let Some((_, progress)) = &mut self.fading {
+            *progress += elapsed;
+            if *progress >= self.crossfade {
+                self.fading = None;
+            }
+        }
+        self.update_volumes();
+    }
+
+    fn update_volumes(&self) {
+        //! Set the volume of each sink, with the track fading in getting
+        //! louder as the one fading out gets quieter
+
+        let fade = self.fading.as_ref().map_or(1.0, |(_, progress)| {
+            (progress.as_secs_f32() / self.crossfade.as_secs_f32()).min(1.0)
+        });
+
+        if let Some(playing) = &self.playing {
+            playing.sink.set_volume(self.volume * fade);
+        }
+        if let Some((fading, _)) = &self.fading {
+            fading.sink.set_volume(self.volume * (1.0 - fade));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::{fs, path::PathBuf};
+
+use log::warn;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{assets, config::CONFIG_DIR, locale::tr};
+
+/// The folder of music in the assets
+const MUSIC_FOLDER: &'static str = "music";
+
+/// The bundled background music, inside the music folder
+const BACKGROUND_FOLDER: &'static str = "background";
+
+lazy_static! {
+    /// Where players can add their own music, played after the bundled music
+    pub static ref USER_MUSIC_DIR: PathBuf = CONFIG_DIR.join(MUSIC_FOLDER);
+}
+
+/// Extensions of the files that can be decoded
+const EXTENSIONS: [&'static str; 4] = ["mp3", "ogg", "wav", "flac"];
+
+/// A piece of music, read from disk as it is played
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    /// The file name without its extension
+    pub name: String,
+    pub path: PathBuf,
+}
+
+pub fn find_tracks() -> Vec<Track> {
+    //! Returns the bundled background music, followed by any music in the
+    //! config directory, each in alphabetical order
+
+    [
+        assets::asset_path(MUSIC_FOLDER).join(BACKGROUND_FOLDER),
+        USER_MUSIC_DIR.clone(),
+    ]
+    .into_iter()
+    .flat_map(|folder| {
+        let mut paths = match fs::read_dir(&folder) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .and_then(|extension| extension.to_str())
+                        .map_or(false, |extension| {
+                            EXTENSIONS.contains(&extension.to_lowercase().as_str())
+                        })
+                })
+                .collect::<Vec<_>>(),
+            // There is no music in the config directory until some is added
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                warn!("Failed to find the music in {:?}: {}", folder, err);
+                Vec::new()
+            }
+        };
+
+        paths.sort();
+        paths
+    })
+    .map(|path| Track {
+        name: path
+            .file_stem()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+        path,
+    })
+    .collect()
+}
+
+/// What happens when a track finishes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RepeatMode {
+    /// Start the playlist again after the last track
+    All,
+    /// Play the same track again, unless it is skipped
+    One,
+    /// Stop after the last track
+    Off,
+}
+impl RepeatMode {
+    pub const ALL: [Self; 3] = [Self::All, Self::One, Self::Off];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::All => tr!("repeat-all"),
+            Self::One => tr!("repeat-one"),
+            Self::Off => tr!("repeat-off"),
+        }
+    }
+}
+
+/// The order the tracks are played in
+#[derive(Debug, Clone)]
+pub struct Playlist {
+    /// Indices of the tracks, in the order they are played
+    order: Vec<usize>,
+    /// Where in the order the current track is
+    position: usize,
+    shuffled: bool,
+    pub repeat: RepeatMode,
+}
+impl Playlist {
+    pub fn new(len: usize) -> Self {
+        Self {
+            order: (0..len).collect(),
+            position: 0,
+            shuffled: false,
+            repeat: RepeatMode::All,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.order[self.position]
+    }
+
+    pub fn next(&mut self, skipped: bool) -> Option<usize> {
+        //! Move on to the track to play after the current one, or none if
+        //! the playlist has finished. Skipping always moves on to another
+        //! track, even when repeating one or at the end
+
+        if self.repeat == RepeatMode::One && !skipped {
+            return Some(self.current());
+        }
+
+        if self.position + 1 < self.order.len() {
+            self.position += 1;
+        } else if self.repeat == RepeatMode::Off && !skipped {
+            return None;
+        } else {
+            // Each time round is in a different order when shuffled
+            if self.shuffled {
+                self.order.shuffle(&mut rand::thread_rng());
+            }
+            self.position = 0;
+        }
+
+        Some(self.current())
+    }
+
+    pub fn shuffled(&self) -> bool {
+        self.shuffled
+    }
+
+    pub fn set_shuffled(&mut self, shuffled: bool) {
+        //! Change the order of the tracks, without changing the current one.
+        //! Once shuffled, every other track is played before any repeat
+
+        let current = self.current();
+        self.shuffled = shuffled;
+
+        self.order.sort();
+        if shuffled {
+            self.order.shuffle(&mut rand::thread_rng());
+            let position = self.order.iter().position(|&i| i == current).unwrap();
+            self.order.swap(0, position);
+        }
+        self.position = self.order.iter().position(|&i| i == current).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Playlist, RepeatMode};
+
+    #[test]
+    fn test_repeat_modes() {
+        let mut playlist = Playlist::new(3);
+        assert_eq!(Some(1), playlist.next(false));
+        assert_eq!(Some(2), playlist.next(false));
+        assert_eq!(Some(0), playlist.next(false));
+
+        playlist.repeat = RepeatMode::One;
+        assert_eq!(Some(0), playlist.next(false));
+        assert_eq!(Some(1), playlist.next(true));
+
+        playlist.repeat = RepeatMode::Off;
+        assert_eq!(Some(2), playlist.next(false));
+        assert_eq!(None, playlist.next(false));
+        assert_eq!(Some(0), playlist.next(true));
+    }
+
+    #[test]
+    fn test_shuffle_plays_every_track() {
+        let mut playlist = Playlist::new(10);
+        playlist.next(false);
+        playlist.set_shuffled(true);
+        assert_eq!(1, playlist.current());
+
+        let mut played = (0..9)
+            .map(|_| playlist.next(false).unwrap())
+            .collect::<Vec<_>>();
+        played.push(1);
+        played.sort();
+        assert_eq!((0..10).collect::<Vec<_>>(), played);
+
+        playlist.set_shuffled(false);
+        let current = playlist.current();
+        assert_eq!(Some((current + 1) % 10), playlist.next(false));
+    }
+}
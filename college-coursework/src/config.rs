@@ -0,0 +1,248 @@
+use std::{fs, io, path::PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use specs::{World, WorldExt};
+
+use crate::{
+    args::Args,
+    renderer::settings::{Backend, PresentMode, RenderSettings},
+    simulation::{GravitationalConstant, SaveError, Scenario, SimulationState, TimeScale},
+    util::app_dir,
+};
+
+lazy_static! {
+    // On Windows the config is kept beside the logs and data in %AppData%,
+    // so it needs a directory of its own
+    pub static ref CONFIG_DIR: PathBuf = app_dir(
+        "SS_CONFIG_DIR",
+        "XDG_CONFIG_HOME",
+        ".config",
+        if cfg!(target_family = "windows") { "config" } else { "" },
+    );
+}
+
+const CONFIG_FILE: &'static str = "config.toml";
+
+/// Written by `--write-default-config`. Every option is commented out, so it
+/// changes nothing until they are uncommented
+const DEFAULT_CONFIG: &'static str = r#"# Options used when the program starts, each overridden by the matching
+# command line argument. Uncomment an option to change it
+
+# Simulated seconds for every real second
+#time_scale = 3155760.0
+
+# The gravitational constant (in m^3 kg^-1 s^-2)
+#gravitational_constant = 6.6743015e-11
+
+# A scenario to start with, instead of the solar system. It can be TOML or
+# RON, depending on its extension
+#scenario = "experiment.toml"
+
+# Volume of the music, from 0 to 1
+#volume = 1.0
+
+# Listen for JSON-RPC commands over a WebSocket on this port, so other
+# programs can control the simulation. Only this machine can connect
+#remote_port = 9001
+
+[graphics]
+# How frames are presented: "AutoVsync", "Immediate" or "Mailbox"
+#present_mode = "AutoVsync"
+
+# The graphics API used: "Auto", "Vulkan", "Metal", "Dx12" or "Gl"
+#backend = "Auto"
+
+# Whether reflective materials reflect the stars and lights around them
+#reflections = true
+
+# Whether the real stars are drawn behind the solar system
+#stars = true
+
+# Whether frames are only drawn when something changes, to save power
+#power_saving = true
+"#;
+
+/// Options set when the program starts, read from the config file. Anything
+/// left unset keeps its usual default, or the value saved by the last session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Simulated seconds for every real second
+    pub time_scale: Option<f64>,
+    /// (in m^3 kg^-1 s^-2)
+    pub gravitational_constant: Option<f64>,
+    /// A scenario to start with, instead of the solar system
+    pub scenario: Option<PathBuf>,
+    /// A saved simulation to start with, which is only given on the command
+    /// line
+    #[serde(skip)]
+    pub load: Option<PathBuf>,
+    /// Volume of the music, from 0 to 1
+    pub volume: Option<f32>,
+    /// The port of the remote control server, which isn't started without one
+    pub remote_port: Option<u16>,
+    pub graphics: GraphicsConfig,
+}
+impl StartupConfig {
+    pub fn load(args: &Args, safe_mode: bool) -> Self {
+        //! Load the config file from the config directory, with any options
+        //! given on the command line taking priority. Safe mode ignores the
+        //! file in case it caused the crash
+
+        let path = CONFIG_DIR.join(CONFIG_FILE);
+
+        let mut config: Self = match fs::read_to_string(&path) {
+            Ok(_) if safe_mode => Self::default(),
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid config in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+
+        if args.time_scale.is_some() {
+            config.time_scale = args.time_scale;
+        }
+        if args.gravitational_constant.is_some() {
+            config.gravitational_constant = args.gravitational_constant;
+        }
+        if args.scenario.is_some() {
+            config.scenario = args.scenario.clone();
+        }
+        config.load = args.load.clone();
+        if args.volume.is_some() {
+            config.volume = args.volume;
+        }
+        if args.remote_port.is_some() {
+            config.remote_port = args.remote_port;
+        }
+        if args.present_mode.is_some() {
+            config.graphics.present_mode = args.present_mode;
+        }
+        if args.backend.is_some() {
+            config.graphics.backend = args.backend;
+        }
+
+        config
+    }
+
+    pub fn write_default() -> io::Result<PathBuf> {
+        //! Write the default config file to the config directory, without
+        //! replacing one that is already there
+
+        let path = CONFIG_DIR.join(CONFIG_FILE);
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{:?} already exists", path),
+            ));
+        }
+
+        fs::create_dir_all(CONFIG_DIR.as_path())?;
+        fs::write(&path, DEFAULT_CONFIG)?;
+
+        Ok(path)
+    }
+
+    pub fn load_scenario(&self) -> Option<SimulationState> {
+        //! Read the saved simulation or scenario to start with, if there is
+        //! one, with a save taking priority. This is done before the world is
+        //! set up, so the solar system isn't made just to be replaced
+
+        let (path, result) = match (&self.load, &self.scenario) {
+            (Some(path), _) => (path, SimulationState::load_file(path)),
+            (None, Some(path)) => (
+                path,
+                Scenario::load_file(path)
+                    .and_then(Scenario::into_state)
+                    .map_err(SaveError::from),
+            ),
+            (None, None) => return None,
+        };
+
+        match result {
+            Ok(state) => {
+                info!("Starting with {:?}", path);
+                Some(state)
+            }
+            Err(err) => {
+                warn!("Failed to start with {:?}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    pub fn apply(&self, world: &mut World) {
+        //! Start the simulation with the time scale and gravitational constant
+        //! from the config, replacing those in the scenario
+
+        if let Some(time_scale) = self.time_scale {
+            let mut current = world.write_resource::<TimeScale>();
+            *current = TimeScale::new(time_scale, current.iterations);
+        }
+        if let Some(gravitational_constant) = self.gravitational_constant {
+            world.insert(GravitationalConstant(gravitational_constant));
+        }
+    }
+}
+
+/// Render settings set when the program starts, replacing the ones saved by
+/// the last session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsConfig {
+    pub present_mode: Option<PresentMode>,
+    pub backend: Option<Backend>,
+    pub reflections: Option<bool>,
+    pub stars: Option<bool>,
+    pub power_saving: Option<bool>,
+}
+impl GraphicsConfig {
+    pub fn apply(&self, settings: &mut RenderSettings) {
+        if let Some(present_mode) = self.present_mode {
+            settings.present_mode = present_mode;
+        }
+        if let Some(backend) = self.backend {
+            settings.backend = backend;
+        }
+        if let Some(reflections) = self.reflections {
+            settings.reflections = reflections;
+        }
+        if let Some(stars) = self.stars {
+            settings.stars = stars;
+        }
+        if let Some(power_saving) = self.power_saving {
+            settings.power_saving = power_saving;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StartupConfig, DEFAULT_CONFIG};
+
+    #[test]
+    fn test_default_config() {
+        // Everything is commented out, so nothing is changed
+        assert_eq!(
+            StartupConfig::default(),
+            toml::from_str::<StartupConfig>(DEFAULT_CONFIG).unwrap()
+        );
+
+        // Every option is valid once uncommented
+        let uncommented = DEFAULT_CONFIG
+            .lines()
+            .map(|line| match line.strip_prefix('#') {
+                Some(option) if !option.starts_with(' ') => option,
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let config = toml::from_str::<StartupConfig>(&uncommented).unwrap();
+        assert_eq!(Some(3155760.0), config.time_scale);
+        assert!(config.scenario.is_some());
+        assert_eq!(Some(true), config.graphics.reflections);
+        assert_eq!(Some(true), config.graphics.power_saving);
+    }
+}
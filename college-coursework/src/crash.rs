@@ -0,0 +1,44 @@
+use std::{fs, process};
+
+use log::warn;
+
+use crate::data::DATA_DIR;
+
+/// Exists while the program is running, so finding it at startup means the
+/// last run didn't close cleanly
+const RUNNING_FILE: &'static str = "running";
+
+pub fn previous_run_crashed() -> bool {
+    //! Returns whether the last run of the program crashed
+    DATA_DIR.join(RUNNING_FILE).exists()
+}
+
+pub fn mark_running() {
+    //! Record that the program is running, to be cleared on a clean exit
+
+    if let Err(err) = fs::create_dir_all(DATA_DIR.as_path())
+        .and_then(|_| fs::write(DATA_DIR.join(RUNNING_FILE), process::id().to_string()))
+    {
+        warn!("Failed to record that the program is running: {}", err);
+    }
+}
+
+pub fn mark_stopped() {
+    //! Record that the program closed cleanly
+    let _ = fs::remove_file(DATA_DIR.join(RUNNING_FILE));
+}
+
+pub fn offer_safe_mode() -> bool {
+    //! Ask the user whether to start in safe mode after a crash, returns
+    //! whether they accepted
+
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("Start in Safe Mode?")
+        .set_description(
+            "The program didn't close properly last time. \
+            Start in safe mode, with audio off and the default settings and layout?",
+        )
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+}
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use crate::util::app_dir;
+
+lazy_static! {
+    // On Windows the data is kept beside the logs and config in %AppData%,
+    // so it needs a directory of its own
+    pub static ref DATA_DIR: PathBuf = app_dir(
+        "SS_DATA_DIR",
+        "XDG_DATA_HOME",
+        ".local/share",
+        if cfg!(target_family = "windows") { "data" } else { "" },
+    );
+}
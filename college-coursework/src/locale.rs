@@ -0,0 +1,229 @@
+use std::{env, fs, path::Path, sync::RwLock};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+use crate::{assets, simulation::Identifier};
+
+/// The folder of translations in the assets, with a folder for each language
+const LOCALES_FOLDER: &'static str = "locales";
+
+/// The files of messages in each language's folder
+const LOCALE_FILES: [&'static str; 3] = ["ui.ftl", "help.ftl", "bodies.ftl"];
+
+/// Messages missing from a translation are shown in this language instead
+const FALLBACK_LANGUAGE: Language = Language::English;
+
+lazy_static! {
+    static ref LOCALISER: RwLock<Localiser> = RwLock::new(Localiser::new(FALLBACK_LANGUAGE));
+}
+
+/// A language the UI can be shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    French,
+}
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::French];
+
+    pub fn code(&self) -> &'static str {
+        //! The code of the language, which names its folder of translations
+        match self {
+            Self::English => "en",
+            Self::French => "fr",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        //! The name of the language in that language, so it can be found by
+        //! someone who can't read the current one
+        match self {
+            Self::English => "English",
+            Self::French => "Français",
+        }
+    }
+
+    pub fn from_system() -> Self {
+        //! The language of the system if it has been translated, otherwise
+        //! English
+
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| {
+                // Such as "fr_FR.UTF-8"
+                Self::ALL
+                    .into_iter()
+                    .find(|language| locale.starts_with(language.code()))
+            })
+            .unwrap_or(FALLBACK_LANGUAGE)
+    }
+
+    fn identifier(&self) -> LanguageIdentifier {
+        self.code().parse().unwrap()
+    }
+}
+impl Default for Language {
+    fn default() -> Self {
+        Self::from_system()
+    }
+}
+
+fn load_bundle(language: Language, folder: &Path) -> FluentBundle<FluentResource> {
+    //! Read every file of messages for a language, skipping any that can't be
+    //! read
+
+    let mut bundle = FluentBundle::new_concurrent(vec![language.identifier()]);
+    // egui can't draw the marks isolating arguments from the text around them
+    bundle.set_use_isolating(false);
+
+    for file in LOCALE_FILES {
+        let path = folder.join(language.code()).join(file);
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) => {
+                warn!("Failed to read the translations in {:?}: {}", path, err);
+                continue;
+            }
+        };
+
+        let resource = FluentResource::try_new(source).unwrap_or_else(|(resource, errors)| {
+            warn!("Invalid translations in {:?}: {:?}", path, errors);
+            resource
+        });
+        if let Err(errors) = bundle.add_resource(resource) {
+            warn!("Repeated translations in {:?}: {:?}", path, errors);
+        }
+    }
+
+    bundle
+}
+
+/// Translates messages into the chosen language
+struct Localiser {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+    /// None while the chosen language is the fallback
+    fallback: Option<FluentBundle<FluentResource>>,
+}
+impl Localiser {
+    fn new(language: Language) -> Self {
+        let folder = assets::asset_path(LOCALES_FOLDER);
+
+        Self {
+            language,
+            bundle: load_bundle(language, &folder),
+            fallback: (language != FALLBACK_LANGUAGE)
+                .then(|| load_bundle(FALLBACK_LANGUAGE, &folder)),
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        [Some(&self.bundle), self.fallback.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(
+                    bundle
+                        .format_pattern(pattern, args, &mut errors)
+                        .into_owned(),
+                )
+            })
+    }
+}
+
+pub fn language() -> Language {
+    LOCALISER.read().unwrap().language
+}
+
+pub fn set_language(language: Language) {
+    //! Show the UI in another language, from the next frame
+
+    if language != self::language() {
+        *LOCALISER.write().unwrap() = Localiser::new(language);
+    }
+}
+
+pub fn text(id: &str, args: Option<&FluentArgs>) -> String {
+    //! The message with an id in the chosen language, or the id itself if no
+    //! language has it, so missing messages can be spotted
+
+    LOCALISER
+        .read()
+        .unwrap()
+        .format(id, args)
+        .unwrap_or_else(|| id.to_string())
+}
+
+pub fn body_name(id: &Identifier) -> String {
+    //! The name of a body in the chosen language. Bodies without a
+    //! translation, such as those from scenarios, keep their own name
+
+    LOCALISER
+        .read()
+        .unwrap()
+        .format(&format!("body-{}", id.get_id()), None)
+        .unwrap_or_else(|| id.get_name().to_string())
+}
+
+/// Translate a message, with any arguments given as `name = value`
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::locale::text($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::locale::text($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fluent_syntax::{ast, parser};
+
+    use super::{Language, LOCALES_FOLDER, LOCALE_FILES};
+
+    #[test]
+    fn test_translations_complete() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(LOCALES_FOLDER);
+
+        let message_ids = |language: Language| {
+            let mut ids = LOCALE_FILES
+                .iter()
+                .flat_map(|file| {
+                    let source =
+                        std::fs::read_to_string(folder.join(language.code()).join(file)).unwrap();
+                    let resource = parser::parse(source.as_str()).unwrap();
+                    resource
+                        .body
+                        .into_iter()
+                        .filter_map(|entry| match entry {
+                            ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+
+        // Every language parses and has the same messages as English
+        let english = message_ids(Language::English);
+        for language in Language::ALL {
+            assert_eq!(english, message_ids(language), "{:?}", language);
+        }
+    }
+}
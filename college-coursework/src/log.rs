@@ -1,46 +1,63 @@
-use std::{env, path::PathBuf};
+use std::{collections::VecDeque, path::PathBuf, sync::Mutex};
 
+use chrono::{DateTime, Local};
 use const_format::concatcp;
 use error_stack::{IntoReport, Result, ResultExt};
 
-use crate::APPLICATION_NAME;
+use crate::{util::app_dir, APPLICATION_NAME};
 
 lazy_static! {
-    pub static ref LOG_DIR: PathBuf = {
-        // Check for the environment variable declaring the log directory
-        let log_dir = env::var("SS_LOG_DIR").map(|v| PathBuf::from(v.as_str()));
+    pub static ref LOG_DIR: PathBuf =
+        app_dir("SS_LOG_DIR", "XDG_STATE_HOME", ".local/state", "logs");
 
-        // If the log directory environment variable is not found use a default
-        // location
+    /// The most recent records of this session, shown in the log window
+    pub static ref SESSION_LOG: Mutex<VecDeque<LogEntry>> =
+        Mutex::new(VecDeque::with_capacity(SESSION_LOG_LENGTH));
+}
+
+/// The number of records kept in memory, older ones are only in the log file
+const SESSION_LOG_LENGTH: usize = 2000;
+
+/// A record logged during this session
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: log::Level,
+    pub target: String,
+    pub message: String,
+}
+impl LogEntry {
+    pub fn line(&self) -> String {
+        //! The entry as it is written to the log file
 
-        // On linux check for the $XDG_STATE_HOME environment variable first,
-        // if not found then coose the default location for $XDG_STATE_HOME
-        // at /home/<user>/.local/state
-        #[cfg(target_family = "unix")]
-        let log_dir = log_dir
-            .or(env::var("XDG_STATE_HOME")
-                .map(|v| PathBuf::from(v.as_str()))
-                .map(|v| v.join(APPLICATION_NAME).join("logs")))
-            .or(env::var("HOME")
-                .map(|v| PathBuf::from(v.as_str()))
-                .map(|v| {
-                    v.join(".local")
-                        .join("state")
-                        .join(APPLICATION_NAME)
-                        .join("logs")
-                }))
-            .expect("Failed to load log directory, no $HOME set");
+        format!(
+            "time={} target={} level={} msg={:?}",
+            self.time.to_rfc3339(),
+            self.target,
+            self.level,
+            self.message
+        )
+    }
+}
 
-        // On Windows check for the %appdata% environment variable
-        #[cfg(target_family = "windows")]
-        let log_dir = log_dir
-            .or(env::var("appdata")
-                .map(|v| PathBuf::from(v.as_str()))
-                .map(|v| v.join(APPLICATION_NAME).join("logs")))
-            .expect("Failed to load log directory, no %AppData% set");
+fn keep_record(record: &log::Record) {
+    //! Add a record to the session's log, forgetting the oldest when it is
+    //! full
 
-        log_dir
+    let mut session_log = match SESSION_LOG.lock() {
+        Ok(session_log) => session_log,
+        Err(_) => return,
     };
+
+    if session_log.len() >= SESSION_LOG_LENGTH {
+        session_log.pop_front();
+    }
+    session_log.push_back(LogEntry {
+        time: Local::now(),
+        level: record.level(),
+        target: record.target().to_string(),
+        message: record.args().to_string(),
+    });
 }
 
 pub fn setup_log() -> Result<(), log::SetLoggerError> {
@@ -59,6 +76,11 @@ pub fn setup_log() -> Result<(), log::SetLoggerError> {
         .warn(Color::Yellow)
         .info(Color::Blue);
 
+    // fern doesn't create the directory the daily log files go in
+    if let Err(err) = std::fs::create_dir_all(LOG_DIR.as_path()) {
+        eprintln!("Failed to create the log directory: {}", err);
+    }
+
     fern::Dispatch::new()
         .filter(|metadata| {
             metadata.target().starts_with(concatcp!(APPLICATION_NAME, "::")) || metadata.level() <= log::Level::Warn
@@ -117,11 +139,15 @@ pub fn setup_log() -> Result<(), log::SetLoggerError> {
                         msg = format!("{}", message).as_str(),
                     ));
                 })
+                // fern adds the date straight onto the end of the path, so it
+                // needs a trailing separator for the files to go in the
+                // directory rather than beside it
                 .chain(fern::DateBased::new(
-                    LOG_DIR.to_path_buf(),
+                    LOG_DIR.join(""),
                     format!("%Y-%m-%d.{}.log", APPLICATION_NAME),
                 )),
         )
+        .chain(fern::Output::call(keep_record))
         .apply()
         .report()
         .attach_printable("Unable to setup logger as a global logger has already been set")
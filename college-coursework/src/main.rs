@@ -1,12 +1,24 @@
+mod accessibility;
 mod args;
 mod assets;
+mod audio;
+mod config;
+mod crash;
+mod data;
+mod locale;
 mod log;
 mod models;
 mod panel;
+mod preferences;
+mod remote;
 mod renderer;
+mod scripting;
+mod self_test;
 mod setup;
 mod simulation;
+mod stats;
 mod test;
+mod toasts;
 mod util;
 
 #[macro_use]
@@ -17,7 +29,7 @@ extern crate clap;
 
 use std::{error::Error, fmt, thread};
 
-use ::log::info;
+use ::log::{info, warn};
 use anyhow::Result as AnyResult;
 use crossbeam::channel;
 use error_stack::{IntoReport, Result, ResultExt};
@@ -26,7 +38,11 @@ use specs::{Join, ReadStorage};
 use thiserror::Error;
 use tokio::io;
 
-use crate::{args::Args, simulation::Identifier, simulation::load_planets_toml};
+use crate::{
+    args::Args, config::StartupConfig, preferences::Preferences, remote::RemoteServer,
+    scripting::Scripts, simulation::load_planets_toml, simulation::DeterministicMode,
+    simulation::Identifier, simulation::ParallelismSettings,
+};
 use clap::Parser;
 
 const APPLICATION_NAME: &'static str = crate_name!();
@@ -40,6 +56,12 @@ pub enum ApplicationError {
 
     #[error("Failed to build Async Runtime")]
     RuntimeBuildError,
+
+    #[error("Failed to build Thread Pool")]
+    ThreadPoolBuildError,
+
+    #[error("Failed to create the window")]
+    WindowError,
 }
 
 fn main() -> Result<(), ApplicationError> {
@@ -57,28 +79,85 @@ fn main() -> Result<(), ApplicationError> {
     // Declare if running in debug mode
     #[cfg(debug_assertions)]
     info!("Running in debug mode");
+
+    // Write the default config instead of running the program
+    if args.write_default_config {
+        match StartupConfig::write_default() {
+            Ok(path) => println!("Wrote the default config to {:?}", path),
+            Err(err) => {
+                eprintln!("Failed to write the default config: {}", err);
+                std::process::exit(1);
+            }
+        }
+        std::process::exit(0);
+    }
     
     load_planets_toml();
+
+    // Build the thread pool the simulation runs on
+    let thread_pool = ParallelismSettings::load(&args)
+        .build_thread_pool()
+        .report()
+        .attach_printable("Failed to build the simulation thread pool")
+        .change_context(ApplicationError::ThreadPoolBuildError)?;
     
-    // Setup a new async runtime throwing an error if it did not
-    let runtime = tokio::runtime::Builder::new_current_thread()
+    // Setup a new async runtime throwing an error if it did not. It has its
+    // own threads, so tasks spawned on it run alongside the main loop
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .report()
         .attach_printable("Failed to build Async Runtime")
         .change_context(ApplicationError::RuntimeBuildError)?;
 
+    // Check the machine can run the program, instead of running it
+    if args.self_test {
+        let passed = runtime.block_on(self_test::run());
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // Offer safe mode if the last run crashed
+    let safe_mode =
+        args.safe_mode || (crash::previous_run_crashed() && crash::offer_safe_mode());
+    if safe_mode {
+        info!("Running in safe mode");
+    }
+    crash::mark_running();
+
+    let config = StartupConfig::load(&args, safe_mode);
+    let preferences = Preferences::load(safe_mode);
+    locale::set_language(preferences.language);
+    let scenario = config.load_scenario();
+
+    // Create the main window, telling the user if it can't be rendered to
+    let window = runtime
+        .block_on(crate::renderer::window::Window::new(
+            safe_mode,
+            &config,
+            &preferences,
+        ))
+        .map_err(|err| {
+            crate::renderer::errors::show_error_dialog(
+                "Failed to Start",
+                &format!("The program could not start rendering.\n\n{}", err),
+            );
+            err
+        })
+        .report()
+        .attach_printable("Failed to create the main window")
+        .change_context(ApplicationError::WindowError)?;
+
     // Run the setup code within an async runtime
-    let (window, world, dispatchers) = runtime
+    let (window, mut world, dispatchers) = runtime
         .block_on(async {
-            // Create the main window
-            let window = crate::renderer::window::Window::new().await;
-
             // Setup the Entity Component System
             let (world, dispatchers) = setup::setup(
                 window.state.device.clone(),
                 window.state.queue.clone(),
                 window.state.texture_bind_group_layout.clone(),
+                thread_pool,
+                safe_mode,
+                scenario.is_none(),
             )
             .await
             .attach_printable("Failed to set up application")?;
@@ -87,6 +166,24 @@ fn main() -> Result<(), ApplicationError> {
         })
         .change_context(ApplicationError::SetupError)?;
 
+    world.insert(DeterministicMode(args.deterministic));
+    world.insert(runtime.handle().clone());
+    window.state.insert_render_settings(&mut world);
+    // The scenario and config each take priority over the preferences
+    preferences.apply(&mut world);
+    if let Some(scenario) = scenario {
+        scenario.deserialize_to_world(&mut world);
+    }
+    config.apply(&mut world);
+    if let Some(port) = config.remote_port {
+        world.insert(RemoteServer::start(runtime.handle(), port));
+    }
+    if let Some(path) = &args.script {
+        if let Err(err) = world.write_resource::<Scripts>().queue_file(path) {
+            warn!("Failed to read the script {:?}: {}", path, err);
+        }
+    }
+
     // Get all the identifiers of the registered planets
     let ids = {
         let (ids,): (ReadStorage<Identifier>,) = world.system_data();
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, sync::Arc};
+
+use image::DynamicImage;
+
+use crate::{
+    assets,
+    models::{
+        procedural::{self, SurfaceGenerator, Terrain},
+        sphere::Icosphere,
+    },
+    renderer::{
+        model::{Material, MaterialMaps, Mesh, Model},
+        texture::Texture,
+    },
+};
+
+/// What a cached material was made from, colours are compared by their bits
+/// as floats can't be hashed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MaterialKey {
+    Colour([u32; 4]),
+    /// The asset path of the diffuse texture
+    Texture(String),
+    Generated {
+        terrain: Terrain,
+        seed: u64,
+        colour: [u32; 4],
+    },
+}
+impl MaterialKey {
+    fn colour(colour: [f32; 4]) -> Self {
+        Self::Colour(colour.map(f32::to_bits))
+    }
+}
+
+/// What the surface of a body's model is drawn with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Surface<'a> {
+    /// The asset path of a texture, the body's colour is used if it fails to
+    /// load
+    Texture(&'a str),
+    /// A texture generated in the body's colour
+    Generated { terrain: Terrain, seed: u64 },
+    /// Only the body's colour
+    Colour,
+}
+impl<'a> Surface<'a> {
+    pub fn for_body(id: &str, texture: Option<&'a str>, mass: f64, emits_light: bool) -> Self {
+        //! The surface of a body: its texture if it has one, otherwise one
+        //! generated from its id to suit its mass. Bodies that emit light are
+        //! drawn in their colour, as they have no surface to see
+        match texture {
+            Some(path) => Self::Texture(path),
+            None if emits_light => Self::Colour,
+            None => match Terrain::for_mass(mass) {
+                Some(terrain) => Self::Generated {
+                    terrain,
+                    seed: procedural::seed(id),
+                },
+                None => Self::Colour,
+            },
+        }
+    }
+}
+
+/// The meshes and materials of the bodies' models, shared between every body
+/// with the same radius, detail and colour so each is only made and uploaded
+/// to the GPU once
+#[derive(Default)]
+pub struct ModelCache {
+    /// Icosphere meshes by the bits of their radius and their detail level
+    meshes: HashMap<(u32, usize), Arc<Mesh>>,
+    materials: HashMap<MaterialKey, Arc<Material>>,
+}
+impl ModelCache {
+    #[allow(clippy::too_many_arguments)]
+    pub fn body_model(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        radius: f32,
+        detail: usize,
+        colour: [f32; 4],
+        surface: Surface,
+        layout: &wgpu::BindGroupLayout,
+    ) -> Model {
+        //! The model of a body, an icosphere with the surface, falling back to
+        //! the specified colour if a texture fails to load
+
+        let mesh = self.sphere_mesh(device, radius, detail);
+        let material = match surface {
+            Surface::Texture(path) => self.texture_material(device, queue, path, layout),
+            Surface::Generated { terrain, seed } => {
+                Some(self.generated_material(device, queue, terrain, seed, colour, layout))
+            }
+            Surface::Colour => None,
+        }
+        .unwrap_or_else(|| self.colour_material(device, queue, colour, layout));
+
+        Model::new(vec![mesh], vec![material])
+    }
+
+    pub fn prune(&mut self) {
+        //! Drop the meshes and materials that no model uses any more, such as
+        //! those of deleted or recoloured bodies
+
+        self.meshes.retain(|_, mesh| Arc::strong_count(mesh) > 1);
+        self.materials
+            .retain(|_, material| Arc::strong_count(material) > 1);
+    }
+
+    fn sphere_mesh(&mut self, device: &wgpu::Device, radius: f32, detail: usize) -> Arc<Mesh> {
+        self.meshes
+            .entry((radius.to_bits(), detail))
+            .or_insert_with(|| {
+                let name = format!("Icosphere {} {}", radius, detail);
+                Arc::new(Icosphere::new(radius, detail).into_mesh(device, name))
+            })
+            .clone()
+    }
+
+    fn colour_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        colour: [f32; 4],
+        layout: &wgpu::BindGroupLayout,
+    ) -> Arc<Material> {
+        self.materials
+            .entry(MaterialKey::colour(colour))
+            .or_insert_with(|| {
+                let name = format!("{:?}", colour);
+
+                // Every texel would be the same, so a single one is enough
+                let texture = Texture::from_colour(
+                    device,
+                    queue,
+                    colour,
+                    Some(&format!("{} Texture", name)),
+                    false,
+                );
+
+                Arc::new(Material::new(
+                    device,
+                    queue,
+                    &format!("{} Material", name),
+                    texture,
+                    MaterialMaps::default(),
+                    layout,
+                ))
+            })
+            .clone()
+    }
+
+    fn generated_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        terrain: Terrain,
+        seed: u64,
+        colour: [f32; 4],
+        layout: &wgpu::BindGroupLayout,
+    ) -> Arc<Material> {
+        let key = MaterialKey::Generated {
+            terrain,
+            seed,
+            colour: colour.map(f32::to_bits),
+        };
+        self.materials
+            .entry(key)
+            .or_insert_with(|| {
+                let name = format!("{:?} {:x}", terrain, seed);
+
+                let image = SurfaceGenerator::new(terrain, seed, colour).generate();
+                let texture = Texture::from_image(
+                    device,
+                    queue,
+                    &DynamicImage::ImageRgba8(image),
+                    Some(&format!("{} Texture", name)),
+                    false,
+                );
+
+                Arc::new(Material::new(
+                    device,
+                    queue,
+                    &format!("{} Material", name),
+                    texture,
+                    MaterialMaps::default(),
+                    layout,
+                ))
+            })
+            .clone()
+    }
+
+    fn texture_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &str,
+        layout: &wgpu::BindGroupLayout,
+    ) -> Option<Arc<Material>> {
+        //! The material using the texture at the asset path, None if it fails
+        //! to load
+
+        let key = MaterialKey::Texture(path.to_string());
+        if let Some(material) = self.materials.get(&key) {
+            return Some(material.clone());
+        }
+
+        let texture = match assets::load_texture_blocking(path, device, queue, false) {
+            Ok(texture) => texture,
+            Err(err) => {
+                log::warn!("Failed to load texture {:?}: {}", path, err);
+                return None;
+            }
+        };
+
+        // Load any maps stored alongside the texture, e.g. `earth_normal.jpg`
+        // for `earth.jpg`
+        let load_map = |map: &str| {
+            let map_path = match path.rsplit_once('.') {
+                Some((stem, extension)) => format!("{}_{}.{}", stem, map, extension),
+                None => format!("{}_{}", path, map),
+            };
+
+            assets::load_texture_blocking(&map_path, device, queue, true)
+                .map_err(|err| log::debug!("No {} map for {:?}: {}", map, path, err))
+                .ok()
+        };
+
+        let maps = MaterialMaps {
+            normal: load_map("normal"),
+            specular: load_map("specular"),
+            roughness: load_map("roughness"),
+            metallic: load_map("metallic"),
+        };
+
+        let material = Arc::new(Material::new(
+            device,
+            queue,
+            &format!("{:?} Material", path),
+            texture,
+            maps,
+            layout,
+        ));
+        self.materials.insert(key, material.clone());
+
+        Some(material)
+    }
+}
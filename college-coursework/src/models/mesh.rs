@@ -0,0 +1,191 @@
+use cgmath::{InnerSpace, Vector2, Vector3};
+
+use crate::renderer::model::ModelVertex;
+
+/// Triangles whose texture coordinates cover less area than this are
+/// treated as having none, as their tangents can't be found
+const MIN_UV_AREA: f32 = 1e-12;
+
+pub fn calculate_tangents(vertices: &mut [ModelVertex], indices: &[u32]) {
+    //! Calculates the tangent and bitangent of each vertex from the texture
+    //! coordinates of the triangles around it, for mapping normal maps onto
+    //! the surface. The tangent follows increasing u and the bitangent
+    //! decreasing v, both perpendicular to the vertex's normal
+
+    let mut tangents = vec![Vector3::new(0.0, 0.0, 0.0); vertices.len()];
+    let mut bitangents = vec![Vector3::new(0.0, 0.0, 0.0); vertices.len()];
+
+    for c in indices.chunks_exact(3) {
+        let [v0, v1, v2] = [0, 1, 2].map(|i| vertices[c[i] as usize]);
+
+        // Get the difference between the 0th vertex and the 1st and 2nd
+        let delta_pos1 = Vector3::from(v1.position) - Vector3::from(v0.position);
+        let delta_pos2 = Vector3::from(v2.position) - Vector3::from(v0.position);
+
+        let delta_uv1 = Vector2::from(v1.tex_coords) - Vector2::from(v0.tex_coords);
+        let delta_uv2 = Vector2::from(v2.tex_coords) - Vector2::from(v0.tex_coords);
+
+        // A triangle with every vertex at the same texture coordinate, or
+        // all of them in a line, would divide by zero, so it adds nothing
+        let determinant = delta_uv1.x * delta_uv2.y - delta_uv1.y * delta_uv2.x;
+        if determinant.abs() < MIN_UV_AREA {
+            continue;
+        }
+
+        // Calculate the tangent and bitangent of the face
+        let r = 1.0 / determinant;
+        let tangent = (delta_pos1 * delta_uv2.y - delta_pos2 * delta_uv1.y) * r;
+        let bitangent = (delta_pos2 * delta_uv1.x - delta_pos1 * delta_uv2.x) * -r;
+
+        // Add the face's tangent and bitangent to each of its vertices
+        for &i in c {
+            tangents[i as usize] += tangent;
+            bitangents[i as usize] += bitangent;
+        }
+    }
+
+    for (vertex, (tangent, bitangent)) in vertices
+        .iter_mut()
+        .zip(tangents.into_iter().zip(bitangents))
+    {
+        let normal = Vector3::from(vertex.normal);
+
+        // Remove any part of the summed tangent along the normal, so the
+        // average is in the surface, falling back to any direction in it for
+        // vertices without a usable triangle
+        let tangent = tangent - normal * normal.dot(tangent);
+        let tangent = if tangent.magnitude2() > 0.0 {
+            tangent.normalize()
+        } else {
+            any_perpendicular(normal)
+        };
+
+        // The bitangent is perpendicular to both, on the side the texture's
+        // v coordinate decreases towards
+        let perpendicular = normal.cross(tangent);
+        let bitangent = if perpendicular.dot(bitangent) < 0.0 {
+            -perpendicular
+        } else {
+            perpendicular
+        };
+
+        vertex.tangent = tangent.into();
+        vertex.bitangent = bitangent.into();
+    }
+}
+
+fn any_perpendicular(normal: Vector3<f32>) -> Vector3<f32> {
+    //! A unit vector perpendicular to the normal, crossing it with whichever
+    //! axis it is furthest from
+
+    let axis = if normal.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+    let perpendicular = normal.cross(axis);
+
+    if perpendicular.magnitude2() > 0.0 {
+        perpendicular.normalize()
+    } else {
+        Vector3::unit_x()
+    }
+}
+
+pub fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    //! Normals for a mesh made without them, averaged from the faces around
+    //! each vertex weighted by their areas
+
+    let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vector3::from(positions[triangle[i] as usize]));
+        // The cross product's length is twice the triangle's area
+        let face = (b - a).cross(c - a);
+        for &i in triangle {
+            normals[i as usize] += face;
+        }
+    }
+
+    normals
+        .into_iter()
+        .map(|normal| {
+            if normal.magnitude2() > 0.0 {
+                normal.normalize().into()
+            } else {
+                [0.0, 0.0, 1.0]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use super::calculate_tangents;
+    use crate::renderer::model::ModelVertex;
+
+    fn vertex(position: [f32; 3], tex_coords: [f32; 2]) -> ModelVertex {
+        ModelVertex {
+            position,
+            tex_coords,
+            normal: [0.0, 0.0, 1.0],
+            tangent: [0.0; 3],
+            bitangent: [0.0; 3],
+        }
+    }
+
+    fn assert_close(expected: [f32; 3], actual: [f32; 3]) {
+        let difference = Vector3::from(expected) - Vector3::from(actual);
+        assert!(
+            difference.magnitude() < 1e-5,
+            "{:?} != {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_tangents_of_a_square() {
+        // A unit square facing up the z axis, with the top of the texture
+        // (v = 0) along its top edge
+        let mut vertices = vec![
+            vertex([0.0, 0.0, 0.0], [0.0, 1.0]),
+            vertex([1.0, 0.0, 0.0], [1.0, 1.0]),
+            vertex([1.0, 1.0, 0.0], [1.0, 0.0]),
+            vertex([0.0, 1.0, 0.0], [0.0, 0.0]),
+        ];
+        calculate_tangents(&mut vertices, &[0, 1, 2, 0, 2, 3]);
+
+        for vertex in vertices.iter() {
+            assert_close([1.0, 0.0, 0.0], vertex.tangent);
+            assert_close([0.0, 1.0, 0.0], vertex.bitangent);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_tex_coords_give_a_perpendicular_basis() {
+        // Every vertex has the same texture coordinates, and the last vertex
+        // isn't in any triangle
+        let mut vertices = vec![
+            vertex([0.0, 0.0, 0.0], [0.5, 0.5]),
+            vertex([1.0, 0.0, 0.0], [0.5, 0.5]),
+            vertex([0.0, 1.0, 0.0], [0.5, 0.5]),
+            vertex([5.0, 5.0, 0.0], [0.0, 0.0]),
+        ];
+        calculate_tangents(&mut vertices, &[0, 1, 2]);
+
+        for vertex in vertices.iter() {
+            let normal = Vector3::from(vertex.normal);
+            let tangent = Vector3::from(vertex.tangent);
+            let bitangent = Vector3::from(vertex.bitangent);
+
+            assert!(tangent.x.is_finite() && bitangent.x.is_finite());
+            assert!((tangent.magnitude() - 1.0).abs() < 1e-5);
+            assert!((bitangent.magnitude() - 1.0).abs() < 1e-5);
+            assert!(tangent.dot(normal).abs() < 1e-5);
+            assert!(bitangent.dot(normal).abs() < 1e-5);
+            assert!(tangent.dot(bitangent).abs() < 1e-5);
+        }
+    }
+}
@@ -1 +1,5 @@
+pub mod cache;
+pub mod mesh;
+pub mod procedural;
+pub mod ring;
 pub mod sphere;
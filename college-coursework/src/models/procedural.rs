@@ -0,0 +1,330 @@
+use std::f32::consts::{PI, TAU};
+
+use cgmath::{InnerSpace, Vector3};
+use image::RgbaImage;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+/// The size of generated textures, 2:1 to be mapped onto a sphere
+const WIDTH: u32 = 512;
+const HEIGHT: u32 = 256;
+
+/// Bodies heavier than this (in kg) can fuse hydrogen, so are stars without a
+/// surface to generate
+const STAR_MASS: f64 = 1.5e29;
+/// Bodies heavier than this (in kg) are taken to be gas giants, around eight
+/// times the mass of the Earth
+const GAS_GIANT_MASS: f64 = 5e25;
+/// Bodies lighter than this (in kg) are taken to have too little atmosphere
+/// to protect them from impacts, a little heavier than Mercury
+const CRATERED_MASS: f64 = 5e23;
+
+/// The elevation below which rocky bodies are covered by oceans
+const SEA_LEVEL: f32 = 0.5;
+const OCEAN_DEEP: [f32; 3] = [0.02, 0.05, 0.18];
+const OCEAN_SHALLOW: [f32; 3] = [0.05, 0.2, 0.35];
+const ICE: [f32; 3] = [0.9, 0.92, 0.95];
+
+/// The number of craters on a cratered body
+const CRATER_COUNT: usize = 80;
+
+/// The kind of surface generated for a body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    /// Continents and oceans, with ice caps at the poles
+    Continents,
+    /// Bands of cloud along lines of latitude
+    Bands,
+    /// A dusty surface covered in impact craters
+    Craters,
+}
+impl Terrain {
+    pub fn for_mass(mass: f64) -> Option<Self> {
+        //! The kind of surface a body of a mass (in kg) is likely to have,
+        //! None for stars
+        if mass >= STAR_MASS {
+            None
+        } else if mass >= GAS_GIANT_MASS {
+            Some(Self::Bands)
+        } else if mass >= CRATERED_MASS {
+            Some(Self::Continents)
+        } else {
+            Some(Self::Craters)
+        }
+    }
+}
+
+pub fn seed(id: &str) -> u64 {
+    //! A seed from a body's id, the same on every run and platform (unlike
+    //! the hashers in std) so a body always gets the same surface
+    id.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn lattice(seed: u64, x: i32, y: i32, z: i32) -> f32 {
+    //! A random value from 0 to 1 for a point of the noise lattice
+    let mut hash = seed
+        ^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+        ^ (y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f)
+        ^ (z as u64).wrapping_mul(0x1656_67b1_9e37_79f9);
+
+    // Mix the bits, so neighbouring points are unrelated
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^= hash >> 31;
+
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn value_noise(seed: u64, point: Vector3<f32>) -> f32 {
+    //! Smoothly varying noise from 0 to 1, interpolated between the random
+    //! values of the surrounding lattice points
+
+    let floor = point.map(f32::floor);
+    let [x, y, z] = [floor.x as i32, floor.y as i32, floor.z as i32];
+    let fade = (point - floor).map(|t| t * t * (3.0 - 2.0 * t));
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let corner = |dx, dy, dz| lattice(seed, x + dx, y + dy, z + dz);
+
+    lerp(
+        lerp(
+            lerp(corner(0, 0, 0), corner(1, 0, 0), fade.x),
+            lerp(corner(0, 1, 0), corner(1, 1, 0), fade.x),
+            fade.y,
+        ),
+        lerp(
+            lerp(corner(0, 0, 1), corner(1, 0, 1), fade.x),
+            lerp(corner(0, 1, 1), corner(1, 1, 1), fade.x),
+            fade.y,
+        ),
+        fade.z,
+    )
+}
+
+fn fractal_noise(seed: u64, point: Vector3<f32>, octaves: u32) -> f32 {
+    //! Layers of noise at doubling frequencies and halving amplitudes, giving
+    //! large features with smaller detail on top, from 0 to 1
+
+    let (mut total, mut amplitude, mut frequency, mut range) = (0.0, 1.0, 1.0, 0.0);
+    for octave in 0..octaves {
+        total += value_noise(seed.wrapping_add(octave as u64), point * frequency) * amplitude;
+        range += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    total / range
+}
+
+fn mix(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    let t = t.clamp(0.0, 1.0);
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn scale(colour: [f32; 3], factor: f32) -> [f32; 3] {
+    colour.map(|c| c * factor)
+}
+
+/// An impact crater, centred on a point of the surface
+struct Crater {
+    centre: Vector3<f32>,
+    /// (in radians)
+    radius: f32,
+}
+impl Crater {
+    /// How far the raised rim extends past the edge, as a fraction of the
+    /// radius
+    const RIM: f32 = 0.3;
+
+    fn new(centre: Vector3<f32>, radius: f32) -> Self {
+        Self { centre, radius }
+    }
+
+    fn shade(&self, direction: Vector3<f32>) -> f32 {
+        //! How much the crater darkens or lightens the surface in a direction
+
+        // Most of the surface is far from any one crater, which is quicker to
+        // check without the angle
+        let cos = direction.dot(self.centre);
+        if cos < (self.radius * (1.0 + Self::RIM)).cos() {
+            return 1.0;
+        }
+
+        let distance = cos.clamp(-1.0, 1.0).acos() / self.radius;
+        if distance < 1.0 {
+            // The floor of the crater is in shadow, darkest in the middle
+            0.75 + 0.25 * distance * distance
+        } else {
+            // The rim is raised, catching more light
+            1.0 + 0.2 * (1.0 - (distance - 1.0) / Self::RIM)
+        }
+    }
+}
+
+/// Generates the surface texture of a body, the same every time for the same
+/// terrain, seed and colour
+pub struct SurfaceGenerator {
+    terrain: Terrain,
+    seed: u64,
+    colour: [f32; 3],
+    craters: Vec<Crater>,
+}
+impl SurfaceGenerator {
+    pub fn new(terrain: Terrain, seed: u64, colour: [f32; 4]) -> Self {
+        let craters = match terrain {
+            Terrain::Craters => {
+                // Spread evenly over the sphere, with many more small craters
+                // than large ones
+                let mut rng = StdRng::seed_from_u64(seed);
+                (0..CRATER_COUNT)
+                    .map(|_| {
+                        let z: f32 = rng.gen_range(-1.0..1.0);
+                        let longitude = rng.gen_range(0.0..TAU);
+                        let ring = (1.0 - z * z).sqrt();
+                        Crater::new(
+                            Vector3::new(ring * longitude.cos(), ring * longitude.sin(), z),
+                            0.02 + 0.2 * rng.gen::<f32>().powi(4),
+                        )
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Self {
+            terrain,
+            seed,
+            colour: [colour[0], colour[1], colour[2]],
+            craters,
+        }
+    }
+
+    fn continents(&self, direction: Vector3<f32>) -> [f32; 3] {
+        let elevation = fractal_noise(self.seed, direction * 1.5, 6);
+        let detail = fractal_noise(self.seed.wrapping_add(100), direction * 8.0, 3);
+
+        // Ice caps, with a ragged edge
+        if direction.z.abs() + 0.15 * (detail - 0.5) > 0.9 {
+            return ICE;
+        }
+
+        if elevation < SEA_LEVEL {
+            // Shallower seas are lighter, near the coasts
+            return mix(OCEAN_DEEP, OCEAN_SHALLOW, (elevation / SEA_LEVEL).powi(6));
+        }
+
+        // The land is the body's colour, lighter on higher ground
+        let height = (elevation - SEA_LEVEL) / (1.0 - SEA_LEVEL);
+        let land = scale(self.colour, 0.6 + 0.5 * detail);
+        mix(land, ICE, (height - 0.3) * 1.5)
+    }
+
+    fn bands(&self, direction: Vector3<f32>) -> [f32; 3] {
+        // The bands are disturbed by turbulence, more so near the equator
+        let turbulence = fractal_noise(self.seed, direction * 3.0, 4) - 0.5;
+        let latitude = direction.z + 0.12 * turbulence * (1.0 - direction.z.abs());
+
+        // Each body has its own number of bands
+        let frequency = 6.0 + (self.seed % 7) as f32;
+        let band = (latitude * frequency * PI).sin();
+        let streaks = fractal_noise(
+            self.seed.wrapping_add(200),
+            Vector3::new(direction.x * 2.0, direction.y * 2.0, direction.z * 24.0),
+            3,
+        );
+
+        // Alternating dark belts and light zones of the body's colour
+        let light = mix(self.colour, [1.0, 1.0, 1.0], 0.35);
+        let dark = scale(self.colour, 0.65);
+        scale(mix(dark, light, 0.5 + 0.5 * band), 0.9 + 0.2 * streaks)
+    }
+
+    fn craters(&self, direction: Vector3<f32>) -> [f32; 3] {
+        let dust = fractal_noise(self.seed, direction * 4.0, 5);
+        let shade = self
+            .craters
+            .iter()
+            .map(|crater| crater.shade(direction))
+            .product::<f32>();
+
+        scale(self.colour, (0.7 + 0.5 * (dust - 0.5)) * shade)
+    }
+
+    fn texel(&self, direction: Vector3<f32>) -> [f32; 3] {
+        match self.terrain {
+            Terrain::Continents => self.continents(direction),
+            Terrain::Bands => self.bands(direction),
+            Terrain::Craters => self.craters(direction),
+        }
+    }
+
+    pub fn generate(&self) -> RgbaImage {
+        //! An equirectangular texture of the surface, mapped the same way as
+        //! the icosphere with the poles on the z axis. The noise is sampled
+        //! on the sphere so there is no seam and the poles aren't pinched
+
+        let mut pixels = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        pixels
+            .par_chunks_mut(WIDTH as usize * 4)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let latitude = (0.5 - (y as f32 + 0.5) / HEIGHT as f32) * PI;
+
+                for (x, pixel) in row.chunks_mut(4).enumerate() {
+                    let longitude = ((x as f32 + 0.5) / WIDTH as f32 - 0.5) * TAU;
+                    let direction = Vector3::new(
+                        latitude.cos() * longitude.cos(),
+                        latitude.cos() * longitude.sin(),
+                        latitude.sin(),
+                    )
+                    .normalize();
+
+                    let [r, g, b] = self.texel(direction);
+                    pixel.copy_from_slice(
+                        &[r, g, b, 1.0].map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8),
+                    );
+                }
+            });
+
+        RgbaImage::from_raw(WIDTH, HEIGHT, pixels).expect("Texture is the wrong size")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{seed, SurfaceGenerator, Terrain};
+
+    #[test]
+    fn test_terrain_by_mass() {
+        assert_eq!(None, Terrain::for_mass(1.989e30));
+        assert_eq!(Some(Terrain::Bands), Terrain::for_mass(1.898e27));
+        assert_eq!(Some(Terrain::Continents), Terrain::for_mass(5.972e24));
+        assert_eq!(Some(Terrain::Craters), Terrain::for_mass(7.346e22));
+    }
+
+    #[test]
+    fn test_surfaces_seeded_by_id() {
+        assert_eq!(seed("planet-1"), seed("planet-1"));
+        assert_ne!(seed("planet-1"), seed("planet-2"));
+
+        // The same body always gets the same surface, but other bodies don't
+        let directions = (0..64)
+            .map(|i| {
+                let angle = i as f32 * 0.1;
+                Vector3::new(angle.cos(), angle.sin(), (angle * 0.3).sin())
+            })
+            .collect::<Vec<_>>();
+        let texels = |id: &str| {
+            let generator = SurfaceGenerator::new(Terrain::Continents, seed(id), [0.4; 4]);
+            directions
+                .iter()
+                .map(|&direction| generator.texel(direction))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texels("planet-1"), texels("planet-1"));
+        assert_ne!(texels("planet-1"), texels("planet-2"));
+    }
+}
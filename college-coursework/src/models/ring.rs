@@ -0,0 +1,102 @@
+use cgmath::Vector3;
+
+use crate::renderer::model::{Mesh, ModelVertex};
+
+/// A flat ring in the xy plane, between two radii around the origin
+pub struct Annulus {
+    inner_radius: f32,
+    outer_radius: f32,
+    segments: usize,
+}
+impl Annulus {
+    pub fn new(inner_radius: f32, outer_radius: f32, segments: usize) -> Self {
+        //! Generates a new annulus, made of `segments` quadrilaterals around
+        //! its circumference
+
+        Self {
+            inner_radius,
+            outer_radius,
+            segments: segments.max(3),
+        }
+    }
+
+    fn vertices(&self) -> (Vec<ModelVertex>, Vec<u32>) {
+        //! The vertices and indices of the annulus, the texture is mapped
+        //! across the ring from the inner edge (v = 0) to the outer edge
+        //! (v = 1) and repeats once around it
+
+        let mut vertices = Vec::with_capacity((self.segments + 1) * 2);
+        let mut indices = Vec::with_capacity(self.segments * 6);
+
+        // The first column of vertices is repeated at the end, so the texture
+        // doesn't stretch back around the whole ring at the seam
+        for i in 0..=self.segments {
+            let u = i as f32 / self.segments as f32;
+            let (sin, cos) = (u * std::f32::consts::TAU).sin_cos();
+            let outwards = Vector3::new(cos, sin, 0.0);
+
+            for (radius, v) in [(self.inner_radius, 0.0), (self.outer_radius, 1.0)] {
+                vertices.push(ModelVertex {
+                    position: (outwards * radius).into(),
+                    tex_coords: [u, v],
+                    normal: [0.0, 0.0, 1.0],
+                    tangent: [-sin, cos, 0.0],
+                    bitangent: outwards.into(),
+                });
+            }
+        }
+
+        // Two triangles between each pair of columns, anticlockwise when
+        // seen from above
+        for i in 0..self.segments as u32 {
+            let (inner, outer) = (i * 2, i * 2 + 1);
+            let (next_inner, next_outer) = (inner + 2, outer + 2);
+
+            #[rustfmt::skip]
+            indices.extend_from_slice(&[
+                inner, outer, next_outer,
+                inner, next_outer, next_inner,
+            ]);
+        }
+
+        (vertices, indices)
+    }
+
+    pub fn into_mesh(self, device: &wgpu::Device, name: String) -> Mesh {
+        //! Converts the annulus into a mesh, facing up the z axis
+
+        let (vertices, indices) = self.vertices();
+        Mesh::new(device, name, vertices, indices, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use super::Annulus;
+
+    #[test]
+    fn test_annulus_between_radii() {
+        let (vertices, indices) = Annulus::new(1.5, 2.5, 32).vertices();
+        assert_eq!(33 * 2, vertices.len());
+        assert_eq!(32 * 6, indices.len());
+
+        for vertex in vertices.iter() {
+            let radius = Vector3::from(vertex.position).magnitude();
+            let expected = if vertex.tex_coords[1] == 0.0 {
+                1.5
+            } else {
+                2.5
+            };
+            assert!((radius - expected).abs() < 1e-5);
+        }
+
+        // Every triangle faces up
+        for triangle in indices.chunks(3) {
+            let [a, b, c] =
+                [0, 1, 2].map(|i| Vector3::from(vertices[triangle[i] as usize].position));
+            assert!((b - a).cross(c - a).z > 0.0);
+        }
+    }
+}
@@ -2,13 +2,11 @@ use std::collections::HashMap;
 
 use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3};
 
-use image::{DynamicImage, Rgb, Rgb32FImage, RgbImage, Rgba, Rgba32FImage, RgbaImage};
 use itertools::Itertools;
 
-use crate::renderer::{
-    model::{Material, Mesh, Model, ModelVertex},
-    texture::Texture,
-};
+use crate::renderer::model::{Mesh, ModelVertex};
+
+use super::mesh::calculate_tangents;
 
 pub struct Icosphere {
     vertices: Vec<Point3<f32>>,
@@ -151,124 +149,81 @@ impl Icosphere {
         }
     }
 
-    pub fn into_model(
-        self,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        name: String,
-        colour: [f32; 4],
-        layout: &wgpu::BindGroupLayout,
-    ) -> Model {
-        //! Converts an icosphere into a model with the specified colour
+    pub fn into_mesh(self, device: &wgpu::Device, name: String) -> Mesh {
+        //! Converts an icosphere into a mesh mapped onto an equirectangular
+        //! texture
+
+        // Map each point onto an equirectangular texture, the poles are on the
+        // z axis as the bodies orbit in the xy plane
+        let tex_coords = |v: Vector3<f32>| {
+            [
+                0.5 + f32::atan2(v.y, v.x) / std::f32::consts::TAU,
+                0.5 - v.z.asin() / std::f32::consts::PI,
+            ]
+        };
 
         // Convert the vertices to ModelVertex
-        let indices: Vec<_> = self.indices.into_iter().map(|i| i as u32).collect();
+        let mut indices: Vec<_> = self.indices.into_iter().map(|i| i as u32).collect();
         let mut vertices: Vec<ModelVertex> = self
             .vertices
             .into_iter()
             .map(|v| ModelVertex {
                 position: v.into(),
-                tex_coords: {
-                    let v = -v.to_vec().normalize();
-                    [
-                        0.5 + f32::atan2(v.x, v.y) / std::f32::consts::TAU,
-                        0.5 + v.y.asin() / std::f32::consts::PI,
-                    ]
-                },
+                tex_coords: tex_coords(v.to_vec().normalize()),
                 normal: v.to_vec().normalize().into(),
                 tangent: [0.0; 3],
                 bitangent: [0.0; 3],
             })
             .collect();
 
-        let mut triangles_included = vec![0; vertices.len()];
-
-        // Calculate the tangent and bitangent for every vertex
-        for c in indices.chunks(3) {
-            let v0 = vertices[c[0] as usize];
-            let v1 = vertices[c[1] as usize];
-            let v2 = vertices[c[2] as usize];
-
-            // Convert each point into a position vector
-            let pos0: cgmath::Vector3<_> = v0.position.into();
-            let pos1: cgmath::Vector3<_> = v1.position.into();
-            let pos2: cgmath::Vector3<_> = v2.position.into();
-
-            let uv0: cgmath::Vector2<_> = v0.tex_coords.into();
-            let uv1: cgmath::Vector2<_> = v1.tex_coords.into();
-            let uv2: cgmath::Vector2<_> = v2.tex_coords.into();
-
-            // Find the difference between the 0th point and the 1st/2nd
-            let delta_pos1 = pos1 - pos0;
-            let delta_pos2 = pos2 - pos0;
-
-            let delta_uv1 = uv1 - uv0;
-            let delta_uv2 = uv2 - uv0;
-
-            // Calculate the tangent and bitanget
-            let r = 1.0 / (delta_uv1.x * delta_uv2.y - delta_uv1.y * delta_uv2.x);
-            let tangent = (delta_pos1 * delta_uv2.y - delta_pos2 * delta_uv1.y) * r;
-            let bitangent = (delta_pos2 * delta_uv1.x - delta_pos1 * delta_uv2.x) * -r;
-
-            // Update the tangent and bitangent for each vertex
-            vertices[c[0] as usize].tangent =
-                (tangent + cgmath::Vector3::from(vertices[c[0] as usize].tangent)).into();
-            vertices[c[1] as usize].tangent =
-                (tangent + cgmath::Vector3::from(vertices[c[1] as usize].tangent)).into();
-            vertices[c[2] as usize].tangent =
-                (tangent + cgmath::Vector3::from(vertices[c[2] as usize].tangent)).into();
-            vertices[c[0] as usize].bitangent =
-                (bitangent + cgmath::Vector3::from(vertices[c[0] as usize].bitangent)).into();
-            vertices[c[1] as usize].bitangent =
-                (bitangent + cgmath::Vector3::from(vertices[c[1] as usize].bitangent)).into();
-            vertices[c[2] as usize].bitangent =
-                (bitangent + cgmath::Vector3::from(vertices[c[2] as usize].bitangent)).into();
-
-            triangles_included[c[0] as usize] += 1;
-            triangles_included[c[1] as usize] += 1;
-            triangles_included[c[2] as usize] += 1;
+        // Triangles that cross the seam would stretch back across the whole
+        // texture, so they are given copies of their vertices past the seam
+        let mut seam_copies = HashMap::new();
+        for triangle in indices.chunks_mut(3) {
+            let (min, max) = triangle
+                .iter()
+                .map(|&i| vertices[i as usize].tex_coords[0])
+                .fold((f32::MAX, f32::MIN), |(min, max), u| {
+                    (min.min(u), max.max(u))
+                });
+
+            if max - min > 0.5 {
+                for i in triangle.iter_mut() {
+                    let index = *i;
+                    if vertices[index as usize].tex_coords[0] < 0.5 {
+                        *i = *seam_copies.entry(index).or_insert_with(|| {
+                            let mut copy = vertices[index as usize];
+                            copy.tex_coords[0] += 1.0;
+                            vertices.push(copy);
+                            (vertices.len() - 1) as u32
+                        });
+                    }
+                }
+            }
         }
 
-        // Average the tangent and bitangent for each vertex
-        for (i, n) in triangles_included.into_iter().enumerate() {
-            let denom = 1.0 / n as f32;
-            let mut v = &mut vertices[i];
-            v.tangent = (cgmath::Vector3::from(v.tangent) * denom).into();
-            v.bitangent = (cgmath::Vector3::from(v.bitangent) * denom).into();
+        // The poles are a single point of the mesh but a whole row of the
+        // texture, so each triangle touching a pole gets its own copy of it
+        // lined up with the rest of the triangle
+        for triangle in indices.chunks_mut(3) {
+            for corner in 0..3 {
+                let index = triangle[corner] as usize;
+                if 1.0 - vertices[index].normal[2].abs() < 1e-6 {
+                    let u = (1..3)
+                        .map(|o| vertices[triangle[(corner + o) % 3] as usize].tex_coords[0])
+                        .sum::<f32>()
+                        / 2.0;
+
+                    let mut copy = vertices[index];
+                    copy.tex_coords[0] = u;
+                    vertices.push(copy);
+                    triangle[corner] = (vertices.len() - 1) as u32;
+                }
+            }
         }
 
-        // Create a texture filled with the specified colour
-        let mut texture = Rgba32FImage::new(100, 100);
-        texture.pixels_mut().for_each(|p| *p = Rgba(colour));
-
-        let texture = Texture::from_image(
-            device,
-            queue,
-            &DynamicImage::ImageRgba32F(texture),
-            Some(&format!("{:?} Texture", name)),
-        );
-
-        // Create a blank normal texture
-        let mut normal = Rgb32FImage::new(10, 10);
-        normal.pixels_mut().for_each(|p| *p = Rgb([1.0, 1.0, 1.0]));
-
-        let normal = Texture::from_image(
-            device,
-            queue,
-            &DynamicImage::ImageRgb32F(normal),
-            Some(&format!("{:?} Normal Texture", name)),
-        );
-
-        // Create the meshes and materials from the vertices, indices and textures
-        let meshes = vec![Mesh::new(device, name.clone(), vertices, indices, 0)];
-        let materials = vec![Material::new(
-            device,
-            &format!("{:?} Material", name),
-            texture,
-            normal,
-            layout,
-        )];
-
-        Model { meshes, materials }
+        calculate_tangents(&mut vertices, &indices);
+
+        Mesh::new(device, name, vertices, indices, 0)
     }
 }
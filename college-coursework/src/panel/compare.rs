@@ -0,0 +1,140 @@
+use cgmath::InnerSpace;
+use specs::{Join, ReadStorage, World};
+
+use crate::{
+    locale::{self, tr},
+    simulation::{Identifier, Mass, Position, Velocity},
+};
+
+use super::{dynamic_exponent_formatter, periods::summarise, UnitSystem};
+
+/// The state of a chosen body this frame
+struct BodyState {
+    mass: f64,
+    position: cgmath::Vector3<f64>,
+    velocity: cgmath::Vector3<f64>,
+    /// Sidereal period around the body it orbits (in seconds)
+    period: Option<f64>,
+}
+
+fn body_state(world: &World, id: &str) -> Option<BodyState> {
+    let (ids, positions, velocities, masses): (
+        ReadStorage<Identifier>,
+        ReadStorage<Position>,
+        ReadStorage<Velocity>,
+        ReadStorage<Mass>,
+    ) = world.system_data();
+
+    let (_, position, velocity, mass) = (&ids, &positions, &velocities, &masses)
+        .join()
+        .find(|(identifier, ..)| identifier.get_id() == id)?;
+
+    Some(BodyState {
+        mass: mass.0,
+        position: position.0,
+        velocity: velocity.0,
+        period: summarise(world, id).and_then(|summary| summary.predicted),
+    })
+}
+
+/// Compares two chosen bodies, updated every frame as they move
+#[derive(Default)]
+pub struct BodyComparison {
+    /// Ids of the chosen bodies
+    first: Option<String>,
+    second: Option<String>,
+}
+impl BodyComparison {
+    pub fn show(&mut self, ctx: &egui::Context, world: &World, open: &mut bool) {
+        //! Show the window to choose the bodies and how they compare
+
+        let candidates = (&world.read_storage::<Identifier>())
+            .join()
+            .cloned()
+            .collect::<Vec<_>>();
+        let name_of = |chosen: &Option<String>| {
+            candidates
+                .iter()
+                .find(|id| Some(id.get_id()) == chosen.as_deref())
+                .map_or(tr!("choose-a-body"), locale::body_name)
+        };
+
+        let first = self.first.as_deref().and_then(|id| body_state(world, id));
+        let second = self.second.as_deref().and_then(|id| body_state(world, id));
+        let units = *world.read_resource::<UnitSystem>();
+
+        egui::Window::new(tr!("compare-title"))
+            .id(egui::Id::new("compare_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                for (id_source, label, chosen) in [
+                    ("first_body", tr!("first-body"), &mut self.first),
+                    ("second_body", tr!("second-body"), &mut self.second),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        egui::ComboBox::from_id_source(("compare", id_source))
+                            .selected_text(name_of(chosen))
+                            .show_ui(ui, |ui| {
+                                for id in candidates.iter() {
+                                    ui.selectable_value(
+                                        chosen,
+                                        Some(id.get_id().to_string()),
+                                        locale::body_name(id),
+                                    );
+                                }
+                            });
+                    });
+                }
+
+                let (first, second) = match (first, second) {
+                    (Some(first), Some(second)) => (first, second),
+                    _ => return,
+                };
+
+                ui.separator();
+
+                let distance = (first.position - second.position).magnitude();
+                let relative_speed = (first.velocity - second.velocity).magnitude();
+
+                egui::Grid::new("body_comparison")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(tr!("compare-mass-ratio"))
+                            .on_hover_text(tr!("compare-mass-ratio-hover"));
+                        ui.label(format!("{:.4e}", first.mass / second.mass));
+                        ui.end_row();
+
+                        ui.label(tr!("compare-distance"));
+                        ui.label(format!(
+                            "{}{}",
+                            dynamic_exponent_formatter()(units.distance().from_si(distance), 0..=4),
+                            units.distance().suffix()
+                        ));
+                        ui.end_row();
+
+                        ui.label(tr!("compare-relative-velocity"));
+                        ui.label(format!(
+                            "{}{}",
+                            dynamic_exponent_formatter()(
+                                units.speed().from_si(relative_speed),
+                                0..=3
+                            ),
+                            units.speed().suffix()
+                        ));
+                        ui.end_row();
+
+                        ui.label(tr!("compare-period-ratio"))
+                            .on_hover_text(tr!("compare-period-ratio-hover"));
+                        ui.label(match (first.period, second.period) {
+                            (Some(a), Some(b)) => format!("{:.4}", a / b),
+                            _ => "-".to_string(),
+                        });
+                        ui.end_row();
+                    });
+            });
+    }
+}
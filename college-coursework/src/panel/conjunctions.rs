@@ -0,0 +1,289 @@
+use std::sync::mpsc::TryRecvError;
+
+use specs::{Entities, Entity, Join, Read, ReadStorage, World, Write, WriteStorage};
+
+use crate::{
+    accessibility::Announcements,
+    locale::{self, tr},
+    simulation::{
+        Alignment, AlignmentKind, AlignmentSearch, BodyType, GravitationalConstant, Identifier,
+        InteractionHandler, Mass, Position, Satellite, SearchBody, SimulationTime, Trail, Velocity,
+    },
+};
+
+use super::UnitSystem;
+
+enum SearchState {
+    Idle,
+    /// The search, along with the entity of each body being propagated and
+    /// the simulated time it started from
+    Searching(AlignmentSearch, Vec<Entity>, f64),
+    Found(Alignment, Vec<Entity>, f64),
+    NotFound,
+}
+
+/// Finds when two bodies next line up with the Sun, and can jump the
+/// simulation forward to then
+pub struct ConjunctionFinder {
+    /// Ids of the chosen bodies
+    first: Option<String>,
+    second: Option<String>,
+    state: SearchState,
+}
+impl Default for ConjunctionFinder {
+    fn default() -> Self {
+        Self {
+            first: None,
+            second: None,
+            state: SearchState::Idle,
+        }
+    }
+}
+impl ConjunctionFinder {
+    pub fn show(&mut self, ctx: &egui::Context, world: &mut World, open: &mut bool) {
+        //! Show the window to choose the bodies and run the search
+
+        self.poll_search();
+
+        // Bodies that orbit the Sun directly, satellites are moved with their
+        // parent instead of being propagated
+        let candidates = {
+            let (entities, ids, handlers, satellites): (
+                Entities,
+                ReadStorage<Identifier>,
+                ReadStorage<InteractionHandler>,
+                ReadStorage<Satellite>,
+            ) = world.system_data();
+
+            (&entities, &ids, &handlers, !&satellites)
+                .join()
+                .filter(|(_, _, handler, _)| !matches!(handler.body_type, BodyType::Star))
+                .map(|(_, id, _, _)| id.clone())
+                .collect::<Vec<_>>()
+        };
+        let name_of = |chosen: &Option<String>| {
+            candidates
+                .iter()
+                .find(|id| Some(id.get_id()) == chosen.as_deref())
+                .map_or(tr!("choose-a-body"), locale::body_name)
+        };
+        let simulation_time = world.read_resource::<SimulationTime>().0;
+        let units = *world.read_resource::<UnitSystem>();
+
+        let mut search_clicked = false;
+        let mut jump_clicked = false;
+
+        egui::Window::new(tr!("conjunctions-title"))
+            .id(egui::Id::new("conjunctions_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                for (id_source, label, chosen) in [
+                    ("first_body", tr!("first-body"), &mut self.first),
+                    ("second_body", tr!("second-body"), &mut self.second),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        egui::ComboBox::from_id_source(id_source)
+                            .selected_text(name_of(chosen))
+                            .show_ui(ui, |ui| {
+                                for id in candidates.iter() {
+                                    ui.selectable_value(
+                                        chosen,
+                                        Some(id.get_id().to_string()),
+                                        locale::body_name(id),
+                                    );
+                                }
+                            });
+                    });
+                }
+
+                let searching = matches!(self.state, SearchState::Searching(..));
+                let can_search = !searching
+                    && self.first.is_some()
+                    && self.second.is_some()
+                    && self.first != self.second;
+
+                ui.horizontal(|ui| {
+                    search_clicked = ui
+                        .add_enabled(can_search, egui::Button::new(tr!("conjunctions-find")))
+                        .on_hover_text(tr!("conjunctions-find-hover"))
+                        .clicked();
+
+                    if searching {
+                        ui.spinner();
+                    }
+                });
+
+                match &self.state {
+                    SearchState::Found(alignment, _, start_time) => {
+                        ui.label(tr!(
+                            "conjunctions-found",
+                            kind = alignment.kind.name(),
+                            time = units
+                                .format_duration(start_time + alignment.time - simulation_time)
+                        ));
+                        jump_clicked = ui.button(tr!("conjunctions-jump")).clicked();
+                    }
+                    SearchState::NotFound => {
+                        ui.label(tr!("conjunctions-not-found"));
+                    }
+                    _ => {}
+                }
+            });
+
+        if search_clicked {
+            self.start_search(world);
+        }
+
+        if jump_clicked {
+            if let SearchState::Found(alignment, entities, start_time) =
+                std::mem::replace(&mut self.state, SearchState::Idle)
+            {
+                jump_to(world, &alignment, &entities, start_time + alignment.time);
+
+                // Selects the wording, as the article depends on the kind in
+                // some languages
+                let kind = match alignment.kind {
+                    AlignmentKind::Conjunction => "conjunction",
+                    AlignmentKind::Opposition => "opposition",
+                };
+                world
+                    .write_resource::<Announcements>()
+                    .push(tr!("conjunctions-jumped", kind = kind));
+            }
+        }
+    }
+
+    fn poll_search(&mut self) {
+        //! Take the result of the search if it has finished
+
+        if let SearchState::Searching(search, entities, start_time) = &self.state {
+            self.state = match search.try_result() {
+                Ok(Some(alignment)) => SearchState::Found(alignment, entities.clone(), *start_time),
+                Ok(None) | Err(TryRecvError::Disconnected) => SearchState::NotFound,
+                Err(TryRecvError::Empty) => return,
+            };
+        }
+    }
+
+    fn start_search(&mut self, world: &World) {
+        //! Copy the state of the bodies and start propagating it on its own
+        //! thread
+
+        let (
+            entities,
+            ids,
+            positions,
+            velocities,
+            masses,
+            handlers,
+            satellites,
+            constant,
+            simulation_time,
+        ): (
+            Entities,
+            ReadStorage<Identifier>,
+            ReadStorage<Position>,
+            ReadStorage<Velocity>,
+            ReadStorage<Mass>,
+            ReadStorage<InteractionHandler>,
+            ReadStorage<Satellite>,
+            Read<GravitationalConstant>,
+            Read<SimulationTime>,
+        ) = world.system_data();
+
+        let (bodies, body_entities): (Vec<_>, Vec<_>) = (
+            &entities,
+            &positions,
+            &velocities,
+            &masses,
+            &handlers,
+            !&satellites,
+        )
+            .join()
+            .map(|(entity, position, velocity, mass, handler, _)| {
+                (
+                    SearchBody {
+                        position: position.0,
+                        velocity: velocity.0,
+                        mass: mass.0,
+                        body_type: handler.body_type,
+                        flags: handler.flags,
+                    },
+                    entity,
+                )
+            })
+            .unzip();
+
+        let index_of = |chosen: &Option<String>| {
+            body_entities
+                .iter()
+                .position(|&entity| ids.get(entity).map(|id| id.get_id()) == chosen.as_deref())
+        };
+
+        self.state = match (index_of(&self.first), index_of(&self.second)) {
+            (Some(first), Some(second)) => SearchState::Searching(
+                AlignmentSearch::start(bodies, first, second, constant.0),
+                body_entities,
+                simulation_time.0,
+            ),
+            _ => SearchState::NotFound,
+        };
+    }
+}
+
+fn jump_to(world: &mut World, alignment: &Alignment, body_entities: &[Entity], time: f64) {
+    //! Move the simulation to the simulated time of an alignment, satellites
+    //! keep their position relative to their parent
+
+    world.exec(
+        |(entities, mut positions, mut velocities, satellites, mut trails, mut simulation_time): (
+            Entities,
+            WriteStorage<Position>,
+            WriteStorage<Velocity>,
+            ReadStorage<Satellite>,
+            WriteStorage<Trail>,
+            Write<SimulationTime>,
+        )| {
+            let satellite_offsets = (&entities, &satellites)
+                .join()
+                .filter_map(|(entity, satellite)| {
+                    Some((
+                        entity,
+                        satellite.parent,
+                        positions.get(entity)?.0 - positions.get(satellite.parent)?.0,
+                        velocities.get(entity)?.0 - velocities.get(satellite.parent)?.0,
+                    ))
+                })
+                .collect::<Vec<_>>();
+
+            // Bodies that have merged since the search started are skipped
+            for (&entity, body) in body_entities.iter().zip(alignment.bodies.iter()) {
+                if let Some(position) = positions.get_mut(entity) {
+                    position.0 = body.position;
+                }
+                if let Some(velocity) = velocities.get_mut(entity) {
+                    velocity.0 = body.velocity;
+                }
+            }
+
+            for (entity, parent, position_offset, velocity_offset) in satellite_offsets {
+                if let Some(parent_position) = positions.get(parent).map(|position| position.0) {
+                    positions.get_mut(entity).unwrap().0 = parent_position + position_offset;
+                }
+                if let Some(parent_velocity) = velocities.get(parent).map(|velocity| velocity.0) {
+                    velocities.get_mut(entity).unwrap().0 = parent_velocity + velocity_offset;
+                }
+            }
+
+            simulation_time.0 = time;
+
+            // The bodies have jumped, so their trails no longer lead to them
+            for trail in (&mut trails).join() {
+                trail.clear();
+            }
+        },
+    );
+}
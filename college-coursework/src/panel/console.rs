@@ -0,0 +1,89 @@
+use crate::{
+    locale::tr,
+    scripting::{ConsoleLine, Scripts},
+};
+
+/// An example shown before anything has been run
+const EXAMPLE_SCRIPT: &'static str = r#"// Push the Earth along its orbit on day 100
+let pushed = false;
+on_step(|t| {
+    if !pushed && t >= 100 * DAY {
+        impulse("earth", [0, 1e28, 0]);
+        pushed = true;
+    }
+});"#;
+
+pub struct ConsoleWindow<'a> {
+    pub scripts: &'a mut Scripts,
+    /// The script being written, kept while the window is closed
+    pub input: &'a mut String,
+}
+impl<'a> super::Window for ConsoleWindow<'a> {
+    fn name(&self) -> String {
+        tr!("console-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("console_window"))
+            .collapsible(true)
+            .resizable(true)
+            .default_width(400.0)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl<'a> super::View for ConsoleWindow<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical()
+            .max_height(200.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in self.scripts.console() {
+                    match line {
+                        ConsoleLine::Input(script) => {
+                            ui.label(egui::RichText::new(script).monospace().weak())
+                        }
+                        ConsoleLine::Output(text) => {
+                            ui.label(egui::RichText::new(text).monospace())
+                        }
+                        ConsoleLine::Error(err) => ui.colored_label(egui::Color32::RED, err),
+                    };
+                }
+            });
+
+        ui.separator();
+
+        ui.add(
+            egui::TextEdit::multiline(self.input)
+                .code_editor()
+                .desired_rows(6)
+                .desired_width(f32::INFINITY)
+                .hint_text(EXAMPLE_SCRIPT),
+        );
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.input.trim().is_empty(),
+                    egui::Button::new(tr!("console-run")),
+                )
+                .clicked()
+            {
+                self.scripts.queue(self.input.clone());
+            }
+            if ui.button(tr!("console-clear")).clicked() {
+                self.scripts.clear_console();
+            }
+            if ui
+                .button(tr!("reset"))
+                .on_hover_text(tr!("console-reset-hover"))
+                .clicked()
+            {
+                self.scripts.reset();
+            }
+            ui.label(tr!("console-callbacks", count = self.scripts.callbacks()));
+        });
+    }
+}
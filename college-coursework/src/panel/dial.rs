@@ -0,0 +1,153 @@
+use std::f64::consts::TAU;
+
+use cgmath::Vector3;
+use specs::{Entities, Join, Read, ReadStorage, World};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    locale::{self, tr},
+    renderer::components::PlanetColour,
+    simulation::{BodyType, Identifier, InteractionHandler, Position},
+};
+
+/// Radius of the dial (in points)
+const DIAL_RADIUS: f32 = 100.0;
+
+/// How far each planet's tick reaches in from the edge of the dial, as a
+/// fraction of its radius
+const TICK_LENGTH: f32 = 0.35;
+
+pub struct DialBody {
+    pub name: String,
+    /// Heliocentric longitude (in radians)
+    pub longitude: f64,
+    pub colour: egui::Color32,
+}
+
+/// A dial showing where each planet is around the Sun, so conjunctions and
+/// oppositions can be seen at a glance
+pub struct LongitudeDialWindow {
+    pub bodies: Vec<DialBody>,
+}
+impl LongitudeDialWindow {
+    pub fn new(world: &World) -> Self {
+        //! Find the longitude of every planet from the positions in the world
+
+        let (entities, ids, positions, handlers, colours, accessibility_settings): (
+            Entities,
+            ReadStorage<Identifier>,
+            ReadStorage<Position>,
+            ReadStorage<InteractionHandler>,
+            ReadStorage<PlanetColour>,
+            Read<AccessibilitySettings>,
+        ) = world.system_data();
+
+        let sun = (&positions, &handlers)
+            .join()
+            .find(|(_, handler)| matches!(handler.body_type, BodyType::Star))
+            .map_or(Vector3::new(0.0, 0.0, 0.0), |(position, _)| position.0);
+
+        let bodies = (&entities, &ids, &positions, &handlers, (&colours).maybe())
+            .join()
+            .filter(|(_, _, _, handler, _)| matches!(handler.body_type, BodyType::Planet))
+            .map(|(entity, id, position, _, colour)| DialBody {
+                name: locale::body_name(id),
+                longitude: longitude(position.0, sun),
+                colour: colour.map_or(egui::Color32::WHITE, |colour| {
+                    let [r, g, b, _] = accessibility_settings.palette.body_colour(entity, colour.0);
+                    egui::Rgba::from_rgb(r, g, b).into()
+                }),
+            })
+            .collect();
+
+        Self { bodies }
+    }
+}
+impl super::Window for LongitudeDialWindow {
+    fn name(&self) -> String {
+        tr!("dial-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("dial_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl super::View for LongitudeDialWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let size = egui::Vec2::splat(2.0 * DIAL_RADIUS + 40.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let center = response.rect.center();
+        let visuals = ui.visuals();
+
+        // Screen y points down, so angles are measured clockwise from the
+        // x axis on screen and have to be flipped
+        let direction =
+            |longitude: f64| egui::vec2(longitude.cos() as f32, -longitude.sin() as f32);
+
+        painter.circle_stroke(
+            center,
+            DIAL_RADIUS,
+            visuals.widgets.noninteractive.fg_stroke,
+        );
+        painter.circle_filled(center, 4.0, egui::Color32::from_rgb(255, 220, 100));
+
+        // Mark every 30 degrees, labelling the quarters
+        for degrees in (0..360).step_by(30) {
+            let direction = direction((degrees as f64).to_radians());
+            let inner = if degrees % 90 == 0 { 0.9 } else { 0.95 };
+            painter.line_segment(
+                [
+                    center + direction * DIAL_RADIUS * inner,
+                    center + direction * DIAL_RADIUS,
+                ],
+                visuals.widgets.noninteractive.fg_stroke,
+            );
+
+            if degrees % 90 == 0 {
+                painter.text(
+                    center + direction * (DIAL_RADIUS + 12.0),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{}\u{b0}", degrees),
+                    egui::FontId::proportional(11.0),
+                    visuals.text_color(),
+                );
+            }
+        }
+
+        for body in self.bodies.iter() {
+            let direction = direction(body.longitude);
+            painter.line_segment(
+                [
+                    center + direction * DIAL_RADIUS * (1.0 - TICK_LENGTH),
+                    center + direction * DIAL_RADIUS,
+                ],
+                egui::Stroke::new(3.0, body.colour),
+            );
+        }
+
+        ui.separator();
+
+        egui::Grid::new("longitude_dial_bodies")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for body in self.bodies.iter() {
+                    ui.colored_label(body.colour, &body.name);
+                    ui.label(format!("{:.1}\u{b0}", body.longitude.to_degrees()));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn longitude(position: Vector3<f64>, sun: Vector3<f64>) -> f64 {
+    //! Heliocentric ecliptic longitude of a position, between 0 and 2π
+
+    let relative = position - sun;
+    relative.y.atan2(relative.x).rem_euclid(TAU)
+}
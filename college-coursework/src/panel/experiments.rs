@@ -0,0 +1,154 @@
+use specs::{Entities, Join, Read, ReadStorage, World, Write, WriteStorage};
+
+use crate::{
+    locale::{self, tr},
+    simulation::{
+        GravitationalConstant, GravityOverride, GravityOverrides, GroupTags, Identifier,
+        InteractionFlags,
+    },
+};
+
+/// The groups overrides can apply to, with the ids of their names
+const GROUPS: [(InteractionFlags, &str); 5] = [
+    (InteractionFlags::STAR, "group-stars"),
+    (InteractionFlags::PLANET, "group-planets"),
+    (InteractionFlags::MOON, "group-moons"),
+    (InteractionFlags::GROUP_A, "group-a"),
+    (InteractionFlags::GROUP_B, "group-b"),
+];
+
+/// Tagged groups bodies can be added to from the window
+const TAGS: [(InteractionFlags, &str); 2] = [
+    (InteractionFlags::GROUP_A, "A"),
+    (InteractionFlags::GROUP_B, "B"),
+];
+
+/// "What if" experiments that change the physics for some bodies, e.g.
+/// making Jupiter pull harder on only the bodies tagged as asteroids
+pub struct ExperimentsWindow;
+impl ExperimentsWindow {
+    pub fn show(ctx: &egui::Context, world: &mut World, open: &mut bool) {
+        //! Show the window to edit the gravity overrides and tag bodies
+
+        let (entities, ids, mut tags, mut overrides, constant): (
+            Entities,
+            ReadStorage<Identifier>,
+            WriteStorage<GroupTags>,
+            Write<GravityOverrides>,
+            Read<GravitationalConstant>,
+        ) = world.system_data();
+
+        egui::Window::new(tr!("experiments-title"))
+            .id(egui::Id::new("experiments_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(tr!("experiments-description"));
+
+                egui::CollapsingHeader::new(tr!("experiments-overrides"))
+                    .id_source("gravity_overrides")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut removed = None;
+                        for (i, gravity_override) in overrides.0.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut gravity_override.enabled, "");
+                                group_picker(
+                                    ui,
+                                    ("override_first", i),
+                                    &mut gravity_override.first,
+                                );
+                                ui.label(tr!("experiments-and"));
+                                group_picker(
+                                    ui,
+                                    ("override_second", i),
+                                    &mut gravity_override.second,
+                                );
+                                ui.add(
+                                    egui::DragValue::new(&mut gravity_override.multiplier)
+                                        .clamp_range(0.0..=1000.0)
+                                        .speed(0.01)
+                                        .suffix(" \u{d7} G"),
+                                )
+                                .on_hover_text(format!(
+                                    "G = {:.4e}",
+                                    constant.0 * gravity_override.multiplier
+                                ));
+
+                                if ui.small_button("\u{2716}").clicked() {
+                                    removed = Some(i);
+                                }
+                            });
+                        }
+                        if let Some(i) = removed {
+                            overrides.0.remove(i);
+                        }
+
+                        if ui.button(tr!("experiments-add-override")).clicked() {
+                            overrides.0.push(GravityOverride::default());
+                        }
+                    });
+
+                egui::CollapsingHeader::new(tr!("experiments-groups"))
+                    .id_source("experiment_groups")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("experiment_groups")
+                            .num_columns(TAGS.len() + 1)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (entity, id) in (&entities, &ids).join() {
+                                    ui.label(locale::body_name(id));
+
+                                    let current = tags
+                                        .get(entity)
+                                        .map_or(InteractionFlags::empty(), |tags| tags.0);
+                                    let mut updated = current;
+                                    for (flag, name) in TAGS {
+                                        let mut tagged = updated.contains(flag);
+                                        if ui.checkbox(&mut tagged, name).changed() {
+                                            updated.set(flag, tagged);
+                                        }
+                                    }
+
+                                    if updated != current {
+                                        if updated.is_empty() {
+                                            tags.remove(entity);
+                                        } else {
+                                            tags.insert(entity, GroupTags(updated)).unwrap();
+                                        }
+                                    }
+
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
+
+fn group_picker(ui: &mut egui::Ui, id_source: impl std::hash::Hash, groups: &mut InteractionFlags) {
+    //! A drop down to choose any number of groups
+
+    let selected = GROUPS
+        .iter()
+        .filter(|(flag, _)| groups.contains(*flag))
+        .map(|(_, name)| locale::text(name, None))
+        .collect::<Vec<_>>();
+
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(if selected.is_empty() {
+            tr!("experiments-no-groups")
+        } else {
+            selected.join(", ")
+        })
+        .show_ui(ui, |ui| {
+            for (flag, name) in GROUPS {
+                let mut chosen = groups.contains(flag);
+                if ui.checkbox(&mut chosen, locale::text(name, None)).changed() {
+                    groups.set(flag, chosen);
+                }
+            }
+        });
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::locale::tr;
+
+/// Facts about the real bodies, by id
+const FACTS: &str = include_str!("../../assets/planets/facts.toml");
+
+lazy_static! {
+    static ref BODY_FACTS: HashMap<String, BodyFacts> =
+        toml::from_str(FACTS).expect("Failed to parse the body facts");
+}
+
+/// What a body is mostly made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Material {
+    Hydrogen,
+    Helium,
+    Rock,
+    Iron,
+    Water,
+    CarbonDioxide,
+    /// Water, ammonia and methane ices, as in the ice giants
+    Ices,
+    NitrogenIce,
+    MethaneIce,
+}
+impl Material {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Hydrogen => tr!("material-hydrogen"),
+            Self::Helium => tr!("material-helium"),
+            Self::Rock => tr!("material-rock"),
+            Self::Iron => tr!("material-iron"),
+            Self::Water => tr!("material-water"),
+            Self::CarbonDioxide => tr!("material-carbon-dioxide"),
+            Self::Ices => tr!("material-ices"),
+            Self::NitrogenIce => tr!("material-nitrogen-ice"),
+            Self::MethaneIce => tr!("material-methane-ice"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub year: i32,
+    /// Who is credited with it
+    pub by: String,
+}
+
+/// Facts about a real body, which are shown but not simulated
+#[derive(Debug, Clone, Deserialize)]
+pub struct BodyFacts {
+    /// (in km)
+    pub mean_radius: f64,
+    /// The sidereal day, negative if the body spins backwards (in hours)
+    pub rotation_period: f64,
+    /// The main materials, most abundant first
+    pub composition: Vec<Material>,
+    /// None if the body has been known since antiquity
+    pub discovery: Option<Discovery>,
+}
+
+pub fn facts(id: &str) -> Option<&'static BodyFacts> {
+    //! The facts about the real body with an id, if there is one
+    BODY_FACTS.get(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BODY_FACTS;
+    use crate::simulation::{moons, planets};
+
+    #[test]
+    fn test_facts_for_every_body() {
+        let moons = moons().into_iter().map(|(_, moon)| moon);
+        for body in planets().into_iter().chain(moons) {
+            let id = body.get_identifier();
+            assert!(
+                BODY_FACTS.contains_key(id.get_id()),
+                "No facts about {}",
+                id.get_id()
+            );
+        }
+    }
+}
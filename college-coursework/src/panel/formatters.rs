@@ -16,3 +16,20 @@ pub fn dynamic_exponent_formatter() -> impl Fn(f64, RangeInclusive<usize>) -> St
 pub fn dynamic_decimals_formatter() -> impl Fn(f64, RangeInclusive<usize>) -> String {
     |value: f64, _| format!("{}", value)
 }
+
+pub fn format_duration(seconds: f64) -> String {
+    //! Formats a duration in seconds as days, hours, minutes and seconds
+
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let seconds = seconds.abs();
+
+    let days = (seconds / 86400.0).floor();
+    let hours = ((seconds % 86400.0) / 3600.0).floor();
+    let minutes = ((seconds % 3600.0) / 60.0).floor();
+    let seconds = (seconds % 60.0).floor();
+
+    format!(
+        "{}{}d {:02}:{:02}:{:02}",
+        sign, days, hours, minutes, seconds
+    )
+}
@@ -3,12 +3,29 @@ use chrono::{DateTime, Local, Utc};
 use egui::RichText;
 
 use crate::{
-    simulation::{Identifier, SUN},
-    util::{convert_datetime_to_julian_date, convert_julian_date_to_datetime},
+    accessibility::{ColourPalette, MAX_UI_SCALE, MIN_UI_SCALE},
+    audio::{MusicPlayer, RepeatMode, MAX_CROSSFADE, USER_MUSIC_DIR},
+    locale::{self, tr, Language},
+    renderer::{
+        camera::CameraControllerType,
+        components::CameraCenter,
+        cursor::CursorStyle,
+        fonts::FontSettings,
+        trails::{Colourmap, TrailColouring},
+    },
+    simulation::{
+        AutosaveClock, BodyType, Identifier, PruneReport, ScaleMode, Stopwatch, AUTOSAVE_DIR, SUN,
+    },
+    util::{convert_datetime_to_julian_date, convert_julian_date_to_datetime, fuzzy_match, AU},
 };
 
 use super::{
-    dynamic_decimals_formatter, dynamic_exponent_formatter, help::HelpWindow, planet::PlanetWindow,
+    dynamic_decimals_formatter, dynamic_exponent_formatter, format_duration,
+    help::HelpWindow,
+    layout::Dock,
+    planet::PlanetWindow,
+    units::UnitSystem,
+    validation::{self, edit_validated},
     DateTimeValue, Vector3Value,
 };
 
@@ -18,68 +35,168 @@ pub const MINUS_ONE_EXPONENT: &'static str = const_format::concatcp!(MINUS_EXPON
 pub const TWO_EXPONENT: &'static str = "\u{F80C}";
 pub const MINUS_TWO_EXPONENT: &'static str = const_format::concatcp!(MINUS_EXPONENT, TWO_EXPONENT);
 
-#[derive(PartialEq)]
-pub enum CameraControllerType {
-    Free,
-    Orbit,
-}
-
 pub struct GlobalWindow<'a> {
     pub camera_section: CameraSection<'a>,
     pub constant_section: ConstantSection<'a>,
+    pub integrator_section: IntegratorSection<'a>,
     pub time_section: TimeSection<'a>,
+    pub display_section: DisplaySection<'a>,
+    pub accessibility_section: AccessibilitySection<'a>,
+    pub font_section: FontSection<'a>,
+    pub audio_section: AudioSection<'a>,
+    pub language_section: LanguageSection,
+    pub autosave_section: AutosaveSection<'a>,
+    pub diagnostics_section: DiagnosticsSection<'a>,
+    pub bodies_section: BodiesSection<'a>,
     pub help_window_shown: &'a mut bool,
-    pub planet_windows_shown: Vec<(Identifier, &'a mut bool)>,
+    pub stats_window_shown: &'a mut bool,
+    pub dial_window_shown: &'a mut bool,
+    pub conjunction_window_shown: &'a mut bool,
+    pub period_window_shown: &'a mut bool,
+    pub compare_window_shown: &'a mut bool,
+    pub experiments_window_shown: &'a mut bool,
+    pub sandbox_window_shown: &'a mut bool,
+    pub camera_path_window_shown: &'a mut bool,
+    pub render_settings_window_shown: &'a mut bool,
+    pub render_stats_window_shown: &'a mut bool,
+    pub console_window_shown: &'a mut bool,
+    pub log_window_shown: &'a mut bool,
+    pub layout_window_shown: &'a mut bool,
+    /// Where this window is docked
+    pub dock: &'a mut Dock,
+    pub new_window_shown: &'a mut bool,
     pub save_window_shown: &'a mut bool,
     pub load_window_shown: &'a mut bool,
 }
 impl<'a> super::View for GlobalWindow<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr!("dock"));
+            egui::ComboBox::from_id_source("dock")
+                .selected_text(self.dock.name())
+                .show_ui(ui, |ui| {
+                    for dock in Dock::ALL {
+                        ui.selectable_value(self.dock, dock, dock.name());
+                    }
+                });
+        });
+
         self.camera_section.ui(ui);
         self.constant_section.ui(ui);
+        self.integrator_section.ui(ui);
         self.time_section.ui(ui);
-
-        egui::CollapsingHeader::new("Bodies")
-            .default_open(false)
-            .show(ui, |ui| {
-                for (id, shown) in self.planet_windows_shown.iter_mut() {
-                    if ui.button(id.get_name()).clicked() {
-                        **shown = !**shown;
-                    }
-                }
-            });
+        self.display_section.ui(ui);
+        self.accessibility_section.ui(ui);
+        self.font_section.ui(ui);
+        self.audio_section.ui(ui);
+        self.language_section.ui(ui);
+        self.autosave_section.ui(ui);
+        self.diagnostics_section.ui(ui);
+        self.bodies_section.ui(ui);
 
         ui.separator();
 
         ui.vertical_centered(|ui| {
-            if ui.link("Save Simulation").clicked() {
+            if ui.link(tr!("new-simulation-title")).clicked() {
+                *self.new_window_shown = !*self.new_window_shown;
+            }
+
+            if ui.link(tr!("save-simulation-title")).clicked() {
                 *self.save_window_shown = !*self.save_window_shown;
             }
 
-            if ui.link("Load Simulation").clicked() {
+            if ui.link(tr!("load-simulation-title")).clicked() {
                 *self.load_window_shown = !*self.load_window_shown;
             }
         });
 
         ui.vertical_centered(|ui| {
-            if ui.link("Help").clicked() {
+            if ui.link(tr!("dial-link")).clicked() {
+                *self.dial_window_shown = !*self.dial_window_shown;
+            }
+
+            if ui.link(tr!("conjunctions-title")).clicked() {
+                *self.conjunction_window_shown = !*self.conjunction_window_shown;
+            }
+
+            if ui.link(tr!("periods-title")).clicked() {
+                *self.period_window_shown = !*self.period_window_shown;
+            }
+
+            if ui.link(tr!("compare-title")).clicked() {
+                *self.compare_window_shown = !*self.compare_window_shown;
+            }
+
+            if ui.link(tr!("experiments-title")).clicked() {
+                *self.experiments_window_shown = !*self.experiments_window_shown;
+            }
+
+            if ui.link(tr!("sandbox-title")).clicked() {
+                *self.sandbox_window_shown = !*self.sandbox_window_shown;
+            }
+
+            if ui.link(tr!("camera-path-title")).clicked() {
+                *self.camera_path_window_shown = !*self.camera_path_window_shown;
+            }
+
+            if ui.link(tr!("console-title")).clicked() {
+                *self.console_window_shown = !*self.console_window_shown;
+            }
+        });
+
+        ui.vertical_centered(|ui| {
+            if ui.link(tr!("render-settings-title")).clicked() {
+                *self.render_settings_window_shown = !*self.render_settings_window_shown;
+            }
+
+            if ui.link(tr!("render-stats-title")).clicked() {
+                *self.render_stats_window_shown = !*self.render_stats_window_shown;
+            }
+
+            if ui.link(tr!("logs-title")).clicked() {
+                *self.log_window_shown = !*self.log_window_shown;
+            }
+
+            if ui.link(tr!("layouts-title")).clicked() {
+                *self.layout_window_shown = !*self.layout_window_shown;
+            }
+
+            if ui.link(tr!("help-title")).clicked() {
                 *self.help_window_shown = !*self.help_window_shown;
             }
+
+            if ui.link(tr!("about-title")).clicked() {
+                *self.stats_window_shown = !*self.stats_window_shown;
+            }
         });
     }
 }
 impl<'a> super::Window for GlobalWindow<'a> {
-    fn name(&self) -> &'static str {
-        "Global State"
+    fn name(&self) -> String {
+        tr!("global-title")
     }
 
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
         use super::View as _;
-        egui::Window::new(self.name())
-            .collapsible(true)
-            .resizable(true)
-            //.open(open)
-            .show(ctx, |ui| self.ui(ui));
+
+        let panel = match *self.dock {
+            Dock::Floating => {
+                egui::Window::new(self.name())
+                    .id(egui::Id::new("global_window"))
+                    .collapsible(true)
+                    .resizable(true)
+                    //.open(open)
+                    .show(ctx, |ui| self.ui(ui));
+                return;
+            }
+            Dock::Left => egui::SidePanel::left("global_panel"),
+            Dock::Right => egui::SidePanel::right("global_panel"),
+        };
+
+        panel.resizable(true).show(ctx, |ui| {
+            ui.heading(self.name());
+            egui::ScrollArea::vertical().show(ui, |ui| self.ui(ui));
+        });
     }
 }
 
@@ -87,37 +204,136 @@ pub struct CameraSection<'a> {
     pub position: &'a mut Vector3<f32>,
     pub speed: &'a mut f32,
     pub controller_type: &'a mut CameraControllerType,
+    pub center: &'a mut CameraCenter,
+    /// The bodies the camera can follow
+    pub bodies: Vec<Identifier>,
+    /// How long flying to a body takes (in seconds)
+    pub fly_duration: &'a mut f32,
+    pub gamepad_enabled: &'a mut bool,
+    pub gamepad_dead_zone: &'a mut f32,
+    pub units: UnitSystem,
 }
 impl<'a> super::View for CameraSection<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
-        egui::CollapsingHeader::new("Camera")
+        egui::CollapsingHeader::new(tr!("camera-section"))
+            .id_source("camera_section")
             .default_open(true)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("Position:");
-                    ui.add(
-                        Vector3Value::new(self.position)
-                            .custom_formatter(dynamic_exponent_formatter())
-                            .suffix(" m")
-                            .speed(0.1),
+                    let distance = self.units.distance();
+
+                    ui.label(tr!("camera-position"));
+                    edit_validated(
+                        ui,
+                        "camera_position",
+                        self.position,
+                        |position| validation::finite_vector(position.map(f64::from)),
+                        |ui, value| {
+                            let shown = value.map(|x| distance.from_si(x as f64) as f32);
+                            let mut position = shown;
+
+                            let response = ui.add(
+                                Vector3Value::new(&mut position)
+                                    .custom_formatter(dynamic_exponent_formatter())
+                                    .suffix(distance.suffix())
+                                    .speed(0.1),
+                            );
+
+                            if position != shown {
+                                *value = position.map(|x| distance.to_si(x as f64) as f32);
+                            }
+                            response
+                        },
                     );
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("Speed:");
-                    ui.add(
-                        egui::Slider::new(self.speed, 1.0..=1_000_000.0)
-                            .logarithmic(true)
-                            .suffix(const_format::concatcp!(" ms", MINUS_ONE_EXPONENT))
-                            .step_by(0.1)
-                            .custom_formatter(dynamic_exponent_formatter()),
-                    )
+                    let speed = self.units.speed();
+                    let range = speed.from_si(1.0) as f32..=speed.from_si(1_000_000.0) as f32;
+                    let mut shown = speed.from_si(*self.speed as f64) as f32;
+
+                    ui.label(tr!("camera-speed"));
+                    if ui
+                        .add(
+                            egui::Slider::new(&mut shown, range)
+                                .logarithmic(true)
+                                .suffix(speed.suffix())
+                                .custom_formatter(dynamic_exponent_formatter()),
+                        )
+                        .changed()
+                    {
+                        *self.speed = speed.to_si(shown as f64) as f32;
+                    }
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("Controller:");
-                    ui.selectable_value(self.controller_type, CameraControllerType::Free, "Free");
-                    ui.selectable_value(self.controller_type, CameraControllerType::Orbit, "Orbit");
+                    ui.label(tr!("camera-controller"));
+                    ui.selectable_value(
+                        self.controller_type,
+                        CameraControllerType::Free,
+                        tr!("camera-controller-free"),
+                    );
+                    ui.selectable_value(
+                        self.controller_type,
+                        CameraControllerType::Orbit,
+                        tr!("camera-controller-orbit"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.center.following, tr!("camera-follow"))
+                        .on_hover_text(tr!("camera-follow-hover"));
+                    egui::ComboBox::from_id_source("followed_body")
+                        .selected_text(locale::body_name(self.center.get_body()))
+                        .show_ui(ui, |ui| {
+                            for id in self.bodies.iter() {
+                                let selected = id.get_id() == self.center.get_body().get_id();
+                                if ui
+                                    .selectable_label(selected, locale::body_name(id))
+                                    .clicked()
+                                {
+                                    self.center.set_body(id.clone());
+                                    self.center.following = true;
+                                }
+                            }
+                        });
+                });
+
+                ui.add_enabled_ui(self.center.following, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("camera-offset"));
+                        edit_validated(
+                            ui,
+                            "camera_offset",
+                            &mut self.center.offset,
+                            |offset| validation::finite_vector(offset.map(f64::from)),
+                            |ui, offset| ui.add(Vector3Value::new(offset).speed(0.1)),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("camera-smoothing"));
+                        ui.add(
+                            egui::Slider::new(&mut self.center.smoothing, 0.0..=5.0).suffix(" s"),
+                        )
+                        .on_hover_text(tr!("camera-smoothing-hover"));
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("camera-fly-duration"));
+                    ui.add(egui::Slider::new(self.fly_duration, 0.0..=10.0).suffix(" s"))
+                        .on_hover_text(tr!("camera-fly-duration-hover"));
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(self.gamepad_enabled, tr!("camera-gamepad"))
+                        .on_hover_text(tr!("camera-gamepad-hover"));
+                    ui.add_enabled_ui(*self.gamepad_enabled, |ui| {
+                        ui.label(tr!("camera-dead-zone"));
+                        ui.add(egui::Slider::new(self.gamepad_dead_zone, 0.0..=0.9))
+                            .on_hover_text(tr!("camera-dead-zone-hover"));
+                    });
                 });
             });
     }
@@ -128,48 +344,100 @@ pub struct ConstantSection<'a> {
 }
 impl<'a> super::View for ConstantSection<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
-        egui::CollapsingHeader::new("Constants")
+        egui::CollapsingHeader::new(tr!("constants-section"))
+            .id_source("constants_section")
             .default_open(true)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("Gravitational Constant:");
-                    ui.add(
-                        egui::DragValue::new(self.gravitational_constant)
-                            .clamp_range(0.0..=f64::INFINITY)
-                            .speed(0.01e-11)
-                            .custom_formatter(dynamic_exponent_formatter())
-                            .suffix(const_format::concatcp!(
-                                " Nm",
-                                TWO_EXPONENT,
-                                "kg",
-                                MINUS_TWO_EXPONENT
-                            )),
+                    ui.label(tr!("constants-gravitational-constant"));
+                    edit_validated(
+                        ui,
+                        "gravitational_constant",
+                        self.gravitational_constant,
+                        validation::non_negative,
+                        |ui, value| {
+                            ui.add(
+                                egui::DragValue::new(value)
+                                    .clamp_range(0.0..=f64::INFINITY)
+                                    .speed(0.01e-11)
+                                    .custom_formatter(dynamic_exponent_formatter())
+                                    .suffix(const_format::concatcp!(
+                                        " Nm",
+                                        TWO_EXPONENT,
+                                        "kg",
+                                        MINUS_TWO_EXPONENT
+                                    )),
+                            )
+                        },
                     );
                 })
             });
     }
 }
 
+pub struct IntegratorSection<'a> {
+    pub analytic_close_encounters: &'a mut bool,
+    pub close_encounter_steps: &'a mut f64,
+}
+impl<'a> super::View for IntegratorSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("integrator-section"))
+            .id_source("integrator_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.checkbox(
+                    self.analytic_close_encounters,
+                    tr!("integrator-analytic-encounters"),
+                )
+                .on_hover_text(tr!("integrator-analytic-encounters-hover"));
+
+                ui.add_enabled_ui(*self.analytic_close_encounters, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("integrator-minimum-steps"));
+                        ui.add(
+                            egui::Slider::new(self.close_encounter_steps, 1.0..=1000.0)
+                                .logarithmic(true),
+                        );
+                    });
+                });
+            });
+    }
+}
+
 pub struct TimeSection<'a> {
     pub time_scale: &'a mut f64,
+    pub paused: &'a mut bool,
     pub current_date_time: &'a mut DateTime<Local>,
+    pub simulation_time: f64,
+    pub stopwatch: &'a mut Stopwatch,
+    pub units: UnitSystem,
 }
 impl<'a> super::View for TimeSection<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
-        egui::CollapsingHeader::new("Time")
+        egui::CollapsingHeader::new(tr!("time-section"))
+            .id_source("time_section")
             .default_open(true)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("Time Scale:");
+                    ui.label(tr!("time-scale"));
                     ui.add(
                         egui::Slider::new(self.time_scale, 0.0..=3_155_760_000.0)
                             .logarithmic(true)
                             .custom_formatter(dynamic_exponent_formatter()),
                     );
+
+                    let label = if *self.paused {
+                        tr!("time-resume")
+                    } else {
+                        tr!("time-pause")
+                    };
+                    if ui.button(label).clicked() {
+                        *self.paused = !*self.paused;
+                    }
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("Date:");
+                    ui.label(tr!("time-date"));
 
                     let mut date = self.current_date_time.date().with_timezone(&Utc);
                     ui.add(egui_extras::DatePickerButton::new(&mut date));
@@ -178,25 +446,724 @@ impl<'a> super::View for TimeSection<'a> {
                         .and_time(self.current_date_time.time())
                         .unwrap();
 
-                    ui.label("Time:");
+                    ui.label(tr!("time-time"));
                     ui.add(DateTimeValue::new("time_value", self.current_date_time));
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("Julian Date:");
+                    ui.label(tr!("time-julian-date"));
                     let mut julian_date = convert_datetime_to_julian_date(
                         &self.current_date_time.with_timezone(&Utc),
                     );
-                    let response = ui.add(
-                        egui::DragValue::new(&mut julian_date)
-                            .speed(0.1)
-                            .custom_formatter(dynamic_decimals_formatter()),
+                    let previous = julian_date;
+                    edit_validated(
+                        ui,
+                        "julian_date",
+                        &mut julian_date,
+                        validation::finite,
+                        |ui, value| {
+                            ui.add(
+                                egui::DragValue::new(value)
+                                    .speed(0.1)
+                                    .custom_formatter(dynamic_decimals_formatter()),
+                            )
+                        },
                     );
-                    if response.changed() {
+                    if julian_date != previous {
                         *self.current_date_time =
                             convert_julian_date_to_datetime(julian_date).with_timezone(&Local);
                     }
                 });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("stopwatch"));
+                    ui.monospace(
+                        self.units
+                            .format_duration(self.stopwatch.elapsed(self.simulation_time)),
+                    );
+
+                    if self.stopwatch.is_running() {
+                        if ui.button(tr!("stopwatch-stop")).clicked() {
+                            self.stopwatch.stop(self.simulation_time);
+                        }
+                        if ui.button(tr!("stopwatch-lap")).clicked() {
+                            self.stopwatch.lap(self.simulation_time);
+                        }
+                    } else if ui.button(tr!("stopwatch-start")).clicked() {
+                        self.stopwatch.start(self.simulation_time);
+                    }
+
+                    if ui.button(tr!("reset")).clicked() {
+                        self.stopwatch.reset();
+                    }
+                });
+
+                for (i, lap) in self.stopwatch.laps().into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("stopwatch-lap-number", number = i + 1));
+                        ui.monospace(self.units.format_duration(lap));
+                    });
+                }
+            });
+    }
+}
+
+pub struct DisplaySection<'a> {
+    pub show_labels: &'a mut bool,
+    pub cursor_style: &'a mut CursorStyle,
+    pub cursor_size: &'a mut f32,
+    pub cursor_colour: &'a mut egui::Color32,
+    pub reticle: &'a mut bool,
+    pub attract_mode: &'a mut bool,
+    pub attract_timeout: &'a mut f32,
+    pub scale_mode: &'a mut ScaleMode,
+    pub scale_multiplier: &'a mut f64,
+    pub trails_shown: &'a mut bool,
+    pub trail_colouring: &'a mut TrailColouring,
+    pub trail_colourmap: &'a mut Colourmap,
+    pub trail_width: &'a mut f32,
+    pub grid_shown: &'a mut bool,
+    pub grid_rings: &'a mut u32,
+    pub axes_shown: &'a mut bool,
+    pub ruler_active: &'a mut bool,
+    pub velocity_arrows: &'a mut bool,
+    pub acceleration_arrows: &'a mut bool,
+    pub field_shown: &'a mut bool,
+    pub field_extent: &'a mut f64,
+    pub units: &'a mut UnitSystem,
+}
+impl<'a> super::View for DisplaySection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("display-section"))
+            .id_source("display_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.checkbox(self.show_labels, tr!("display-labels"));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-units"));
+                    egui::ComboBox::from_id_source("unit_system")
+                        .selected_text(self.units.name())
+                        .show_ui(ui, |ui| {
+                            for units in UnitSystem::ALL {
+                                ui.selectable_value(self.units, units, units.name());
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-body-size"));
+                    ui.selectable_value(
+                        self.scale_mode,
+                        ScaleMode::True,
+                        tr!("display-true-scale"),
+                    );
+                    ui.selectable_value(
+                        self.scale_mode,
+                        ScaleMode::Exaggerated,
+                        tr!("display-exaggerated"),
+                    );
+                });
+
+                ui.add_enabled_ui(*self.scale_mode == ScaleMode::Exaggerated, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("display-exaggeration"));
+                        ui.add(
+                            egui::Slider::new(self.scale_multiplier, 1.0..=5000.0)
+                                .logarithmic(true)
+                                .prefix("\u{d7}"),
+                        )
+                        .on_hover_text(tr!("display-exaggeration-hover"));
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(self.grid_shown, tr!("display-grid"));
+                    ui.add_enabled(
+                        *self.grid_shown,
+                        egui::DragValue::new(self.grid_rings)
+                            .clamp_range(1..=100)
+                            .suffix(" AU"),
+                    );
+                });
+                ui.checkbox(self.axes_shown, tr!("display-axes"));
+                ui.checkbox(self.ruler_active, tr!("display-ruler"))
+                    .on_hover_text(tr!("display-ruler-hover"));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-arrows"));
+                    ui.checkbox(self.velocity_arrows, tr!("display-velocity-arrows"));
+                    ui.checkbox(self.acceleration_arrows, tr!("display-acceleration-arrows"));
+                })
+                .response
+                .on_hover_text(tr!("display-arrows-hover"));
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(self.field_shown, tr!("display-field"))
+                        .on_hover_text(tr!("display-field-hover"));
+                    ui.add_enabled(
+                        *self.field_shown,
+                        egui::DragValue::new(self.field_extent)
+                            .clamp_range(0.1..=100.0)
+                            .speed(0.1)
+                            .suffix(" AU"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(self.trails_shown, tr!("display-trails"));
+                    egui::ComboBox::from_id_source("trail_colouring")
+                        .selected_text(self.trail_colouring.name())
+                        .show_ui(ui, |ui| {
+                            for colouring in TrailColouring::ALL {
+                                ui.selectable_value(
+                                    self.trail_colouring,
+                                    colouring,
+                                    colouring.name(),
+                                );
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-trail-width"));
+                    ui.add_enabled(
+                        *self.trails_shown,
+                        egui::Slider::new(self.trail_width, 1.0..=8.0).suffix(" px"),
+                    )
+                    .on_hover_text(tr!("display-trail-width-hover"));
+                });
+
+                ui.add_enabled_ui(*self.trail_colouring != TrailColouring::BodyColour, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("display-colourmap"));
+                        egui::ComboBox::from_id_source("trail_colourmap")
+                            .selected_text(self.trail_colourmap.name())
+                            .show_ui(ui, |ui| {
+                                for colourmap in Colourmap::ALL {
+                                    ui.selectable_value(
+                                        self.trail_colourmap,
+                                        colourmap,
+                                        colourmap.name(),
+                                    );
+                                }
+                            });
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-cursor"));
+                    egui::ComboBox::from_id_source("cursor_style")
+                        .selected_text(self.cursor_style.name())
+                        .show_ui(ui, |ui| {
+                            for style in CursorStyle::ALL {
+                                ui.selectable_value(self.cursor_style, style, style.name());
+                            }
+                        });
+                    ui.color_edit_button_srgba(self.cursor_colour);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("display-cursor-size"));
+                    ui.add(egui::Slider::new(self.cursor_size, 4.0..=64.0).suffix(" px"));
+                });
+
+                ui.checkbox(self.reticle, tr!("display-reticle"));
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(self.attract_mode, tr!("display-attract-mode"));
+                    ui.add_enabled(
+                        *self.attract_mode,
+                        egui::DragValue::new(self.attract_timeout)
+                            .clamp_range(10.0..=3600.0)
+                            .suffix(" s"),
+                    );
+                    ui.label(tr!("display-attract-mode-idle"));
+                });
+            });
+    }
+}
+
+pub struct AccessibilitySection<'a> {
+    pub narration: &'a mut bool,
+    pub high_contrast: &'a mut bool,
+    pub ui_scale: &'a mut f32,
+    pub palette: &'a mut ColourPalette,
+}
+impl<'a> super::View for AccessibilitySection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("accessibility-section"))
+            .id_source("accessibility_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.checkbox(self.narration, tr!("accessibility-narration"));
+                ui.checkbox(self.high_contrast, tr!("accessibility-high-contrast"));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("accessibility-ui-scale"));
+                    ui.add(
+                        egui::Slider::new(self.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                            .step_by(0.05)
+                            .prefix("\u{d7}"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("accessibility-palette"))
+                        .on_hover_text(tr!("accessibility-palette-hover"));
+                    egui::ComboBox::from_id_source("colour_palette")
+                        .selected_text(self.palette.name())
+                        .show_ui(ui, |ui| {
+                            for palette in ColourPalette::ALL {
+                                ui.selectable_value(self.palette, palette, palette.name());
+                            }
+                        });
+                });
+            });
+    }
+}
+
+pub struct FontSection<'a> {
+    pub settings: &'a mut FontSettings,
+    /// File names of the bundled fonts which can be chosen
+    pub available: &'a [String],
+}
+impl<'a> FontSection<'a> {
+    fn font_choice(
+        ui: &mut egui::Ui,
+        id: &str,
+        font: &mut Option<String>,
+        default_name: &str,
+        available: &[String],
+    ) {
+        //! Show a drop down to choose one of the bundled fonts, or the
+        //! default named for when none is chosen
+
+        egui::ComboBox::from_id_source(id)
+            .selected_text(font.as_deref().unwrap_or(default_name))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(font, None, default_name);
+                for name in available {
+                    ui.selectable_value(font, Some(name.clone()), name);
+                }
+            });
+    }
+}
+impl<'a> super::View for FontSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("fonts-section"))
+            .id_source("fonts_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("font_settings")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("fonts-interface"));
+                        Self::font_choice(
+                            ui,
+                            "ui_font",
+                            &mut self.settings.ui,
+                            &tr!("fonts-built-in"),
+                            self.available,
+                        );
+                        ui.end_row();
+
+                        ui.label(tr!("fonts-keys"));
+                        Self::font_choice(
+                            ui,
+                            "keycap_font",
+                            &mut self.settings.keycap,
+                            &tr!("fonts-same-as-interface"),
+                            self.available,
+                        );
+                        ui.end_row();
+
+                        ui.label(tr!("fonts-monospace"));
+                        Self::font_choice(
+                            ui,
+                            "monospace_font",
+                            &mut self.settings.monospace,
+                            &tr!("fonts-built-in"),
+                            self.available,
+                        );
+                        ui.end_row();
+                    });
+
+                if ui.button(tr!("reset")).clicked() {
+                    *self.settings = FontSettings::default();
+                }
+            });
+    }
+}
+
+pub struct AudioSection<'a> {
+    pub player: &'a mut MusicPlayer,
+}
+impl<'a> super::View for AudioSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("audio-section"))
+            .id_source("audio_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                if !self.player.enabled() {
+                    ui.label(tr!("audio-no-music"));
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("audio-volume"));
+                    let mut volume = self.player.volume();
+                    if ui
+                        .add_enabled(
+                            !self.player.muted(),
+                            egui::Slider::new(&mut volume, 0.0..=1.0),
+                        )
+                        .changed()
+                    {
+                        self.player.set_volume(volume);
+                    }
+                });
+
+                let mut muted = self.player.muted();
+                if ui.checkbox(&mut muted, tr!("audio-mute")).changed() {
+                    self.player.set_muted(muted);
+                }
+
+                ui.horizontal(|ui| {
+                    let paused = self.player.paused();
+                    let label = if paused {
+                        tr!("audio-play")
+                    } else {
+                        tr!("audio-pause")
+                    };
+                    if ui.button(label).clicked() {
+                        self.player.set_paused(!paused);
+                    }
+                    if ui.button(tr!("audio-skip")).clicked() {
+                        self.player.skip();
+                    }
+                });
+
+                let mut shuffled = self.player.shuffled();
+                if ui.checkbox(&mut shuffled, tr!("audio-shuffle")).changed() {
+                    self.player.set_shuffled(shuffled);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("audio-repeat"));
+                    for repeat in RepeatMode::ALL {
+                        if ui
+                            .selectable_label(self.player.repeat() == repeat, repeat.name())
+                            .clicked()
+                        {
+                            self.player.set_repeat(repeat);
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("audio-crossfade"));
+                    let mut crossfade = self.player.crossfade();
+                    if ui
+                        .add(egui::Slider::new(&mut crossfade, 0.0..=MAX_CROSSFADE).suffix(" s"))
+                        .on_hover_text(tr!("audio-crossfade-hover"))
+                        .changed()
+                    {
+                        self.player.set_crossfade(crossfade);
+                    }
+                });
+
+                let status = match self.player.current_track() {
+                    Some(track) if self.player.paused() => tr!("audio-paused", track = track),
+                    Some(track) => tr!("audio-playing", track = track),
+                    None => tr!("audio-playlist-ended"),
+                };
+                ui.label(status).on_hover_text(tr!(
+                    "audio-user-music-hover",
+                    folder = USER_MUSIC_DIR.display().to_string()
+                ));
+            });
+    }
+}
+
+pub struct AutosaveSection<'a> {
+    pub enabled: &'a mut bool,
+    /// Minutes between autosaves
+    pub interval: &'a mut f32,
+    pub clock: &'a mut AutosaveClock,
+    pub kept: &'a mut usize,
+}
+impl<'a> super::View for AutosaveSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("autosave-section"))
+            .id_source("autosave_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.checkbox(self.enabled, tr!("autosave-enabled"))
+                    .on_hover_text(tr!(
+                        "autosave-enabled-hover",
+                        folder = AUTOSAVE_DIR.display().to_string()
+                    ));
+
+                ui.add_enabled_ui(*self.enabled, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("autosave-interval"));
+                        ui.add(
+                            egui::Slider::new(self.interval, 1.0..=120.0)
+                                .logarithmic(true)
+                                .suffix(tr!("autosave-minutes")),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("autosave-clock"));
+                        for clock in AutosaveClock::ALL {
+                            ui.selectable_value(self.clock, clock, clock.name());
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("autosave-kept"));
+                        ui.add(
+                            egui::DragValue::new(self.kept)
+                                .clamp_range(1..=100)
+                                .suffix(tr!("autosave-saves")),
+                        )
+                        .on_hover_text(tr!("autosave-kept-hover"));
+                    });
+                });
+            });
+    }
+}
+
+pub struct DiagnosticsSection<'a> {
+    pub memory_limit: &'a mut usize,
+    pub prune_report: &'a PruneReport,
+}
+impl<'a> super::View for DiagnosticsSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        const MEBIBYTE: f64 = 1024.0 * 1024.0;
+
+        egui::CollapsingHeader::new(tr!("diagnostics-section"))
+            .id_source("diagnostics_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("diagnostics-memory"));
+                    ui.label(tr!(
+                        "diagnostics-memory-used",
+                        used = format!("{:.1}", self.prune_report.used as f64 / MEBIBYTE)
+                    ));
+
+                    let mut limit = *self.memory_limit as f64 / MEBIBYTE;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut limit)
+                                .clamp_range(1.0..=f64::INFINITY)
+                                .suffix(" MiB"),
+                        )
+                        .on_hover_text(tr!("diagnostics-memory-hover"))
+                        .changed()
+                    {
+                        *self.memory_limit = (limit * MEBIBYTE) as usize;
+                    }
+                });
+
+                if let Some(last_prune) = self.prune_report.last_prune {
+                    ui.label(tr!(
+                        "diagnostics-pruned",
+                        points = self.prune_report.trail_points_pruned,
+                        prunes = self.prune_report.prunes,
+                        last = format_duration(last_prune),
+                    ));
+                } else {
+                    ui.label(tr!("diagnostics-nothing-pruned"));
+                }
+            });
+    }
+}
+
+/// Chooses the language of the UI, which is changed straight away
+pub struct LanguageSection;
+impl super::View for LanguageSection {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("language-section"))
+            .id_source("language_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                let current = locale::language();
+                for language in Language::ALL {
+                    if ui
+                        .selectable_label(current == language, language.name())
+                        .clicked()
+                    {
+                        locale::set_language(language);
+                    }
+                }
+            });
+    }
+}
+
+/// The order the bodies are listed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodySort {
+    Name,
+    Mass,
+    /// Distance from the Sun
+    Distance,
+}
+impl BodySort {
+    pub const ALL: [Self; 3] = [Self::Name, Self::Mass, Self::Distance];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Name => tr!("bodies-sort-name"),
+            Self::Mass => tr!("bodies-sort-mass"),
+            Self::Distance => tr!("bodies-sort-distance"),
+        }
+    }
+}
+
+/// How the bodies are searched and listed, kept while the section is closed
+pub struct BodyFilter {
+    pub search: String,
+    pub sort: BodySort,
+    /// List the stars, planets and moons separately
+    pub grouped: bool,
+}
+impl Default for BodyFilter {
+    fn default() -> Self {
+        Self {
+            search: String::new(),
+            sort: BodySort::Name,
+            grouped: true,
+        }
+    }
+}
+
+/// A body listed in the Bodies section
+pub struct BodyEntry<'a> {
+    pub id: Identifier,
+    pub body_type: BodyType,
+    pub mass: f64,
+    /// Distance from the Sun (in m)
+    pub distance: f64,
+    pub window_shown: &'a mut bool,
+}
+
+pub struct BodiesSection<'a> {
+    pub bodies: Vec<BodyEntry<'a>>,
+    pub filter: &'a mut BodyFilter,
+    /// Set to the body chosen to fly the camera to
+    pub fly_to: &'a mut Option<Identifier>,
+}
+impl<'a> BodiesSection<'a> {
+    fn body_ui(
+        ui: &mut egui::Ui,
+        body: &mut BodyEntry,
+        sort: BodySort,
+        fly_to: &mut Option<Identifier>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button(locale::body_name(&body.id)).clicked() {
+                *body.window_shown = !*body.window_shown;
+            }
+            if ui
+                .small_button(tr!("bodies-go-to"))
+                .on_hover_text(tr!("bodies-go-to-hover"))
+                .clicked()
+            {
+                *fly_to = Some(body.id.clone());
+            }
+
+            match sort {
+                BodySort::Name => {}
+                BodySort::Mass => {
+                    ui.weak(format!("{:.3e} kg", body.mass));
+                }
+                BodySort::Distance => {
+                    ui.weak(format!("{:.3} AU", body.distance / AU));
+                }
+            }
+        });
+    }
+}
+impl<'a> super::View for BodiesSection<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("bodies-section"))
+            .id_source("bodies_section")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.filter.search)
+                        .hint_text(tr!("bodies-search")),
+                );
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("bodies-sort"));
+                    egui::ComboBox::from_id_source("bodies_sort")
+                        .selected_text(self.filter.sort.name())
+                        .show_ui(ui, |ui| {
+                            for sort in BodySort::ALL {
+                                ui.selectable_value(&mut self.filter.sort, sort, sort.name());
+                            }
+                        });
+                    ui.checkbox(&mut self.filter.grouped, tr!("bodies-grouped"));
+                });
+
+                // Only the bodies matching the search, best matches first
+                let mut bodies = self
+                    .bodies
+                    .iter_mut()
+                    .filter_map(|body| {
+                        let name = locale::body_name(&body.id);
+                        let score = fuzzy_match(&self.filter.search, &name)
+                            .max(fuzzy_match(&self.filter.search, body.id.get_name()))?;
+                        Some((score, name, body))
+                    })
+                    .collect::<Vec<_>>();
+                let sort = self.filter.sort;
+                bodies.sort_by(|(a_score, a_name, a), (b_score, b_name, b)| {
+                    b_score.cmp(a_score).then_with(|| match sort {
+                        BodySort::Name => a_name.cmp(b_name),
+                        BodySort::Mass => b.mass.total_cmp(&a.mass),
+                        BodySort::Distance => a.distance.total_cmp(&b.distance),
+                    })
+                });
+
+                if bodies.is_empty() {
+                    ui.weak(tr!("bodies-none-found"));
+                } else if self.filter.grouped {
+                    for (body_type, title) in [
+                        (BodyType::Star, tr!("group-stars")),
+                        (BodyType::Planet, tr!("group-planets")),
+                        (BodyType::Moon, tr!("group-moons")),
+                    ] {
+                        let count = bodies
+                            .iter()
+                            .filter(|(_, _, body)| body.body_type == body_type)
+                            .count();
+                        if count == 0 {
+                            continue;
+                        }
+
+                        egui::CollapsingHeader::new(format!("{} ({})", title, count))
+                            .id_source(("bodies_group", body_type as u32))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for (_, _, body) in bodies
+                                    .iter_mut()
+                                    .filter(|(_, _, body)| body.body_type == body_type)
+                                {
+                                    Self::body_ui(ui, body, sort, self.fly_to);
+                                }
+                            });
+                    }
+                } else {
+                    for (_, _, body) in bodies.iter_mut() {
+                        Self::body_ui(ui, body, sort, self.fly_to);
+                    }
+                }
             });
     }
 }
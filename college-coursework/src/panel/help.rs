@@ -1,33 +1,45 @@
-pub struct HelpWindow;
-impl Default for HelpWindow {
-    fn default() -> Self {
-        Self {}
-    }
+use crate::{locale::tr, renderer::fonts::KEYCAP_FAMILY};
+
+use super::Tutorial;
+
+pub struct HelpWindow<'a> {
+    pub tutorial: &'a mut Tutorial,
 }
-impl super::Window for HelpWindow {
-    fn name(&self) -> &'static str {
-        "Help"
+impl<'a> super::Window for HelpWindow<'a> {
+    fn name(&self) -> String {
+        tr!("help-title")
     }
 
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
         use super::View as _;
         egui::Window::new(self.name())
+            .id(egui::Id::new("help_window"))
             .collapsible(false)
             .resizable(true)
             .open(open)
             .show(ctx, |ui| self.ui(ui));
     }
 }
-impl super::View for HelpWindow {
+impl<'a> super::View for HelpWindow<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
-            ui.heading("Keyboard Controls");
+            if ui
+                .button(tr!("help-start-tutorial"))
+                .on_hover_text(tr!("help-start-tutorial-hover"))
+                .clicked()
+            {
+                self.tutorial.start();
+            }
+        });
+
+        ui.vertical_centered(|ui| {
+            ui.heading(tr!("help-keyboard-controls"));
         });
 
         ui.separator();
 
         let keycap_font: egui::FontId =
-            egui::FontId::new(20.0, egui::FontFamily::Name("keycap".into()));
+            egui::FontId::new(20.0, egui::FontFamily::Name(KEYCAP_FAMILY.into()));
 
         ui.horizontal_wrapped(|ui| {
             ui.horizontal(|ui| {
@@ -42,7 +54,7 @@ impl super::View for HelpWindow {
                 ui.label(":")
             });
 
-            ui.label("Move the camera forward/left/right/back");
+            ui.label(tr!("help-move"));
         });
 
         ui.horizontal_wrapped(|ui| {
@@ -54,7 +66,7 @@ impl super::View for HelpWindow {
                 ui.label(":")
             });
 
-            ui.label("Move the camera up/down");
+            ui.label(tr!("help-move-vertically"));
         });
 
         ui.horizontal_wrapped(|ui| {
@@ -66,7 +78,72 @@ impl super::View for HelpWindow {
                 ui.label(":")
             });
 
-            ui.label("Roll the camera left/right");
+            ui.label(tr!("help-roll"));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
+                ui.label(egui::RichText::new("F3").strong());
+                ui.label(":")
+            });
+
+            ui.label(tr!("help-statistics"));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
+                ui.label(egui::RichText::new("F5").strong());
+                ui.label("/");
+                ui.label(egui::RichText::new("F9").strong());
+                ui.label(":")
+            });
+
+            ui.label(tr!("help-quick-save"));
         });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
+                ui.label(egui::RichText::new("F10").strong());
+                ui.label(":")
+            });
+
+            ui.label(tr!("help-capture"));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
+                ui.label(egui::RichText::new(tr!("help-double-click")).strong());
+                ui.label(":")
+            });
+
+            ui.label(tr!("help-follow"));
+        });
+
+        ui.vertical_centered(|ui| {
+            ui.heading(tr!("help-gamepad-controls"));
+        });
+
+        ui.separator();
+
+        for (control, action) in [
+            (tr!("help-left-stick"), tr!("help-move")),
+            (tr!("help-right-stick"), tr!("help-turn")),
+            (tr!("help-bumpers"), tr!("help-roll")),
+            (tr!("help-triggers"), tr!("help-change-speed")),
+        ] {
+            ui.horizontal_wrapped(|ui| {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
+                    ui.label(egui::RichText::new(control).strong());
+                    ui.label(":")
+                });
+
+                ui.label(action);
+            });
+        }
     }
 }
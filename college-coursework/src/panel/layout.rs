@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale::tr;
+
+use super::session::{Layouts, Session};
+
+/// Where the settings window is shown, either as a window or filling one
+/// side of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dock {
+    Floating,
+    Left,
+    Right,
+}
+impl Default for Dock {
+    fn default() -> Self {
+        Self::Floating
+    }
+}
+impl Dock {
+    pub const ALL: [Self; 3] = [Self::Floating, Self::Left, Self::Right];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Floating => tr!("dock-floating"),
+            Self::Left => tr!("dock-left"),
+            Self::Right => tr!("dock-right"),
+        }
+    }
+}
+
+/// What the user asked for from the layouts window, which needs the rest of
+/// the UI to carry out
+pub enum LayoutRequest {
+    /// Save the current layout under a name, replacing any with that name
+    Save(String),
+    Apply(Session),
+}
+
+/// Saves the arrangement of windows under names to be switched back to
+pub struct LayoutManager {
+    layouts: Layouts,
+    /// The name the next layout will be saved as
+    name: String,
+}
+impl Default for LayoutManager {
+    fn default() -> Self {
+        Self {
+            layouts: Layouts::load(),
+            name: String::new(),
+        }
+    }
+}
+impl LayoutManager {
+    pub fn insert(&mut self, name: String, layout: Session) {
+        self.layouts.0.insert(name, layout);
+        self.layouts.save();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<LayoutRequest> {
+        //! Show the window listing the saved layouts
+
+        let mut request = None;
+        let mut deleted = None;
+
+        egui::Window::new(tr!("layouts-title"))
+            .id(egui::Id::new("layout_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.name)
+                            .hint_text(tr!("layouts-name"))
+                            .desired_width(150.0),
+                    );
+
+                    let name = self.name.trim();
+                    if ui
+                        .add_enabled(!name.is_empty(), egui::Button::new(tr!("layouts-save")))
+                        .on_hover_text(tr!("layouts-save-hover"))
+                        .clicked()
+                    {
+                        request = Some(LayoutRequest::Save(name.to_string()));
+                        self.name.clear();
+                    }
+                });
+
+                if self.layouts.0.is_empty() {
+                    ui.weak(tr!("layouts-none"));
+                    return;
+                }
+
+                ui.separator();
+                egui::Grid::new("layouts").striped(true).show(ui, |ui| {
+                    for (name, layout) in self.layouts.0.iter() {
+                        ui.label(name);
+                        if ui.button(tr!("layouts-apply")).clicked() {
+                            request = Some(LayoutRequest::Apply(layout.clone()));
+                        }
+                        if ui.button(tr!("layouts-delete")).clicked() {
+                            deleted = Some(name.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(name) = deleted {
+            self.layouts.0.remove(&name);
+            self.layouts.save();
+        }
+
+        request
+    }
+}
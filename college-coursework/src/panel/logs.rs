@@ -0,0 +1,141 @@
+use log::{Level, LevelFilter};
+
+use crate::{
+    locale::tr,
+    log::{LogEntry, SESSION_LOG},
+};
+
+/// Which of the session's records are shown, kept while the window is closed
+pub struct LogFilter {
+    pub level: LevelFilter,
+    /// Only records containing this are shown, ignoring case
+    pub search: String,
+}
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Info,
+            search: String::new(),
+        }
+    }
+}
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        let search = self.search.to_lowercase();
+
+        entry.level <= self.level
+            && (search.is_empty()
+                || entry.message.to_lowercase().contains(&search)
+                || entry.target.to_lowercase().contains(&search))
+    }
+}
+
+fn level_colour(ui: &egui::Ui, level: Level) -> egui::Color32 {
+    match level {
+        Level::Error => egui::Color32::RED,
+        Level::Warn => egui::Color32::YELLOW,
+        Level::Info => ui.visuals().text_color(),
+        Level::Debug | Level::Trace => ui.visuals().weak_text_color(),
+    }
+}
+
+pub struct LogWindow<'a> {
+    pub filter: &'a mut LogFilter,
+}
+impl<'a> super::Window for LogWindow<'a> {
+    fn name(&self) -> String {
+        tr!("logs-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("log_window"))
+            .collapsible(true)
+            .resizable(true)
+            .default_width(600.0)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl<'a> super::View for LogWindow<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        // Copied so the log isn't locked while drawing, as anything logged
+        // meanwhile would deadlock
+        let session_log = match SESSION_LOG.lock() {
+            Ok(session_log) => session_log.clone(),
+            Err(_) => return,
+        };
+        let entries = session_log
+            .iter()
+            .filter(|entry| self.filter.matches(entry))
+            .collect::<Vec<_>>();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("log_level")
+                .selected_text(self.filter.level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LevelFilter::iter().skip(1) {
+                        ui.selectable_value(&mut self.filter.level, level, level.as_str());
+                    }
+                });
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter.search)
+                    .hint_text(tr!("logs-search"))
+                    .desired_width(200.0),
+            );
+
+            if ui
+                .button(tr!("logs-copy-all"))
+                .on_hover_text(tr!("logs-copy-all-hover"))
+                .clicked()
+            {
+                ui.output().copied_text = entries
+                    .iter()
+                    .map(|entry| entry.line())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+
+            ui.label(tr!(
+                "logs-shown",
+                shown = entries.len(),
+                total = session_log.len()
+            ));
+        });
+
+        ui.separator();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .max_height(300.0)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for entry in &entries[rows] {
+                    let text = egui::RichText::new(format!(
+                        "{} {:<5} {}: {}",
+                        entry.time.format("%H:%M:%S"),
+                        entry.level,
+                        entry.target,
+                        entry.message
+                    ))
+                    .monospace()
+                    .color(level_colour(ui, entry.level));
+
+                    if ui
+                        .add(
+                            egui::Label::new(text)
+                                .wrap(false)
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(tr!("logs-copy-hover"))
+                        .clicked()
+                    {
+                        ui.output().copied_text = entry.line();
+                    }
+                }
+            });
+    }
+}
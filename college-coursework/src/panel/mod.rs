@@ -383,28 +383,86 @@ impl Ui {
     }
 }*/
 
+mod compare;
+mod conjunctions;
+mod console;
+mod dial;
+mod experiments;
+mod facts;
 mod formatters;
 mod global;
 mod help;
+mod layout;
+mod logs;
+mod path;
+mod periods;
 mod planet;
+mod render;
+mod render_stats;
+mod sandbox;
+mod session;
+mod stats;
+mod toolbar;
+mod tutorial;
+mod units;
+pub mod validation;
 mod vector_ui;
 
+use std::path::PathBuf;
+
 use cgmath::Point3;
 pub use formatters::*;
 pub use global::GlobalWindow;
 pub use planet::PlanetWindowShown;
+use tokio::runtime::Handle;
+pub use tutorial::{Tutorial, TutorialAction};
+pub use units::UnitSystem;
 pub use vector_ui::*;
 
-use crate::simulation::{Identifier, SaveHandler, SimulationState, SUN};
+use crate::{
+    accessibility::{AccessibilitySettings, Announcements, ColourPalette},
+    locale::tr,
+    renderer::{
+        components,
+        fonts::{self, FontSettings},
+    },
+    scripting::Scripts,
+    simulation::{
+        available_scenarios, quick_slot_time, DroppedFile, Identifier, QuickSlotRequest,
+        SaveFormat, SaveHandler, SimulationState, TimeJump, QUICK_SLOTS, SUN,
+    },
+    stats::UsageStats,
+    toasts::Toasts,
+};
 
-use self::{help::HelpWindow, planet::PlanetWindow};
+use self::{
+    compare::BodyComparison,
+    conjunctions::ConjunctionFinder,
+    console::ConsoleWindow,
+    dial::LongitudeDialWindow,
+    experiments::ExperimentsWindow,
+    global::BodyFilter,
+    help::HelpWindow,
+    layout::{Dock, LayoutManager, LayoutRequest},
+    logs::{LogFilter, LogWindow},
+    path::CameraPathWindow,
+    periods::PeriodCalculator,
+    planet::PlanetWindow,
+    render::RenderSettingsWindow,
+    render_stats::RenderStatsWindow,
+    sandbox::SandboxWindow,
+    session::Session,
+    stats::StatsWindow,
+    toolbar::TimeToolbar,
+};
 
 pub trait View {
     fn ui(&mut self, ui: &mut egui::Ui);
 }
 
 pub trait Window {
-    fn name(&self) -> &str;
+    /// The title of the window, in the chosen language
+    fn name(&self) -> String;
 
     fn show(&mut self, ctx: &egui::Context, open: &mut bool);
 }
@@ -414,148 +472,891 @@ struct PlanetWindowInfo {
 }
 
 pub struct UiHandler {
+    applied_accessibility_settings: Option<AccessibilitySettings>,
+    applied_font_settings: Option<FontSettings>,
+    /// File names of the bundled fonts, found when the UI is created
+    available_fonts: Vec<String>,
+    session_restored: bool,
     help_window_shown: bool,
+    stats_window_shown: bool,
+    dial_window_shown: bool,
+    conjunction_window_shown: bool,
+    period_window_shown: bool,
+    compare_window_shown: bool,
+    experiments_window_shown: bool,
+    sandbox_window_shown: bool,
+    camera_path_window_shown: bool,
+    render_settings_window_shown: bool,
+    render_stats_window_shown: bool,
+    console_window_shown: bool,
+    /// The script being written in the console
+    script_input: String,
+    log_window_shown: bool,
+    log_filter: LogFilter,
+    layout_window_shown: bool,
+    layout_manager: LayoutManager,
+    /// Where the settings window is docked
+    dock: Dock,
+    body_filter: BodyFilter,
+    conjunction_finder: ConjunctionFinder,
+    period_calculator: PeriodCalculator,
+    body_comparison: BodyComparison,
+    sandbox_window: SandboxWindow,
+    time_toolbar: TimeToolbar,
+    new_window_shown: bool,
     save_window_shown: bool,
     load_window_shown: bool,
     save_handler: SaveHandler,
+    /// Paths of the scenarios that can be started, found when the UI is
+    /// created
+    scenarios: Vec<PathBuf>,
+    /// The quick save slot the hotkeys save to and load from, numbered from 1
+    quick_slot: usize,
 }
 impl Default for UiHandler {
     fn default() -> Self {
         Self {
+            applied_accessibility_settings: None,
+            applied_font_settings: None,
+            available_fonts: fonts::available_fonts(),
+            session_restored: false,
             help_window_shown: true,
+            stats_window_shown: false,
+            dial_window_shown: false,
+            conjunction_window_shown: false,
+            period_window_shown: false,
+            compare_window_shown: false,
+            experiments_window_shown: false,
+            sandbox_window_shown: false,
+            camera_path_window_shown: false,
+            render_settings_window_shown: false,
+            render_stats_window_shown: false,
+            console_window_shown: false,
+            script_input: String::new(),
+            log_window_shown: false,
+            log_filter: LogFilter::default(),
+            layout_window_shown: false,
+            layout_manager: LayoutManager::default(),
+            dock: Dock::default(),
+            body_filter: BodyFilter::default(),
+            conjunction_finder: ConjunctionFinder::default(),
+            period_calculator: PeriodCalculator::default(),
+            body_comparison: BodyComparison::default(),
+            sandbox_window: SandboxWindow::default(),
+            time_toolbar: TimeToolbar::default(),
+            new_window_shown: false,
             save_window_shown: false,
             load_window_shown: false,
             save_handler: SaveHandler::new(),
+            scenarios: available_scenarios(),
+            quick_slot: 1,
         }
     }
 }
 impl UiHandler {
+    pub fn new(safe_mode: bool) -> Self {
+        //! Create the UI, which starts with the default layout in safe mode
+        //! instead of restoring the last session
+
+        Self {
+            session_restored: safe_mode,
+            ..Self::default()
+        }
+    }
+
     pub fn show(&mut self, ctx: &egui::Context, ecs_world: &mut specs::World) {
         use crate::{
-            panel::global::{CameraControllerType, CameraSection, ConstantSection, TimeSection},
-            renderer::camera::{CameraPosition, CameraSpeed},
-            simulation::{GravitationalConstant, Mass, Position, TimeScale, Velocity},
+            audio::MusicPlayer,
+            panel::global::{
+                AccessibilitySection, AudioSection, AutosaveSection, BodiesSection, BodyEntry,
+                CameraSection, ConstantSection, DiagnosticsSection, DisplaySection, FontSection,
+                IntegratorSection, LanguageSection, TimeSection,
+            },
+            renderer::{
+                arrows::ArrowSettings,
+                atmosphere::Atmosphere,
+                attract::AttractSettings,
+                camera::{CameraControllerType, CameraPosition, CameraSpeed},
+                components::{BodyStyle, CameraCenter, PlanetColour, PlanetTexture},
+                cursor::CursorSettings,
+                field::FieldSettings,
+                follow::FlyTo,
+                gamepad::GamepadSettings,
+                grid::GridSettings,
+                hud::RenderStats,
+                labels::LabelSettings,
+                light::Light,
+                rings::Rings,
+                ruler::Ruler,
+                settings::{AdapterReport, RenderSettings},
+                trails::TrailStyle,
+            },
+            simulation::{
+                AutosaveSettings, BodyScale, BodyType, CloseEncounterSettings,
+                GravitationalConstant, InteractionHandler, Lineage, Mass, MassRate, MemoryBudget,
+                Position, PruneReport, Radius, SimulationTime, Stopwatch, TimeScale, Velocity,
+            },
         };
-        use cgmath::EuclideanSpace as _;
-        use specs::{Join as _, ReadStorage, Write, WriteStorage};
+        use cgmath::{EuclideanSpace as _, InnerSpace as _};
+        use specs::{Entities, Join as _, Read, ReadStorage, Write, WriteExpect, WriteStorage};
+
+        if !self.session_restored {
+            self.restore_session(ctx, ecs_world);
+        }
+        self.time_toolbar.show(ctx, ecs_world);
+
+        let mut body_edited = false;
+        let mut recoloured_bodies = Vec::new();
+        let mut time_scale_changed = false;
+        self.apply_accessibility_settings(ctx, ecs_world);
+        self.apply_font_settings(ctx, &ecs_world.read_resource());
 
         ecs_world.exec(
             |state: (
-                Write<CameraPosition>,
+                (
+                    Write<CameraPosition>,
+                    WriteExpect<CameraCenter>,
+                    Write<FlyTo>,
+                    Write<GamepadSettings>,
+                    Write<CameraControllerType>,
+                ),
                 Write<CameraSpeed>,
                 Write<GravitationalConstant>,
+                Write<CloseEncounterSettings>,
                 Write<TimeScale>,
-                ReadStorage<Identifier>,
+                Read<SimulationTime>,
+                Write<Stopwatch>,
+                Write<LabelSettings>,
+                Write<CursorSettings>,
+                Write<AttractSettings>,
+                (Write<BodyScale>, Write<UnitSystem>),
+                Write<TrailStyle>,
+                (Write<GridSettings>, Write<Ruler>, Write<ArrowSettings>),
+                Write<FieldSettings>,
+                (
+                    Write<AccessibilitySettings>,
+                    Write<FontSettings>,
+                    Write<MusicPlayer>,
+                ),
+                (Write<MemoryBudget>, Write<AutosaveSettings>),
+                Read<PruneReport>,
+                Write<UsageStats>,
+                (ReadStorage<Identifier>, ReadStorage<InteractionHandler>),
                 WriteStorage<PlanetWindowShown>,
                 WriteStorage<Position>,
                 WriteStorage<Velocity>,
-                WriteStorage<Mass>,
+                // Grouped to stay within the number of items a tuple of
+                // system data can have
+                (
+                    WriteStorage<Mass>,
+                    WriteStorage<MassRate>,
+                    ReadStorage<Radius>,
+                ),
+                ReadStorage<Lineage>,
+                (
+                    WriteStorage<Light>,
+                    WriteStorage<Atmosphere>,
+                    WriteStorage<Rings>,
+                ),
+                (
+                    Entities,
+                    WriteStorage<PlanetColour>,
+                    ReadStorage<PlanetTexture>,
+                    WriteStorage<BodyStyle>,
+                ),
             )| {
                 let (
-                    mut camera_position,
+                    (
+                        mut camera_position,
+                        mut camera_center,
+                        mut fly_to,
+                        mut gamepad_settings,
+                        mut camera_type,
+                    ),
                     mut camera_speed,
                     mut gravitational_constant,
+                    mut close_encounter_settings,
                     mut time_scale,
-                    planet_id,
+                    simulation_time,
+                    mut stopwatch,
+                    mut label_settings,
+                    mut cursor_settings,
+                    mut attract_settings,
+                    (mut body_scale, mut units),
+                    mut trail_style,
+                    (mut grid_settings, mut ruler, mut arrow_settings),
+                    mut field_settings,
+                    (mut accessibility_settings, mut font_settings, mut music_player),
+                    (mut memory_budget, mut autosave_settings),
+                    prune_report,
+                    mut usage_stats,
+                    (planet_id, planet_interaction),
                     mut planet_window_shown,
                     mut planet_position,
                     mut planet_velocity,
-                    mut planet_mass,
+                    (mut planet_mass, mut planet_mass_rate, planet_radius),
+                    planet_lineage,
+                    (mut planet_light, mut planet_atmosphere, mut planet_rings),
+                    (entities, mut planet_colour, planet_texture, mut planet_style),
                 ) = state;
 
                 let mut camera_position_vector = camera_position.0.to_vec();
                 let mut time_scale_raw = time_scale.total_time_elapsed;
-                // TODO: Move to ECS
-                let mut camera_type = CameraControllerType::Free;
                 let mut current_date_time = chrono::Local::now();
+                let stopwatch_was_running = stopwatch.is_running();
+                let mut fly_to_body = None;
+                let sun_position = (&planet_id, &planet_position)
+                    .join()
+                    .find(|(id, _)| id.get_id() == SUN.id)
+                    .map(|(_, position)| position.0);
 
                 GlobalWindow {
                     camera_section: CameraSection {
                         position: &mut camera_position_vector,
                         speed: &mut camera_speed.0,
                         controller_type: &mut camera_type,
+                        center: &mut camera_center,
+                        bodies: (&planet_id).join().cloned().collect(),
+                        fly_duration: &mut fly_to.duration,
+                        gamepad_enabled: &mut gamepad_settings.enabled,
+                        gamepad_dead_zone: &mut gamepad_settings.dead_zone,
+                        units: *units,
                     },
                     constant_section: ConstantSection {
                         gravitational_constant: &mut gravitational_constant.0,
                     },
+                    integrator_section: IntegratorSection {
+                        analytic_close_encounters: &mut close_encounter_settings.analytic,
+                        close_encounter_steps: &mut close_encounter_settings.min_steps,
+                    },
                     time_section: TimeSection {
                         time_scale: &mut time_scale_raw,
+                        paused: &mut time_scale.paused,
                         current_date_time: &mut current_date_time,
+                        simulation_time: simulation_time.0,
+                        stopwatch: &mut stopwatch,
+                        units: *units,
+                    },
+                    display_section: DisplaySection {
+                        show_labels: &mut label_settings.shown,
+                        cursor_style: &mut cursor_settings.style,
+                        cursor_size: &mut cursor_settings.size,
+                        cursor_colour: &mut cursor_settings.colour,
+                        reticle: &mut cursor_settings.reticle,
+                        attract_mode: &mut attract_settings.enabled,
+                        attract_timeout: &mut attract_settings.idle_timeout,
+                        scale_mode: &mut body_scale.mode,
+                        scale_multiplier: &mut body_scale.multiplier,
+                        trails_shown: &mut trail_style.shown,
+                        trail_colouring: &mut trail_style.colouring,
+                        trail_colourmap: &mut trail_style.colourmap,
+                        trail_width: &mut trail_style.width,
+                        grid_shown: &mut grid_settings.shown,
+                        grid_rings: &mut grid_settings.rings,
+                        axes_shown: &mut grid_settings.axes,
+                        ruler_active: &mut ruler.active,
+                        velocity_arrows: &mut arrow_settings.velocity,
+                        acceleration_arrows: &mut arrow_settings.acceleration,
+                        field_shown: &mut field_settings.shown,
+                        field_extent: &mut field_settings.extent,
+                        units: &mut units,
+                    },
+                    accessibility_section: AccessibilitySection {
+                        narration: &mut accessibility_settings.narration,
+                        high_contrast: &mut accessibility_settings.high_contrast,
+                        ui_scale: &mut accessibility_settings.ui_scale,
+                        palette: &mut accessibility_settings.palette,
+                    },
+                    font_section: FontSection {
+                        settings: &mut font_settings,
+                        available: &self.available_fonts,
+                    },
+                    audio_section: AudioSection {
+                        player: &mut music_player,
+                    },
+                    language_section: LanguageSection,
+                    autosave_section: AutosaveSection {
+                        enabled: &mut autosave_settings.enabled,
+                        interval: &mut autosave_settings.interval,
+                        clock: &mut autosave_settings.clock,
+                        kept: &mut autosave_settings.kept,
+                    },
+                    bodies_section: BodiesSection {
+                        bodies: (
+                            &planet_id,
+                            (&planet_interaction).maybe(),
+                            &planet_position,
+                            &planet_mass,
+                            &mut planet_window_shown,
+                        )
+                            .join()
+                            .map(|(id, interaction, position, mass, shown)| BodyEntry {
+                                id: id.clone(),
+                                body_type: interaction
+                                    .map_or(BodyType::Planet, |interaction| interaction.body_type),
+                                mass: mass.0,
+                                distance: sun_position
+                                    .map_or(0.0, |sun| (position.0 - sun).magnitude()),
+                                window_shown: &mut shown.0,
+                            })
+                            .collect(),
+                        filter: &mut self.body_filter,
+                        fly_to: &mut fly_to_body,
+                    },
+                    diagnostics_section: DiagnosticsSection {
+                        memory_limit: &mut memory_budget.limit,
+                        prune_report: &prune_report,
                     },
 
                     help_window_shown: &mut self.help_window_shown,
+                    stats_window_shown: &mut self.stats_window_shown,
+                    dial_window_shown: &mut self.dial_window_shown,
+                    conjunction_window_shown: &mut self.conjunction_window_shown,
+                    period_window_shown: &mut self.period_window_shown,
+                    compare_window_shown: &mut self.compare_window_shown,
+                    experiments_window_shown: &mut self.experiments_window_shown,
+                    sandbox_window_shown: &mut self.sandbox_window_shown,
+                    camera_path_window_shown: &mut self.camera_path_window_shown,
+                    render_settings_window_shown: &mut self.render_settings_window_shown,
+                    render_stats_window_shown: &mut self.render_stats_window_shown,
+                    console_window_shown: &mut self.console_window_shown,
+                    log_window_shown: &mut self.log_window_shown,
+                    layout_window_shown: &mut self.layout_window_shown,
+                    dock: &mut self.dock,
+                    new_window_shown: &mut self.new_window_shown,
                     save_window_shown: &mut self.save_window_shown,
                     load_window_shown: &mut self.load_window_shown,
-                    planet_windows_shown: (&planet_id, &mut planet_window_shown)
-                        .join()
-                        .map(|(id, shown)| (id.clone(), &mut shown.0))
-                        .collect(),
                 }
                 .show(ctx, &mut true);
 
                 camera_position.0 = Point3::from_vec(camera_position_vector);
-                *time_scale = TimeScale::from_max_time_per_iteration(time_scale_raw, 86400.0);
+                if let Some(body) = fly_to_body {
+                    fly_to.fly_to(body);
+                }
+
+                if stopwatch.is_running() && !stopwatch_was_running {
+                    usage_stats.use_feature("Stopwatch");
+                }
+                // Only choose the iterations again when the time scale is
+                // changed, so those loaded from a save are kept
+                if time_scale_raw != time_scale.total_time_elapsed {
+                    time_scale_changed = true;
+                    *time_scale = TimeScale {
+                        paused: time_scale.paused,
+                        ..TimeScale::from_max_time_per_iteration(time_scale_raw, 86400.0)
+                    };
+                }
 
                 (
+                    &entities,
                     &planet_id,
                     &mut planet_window_shown,
                     &mut planet_position,
                     &mut planet_velocity,
                     &mut planet_mass,
+                    (&mut planet_mass_rate).maybe(),
+                    (&planet_radius).maybe(),
+                    (&planet_lineage).maybe(),
+                    (&mut planet_light).maybe(),
+                    (&mut planet_atmosphere).maybe(),
+                    (&mut planet_rings).maybe(),
+                    &mut planet_colour,
+                    (&planet_texture).maybe(),
+                    (&mut planet_style).maybe(),
                 )
                     .join()
-                    .for_each(|(id, shown, position, velocity, mass)| {
-                        PlanetWindow {
-                            id: id.clone(),
-                            position: &mut position.0,
-                            velociy: &mut velocity.0,
-                            mass: &mut mass.0,
-                        }
-                        .show(ctx, &mut shown.0);
-                    });
+                    .for_each(
+                        |(
+                            entity,
+                            id,
+                            shown,
+                            position,
+                            velocity,
+                            mass,
+                            mass_rate,
+                            radius,
+                            lineage,
+                            light,
+                            atmosphere,
+                            rings,
+                            colour,
+                            texture,
+                            style,
+                        )| {
+                            let mut recoloured = false;
+                            PlanetWindow {
+                                id: id.clone(),
+                                position: &mut position.0,
+                                velociy: &mut velocity.0,
+                                mass: &mut mass.0,
+                                mass_rate: mass_rate.map(|mass_rate| &mut mass_rate.0),
+                                radius: radius.map(|radius| radius.0),
+                                gravitational_constant: gravitational_constant.0,
+                                lineage,
+                                light,
+                                atmosphere,
+                                rings,
+                                colour: &mut colour.0,
+                                textured: texture.is_some(),
+                                style,
+                                recoloured: &mut recoloured,
+                                fly_to: &mut fly_to,
+                                units: *units,
+                                edited: &mut body_edited,
+                            }
+                            .show(ctx, &mut shown.0);
+
+                            // Textured bodies are drawn the same whatever
+                            // their colour
+                            if recoloured && texture.is_none() {
+                                recoloured_bodies.push(entity);
+                            }
+                        },
+                    );
             },
         );
 
-        HelpWindow::default().show(ctx, &mut self.help_window_shown);
+        for body in recoloured_bodies {
+            components::rebuild_body_model(ecs_world, body);
+        }
+
+        {
+            let mut tutorial = ecs_world.write_resource::<Tutorial>();
+            if body_edited {
+                tutorial.record(TutorialAction::EditBody);
+            }
+            if time_scale_changed {
+                tutorial.record(TutorialAction::ChangeTimeScale);
+            }
+
+            HelpWindow {
+                tutorial: &mut tutorial,
+            }
+            .show(ctx, &mut self.help_window_shown);
+        }
+
+        StatsWindow {
+            stats: &ecs_world.read_resource::<UsageStats>(),
+        }
+        .show(ctx, &mut self.stats_window_shown);
+
+        if self.dial_window_shown {
+            LongitudeDialWindow::new(ecs_world).show(ctx, &mut self.dial_window_shown);
+        }
+
+        if self.conjunction_window_shown {
+            self.conjunction_finder
+                .show(ctx, ecs_world, &mut self.conjunction_window_shown);
+        }
+
+        if self.period_window_shown {
+            self.period_calculator
+                .show(ctx, ecs_world, &mut self.period_window_shown);
+        }
+
+        if self.compare_window_shown {
+            self.body_comparison
+                .show(ctx, ecs_world, &mut self.compare_window_shown);
+        }
+
+        if self.experiments_window_shown {
+            ExperimentsWindow::show(ctx, ecs_world, &mut self.experiments_window_shown);
+        }
+
+        if self.sandbox_window_shown {
+            self.sandbox_window
+                .show(ctx, ecs_world, &mut self.sandbox_window_shown);
+        }
+
+        if self.camera_path_window_shown {
+            CameraPathWindow::show(ctx, ecs_world, &mut self.camera_path_window_shown);
+        }
+
+        if self.render_settings_window_shown {
+            let (mut render_settings, adapter_report): (
+                Write<RenderSettings>,
+                Read<AdapterReport>,
+            ) = ecs_world.system_data();
+            RenderSettingsWindow {
+                settings: &mut render_settings,
+                adapter: &adapter_report,
+            }
+            .show(ctx, &mut self.render_settings_window_shown);
+        }
+
+        if self.render_stats_window_shown {
+            RenderStatsWindow {
+                stats: &ecs_world.read_resource::<RenderStats>(),
+            }
+            .show(ctx, &mut self.render_stats_window_shown);
+        }
+
+        if self.console_window_shown {
+            ConsoleWindow {
+                scripts: &mut ecs_world.write_resource::<Scripts>(),
+                input: &mut self.script_input,
+            }
+            .show(ctx, &mut self.console_window_shown);
+        }
+
+        if self.log_window_shown {
+            LogWindow {
+                filter: &mut self.log_filter,
+            }
+            .show(ctx, &mut self.log_window_shown);
+        }
+
+        if self.layout_window_shown {
+            let request = self.layout_manager.show(ctx, &mut self.layout_window_shown);
+            match request {
+                Some(LayoutRequest::Save(name)) => {
+                    let layout = self.current_session(ctx, ecs_world);
+                    self.layout_manager.insert(name, layout);
+                }
+                Some(LayoutRequest::Apply(layout)) => {
+                    self.apply_session(ctx, ecs_world, layout);
+                    // Kept open so another layout can be tried
+                    self.layout_window_shown = true;
+                }
+                None => (),
+            }
+        }
+
+        let runtime = ecs_world.read_resource::<Handle>().clone();
+
+        egui::Window::new(tr!("new-simulation-title"))
+            .id(egui::Id::new("new_simulation_window"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut self.new_window_shown)
+            .show(ctx, |ui| {
+                ui.label(tr!("new-simulation-scenarios"));
+                if self.scenarios.is_empty() {
+                    ui.weak(tr!("new-simulation-no-scenarios"));
+                }
+                for path in &self.scenarios {
+                    let name = path
+                        .file_stem()
+                        .map_or(String::new(), |name| name.to_string_lossy().to_string());
+                    if ui
+                        .button(name)
+                        .on_hover_text(path.display().to_string())
+                        .clicked()
+                    {
+                        self.save_handler.start_scenario(path.clone());
+                        ecs_world
+                            .write_resource::<UsageStats>()
+                            .use_feature("Start scenario");
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("new-simulation-open-file")).clicked() {
+                        self.save_handler.open_scenario(&runtime);
+                        ecs_world
+                            .write_resource::<UsageStats>()
+                            .use_feature("Start scenario");
+                    }
+                    if ui.button(tr!("new-simulation-refresh")).clicked() {
+                        self.scenarios = available_scenarios();
+                    }
+                });
+            });
 
-        egui::Window::new("Save Simulation")
+        egui::Window::new(tr!("save-simulation-title"))
+            .id(egui::Id::new("save_simulation_window"))
             .collapsible(false)
             .resizable(false)
             .open(&mut self.save_window_shown)
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
-                    if ui.button("Save as JSON").clicked() {
-                        SimulationState::serialize_from_world(ecs_world)
-                            .save_json()
-                            .unwrap()
+                    for format in SaveFormat::ALL {
+                        if ui.button(tr!("save-as", format = format.name())).clicked() {
+                            self.save_handler.save(
+                                &runtime,
+                                SimulationState::serialize_from_world(ecs_world),
+                                format,
+                            );
+
+                            ecs_world
+                                .write_resource::<Tutorial>()
+                                .record(TutorialAction::Save);
+                            let mut usage_stats = ecs_world.write_resource::<UsageStats>();
+                            usage_stats.saves_made += 1;
+                            usage_stats.use_feature(&format!("Save as {}", format.name()));
+                        }
                     }
+                });
 
-                    if ui.button("Save as TOML").clicked() {
-                        SimulationState::serialize_from_world(ecs_world)
-                            .save_toml()
-                            .unwrap()
+                ui.separator();
+                ui.label(tr!("quick-slots"))
+                    .on_hover_text(tr!("quick-slots-hover"));
+                egui::Grid::new("quick_slots").striped(true).show(ui, |ui| {
+                    for slot in 1..=QUICK_SLOTS {
+                        ui.radio_value(&mut self.quick_slot, slot, tr!("quick-slot", slot = slot));
+                        let time = quick_slot_time(slot);
+                        ui.label(time.map_or(tr!("quick-slot-empty"), |time| {
+                            time.format("%Y-%m-%d %H:%M:%S").to_string()
+                        }));
+
+                        let mut request = ecs_world.write_resource::<QuickSlotRequest>();
+                        if ui.button(tr!("quick-slot-save")).clicked() {
+                            self.quick_slot = slot;
+                            request.save = true;
+                        }
+                        if ui
+                            .add_enabled(time.is_some(), egui::Button::new(tr!("quick-slot-load")))
+                            .clicked()
+                        {
+                            self.quick_slot = slot;
+                            request.load = true;
+                        }
+                        ui.end_row();
                     }
                 });
             });
 
-        egui::Window::new("Load Simulation")
+        egui::Window::new(tr!("load-simulation-title"))
+            .id(egui::Id::new("load_simulation_window"))
             .collapsible(false)
             .resizable(false)
             .open(&mut self.load_window_shown)
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
-                    if ui.button("Load from JSON").clicked() {
-                        self.save_handler.load_json()
-                    }
-
-                    if ui.button("Load from TOML").clicked() {
-                        self.save_handler.load_toml()
+                    for format in SaveFormat::ALL {
+                        if ui
+                            .button(tr!("load-from", format = format.name()))
+                            .clicked()
+                        {
+                            self.save_handler.load(&runtime, format);
+                            ecs_world
+                                .write_resource::<UsageStats>()
+                                .use_feature(&format!("Load from {}", format.name()));
+                        }
                     }
                 });
             });
 
-        if let Ok(state) = self.save_handler.try_load_state() {
+        let dropped_file = ecs_world.read_resource::<DroppedFile>().0.clone();
+        if let Some(path) = dropped_file {
+            let mut confirmed = None;
+            egui::Window::new(tr!("dropped-file-title"))
+                .id(egui::Id::new("dropped_file_window"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    let file = path
+                        .file_name()
+                        .map_or(String::new(), |name| name.to_string_lossy().to_string());
+                    ui.label(tr!("dropped-file-confirm", file = file))
+                        .on_hover_text(path.display().to_string());
+
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("dropped-file-load")).clicked() {
+                            confirmed = Some(true);
+                        }
+                        if ui.button(tr!("dropped-file-cancel")).clicked() {
+                            confirmed = Some(false);
+                        }
+                    });
+                });
+
+            if let Some(confirmed) = confirmed {
+                ecs_world.write_resource::<DroppedFile>().0 = None;
+                if confirmed {
+                    self.save_handler.load_dropped(path);
+                    ecs_world
+                        .write_resource::<UsageStats>()
+                        .use_feature("Load dropped file");
+                }
+            }
+        }
+
+        let request = std::mem::take(&mut *ecs_world.write_resource::<QuickSlotRequest>());
+        if request.save {
+            self.save_handler.quick_save(
+                SimulationState::serialize_from_world(ecs_world),
+                self.quick_slot,
+            );
+
+            ecs_world
+                .write_resource::<Tutorial>()
+                .record(TutorialAction::Save);
+            let mut usage_stats = ecs_world.write_resource::<UsageStats>();
+            usage_stats.saves_made += 1;
+            usage_stats.use_feature("Quick save");
+        }
+        if request.load {
+            self.save_handler.quick_load(self.quick_slot);
+            ecs_world
+                .write_resource::<UsageStats>()
+                .use_feature("Quick load");
+        }
+
+        let loaded_state = self
+            .save_handler
+            .try_load_state(&ecs_world.read_resource::<Toasts>());
+        if let Some(state) = loaded_state {
             state.deserialize_to_world(ecs_world);
+            // The loaded simulation is at its own time, not one being jumped to
+            ecs_world.write_resource::<TimeJump>().cancel();
+
+            ecs_world
+                .write_resource::<Announcements>()
+                .push(tr!("simulation-loaded"));
+            ecs_world
+                .read_resource::<Toasts>()
+                .info(tr!("simulation-loaded"));
+        }
+        ecs_world.write_resource::<Tutorial>().show(ctx);
+        ecs_world.write_resource::<Toasts>().show(ctx);
+    }
+
+    fn restore_session(&mut self, ctx: &egui::Context, ecs_world: &mut specs::World) {
+        //! Reopen the windows and bodies that were open when the program was
+        //! last closed, putting the windows back where they were
+
+        self.session_restored = true;
+
+        match Session::load() {
+            Some(session) => self.apply_session(ctx, ecs_world, session),
+            // The first launch, so the tutorial is shown in place of the help
+            None => {
+                self.help_window_shown = false;
+                ecs_world.write_resource::<Tutorial>().start();
+            }
         }
     }
+
+    fn apply_session(
+        &mut self,
+        ctx: &egui::Context,
+        ecs_world: &mut specs::World,
+        session: Session,
+    ) {
+        //! Open the windows and bodies of a session or saved layout, closing
+        //! the others
+
+        use specs::{Join as _, ReadStorage, WriteStorage};
+
+        if let Some(memory) = session.memory {
+            *ctx.memory() = memory;
+        }
+
+        self.help_window_shown = session.help_window_shown;
+        self.stats_window_shown = session.stats_window_shown;
+        self.dial_window_shown = session.dial_window_shown;
+        self.conjunction_window_shown = session.conjunction_window_shown;
+        self.period_window_shown = session.period_window_shown;
+        self.compare_window_shown = session.compare_window_shown;
+        self.experiments_window_shown = session.experiments_window_shown;
+        self.sandbox_window_shown = session.sandbox_window_shown;
+        self.camera_path_window_shown = session.camera_path_window_shown;
+        self.render_settings_window_shown = session.render_settings_window_shown;
+        self.render_stats_window_shown = session.render_stats_window_shown;
+        self.console_window_shown = session.console_window_shown;
+        self.log_window_shown = session.log_window_shown;
+        self.layout_window_shown = session.layout_window_shown;
+        self.dock = session.dock;
+
+        ecs_world.exec(
+            |(ids, mut shown): (ReadStorage<Identifier>, WriteStorage<PlanetWindowShown>)| {
+                (&ids, &mut shown).join().for_each(|(id, shown)| {
+                    shown.0 = session
+                        .selected_bodies
+                        .iter()
+                        .any(|selected| selected == id.get_id())
+                });
+            },
+        );
+    }
+
+    pub fn save_session(&self, ctx: &egui::Context, ecs_world: &specs::World) {
+        //! Save the open windows and bodies to be restored on the next launch
+
+        self.current_session(ctx, ecs_world).save();
+    }
+
+    fn current_session(&self, ctx: &egui::Context, ecs_world: &specs::World) -> Session {
+        //! The windows and bodies open now, and where the windows are
+
+        use specs::{Join as _, ReadStorage};
+
+        let selected_bodies = ecs_world.exec(
+            |(ids, shown): (ReadStorage<Identifier>, ReadStorage<PlanetWindowShown>)| {
+                (&ids, &shown)
+                    .join()
+                    .filter(|(_, shown)| shown.0)
+                    .map(|(id, _)| id.get_id().to_string())
+                    .collect()
+            },
+        );
+
+        Session {
+            help_window_shown: self.help_window_shown,
+            stats_window_shown: self.stats_window_shown,
+            dial_window_shown: self.dial_window_shown,
+            conjunction_window_shown: self.conjunction_window_shown,
+            period_window_shown: self.period_window_shown,
+            compare_window_shown: self.compare_window_shown,
+            experiments_window_shown: self.experiments_window_shown,
+            sandbox_window_shown: self.sandbox_window_shown,
+            camera_path_window_shown: self.camera_path_window_shown,
+            render_settings_window_shown: self.render_settings_window_shown,
+            render_stats_window_shown: self.render_stats_window_shown,
+            console_window_shown: self.console_window_shown,
+            log_window_shown: self.log_window_shown,
+            layout_window_shown: self.layout_window_shown,
+            dock: self.dock,
+            selected_bodies,
+            memory: Some(ctx.memory().clone()),
+        }
+    }
+
+    fn apply_accessibility_settings(&mut self, ctx: &egui::Context, ecs_world: &specs::World) {
+        //! Restyle the UI and recolour the bodies when the accessibility
+        //! settings change, saving the new settings for the next session
+
+        let settings = *ecs_world.read_resource::<AccessibilitySettings>();
+        if self.applied_accessibility_settings == Some(settings) {
+            return;
+        }
+
+        ctx.set_style(settings.style());
+
+        // The models of the bodies are made in their natural colours
+        let applied_palette = self
+            .applied_accessibility_settings
+            .map_or(ColourPalette::Natural, |applied| applied.palette);
+        if settings.palette != applied_palette {
+            components::rebuild_body_models(ecs_world);
+        }
+
+        // Only save settings that were changed by the user, not the ones
+        // loaded at startup
+        if self.applied_accessibility_settings.is_some() {
+            settings.save();
+        }
+
+        self.applied_accessibility_settings = Some(settings);
+    }
+
+    fn apply_font_settings(&mut self, ctx: &egui::Context, settings: &FontSettings) {
+        //! Load the chosen fonts when the font settings change, saving the
+        //! new settings for the next session. The fonts loaded at startup
+        //! were already set when the renderer was created
+
+        if self.applied_font_settings.as_ref() == Some(settings) {
+            return;
+        }
+
+        if self.applied_font_settings.is_some() {
+            ctx.set_fonts(settings.definitions());
+            settings.save();
+        }
+
+        self.applied_font_settings = Some(settings.clone());
+    }
 }
@@ -0,0 +1,124 @@
+use specs::{World, WorldExt};
+
+use crate::{locale::tr, renderer::path::CameraPath};
+
+/// Editor for the path the camera flies along, made of keyframes placed
+/// where the camera is
+pub struct CameraPathWindow;
+impl CameraPathWindow {
+    pub fn show(ctx: &egui::Context, world: &mut World, open: &mut bool) {
+        //! Show the window to place, edit and play the keyframes
+
+        let mut path = world.write_resource::<CameraPath>();
+
+        egui::Window::new(tr!("camera-path-title"))
+            .id(egui::Id::new("camera_path_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let playable = path.keyframes.len() >= 2;
+                    if path.playing {
+                        if ui
+                            .button(format!("\u{23F9} {}", tr!("camera-path-stop")))
+                            .clicked()
+                        {
+                            path.playing = false;
+                        }
+                    } else if ui
+                        .add_enabled(
+                            playable,
+                            egui::Button::new(format!("\u{25B6} {}", tr!("camera-path-play"))),
+                        )
+                        .on_disabled_hover_text(tr!("camera-path-play-disabled-hover"))
+                        .clicked()
+                    {
+                        // Carry on from where it was stopped, unless it
+                        // reached the end
+                        if path.time >= path.duration() {
+                            path.time = 0.0;
+                        }
+                        path.playing = true;
+                    }
+
+                    ui.checkbox(&mut path.looped, tr!("camera-path-loop"));
+                });
+
+                let duration = path.duration();
+                ui.add_enabled(
+                    !path.keyframes.is_empty(),
+                    egui::ProgressBar::new(if duration > 0.0 {
+                        path.time / duration
+                    } else {
+                        0.0
+                    })
+                    .text(format!("{:.1} / {:.1} s", path.time, duration)),
+                );
+
+                ui.separator();
+
+                let mut removed = None;
+                let mut retimed = false;
+                egui::Grid::new("camera_path_keyframes")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for i in 0..path.keyframes.len() {
+                            retimed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut path.keyframes[i].time)
+                                        .clamp_range(0.0..=f32::MAX)
+                                        .speed(0.1)
+                                        .suffix(" s"),
+                                )
+                                .changed();
+
+                            let position = path.keyframes[i].position;
+                            ui.label(format!(
+                                "({:.1}, {:.1}, {:.1})",
+                                position.x, position.y, position.z
+                            ));
+
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .small_button("\u{1F441}")
+                                    .on_hover_text(tr!("camera-path-view-hover"))
+                                    .clicked()
+                                {
+                                    path.view_keyframe = Some(i);
+                                }
+                                if ui.small_button("\u{2716}").clicked() {
+                                    removed = Some(i);
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = removed {
+                    path.keyframes.remove(i);
+                }
+                if retimed {
+                    path.sort();
+                }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr!("camera-path-add-keyframe"))
+                        .on_hover_text(tr!("camera-path-add-keyframe-hover"))
+                        .clicked()
+                    {
+                        path.add_keyframe = true;
+                    }
+
+                    if ui.button(tr!("camera-path-clear")).clicked() {
+                        let looped = path.looped;
+                        *path = CameraPath {
+                            looped,
+                            ..Default::default()
+                        };
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,232 @@
+use specs::{Entities, Entity, Join, Read, ReadStorage, World};
+
+use crate::{
+    locale::{self, tr},
+    simulation::{
+        semi_major_axis, sidereal_period, synodic_period, BodyType, GravitationalConstant,
+        Identifier, InteractionHandler, Mass, OrbitTracker, Position, Satellite, Velocity,
+    },
+};
+
+use super::{dynamic_exponent_formatter, UnitSystem};
+
+/// The orbit of a chosen body, from its current state and as measured
+pub(super) struct OrbitSummary {
+    name: String,
+    orbited: Entity,
+    orbited_name: String,
+    /// (in metres)
+    semi_major_axis: f64,
+    /// Sidereal period from Kepler's third law (in seconds)
+    pub(super) predicted: Option<f64>,
+    /// Sidereal period of the last complete orbit (in seconds)
+    measured: Option<f64>,
+}
+
+/// Calculates the sidereal periods of two bodies and their synodic period,
+/// and compares them to the periods measured in the simulation
+#[derive(Default)]
+pub struct PeriodCalculator {
+    /// Ids of the chosen bodies
+    first: Option<String>,
+    second: Option<String>,
+}
+impl PeriodCalculator {
+    pub fn show(&mut self, ctx: &egui::Context, world: &World, open: &mut bool) {
+        //! Show the window to choose the bodies and their periods
+
+        let candidates = {
+            let (ids, handlers): (ReadStorage<Identifier>, ReadStorage<InteractionHandler>) =
+                world.system_data();
+
+            (&ids, &handlers)
+                .join()
+                .filter(|(_, handler)| !matches!(handler.body_type, BodyType::Star))
+                .map(|(id, _)| id.clone())
+                .collect::<Vec<_>>()
+        };
+        let name_of = |chosen: &Option<String>| {
+            candidates
+                .iter()
+                .find(|id| Some(id.get_id()) == chosen.as_deref())
+                .map_or(tr!("choose-a-body"), locale::body_name)
+        };
+
+        let first = self.first.as_deref().and_then(|id| summarise(world, id));
+        let second = self.second.as_deref().and_then(|id| summarise(world, id));
+        let units = *world.read_resource::<UnitSystem>();
+
+        egui::Window::new(tr!("periods-title"))
+            .id(egui::Id::new("periods_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| {
+                for (id_source, label, chosen) in [
+                    ("first_body", tr!("first-body"), &mut self.first),
+                    ("second_body", tr!("second-body"), &mut self.second),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        egui::ComboBox::from_id_source(("orbital_periods", id_source))
+                            .selected_text(name_of(chosen))
+                            .show_ui(ui, |ui| {
+                                for id in candidates.iter() {
+                                    ui.selectable_value(
+                                        chosen,
+                                        Some(id.get_id().to_string()),
+                                        locale::body_name(id),
+                                    );
+                                }
+                            });
+                    });
+                }
+
+                ui.separator();
+
+                let summaries = [&first, &second].into_iter().flatten().collect::<Vec<_>>();
+                if summaries.is_empty() {
+                    return;
+                }
+
+                let format_period = |period: Option<f64>| {
+                    period.map_or("-".to_string(), |period| units.format_duration(period))
+                };
+                let distance = units.distance();
+
+                egui::Grid::new("orbital_periods")
+                    .num_columns(summaries.len() + 1)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for summary in summaries.iter() {
+                            ui.strong(&summary.name);
+                        }
+                        ui.end_row();
+
+                        ui.label(tr!("periods-orbits"));
+                        for summary in summaries.iter() {
+                            ui.label(&summary.orbited_name);
+                        }
+                        ui.end_row();
+
+                        ui.label(tr!("periods-semi-major-axis"));
+                        for summary in summaries.iter() {
+                            ui.label(format!(
+                                "{}{}",
+                                dynamic_exponent_formatter()(
+                                    distance.from_si(summary.semi_major_axis),
+                                    0..=4
+                                ),
+                                distance.suffix()
+                            ));
+                        }
+                        ui.end_row();
+
+                        ui.label(tr!("periods-sidereal"))
+                            .on_hover_text(tr!("periods-sidereal-hover"));
+                        for summary in summaries.iter() {
+                            ui.label(format_period(summary.predicted));
+                        }
+                        ui.end_row();
+
+                        ui.label(tr!("periods-measured"))
+                            .on_hover_text(tr!("periods-measured-hover"));
+                        for summary in summaries.iter() {
+                            ui.label(format_period(summary.measured));
+                        }
+                        ui.end_row();
+
+                        ui.label(tr!("periods-difference"));
+                        for summary in summaries.iter() {
+                            ui.label(match (summary.predicted, summary.measured) {
+                                (Some(predicted), Some(measured)) => {
+                                    format!("{:+.2}%", (measured - predicted) / predicted * 100.0)
+                                }
+                                _ => "-".to_string(),
+                            });
+                        }
+                        ui.end_row();
+                    });
+
+                if let (Some(first), Some(second)) = (&first, &second) {
+                    ui.separator();
+
+                    if first.orbited != second.orbited {
+                        ui.label(tr!("periods-no-synodic"));
+                        return;
+                    }
+
+                    let synodic = |a: Option<f64>, b: Option<f64>| {
+                        format_period(a.zip(b).and_then(|(a, b)| synodic_period(a, b)))
+                    };
+
+                    egui::Grid::new("synodic_period")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label(tr!("periods-synodic"))
+                                .on_hover_text(tr!("periods-synodic-hover"));
+                            ui.label(synodic(first.predicted, second.predicted));
+                            ui.end_row();
+
+                            ui.label(tr!("periods-synodic-measured"));
+                            ui.label(synodic(first.measured, second.measured));
+                            ui.end_row();
+                        });
+                }
+            });
+    }
+}
+
+pub(super) fn summarise(world: &World, id: &str) -> Option<OrbitSummary> {
+    //! Find the orbit of a body around its parent, or the most massive body
+    //! if it isn't a satellite
+
+    let (entities, ids, positions, velocities, masses, satellites, trackers, constant): (
+        Entities,
+        ReadStorage<Identifier>,
+        ReadStorage<Position>,
+        ReadStorage<Velocity>,
+        ReadStorage<Mass>,
+        ReadStorage<Satellite>,
+        ReadStorage<OrbitTracker>,
+        Read<GravitationalConstant>,
+    ) = world.system_data();
+
+    let (entity, identifier) = (&entities, &ids)
+        .join()
+        .find(|(_, identifier)| identifier.get_id() == id)?;
+
+    let orbited = match satellites.get(entity) {
+        Some(satellite) => satellite.parent,
+        None => {
+            (&entities, &masses)
+                .join()
+                .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))?
+                .0
+        }
+    };
+    if orbited == entity {
+        return None;
+    }
+
+    let mu = constant.0 * (masses.get(entity)?.0 + masses.get(orbited)?.0);
+    let semi_major_axis = semi_major_axis(
+        positions.get(entity)?.0 - positions.get(orbited)?.0,
+        velocities.get(entity)?.0 - velocities.get(orbited)?.0,
+        mu,
+    );
+
+    Some(OrbitSummary {
+        name: locale::body_name(identifier),
+        orbited,
+        orbited_name: ids
+            .get(orbited)
+            .map_or(tr!("periods-unknown-body"), locale::body_name),
+        semi_major_axis,
+        predicted: sidereal_period(semi_major_axis, mu),
+        measured: trackers
+            .get(entity)
+            .and_then(|tracker| tracker.measured_period),
+    })
+}
@@ -1,9 +1,42 @@
 use cgmath::Vector3;
 use specs::{Component, VecStorage};
 
-use crate::simulation::Identifier;
+use crate::{
+    locale::{self, tr},
+    renderer::{
+        atmosphere::Atmosphere, components::BodyStyle, follow::FlyTo, light::Light, rings::Rings,
+    },
+    simulation::{Identifier, Lineage},
+};
 
-use super::{dynamic_exponent_formatter, global::MINUS_ONE_EXPONENT, Vector3Value};
+use super::{
+    dynamic_exponent_formatter,
+    facts::{facts, BodyFacts, Material},
+    format_duration,
+    units::{UnitSystem, EARTH_MASS},
+    validation::{self, edit_validated, Validation},
+    Vector3Value,
+};
+
+/// (in kg)
+const JUPITER_MASS: f64 = 1.89813e27;
+/// (in kg)
+const SOLAR_MASS: f64 = 1.98847e30;
+
+/// The range of the mass slider, from the smallest moons to the largest
+/// stars (in kg)
+const MIN_SLIDER_MASS: f64 = 1e15;
+const MAX_SLIDER_MASS: f64 = 1e32;
+
+/// Acceleration due to gravity at the Earth's surface (in ms^-2)
+const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Which tab of a body's window is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlanetTab {
+    Properties,
+    Info,
+}
 
 #[derive(Component)]
 #[storage(VecStorage)]
@@ -19,20 +52,73 @@ pub struct PlanetWindow<'a> {
     pub position: &'a mut Vector3<f64>,
     pub velociy: &'a mut Vector3<f64>,
     pub mass: &'a mut f64,
+    pub mass_rate: Option<&'a mut f64>,
+    /// (in m)
+    pub radius: Option<f64>,
+    /// (in m^3 kg^-1 s^-2)
+    pub gravitational_constant: f64,
+    pub lineage: Option<&'a Lineage>,
+    pub light: Option<&'a mut Light>,
+    pub atmosphere: Option<&'a mut Atmosphere>,
+    pub rings: Option<&'a mut Rings>,
+    /// (in linear RGBA)
+    pub colour: &'a mut [f32; 4],
+    /// Whether the body is drawn with a texture rather than its colour
+    pub textured: bool,
+    pub style: Option<&'a mut BodyStyle>,
+    /// Set if the body's colour was changed, so its model is made again
+    pub recoloured: &'a mut bool,
+    pub fly_to: &'a mut FlyTo,
+    pub units: UnitSystem,
+    /// Set if any of the body's values were changed
+    pub edited: &'a mut bool,
 }
 impl<'a> PlanetWindow<'a> {
     pub fn get_id(&self) -> Identifier {
         self.id.clone()
     }
+
+    fn lineage_ui(ui: &mut egui::Ui, lineage: &Lineage, id_source: &str, units: UnitSystem) {
+        //! Show the bodies that merged to form a body, and recursively the
+        //! bodies that merged to form those
+        if let Some(merge) = &lineage.0 {
+            ui.label(tr!(
+                "planet-merged-at",
+                time = units.format_duration(merge.time)
+            ));
+
+            let mass = units.mass();
+            for ancestor in merge.bodies.iter() {
+                let id_source = format!("{}/{}", id_source, ancestor.id);
+                let text = format!(
+                    "{} ({}{})",
+                    ancestor.name,
+                    dynamic_exponent_formatter()(mass.from_si(ancestor.mass), 0..=2),
+                    mass.suffix()
+                );
+
+                if ancestor.lineage.0.is_some() {
+                    egui::CollapsingHeader::new(text)
+                        .id_source(&id_source)
+                        .show(ui, |ui| {
+                            Self::lineage_ui(ui, &ancestor.lineage, &id_source, units)
+                        });
+                } else {
+                    ui.label(text);
+                }
+            }
+        }
+    }
 }
 impl<'a> super::Window for PlanetWindow<'a> {
-    fn name(&self) -> &str {
-        self.id.get_name()
+    fn name(&self) -> String {
+        locale::body_name(&self.id)
     }
 
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
         use super::View as _;
         egui::Window::new(self.name())
+            .id(egui::Id::new("planet_window").with(self.id.get_id()))
             .collapsible(true)
             .resizable(true)
             .open(open)
@@ -41,40 +127,426 @@ impl<'a> super::Window for PlanetWindow<'a> {
 }
 impl<'a> super::View for PlanetWindow<'a> {
     fn ui(&mut self, ui: &mut egui::Ui) {
+        use super::Window as _;
+
         ui.horizontal(|ui| {
-            ui.label("Name:");
-            ui.label(self.id.get_name());
-            ui.label("ID:");
+            ui.label(tr!("planet-name"));
+            ui.label(self.name());
+            ui.label(tr!("planet-id"));
             ui.label(self.id.get_id());
+
+            if ui
+                .button(tr!("planet-fly-to"))
+                .on_hover_text(tr!("planet-fly-to-hover"))
+                .clicked()
+            {
+                self.fly_to.fly_to(self.id.clone());
+            }
         });
 
+        // Only the real bodies have an info tab
+        let facts = match facts(self.id.get_id()) {
+            Some(facts) => facts,
+            None => {
+                self.properties_ui(ui);
+                return;
+            }
+        };
+
+        let tab_id = ui.id().with("planet_tab");
+        let mut tab = ui
+            .data()
+            .get_temp::<PlanetTab>(tab_id)
+            .unwrap_or(PlanetTab::Properties);
+
         ui.horizontal(|ui| {
-            ui.label("Position:");
-            ui.add(
-                Vector3Value::new(self.position)
-                    .custom_formatter(dynamic_exponent_formatter())
-                    .suffix(" m")
-                    .speed(0.1),
-            );
+            ui.selectable_value(&mut tab, PlanetTab::Properties, tr!("planet-properties"));
+            ui.selectable_value(&mut tab, PlanetTab::Info, tr!("planet-info"));
         });
+        ui.data().insert_temp(tab_id, tab);
+        ui.separator();
+
+        match tab {
+            PlanetTab::Properties => self.properties_ui(ui),
+            PlanetTab::Info => self.info_ui(ui, facts),
+        }
+    }
+}
+impl<'a> PlanetWindow<'a> {
+    fn properties_ui(&mut self, ui: &mut egui::Ui) {
+        //! Show the body's simulated values, which can be edited
+        let before = (*self.position, *self.velociy, *self.mass);
+
+        let vectors: [(_, _, _, _, fn(Vector3<f64>) -> Validation); 2] = [
+            (
+                "position",
+                tr!("planet-position"),
+                &mut *self.position,
+                self.units.distance(),
+                validation::finite_vector,
+            ),
+            (
+                "velocity",
+                tr!("planet-velocity"),
+                &mut *self.velociy,
+                self.units.speed(),
+                validation::below_light_speed,
+            ),
+        ];
+        for (id_source, label, value, unit, validate) in vectors {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                edit_validated(ui, id_source, value, validate, |ui, value| {
+                    let shown = value.map(|x| unit.from_si(x));
+                    let mut edited = shown;
+
+                    let response = ui.add(
+                        Vector3Value::new(&mut edited)
+                            .custom_formatter(dynamic_exponent_formatter())
+                            .suffix(unit.suffix())
+                            .speed(0.1),
+                    );
+
+                    if edited != shown {
+                        *value = edited.map(|x| unit.to_si(x));
+                    }
+                    response
+                });
+            });
+        }
 
         ui.horizontal(|ui| {
-            ui.label("Velocity:");
-            ui.add(
-                Vector3Value::new(self.velociy)
-                    .custom_formatter(dynamic_exponent_formatter())
-                    .suffix(const_format::concatcp!(" ms", MINUS_ONE_EXPONENT))
-                    .speed(0.1),
-            )
+            let unit = self.units.mass();
+
+            ui.label(tr!("planet-mass"));
+            edit_validated(ui, "mass", self.mass, validation::positive, |ui, value| {
+                let mut mass = unit.from_si(*value);
+                let response = ui.add(
+                    egui::DragValue::new(&mut mass)
+                        .speed(0.1)
+                        .custom_formatter(dynamic_exponent_formatter())
+                        .suffix(unit.suffix()),
+                );
+
+                if response.changed() {
+                    *value = unit.to_si(mass);
+                }
+                response
+            });
         });
 
         ui.horizontal(|ui| {
-            ui.label("Mass:");
-            ui.add(
-                egui::DragValue::new(self.mass)
-                    .speed(0.1)
-                    .custom_formatter(dynamic_exponent_formatter()),
-            )
+            let unit = self.units.mass();
+            let mut mass = unit.from_si(*self.mass);
+
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut mass,
+                        unit.from_si(MIN_SLIDER_MASS)..=unit.from_si(MAX_SLIDER_MASS),
+                    )
+                    .logarithmic(true)
+                    .show_value(false),
+                )
+                .on_hover_text(tr!("planet-mass-slider-hover"))
+                .changed()
+            {
+                *self.mass = unit.to_si(mass);
+            }
+
+            for (name, preset) in [
+                (tr!("planet-earth-mass"), EARTH_MASS),
+                (tr!("planet-jupiter-mass"), JUPITER_MASS),
+                (tr!("planet-solar-mass"), SOLAR_MASS),
+            ] {
+                if ui.small_button(name).clicked() {
+                    *self.mass = preset;
+                }
+            }
         });
+
+        if let Some(radius) = self.radius.filter(|radius| *radius > 0.0) {
+            let gravity = self.gravitational_constant * *self.mass / radius.powi(2);
+            let escape_velocity = (2.0 * gravity * radius).sqrt();
+            let (acceleration, speed) = (self.units.acceleration(), self.units.speed());
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("planet-surface-gravity"));
+                ui.label(format!(
+                    "{}{} ({:.3} g)",
+                    dynamic_exponent_formatter()(acceleration.from_si(gravity), 0..=3),
+                    acceleration.suffix(),
+                    gravity / STANDARD_GRAVITY
+                ));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("planet-escape-velocity"));
+                ui.label(format!(
+                    "{}{}",
+                    dynamic_exponent_formatter()(speed.from_si(escape_velocity), 0..=3),
+                    speed.suffix()
+                ));
+            });
+        }
+
+        if let Some(mass_rate) = self.mass_rate.as_deref_mut() {
+            ui.horizontal(|ui| {
+                let unit = self.units.mass_rate();
+
+                ui.label(tr!("planet-mass-rate"));
+                edit_validated(
+                    ui,
+                    "mass_rate",
+                    mass_rate,
+                    validation::finite,
+                    |ui, value| {
+                        let mut rate = unit.from_si(*value);
+                        let response = ui.add(
+                            egui::DragValue::new(&mut rate)
+                                .speed(0.1)
+                                .custom_formatter(dynamic_exponent_formatter())
+                                .suffix(unit.suffix()),
+                        );
+
+                        if response.changed() {
+                            *value = unit.to_si(rate);
+                        }
+                        response
+                    },
+                )
+                .on_hover_text(tr!("planet-mass-rate-hover"));
+            });
+        }
+
+        if let Some(light) = self.light.as_mut() {
+            egui::CollapsingHeader::new(tr!("planet-light"))
+                .id_source("light")
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-colour"));
+                        ui.color_edit_button_rgb(&mut light.colour);
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-intensity"));
+                        edit_validated(
+                            ui,
+                            "intensity",
+                            &mut light.intensity,
+                            |intensity| validation::non_negative(intensity as f64),
+                            |ui, intensity| {
+                                ui.add(
+                                    egui::DragValue::new(intensity)
+                                        .clamp_range(0.0..=f32::INFINITY)
+                                        .speed(0.01),
+                                )
+                            },
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-luminosity"));
+                        edit_validated(
+                            ui,
+                            "luminosity",
+                            &mut light.luminosity,
+                            |luminosity| validation::non_negative(luminosity as f64),
+                            |ui, luminosity| {
+                                ui.add(
+                                    egui::DragValue::new(luminosity)
+                                        .clamp_range(0.0..=f32::INFINITY)
+                                        .speed(0.01)
+                                        .suffix(" L\u{2609}"),
+                                )
+                            },
+                        )
+                        .on_hover_text(tr!("planet-luminosity-hover"));
+                    });
+                });
+        }
+
+        if let Some(atmosphere) = self.atmosphere.as_mut() {
+            egui::CollapsingHeader::new(tr!("planet-atmosphere"))
+                .id_source("atmosphere")
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-colour"));
+                        ui.color_edit_button_rgb(&mut atmosphere.colour);
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-thickness"));
+                        ui.add(
+                            egui::Slider::new(&mut atmosphere.thickness, 0.0..=0.5)
+                                .custom_formatter(|n, _| format!("{:.0}%", n * 100.0)),
+                        )
+                        .on_hover_text(tr!("planet-thickness-hover"));
+                    });
+                });
+        }
+
+        if let Some(rings) = self.rings.as_mut() {
+            egui::CollapsingHeader::new(tr!("planet-rings"))
+                .id_source("rings")
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-colour"));
+                        ui.color_edit_button_rgb(&mut rings.colour);
+                    });
+
+                    // The rings can't start inside the body or end before they
+                    // start
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-rings-inner-radius"));
+                        ui.add(
+                            egui::DragValue::new(&mut rings.inner_radius)
+                                .clamp_range(1.0..=rings.outer_radius)
+                                .speed(0.01)
+                                .suffix(" R"),
+                        )
+                        .on_hover_text(tr!("planet-rings-radius-hover"));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-rings-outer-radius"));
+                        ui.add(
+                            egui::DragValue::new(&mut rings.outer_radius)
+                                .clamp_range(rings.inner_radius..=10.0)
+                                .speed(0.01)
+                                .suffix(" R"),
+                        )
+                        .on_hover_text(tr!("planet-rings-radius-hover"));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-rings-tilt"));
+                        ui.add(egui::Slider::new(&mut rings.tilt, 0.0..=180.0).suffix("\u{b0}"))
+                            .on_hover_text(tr!("planet-rings-tilt-hover"));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("planet-rings-opacity"));
+                        ui.add(
+                            egui::Slider::new(&mut rings.opacity, 0.0..=1.0)
+                                .custom_formatter(|n, _| format!("{:.0}%", n * 100.0)),
+                        )
+                        .on_hover_text(tr!("planet-rings-opacity-hover"));
+                    });
+                });
+        }
+
+        egui::CollapsingHeader::new(tr!("planet-appearance"))
+            .id_source("appearance")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let [r, g, b, a] = *self.colour;
+                    let mut colour = [r, g, b];
+
+                    ui.label(tr!("planet-colour"));
+                    let response = ui.color_edit_button_rgb(&mut colour);
+                    if self.textured {
+                        response.on_hover_text(tr!("planet-colour-textured-hover"));
+                    }
+
+                    if colour != [r, g, b] {
+                        *self.colour = [colour[0], colour[1], colour[2], a];
+                        *self.recoloured = true;
+                    }
+                });
+
+                if let Some(style) = self.style.as_mut() {
+                    ui.horizontal(|ui| {
+                        let mut custom = style.trail_colour.is_some();
+                        if ui
+                            .checkbox(&mut custom, tr!("planet-trail-colour"))
+                            .on_hover_text(tr!("planet-trail-colour-hover"))
+                            .changed()
+                        {
+                            let [r, g, b, _] = *self.colour;
+                            style.trail_colour = custom.then(|| [r, g, b]);
+                        }
+
+                        if let Some(trail_colour) = style.trail_colour.as_mut() {
+                            ui.color_edit_button_rgb(trail_colour);
+                        }
+                    });
+
+                    ui.checkbox(&mut style.label_shown, tr!("planet-label-shown"));
+                    ui.checkbox(&mut style.arrows_shown, tr!("planet-arrows-shown"))
+                        .on_hover_text(tr!("planet-arrows-shown-hover"));
+                }
+            });
+
+        if let Some(lineage) = self.lineage.filter(|lineage| lineage.0.is_some()) {
+            egui::CollapsingHeader::new(tr!("planet-lineage"))
+                .id_source("lineage")
+                .default_open(true)
+                .show(ui, |ui| {
+                    Self::lineage_ui(ui, lineage, self.id.get_id(), self.units)
+                });
+        }
+
+        if (*self.position, *self.velociy, *self.mass) != before {
+            *self.edited = true;
+        }
+    }
+    fn info_ui(&self, ui: &mut egui::Ui, facts: &BodyFacts) {
+        //! Show facts about the real body, which are unaffected by any
+        //! changes made to it in the simulation
+
+        let distance = self.units.distance();
+        let rotation = if facts.rotation_period < 0.0 {
+            tr!(
+                "facts-retrograde",
+                period = format_duration(-facts.rotation_period * 3600.0)
+            )
+        } else {
+            format_duration(facts.rotation_period * 3600.0)
+        };
+        let composition = facts
+            .composition
+            .iter()
+            .map(Material::name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let discovery = match &facts.discovery {
+            Some(discovery) => tr!(
+                "facts-discovered",
+                year = discovery.year.to_string(),
+                by = discovery.by.as_str()
+            ),
+            None => tr!("facts-antiquity"),
+        };
+
+        egui::Grid::new("facts")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("facts-radius"));
+                ui.label(format!(
+                    "{}{}",
+                    dynamic_exponent_formatter()(
+                        distance.from_si(facts.mean_radius * 1000.0),
+                        0..=3
+                    ),
+                    distance.suffix()
+                ));
+                ui.end_row();
+
+                ui.label(tr!("facts-day-length"));
+                ui.label(rotation);
+                ui.end_row();
+
+                ui.label(tr!("facts-composition"));
+                ui.label(composition);
+                ui.end_row();
+
+                ui.label(tr!("facts-discovery"));
+                ui.label(discovery);
+                ui.end_row();
+            });
     }
 }
@@ -0,0 +1,197 @@
+use crate::{
+    locale::tr,
+    renderer::{
+        post::Effect,
+        settings::{format_name, AdapterReport, Backend, PresentMode, RenderSettings},
+    },
+};
+
+pub struct RenderSettingsWindow<'a> {
+    pub settings: &'a mut RenderSettings,
+    pub adapter: &'a AdapterReport,
+}
+impl<'a> super::Window for RenderSettingsWindow<'a> {
+    fn name(&self) -> String {
+        tr!("render-settings-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("render_settings_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl<'a> super::View for RenderSettingsWindow<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("render_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr!("render-present-mode"));
+                ui.horizontal(|ui| {
+                    for present_mode in PresentMode::ALL {
+                        let selected = self.settings.present_mode == present_mode;
+                        if ui
+                            .add_enabled(
+                                self.adapter.supports(present_mode),
+                                egui::SelectableLabel::new(selected, present_mode.name()),
+                            )
+                            .on_disabled_hover_text(tr!("render-present-mode-unsupported"))
+                            .clicked()
+                        {
+                            self.settings.present_mode = present_mode;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label(tr!("render-backend"));
+                egui::ComboBox::from_id_source("render_backend")
+                    .selected_text(self.settings.backend.name())
+                    .show_ui(ui, |ui| {
+                        for backend in Backend::ALL {
+                            ui.selectable_value(
+                                &mut self.settings.backend,
+                                backend,
+                                backend.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("render-surface-format"));
+                egui::ComboBox::from_id_source("render_surface_format")
+                    .selected_text(
+                        self.settings
+                            .surface_format
+                            .clone()
+                            .unwrap_or_else(|| tr!("render-automatic")),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.settings.surface_format,
+                            None,
+                            tr!("render-automatic"),
+                        );
+                        for format in self.adapter.formats.iter() {
+                            let name = format_name(*format);
+                            ui.selectable_value(
+                                &mut self.settings.surface_format,
+                                Some(name.clone()),
+                                name,
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("render-reflections"));
+                ui.checkbox(&mut self.settings.reflections, "")
+                    .on_hover_text(tr!("render-reflections-hover"));
+                ui.end_row();
+
+                ui.label(tr!("render-stars"));
+                ui.checkbox(&mut self.settings.stars, "")
+                    .on_hover_text(tr!("render-stars-hover"));
+                ui.end_row();
+
+                ui.label(tr!("render-power-saving"));
+                ui.checkbox(&mut self.settings.power_saving, "")
+                    .on_hover_text(tr!("render-power-saving-hover"));
+                ui.end_row();
+            });
+
+        ui.label(
+            egui::RichText::new(tr!("render-next-launch"))
+                .small()
+                .weak(),
+        );
+
+        ui.separator();
+
+        ui.strong(tr!("render-post-processing"));
+        egui::Grid::new("render_post_processing")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for effect in Effect::ALL {
+                    ui.label(effect.name());
+                    ui.checkbox(self.settings.post.enabled_mut(effect), "")
+                        .on_hover_text(effect.description());
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        let info = &self.adapter.info;
+        egui::Grid::new("render_adapter")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("render-adapter"));
+                ui.label(&info.name);
+                ui.end_row();
+
+                ui.label(tr!("render-adapter-type"));
+                ui.label(format!("{:?}", info.device_type));
+                ui.end_row();
+
+                ui.label(tr!("render-backend"));
+                ui.label(format!("{:?}", info.backend));
+                ui.end_row();
+
+                ui.label(tr!("render-surface-format"));
+                ui.label(format_name(self.adapter.format));
+                ui.end_row();
+            });
+
+        let limits = &self.adapter.limits;
+        egui::CollapsingHeader::new(tr!("render-limits"))
+            .id_source("render_limits")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("render_limits")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (name, value) in [
+                            (
+                                tr!("render-limit-texture-size"),
+                                limits.max_texture_dimension_2d as u64,
+                            ),
+                            (
+                                tr!("render-limit-texture-layers"),
+                                limits.max_texture_array_layers as u64,
+                            ),
+                            (
+                                tr!("render-limit-bind-groups"),
+                                limits.max_bind_groups as u64,
+                            ),
+                            (
+                                tr!("render-limit-vertex-buffers"),
+                                limits.max_vertex_buffers as u64,
+                            ),
+                            (
+                                tr!("render-limit-vertex-attributes"),
+                                limits.max_vertex_attributes as u64,
+                            ),
+                            (
+                                tr!("render-limit-uniform-binding-size"),
+                                limits.max_uniform_buffer_binding_size as u64,
+                            ),
+                            (
+                                tr!("render-limit-storage-binding-size"),
+                                limits.max_storage_buffer_binding_size as u64,
+                            ),
+                            (tr!("render-limit-buffer-size"), limits.max_buffer_size),
+                        ] {
+                            ui.label(name);
+                            ui.label(value.to_string());
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
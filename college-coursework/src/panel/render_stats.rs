@@ -0,0 +1,109 @@
+use instant::Duration;
+
+use crate::{locale::tr, renderer::hud::RenderStats};
+
+pub struct RenderStatsWindow<'a> {
+    pub stats: &'a RenderStats,
+}
+impl<'a> super::Window for RenderStatsWindow<'a> {
+    fn name(&self) -> String {
+        tr!("render-stats-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("render_stats_window"))
+            .collapsible(true)
+            .resizable(false)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl<'a> super::View for RenderStatsWindow<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let stats = self.stats;
+        let milliseconds =
+            |duration: Duration| format!("{:.3} ms", duration.as_secs_f64() * 1000.0);
+
+        egui::Grid::new("render_stats_counts")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("render-stats-draw-calls"));
+                ui.monospace(stats.draw_calls.to_string());
+                ui.end_row();
+
+                ui.label(tr!("render-stats-triangles"));
+                ui.monospace(stats.triangles.to_string());
+                ui.end_row();
+
+                ui.label(tr!("render-stats-line-segments"));
+                ui.monospace(stats.line_segments.to_string());
+                ui.end_row();
+
+                ui.label(tr!("render-stats-culled"));
+                ui.monospace(stats.culled.to_string());
+                ui.end_row();
+            });
+
+        ui.separator();
+
+        if !stats.timed {
+            ui.weak(tr!("render-stats-untimed"));
+            return;
+        }
+        let gpu_time = match stats.gpu_time {
+            Some(gpu_time) => gpu_time,
+            None => {
+                ui.spinner();
+                return;
+            }
+        };
+
+        egui::Grid::new("render_stats_passes")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr!("render-stats-pass"));
+                ui.strong(tr!("render-stats-gpu-time"));
+                ui.label("");
+                ui.end_row();
+
+                for (pass, time) in stats.pass_times.iter() {
+                    // The parts of the opaque pass are indented under it
+                    if pass.is_subpass() {
+                        ui.label(format!("    {}", pass.name()));
+                    } else {
+                        ui.label(pass.name());
+                    }
+                    ui.monospace(milliseconds(*time));
+
+                    let fraction = if gpu_time.is_zero() {
+                        0.0
+                    } else {
+                        (time.as_secs_f64() / gpu_time.as_secs_f64()) as f32
+                    };
+                    ui.add(
+                        egui::ProgressBar::new(fraction)
+                            .desired_width(100.0)
+                            .text(format!("{:.0}%", fraction * 100.0)),
+                    );
+                    ui.end_row();
+                }
+
+                ui.strong(tr!("render-stats-frame"));
+                ui.monospace(milliseconds(gpu_time));
+                ui.label("");
+                ui.end_row();
+            });
+
+        if !stats.subpasses_timed {
+            ui.label(
+                egui::RichText::new(tr!("render-stats-no-subpasses"))
+                    .small()
+                    .weak(),
+            );
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use specs::{Entities, Join, Read, ReadStorage, World, WriteStorage};
+
+use crate::{
+    locale::{self, tr},
+    simulation::{
+        BranchRun, DivergenceSample, GravitationalConstant, Identifier, InteractionHandler, Mass,
+        Perturbation, Position, Satellite, SearchBody, Velocity,
+    },
+};
+
+use super::UnitSystem;
+
+/// (in seconds)
+const YEAR: f64 = 365.25 * 86400.0;
+
+/// Branches the simulation into a sandbox copy with some bodies removed or
+/// made heavier, then runs both to see how differently the bodies move
+pub struct SandboxWindow {
+    /// Changes made to the sandbox copy of each body, by id
+    perturbations: BTreeMap<String, Perturbation>,
+    /// Ids of the bodies whose divergence is plotted
+    tracked: BTreeSet<String>,
+    /// How long both branches are run for (in years)
+    duration: f64,
+    run: Option<BranchRun>,
+    /// Names of the bodies the samples were taken of, in the order of their
+    /// distances
+    plotted: Vec<String>,
+    samples: Vec<DivergenceSample>,
+}
+impl Default for SandboxWindow {
+    fn default() -> Self {
+        Self {
+            perturbations: BTreeMap::new(),
+            tracked: BTreeSet::new(),
+            duration: 100.0,
+            run: None,
+            plotted: Vec::new(),
+            samples: Vec::new(),
+        }
+    }
+}
+impl SandboxWindow {
+    pub fn show(&mut self, ctx: &egui::Context, world: &mut World, open: &mut bool) {
+        //! Show the window to perturb the sandbox and plot how far it drifts
+        //! from the simulation
+
+        self.poll_run();
+
+        // Satellites are moved with their parent in the branches, as with
+        // the conjunction search
+        let candidates = {
+            let (ids, handlers, satellites): (
+                ReadStorage<Identifier>,
+                ReadStorage<InteractionHandler>,
+                ReadStorage<Satellite>,
+            ) = world.system_data();
+
+            (&ids, &handlers, !&satellites)
+                .join()
+                .map(|(id, _, _)| id.clone())
+                .collect::<Vec<_>>()
+        };
+        let units = *world.read_resource::<UnitSystem>();
+
+        let perturbed = self
+            .perturbations
+            .values()
+            .any(|perturbation| *perturbation != Perturbation::default());
+        let tracking = self.tracked.iter().any(|id| !self.perturbation(id).removed);
+
+        let mut run_clicked = false;
+        let mut apply_clicked = false;
+
+        egui::Window::new(tr!("sandbox-title"))
+            .id(egui::Id::new("sandbox_window"))
+            .collapsible(true)
+            .resizable(true)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(tr!("sandbox-description"));
+
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("sandbox_bodies")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr!("sandbox-body"));
+                                ui.strong(tr!("sandbox-removed"));
+                                ui.strong(tr!("sandbox-mass"));
+                                ui.strong(tr!("sandbox-tracked"));
+                                ui.end_row();
+
+                                for id in candidates.iter() {
+                                    let perturbation = self
+                                        .perturbations
+                                        .entry(id.get_id().to_string())
+                                        .or_default();
+
+                                    ui.label(locale::body_name(id));
+                                    ui.checkbox(&mut perturbation.removed, "");
+                                    ui.add_enabled(
+                                        !perturbation.removed,
+                                        egui::DragValue::new(&mut perturbation.mass_multiplier)
+                                            .clamp_range(0.01..=1000.0)
+                                            .speed(0.01)
+                                            .prefix("\u{d7}"),
+                                    );
+
+                                    let mut tracked = self.tracked.contains(id.get_id());
+                                    if ui
+                                        .add_enabled(
+                                            !perturbation.removed,
+                                            egui::Checkbox::new(&mut tracked, ""),
+                                        )
+                                        .changed()
+                                    {
+                                        if tracked {
+                                            self.tracked.insert(id.get_id().to_string());
+                                        } else {
+                                            self.tracked.remove(id.get_id());
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("sandbox-duration"));
+                    ui.add_enabled(
+                        self.run.is_none(),
+                        egui::DragValue::new(&mut self.duration)
+                            .clamp_range(1.0..=1000.0)
+                            .speed(1.0)
+                            .suffix(" yr"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    if self.run.is_some() {
+                        ui.spinner();
+                        if ui.button(tr!("sandbox-stop")).clicked() {
+                            self.run = None;
+                        }
+                    } else {
+                        run_clicked = ui
+                            .add_enabled(
+                                perturbed && tracking,
+                                egui::Button::new(tr!("sandbox-run")),
+                            )
+                            .on_hover_text(tr!("sandbox-run-hover"))
+                            .clicked();
+                    }
+
+                    apply_clicked = ui
+                        .add_enabled(perturbed, egui::Button::new(tr!("sandbox-apply")))
+                        .on_hover_text(tr!("sandbox-apply-hover"))
+                        .clicked();
+
+                    if ui.button(tr!("sandbox-reset")).clicked() {
+                        self.perturbations.clear();
+                    }
+                });
+
+                if !self.samples.is_empty() {
+                    ui.separator();
+                    self.plot(ui, units);
+                }
+            });
+
+        if run_clicked {
+            self.start_run(world);
+        }
+        if apply_clicked {
+            self.apply(world);
+        }
+    }
+
+    fn perturbation(&self, id: &str) -> Perturbation {
+        self.perturbations.get(id).copied().unwrap_or_default()
+    }
+
+    fn poll_run(&mut self) {
+        //! Take the samples taken since the last frame, ending the run once
+        //! it has covered the whole duration
+
+        if let Some(run) = &self.run {
+            self.samples.extend(run.samples());
+
+            let finished = self
+                .samples
+                .last()
+                .map_or(false, |sample| sample.time >= self.duration * YEAR);
+            if finished {
+                self.run = None;
+            }
+        }
+    }
+
+    fn start_run(&mut self, world: &World) {
+        //! Copy the state of the bodies and start running both branches on
+        //! their own thread
+
+        let (ids, positions, velocities, masses, handlers, satellites, constant): (
+            ReadStorage<Identifier>,
+            ReadStorage<Position>,
+            ReadStorage<Velocity>,
+            ReadStorage<Mass>,
+            ReadStorage<InteractionHandler>,
+            ReadStorage<Satellite>,
+            Read<GravitationalConstant>,
+        ) = world.system_data();
+
+        let mut bodies = Vec::new();
+        let mut perturbations = Vec::new();
+        let mut tracked = Vec::new();
+        self.plotted.clear();
+
+        for (id, position, velocity, mass, handler, _) in (
+            &ids,
+            &positions,
+            &velocities,
+            &masses,
+            &handlers,
+            !&satellites,
+        )
+            .join()
+        {
+            let perturbation = self.perturbation(id.get_id());
+            if self.tracked.contains(id.get_id()) && !perturbation.removed {
+                tracked.push(bodies.len());
+                self.plotted.push(locale::body_name(id));
+            }
+
+            bodies.push(SearchBody {
+                position: position.0,
+                velocity: velocity.0,
+                mass: mass.0,
+                body_type: handler.body_type,
+                flags: handler.flags,
+            });
+            perturbations.push(perturbation);
+        }
+
+        self.samples.clear();
+        self.run = Some(BranchRun::start(
+            bodies,
+            &perturbations,
+            tracked,
+            constant.0,
+            self.duration * YEAR,
+        ));
+    }
+
+    fn apply(&mut self, world: &mut World) {
+        //! Make the changes to the simulation itself, removing the satellites
+        //! of any body that is removed along with it
+
+        world.exec(
+            |(entities, ids, mut masses, satellites): (
+                Entities,
+                ReadStorage<Identifier>,
+                WriteStorage<Mass>,
+                ReadStorage<Satellite>,
+            )| {
+                let mut removed = Vec::new();
+                for (entity, id, mass) in (&entities, &ids, &mut masses).join() {
+                    let perturbation = self.perturbation(id.get_id());
+                    if perturbation.removed {
+                        removed.push(entity);
+                    } else {
+                        mass.0 *= perturbation.mass_multiplier;
+                    }
+                }
+
+                for (entity, satellite) in (&entities, &satellites).join() {
+                    if removed.contains(&satellite.parent) {
+                        removed.push(entity);
+                    }
+                }
+
+                for entity in removed {
+                    let _ = entities.delete(entity);
+                }
+            },
+        );
+        world.maintain();
+
+        // The simulation now matches the sandbox
+        self.perturbations.clear();
+    }
+
+    fn plot(&self, ui: &mut egui::Ui, units: UnitSystem) {
+        //! Plot the distance between each tracked body's positions in the two
+        //! branches over time
+
+        use egui::plot::{Legend, Line, Plot, PlotPoints};
+
+        let distance = units.distance();
+        ui.label(tr!("sandbox-plot", unit = distance.symbol));
+
+        Plot::new("divergence_plot")
+            .height(200.0)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for (i, name) in self.plotted.iter().enumerate() {
+                    let points = self
+                        .samples
+                        .iter()
+                        .filter_map(|sample| {
+                            Some([
+                                sample.time / YEAR,
+                                distance.from_si(*sample.distances.get(i)?),
+                            ])
+                        })
+                        .collect::<Vec<_>>();
+
+                    plot_ui.line(Line::new(PlotPoints::new(points)).name(name));
+                }
+            });
+    }
+}
@@ -0,0 +1,108 @@
+use std::{collections::BTreeMap, fs};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::config::CONFIG_DIR;
+
+use super::layout::Dock;
+
+// egui's memory has maps keyed by integers, which TOML can't represent
+const SESSION_FILE: &'static str = "session.json";
+const LAYOUTS_FILE: &'static str = "layouts.json";
+
+/// The layout of the UI, restored on the next launch so the user can carry on
+/// where they left off
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub help_window_shown: bool,
+    pub stats_window_shown: bool,
+    pub dial_window_shown: bool,
+    pub conjunction_window_shown: bool,
+    pub period_window_shown: bool,
+    pub compare_window_shown: bool,
+    pub experiments_window_shown: bool,
+    pub sandbox_window_shown: bool,
+    pub camera_path_window_shown: bool,
+    pub render_settings_window_shown: bool,
+    pub render_stats_window_shown: bool,
+    pub console_window_shown: bool,
+    pub log_window_shown: bool,
+    pub layout_window_shown: bool,
+    /// Where the settings window is docked
+    pub dock: Dock,
+    /// Ids of the bodies whose windows were open
+    pub selected_bodies: Vec<String>,
+    /// egui's own state, which holds the positions and sizes of windows
+    pub memory: Option<egui::Memory>,
+}
+impl Session {
+    pub fn load() -> Option<Self> {
+        //! Load the session saved when the program was last closed, if there
+        //! is one
+
+        let path = CONFIG_DIR.join(SESSION_FILE);
+        let contents = fs::read_to_string(&path).ok()?;
+
+        serde_json::from_str(&contents)
+            .map_err(|err| warn!("Invalid session in {:?}: {}", path, err))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        //! Save the session to be restored on the next launch
+
+        let contents = match serde_json::to_string(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize session: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(SESSION_FILE), contents))
+        {
+            warn!("Failed to save session: {}", err);
+        }
+    }
+}
+
+/// Layouts the user has saved by name, to switch between arrangements of
+/// windows
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Layouts(pub BTreeMap<String, Session>);
+impl Layouts {
+    pub fn load() -> Self {
+        //! Load the saved layouts, with none if they can't be read
+
+        let path = CONFIG_DIR.join(LAYOUTS_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => return Self::default(),
+        };
+
+        serde_json::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Invalid layouts in {:?}: {}", path, err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let contents = match serde_json::to_string(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize layouts: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(LAYOUTS_FILE), contents))
+        {
+            warn!("Failed to save layouts: {}", err);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    locale::tr, stats::UsageStats, APPLICATION_AUTHOR, APPLICATION_NAME, APPLICATION_VERSION,
+};
+
+use super::format_duration;
+
+pub struct StatsWindow<'a> {
+    pub stats: &'a UsageStats,
+}
+impl<'a> super::Window for StatsWindow<'a> {
+    fn name(&self) -> String {
+        tr!("about-title")
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .id(egui::Id::new("about_window"))
+            .collapsible(false)
+            .resizable(true)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+impl<'a> super::View for StatsWindow<'a> {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.heading(APPLICATION_NAME);
+            ui.label(tr!("about-version", version = APPLICATION_VERSION));
+            ui.label(tr!("about-author", author = APPLICATION_AUTHOR));
+        });
+
+        ui.separator();
+
+        ui.label(tr!("about-usage-statistics"));
+
+        egui::Grid::new("usage_stats")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("about-time-run"));
+                ui.label(format_duration(self.stats.time_run));
+                ui.end_row();
+
+                ui.label(tr!("about-sessions"));
+                ui.label(self.stats.sessions.to_string());
+                ui.end_row();
+
+                ui.label(tr!("about-bodies-created"));
+                ui.label(self.stats.bodies_created.to_string());
+                ui.end_row();
+
+                ui.label(tr!("about-saves-made"));
+                ui.label(self.stats.saves_made.to_string());
+                ui.end_row();
+            });
+
+        if !self.stats.features_used.is_empty() {
+            egui::CollapsingHeader::new(tr!("about-features-used"))
+                .id_source("features_used")
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new("features_used")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (feature, count) in self.stats.features_used.iter() {
+                                ui.label(feature);
+                                ui.label(count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use specs::{Read, World, Write};
+
+use crate::{
+    locale::tr,
+    simulation::{SimulationTime, TimeJump, TimeScale},
+    util::{convert_julian_date_to_datetime, J2000},
+};
+
+use super::{
+    dynamic_decimals_formatter,
+    validation::{self, edit_validated},
+    Tutorial, TutorialAction,
+};
+
+/// (in seconds)
+const DAY: f64 = 86400.0;
+const YEAR: f64 = 365.25 * DAY;
+
+/// A time scale picked from the toolbar
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimePreset {
+    RealTime,
+    DayPerSecond,
+    MonthPerSecond,
+    YearPerSecond,
+}
+impl TimePreset {
+    const ALL: [Self; 4] = [
+        Self::RealTime,
+        Self::DayPerSecond,
+        Self::MonthPerSecond,
+        Self::YearPerSecond,
+    ];
+
+    fn name(&self) -> String {
+        match self {
+            Self::RealTime => tr!("toolbar-real-time"),
+            Self::DayPerSecond => tr!("toolbar-day-per-second"),
+            Self::MonthPerSecond => tr!("toolbar-month-per-second"),
+            Self::YearPerSecond => tr!("toolbar-year-per-second"),
+        }
+    }
+
+    /// Simulated seconds for every real second
+    fn time_scale(&self) -> f64 {
+        match self {
+            Self::RealTime => 1.0,
+            Self::DayPerSecond => DAY,
+            Self::MonthPerSecond => YEAR / 12.0,
+            Self::YearPerSecond => YEAR,
+        }
+    }
+}
+
+/// The bar along the bottom of the screen with the simulated date and quick
+/// ways to change the time
+pub struct TimeToolbar {
+    /// The Julian date to jump to
+    target: f64,
+}
+impl Default for TimeToolbar {
+    fn default() -> Self {
+        Self { target: J2000 }
+    }
+}
+impl TimeToolbar {
+    pub fn show(&mut self, ctx: &egui::Context, world: &World) {
+        //! Show the toolbar, which must be done before any side panels so they
+        //! stop above it
+
+        let (mut time_scale, mut jump, simulation_time, mut tutorial): (
+            Write<TimeScale>,
+            Write<TimeJump>,
+            Read<SimulationTime>,
+            Write<Tutorial>,
+        ) = world.system_data();
+
+        // The bodies start at their positions at the J2000 epoch
+        let julian_date = J2000 + simulation_time.0 / DAY;
+        let date = convert_julian_date_to_datetime(julian_date);
+
+        egui::TopBottomPanel::bottom("time_toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("time-date"));
+                ui.monospace(date.format("%Y-%m-%d %H:%M").to_string())
+                    .on_hover_text(tr!(
+                        "toolbar-julian-date",
+                        date = format!("{:.4}", julian_date)
+                    ));
+
+                ui.separator();
+
+                let label = if time_scale.paused {
+                    tr!("time-resume")
+                } else {
+                    tr!("time-pause")
+                };
+                if ui
+                    .add_enabled(jump.target().is_none(), egui::Button::new(label))
+                    .clicked()
+                {
+                    time_scale.paused = !time_scale.paused;
+                }
+
+                for preset in TimePreset::ALL {
+                    let selected = jump.target().is_none()
+                        && time_scale.total_time_elapsed == preset.time_scale();
+                    if ui.selectable_label(selected, preset.name()).clicked() {
+                        jump.cancel();
+                        *time_scale = TimeScale {
+                            paused: time_scale.paused,
+                            ..TimeScale::from_max_time_per_iteration(preset.time_scale(), DAY)
+                        };
+                        tutorial.record(TutorialAction::ChangeTimeScale);
+                    }
+                }
+
+                ui.separator();
+
+                ui.label(tr!("toolbar-jump-to"));
+                edit_validated(
+                    ui,
+                    "jump_target",
+                    &mut self.target,
+                    validation::finite,
+                    |ui, target| {
+                        ui.add(
+                            egui::DragValue::new(target)
+                                .speed(1.0)
+                                .prefix(tr!("toolbar-julian-date-prefix"))
+                                .custom_formatter(dynamic_decimals_formatter()),
+                        )
+                    },
+                )
+                .on_hover_text(tr!("toolbar-jump-to-hover"));
+
+                if jump.target().is_some() {
+                    ui.spinner();
+                    if ui.button(tr!("toolbar-cancel-jump")).clicked() {
+                        if let Some(resume) = jump.cancel() {
+                            *time_scale = resume;
+                        }
+                    }
+                } else if ui.button(tr!("toolbar-jump")).clicked() {
+                    jump.start((self.target - J2000) * DAY, &time_scale);
+                }
+            });
+        });
+    }
+}
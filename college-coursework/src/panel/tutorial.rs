@@ -0,0 +1,168 @@
+use crate::locale::tr;
+
+/// Something the user does that a step of the tutorial can ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialAction {
+    MoveCamera,
+    EditBody,
+    ChangeTimeScale,
+    Save,
+}
+
+/// A page of the tutorial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TutorialStep {
+    Welcome,
+    Camera,
+    EditBody,
+    TimeScale,
+    Saving,
+    Finished,
+}
+impl TutorialStep {
+    const ALL: [Self; 6] = [
+        Self::Welcome,
+        Self::Camera,
+        Self::EditBody,
+        Self::TimeScale,
+        Self::Saving,
+        Self::Finished,
+    ];
+
+    fn title(&self) -> String {
+        match self {
+            Self::Welcome => tr!("tutorial-welcome-title"),
+            Self::Camera => tr!("tutorial-camera-title"),
+            Self::EditBody => tr!("tutorial-edit-body-title"),
+            Self::TimeScale => tr!("tutorial-time-scale-title"),
+            Self::Saving => tr!("tutorial-saving-title"),
+            Self::Finished => tr!("tutorial-finished-title"),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Self::Welcome => tr!("tutorial-welcome"),
+            Self::Camera => tr!("tutorial-camera"),
+            Self::EditBody => tr!("tutorial-edit-body"),
+            Self::TimeScale => tr!("tutorial-time-scale"),
+            Self::Saving => tr!("tutorial-saving"),
+            Self::Finished => tr!("tutorial-finished"),
+        }
+    }
+
+    /// What the user is asked to try before moving on, if anything
+    fn action(&self) -> Option<TutorialAction> {
+        match self {
+            Self::Camera => Some(TutorialAction::MoveCamera),
+            Self::EditBody => Some(TutorialAction::EditBody),
+            Self::TimeScale => Some(TutorialAction::ChangeTimeScale),
+            Self::Saving => Some(TutorialAction::Save),
+            Self::Welcome | Self::Finished => None,
+        }
+    }
+}
+
+/// A guided tour of the controls, shown on the first launch and started
+/// again from the Help window
+#[derive(Default)]
+pub struct Tutorial {
+    shown: bool,
+    /// Index of the current step
+    step: usize,
+    /// Whether the user has done what the current step asks
+    done: bool,
+}
+impl Tutorial {
+    pub fn start(&mut self) {
+        *self = Self {
+            shown: true,
+            ..Self::default()
+        };
+    }
+
+    pub fn record(&mut self, action: TutorialAction) {
+        //! Record that the user has done something, completing the current
+        //! step if it asked for that
+
+        if self.shown && TutorialStep::ALL[self.step].action() == Some(action) {
+            self.done = true;
+        }
+    }
+
+    fn go_to(&mut self, step: usize) {
+        self.step = step;
+        self.done = false;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        //! Show the current step, if the tutorial is running
+
+        if !self.shown {
+            return;
+        }
+
+        let step = TutorialStep::ALL[self.step];
+        let last = self.step + 1 == TutorialStep::ALL.len();
+        let mut shown = self.shown;
+
+        egui::Window::new(tr!("tutorial-title"))
+            .id(egui::Id::new("tutorial_window"))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(320.0)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 32.0))
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(step.title());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.weak(tr!(
+                            "tutorial-step",
+                            step = self.step + 1,
+                            steps = TutorialStep::ALL.len()
+                        ));
+                    });
+                });
+                ui.separator();
+
+                ui.label(step.text());
+
+                if step.action().is_some() {
+                    ui.add_space(4.0);
+                    if self.done {
+                        ui.colored_label(egui::Color32::GREEN, tr!("tutorial-step-done"));
+                    } else {
+                        ui.weak(tr!("tutorial-step-waiting"));
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.step > 0, egui::Button::new(tr!("tutorial-back")))
+                        .clicked()
+                    {
+                        self.go_to(self.step - 1);
+                    }
+
+                    let next = if last {
+                        tr!("tutorial-finish")
+                    } else if step.action().is_some() && !self.done {
+                        tr!("tutorial-skip")
+                    } else {
+                        tr!("tutorial-next")
+                    };
+                    if ui.button(next).clicked() {
+                        if last {
+                            self.shown = false;
+                        } else {
+                            self.go_to(self.step + 1);
+                        }
+                    }
+                });
+            });
+
+        self.shown &= shown;
+    }
+}
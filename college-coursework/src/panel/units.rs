@@ -0,0 +1,174 @@
+use const_format::concatcp;
+use serde::{Deserialize, Serialize};
+
+use crate::{locale::tr, util::AU};
+
+use super::{
+    format_duration,
+    global::{MINUS_ONE_EXPONENT, MINUS_TWO_EXPONENT},
+};
+
+/// (in m)
+const MILE: f64 = 1609.344;
+/// (in m)
+const FOOT: f64 = 0.3048;
+/// (in kg)
+const POUND: f64 = 0.45359237;
+/// (in kg)
+pub const EARTH_MASS: f64 = 5.9722e24;
+/// (in s)
+const DAY: f64 = 86400.0;
+/// A Julian year (in s)
+const YEAR: f64 = 365.25 * DAY;
+
+/// A unit values are shown in, instead of the SI unit they are stored in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    /// The size of the unit in the SI unit
+    pub size: f64,
+    pub symbol: &'static str,
+}
+impl Unit {
+    pub fn from_si(&self, value: f64) -> f64 {
+        value / self.size
+    }
+
+    pub fn to_si(&self, value: f64) -> f64 {
+        value * self.size
+    }
+
+    pub fn suffix(&self) -> String {
+        format!(" {}", self.symbol)
+    }
+}
+
+/// The units values are shown in by the panels. Everything is still
+/// simulated and saved in SI units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitSystem {
+    Si,
+    /// Astronomical units, days and Earth masses
+    Astronomical,
+    Imperial,
+}
+impl Default for UnitSystem {
+    fn default() -> Self {
+        Self::Si
+    }
+}
+impl UnitSystem {
+    pub const ALL: [Self; 3] = [Self::Si, Self::Astronomical, Self::Imperial];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Si => tr!("units-si"),
+            Self::Astronomical => tr!("units-astronomical"),
+            Self::Imperial => tr!("units-imperial"),
+        }
+    }
+
+    pub fn distance(&self) -> Unit {
+        match self {
+            Self::Si => Unit {
+                size: 1.0,
+                symbol: "m",
+            },
+            Self::Astronomical => Unit {
+                size: AU,
+                symbol: "AU",
+            },
+            Self::Imperial => Unit {
+                size: MILE,
+                symbol: "mi",
+            },
+        }
+    }
+
+    pub fn speed(&self) -> Unit {
+        match self {
+            Self::Si => Unit {
+                size: 1.0,
+                symbol: concatcp!("ms", MINUS_ONE_EXPONENT),
+            },
+            Self::Astronomical => Unit {
+                size: 1000.0,
+                symbol: concatcp!("kms", MINUS_ONE_EXPONENT),
+            },
+            Self::Imperial => Unit {
+                size: MILE,
+                symbol: concatcp!("mi s", MINUS_ONE_EXPONENT),
+            },
+        }
+    }
+
+    pub fn acceleration(&self) -> Unit {
+        match self {
+            Self::Si | Self::Astronomical => Unit {
+                size: 1.0,
+                symbol: concatcp!("ms", MINUS_TWO_EXPONENT),
+            },
+            Self::Imperial => Unit {
+                size: FOOT,
+                symbol: concatcp!("ft s", MINUS_TWO_EXPONENT),
+            },
+        }
+    }
+
+    pub fn mass(&self) -> Unit {
+        match self {
+            Self::Si => Unit {
+                size: 1.0,
+                symbol: "kg",
+            },
+            Self::Astronomical => Unit {
+                size: EARTH_MASS,
+                symbol: "M\u{2295}",
+            },
+            Self::Imperial => Unit {
+                size: POUND,
+                symbol: "lb",
+            },
+        }
+    }
+
+    pub fn mass_rate(&self) -> Unit {
+        let mass = self.mass();
+        Unit {
+            size: mass.size,
+            symbol: match self {
+                Self::Si => concatcp!("kgs", MINUS_ONE_EXPONENT),
+                Self::Astronomical => concatcp!("M\u{2295}s", MINUS_ONE_EXPONENT),
+                Self::Imperial => concatcp!("lb s", MINUS_ONE_EXPONENT),
+            },
+        }
+    }
+
+    pub fn format_duration(&self, seconds: f64) -> String {
+        //! Format a duration in seconds, as days or years in astronomical
+        //! units and as days, hours, minutes and seconds otherwise
+
+        match self {
+            Self::Astronomical if seconds.abs() >= YEAR => format!("{:.3} yr", seconds / YEAR),
+            Self::Astronomical => format!("{:.3} d", seconds / DAY),
+            Self::Si | Self::Imperial => format_duration(seconds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnitSystem;
+    use crate::util::AU;
+
+    #[test]
+    fn test_unit_conversion() {
+        let units = UnitSystem::Astronomical;
+        assert_eq!(1.0, units.distance().from_si(AU));
+        assert_eq!(1000.0, units.speed().to_si(1.0));
+
+        for units in UnitSystem::ALL {
+            let mass = 1.989e30;
+            assert!((units.mass().to_si(units.mass().from_si(mass)) - mass).abs() < 1e18);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::locale;
+
+/// (in ms^-1)
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Whether an edited value can be kept, with the id of the message saying
+/// why not if it can't
+pub type Validation = Result<(), &'static str>;
+
+pub fn finite(value: f64) -> Validation {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err("invalid-not-finite")
+    }
+}
+
+pub fn finite_vector(value: Vector3<f64>) -> Validation {
+    finite(value.x)?;
+    finite(value.y)?;
+    finite(value.z)
+}
+
+pub fn positive(value: f64) -> Validation {
+    finite(value)?;
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err("invalid-not-positive")
+    }
+}
+
+pub fn non_negative(value: f64) -> Validation {
+    finite(value)?;
+    if value >= 0.0 {
+        Ok(())
+    } else {
+        Err("invalid-negative")
+    }
+}
+
+pub fn below_light_speed(velocity: Vector3<f64>) -> Validation {
+    finite_vector(velocity)?;
+    if velocity.magnitude() < SPEED_OF_LIGHT {
+        Ok(())
+    } else {
+        Err("invalid-light-speed")
+    }
+}
+
+pub fn edit_validated<T: Copy + PartialEq>(
+    ui: &mut egui::Ui,
+    id_source: impl std::hash::Hash,
+    value: &mut T,
+    validate: impl Fn(T) -> Validation,
+    add_contents: impl FnOnce(&mut egui::Ui, &mut T) -> egui::Response,
+) -> egui::Response {
+    //! Edit a copy of a value with a widget, only keeping the edit if it is
+    //! valid. An invalid edit is reverted and the widget outlined in red,
+    //! with the reason on hover, until the value is next edited validly
+
+    let id = ui.make_persistent_id(id_source);
+
+    let mut edited = *value;
+    let response = add_contents(ui, &mut edited);
+
+    if edited != *value {
+        match validate(edited) {
+            Ok(()) => {
+                *value = edited;
+                ui.data().remove::<&'static str>(id);
+            }
+            Err(message) => ui.data().insert_temp(id, message),
+        }
+    }
+
+    match ui.data().get_temp::<&'static str>(id) {
+        Some(message) => {
+            ui.painter().rect_stroke(
+                response.rect.expand(1.0),
+                ui.visuals().widgets.inactive.rounding,
+                egui::Stroke::new(1.5, egui::Color32::RED),
+            );
+            response.on_hover_text(locale::text(message, None))
+        }
+        None => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{below_light_speed, positive};
+
+    #[test]
+    fn test_validation() {
+        assert!(positive(5.972e24).is_ok());
+        assert!(positive(0.0).is_err());
+        assert!(positive(f64::NAN).is_err());
+
+        assert!(below_light_speed(Vector3::new(0.0, 29_780.0, 0.0)).is_ok());
+        assert!(below_light_speed(Vector3::new(3e8, 0.0, 0.0)).is_err());
+        assert!(below_light_speed(Vector3::new(f64::INFINITY, 0.0, 0.0)).is_err());
+    }
+}
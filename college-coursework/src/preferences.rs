@@ -0,0 +1,236 @@
+use std::{fs, time::Duration};
+
+use instant::Instant;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use specs::{World, WorldExt};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    audio::{MusicPlayer, RepeatMode},
+    config::CONFIG_DIR,
+    locale::{self, Language},
+    panel::UnitSystem,
+    renderer::camera::{CameraControllerType, CameraSpeed, DEFAULT_CAMERA_SPEED},
+    simulation::{BodyScale, GravitationalConstant, ScaleMode},
+    util::BIG_G,
+};
+
+const PREFERENCES_FILE: &'static str = "preferences.toml";
+
+/// How often the preferences are checked for changes to save, so dragging a
+/// slider doesn't write the file every frame
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Values changed from the panels, restored on the next launch. The options
+/// in the config file and on the command line take priority over them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub camera_speed: f32,
+    pub camera_controller: CameraControllerType,
+    /// (in m^3 kg^-1 s^-2)
+    pub gravitational_constant: f64,
+    pub scale_mode: ScaleMode,
+    /// How many times larger than their real size bodies are drawn when
+    /// exaggerated
+    pub scale_multiplier: f64,
+    /// The size of the window (in physical pixels), left to the system if
+    /// not set
+    pub window_size: Option<[u32; 2]>,
+    /// The language of the UI, the system's language if it has been
+    /// translated
+    pub language: Language,
+    /// The units values are shown in by the panels
+    pub units: UnitSystem,
+    // Tables have to come after values in TOML, so this must be last
+    pub audio: AudioPreferences,
+}
+impl Default for Preferences {
+    fn default() -> Self {
+        let body_scale = BodyScale::default();
+
+        Self {
+            camera_speed: DEFAULT_CAMERA_SPEED,
+            camera_controller: CameraControllerType::default(),
+            gravitational_constant: BIG_G,
+            scale_mode: body_scale.mode,
+            scale_multiplier: body_scale.multiplier,
+            window_size: None,
+            language: Language::default(),
+            units: UnitSystem::default(),
+            audio: AudioPreferences::default(),
+        }
+    }
+}
+impl Preferences {
+    pub fn load(safe_mode: bool) -> Self {
+        //! Load the preferences saved by a previous session, falling back to
+        //! the defaults if there are none. Safe mode ignores them in case
+        //! they caused the crash
+
+        let path = CONFIG_DIR.join(PREFERENCES_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(_) if safe_mode => Self::default(),
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid preferences in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        //! Save the preferences for use by later sessions
+
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize preferences: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(PREFERENCES_FILE), contents))
+        {
+            warn!("Failed to save preferences: {}", err);
+        }
+    }
+
+    pub fn from_world(world: &World, window_size: PhysicalSize<u32>) -> Self {
+        //! The preferences as they are now
+
+        let body_scale = *world.read_resource::<BodyScale>();
+
+        Self {
+            camera_speed: world.read_resource::<CameraSpeed>().0,
+            camera_controller: *world.read_resource::<CameraControllerType>(),
+            gravitational_constant: world.read_resource::<GravitationalConstant>().0,
+            scale_mode: body_scale.mode,
+            scale_multiplier: body_scale.multiplier,
+            window_size: Some([window_size.width, window_size.height]),
+            language: locale::language(),
+            units: *world.read_resource::<UnitSystem>(),
+            audio: AudioPreferences::from_player(&world.read_resource::<MusicPlayer>()),
+        }
+    }
+
+    pub fn window_size(&self) -> Option<PhysicalSize<u32>> {
+        self.window_size
+            .filter(|[width, height]| *width > 0 && *height > 0)
+            .map(|[width, height]| PhysicalSize::new(width, height))
+    }
+
+    pub fn apply(&self, world: &mut World) {
+        //! Restore the preferences kept in the world. The window size and
+        //! audio are restored as the window is created
+
+        world.insert(CameraSpeed(self.camera_speed));
+        world.insert(self.camera_controller);
+        world.insert(GravitationalConstant(self.gravitational_constant));
+        world.insert(self.units);
+
+        let mut body_scale = world.write_resource::<BodyScale>();
+        body_scale.mode = self.scale_mode;
+        body_scale.multiplier = self.scale_multiplier;
+    }
+}
+
+/// The music settings from the Audio section
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioPreferences {
+    /// From 0 to 1
+    pub volume: f32,
+    pub muted: bool,
+    pub shuffled: bool,
+    pub repeat: RepeatMode,
+    /// How long one track fades into the next (in seconds)
+    pub crossfade: f32,
+}
+impl Default for AudioPreferences {
+    fn default() -> Self {
+        Self::from_player(&MusicPlayer::default())
+    }
+}
+impl AudioPreferences {
+    pub fn from_player(player: &MusicPlayer) -> Self {
+        Self {
+            volume: player.volume(),
+            muted: player.muted(),
+            shuffled: player.shuffled(),
+            repeat: player.repeat(),
+            crossfade: player.crossfade(),
+        }
+    }
+
+    pub fn apply(&self, player: &mut MusicPlayer) {
+        player.set_volume(self.volume);
+        player.set_muted(self.muted);
+        player.set_shuffled(self.shuffled);
+        player.set_repeat(self.repeat);
+        player.set_crossfade(self.crossfade);
+    }
+}
+
+/// Saves the preferences whenever they are changed
+pub struct PreferenceSaver {
+    /// The preferences last saved, or those the session started with
+    saved: Preferences,
+    last_check: Instant,
+}
+impl PreferenceSaver {
+    pub fn new(world: &World, window_size: PhysicalSize<u32>) -> Self {
+        //! Start watching for changes. Nothing is saved until something is
+        //! changed, so options given on the command line or safe mode's
+        //! defaults aren't kept
+
+        Self {
+            saved: Preferences::from_world(world, window_size),
+            last_check: Instant::now(),
+        }
+    }
+
+    pub fn update(&mut self, world: &World, window_size: PhysicalSize<u32>) {
+        //! Save the preferences if they have changed since they were last
+        //! checked
+
+        if self.last_check.elapsed() >= SAVE_INTERVAL {
+            self.save_changes(world, window_size);
+        }
+    }
+
+    pub fn save_changes(&mut self, world: &World, window_size: PhysicalSize<u32>) {
+        //! Save the preferences straight away if they have changed, such as
+        //! when the program closes
+
+        self.last_check = Instant::now();
+
+        let preferences = Preferences::from_world(world, window_size);
+        if preferences != self.saved {
+            preferences.save();
+            self.saved = preferences;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Preferences;
+
+    #[test]
+    fn test_preferences_saved() {
+        let preferences = Preferences {
+            camera_speed: 55.0,
+            window_size: Some([1280, 720]),
+            ..Default::default()
+        };
+        let contents = toml::to_string_pretty(&preferences).unwrap();
+        assert_eq!(preferences, toml::from_str(&contents).unwrap());
+
+        // Preferences missing from the file keep their defaults
+        assert_eq!(Preferences::default(), toml::from_str("").unwrap());
+    }
+}
@@ -0,0 +1,230 @@
+mod rpc;
+
+use std::net::SocketAddr;
+
+use crossbeam::channel::{self, Receiver, Sender};
+use futures_util::{SinkExt, StreamExt};
+use log::{info, warn};
+use serde_json::{json, Value};
+use specs::{Join, ReadStorage, World, WorldExt, WriteStorage};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    runtime::Handle,
+    sync::oneshot,
+};
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+    renderer::screenshot::{ScreenshotRequest, ScreenshotRequests},
+    simulation::{Identifier, Mass, Position, Radius, SimulationState, TimeScale, Velocity},
+};
+
+use self::rpc::{Command, Message, RpcError, SetBody, SetTimeScale};
+
+/// A command from a client, waiting for the main thread to carry it out
+struct RemoteRequest {
+    command: Command,
+    reply: oneshot::Sender<Result<Value, RpcError>>,
+}
+
+/// Lets other programs control the simulation with JSON-RPC over a
+/// WebSocket. Only connections from this machine are accepted
+pub struct RemoteServer {
+    receiver: Receiver<RemoteRequest>,
+}
+impl RemoteServer {
+    pub fn start(runtime: &Handle, port: u16) -> Self {
+        //! Listen for clients on a port, on the async runtime
+
+        let (sender, receiver) = channel::unbounded();
+
+        runtime.spawn(async move {
+            let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    warn!("Failed to start the remote control server: {}", err);
+                    return;
+                }
+            };
+            info!("Listening for remote control on ws://127.0.0.1:{}", port);
+
+            loop {
+                match listener.accept().await {
+                    Ok((stream, address)) => {
+                        tokio::spawn(serve(stream, address, sender.clone()));
+                    }
+                    Err(err) => warn!("Failed to accept a remote client: {}", err),
+                }
+            }
+        });
+
+        Self { receiver }
+    }
+}
+
+async fn serve(stream: TcpStream, address: SocketAddr, sender: Sender<RemoteRequest>) {
+    //! Answer the requests of a client until it disconnects
+
+    let mut socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            warn!("Failed to accept the remote client {}: {}", address, err);
+            return;
+        }
+    };
+    info!("Remote client {} connected", address);
+
+    while let Some(message) = socket.next().await {
+        let text = match message {
+            Ok(tungstenite::Message::Text(text)) => text,
+            Ok(tungstenite::Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(err) => {
+                warn!("Lost the remote client {}: {}", address, err);
+                break;
+            }
+        };
+
+        let message = Message::parse(&text);
+        let result = match message.command.clone() {
+            Ok(command) => {
+                // Commands are carried out on the main thread, which owns the
+                // world, and replied to once they are done
+                let (reply, receiver) = oneshot::channel();
+                let _ = sender.send(RemoteRequest { command, reply });
+                receiver
+                    .await
+                    .unwrap_or_else(|_| Err(RpcError::internal("The simulation has stopped")))
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Some(response) = message.respond(result) {
+            if let Err(err) = socket.send(tungstenite::Message::Text(response)).await {
+                warn!("Lost the remote client {}: {}", address, err);
+                break;
+            }
+        }
+    }
+
+    info!("Remote client {} disconnected", address);
+}
+
+pub fn update(world: &mut World) {
+    //! Carry out the commands received since the last frame, if the server
+    //! was started
+
+    let requests = match world.try_fetch::<RemoteServer>() {
+        Some(server) => server.receiver.try_iter().collect::<Vec<_>>(),
+        None => return,
+    };
+
+    for RemoteRequest { command, reply } in requests {
+        let result = match command {
+            Command::GetState => serde_json::to_value(SimulationState::serialize_from_world(world))
+                .map_err(RpcError::internal),
+            Command::SetBody(params) => set_body(world, params),
+            Command::SetTimeScale(params) => set_time_scale(world, params),
+            Command::Save(params) => {
+                let state = SimulationState::serialize_from_world(world);
+
+                // Written on another thread, so a large simulation doesn't
+                // stall the frame
+                std::thread::spawn(move || {
+                    let _ = reply.send(
+                        state
+                            .save_file(&params.path)
+                            .map(|_| json!(params.path))
+                            .map_err(RpcError::internal),
+                    );
+                });
+                continue;
+            }
+            Command::Screenshot(params) => {
+                let (request, receiver) = ScreenshotRequest::new(params.path);
+                world.write_resource::<ScreenshotRequests>().0.push(request);
+
+                // Replied to once the next frame has been saved
+                world.read_resource::<Handle>().spawn(async move {
+                    let _ = reply.send(match receiver.await {
+                        Ok(Ok(path)) => Ok(json!(path)),
+                        Ok(Err(err)) => Err(RpcError::internal(err)),
+                        Err(_) => Err(RpcError::internal("The screenshot was never taken")),
+                    });
+                });
+                continue;
+            }
+        };
+
+        let _ = reply.send(result);
+    }
+}
+
+fn set_body(world: &mut World, params: SetBody) -> Result<Value, RpcError> {
+    if params.mass.map_or(false, |mass| mass < 0.0) {
+        return Err(RpcError::invalid_params("Mass can't be negative"));
+    }
+    if params.radius.map_or(false, |radius| radius <= 0.0) {
+        return Err(RpcError::invalid_params("Radius has to be positive"));
+    }
+
+    world.exec(
+        |(ids, mut positions, mut velocities, mut masses, mut radii): (
+            ReadStorage<Identifier>,
+            WriteStorage<Position>,
+            WriteStorage<Velocity>,
+            WriteStorage<Mass>,
+            WriteStorage<Radius>,
+        )| {
+            let (_, position, velocity, mass, radius) = (
+                &ids,
+                &mut positions,
+                &mut velocities,
+                &mut masses,
+                (&mut radii).maybe(),
+            )
+                .join()
+                .find(|(id, ..)| id.get_id() == params.id)
+                .ok_or_else(|| RpcError::invalid_params(format!("Unknown body: {}", params.id)))?;
+
+            if let Some(new_position) = params.position {
+                position.0 = new_position.into();
+            }
+            if let Some(new_velocity) = params.velocity {
+                velocity.0 = new_velocity.into();
+            }
+            if let Some(new_mass) = params.mass {
+                mass.0 = new_mass;
+            }
+            if let (Some(radius), Some(new_radius)) = (radius, params.radius) {
+                radius.0 = new_radius;
+            }
+
+            Ok(Value::Null)
+        },
+    )
+}
+
+fn set_time_scale(world: &mut World, params: SetTimeScale) -> Result<Value, RpcError> {
+    //! Change the time scale as the UI does, returning the new one
+
+    let mut time_scale = world.write_resource::<TimeScale>();
+
+    if let Some(new_time_scale) = params.time_scale {
+        if new_time_scale < 0.0 {
+            return Err(RpcError::invalid_params("Time scale can't be negative"));
+        }
+        *time_scale = TimeScale {
+            paused: time_scale.paused,
+            ..TimeScale::from_max_time_per_iteration(new_time_scale, 86400.0)
+        };
+    }
+    if let Some(paused) = params.paused {
+        time_scale.paused = paused;
+    }
+
+    Ok(json!({
+        "time_scale": time_scale.total_time_elapsed,
+        "paused": time_scale.paused,
+    }))
+}
@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// A JSON-RPC 2.0 request, or a notification if it has no id
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+/// An error sent back to the client, with one of the codes from the JSON-RPC
+/// specification
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+impl RpcError {
+    pub fn parse(message: impl ToString) -> Self {
+        Self {
+            code: -32700,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn invalid_request(message: impl ToString) -> Self {
+        Self {
+            code: -32600,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: -32601,
+            message: format!("Unknown method: {}", method),
+        }
+    }
+
+    pub fn invalid_params(message: impl ToString) -> Self {
+        Self {
+            code: -32602,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn internal(message: impl ToString) -> Self {
+        Self {
+            code: -32603,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Changes to a body, leaving anything not given as it was
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetBody {
+    pub id: String,
+    /// (in m)
+    pub position: Option<[f64; 3]>,
+    /// (in m/s)
+    pub velocity: Option<[f64; 3]>,
+    /// (in kg)
+    pub mass: Option<f64>,
+    /// (in m)
+    pub radius: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SetTimeScale {
+    /// Simulated seconds for every real second
+    pub time_scale: Option<f64>,
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Save {
+    /// Where to save the simulation, in the format of its extension
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Screenshot {
+    /// Where to save the screenshot, the screenshot directory if not given
+    pub path: Option<PathBuf>,
+}
+
+/// The commands a remote client can send
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// The whole simulation, as it would be saved
+    GetState,
+    SetBody(SetBody),
+    SetTimeScale(SetTimeScale),
+    Save(Save),
+    Screenshot(Screenshot),
+}
+impl Command {
+    fn from_request(method: &str, params: Value) -> Result<Self, RpcError> {
+        Ok(match method {
+            "get_state" => Self::GetState,
+            "set_body" => Self::SetBody(parse_params(params)?),
+            "set_time_scale" => Self::SetTimeScale(parse_params(params)?),
+            "save" => Self::Save(parse_params(params)?),
+            "screenshot" => Self::Screenshot(parse_params(params)?),
+            _ => return Err(RpcError::method_not_found(method)),
+        })
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    //! Read the parameters of a method, which can be left out when none are
+    //! needed
+
+    let params = match params {
+        Value::Null => Value::Object(Default::default()),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+/// A request read from a client, which is answered if it has an id
+pub struct Message {
+    id: Option<Value>,
+    pub command: Result<Command, RpcError>,
+}
+impl Message {
+    pub fn parse(text: &str) -> Self {
+        let request: Request = match serde_json::from_str(text) {
+            Ok(request) => request,
+            // The id can't be known, so it is null as the specification says
+            Err(err) if err.is_data() => {
+                return Self {
+                    id: Some(Value::Null),
+                    command: Err(RpcError::invalid_request(err)),
+                }
+            }
+            Err(err) => {
+                return Self {
+                    id: Some(Value::Null),
+                    command: Err(RpcError::parse(err)),
+                }
+            }
+        };
+
+        let command = if request.jsonrpc != "2.0" {
+            Err(RpcError::invalid_request("Only JSON-RPC 2.0 is supported"))
+        } else {
+            Command::from_request(&request.method, request.params)
+        };
+
+        Self {
+            id: request.id,
+            command,
+        }
+    }
+
+    pub fn respond(&self, result: Result<Value, RpcError>) -> Option<String> {
+        //! The text to send back to the client, or none if the request was a
+        //! notification
+
+        let id = self.id.clone()?;
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(err) => (None, Some(err)),
+        };
+
+        serde_json::to_string(&Response {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        })
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{Command, Message, RpcError, SetBody};
+
+    #[test]
+    fn test_commands_parsed() {
+        let message = Message::parse(
+            r#"{"jsonrpc":"2.0","id":1,"method":"set_body","params":{"id":"earth","mass":6e24}}"#,
+        );
+        assert_eq!(
+            Ok(Command::SetBody(SetBody {
+                id: "earth".to_string(),
+                mass: Some(6e24),
+                ..Default::default()
+            })),
+            message.command
+        );
+
+        let message = Message::parse(r#"{"jsonrpc":"2.0","id":"a","method":"get_state"}"#);
+        assert_eq!(Ok(Command::GetState), message.command);
+        assert_eq!(
+            json!({"jsonrpc": "2.0", "id": "a", "result": true}),
+            serde_json::from_str::<Value>(&message.respond(Ok(json!(true))).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors_returned() {
+        let message = Message::parse(r#"{"jsonrpc":"2.0","id":2,"method":"launch"}"#);
+        assert_eq!(Err(RpcError::method_not_found("launch")), message.command);
+
+        let message = Message::parse(r#"{"jsonrpc":"2.0","id":3,"method":"save"}"#);
+        assert_eq!(-32602, message.command.unwrap_err().code);
+
+        let message = Message::parse("{");
+        let response: Value = serde_json::from_str(
+            &message
+                .respond(message.command.clone().map(|_| json!(null)))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json!(-32700), response["error"]["code"]);
+        assert_eq!(Value::Null, response["id"]);
+
+        // Notifications are never answered
+        let message = Message::parse(r#"{"jsonrpc":"2.0","method":"get_state"}"#);
+        assert_eq!(None, message.respond(Ok(json!(null))));
+    }
+}
@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use cgmath::{InnerSpace, Vector3, Zero};
+use specs::{Entities, Join, Read, ReadStorage, World};
+
+use crate::{
+    simulation::{
+        GravitationalConstant, InteractionHandler, Mass, Position, PositionScaleFactor, Velocity,
+    },
+    util::AU,
+};
+
+use super::{components::BodyStyle, lines::LineVertex};
+
+/// Length of an arrow for each factor of ten its vector is above the
+/// reference (in AU)
+const ARROW_LENGTH_PER_DECADE: f64 = 0.1;
+
+/// The speed and acceleration an arrow starts growing from, well below any of
+/// the planets' (in m/s and m/s^2)
+const VELOCITY_REFERENCE: f64 = 1000.0;
+const ACCELERATION_REFERENCE: f64 = 1e-6;
+
+/// Length of each side of an arrow head, relative to the arrow
+const HEAD_LENGTH: f64 = 0.2;
+
+const VELOCITY_COLOUR: [f32; 4] = [0.3, 0.9, 0.4, 1.0];
+const ACCELERATION_COLOUR: [f32; 4] = [1.0, 0.4, 0.3, 1.0];
+
+/// Which vectors are drawn as arrows from the bodies, to show why their
+/// orbits curve
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArrowSettings {
+    pub velocity: bool,
+    /// The net gravitational acceleration
+    pub acceleration: bool,
+}
+
+fn arrow_length(magnitude: f64, reference: f64) -> f64 {
+    //! The length of an arrow for a vector (in AU), which grows with the
+    //! logarithm of its magnitude so the fastest bodies don't dwarf the rest
+    (1.0 + magnitude / reference).log10() * ARROW_LENGTH_PER_DECADE
+}
+
+fn push_arrow(
+    vertices: &mut Vec<LineVertex>,
+    ranges: &mut Vec<Range<u32>>,
+    from: Vector3<f64>,
+    vector: Vector3<f64>,
+    length: f64,
+    colour: [f32; 4],
+) {
+    //! Add the shaft and head of an arrow, with positions in the scene's
+    //! units
+
+    if vector.is_zero() || length <= 0.0 {
+        return;
+    }
+    let direction = vector.normalize();
+    let tip = from + direction * length;
+
+    // The head is drawn in the plane containing the ecliptic's normal, unless
+    // the arrow points along it
+    let side = direction.cross(Vector3::unit_z());
+    let side = if side.magnitude2() > 1e-6 {
+        side.normalize()
+    } else {
+        Vector3::unit_x()
+    };
+    let back = tip - direction * length * HEAD_LENGTH;
+    let spread = side * length * HEAD_LENGTH / 2.0;
+
+    let mut push_line = |points: &[Vector3<f64>]| {
+        let start = vertices.len() as u32;
+        vertices.extend(
+            points
+                .iter()
+                .map(|point| LineVertex::new(point.map(|a| a as f32).into(), colour)),
+        );
+        ranges.push(start..vertices.len() as u32);
+    };
+    push_line(&[from, tip]);
+    push_line(&[back + spread, tip, back - spread]);
+}
+
+pub fn build_vertices(world: &World, vertices: &mut Vec<LineVertex>, ranges: &mut Vec<Range<u32>>) {
+    //! Add the vertices of the velocity and acceleration arrows of each body
+    //! that has them shown, along with the range of vertices each line is
+    //! drawn from
+
+    let (
+        entities,
+        positions,
+        velocities,
+        masses,
+        handlers,
+        styles,
+        gravitational_constant,
+        scale_factor,
+        settings,
+    ): (
+        Entities,
+        ReadStorage<Position>,
+        ReadStorage<Velocity>,
+        ReadStorage<Mass>,
+        ReadStorage<InteractionHandler>,
+        ReadStorage<BodyStyle>,
+        Read<GravitationalConstant>,
+        Read<PositionScaleFactor>,
+        Read<ArrowSettings>,
+    ) = world.system_data();
+
+    if !settings.velocity && !settings.acceleration {
+        return;
+    }
+    let au = AU / scale_factor.0;
+
+    for (entity, position, velocity, handler, style) in (
+        &entities,
+        &positions,
+        &velocities,
+        &handlers,
+        styles.maybe(),
+    )
+        .join()
+    {
+        if !style.map_or(true, |style| style.arrows_shown) {
+            continue;
+        }
+        let from = position.0 / scale_factor.0;
+
+        if settings.velocity {
+            let length = arrow_length(velocity.0.magnitude(), VELOCITY_REFERENCE) * au;
+            push_arrow(vertices, ranges, from, velocity.0, length, VELOCITY_COLOUR);
+        }
+
+        if settings.acceleration {
+            // Pulled by the same bodies as in the simulation
+            let acceleration = (&entities, &positions, &masses, &handlers)
+                .join()
+                .filter(|(other, _, _, _)| *other != entity)
+                .filter(|(_, _, _, other_handler)| handler.interacts_with(other_handler.body_type))
+                .map(|(_, other, mass, _)| {
+                    let r = other.0 - position.0;
+                    gravitational_constant.0 * mass.0 / r.magnitude2() * r.normalize()
+                })
+                .fold(Vector3::zero(), |a, b| a + b);
+
+            let length = arrow_length(acceleration.magnitude(), ACCELERATION_REFERENCE) * au;
+            push_arrow(
+                vertices,
+                ranges,
+                from,
+                acceleration,
+                length,
+                ACCELERATION_COLOUR,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{arrow_length, ARROW_LENGTH_PER_DECADE};
+
+    #[test]
+    fn test_arrow_length_is_logarithmic() {
+        assert_eq!(0.0, arrow_length(0.0, 1.0));
+
+        // Each factor of ten adds the same length
+        let short = arrow_length(99.0, 1.0);
+        let long = arrow_length(999.0, 1.0);
+        assert!((short - 2.0 * ARROW_LENGTH_PER_DECADE).abs() < 1e-9);
+        assert!((long - short - ARROW_LENGTH_PER_DECADE).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,134 @@
+use specs::{Component, VecStorage};
+
+use super::{light::include_lit_wgsl, texture, vertex};
+
+/// A glow around the edge of a body, approximating the scattering of light
+/// by its atmosphere
+#[derive(Debug, Clone, Copy, Component)]
+#[storage(VecStorage)]
+pub struct Atmosphere {
+    pub colour: [f32; 3],
+    /// How far the atmosphere extends above the surface, as a fraction of the
+    /// body's radius
+    pub thickness: f32,
+}
+impl Atmosphere {
+    pub fn new(colour: [f32; 3], thickness: f32) -> Self {
+        Self { colour, thickness }
+    }
+
+    pub fn for_body(id: &str) -> Option<Self> {
+        //! The atmosphere a body starts with, if it has a noticeable one
+        match id {
+            "venus" => Some(Self::new([1.0, 0.9, 0.6], 0.06)),
+            "earth" => Some(Self::new([0.3, 0.6, 1.0], 0.05)),
+            "mars" => Some(Self::new([1.0, 0.6, 0.4], 0.02)),
+            "jupiter" => Some(Self::new([0.9, 0.8, 0.6], 0.04)),
+            "saturn" => Some(Self::new([0.9, 0.85, 0.6], 0.04)),
+            "uranus" => Some(Self::new([0.6, 0.9, 1.0], 0.05)),
+            "neptune" => Some(Self::new([0.4, 0.6, 1.0], 0.05)),
+            _ => None,
+        }
+    }
+
+    pub fn to_raw(&self) -> AtmosphereRaw {
+        AtmosphereRaw {
+            colour: self.colour,
+            thickness: self.thickness,
+        }
+    }
+}
+
+/// The raw representation of an atmosphere, drawn as a second instance
+/// buffer alongside the body's instance
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct AtmosphereRaw {
+    colour: [f32; 3],
+    thickness: f32,
+}
+impl vertex::Vertex for AtmosphereRaw {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<AtmosphereRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 12,
+                format: wgpu::VertexFormat::Float32x4,
+            }],
+        }
+    }
+}
+
+pub fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    colour_format: wgpu::TextureFormat,
+    vertex_layouts: &[wgpu::VertexBufferLayout],
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline for atmospheres, which are blended on top
+    //! of the bodies and do not hide anything behind them
+
+    let shader = device.create_shader_module(include_lit_wgsl!("shaders/atmosphere.wgsl"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Atmosphere Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: vertex_layouts,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: colour_format,
+                // Additive blending, so the glow brightens what is behind it
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::SrcAlpha,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent::OVER,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: texture::Texture::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: texture::Texture::DEPTH_COMPARE,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+pub fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    //! Creates a buffer that can hold the raw atmospheres of `capacity` bodies
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Atmosphere Instance Buffer"),
+        size: (capacity * std::mem::size_of::<AtmosphereRaw>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
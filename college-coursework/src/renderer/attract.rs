@@ -0,0 +1,153 @@
+use cgmath::{EuclideanSpace, Point3, Vector3};
+use instant::{Duration, Instant};
+use rand::Rng;
+use specs::{Join, Read, ReadStorage, World, WorldExt};
+
+use crate::{simulation::Identifier, stats::UsageStats};
+
+use super::{camera::Camera, components::RenderModel};
+
+/// How long the camera stays on each body before moving on
+const TIME_PER_BODY: f32 = 30.0;
+/// How long the camera takes to move between bodies
+const TRANSITION_TIME: f32 = 5.0;
+/// Distance of the camera from the body it is orbiting
+const ORBIT_DISTANCE: f32 = 25.0;
+/// Angular speed of the camera around the body (in radians per second)
+const ORBIT_SPEED: f32 = 0.1;
+
+/// Options for the attract mode shown when the application is left idle
+#[derive(Debug, Clone, Copy)]
+pub struct AttractSettings {
+    pub enabled: bool,
+    /// Seconds without input before the attract mode starts
+    pub idle_timeout: f32,
+}
+impl Default for AttractSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_timeout: 120.0,
+        }
+    }
+}
+
+/// Slowly orbits the camera around random bodies while the application is idle
+pub struct AttractMode {
+    last_input: Instant,
+    saved_camera: Option<Camera>,
+    target: Option<Identifier>,
+    previous_focus: Point3<f32>,
+    time_on_target: f32,
+    angle: f32,
+}
+impl AttractMode {
+    pub fn new() -> Self {
+        Self {
+            last_input: Instant::now(),
+            saved_camera: None,
+            target: None,
+            previous_focus: Point3::origin(),
+            time_on_target: 0.0,
+            angle: 0.0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.saved_camera.is_some()
+    }
+
+    pub fn register_input(&mut self, camera: &mut Camera) -> bool {
+        //! Record user input, restoring the camera if attract mode was active.
+        //! Returns whether the input was used to leave attract mode
+
+        self.last_input = Instant::now();
+
+        match self.saved_camera.take() {
+            Some(saved_camera) => {
+                *camera = saved_camera;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn update(&mut self, dt: Duration, camera: &mut Camera, world: &World) -> bool {
+        //! Move the camera if attract mode is active, starting it if the
+        //! application has been idle for long enough. Returns whether the
+        //! camera is being controlled by attract mode
+
+        let (settings, ids, models): (
+            Read<AttractSettings>,
+            ReadStorage<Identifier>,
+            ReadStorage<RenderModel>,
+        ) = world.system_data();
+
+        if !settings.enabled {
+            if let Some(saved_camera) = self.saved_camera.take() {
+                *camera = saved_camera;
+            }
+            return false;
+        }
+
+        if !self.is_active() {
+            if self.last_input.elapsed().as_secs_f32() < settings.idle_timeout {
+                return false;
+            }
+
+            self.saved_camera = Some(*camera);
+            world
+                .write_resource::<UsageStats>()
+                .use_feature("Attract mode");
+            self.target = None;
+            self.previous_focus = camera.position;
+        }
+
+        let bodies = (&ids, &models)
+            .join()
+            .map(|(id, model)| (id.clone(), Point3::from_vec(model.instance.position)))
+            .collect::<Vec<_>>();
+
+        if bodies.is_empty() {
+            return true;
+        }
+
+        self.time_on_target += dt.as_secs_f32();
+        self.angle += ORBIT_SPEED * dt.as_secs_f32();
+
+        // Find the position of the current target, it may have been removed
+        let target_position = self.target.as_ref().and_then(|target| {
+            bodies
+                .iter()
+                .find(|(id, _)| id.get_id() == target.get_id())
+                .map(|(_, position)| *position)
+        });
+
+        let target_position = match target_position {
+            Some(position) if self.time_on_target < TIME_PER_BODY => position,
+            _ => {
+                // Move on to a random body, starting from the current focus
+                if let Some(position) = target_position {
+                    self.previous_focus = position;
+                }
+
+                let (id, position) = &bodies[rand::thread_rng().gen_range(0..bodies.len())];
+                self.target = Some(id.clone());
+                self.time_on_target = 0.0;
+
+                *position
+            }
+        };
+
+        // Ease the focus from the previous body to the new target
+        let t = (self.time_on_target / TRANSITION_TIME).min(1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        let focus = self.previous_focus + (target_position - self.previous_focus) * t;
+
+        camera.position =
+            focus + Vector3::new(self.angle.cos(), 0.35, self.angle.sin()) * ORBIT_DISTANCE;
+        camera.look_at(focus);
+
+        true
+    }
+}
@@ -1,10 +1,11 @@
 use std::f32::consts::FRAC_PI_2;
 
 use cgmath::{
-    perspective, Angle, EuclideanSpace, Euler, InnerSpace, Matrix3, Matrix4, Point3, Quaternion,
-    Rad, Rotation, Transform, Vector3,
+    Angle, EuclideanSpace, Euler, InnerSpace, Matrix, Matrix3, Matrix4, One, Point3, Quaternion,
+    Rad, Rotation, Transform, Vector3, Vector4,
 };
 use instant::Duration;
+use serde::{Deserialize, Serialize};
 use specs::{Component, VecStorage};
 use winit::{
     dpi::PhysicalPosition,
@@ -13,18 +14,14 @@ use winit::{
     },
 };
 
-use crate::renderer::camera;
-
-#[rustfmt::skip]
-pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
-    1.0, 0.0, 0.0, 0.0,
-    0.0, 1.0, 0.0, 0.0,
-    0.0, 0.0, 0.5, 0.0,
-    0.0, 0.0, 0.5, 1.0,
-);
+use crate::renderer::{camera, gamepad::GamepadInput};
 
 const SAFE_FRAC_PI_2: f32 = FRAC_PI_2 - 0.0001;
 
+/// How fast a gamepad stick pushed all the way turns the camera (in radians
+/// per second, before the pan sensitivity)
+const GAMEPAD_TURN_RATE: f32 = 1.5;
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct CameraCenterUniform {
@@ -75,16 +72,41 @@ impl Default for CameraPosition {
     }
 }
 
+/// How fast the camera moves when the program starts
+pub const DEFAULT_CAMERA_SPEED: f32 = 20.0;
+
 /// Container to store the speed of the camera in the Entity Component System
 #[derive(Debug, Component, Default)]
 #[storage(VecStorage)]
 pub struct CameraSpeed(pub f32);
 
+/// Container to store the rotation of the camera in the Entity Component System
+#[derive(Debug, Component)]
+#[storage(VecStorage)]
+pub struct CameraRotation(pub Quaternion<f32>);
+impl Default for CameraRotation {
+    fn default() -> Self {
+        Self(Quaternion::one())
+    }
+}
+
+/// The kind of controller moving the camera
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CameraControllerType {
+    Free,
+    Orbit,
+}
+impl Default for CameraControllerType {
+    fn default() -> Self {
+        Self::Free
+    }
+}
+
 /// Data structure that stores the position and rotation of the camera
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Camera {
     pub position: Point3<f32>,
-    rotation: Quaternion<f32>,
+    pub rotation: Quaternion<f32>,
 }
 
 impl Camera {
@@ -106,6 +128,42 @@ impl Camera {
             self.rotation.rotate_vector(Vector3::unit_y()),
         )
     }
+
+    pub fn look_at(&mut self, target: Point3<f32>) {
+        //! Rotate the camera to face a point, keeping the y-axis up
+
+        let direction = (target - self.position).normalize();
+        self.rotation =
+            Quaternion::from(Matrix3::look_to_lh(direction, Vector3::unit_y()).transpose());
+    }
+
+    pub fn project_to_screen(
+        &self,
+        projection: &Projection,
+        position: Vector3<f32>,
+        screen: egui::Rect,
+    ) -> Option<egui::Pos2> {
+        //! Where a point in the world is drawn on the screen, or none if it is
+        //! behind the camera or off the screen
+
+        let clip = projection.calc_matrix()
+            * self.calc_matrix()
+            * Vector4::new(position.x, position.y, position.z, 1.0);
+        // With reversed-Z a point in front of the camera always has a positive w
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        // Convert from normalised device coordinates to screen coordinates
+        let ndc = clip.truncate() / clip.w;
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            return None;
+        }
+        Some(egui::pos2(
+            screen.left() + (ndc.x + 1.0) / 2.0 * screen.width(),
+            screen.top() + (1.0 - ndc.y) / 2.0 * screen.height(),
+        ))
+    }
 }
 
 /// Projection of the camera
@@ -132,9 +190,60 @@ impl Projection {
         self.aspect = width as f32 / height as f32;
     }
 
+    pub fn pixel_size(&self, height: u32) -> f32 {
+        //! The height of a pixel at a distance of 1 from the camera, in a
+        //! window `height` pixels tall
+        2.0 * (self.fovy / 2.0).tan() / height.max(1) as f32
+    }
+
+    #[rustfmt::skip]
     pub fn calc_matrix(&self) -> Matrix4<f32> {
-        //! Return the transformation matrix
-        OPENGL_TO_WGPU_MATRIX * cgmath::perspective(self.fovy, self.aspect, self.znear, self.zfar)
+        //! Return the transformation matrix, using reversed-Z so the near plane
+        //! has a depth of 1 and the far plane 0. Floating point depth values
+        //! are most precise close to 0, which reversing spreads evenly over
+        //! the distance, so far away bodies do not z-fight
+
+        let f = 1.0 / (self.fovy / 2.0).tan();
+        let (near, far) = (self.znear, self.zfar);
+
+        Matrix4::new(
+            f / self.aspect, 0.0, 0.0, 0.0,
+            0.0, f, 0.0, 0.0,
+            0.0, 0.0, near / (far - near), -1.0,
+            0.0, 0.0, near * far / (far - near), 0.0,
+        )
+    }
+}
+
+/// The space the camera can see, bounded by six planes
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    /// The normal of each plane points into the frustum, they aren't
+    /// normalised
+    planes: [Vector4<f32>; 6],
+}
+impl Frustum {
+    pub fn new(camera: &Camera, projection: &Projection) -> Self {
+        //! The frustum of the camera, found from the rows of its view
+        //! projection matrix
+
+        let matrix = projection.calc_matrix() * camera.calc_matrix();
+        let [x, y, z, w] = [0, 1, 2, 3].map(|i| matrix.row(i));
+
+        // A point is inside when -w <= x <= w, -w <= y <= w and 0 <= z <= w,
+        // using reversed-Z the far plane is at z = 0 and the near at z = w
+        Self {
+            planes: [w + x, w - x, w + y, w - y, z, w - z],
+        }
+    }
+
+    pub fn intersects_sphere(&self, centre: Vector3<f32>, radius: f32) -> bool {
+        //! Whether any part of a sphere is inside the frustum
+
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            normal.dot(centre) + plane.w >= -radius * normal.magnitude()
+        })
     }
 }
 
@@ -144,6 +253,7 @@ pub trait CameraController {
     fn process_mouse_button_event(&mut self, button: MouseButton, state: ElementState);
     fn process_mouse_scroll_event(&mut self, delta: MouseScrollDelta);
     fn process_mouse_move_event(&mut self, dx: f64, dy: f64);
+    fn process_gamepad_input(&mut self, input: GamepadInput);
     fn update_camera(&mut self, camera: &mut Camera, dt: Duration);
 
     fn get_speed(&self) -> f32;
@@ -165,6 +275,7 @@ pub struct FreeCameraController {
     rotate_horizontal: f32,
     rotate_vertical: f32,
     scroll: f32,
+    gamepad: GamepadInput,
     speed: f32,
     scroll_sensitivity: f32,
     pan_sensitivity: f32,
@@ -193,6 +304,7 @@ impl FreeCameraController {
             rotate_horizontal: 0.0,
             rotate_vertical: 0.0,
             scroll: 0.0,
+            gamepad: GamepadInput::default(),
             speed,
             scroll_sensitivity,
             pan_sensitivity,
@@ -268,20 +380,35 @@ impl CameraController for FreeCameraController {
         }
     }
 
+    fn process_gamepad_input(&mut self, input: GamepadInput) {
+        //! Handle the sticks and triggers of a gamepad, which stay where they
+        //! are until the next input
+
+        self.gamepad = input;
+    }
+
     fn update_camera(&mut self, camera: &mut Camera, dt: Duration) {
         //! Update the camera using the camera controller
 
         let dt = dt.as_secs_f32();
 
-        // Move forward/backward, left/right and up/down
+        // Move forward/backward, left/right and up/down, with the left stick
+        // moving the same way as the keys
         let right = camera.rotation.rotate_vector(Vector3::unit_x());
         let up = camera.rotation.rotate_vector(Vector3::unit_y());
         let forward = camera.rotation.rotate_vector(Vector3::unit_z());
-        camera.position += forward * (self.amount_forward - self.amount_backward) * self.speed * dt;
-        camera.position += -right * (self.amount_right - self.amount_left) * self.speed * dt;
+        let amount_forward = (self.amount_forward - self.amount_backward
+            + self.gamepad.movement[1])
+            .clamp(-1.0, 1.0);
+        let amount_right =
+            (self.amount_right - self.amount_left + self.gamepad.movement[0]).clamp(-1.0, 1.0);
+        camera.position += forward * amount_forward * self.speed * dt;
+        camera.position += -right * amount_right * self.speed * dt;
         camera.position += up * (self.amount_up - self.amount_down) * self.speed * dt;
 
         self.speed *= 2_f32.powf(self.scroll * 1e-2 * self.scroll_sensitivity);
+        // The triggers double or halve the speed every second
+        self.speed *= 2_f32.powf(self.gamepad.speed * dt);
         if self.speed < 0.1 {
             self.speed = 0.1;
         }
@@ -289,10 +416,14 @@ impl CameraController for FreeCameraController {
         self.scroll = 0.0;
 
         // Rotate the camera yaw, pitch and roll
+        let rotate_horizontal = self.rotate_horizontal + self.gamepad.look[0] * GAMEPAD_TURN_RATE;
+        let rotate_vertical = self.rotate_vertical - self.gamepad.look[1] * GAMEPAD_TURN_RATE;
+        let amount_roll =
+            (self.amount_roll_left - self.amount_roll_right - self.gamepad.roll).clamp(-1.0, 1.0);
         let rotation = Quaternion::from(Euler {
-            x: Rad(self.rotate_vertical) * self.pan_sensitivity * dt,
-            y: Rad(-self.rotate_horizontal) * self.pan_sensitivity * dt,
-            z: Rad(self.amount_roll_left - self.amount_roll_right) * self.roll_sensitivity * dt,
+            x: Rad(rotate_vertical) * self.pan_sensitivity * dt,
+            y: Rad(-rotate_horizontal) * self.pan_sensitivity * dt,
+            z: Rad(amount_roll) * self.roll_sensitivity * dt,
         });
 
         camera.rotation = camera.rotation * rotation;
@@ -314,3 +445,26 @@ impl CameraController for FreeCameraController {
         self.speed = speed;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, Point3, Quaternion, Vector3, Zero};
+
+    use super::{Camera, Frustum, Projection};
+
+    #[test]
+    fn test_frustum_intersects_spheres_in_view() {
+        let mut camera = Camera::new(Point3::new(0.0, 0.0, 0.0), Quaternion::zero());
+        camera.look_at(Point3::new(1.0, 0.0, 0.0));
+        let projection = Projection::new(800, 600, Deg(45.0), 0.1, 100.0);
+        let frustum = Frustum::new(&camera, &projection);
+
+        assert!(frustum.intersects_sphere(Vector3::new(10.0, 0.0, 0.0), 1.0));
+        // Behind the camera and past the far plane
+        assert!(!frustum.intersects_sphere(Vector3::new(-10.0, 0.0, 0.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vector3::new(200.0, 0.0, 0.0), 1.0));
+        // Off to the side, unless it is big enough to reach into view
+        assert!(!frustum.intersects_sphere(Vector3::new(10.0, 50.0, 0.0), 1.0));
+        assert!(frustum.intersects_sphere(Vector3::new(10.0, 50.0, 0.0), 50.0));
+    }
+}
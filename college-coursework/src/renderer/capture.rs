@@ -0,0 +1,48 @@
+use log::{info, warn};
+use renderdoc::{RenderDoc, V110};
+use specs::{World, WorldExt};
+
+/// Set to capture the next frame with RenderDoc, from the hotkey or anywhere
+/// else with access to the world
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameCaptureRequest(pub bool);
+impl FrameCaptureRequest {
+    pub fn request(&mut self) {
+        self.0 = true;
+    }
+}
+
+/// Triggers captures of frames through RenderDoc's in-application API, which
+/// is only available when the program is launched from RenderDoc
+pub struct FrameCapturer {
+    /// None until a capture is first requested, then whether RenderDoc was
+    /// found
+    renderdoc: Option<Option<RenderDoc<V110>>>,
+}
+impl FrameCapturer {
+    pub fn new() -> Self {
+        Self { renderdoc: None }
+    }
+
+    pub fn update(&mut self, world: &World) {
+        //! Trigger a capture of the next frame if one was requested
+
+        if !std::mem::take(&mut world.write_resource::<FrameCaptureRequest>().0) {
+            return;
+        }
+
+        let renderdoc = self.renderdoc.get_or_insert_with(|| {
+            RenderDoc::new()
+                .map_err(|err| warn!("RenderDoc is not available: {}", err))
+                .ok()
+        });
+
+        match renderdoc {
+            Some(renderdoc) => {
+                renderdoc.trigger_capture();
+                info!("Capturing the next frame with RenderDoc");
+            }
+            None => warn!("Can't capture the frame, launch the program from RenderDoc"),
+        }
+    }
+}
@@ -1,17 +1,56 @@
+use std::sync::Arc;
+
 use cgmath::{EuclideanSpace, Point3, Quaternion, Vector3, Zero};
 use specs::{
-    Component, Join, Read, ReadExpect, ReadStorage, System, VecStorage, Write, WriteExpect,
+    Component, Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, VecStorage, World,
+    Write, WriteExpect, WriteStorage,
 };
 use wgpu::util::DeviceExt;
 
-use crate::simulation::{Identifier, Position, PositionScaleFactor};
+use crate::{
+    accessibility::AccessibilitySettings,
+    models::cache::{ModelCache, Surface},
+    simulation::{Identifier, Mass, Position, PositionScaleFactor},
+};
 
-use super::{camera::CameraPosition, instance::Instance, model::Model};
+use super::{camera::CameraPosition, instance::Instance, light::Light, model::Model};
 
 #[derive(Component)]
 #[storage(VecStorage)]
 pub struct PlanetColour(pub [f32; 4]);
 
+/// How a body's trail and label are drawn, chosen in the body's window
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[storage(VecStorage)]
+pub struct BodyStyle {
+    /// The colour of the body's trail when trails are coloured by body, the
+    /// body's own colour if None
+    pub trail_colour: Option<[f32; 3]>,
+    pub label_shown: bool,
+    /// Whether arrows are drawn from the body, when they are turned on
+    pub arrows_shown: bool,
+}
+impl Default for BodyStyle {
+    fn default() -> Self {
+        Self {
+            trail_colour: None,
+            label_shown: true,
+            arrows_shown: true,
+        }
+    }
+}
+
+/// The asset path of a body's equirectangular surface texture
+#[derive(Component)]
+#[storage(VecStorage)]
+pub struct PlanetTexture(pub String);
+
+/// The detail level of the icosphere a body's model was made from, kept so
+/// the model can be made again
+#[derive(Component)]
+#[storage(VecStorage)]
+pub struct SphereDetail(pub usize);
+
 /// Represents a model in the Entity COmponent System
 #[derive(Component)]
 #[storage(VecStorage)]
@@ -46,16 +85,25 @@ impl RenderModel {
         }
     }
 
+    pub fn bounding_sphere(&self) -> (Vector3<f32>, f32) {
+        //! The centre and radius of a sphere around the whole model, where
+        //! it is in the scene
+        self.model.bounds.sphere(&self.instance)
+    }
+
     pub fn update_instance(
         &mut self,
         queue: &wgpu::Queue,
         position: Vector3<f32>,
         rotation: Quaternion<f32>,
+        scale: f32,
     ) {
-        //! Updates the position of the model for use by the GPU
+        //! Updates the position, rotation and scale of the model for use by
+        //! the GPU
 
         self.instance.position = position;
         self.instance.rotation = rotation;
+        self.instance.scale = scale;
 
         queue.write_buffer(
             &self.instance_buffer,
@@ -65,19 +113,134 @@ impl RenderModel {
     }
 }
 
+pub fn rebuild_body_models(world: &World) {
+    //! Make the model of every body again with the device in the world, after
+    //! the device it was made with has been lost or the palette has changed.
+    //! Everything is rebuilt from the body's colour and texture path, or the
+    //! surface generated for it
+
+    rebuild_models(world, None);
+}
+
+pub fn rebuild_body_model(world: &World, body: Entity) {
+    //! Make the model of a single body again, after its colour has changed
+
+    rebuild_models(world, Some(body));
+}
+
+fn rebuild_models(world: &World, only: Option<Entity>) {
+    let (
+        device,
+        queue,
+        layout,
+        accessibility_settings,
+        entities,
+        ids,
+        colours,
+        textures,
+        details,
+        masses,
+        lights,
+        mut render_models,
+        mut model_cache,
+    ): (
+        ReadExpect<Arc<wgpu::Device>>,
+        ReadExpect<Arc<wgpu::Queue>>,
+        ReadExpect<Arc<wgpu::BindGroupLayout>>,
+        Read<AccessibilitySettings>,
+        Entities,
+        ReadStorage<Identifier>,
+        ReadStorage<PlanetColour>,
+        ReadStorage<PlanetTexture>,
+        ReadStorage<SphereDetail>,
+        ReadStorage<Mass>,
+        ReadStorage<Light>,
+        WriteStorage<RenderModel>,
+        Write<ModelCache>,
+    ) = world.system_data();
+
+    for (entity, id, colour, texture, detail, mass, render_model) in (
+        &entities,
+        &ids,
+        &colours,
+        (&textures).maybe(),
+        &details,
+        (&masses).maybe(),
+        &mut render_models,
+    )
+        .join()
+        .filter(|(entity, ..)| only.map_or(true, |only| only == *entity))
+    {
+        let model = model_cache.body_model(
+            &device,
+            &queue,
+            1.0,
+            detail.0,
+            accessibility_settings.palette.body_colour(entity, colour.0),
+            Surface::for_body(
+                id.get_id(),
+                texture.map(|texture| texture.0.as_str()),
+                mass.map_or(0.0, |mass| mass.0),
+                lights.contains(entity),
+            ),
+            &layout,
+        );
+        let instance = Instance {
+            position: render_model.instance.position,
+            rotation: render_model.instance.rotation,
+            scale: render_model.instance.scale,
+        };
+
+        *render_model = RenderModel::new(
+            &device,
+            model,
+            instance,
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            Some(id.get_id()),
+        );
+    }
+
+    // The bodies may no longer use what they were made from before
+    model_cache.prune();
+}
+
+/// The body the camera is centred on, which the camera can follow
 #[derive(Component)]
 #[storage(VecStorage)]
 pub struct CameraCenter {
     body: Identifier,
     displacement: Vector3<f32>,
+    /// Whether the camera moves with the body
+    pub following: bool,
+    /// Where the camera sits relative to the body while following (in
+    /// scene units)
+    pub offset: Vector3<f32>,
+    /// Roughly how long the camera takes to catch up with the body (in
+    /// seconds), 0 to stay locked to it
+    pub smoothing: f32,
+    /// Velocity of the camera as it catches up with the body
+    pub(crate) velocity: Vector3<f32>,
 }
 impl CameraCenter {
     pub fn new(body: Identifier) -> Self {
         Self {
             body,
             displacement: Vector3::<f32>::zero(),
+            following: false,
+            offset: Vector3::new(0.0, 3.0, 8.0),
+            smoothing: 0.5,
+            velocity: Vector3::zero(),
         }
     }
+
+    pub fn get_body(&self) -> &Identifier {
+        &self.body
+    }
+
+    pub fn set_body(&mut self, body: Identifier) {
+        //! Centre the camera on a different body
+        self.body = body;
+    }
 }
 
 pub struct UpdateCameraDisplacement;
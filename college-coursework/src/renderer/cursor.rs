@@ -0,0 +1,147 @@
+use specs::{Read, World};
+
+use crate::{accessibility::AccessibilitySettings, locale::tr};
+
+/// Width of the lines making up cursors before any accessibility scaling
+const CURSOR_LINE_WIDTH: f32 = 2.0;
+
+/// The shape drawn in place of the mouse cursor over the scene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorStyle {
+    System,
+    Crosshair,
+    Circle,
+    Dot,
+}
+impl CursorStyle {
+    pub const ALL: [CursorStyle; 4] = [
+        CursorStyle::System,
+        CursorStyle::Crosshair,
+        CursorStyle::Circle,
+        CursorStyle::Dot,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            CursorStyle::System => tr!("cursor-system"),
+            CursorStyle::Crosshair => tr!("cursor-crosshair"),
+            CursorStyle::Circle => tr!("cursor-circle"),
+            CursorStyle::Dot => tr!("cursor-dot"),
+        }
+    }
+}
+
+/// Options for the cursor drawn over the scene and the reticle in the
+/// center of the screen
+#[derive(Debug, Clone, Copy)]
+pub struct CursorSettings {
+    pub style: CursorStyle,
+    pub size: f32,
+    pub colour: egui::Color32,
+    pub reticle: bool,
+}
+impl Default for CursorSettings {
+    fn default() -> Self {
+        Self {
+            style: CursorStyle::System,
+            size: 12.0,
+            colour: egui::Color32::WHITE,
+            reticle: false,
+        }
+    }
+}
+
+pub fn draw_cursor(ctx: &egui::Context, world: &World) {
+    //! Paint the custom cursor and the center-screen reticle
+
+    let (cursor_settings, accessibility_settings): (
+        Read<CursorSettings>,
+        Read<AccessibilitySettings>,
+    ) = world.system_data();
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("cursor"),
+    ));
+    let stroke = egui::Stroke::new(
+        CURSOR_LINE_WIDTH * accessibility_settings.line_width_scale(),
+        cursor_settings.colour,
+    );
+    let outline = accessibility_settings.marker_outline();
+
+    if cursor_settings.reticle {
+        draw_shape(
+            &painter,
+            CursorStyle::Crosshair,
+            ctx.input().screen_rect().center(),
+            cursor_settings.size,
+            stroke,
+            outline,
+        );
+    }
+
+    // Only replace the cursor while it is over the scene, the UI keeps the
+    // system cursor
+    if cursor_settings.style == CursorStyle::System || ctx.is_pointer_over_area() {
+        return;
+    }
+
+    let hover_pos = ctx.input().pointer.hover_pos();
+    if let Some(position) = hover_pos {
+        ctx.output().cursor_icon = egui::CursorIcon::None;
+
+        draw_shape(
+            &painter,
+            cursor_settings.style,
+            position,
+            cursor_settings.size,
+            stroke,
+            outline,
+        );
+    }
+}
+
+fn draw_shape(
+    painter: &egui::Painter,
+    style: CursorStyle,
+    center: egui::Pos2,
+    size: f32,
+    stroke: egui::Stroke,
+    outline: egui::Stroke,
+) {
+    //! Draw a cursor shape, with an outline drawn beneath it if requested
+
+    let strokes = if outline.width > 0.0 {
+        vec![
+            egui::Stroke::new(stroke.width + 2.0 * outline.width, outline.color),
+            stroke,
+        ]
+    } else {
+        vec![stroke]
+    };
+
+    for stroke in strokes {
+        match style {
+            CursorStyle::System => {}
+            CursorStyle::Crosshair => {
+                // Leave a gap in the middle so the target is not covered
+                let gap = size / 4.0;
+                for direction in [
+                    egui::vec2(1.0, 0.0),
+                    egui::vec2(-1.0, 0.0),
+                    egui::vec2(0.0, 1.0),
+                    egui::vec2(0.0, -1.0),
+                ] {
+                    painter.line_segment(
+                        [center + direction * gap, center + direction * size],
+                        stroke,
+                    );
+                }
+            }
+            CursorStyle::Circle => painter.circle_stroke(center, size / 2.0, stroke),
+            CursorStyle::Dot => {
+                painter.circle_filled(center, (size + stroke.width) / 4.0, stroke.color)
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::{f32::consts::TAU, ops::Range, sync::Mutex};
+
+use cgmath::Vector3;
+use specs::{Read, World};
+
+use crate::simulation::PositionScaleFactor;
+
+use super::{
+    camera::{Camera, Projection},
+    lines::LineVertex,
+};
+
+/// Number of segments each circle of a debug sphere is drawn with
+const SPHERE_SEGMENTS: usize = 32;
+
+/// Font size of debug text
+const TEXT_FONT_SIZE: f32 = 12.0;
+
+struct DebugLine {
+    from: Vector3<f64>,
+    to: Vector3<f64>,
+    colour: [f32; 4],
+}
+
+struct DebugSphere {
+    center: Vector3<f64>,
+    radius: f64,
+    colour: [f32; 4],
+}
+
+struct DebugText {
+    position: Vector3<f64>,
+    text: String,
+    colour: [f32; 4],
+}
+
+#[derive(Default)]
+struct DebugShapes {
+    lines: Vec<DebugLine>,
+    spheres: Vec<DebugSphere>,
+    texts: Vec<DebugText>,
+}
+
+/// Shapes drawn for a single frame, to quickly see what a system is doing.
+/// Any system can push to it through a `Read<DebugDraw>` without stopping
+/// other systems running alongside it, and the renderer clears it once the
+/// shapes have been drawn. Positions are in metres, like a body's Position
+#[derive(Default)]
+pub struct DebugDraw(Mutex<DebugShapes>);
+impl DebugDraw {
+    pub fn line(&self, from: Vector3<f64>, to: Vector3<f64>, colour: [f32; 4]) {
+        //! Draw a straight line between two points
+        self.0
+            .lock()
+            .unwrap()
+            .lines
+            .push(DebugLine { from, to, colour });
+    }
+
+    pub fn sphere(&self, center: Vector3<f64>, radius: f64, colour: [f32; 4]) {
+        //! Draw the outline of a sphere, as a circle around each axis
+        self.0.lock().unwrap().spheres.push(DebugSphere {
+            center,
+            radius,
+            colour,
+        });
+    }
+
+    pub fn text(&self, position: Vector3<f64>, text: impl Into<String>, colour: [f32; 4]) {
+        //! Write text on the screen next to a point
+        self.0.lock().unwrap().texts.push(DebugText {
+            position,
+            text: text.into(),
+            colour,
+        });
+    }
+}
+
+pub fn build_vertices(world: &World, vertices: &mut Vec<LineVertex>, ranges: &mut Vec<Range<u32>>) {
+    //! Add the vertices of the debug lines and spheres pushed since the last
+    //! frame, along with the range of vertices each line is drawn from
+
+    let (debug_draw, scale_factor): (Read<DebugDraw>, Read<PositionScaleFactor>) =
+        world.system_data();
+
+    let mut shapes = debug_draw.0.lock().unwrap();
+    let to_scene = |position: Vector3<f64>| -> [f32; 3] {
+        (position / scale_factor.0).map(|a| a as f32).into()
+    };
+
+    for line in shapes.lines.drain(..) {
+        let start = vertices.len() as u32;
+        vertices.push(LineVertex::new(to_scene(line.from), line.colour));
+        vertices.push(LineVertex::new(to_scene(line.to), line.colour));
+        ranges.push(start..vertices.len() as u32);
+    }
+
+    for sphere in shapes.spheres.drain(..) {
+        let center = to_scene(sphere.center);
+        let radius = (sphere.radius / scale_factor.0) as f32;
+
+        // A circle in each of the planes between the axes
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let start = vertices.len() as u32;
+            vertices.extend((0..=SPHERE_SEGMENTS).map(|i| {
+                let angle = i as f32 / SPHERE_SEGMENTS as f32 * TAU;
+                let mut point = center;
+                point[a] += radius * angle.cos();
+                point[b] += radius * angle.sin();
+                LineVertex::new(point, sphere.colour)
+            }));
+            ranges.push(start..vertices.len() as u32);
+        }
+    }
+}
+
+pub fn draw_text(ctx: &egui::Context, world: &World, camera: &Camera, projection: &Projection) {
+    //! Paint the debug text pushed since the last frame
+
+    let (debug_draw, scale_factor): (Read<DebugDraw>, Read<PositionScaleFactor>) =
+        world.system_data();
+
+    let texts = std::mem::take(&mut debug_draw.0.lock().unwrap().texts);
+    if texts.is_empty() {
+        return;
+    }
+
+    let screen = ctx.input().screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("debug_text"),
+    ));
+
+    for text in texts {
+        let position = (text.position / scale_factor.0).map(|a| a as f32);
+
+        // Skip text behind the camera or off the screen
+        let screen_position = match camera.project_to_screen(projection, position, screen) {
+            Some(screen_position) => screen_position,
+            None => continue,
+        };
+
+        let [r, g, b, a] = text.colour;
+        painter.text(
+            screen_position,
+            egui::Align2::LEFT_BOTTOM,
+            text.text,
+            egui::FontId::monospace(TEXT_FONT_SIZE),
+            egui::Rgba::from_rgba_unmultiplied(r, g, b, a).into(),
+        );
+    }
+}
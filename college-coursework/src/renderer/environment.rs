@@ -0,0 +1,294 @@
+use std::num::NonZeroU32;
+
+use cgmath::Point3;
+use instant::{Duration, Instant};
+
+use super::light::include_lit_wgsl;
+
+/// Size of each face of the environment map at full detail
+const FACE_SIZE: u32 = 128;
+/// Number of mip levels, each prefiltered for a rougher surface than the last
+const MIP_LEVELS: u32 = 5;
+/// How long the environment map is kept before being captured again, as the
+/// sky only changes slowly
+const CAPTURE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Where the environment is captured from and for which face and roughness
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct CaptureUniform {
+    origin: [f32; 4],
+    face: u32,
+    roughness: f32,
+    _padding: [u32; 2],
+}
+
+/// A cube map of the stars and lights around the camera, which reflective
+/// materials sample so they reflect the sky. Smaller mip levels are blurred
+/// for rougher surfaces
+pub struct EnvironmentMap {
+    /// A view of each face at each mip level, to render the capture into
+    face_views: Vec<wgpu::TextureView>,
+    capture_pipeline: wgpu::RenderPipeline,
+    capture_buffer: wgpu::Buffer,
+    capture_bind_group: wgpu::BindGroup,
+    /// Distance between the uniforms of each face in the capture buffer
+    capture_stride: wgpu::BufferAddress,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    /// When the map was last captured, and whether reflections were enabled
+    last_capture: Option<(Instant, bool)>,
+}
+impl EnvironmentMap {
+    pub fn new(device: &wgpu::Device, light_bind_group_layout: &wgpu::BindGroupLayout) -> Self {
+        //! Create an empty environment map, and the pipeline that captures it
+        //! using the lights
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Environment Map"),
+            size: wgpu::Extent3d {
+                width: FACE_SIZE,
+                height: FACE_SIZE,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: MIP_LEVELS,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // A high dynamic range format, so the sun can be far brighter
+            // than the stars
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+
+        let face_views = (0..MIP_LEVELS)
+            .flat_map(|mip| (0..6).map(move |face| (mip, face)))
+            .map(|(mip, face)| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("Environment Map Face"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_mip_level: mip,
+                    mip_level_count: NonZeroU32::new(1),
+                    base_array_layer: face,
+                    array_layer_count: NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Environment Map View"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Environment Map Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("environment_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("environment_bind_group"),
+        });
+
+        // Each face of each mip level has its own uniform, picked with a
+        // dynamic offset
+        let capture_size = std::mem::size_of::<CaptureUniform>() as wgpu::BufferAddress;
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+        let capture_stride = (capture_size + alignment - 1) / alignment * alignment;
+        let capture_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Environment Capture Buffer"),
+            size: capture_stride * face_views.len() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let capture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(capture_size),
+                    },
+                    count: None,
+                }],
+                label: Some("environment_capture_bind_group_layout"),
+            });
+        let capture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &capture_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &capture_buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(capture_size),
+                }),
+            }],
+            label: Some("environment_capture_bind_group"),
+        });
+
+        let capture_pipeline = create_capture_pipeline(
+            device,
+            &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Environment Capture Pipeline Layout"),
+                bind_group_layouts: &[&capture_bind_group_layout, light_bind_group_layout],
+                push_constant_ranges: &[],
+            }),
+        );
+
+        Self {
+            face_views,
+            capture_pipeline,
+            capture_buffer,
+            capture_bind_group,
+            capture_stride,
+            bind_group_layout,
+            bind_group,
+            last_capture: None,
+        }
+    }
+
+    pub fn capture(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        light_bind_group: &wgpu::BindGroup,
+        origin: Point3<f32>,
+        enabled: bool,
+    ) {
+        //! Capture the sky around a point if the last capture is out of date.
+        //! When reflections are disabled the map is cleared once, so nothing
+        //! is reflected
+
+        let due = match self.last_capture {
+            Some((last_capture, was_enabled)) => {
+                enabled != was_enabled || (enabled && last_capture.elapsed() >= CAPTURE_INTERVAL)
+            }
+            None => true,
+        };
+        if !due {
+            return;
+        }
+        self.last_capture = Some((Instant::now(), enabled));
+
+        for i in 0..self.face_views.len() {
+            let mip = i as u32 / 6;
+            let uniform = CaptureUniform {
+                origin: [origin.x, origin.y, origin.z, 1.0],
+                face: i as u32 % 6,
+                roughness: mip as f32 / (MIP_LEVELS - 1) as f32,
+                _padding: [0; 2],
+            };
+            queue.write_buffer(
+                &self.capture_buffer,
+                i as wgpu::BufferAddress * self.capture_stride,
+                bytemuck::cast_slice(&[uniform]),
+            );
+        }
+
+        for (i, view) in self.face_views.iter().enumerate() {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Environment Capture Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+
+            if enabled {
+                let offset =
+                    (i as wgpu::BufferAddress * self.capture_stride) as wgpu::DynamicOffset;
+                render_pass.set_pipeline(&self.capture_pipeline);
+                render_pass.set_bind_group(0, &self.capture_bind_group, &[offset]);
+                render_pass.set_bind_group(1, light_bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            }
+        }
+    }
+}
+
+fn create_capture_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline that draws the sky onto a face of the
+    //! environment map
+
+    let shader = device.create_shader_module(include_lit_wgsl!("shaders/environment.wgsl"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Environment Capture Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Rgba16Float,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
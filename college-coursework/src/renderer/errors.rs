@@ -0,0 +1,58 @@
+use std::sync::{Arc, Mutex};
+
+use log::error;
+
+/// Possible errors produced while rendering, or creating what's needed to
+#[derive(thiserror::Error, Debug)]
+pub enum RenderError {
+    #[error("No graphics adapter can render to the window")]
+    NoAdapter,
+
+    #[error("Failed to create the graphics device")]
+    RequestDeviceError(#[from] wgpu::RequestDeviceError),
+
+    #[error(transparent)]
+    SurfaceError(#[from] wgpu::SurfaceError),
+
+    #[error("The graphics device was lost")]
+    DeviceLost,
+
+    #[error("Failed to wait for the graphics device")]
+    RuntimeError(#[from] std::io::Error),
+}
+
+/// Errors reported by the device outside of any error scope. Without this
+/// wgpu panics on any error
+#[derive(Clone, Default)]
+pub struct DeviceErrors(Arc<Mutex<Vec<wgpu::Error>>>);
+impl DeviceErrors {
+    pub fn watch(device: &wgpu::Device) -> Self {
+        //! Collect the errors the device reports from now on
+
+        let errors = Self::default();
+        let collected = errors.0.clone();
+        device.on_uncaptured_error(move |err| {
+            error!("Graphics device error: {}", err);
+            collected.lock().unwrap().push(err);
+        });
+
+        errors
+    }
+
+    pub fn take(&self) -> Vec<wgpu::Error> {
+        //! Remove and return the errors reported since the last call
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+pub fn show_error_dialog(title: &str, message: &str) {
+    //! Tell the user about an error with a message box, blocking until it is
+    //! closed
+
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title(title)
+        .set_description(message)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
@@ -0,0 +1,184 @@
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use cgmath::{InnerSpace, Vector3, Zero};
+use specs::{Join, Read, ReadStorage, World};
+
+use crate::{
+    simulation::{GravitationalConstant, Mass, Position, PositionScaleFactor},
+    util::AU,
+};
+
+use super::{lines::LineVertex, trails::Colourmap};
+
+/// Number of arrows along each side of the field
+const FIELD_RESOLUTION: usize = 32;
+
+/// How often the field is sampled again, it changes slowly compared to the
+/// frame rate and sampling it is quadratic in the number of arrows and bodies
+const FIELD_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Length of each arrow as a fraction of the spacing between them
+const ARROW_LENGTH: f32 = 0.8;
+
+/// Length of the head of each arrow as a fraction of the arrow
+const ARROW_HEAD_LENGTH: f32 = 0.3;
+
+const FIELD_COLOURMAP: Colourmap = Colourmap::Inferno;
+
+/// Options for the overlay of the gravitational field on the ecliptic plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldSettings {
+    pub shown: bool,
+    /// Distance from the origin to the edge of the field (in AU)
+    pub extent: f64,
+}
+impl Default for FieldSettings {
+    fn default() -> Self {
+        Self {
+            shown: false,
+            extent: 10.0,
+        }
+    }
+}
+
+/// Arrows showing the direction and strength of gravity across the ecliptic,
+/// kept between samples so the field isn't recalculated every frame
+pub struct FieldOverlay {
+    arrows: Vec<[LineVertex; 5]>,
+    last_update: Option<(Instant, FieldSettings)>,
+}
+impl FieldOverlay {
+    pub fn new() -> Self {
+        Self {
+            arrows: Vec::new(),
+            last_update: None,
+        }
+    }
+
+    pub fn build_vertices(
+        &mut self,
+        world: &World,
+        vertices: &mut Vec<LineVertex>,
+        ranges: &mut Vec<Range<u32>>,
+    ) {
+        //! Add the vertices of every arrow, along with the range of vertices
+        //! each arrow is drawn from, sampling the field again if it is due
+
+        let settings = *world.read_resource::<FieldSettings>();
+        if !settings.shown {
+            self.last_update = None;
+            return;
+        }
+
+        let due = match self.last_update {
+            Some((time, last_settings)) => {
+                time.elapsed() >= FIELD_UPDATE_INTERVAL || last_settings != settings
+            }
+            None => true,
+        };
+        if due {
+            self.sample(world, &settings);
+            self.last_update = Some((Instant::now(), settings));
+        }
+
+        for arrow in self.arrows.iter() {
+            let start = vertices.len() as u32;
+            vertices.extend_from_slice(arrow);
+            ranges.push(start..vertices.len() as u32);
+        }
+    }
+
+    fn sample(&mut self, world: &World, settings: &FieldSettings) {
+        //! Sample the gravitational acceleration on a grid across the ecliptic
+        //! and build an arrow at each point
+
+        let (positions, masses, constant, scale_factor): (
+            ReadStorage<Position>,
+            ReadStorage<Mass>,
+            Read<GravitationalConstant>,
+            Read<PositionScaleFactor>,
+        ) = world.system_data();
+
+        let bodies = (&positions, &masses)
+            .join()
+            .map(|(position, mass)| (position.0, mass.0))
+            .collect::<Vec<_>>();
+
+        let extent = settings.extent * AU;
+        let spacing = 2.0 * extent / FIELD_RESOLUTION as f64;
+
+        let samples = (0..FIELD_RESOLUTION)
+            .flat_map(|i| (0..FIELD_RESOLUTION).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                // Sample at the centre of each cell, so no point is on the Sun
+                let point = Vector3::new(
+                    -extent + (i as f64 + 0.5) * spacing,
+                    -extent + (j as f64 + 0.5) * spacing,
+                    0.0,
+                );
+
+                let acceleration = bodies
+                    .iter()
+                    .map(|&(position, mass)| {
+                        let r = position - point;
+                        // Bodies closer than a cell would dwarf everything else
+                        let distance = r.magnitude().max(0.5 * spacing);
+                        constant.0 * mass / distance.powi(3) * r
+                    })
+                    .fold(Vector3::zero(), |a, b| a + b);
+
+                (point, acceleration)
+            })
+            .collect::<Vec<_>>();
+
+        // The field falls off with the square of distance, so it is coloured
+        // by the logarithm of its strength to keep the outskirts visible
+        let strength =
+            |acceleration: &Vector3<f64>| acceleration.magnitude().max(f64::MIN_POSITIVE).log10();
+        let (min, max) = samples
+            .iter()
+            .map(|(_, acceleration)| strength(acceleration))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (value.min(min), value.max(max))
+            });
+
+        let length = (spacing / scale_factor.0) as f32 * ARROW_LENGTH;
+        self.arrows = samples
+            .iter()
+            .filter_map(|(point, acceleration)| {
+                // Arrows are all the same length and show the direction of the
+                // field across the plane, their colour shows its strength
+                let direction = Vector3::new(acceleration.x, acceleration.y, 0.0);
+                if direction.magnitude2() == 0.0 {
+                    return None;
+                }
+                let direction = direction.normalize().cast::<f32>()?;
+
+                let t = if max > min {
+                    ((strength(acceleration) - min) / (max - min)) as f32
+                } else {
+                    1.0
+                };
+                let [r, g, b] = FIELD_COLOURMAP.sample(t);
+                let colour = [r, g, b, 0.3 + 0.7 * t];
+
+                let centre = (point / scale_factor.0).cast::<f32>()?;
+                let tail = centre - direction * length * 0.5;
+                let tip = centre + direction * length * 0.5;
+                let side = Vector3::new(-direction.y, direction.x, 0.0);
+                let head =
+                    |sign: f32| tip - (direction - side * 0.5 * sign) * length * ARROW_HEAD_LENGTH;
+
+                // Drawn as a single strip, back and forth along each side of
+                // the head
+                Some(
+                    [tail, tip, head(1.0), tip, head(-1.0)]
+                        .map(|position| LineVertex::new(position.into(), colour)),
+                )
+            })
+            .collect();
+    }
+}
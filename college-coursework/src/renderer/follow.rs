@@ -0,0 +1,299 @@
+use cgmath::{EuclideanSpace, InnerSpace, Point3, SquareMatrix, Vector3, Vector4, Zero};
+use instant::Duration;
+use specs::{Join, ReadStorage, World, Write, WriteExpect};
+
+use crate::simulation::Identifier;
+
+use super::{
+    camera::{Camera, Projection},
+    components::{CameraCenter, RenderModel},
+};
+
+/// How far from a body the camera follows it from after picking it, as a
+/// multiple of the radius of its bounding sphere
+const PICKED_DISTANCE: f32 = 5.0;
+
+/// Moves the camera smoothly to a body chosen from the UI, rather than
+/// jumping straight to it, then follows the body
+#[derive(Debug, Clone)]
+pub struct FlyTo {
+    /// How long the flight takes (in seconds)
+    pub duration: f32,
+    requested: Option<Identifier>,
+    flight: Option<Flight>,
+}
+impl Default for FlyTo {
+    fn default() -> Self {
+        Self {
+            duration: 3.0,
+            requested: None,
+            flight: None,
+        }
+    }
+}
+impl FlyTo {
+    pub fn fly_to(&mut self, body: Identifier) {
+        //! Start flying the camera to a body from wherever it is
+        self.requested = Some(body);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Flight {
+    body: Identifier,
+    /// The camera as it was when the flight started
+    start: Camera,
+    elapsed: f32,
+}
+
+pub fn follow_body(camera: &mut Camera, moved: Vector3<f32>, dt: Duration, world: &World) {
+    //! Move the camera with the body it is following, if it is following
+    //! one. `moved` is how far the camera controller moved the camera this
+    //! frame, which moves the camera around the body
+
+    let (ids, models, mut center): (
+        ReadStorage<Identifier>,
+        ReadStorage<RenderModel>,
+        WriteExpect<CameraCenter>,
+    ) = world.system_data();
+
+    if !center.following {
+        center.velocity = Vector3::zero();
+        return;
+    }
+
+    let target = match (&ids, &models)
+        .join()
+        .find(|(id, _)| id.get_id() == center.get_body().get_id())
+    {
+        Some((_, model)) => Point3::from_vec(model.bounding_sphere().0),
+        // The body has gone, e.g. it was absorbed in a merge
+        None => return,
+    };
+
+    center.offset += moved;
+    let (smoothing, mut velocity) = (center.smoothing, center.velocity);
+    camera.position = critically_damped(
+        camera.position,
+        target + center.offset,
+        &mut velocity,
+        smoothing,
+        dt.as_secs_f32(),
+    );
+    center.velocity = velocity;
+
+    if center.offset.magnitude2() > 0.0 {
+        camera.look_at(target);
+    }
+}
+
+pub fn fly_to_body(camera: &mut Camera, dt: Duration, world: &World) -> bool {
+    //! Move the camera towards the body it is flying to, if it is flying to
+    //! one, easing in and out so it doesn't jolt. Returns whether the camera
+    //! is being controlled by the flight
+
+    let (ids, models, mut fly_to, mut center): (
+        ReadStorage<Identifier>,
+        ReadStorage<RenderModel>,
+        Write<FlyTo>,
+        WriteExpect<CameraCenter>,
+    ) = world.system_data();
+
+    if let Some(body) = fly_to.requested.take() {
+        fly_to.flight = Some(Flight {
+            body,
+            start: *camera,
+            elapsed: 0.0,
+        });
+        center.following = false;
+    }
+
+    let duration = fly_to.duration;
+    let flight = match &mut fly_to.flight {
+        Some(flight) => flight,
+        None => return false,
+    };
+
+    let (target, radius) = match (&ids, &models)
+        .join()
+        .find(|(id, _)| id.get_id() == flight.body.get_id())
+    {
+        Some((_, model)) => {
+            let (centre, radius) = model.bounding_sphere();
+            (Point3::from_vec(centre), radius)
+        }
+        // The body has gone, e.g. it was absorbed in a merge
+        None => {
+            fly_to.flight = None;
+            return false;
+        }
+    };
+
+    flight.elapsed += dt.as_secs_f32();
+    let t = if duration > 0.0 {
+        (flight.elapsed / duration).min(1.0)
+    } else {
+        1.0
+    };
+    let eased = t * t * (3.0 - 2.0 * t);
+
+    // Frame the body from the side the camera approaches it from, the body
+    // keeps moving so the end of the flight is found again each frame
+    let approach = flight.start.position - target;
+    let direction = if approach.magnitude2() > 0.0 {
+        approach.normalize()
+    } else {
+        Vector3::unit_z()
+    };
+    let mut end = Camera::new(
+        target + direction * radius * PICKED_DISTANCE,
+        camera.rotation,
+    );
+    end.look_at(target);
+
+    let start = flight.start;
+    camera.position = start.position + (end.position - start.position) * eased;
+    camera.rotation = start.rotation.slerp(end.rotation, eased);
+
+    if t >= 1.0 {
+        center.set_body(flight.body.clone());
+        center.following = true;
+        center.offset = end.position - target;
+        center.velocity = Vector3::zero();
+        fly_to.flight = None;
+    }
+
+    true
+}
+
+pub fn pick_body(ctx: &egui::Context, world: &World, camera: &Camera, projection: &Projection) {
+    //! Follow the body under the cursor when it is double clicked
+
+    let (double_clicked, pointer, screen) = {
+        let input = ctx.input();
+        (
+            input
+                .pointer
+                .button_double_clicked(egui::PointerButton::Primary),
+            input.pointer.interact_pos(),
+            input.screen_rect(),
+        )
+    };
+    let pointer = match pointer {
+        Some(pointer) if double_clicked && !ctx.is_pointer_over_area() => pointer,
+        _ => return,
+    };
+
+    let (origin, direction) = match pointer_ray(pointer, screen, camera, projection) {
+        Some(ray) => ray,
+        None => return,
+    };
+
+    let (ids, models, mut center): (
+        ReadStorage<Identifier>,
+        ReadStorage<RenderModel>,
+        WriteExpect<CameraCenter>,
+    ) = world.system_data();
+
+    let picked = (&ids, &models)
+        .join()
+        .filter_map(|(id, model)| {
+            let (centre, radius) = model.bounding_sphere();
+            ray_sphere(origin, direction, centre, radius)
+                .map(|distance| (distance, id, centre, radius))
+        })
+        .min_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+    if let Some((_, id, position, radius)) = picked {
+        center.set_body(id.clone());
+        center.following = true;
+        center.offset =
+            (camera.position.to_vec() - position).normalize() * radius * PICKED_DISTANCE;
+    }
+}
+
+pub(super) fn pointer_ray(
+    pointer: egui::Pos2,
+    screen: egui::Rect,
+    camera: &Camera,
+    projection: &Projection,
+) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    //! The ray from the camera through a point on the screen, as its origin
+    //! and direction in the scene
+
+    // Reversed-Z is used, so the near plane has a depth of 1
+    let ndc_x = (pointer.x - screen.left()) / screen.width() * 2.0 - 1.0;
+    let ndc_y = 1.0 - (pointer.y - screen.top()) / screen.height() * 2.0;
+    let inverse = (projection.calc_matrix() * camera.calc_matrix()).invert()?;
+    let unproject = |depth: f32| {
+        let point = inverse * Vector4::new(ndc_x, ndc_y, depth, 1.0);
+        point.truncate() / point.w
+    };
+    let origin = unproject(1.0);
+
+    Some((origin, (unproject(0.0) - origin).normalize()))
+}
+
+pub(super) fn ray_sphere(
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    center: Vector3<f32>,
+    radius: f32,
+) -> Option<f32> {
+    //! The distance along a ray to where it first hits a sphere, if it does
+
+    let to_center = center - origin;
+    let along = to_center.dot(direction);
+    let miss = to_center.magnitude2() - along * along;
+    if along < 0.0 || miss > radius * radius {
+        return None;
+    }
+
+    Some(along - (radius * radius - miss).sqrt())
+}
+
+fn critically_damped(
+    position: Point3<f32>,
+    target: Point3<f32>,
+    velocity: &mut Vector3<f32>,
+    smoothing: f32,
+    dt: f32,
+) -> Point3<f32> {
+    //! Move towards a target like a critically damped spring, which catches
+    //! up as fast as it can without overshooting. `smoothing` is roughly the
+    //! time it takes to catch up
+
+    if smoothing <= 0.0 {
+        *velocity = Vector3::zero();
+        return target;
+    }
+
+    let omega = 2.0 / smoothing;
+    let displacement = position - target;
+    let decay = (-omega * dt).exp();
+
+    let change = (*velocity + displacement * omega) * dt;
+    *velocity = (*velocity - change * omega) * decay;
+    target + (displacement + change) * decay
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Point3, Vector3, Zero};
+
+    use super::critically_damped;
+
+    #[test]
+    fn test_follow_does_not_overshoot() {
+        let target = Point3::new(10.0, 0.0, 0.0);
+        let mut position = Point3::new(0.0, 0.0, 0.0);
+        let mut velocity = Vector3::zero();
+
+        for _ in 0..300 {
+            position = critically_damped(position, target, &mut velocity, 0.5, 1.0 / 60.0);
+            assert!(position.x <= target.x);
+        }
+
+        assert!((target - position).magnitude() < 1e-3);
+    }
+}
@@ -0,0 +1,159 @@
+use std::{fs, io::Error};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{assets, config::CONFIG_DIR};
+
+const FONTS_FILE: &'static str = "fonts.toml";
+
+/// Folder in the assets the bundled fonts are kept in
+const FONTS_FOLDER: &'static str = "fonts";
+
+/// Name of the font family the help window draws keys with
+pub const KEYCAP_FAMILY: &'static str = "keycap";
+
+/// The bundled font used for each kind of text, by its file name in the
+/// fonts folder. When a font isn't chosen, or can't be loaded, egui's
+/// built-in font is used instead
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontSettings {
+    /// Font of the text throughout the UI and of the body labels
+    pub ui: Option<String>,
+    /// Font of the keys shown in the help window, which falls back to the UI
+    /// font
+    pub keycap: Option<String>,
+    /// Font of the logs and debug text
+    pub monospace: Option<String>,
+}
+impl Default for FontSettings {
+    fn default() -> Self {
+        Self {
+            ui: None,
+            keycap: Some("BkcapRegular.ttf".to_owned()),
+            monospace: None,
+        }
+    }
+}
+impl FontSettings {
+    pub fn load() -> Self {
+        //! Load the font settings saved by a previous session, falling back
+        //! to the defaults if there are none
+
+        let path = CONFIG_DIR.join(FONTS_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid font settings in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        //! Save the font settings for use by later sessions
+
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize font settings: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(FONTS_FILE), contents))
+        {
+            warn!("Failed to save font settings: {}", err);
+        }
+    }
+
+    pub fn definitions(&self) -> egui::FontDefinitions {
+        //! Build the fonts for egui from these settings. The chosen fonts are
+        //! put before the built-in ones, which still draw any characters the
+        //! chosen fonts are missing. The keycap family is always defined, so
+        //! text can use it even if no keycap font could be loaded
+
+        let mut definitions = egui::FontDefinitions::default();
+
+        add_font(
+            &mut definitions,
+            egui::FontFamily::Proportional,
+            self.ui.as_deref(),
+        );
+        add_font(
+            &mut definitions,
+            egui::FontFamily::Monospace,
+            self.monospace.as_deref(),
+        );
+
+        let proportional = definitions.families[&egui::FontFamily::Proportional].clone();
+        definitions
+            .families
+            .insert(egui::FontFamily::Name(KEYCAP_FAMILY.into()), proportional);
+        add_font(
+            &mut definitions,
+            egui::FontFamily::Name(KEYCAP_FAMILY.into()),
+            self.keycap.as_deref(),
+        );
+
+        definitions
+    }
+}
+
+pub fn available_fonts() -> Vec<String> {
+    //! Returns the file names of the bundled fonts, in alphabetical order
+
+    let folder = assets::asset_path(FONTS_FOLDER);
+    let mut fonts = match fs::read_dir(&folder) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| {
+                let name = name.to_lowercase();
+                name.ends_with(".ttf") || name.ends_with(".otf")
+            })
+            .collect::<Vec<_>>(),
+        Err(err) => {
+            warn!("Failed to find the bundled fonts in {:?}: {}", folder, err);
+            Vec::new()
+        }
+    };
+
+    fonts.sort();
+    fonts
+}
+
+fn load_font(file_name: &str) -> Result<egui::FontData, Error> {
+    //! Read a bundled font from the assets
+
+    fs::read(assets::asset_path(FONTS_FOLDER).join(file_name)).map(egui::FontData::from_owned)
+}
+
+fn add_font(
+    definitions: &mut egui::FontDefinitions,
+    family: egui::FontFamily,
+    file_name: Option<&str>,
+) {
+    //! Load a font and use it first in a family, leaving the family as it
+    //! was if the font can't be loaded
+
+    let file_name = match file_name {
+        Some(file_name) => file_name,
+        None => return,
+    };
+
+    match load_font(file_name) {
+        Ok(font) => {
+            definitions.font_data.insert(file_name.to_owned(), font);
+            definitions
+                .families
+                .entry(family)
+                .or_default()
+                .insert(0, file_name.to_owned());
+        }
+        Err(err) => warn!("Failed to load the font {:?}: {}", file_name, err),
+    }
+}
@@ -0,0 +1,148 @@
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs, GilrsBuilder};
+use log::{info, warn};
+use specs::{World, WorldExt};
+
+use crate::stats::UsageStats;
+
+/// Options for controlling the camera with a gamepad, changed from the UI
+#[derive(Debug, Clone, Copy)]
+pub struct GamepadSettings {
+    pub enabled: bool,
+    /// How far a stick or trigger has to move before it does anything, as a
+    /// fraction of its full range, so worn sticks don't drift the camera
+    pub dead_zone: f32,
+}
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dead_zone: 0.15,
+        }
+    }
+}
+
+/// The sticks, triggers and bumpers of a gamepad, after the dead zone. Each
+/// ranges from -1 to 1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GamepadInput {
+    /// Left stick, moving the camera right and forward
+    pub movement: [f32; 2],
+    /// Right stick, turning the camera right and up
+    pub look: [f32; 2],
+    /// Bumpers, rolling the camera right
+    pub roll: f32,
+    /// Right trigger less the left trigger, speeding the camera up or down
+    pub speed: f32,
+}
+impl GamepadInput {
+    pub fn is_idle(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Reads the gamepad used most recently, through gilrs
+pub struct GamepadHandler {
+    /// None if gamepads aren't supported on this platform
+    gilrs: Option<Gilrs>,
+    active: Option<GamepadId>,
+    used: bool,
+}
+impl GamepadHandler {
+    pub fn new() -> Self {
+        // The default filters are disabled as they have their own dead zone,
+        // which would stop the one in the settings going any lower
+        let gilrs = GilrsBuilder::new()
+            .with_default_filters(false)
+            .build()
+            .map_err(|err| warn!("Gamepads are not available: {}", err))
+            .ok();
+
+        Self {
+            gilrs,
+            active: None,
+            used: false,
+        }
+    }
+
+    pub fn poll(&mut self, world: &World) -> GamepadInput {
+        //! Handle the gamepad events since the last frame, returning where
+        //! the sticks and triggers of the active gamepad are
+
+        let gilrs = match &mut self.gilrs {
+            Some(gilrs) => gilrs,
+            None => return GamepadInput::default(),
+        };
+
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected => {
+                    info!("Gamepad connected: {}", gilrs.gamepad(event.id).name());
+                }
+                EventType::Disconnected => {
+                    info!("Gamepad disconnected: {}", gilrs.gamepad(event.id).name());
+                    if self.active == Some(event.id) {
+                        self.active = None;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            // Whichever gamepad was touched last controls the camera
+            self.active = Some(event.id);
+        }
+
+        let settings = *world.read_resource::<GamepadSettings>();
+        let gamepad = match self.active.and_then(|id| gilrs.connected_gamepad(id)) {
+            Some(gamepad) if settings.enabled => gamepad,
+            _ => return GamepadInput::default(),
+        };
+
+        let axis = |axis| apply_dead_zone(gamepad.value(axis), settings.dead_zone);
+        let button = |button| {
+            apply_dead_zone(
+                gamepad.button_data(button).map_or(0.0, |data| data.value()),
+                settings.dead_zone,
+            )
+        };
+
+        let input = GamepadInput {
+            movement: [axis(Axis::LeftStickX), axis(Axis::LeftStickY)],
+            look: [axis(Axis::RightStickX), axis(Axis::RightStickY)],
+            roll: button(Button::RightTrigger) - button(Button::LeftTrigger),
+            speed: button(Button::RightTrigger2) - button(Button::LeftTrigger2),
+        };
+
+        if !self.used && !input.is_idle() {
+            self.used = true;
+            world.write_resource::<UsageStats>().use_feature("Gamepad");
+        }
+
+        input
+    }
+}
+
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    //! Ignore values within the dead zone, scaling the rest so they still
+    //! start from zero and reach the full range
+
+    if value.abs() <= dead_zone {
+        return 0.0;
+    }
+
+    value.signum() * ((value.abs() - dead_zone) / (1.0 - dead_zone)).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_dead_zone;
+
+    #[test]
+    fn test_dead_zone() {
+        assert_eq!(0.0, apply_dead_zone(0.125, 0.25));
+        assert_eq!(0.0, apply_dead_zone(-0.25, 0.25));
+        assert_eq!(0.5, apply_dead_zone(0.625, 0.25));
+        assert_eq!(-1.0, apply_dead_zone(-1.0, 0.25));
+        assert_eq!(0.5, apply_dead_zone(0.5, 0.0));
+    }
+}
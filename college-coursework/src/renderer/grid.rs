@@ -0,0 +1,93 @@
+use std::{f32::consts::TAU, ops::Range};
+
+use specs::{Read, World};
+
+use crate::{simulation::PositionScaleFactor, util::AU};
+
+use super::lines::LineVertex;
+
+/// Number of segments each ring of the grid is drawn with
+const RING_SEGMENTS: usize = 256;
+
+/// Number of radial lines, evenly spaced around the grid
+const RADIAL_LINES: usize = 12;
+
+/// Every this many rings is drawn brighter, to make distances easier to read
+const MAJOR_RING_INTERVAL: u32 = 5;
+
+const GRID_COLOUR: [f32; 4] = [0.5, 0.5, 0.5, 0.2];
+const MAJOR_GRID_COLOUR: [f32; 4] = [0.6, 0.6, 0.6, 0.45];
+
+/// Options for the reference grid on the ecliptic plane and the axes at the
+/// origin
+#[derive(Debug, Clone, Copy)]
+pub struct GridSettings {
+    pub shown: bool,
+    pub axes: bool,
+    /// Number of rings, spaced 1 AU apart
+    pub rings: u32,
+}
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            shown: false,
+            axes: false,
+            rings: 40,
+        }
+    }
+}
+
+pub fn build_vertices(world: &World, vertices: &mut Vec<LineVertex>, ranges: &mut Vec<Range<u32>>) {
+    //! Add the vertices of the grid and axes, along with the range of
+    //! vertices each line is drawn from
+
+    let (settings, scale_factor): (Read<GridSettings>, Read<PositionScaleFactor>) =
+        world.system_data();
+
+    let au = (AU / scale_factor.0) as f32;
+
+    let mut push_line = |points: &[[f32; 3]], colour: [f32; 4]| {
+        let start = vertices.len() as u32;
+        vertices.extend(points.iter().map(|&point| LineVertex::new(point, colour)));
+        ranges.push(start..vertices.len() as u32);
+    };
+
+    if settings.shown && settings.rings > 0 {
+        // The ecliptic is the plane the Earth orbits in, z = 0
+        for ring in 1..=settings.rings {
+            let radius = ring as f32 * au;
+            let points = (0..=RING_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / RING_SEGMENTS as f32 * TAU;
+                    [radius * angle.cos(), radius * angle.sin(), 0.0]
+                })
+                .collect::<Vec<_>>();
+
+            let colour = if ring % MAJOR_RING_INTERVAL == 0 {
+                MAJOR_GRID_COLOUR
+            } else {
+                GRID_COLOUR
+            };
+            push_line(&points, colour);
+        }
+
+        let radius = settings.rings as f32 * au;
+        for i in 0..RADIAL_LINES {
+            let angle = i as f32 / RADIAL_LINES as f32 * TAU;
+            push_line(
+                &[
+                    [0.0, 0.0, 0.0],
+                    [radius * angle.cos(), radius * angle.sin(), 0.0],
+                ],
+                GRID_COLOUR,
+            );
+        }
+    }
+
+    if settings.axes {
+        // Red, green and blue for the x, y and z axes, each 1 AU long
+        push_line(&[[0.0, 0.0, 0.0], [au, 0.0, 0.0]], [1.0, 0.2, 0.2, 1.0]);
+        push_line(&[[0.0, 0.0, 0.0], [0.0, au, 0.0]], [0.2, 1.0, 0.2, 1.0]);
+        push_line(&[[0.0, 0.0, 0.0], [0.0, 0.0, au]], [0.2, 0.4, 1.0, 1.0]);
+    }
+}
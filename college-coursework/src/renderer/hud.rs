@@ -0,0 +1,419 @@
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use instant::{Duration, Instant};
+use specs::{Read, World};
+
+use crate::{
+    locale::tr,
+    simulation::{SimulationTime, TimeScale},
+    util::{convert_julian_date_to_datetime, J2000},
+};
+
+use super::model::Model;
+
+/// Number of frames the frame rate is averaged over
+const FRAME_HISTORY: usize = 60;
+
+/// Whether the overlay of performance and simulation statistics is shown
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HudSettings {
+    pub shown: bool,
+}
+
+/// Times of recent frames, shown on the HUD
+pub struct FrameStats {
+    /// Time between the most recent frames
+    intervals: VecDeque<Duration>,
+    /// When the CPU started work on the current frame
+    frame_start: Instant,
+    /// Time the CPU spent on the last frame
+    cpu_time: Duration,
+}
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            intervals: VecDeque::with_capacity(FRAME_HISTORY),
+            frame_start: Instant::now(),
+            cpu_time: Duration::ZERO,
+        }
+    }
+
+    pub fn begin_frame(&mut self, dt: Duration) {
+        //! Start timing a frame, `dt` is the time since the last one started
+
+        if self.intervals.len() == FRAME_HISTORY {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(dt);
+        self.frame_start = Instant::now();
+    }
+
+    pub fn end_frame(&mut self) {
+        //! Finish timing the CPU's work on a frame, once it has been submitted
+        self.cpu_time = self.frame_start.elapsed();
+    }
+
+    fn frame_rate(&self) -> f64 {
+        //! The average number of frames per second over the recent frames
+
+        let total = self.intervals.iter().sum::<Duration>().as_secs_f64();
+        if total > 0.0 {
+            self.intervals.len() as f64 / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A point in a frame a timestamp is written at, in the order they are
+/// written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamp {
+    FrameStart,
+    EnvironmentEnd,
+    /// Inside the opaque render pass, after the bodies that don't emit light
+    BodiesEnd,
+    OpaquePassEnd,
+    TransparentPassEnd,
+    PostEnd,
+    UiEnd,
+    FrameEnd,
+}
+const TIMESTAMP_COUNT: usize = 8;
+
+/// A part of a frame whose time on the GPU is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuPass {
+    /// Capturing the environment map for reflections
+    Environment,
+    /// The whole opaque render pass, made up of the bodies and lights
+    OpaquePass,
+    Bodies,
+    Lights,
+    /// The trails, lines and atmospheres blended over the opaque bodies
+    TransparentPass,
+    /// The full screen effects, and copying the scene to the window
+    PostProcess,
+    Ui,
+}
+impl GpuPass {
+    pub const ALL: [Self; 7] = [
+        Self::Environment,
+        Self::OpaquePass,
+        Self::Bodies,
+        Self::Lights,
+        Self::TransparentPass,
+        Self::PostProcess,
+        Self::Ui,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Environment => tr!("gpu-pass-environment"),
+            Self::OpaquePass => tr!("gpu-pass-opaque"),
+            Self::Bodies => tr!("gpu-pass-bodies"),
+            Self::Lights => tr!("gpu-pass-lights"),
+            Self::TransparentPass => tr!("gpu-pass-transparent"),
+            Self::PostProcess => tr!("gpu-pass-post"),
+            Self::Ui => tr!("gpu-pass-ui"),
+        }
+    }
+
+    /// Whether the pass is part of the opaque render pass, which needs
+    /// timestamps to be written inside it
+    pub fn is_subpass(&self) -> bool {
+        matches!(self, Self::Bodies | Self::Lights)
+    }
+
+    /// The timestamps either side of the pass
+    fn span(&self) -> (Timestamp, Timestamp) {
+        match self {
+            Self::Environment => (Timestamp::FrameStart, Timestamp::EnvironmentEnd),
+            Self::OpaquePass => (Timestamp::EnvironmentEnd, Timestamp::OpaquePassEnd),
+            Self::Bodies => (Timestamp::EnvironmentEnd, Timestamp::BodiesEnd),
+            Self::Lights => (Timestamp::BodiesEnd, Timestamp::OpaquePassEnd),
+            Self::TransparentPass => (Timestamp::OpaquePassEnd, Timestamp::TransparentPassEnd),
+            Self::PostProcess => (Timestamp::TransparentPassEnd, Timestamp::PostEnd),
+            Self::Ui => (Timestamp::PostEnd, Timestamp::UiEnd),
+        }
+    }
+}
+
+/// Measures how long the GPU takes to render a frame, and each pass of it,
+/// with timestamp queries if the adapter supports them
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick
+    period: f32,
+    /// Whether timestamps can be written inside a render pass, without which
+    /// the parts of the opaque pass aren't timed
+    inside_passes: bool,
+    /// Set once the buffer has been mapped, along with whether it succeeded
+    mapped: Arc<AtomicBool>,
+    map_succeeded: Arc<AtomicBool>,
+    /// The buffer is waiting to be read, so can't be written to
+    in_flight: bool,
+    /// Whether the frame being encoded is being timed
+    timing: bool,
+    pub gpu_time: Option<Duration>,
+    /// Time each pass took in the last timed frame
+    pub pass_times: Vec<(GpuPass, Duration)>,
+}
+impl GpuTimer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        //! Create a timer, None if the device can't write timestamps
+
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("GPU Timer Queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: TIMESTAMP_COUNT as u32,
+        });
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GPU Timer Buffer"),
+            size: (TIMESTAMP_COUNT * std::mem::size_of::<u64>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            buffer,
+            period: queue.get_timestamp_period(),
+            inside_passes: device
+                .features()
+                .contains(wgpu::Features::WRITE_TIMESTAMP_INSIDE_PASSES),
+            mapped: Arc::new(AtomicBool::new(false)),
+            map_succeeded: Arc::new(AtomicBool::new(false)),
+            in_flight: false,
+            timing: false,
+            gpu_time: None,
+            pass_times: Vec::new(),
+        })
+    }
+
+    pub fn times_subpasses(&self) -> bool {
+        self.inside_passes
+    }
+
+    pub fn begin(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        //! Write the timestamp at the start of a frame, unless the last
+        //! timestamps haven't been read yet
+
+        self.timing = !self.in_flight;
+        self.write(encoder, Timestamp::FrameStart);
+    }
+
+    pub fn write(&self, encoder: &mut wgpu::CommandEncoder, timestamp: Timestamp) {
+        //! Write a timestamp between passes, if the frame is being timed
+
+        if self.timing {
+            encoder.write_timestamp(&self.query_set, timestamp as u32);
+        }
+    }
+
+    pub fn write_in_pass(&self, render_pass: &mut wgpu::RenderPass, timestamp: Timestamp) {
+        //! Write a timestamp inside a render pass, if the frame is being timed
+        //! and the device can
+
+        if self.timing && self.inside_passes {
+            render_pass.write_timestamp(&self.query_set, timestamp as u32);
+        }
+    }
+
+    pub fn end(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        //! Write the timestamp at the end of a frame and copy them all to the
+        //! buffer to be read
+
+        if self.timing {
+            self.write(encoder, Timestamp::FrameEnd);
+            encoder.resolve_query_set(&self.query_set, 0..TIMESTAMP_COUNT as u32, &self.buffer, 0);
+        }
+    }
+
+    pub fn after_submit(&mut self) {
+        //! Start mapping the buffer once the frame has been submitted
+
+        if !self.timing {
+            return;
+        }
+        self.timing = false;
+        self.in_flight = true;
+
+        let mapped = self.mapped.clone();
+        let map_succeeded = self.map_succeeded.clone();
+        self.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                map_succeeded.store(result.is_ok(), Ordering::Release);
+                mapped.store(true, Ordering::Release);
+            });
+    }
+
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        //! Read the timestamps of the last timed frame if they are ready
+
+        if !self.in_flight {
+            return;
+        }
+        device.poll(wgpu::Maintain::Poll);
+        if !self.mapped.swap(false, Ordering::Acquire) {
+            return;
+        }
+
+        if self.map_succeeded.load(Ordering::Acquire) {
+            let timestamps: [u64; TIMESTAMP_COUNT] =
+                bytemuck::pod_read_unaligned(&self.buffer.slice(..).get_mapped_range());
+            let between = |from: Timestamp, to: Timestamp| {
+                let ticks = timestamps[to as usize].saturating_sub(timestamps[from as usize]);
+                Duration::from_nanos((ticks as f64 * self.period as f64) as u64)
+            };
+
+            self.gpu_time = Some(between(Timestamp::FrameStart, Timestamp::FrameEnd));
+            self.pass_times = GpuPass::ALL
+                .into_iter()
+                .filter(|pass| self.inside_passes || !pass.is_subpass())
+                .map(|pass| {
+                    let (from, to) = pass.span();
+                    (pass, between(from, to))
+                })
+                .collect();
+            self.buffer.unmap();
+        }
+        self.in_flight = false;
+    }
+}
+
+/// How much was drawn in the last frame and how long it took the GPU, shown
+/// in the Render Stats window
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    /// Whether the adapter can time frames at all
+    pub timed: bool,
+    /// Whether the parts of the opaque render pass are timed separately
+    pub subpasses_timed: bool,
+    pub gpu_time: Option<Duration>,
+    pub pass_times: Vec<(GpuPass, Duration)>,
+    pub draw_calls: u32,
+    pub triangles: u64,
+    pub line_segments: u64,
+    /// How many bodies were outside the camera's view, so weren't drawn
+    pub culled: u32,
+}
+impl RenderStats {
+    pub fn count_model(&mut self, model: &Model) {
+        //! Count the draw calls and triangles of drawing a model once
+        for mesh in model.meshes.iter() {
+            self.draw_calls += 1;
+            self.triangles += mesh.num_elements as u64 / 3;
+        }
+    }
+
+    pub fn count_stars(&mut self, stars: u32) {
+        //! Count the draw call and triangles of the starfield, two for each
+        //! star
+        self.draw_calls += 1;
+        self.triangles += 2 * stars as u64;
+    }
+
+    pub fn count_post(&mut self, passes: u32) {
+        //! Count the draw calls of the full screen passes, which are a single
+        //! triangle each
+        self.draw_calls += passes;
+        self.triangles += passes as u64;
+    }
+
+    pub fn count_ribbons(&mut self, ranges: &[Range<u32>]) {
+        //! Count the draw calls and triangles of drawing ribbons, as triangle
+        //! strips
+        for range in ranges {
+            self.draw_calls += 1;
+            self.triangles += range.len().saturating_sub(2) as u64;
+        }
+    }
+
+    pub fn count_lines(&mut self, ranges: &[Range<u32>]) {
+        //! Count the draw calls and segments of drawing lines
+        for range in ranges {
+            self.draw_calls += 1;
+            self.line_segments += range.len().saturating_sub(1) as u64;
+        }
+    }
+
+    pub fn count_ui(&mut self, paint_jobs: &[egui::ClippedPrimitive]) {
+        //! Count the draw calls and triangles of drawing the UI
+        for job in paint_jobs {
+            self.draw_calls += 1;
+            if let egui::epaint::Primitive::Mesh(mesh) = &job.primitive {
+                self.triangles += mesh.indices.len() as u64 / 3;
+            }
+        }
+    }
+}
+
+pub fn draw_hud(
+    ctx: &egui::Context,
+    world: &World,
+    frame_stats: &FrameStats,
+    gpu_timer: Option<&GpuTimer>,
+) {
+    //! Show the frame rate, frame times, iterations and simulated date in the
+    //! top right corner
+
+    let (settings, time_scale, simulation_time): (
+        Read<HudSettings>,
+        Read<TimeScale>,
+        Read<SimulationTime>,
+    ) = world.system_data();
+
+    if !settings.shown {
+        return;
+    }
+
+    // The bodies start at their positions at the J2000 epoch
+    let date = convert_julian_date_to_datetime(J2000 + simulation_time.0 / 86400.0);
+    let milliseconds = |duration: Duration| format!("{:.2} ms", duration.as_secs_f64() * 1000.0);
+
+    egui::Area::new("hud")
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 10.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                egui::Grid::new("hud_stats").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("hud-frame-rate"));
+                    ui.monospace(format!("{:.1}", frame_stats.frame_rate()));
+                    ui.end_row();
+
+                    ui.label(tr!("hud-cpu-time"));
+                    ui.monospace(milliseconds(frame_stats.cpu_time));
+                    ui.end_row();
+
+                    ui.label(tr!("hud-gpu-time"));
+                    ui.monospace(match gpu_timer {
+                        Some(timer) => timer.gpu_time.map_or("-".to_string(), milliseconds),
+                        None => tr!("hud-unsupported"),
+                    });
+                    ui.end_row();
+
+                    ui.label(tr!("hud-iterations"));
+                    ui.monospace(time_scale.iterations.to_string());
+                    ui.end_row();
+
+                    ui.label(tr!("time-date"));
+                    ui.monospace(date.format("%Y-%m-%d %H:%M").to_string());
+                    ui.end_row();
+                });
+            });
+        });
+}
@@ -1,19 +1,25 @@
 use super::vertex;
 
-/// Position and rotation of an object within the render
+/// Position, rotation and scale of an object within the render
 pub struct Instance {
     pub(crate) position: cgmath::Vector3<f32>,
     pub(crate) rotation: cgmath::Quaternion<f32>,
+    pub(crate) scale: f32,
 }
 impl Instance {
     pub fn new(position: cgmath::Vector3<f32>, rotation: cgmath::Quaternion<f32>) -> Self {
-        Self { position, rotation }
+        Self {
+            position,
+            rotation,
+            scale: 1.0,
+        }
     }
 
     pub fn to_raw(&self) -> InstanceRaw {
         InstanceRaw {
             model: (cgmath::Matrix4::from_translation(self.position)
-                * cgmath::Matrix4::from(self.rotation))
+                * cgmath::Matrix4::from(self.rotation)
+                * cgmath::Matrix4::from_scale(self.scale))
             .into(),
             normal: cgmath::Matrix3::from(self.rotation).into(),
         }
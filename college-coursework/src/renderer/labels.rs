@@ -0,0 +1,107 @@
+use cgmath::{EuclideanSpace, InnerSpace};
+use specs::{Entities, Join, Read, ReadStorage, World};
+
+use crate::{accessibility::AccessibilitySettings, locale, simulation::Identifier};
+
+use super::{
+    camera::{Camera, Projection},
+    components::{BodyStyle, PlanetColour, RenderModel},
+};
+
+/// Font size of the body labels before any accessibility scaling
+const LABEL_FONT_SIZE: f32 = 14.0;
+
+/// Options for drawing the names of bodies next to them
+#[derive(Debug, Clone, Copy)]
+pub struct LabelSettings {
+    pub shown: bool,
+    /// Distance from the camera at which labels start to fade out
+    pub fade_start: f32,
+    /// Distance from the camera at which labels are no longer drawn
+    pub fade_end: f32,
+}
+impl Default for LabelSettings {
+    fn default() -> Self {
+        Self {
+            shown: true,
+            fade_start: 500.0,
+            fade_end: 2000.0,
+        }
+    }
+}
+impl LabelSettings {
+    pub fn opacity(&self, distance: f32) -> f32 {
+        //! Returns how opaque a label should be at a distance from the camera
+
+        if distance <= self.fade_start {
+            1.0
+        } else if distance >= self.fade_end {
+            0.0
+        } else {
+            1.0 - (distance - self.fade_start) / (self.fade_end - self.fade_start)
+        }
+    }
+}
+
+pub fn draw_body_labels(
+    ctx: &egui::Context,
+    world: &World,
+    camera: &Camera,
+    projection: &Projection,
+) {
+    //! Paint the name of each body next to its position on the screen
+
+    let (entities, ids, colours, styles, models, label_settings, accessibility_settings): (
+        Entities,
+        ReadStorage<Identifier>,
+        ReadStorage<PlanetColour>,
+        ReadStorage<BodyStyle>,
+        ReadStorage<RenderModel>,
+        Read<LabelSettings>,
+        Read<AccessibilitySettings>,
+    ) = world.system_data();
+
+    if !label_settings.shown {
+        return;
+    }
+
+    let screen = ctx.input().screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("body_labels"),
+    ));
+    let font = egui::FontId::proportional(LABEL_FONT_SIZE * accessibility_settings.label_scale());
+
+    (&entities, &ids, &colours, (&styles).maybe(), &models)
+        .join()
+        .filter(|(_, _, _, style, _)| style.map_or(true, |style| style.label_shown))
+        .for_each(|(entity, id, colour, _, model)| {
+            let position = model.instance.position;
+
+            let opacity = label_settings.opacity((position - camera.position.to_vec()).magnitude());
+            if opacity <= 0.0 {
+                return;
+            }
+
+            // Skip bodies behind the camera or off the screen
+            let screen_position = match camera.project_to_screen(projection, position, screen) {
+                Some(screen_position) => screen_position,
+                None => return,
+            };
+
+            let colour = if accessibility_settings.high_contrast {
+                egui::Rgba::WHITE
+            } else {
+                let [r, g, b, _] = accessibility_settings.palette.body_colour(entity, colour.0);
+                egui::Rgba::from_rgb(r, g, b)
+            };
+
+            painter.text(
+                screen_position + egui::vec2(8.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                locale::body_name(id),
+                font.clone(),
+                (colour * opacity).into(),
+            );
+        });
+}
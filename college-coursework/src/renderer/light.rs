@@ -1,27 +1,97 @@
 use std::ops::Range;
 
+use specs::{Component, VecStorage};
+
 use super::model::{Mesh, Model};
 
+/// The maximum number of lights that can be in the scene at once, this must
+/// match the size of the lights array in the shaders
+pub const MAX_LIGHTS: usize = 4;
+
+/// Like `wgpu::include_wgsl!`, but with the lights and their falloff in
+/// `lighting.wgsl`, shared by every shader that lights bodies, put before the
+/// shader
+macro_rules! include_lit_wgsl {
+    ($file:literal) => {
+        wgpu::ShaderModuleDescriptor {
+            label: Some($file),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(include_str!("shaders/lighting.wgsl"), include_str!($file)).into(),
+            ),
+        }
+    };
+}
+pub(crate) use include_lit_wgsl;
+
+/// A point light emitted by a body, such as a star
+#[derive(Debug, Clone, Copy, Component)]
+#[storage(VecStorage)]
+pub struct Light {
+    pub colour: [f32; 3],
+    pub intensity: f32,
+    /// How bright the star is compared to the Sun, which sets how brightly
+    /// it lights bodies at a distance
+    pub luminosity: f32,
+}
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            colour: [1.0, 1.0, 1.0],
+            intensity: 1.0,
+            luminosity: 1.0,
+        }
+    }
+}
+
 /// Data structure representing the position and colour of a light
 #[repr(C)]
-#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct LightUniform {
     position: [f32; 3],
-    _padding: u32,
+    intensity: f32,
     colour: [f32; 3],
-    _padding2: u32,
+    luminosity: f32,
 }
 impl LightUniform {
-    pub fn new(position: [f32; 3], colour: [f32; 3]) -> Self {
+    pub fn new(position: [f32; 3], light: &Light) -> Self {
         Self {
             position,
-            _padding: 0,
-            colour,
-            _padding2: 0,
+            intensity: light.intensity,
+            colour: light.colour,
+            luminosity: light.luminosity,
         }
     }
 }
 
+/// Data structure representing every light in the scene
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightsUniform {
+    lights: [LightUniform; MAX_LIGHTS],
+    count: u32,
+    /// The distance a star as luminous as the Sun lights bodies with its full
+    /// intensity, light falls off with the inverse square of the distance
+    /// from there
+    reference_distance: f32,
+    _padding: [u32; 2],
+}
+impl LightsUniform {
+    pub fn new(lights: impl IntoIterator<Item = LightUniform>, reference_distance: f32) -> Self {
+        //! Creates a uniform from the lights, any past MAX_LIGHTS are ignored
+        let mut uniform = Self {
+            reference_distance,
+            ..Default::default()
+        };
+
+        for (i, light) in lights.into_iter().take(MAX_LIGHTS).enumerate() {
+            uniform.lights[i] = light;
+            uniform.count = i as u32 + 1;
+        }
+
+        uniform
+    }
+}
+
 /// Trait for use by the render pipeline to draw a light
 pub trait DrawLight<'a> {
     fn draw_light_mesh(
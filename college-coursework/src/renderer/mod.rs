@@ -1,9 +1,35 @@
+pub mod arrows;
+pub mod atmosphere;
+pub mod attract;
 pub mod camera;
+pub mod capture;
 pub mod components;
+pub mod cursor;
+pub mod debug;
+pub mod environment;
+pub mod errors;
+pub mod field;
+pub mod follow;
+pub mod fonts;
+pub mod gamepad;
+pub mod grid;
+pub mod hud;
 pub mod instance;
+pub mod labels;
 pub mod light;
+pub mod lines;
 pub mod model;
+pub mod path;
+pub mod post;
+pub mod ribbons;
+pub mod rings;
+pub mod ruler;
+pub mod screenshot;
+pub mod settings;
+pub mod stars;
 pub mod state;
 pub mod texture;
+pub mod trails;
+pub mod transparent;
 pub mod vertex;
 pub mod window;
@@ -1,8 +1,9 @@
-use std::{io, ops::Range};
+use std::{io, ops::Range, sync::Arc};
 
+use cgmath::{InnerSpace, Rotation, Vector3, Zero};
 use wgpu::util::DeviceExt;
 
-use super::{texture, vertex::Vertex};
+use super::{instance::Instance, texture, vertex::Vertex};
 
 /// Data sturcture representing a vertex of a model
 #[repr(C)]
@@ -55,10 +56,145 @@ impl Vertex for ModelVertex {
     }
 }
 
-/// Represents a model for rendering
+/// The space a mesh or model takes up in its own coordinates, as both a box
+/// along the axes and a sphere around it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+    /// The centre of the bounding sphere
+    pub centre: Vector3<f32>,
+    pub radius: f32,
+}
+impl Bounds {
+    pub fn from_points(points: impl IntoIterator<Item = Vector3<f32>> + Clone) -> Self {
+        //! The bounds of a set of points, the sphere is centred on the box
+        //! and reaches the furthest point from its centre. Without any points
+        //! the bounds are a single point at the origin
+
+        let (min, max) = points
+            .clone()
+            .into_iter()
+            .fold(
+                None,
+                |bounds: Option<(Vector3<f32>, Vector3<f32>)>, point| {
+                    Some(match bounds {
+                        Some((min, max)) => (
+                            Vector3::new(
+                                min.x.min(point.x),
+                                min.y.min(point.y),
+                                min.z.min(point.z),
+                            ),
+                            Vector3::new(
+                                max.x.max(point.x),
+                                max.y.max(point.y),
+                                max.z.max(point.z),
+                            ),
+                        ),
+                        None => (point, point),
+                    })
+                },
+            )
+            .unwrap_or((Vector3::zero(), Vector3::zero()));
+
+        let centre = (min + max) / 2.0;
+        let radius = points
+            .into_iter()
+            .map(|point| (point - centre).magnitude())
+            .fold(0.0, f32::max);
+
+        Self {
+            min,
+            max,
+            centre,
+            radius,
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        //! The smallest bounds that hold both bounds
+
+        let min = Vector3::new(
+            self.min.x.min(other.min.x),
+            self.min.y.min(other.min.y),
+            self.min.z.min(other.min.z),
+        );
+        let max = Vector3::new(
+            self.max.x.max(other.max.x),
+            self.max.y.max(other.max.y),
+            self.max.z.max(other.max.z),
+        );
+
+        // If one sphere is inside the other the larger holds both, otherwise
+        // the new sphere reaches across both of them
+        let between = other.centre - self.centre;
+        let distance = between.magnitude();
+        let (centre, radius) = if distance + other.radius <= self.radius {
+            (self.centre, self.radius)
+        } else if distance + self.radius <= other.radius {
+            (other.centre, other.radius)
+        } else {
+            let radius = (distance + self.radius + other.radius) / 2.0;
+            (
+                self.centre + between * ((radius - self.radius) / distance),
+                radius,
+            )
+        };
+
+        Self {
+            min,
+            max,
+            centre,
+            radius,
+        }
+    }
+
+    pub fn sphere(&self, instance: &Instance) -> (Vector3<f32>, f32) {
+        //! The centre and radius of the bounding sphere once it has been
+        //! placed in the scene by an instance
+
+        (
+            instance.position
+                + instance
+                    .rotation
+                    .rotate_vector(self.centre * instance.scale),
+            self.radius * instance.scale,
+        )
+    }
+}
+
+/// Represents a model for rendering, its meshes and materials can be shared
+/// with other models
 pub struct Model {
-    pub meshes: Vec<Mesh>,
-    pub materials: Vec<Material>,
+    pub meshes: Vec<Arc<Mesh>>,
+    pub materials: Vec<Arc<Material>>,
+    /// The bounds of every mesh together
+    pub bounds: Bounds,
+}
+impl Model {
+    pub fn new(meshes: Vec<Arc<Mesh>>, materials: Vec<Arc<Material>>) -> Self {
+        //! Creates a new model, bounded by its meshes
+        let bounds = meshes
+            .iter()
+            .map(|mesh| mesh.bounds)
+            .reduce(|a, b| a.union(&b))
+            .unwrap_or_else(|| Bounds::from_points(std::iter::empty::<Vector3<f32>>()));
+
+        Self {
+            meshes,
+            materials,
+            bounds,
+        }
+    }
+}
+
+/// The optional maps of a material, any that are missing use a default
+#[derive(Default)]
+pub struct MaterialMaps {
+    pub normal: Option<texture::Texture>,
+    pub specular: Option<texture::Texture>,
+    pub roughness: Option<texture::Texture>,
+    pub metallic: Option<texture::Texture>,
 }
 
 // Represents a material for use by meshes in models
@@ -66,18 +202,52 @@ pub struct Material {
     pub name: String,
     pub diffuse_texture: texture::Texture,
     pub normal_texture: texture::Texture,
+    pub specular_texture: texture::Texture,
+    pub roughness_texture: texture::Texture,
+    pub metallic_texture: texture::Texture,
     pub bind_group: wgpu::BindGroup,
 }
 
 impl Material {
+    // Fallbacks for materials that only have a diffuse texture: a flat,
+    // non-metallic surface with no specular highlights
+    const FLAT_NORMAL: [f32; 4] = [0.5, 0.5, 1.0, 1.0];
+    const NO_SPECULAR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    const FULLY_ROUGH: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    const NON_METALLIC: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
     pub fn new(
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
         name: &str,
         diffuse_texture: texture::Texture,
-        normal_texture: texture::Texture, // NEW!
+        maps: MaterialMaps,
         layout: &wgpu::BindGroupLayout,
     ) -> Self {
-        //! Creates a new material with the specified textures
+        //! Creates a new material with the specified textures, using defaults
+        //! for any of the maps that are not specified
+
+        let fallback = |colour, map: &str| {
+            texture::Texture::from_colour(
+                device,
+                queue,
+                colour,
+                Some(&format!("{:?} Default {} Texture", name, map)),
+                true,
+            )
+        };
+        let normal_texture = maps
+            .normal
+            .unwrap_or_else(|| fallback(Self::FLAT_NORMAL, "Normal"));
+        let specular_texture = maps
+            .specular
+            .unwrap_or_else(|| fallback(Self::NO_SPECULAR, "Specular"));
+        let roughness_texture = maps
+            .roughness
+            .unwrap_or_else(|| fallback(Self::FULLY_ROUGH, "Roughness"));
+        let metallic_texture = maps
+            .metallic
+            .unwrap_or_else(|| fallback(Self::NON_METALLIC, "Metallic"));
 
         // Create a bind group for the material
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -99,6 +269,30 @@ impl Material {
                     binding: 3,
                     resource: wgpu::BindingResource::Sampler(&normal_texture.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&specular_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::Sampler(&specular_texture.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: wgpu::BindingResource::TextureView(&roughness_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: wgpu::BindingResource::Sampler(&roughness_texture.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: wgpu::BindingResource::TextureView(&metallic_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 9,
+                    resource: wgpu::BindingResource::Sampler(&metallic_texture.sampler),
+                },
             ],
             label: Some(name),
         });
@@ -106,7 +300,10 @@ impl Material {
         Self {
             name: String::from(name),
             diffuse_texture,
-            normal_texture, // NEW!
+            normal_texture,
+            specular_texture,
+            roughness_texture,
+            metallic_texture,
             bind_group,
         }
     }
@@ -119,6 +316,7 @@ pub struct Mesh {
     pub index_buffer: wgpu::Buffer,
     pub num_elements: u32,
     pub material: usize,
+    pub bounds: Bounds,
 }
 impl Mesh {
     pub fn new(
@@ -142,12 +340,16 @@ impl Mesh {
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        let bounds =
+            Bounds::from_points(vertices.iter().map(|vertex| Vector3::from(vertex.position)));
+
         Self {
             name,
             vertex_buffer,
             index_buffer,
             num_elements: indices.len() as u32,
             material,
+            bounds,
         }
     }
 }
@@ -246,3 +448,35 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Quaternion, Vector3, Zero};
+
+    use super::{super::instance::Instance, Bounds};
+
+    #[test]
+    fn test_bounds_hold_every_point() {
+        let a = Bounds::from_points([Vector3::new(-1.0, 0.0, 0.0), Vector3::new(1.0, 2.0, 0.0)]);
+        assert_eq!(Vector3::new(-1.0, 0.0, 0.0), a.min);
+        assert_eq!(Vector3::new(1.0, 2.0, 0.0), a.max);
+        assert_eq!(Vector3::new(0.0, 1.0, 0.0), a.centre);
+        assert!((a.radius - 2f32.sqrt()).abs() < 1e-5);
+
+        // The union reaches around both spheres
+        let b = Bounds::from_points([Vector3::new(3.0, 1.0, -1.0), Vector3::new(5.0, 1.0, 1.0)]);
+        let union = a.union(&b);
+        assert_eq!(Vector3::new(5.0, 2.0, 1.0), union.max);
+        for bounds in [a, b] {
+            let reach = (bounds.centre - union.centre).magnitude() + bounds.radius;
+            assert!(reach <= union.radius + 1e-5);
+        }
+
+        // Moved and scaled into the scene
+        let mut instance = Instance::new(Vector3::new(10.0, 0.0, 0.0), Quaternion::zero());
+        instance.scale = 2.0;
+        let (centre, radius) = a.sphere(&instance);
+        assert_eq!(Vector3::new(10.0, 2.0, 0.0), centre);
+        assert!((radius - 2.0 * 2f32.sqrt()).abs() < 1e-5);
+    }
+}
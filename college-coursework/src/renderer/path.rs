@@ -0,0 +1,209 @@
+use cgmath::{Point3, Quaternion};
+use instant::Duration;
+use specs::{World, WorldExt};
+
+use crate::stats::UsageStats;
+
+use super::camera::Camera;
+
+/// Time added after the last keyframe when a new one is placed
+const DEFAULT_KEYFRAME_GAP: f32 = 3.0;
+
+/// Where the camera is and which way it faces at a time along a path
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub position: Point3<f32>,
+    pub rotation: Quaternion<f32>,
+    /// Seconds from the start of the path
+    pub time: f32,
+}
+
+/// A path the camera can fly along, through keyframes placed by the user,
+/// e.g. to record demo footage
+#[derive(Debug, Clone, Default)]
+pub struct CameraPath {
+    /// The keyframes, ordered by their time
+    pub keyframes: Vec<Keyframe>,
+    pub playing: bool,
+    /// Start again from the first keyframe after reaching the last
+    pub looped: bool,
+    /// Seconds along the path the camera is at while playing
+    pub time: f32,
+    /// Set by the UI to place a keyframe where the camera is
+    pub add_keyframe: bool,
+    /// Set by the UI to move the camera to a keyframe
+    pub view_keyframe: Option<usize>,
+}
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    pub fn sort(&mut self) {
+        //! Put the keyframes back in order after their times have changed
+        self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    pub fn sample(&self, time: f32) -> Option<(Point3<f32>, Quaternion<f32>)> {
+        //! The position and rotation of the camera at a time along the path,
+        //! the position follows a Catmull-Rom spline through the keyframes
+        //! and the rotation turns evenly between them
+
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some((first.position, first.rotation));
+        }
+        if time >= last.time {
+            return Some((last.position, last.rotation));
+        }
+
+        // Find the keyframes either side of the time, and the ones beyond
+        // them which shape the curve
+        let i = self
+            .keyframes
+            .iter()
+            .rposition(|keyframe| keyframe.time <= time)?;
+        let start = &self.keyframes[i];
+        let end = &self.keyframes[i + 1];
+        let before = &self.keyframes[i.saturating_sub(1)];
+        let after = &self.keyframes[(i + 2).min(self.keyframes.len() - 1)];
+
+        let length = end.time - start.time;
+        let t = if length > 0.0 {
+            (time - start.time) / length
+        } else {
+            1.0
+        };
+
+        Some((
+            catmull_rom(
+                before.position,
+                start.position,
+                end.position,
+                after.position,
+                t,
+            ),
+            start.rotation.slerp(end.rotation, t),
+        ))
+    }
+}
+
+pub fn play_path(camera: &mut Camera, dt: Duration, world: &World) -> bool {
+    //! Handle the keyframes placed or viewed from the UI, and move the camera
+    //! along the path while it is playing. Returns whether the camera is
+    //! being controlled by the path
+
+    let mut path = world.write_resource::<CameraPath>();
+
+    if std::mem::take(&mut path.add_keyframe) {
+        let time = path
+            .keyframes
+            .last()
+            .map_or(0.0, |keyframe| keyframe.time + DEFAULT_KEYFRAME_GAP);
+        path.keyframes.push(Keyframe {
+            position: camera.position,
+            rotation: camera.rotation,
+            time,
+        });
+    }
+
+    if let Some(keyframe) = path
+        .view_keyframe
+        .take()
+        .and_then(|i| path.keyframes.get(i).copied())
+    {
+        path.playing = false;
+        camera.position = keyframe.position;
+        camera.rotation = keyframe.rotation;
+    }
+
+    if !path.playing {
+        return false;
+    }
+    if path.keyframes.len() < 2 {
+        path.playing = false;
+        return false;
+    }
+    if path.time == 0.0 {
+        world
+            .write_resource::<UsageStats>()
+            .use_feature("Camera path");
+    }
+
+    path.time += dt.as_secs_f32();
+    if path.time > path.duration() {
+        if path.looped {
+            path.time %= path.duration().max(f32::EPSILON);
+        } else {
+            path.time = path.duration();
+            path.playing = false;
+        }
+    }
+
+    if let Some((position, rotation)) = path.sample(path.time) {
+        camera.position = position;
+        camera.rotation = rotation;
+    }
+
+    true
+}
+
+fn catmull_rom(
+    p0: Point3<f32>,
+    p1: Point3<f32>,
+    p2: Point3<f32>,
+    p3: Point3<f32>,
+    t: f32,
+) -> Point3<f32> {
+    //! A point `t` of the way between p1 and p2 on a Catmull-Rom spline,
+    //! which passes through every point it is given
+
+    use cgmath::EuclideanSpace as _;
+
+    let (p0, p1, p2, p3) = (p0.to_vec(), p1.to_vec(), p2.to_vec(), p3.to_vec());
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    Point3::from_vec(
+        (p1 * 2.0
+            + (p2 - p0) * t
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+            * 0.5,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Point3, Quaternion};
+
+    use super::{CameraPath, Keyframe};
+
+    #[test]
+    fn test_path_passes_through_keyframes() {
+        let keyframe = |x: f32, time: f32| Keyframe {
+            position: Point3::new(x, 0.0, 0.0),
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            time,
+        };
+        let path = CameraPath {
+            keyframes: vec![
+                keyframe(0.0, 0.0),
+                keyframe(2.0, 1.0),
+                keyframe(4.0, 2.0),
+                keyframe(6.0, 4.0),
+            ],
+            ..Default::default()
+        };
+
+        for keyframe in path.keyframes.iter() {
+            let (position, _) = path.sample(keyframe.time).unwrap();
+            assert_eq!(keyframe.position, position);
+        }
+
+        // Evenly spaced points in a line are followed at an even speed
+        let (position, _) = path.sample(1.5).unwrap();
+        assert_eq!(Point3::new(3.0, 0.0, 0.0), position);
+    }
+}
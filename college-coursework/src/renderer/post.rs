@@ -0,0 +1,439 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale::tr;
+
+/// The format the scene is drawn in before post-processing, with a high
+/// dynamic range so bright lights aren't clipped before they are tonemapped
+pub const SCENE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// A full screen effect applied to the drawn scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Tonemapping,
+    Vignette,
+    Fxaa,
+    GammaCorrection,
+}
+impl Effect {
+    /// Every effect, in the order they are applied
+    pub const ALL: [Self; 4] = [
+        Self::Tonemapping,
+        Self::Vignette,
+        Self::Fxaa,
+        Self::GammaCorrection,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Tonemapping => tr!("post-tonemapping"),
+            Self::Vignette => tr!("post-vignette"),
+            Self::Fxaa => tr!("post-fxaa"),
+            Self::GammaCorrection => tr!("post-gamma-correction"),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Tonemapping => tr!("post-tonemapping-hover"),
+            Self::Vignette => tr!("post-vignette-hover"),
+            Self::Fxaa => tr!("post-fxaa-hover"),
+            Self::GammaCorrection => tr!("post-gamma-correction-hover"),
+        }
+    }
+
+    fn entry_point(&self) -> &'static str {
+        match self {
+            Self::Tonemapping => "fs_tonemap",
+            Self::Vignette => "fs_vignette",
+            Self::Fxaa => "fs_fxaa",
+            Self::GammaCorrection => "fs_gamma",
+        }
+    }
+}
+
+/// Which post-processing effects are applied to the scene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostSettings {
+    pub tonemapping: bool,
+    pub vignette: bool,
+    pub fxaa: bool,
+    /// Only applied when the surface doesn't encode colours itself
+    pub gamma_correction: bool,
+}
+impl Default for PostSettings {
+    fn default() -> Self {
+        Self {
+            tonemapping: true,
+            vignette: false,
+            fxaa: true,
+            gamma_correction: true,
+        }
+    }
+}
+impl PostSettings {
+    pub fn enabled(&self, effect: Effect) -> bool {
+        match effect {
+            Effect::Tonemapping => self.tonemapping,
+            Effect::Vignette => self.vignette,
+            Effect::Fxaa => self.fxaa,
+            Effect::GammaCorrection => self.gamma_correction,
+        }
+    }
+
+    pub fn enabled_mut(&mut self, effect: Effect) -> &mut bool {
+        match effect {
+            Effect::Tonemapping => &mut self.tonemapping,
+            Effect::Vignette => &mut self.vignette,
+            Effect::Fxaa => &mut self.fxaa,
+            Effect::GammaCorrection => &mut self.gamma_correction,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostUniform {
+    /// The size of a pixel in texture coordinates
+    texel_size: [f32; 2],
+    _padding: [f32; 2],
+}
+
+/// An offscreen colour texture the scene or an effect is drawn into, and the
+/// bind group the next effect reads it with
+struct Target {
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+/// The chain of full screen passes between drawing the scene and the UI. The
+/// scene is drawn into an offscreen target, then each enabled effect reads the
+/// last target and draws into the other, before the result is copied to the
+/// surface
+pub struct PostProcessor {
+    targets: [Target; 2],
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    effect_pipelines: Vec<(Effect, wgpu::RenderPipeline)>,
+    copy_pipeline: wgpu::RenderPipeline,
+    /// Whether the surface encodes colours as sRGB itself, in which case
+    /// gamma correction would be applied twice
+    surface_srgb: bool,
+}
+impl PostProcessor {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Self {
+        //! Create the offscreen targets for a surface and the pipeline for
+        //! each effect
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("post_bind_group_layout"),
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post Buffer"),
+            size: std::mem::size_of::<PostUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        write_texel_size(queue, &uniform_buffer, config);
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/post.wgsl"));
+
+        // The effects draw into the offscreen targets, only the copy draws to
+        // the surface
+        let effect_pipelines = Effect::ALL
+            .into_iter()
+            .map(|effect| {
+                let pipeline =
+                    create_pipeline(device, &layout, &shader, effect.entry_point(), SCENE_FORMAT);
+                (effect, pipeline)
+            })
+            .collect();
+        let copy_pipeline = create_pipeline(device, &layout, &shader, "fs_copy", config.format);
+
+        let targets = [
+            create_target(
+                device,
+                &bind_group_layout,
+                &sampler,
+                &uniform_buffer,
+                config,
+            ),
+            create_target(
+                device,
+                &bind_group_layout,
+                &sampler,
+                &uniform_buffer,
+                config,
+            ),
+        ];
+
+        Self {
+            targets,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            effect_pipelines,
+            copy_pipeline,
+            surface_srgb: config.format.describe().srgb,
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        //! Make the offscreen targets again to match the size of the surface
+
+        self.targets = [
+            create_target(
+                device,
+                &self.bind_group_layout,
+                &self.sampler,
+                &self.uniform_buffer,
+                config,
+            ),
+            create_target(
+                device,
+                &self.bind_group_layout,
+                &self.sampler,
+                &self.uniform_buffer,
+                config,
+            ),
+        ];
+
+        write_texel_size(queue, &self.uniform_buffer, config);
+    }
+
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        //! The target the scene is drawn into
+        &self.targets[0].view
+    }
+
+    pub fn apply(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        settings: &PostSettings,
+        output: &wgpu::TextureView,
+    ) -> u32 {
+        //! Apply the enabled effects to the scene and draw the result onto
+        //! the output, returns the number of passes drawn
+
+        let mut source = 0;
+        let mut passes = 0;
+        for (effect, pipeline) in self.effect_pipelines.iter() {
+            if !settings.enabled(*effect)
+                || (*effect == Effect::GammaCorrection && self.surface_srgb)
+            {
+                continue;
+            }
+
+            draw_pass(
+                encoder,
+                pipeline,
+                &self.targets[source].bind_group,
+                &self.targets[1 - source].view,
+            );
+            source = 1 - source;
+            passes += 1;
+        }
+
+        draw_pass(
+            encoder,
+            &self.copy_pipeline,
+            &self.targets[source].bind_group,
+            output,
+        );
+
+        passes + 1
+    }
+}
+
+fn write_texel_size(
+    queue: &wgpu::Queue,
+    uniform_buffer: &wgpu::Buffer,
+    config: &wgpu::SurfaceConfiguration,
+) {
+    let uniform = PostUniform {
+        texel_size: [1.0 / config.width as f32, 1.0 / config.height as f32],
+        _padding: [0.0; 2],
+    };
+    queue.write_buffer(uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+}
+
+fn draw_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    source: &wgpu::BindGroup,
+    target: &wgpu::TextureView,
+) {
+    //! Draw a full screen triangle reading from one texture into another
+
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Post Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+    });
+
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, source, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+fn create_target(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    uniform_buffer: &wgpu::Buffer,
+    config: &wgpu::SurfaceConfiguration,
+) -> Target {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Post Target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: SCENE_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+        label: Some("post_bind_group"),
+    });
+
+    Target { view, bind_group }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline of a full screen pass
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Post Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Effect, PostSettings};
+
+    #[test]
+    fn test_enabled_matches_fields() {
+        let mut settings = PostSettings::default();
+        for effect in Effect::ALL {
+            let enabled = settings.enabled(effect);
+            *settings.enabled_mut(effect) = !enabled;
+            assert_eq!(!enabled, settings.enabled(effect));
+        }
+        assert_ne!(PostSettings::default(), settings);
+    }
+}
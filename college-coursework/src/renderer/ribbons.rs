@@ -0,0 +1,221 @@
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3};
+
+use super::{texture, vertex};
+
+/// A point along the path a ribbon follows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RibbonPoint {
+    pub position: Vector3<f32>,
+    pub colour: [f32; 4],
+    /// How far along the ribbon the point is, from 0 at its head to 1 at its
+    /// tail, the ribbon fades out towards its tail
+    pub age: f32,
+}
+
+/// A corner of a ribbon as it is sent to the GPU, each point of the path
+/// has one either side of it
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct RibbonVertex {
+    position: [f32; 3],
+    age: f32,
+    colour: [f32; 4],
+    /// Which side of the path the vertex is on, -1 or 1, so the edges can
+    /// be softened
+    edge: f32,
+}
+impl RibbonVertex {
+    pub fn position(&self) -> [f32; 3] {
+        self.position
+    }
+}
+impl vertex::Vertex for RibbonVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<RibbonVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ],
+        }
+    }
+}
+
+pub fn extrude(
+    points: &[RibbonPoint],
+    camera: Point3<f32>,
+    width: f32,
+    pixel_size: f32,
+    vertices: &mut Vec<RibbonVertex>,
+) {
+    //! Add the vertices of a ribbon along a path, drawn as a triangle strip.
+    //! The ribbon faces the camera and is `width` pixels wide wherever it
+    //! is, `pixel_size` being the size of a pixel at a distance of 1 from
+    //! the camera
+
+    let camera = camera.to_vec();
+    let mut previous_side = None;
+
+    for (i, point) in points.iter().enumerate() {
+        // The direction of the path through the point, from the points
+        // either side of it
+        let before = points[i.saturating_sub(1)].position;
+        let after = points[(i + 1).min(points.len() - 1)].position;
+        let to_camera = camera - point.position;
+
+        // The ribbon is extruded across the path and the view of it, if the
+        // path runs straight towards the camera the last side is kept
+        let side = (after - before).cross(to_camera);
+        let side = if side.magnitude2() > 0.0 {
+            side.normalize()
+        } else {
+            previous_side.unwrap_or_else(|| any_perpendicular(to_camera))
+        };
+        previous_side = Some(side);
+
+        let half_width = side * (width / 2.0 * pixel_size * to_camera.magnitude());
+        for (offset, edge) in [(-half_width, -1.0), (half_width, 1.0)] {
+            vertices.push(RibbonVertex {
+                position: (point.position + offset).into(),
+                age: point.age,
+                colour: point.colour,
+                edge,
+            });
+        }
+    }
+}
+
+fn any_perpendicular(direction: Vector3<f32>) -> Vector3<f32> {
+    //! A unit vector perpendicular to a direction, or along the x axis if
+    //! the direction has no length
+
+    let perpendicular = direction.cross(Vector3::unit_z());
+    let perpendicular = if perpendicular.magnitude2() > 0.0 {
+        perpendicular
+    } else {
+        direction.cross(Vector3::unit_x())
+    };
+
+    if perpendicular.magnitude2() > 0.0 {
+        perpendicular.normalize()
+    } else {
+        Vector3::unit_x()
+    }
+}
+
+pub fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    colour_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline for ribbons, which are blended like lines
+    //! and seen from either side
+
+    use vertex::Vertex as _;
+
+    let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/ribbon.wgsl"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Ribbon Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[RibbonVertex::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: colour_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: texture::Texture::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: texture::Texture::DEPTH_COMPARE,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+pub fn create_vertex_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    //! Creates a buffer that can hold `capacity` ribbon vertices
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Ribbon Vertex Buffer"),
+        size: (capacity * std::mem::size_of::<RibbonVertex>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Point3, Vector3};
+
+    use super::{extrude, RibbonPoint};
+
+    #[test]
+    fn test_ribbon_faces_the_camera() {
+        // A path along the x axis, seen from 10 above it
+        let points = (0..4)
+            .map(|i| RibbonPoint {
+                position: Vector3::new(i as f32, 0.0, 0.0),
+                colour: [1.0; 4],
+                age: i as f32 / 3.0,
+            })
+            .collect::<Vec<_>>();
+        let camera = Point3::new(1.5, 0.0, 10.0);
+
+        let mut vertices = Vec::new();
+        extrude(&points, camera, 4.0, 0.001, &mut vertices);
+        assert_eq!(points.len() * 2, vertices.len());
+
+        for (pair, point) in vertices.chunks(2).zip(points.iter()) {
+            let distance = (camera.x - point.position.x).hypot(camera.z);
+            for vertex in pair {
+                let [x, y, z] = vertex.position;
+                // Spread across the path in the plane facing the camera,
+                // wider where it is further away
+                assert_eq!(point.position.x, x);
+                assert_eq!(0.0, z);
+                assert!((y.abs() - 2.0 * 0.001 * distance).abs() < 1e-6);
+                assert_eq!(point.age, vertex.age);
+            }
+            assert!(pair[0].position[1] * pair[1].position[1] < 0.0);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use cgmath::{Deg, Rad};
+use image::{DynamicImage, Rgba, RgbaImage};
+use specs::{Component, VecStorage};
+
+use crate::models::ring::Annulus;
+
+use super::{
+    light::include_lit_wgsl,
+    model::{Material, MaterialMaps, Model},
+    texture, vertex,
+};
+
+/// The number of quadrilaterals around the shared ring mesh
+const RING_SEGMENTS: usize = 128;
+
+/// The number of texels across the ring texture, from the inner edge to the
+/// outer edge
+const TEXTURE_SIZE: u32 = 256;
+
+/// A flat system of rings around a body, in the plane of its equator
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[storage(VecStorage)]
+pub struct Rings {
+    /// (as a multiple of the body's radius)
+    pub inner_radius: f32,
+    /// (as a multiple of the body's radius)
+    pub outer_radius: f32,
+    /// The angle between the rings and the ecliptic (in degrees)
+    pub tilt: f32,
+    pub colour: [f32; 3],
+    /// How much of the light behind the densest parts of the rings is
+    /// blocked, from 0 to 1
+    pub opacity: f32,
+}
+impl Rings {
+    pub fn new(inner_radius: f32, outer_radius: f32, tilt: f32, colour: [f32; 3]) -> Self {
+        Self {
+            inner_radius,
+            outer_radius,
+            tilt,
+            colour,
+            opacity: 0.8,
+        }
+    }
+
+    pub fn for_body(id: &str) -> Option<Self> {
+        //! The rings a body starts with, if it has noticeable ones
+        match id {
+            "saturn" => Some(Self::new(1.24, 2.27, 26.7, [0.85, 0.78, 0.65])),
+            "uranus" => Some(Self {
+                opacity: 0.3,
+                ..Self::new(1.64, 2.0, 97.8, [0.4, 0.4, 0.42])
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn to_raw(&self) -> RingsRaw {
+        let tilt: Rad<f32> = Deg(self.tilt).into();
+        RingsRaw {
+            colour: self.colour,
+            opacity: self.opacity,
+            radii_tilt: [self.inner_radius, self.outer_radius, tilt.0],
+        }
+    }
+}
+
+/// The raw representation of rings, drawn as a second instance buffer
+/// alongside the body's instance
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct RingsRaw {
+    colour: [f32; 3],
+    opacity: f32,
+    radii_tilt: [f32; 3],
+}
+impl vertex::Vertex for RingsRaw {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<RingsRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 12,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 13,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+fn density(v: f32) -> f32 {
+    //! How much light the rings block at a distance across them, from the
+    //! inner edge (0) to the outer edge (1)
+
+    let smoothstep = |edge0: f32, edge1: f32, x: f32| {
+        let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    };
+
+    // Many narrow ringlets of differing density
+    let ringlets = 0.7 + 0.3 * (v * 97.0).sin() * (v * 41.0).sin();
+    // The faint inner ring, brightening into the dense middle ring
+    let inner = 0.35 + 0.65 * smoothstep(0.15, 0.3, v);
+    // Wide and narrow gaps cleared by moons, like the Cassini division and
+    // the Encke gap in Saturn's rings
+    let gap = |centre: f32, width: f32| smoothstep(0.0, width, (v - centre).abs());
+    let gaps = (0.1 + 0.9 * gap(0.6, 0.035)) * (0.3 + 0.7 * gap(0.9, 0.008));
+    // The edges fade out rather than stopping sharply
+    let edges = smoothstep(0.0, 0.03, v) * (1.0 - smoothstep(0.97, 1.0, v));
+
+    (ringlets * inner * gaps * edges).clamp(0.0, 1.0)
+}
+
+fn texture_image() -> RgbaImage {
+    //! A column of texels across the rings, brighter where they are denser
+    //! and translucent everywhere
+
+    RgbaImage::from_fn(1, TEXTURE_SIZE, |_, y| {
+        let v = (y as f32 + 0.5) / TEXTURE_SIZE as f32;
+        let density = density(v);
+        let brightness = ((0.6 + 0.4 * density) * 255.0) as u8;
+        Rgba([brightness, brightness, brightness, (density * 255.0) as u8])
+    })
+}
+
+pub fn create_model(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+) -> Model {
+    //! Creates the model shared by every body's rings, an annulus whose
+    //! radii are replaced by each body's own when drawn
+
+    let mesh = Annulus::new(1.0, 2.0, RING_SEGMENTS).into_mesh(device, "Rings".to_string());
+
+    let texture = texture::Texture::from_image(
+        device,
+        queue,
+        &DynamicImage::ImageRgba8(texture_image()),
+        Some("Rings Texture"),
+        false,
+    );
+    let material = Material::new(
+        device,
+        queue,
+        "Rings Material",
+        texture,
+        MaterialMaps::default(),
+        layout,
+    );
+
+    Model::new(vec![Arc::new(mesh)], vec![Arc::new(material)])
+}
+
+pub fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    colour_format: wgpu::TextureFormat,
+    vertex_layouts: &[wgpu::VertexBufferLayout],
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline for rings, which are blended over what is
+    //! behind them and seen from either side
+
+    let shader = device.create_shader_module(include_lit_wgsl!("shaders/rings.wgsl"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Rings Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: vertex_layouts,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: colour_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: texture::Texture::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: texture::Texture::DEPTH_COMPARE,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+pub fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    //! Creates a buffer that can hold the raw rings of `capacity` bodies
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Rings Instance Buffer"),
+        size: (capacity * std::mem::size_of::<RingsRaw>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::density;
+
+    #[test]
+    fn test_density_has_gaps_and_soft_edges() {
+        assert_eq!(0.0, density(0.0));
+        assert!(density(0.995) < 0.1);
+
+        // The wide gap is much thinner than the rings either side of it
+        assert!(density(0.6) < 0.2 * density(0.55).max(density(0.66)));
+        assert!((0.0..=1.0).contains(&density(0.4)));
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::Range;
+
+use cgmath::{InnerSpace, Vector3};
+use specs::{Join, Read, ReadStorage, World, Write};
+
+use crate::{
+    locale::tr,
+    panel::{dynamic_exponent_formatter, format_duration},
+    simulation::{Identifier, Position, PositionScaleFactor},
+    util::AU,
+};
+
+use super::{
+    camera::{Camera, Projection},
+    components::RenderModel,
+    follow::{pointer_ray, ray_sphere},
+    lines::LineVertex,
+};
+
+/// (in ms^-1)
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+const RULER_COLOUR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+
+/// One end of a measurement
+#[derive(Debug, Clone)]
+pub enum RulerEnd {
+    /// Moves with the body
+    Body(Identifier),
+    /// A fixed point on the ecliptic (in metres)
+    Point(Vector3<f64>),
+}
+
+/// Measures the distance between two bodies or points on the ecliptic, picked
+/// by clicking on them while the ruler is active
+#[derive(Debug, Clone, Default)]
+pub struct Ruler {
+    pub active: bool,
+    ends: Vec<RulerEnd>,
+}
+impl Ruler {
+    pub fn clear(&mut self) {
+        self.ends.clear();
+    }
+
+    fn pick(&mut self, end: RulerEnd) {
+        //! Add an end, starting a new measurement if there already is one
+
+        if self.ends.len() == 2 {
+            self.ends.clear();
+        }
+        self.ends.push(end);
+    }
+
+    fn positions(
+        &self,
+        ids: &ReadStorage<Identifier>,
+        positions: &ReadStorage<Position>,
+    ) -> Vec<Vector3<f64>> {
+        //! The current position of each end (in metres), leaving out bodies
+        //! that no longer exist
+
+        self.ends
+            .iter()
+            .filter_map(|end| match end {
+                RulerEnd::Body(body) => (ids, positions)
+                    .join()
+                    .find(|(id, _)| id.get_id() == body.get_id())
+                    .map(|(_, position)| position.0),
+                RulerEnd::Point(point) => Some(*point),
+            })
+            .collect()
+    }
+}
+
+pub fn pick(ctx: &egui::Context, world: &World, camera: &Camera, projection: &Projection) {
+    //! Add the body under the cursor as an end of the ruler when it is
+    //! clicked, or the point on the ecliptic under it if there is no body
+
+    let (clicked, pointer, screen) = {
+        let input = ctx.input();
+        (
+            input.pointer.primary_clicked(),
+            input.pointer.interact_pos(),
+            input.screen_rect(),
+        )
+    };
+
+    let (ids, models, scale_factor, mut ruler): (
+        ReadStorage<Identifier>,
+        ReadStorage<RenderModel>,
+        Read<PositionScaleFactor>,
+        Write<Ruler>,
+    ) = world.system_data();
+
+    if !ruler.active {
+        return;
+    }
+    let pointer = match pointer {
+        Some(pointer) if clicked && !ctx.is_pointer_over_area() => pointer,
+        _ => return,
+    };
+    let (origin, direction) = match pointer_ray(pointer, screen, camera, projection) {
+        Some(ray) => ray,
+        None => return,
+    };
+
+    let body = (&ids, &models)
+        .join()
+        .filter_map(|(id, model)| {
+            let (centre, radius) = model.bounding_sphere();
+            ray_sphere(origin, direction, centre, radius).map(|distance| (distance, id))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, id)| RulerEnd::Body(id.clone()));
+
+    // The ecliptic is the plane z = 0, which the ray may never reach
+    let point = || {
+        let along = -origin.z / direction.z;
+        (along.is_finite() && along > 0.0).then(|| {
+            RulerEnd::Point((origin + direction * along).map(|a| a as f64) * scale_factor.0)
+        })
+    };
+
+    if let Some(end) = body.or_else(point) {
+        ruler.pick(end);
+    }
+}
+
+pub fn build_vertices(world: &World, vertices: &mut Vec<LineVertex>, ranges: &mut Vec<Range<u32>>) {
+    //! Add the vertices of the line between the ends of the ruler, once both
+    //! have been picked
+
+    let (ids, positions, scale_factor, ruler): (
+        ReadStorage<Identifier>,
+        ReadStorage<Position>,
+        Read<PositionScaleFactor>,
+        Read<Ruler>,
+    ) = world.system_data();
+
+    if let [from, to] = ruler.positions(&ids, &positions)[..] {
+        let start = vertices.len() as u32;
+        for point in [from, to] {
+            vertices.push(LineVertex::new(
+                (point / scale_factor.0).map(|a| a as f32).into(),
+                RULER_COLOUR,
+            ));
+        }
+        ranges.push(start..vertices.len() as u32);
+    }
+}
+
+pub fn draw_label(ctx: &egui::Context, world: &World, camera: &Camera, projection: &Projection) {
+    //! Show the distance measured by the ruler halfway along it, or where to
+    //! click next if only one end has been picked
+
+    let (ids, positions, scale_factor, mut ruler): (
+        ReadStorage<Identifier>,
+        ReadStorage<Position>,
+        Read<PositionScaleFactor>,
+        Write<Ruler>,
+    ) = world.system_data();
+
+    let ends = ruler.positions(&ids, &positions);
+    let (anchor, distance) = match ends[..] {
+        [from, to] => ((from + to) / 2.0, Some((to - from).magnitude())),
+        [end] => (end, None),
+        _ => return,
+    };
+
+    // Skip the label if its anchor is behind the camera or off the screen
+    let anchor = (anchor / scale_factor.0).map(|a| a as f32);
+    let screen_position =
+        match camera.project_to_screen(projection, anchor, ctx.input().screen_rect()) {
+            Some(screen_position) => screen_position,
+            None => return,
+        };
+
+    let mut dismissed = false;
+    egui::Area::new("ruler_label")
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen_position + egui::vec2(8.0, 8.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                match distance {
+                    Some(distance) => {
+                        // Light takes about a second to reach the Moon, which
+                        // would show as 0 or 1 in whole seconds
+                        let light_time = distance / SPEED_OF_LIGHT;
+                        let light_time = if light_time < 60.0 {
+                            format!("{:.3} s", light_time)
+                        } else {
+                            format_duration(light_time)
+                        };
+
+                        ui.label(tr!(
+                            "ruler-distance",
+                            km = dynamic_exponent_formatter()(distance / 1000.0, 0..=3),
+                            au = format!("{:.4}", distance / AU)
+                        ));
+                        ui.label(tr!("ruler-light-time", time = light_time));
+                    }
+                    None => {
+                        ui.weak(tr!("ruler-pick-second"));
+                    }
+                }
+
+                if ui.small_button(tr!("ruler-dismiss")).clicked() {
+                    dismissed = true;
+                }
+            });
+        });
+
+    if dismissed {
+        ruler.clear();
+    }
+}
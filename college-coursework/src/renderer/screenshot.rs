@@ -0,0 +1,211 @@
+use std::{num::NonZeroU32, path::PathBuf};
+
+use image::RgbaImage;
+use log::{info, warn};
+use tokio::sync::oneshot;
+
+use crate::{locale::tr, log::LOG_DIR, toasts::ToastSender};
+
+lazy_static! {
+    /// Where screenshots are saved when no path is given, next to the logs
+    pub static ref SCREENSHOT_DIR: PathBuf = LOG_DIR
+        .parent()
+        .map_or_else(|| LOG_DIR.join("screenshots"), |dir| dir.join("screenshots"));
+}
+
+/// A screenshot to take of the next frame
+pub struct ScreenshotRequest {
+    pub path: PathBuf,
+    /// Sent the path the screenshot was saved to, or why it wasn't taken
+    reply: oneshot::Sender<Result<PathBuf, String>>,
+}
+impl ScreenshotRequest {
+    pub fn new(path: Option<PathBuf>) -> (Self, oneshot::Receiver<Result<PathBuf, String>>) {
+        //! Request a screenshot saved to a path, or the screenshot directory
+        //! if there isn't one. The receiver is sent the result once the
+        //! frame has been saved
+
+        let path = path.unwrap_or_else(|| {
+            SCREENSHOT_DIR.join(format!(
+                "screenshot-{}.png",
+                chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+            ))
+        });
+        let (reply, receiver) = oneshot::channel();
+
+        (Self { path, reply }, receiver)
+    }
+
+    fn fail(self, err: &str, toasts: &ToastSender) {
+        warn!("Failed to take a screenshot: {}", err);
+        toasts.error(tr!("screenshot-failed", error = err));
+        let _ = self.reply.send(Err(err.to_string()));
+    }
+}
+
+/// The screenshots waiting for the next frame
+#[derive(Default)]
+pub struct ScreenshotRequests(pub Vec<ScreenshotRequest>);
+
+/// A frame being copied out of the surface for the screenshots requested
+pub struct ScreenshotCapture {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    /// Rows of the copy are padded to the alignment wgpu requires
+    padded_bytes_per_row: u32,
+    /// The surface stores blue before red
+    bgra: bool,
+    requests: Vec<ScreenshotRequest>,
+    toasts: ToastSender,
+}
+impl ScreenshotCapture {
+    pub fn begin(
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        config: &wgpu::SurfaceConfiguration,
+        requests: Vec<ScreenshotRequest>,
+        toasts: ToastSender,
+    ) -> Option<Self> {
+        //! Copy the frame about to be presented into a buffer, if any
+        //! screenshots were requested
+
+        if requests.is_empty() {
+            return None;
+        }
+
+        let bgra = match config.format {
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            _ => {
+                let err = format!("Can't read frames in the {:?} format", config.format);
+                requests
+                    .into_iter()
+                    .for_each(|request| request.fail(&err, &toasts));
+                return None;
+            }
+        };
+        // GL surfaces can only be rendered to, see `AdapterReport`
+        if !config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            requests.into_iter().for_each(|request| {
+                request.fail("Frames can't be read with this backend", &toasts)
+            });
+            return None;
+        }
+
+        let unpadded_bytes_per_row = config.width * 4;
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = (unpadded_bytes_per_row + alignment - 1) / alignment * alignment;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Screenshot Buffer"),
+            size: (padded_bytes_per_row * config.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Some(Self {
+            buffer,
+            width: config.width,
+            height: config.height,
+            padded_bytes_per_row,
+            bgra,
+            requests,
+            toasts,
+        })
+    }
+
+    pub fn finish(self, device: &wgpu::Device) {
+        //! Read the frame back once it has been rendered, then save it to
+        //! each requested path on another thread
+
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        if let Err(err) = receiver
+            .recv()
+            .map_err(|err| err.to_string())
+            .and_then(|result| result.map_err(|err| err.to_string()))
+        {
+            let err = format!("Failed to read the frame: {}", err);
+            let toasts = self.toasts;
+            self.requests
+                .into_iter()
+                .for_each(|request| request.fail(&err, &toasts));
+            return;
+        }
+
+        // Remove the padding from the end of each row
+        let mut pixels = Vec::with_capacity((self.width * self.height * 4) as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks(self.padded_bytes_per_row as usize)
+        {
+            pixels.extend_from_slice(&row[..(self.width * 4) as usize]);
+        }
+        self.buffer.unmap();
+
+        if self.bgra {
+            pixels.chunks_mut(4).for_each(|pixel| pixel.swap(0, 2));
+        }
+
+        let (width, height, requests, toasts) =
+            (self.width, self.height, self.requests, self.toasts);
+        // Encoding the image is slow, so it is done without holding up the
+        // next frame
+        std::thread::spawn(move || {
+            let image = match RgbaImage::from_raw(width, height, pixels) {
+                Some(image) => image,
+                None => {
+                    requests
+                        .into_iter()
+                        .for_each(|request| request.fail("The frame was the wrong size", &toasts));
+                    return;
+                }
+            };
+
+            for request in requests {
+                let result = request
+                    .path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .map_err(|err| err.to_string())
+                    .and_then(|_| image.save(&request.path).map_err(|err| err.to_string()));
+
+                match result {
+                    Ok(()) => {
+                        info!("Saved a screenshot to {:?}", request.path);
+                        toasts.info(tr!(
+                            "screenshot-saved",
+                            path = request.path.display().to_string()
+                        ));
+                        let _ = request.reply.send(Ok(request.path));
+                    }
+                    Err(err) => request.fail(&err, &toasts),
+                }
+            }
+        });
+    }
+}
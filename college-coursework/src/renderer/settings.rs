@@ -0,0 +1,199 @@
+use std::fs;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::CONFIG_DIR, locale::tr};
+
+use super::post::PostSettings;
+
+const RENDER_SETTINGS_FILE: &'static str = "render.toml";
+
+/// How frames are presented to the window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum PresentMode {
+    /// Wait for the display to refresh, so there is no tearing
+    AutoVsync,
+    /// Present as soon as a frame is ready, which may tear
+    Immediate,
+    /// Replace the waiting frame with newer ones without tearing
+    Mailbox,
+}
+impl PresentMode {
+    pub const ALL: [PresentMode; 3] = [
+        PresentMode::AutoVsync,
+        PresentMode::Immediate,
+        PresentMode::Mailbox,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            PresentMode::AutoVsync => tr!("present-mode-vsync"),
+            PresentMode::Immediate => tr!("present-mode-immediate"),
+            PresentMode::Mailbox => tr!("present-mode-mailbox"),
+        }
+    }
+
+    pub fn to_wgpu(&self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+}
+
+/// The graphics API used to render
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum Backend {
+    /// Whichever of the primary backends is available
+    Auto,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+}
+impl Backend {
+    pub const ALL: [Backend; 5] = [
+        Backend::Auto,
+        Backend::Vulkan,
+        Backend::Metal,
+        Backend::Dx12,
+        Backend::Gl,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Backend::Auto => tr!("backend-automatic"),
+            Backend::Vulkan => "Vulkan".to_string(),
+            Backend::Metal => "Metal".to_string(),
+            Backend::Dx12 => "DirectX 12".to_string(),
+            Backend::Gl => "OpenGL".to_string(),
+        }
+    }
+
+    pub fn backends(&self) -> wgpu::Backends {
+        match self {
+            Backend::Auto => wgpu::Backends::PRIMARY,
+            Backend::Vulkan => wgpu::Backends::VULKAN,
+            Backend::Metal => wgpu::Backends::METAL,
+            Backend::Dx12 => wgpu::Backends::DX12,
+            Backend::Gl => wgpu::Backends::GL,
+        }
+    }
+}
+
+/// Options for how the window is rendered. The present mode, reflections and
+/// effects can be changed while running, the backend and surface format are
+/// only used on launch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderSettings {
+    pub present_mode: PresentMode,
+    pub backend: Backend,
+    /// Name of the preferred surface format, the first supported format is
+    /// used if it is None or isn't supported
+    pub surface_format: Option<String>,
+    /// Whether reflective materials reflect the stars and lights around them
+    pub reflections: bool,
+    /// Whether the real stars are drawn behind the solar system
+    pub stars: bool,
+    /// Whether frames are only drawn when something has changed, rather than
+    /// continuously
+    pub power_saving: bool,
+    /// The full screen effects applied after the scene is drawn
+    pub post: PostSettings,
+}
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::AutoVsync,
+            backend: Backend::Auto,
+            surface_format: None,
+            reflections: true,
+            stars: true,
+            power_saving: true,
+            post: PostSettings::default(),
+        }
+    }
+}
+impl RenderSettings {
+    pub fn load() -> Self {
+        //! Load the render settings saved by a previous session, falling back
+        //! to the defaults if there are none
+
+        let path = CONFIG_DIR.join(RENDER_SETTINGS_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid render settings in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        //! Save the render settings for use by later sessions
+
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize render settings: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(CONFIG_DIR.as_path())
+            .and_then(|_| fs::write(CONFIG_DIR.join(RENDER_SETTINGS_FILE), contents))
+        {
+            warn!("Failed to save render settings: {}", err);
+        }
+    }
+
+    pub fn choose_format(&self, supported: &[wgpu::TextureFormat]) -> wgpu::TextureFormat {
+        //! The preferred surface format if it is supported, otherwise the
+        //! surface's own preference
+
+        self.surface_format
+            .as_ref()
+            .and_then(|name| {
+                supported
+                    .iter()
+                    .find(|format| &format_name(**format) == name)
+            })
+            .copied()
+            .unwrap_or(supported[0])
+    }
+}
+
+pub fn format_name(format: wgpu::TextureFormat) -> String {
+    //! The name a surface format is saved under
+    format!("{:?}", format)
+}
+
+/// What the graphics adapter in use is and what it supports
+#[derive(Debug, Clone)]
+pub struct AdapterReport {
+    pub info: wgpu::AdapterInfo,
+    pub limits: wgpu::Limits,
+    pub present_modes: Vec<wgpu::PresentMode>,
+    pub formats: Vec<wgpu::TextureFormat>,
+    /// The format the surface was configured with on launch
+    pub format: wgpu::TextureFormat,
+}
+impl AdapterReport {
+    pub fn supports(&self, present_mode: PresentMode) -> bool {
+        //! Whether the surface can be presented to in a mode, vsync is always
+        //! supported since wgpu falls back to plain Fifo
+
+        present_mode == PresentMode::AutoVsync
+            || self.present_modes.contains(&present_mode.to_wgpu())
+    }
+
+    pub fn supports_screenshots(&self) -> bool {
+        //! Whether frames can be copied out of the surface, which GL surfaces
+        //! don't allow
+        self.info.backend != wgpu::Backend::Gl
+    }
+}
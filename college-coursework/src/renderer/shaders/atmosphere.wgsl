@@ -0,0 +1,89 @@
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+@group(1) @binding(0)
+var<uniform> lights: Lights;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+};
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+    @location(9) normal_matrix_0: vec3<f32>,
+    @location(10) normal_matrix_1: vec3<f32>,
+    @location(11) normal_matrix_2: vec3<f32>,
+};
+struct AtmosphereInput {
+    // The colour, with the thickness in the last component
+    @location(12) colour_thickness: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) colour: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+    atmosphere: AtmosphereInput,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+    let normal_matrix = mat3x3<f32>(
+        instance.normal_matrix_0,
+        instance.normal_matrix_1,
+        instance.normal_matrix_2,
+    );
+
+    // The atmosphere is a shell slightly larger than the body
+    let scale = 1.0 + atmosphere.colour_thickness.w;
+    let world_position = model_matrix * vec4<f32>(model.position * scale, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * world_position;
+    out.world_position = world_position.xyz;
+    out.world_normal = normalize(normal_matrix * model.normal);
+    out.colour = atmosphere.colour_thickness.xyz;
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let view_dir = normalize(camera.view_pos.xyz - in.world_position);
+
+    // Fresnel term, the atmosphere is seen through at a grazing angle near
+    // the edge of the body so glows the most there
+    let rim = pow(1.0 - max(dot(normal, view_dir), 0.0), 3.0);
+
+    // Only the lit side of the atmosphere glows, the glow wraps slightly past
+    // the terminator as light is scattered around the body
+    var lit = vec3<f32>(0.0, 0.0, 0.0);
+    for (var i = 0u; i < lights.count; i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - in.world_position);
+        let strength = clamp(dot(normal, light_dir) + 0.3, 0.0, 1.0);
+        lit = lit + light.colour * light.intensity
+            * attenuation(light, in.world_position, lights.reference_distance) * strength;
+    }
+
+    return vec4<f32>(in.colour * lit, rim);
+}
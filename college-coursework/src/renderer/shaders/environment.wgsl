@@ -0,0 +1,68 @@
+struct Capture {
+    origin: vec4<f32>,
+    face: u32,
+    roughness: f32,
+};
+@group(0) @binding(0)
+var<uniform> capture: Capture;
+
+@group(1) @binding(0)
+var<uniform> lights: Lights;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// A single triangle covering the whole face
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// The direction a point on a face of the cube looks in, in the order of the
+// layers of a cube texture
+fn face_direction(face: u32, uv: vec2<f32>) -> vec3<f32> {
+    let u = uv.x * 2.0 - 1.0;
+    let v = uv.y * 2.0 - 1.0;
+    switch face {
+        case 0u: { return vec3<f32>(1.0, -v, -u); }
+        case 1u: { return vec3<f32>(-1.0, -v, u); }
+        case 2u: { return vec3<f32>(u, 1.0, v); }
+        case 3u: { return vec3<f32>(u, -1.0, -v); }
+        case 4u: { return vec3<f32>(u, -v, 1.0); }
+        default: { return vec3<f32>(-u, -v, -1.0); }
+    }
+}
+
+fn hash(cell: vec3<f32>) -> f32 {
+    return fract(sin(dot(cell, vec3<f32>(12.9898, 78.233, 37.719))) * 43758.5453);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let direction = normalize(face_direction(capture.face, in.uv));
+
+    // Scatter faint stars across the sky, which blur into an even glow on
+    // rough surfaces
+    let star = step(0.997, hash(floor(direction * 300.0))) * 0.5;
+    var colour = vec3<f32>(1.0, 1.0, 1.0) * mix(star, 0.0015, capture.roughness);
+
+    // Each light is a bright disc with a glow that spreads out on rougher
+    // surfaces, keeping roughly the same total energy
+    let sharpness = mix(4000.0, 4.0, capture.roughness);
+    for (var i = 0u; i < lights.count; i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - capture.origin.xyz);
+        let glow = pow(max(dot(direction, light_dir), 0.0), sharpness) * sharpness / 4000.0;
+        colour = colour + light.colour * light.intensity
+            * attenuation(light, capture.origin.xyz, lights.reference_distance) * glow * 20.0;
+    }
+
+    return vec4<f32>(colour, 1.0);
+}
@@ -6,10 +6,17 @@ var<uniform> camera: Camera;
 
 struct Light {
     position: vec3<f32>,
+    intensity: f32,
     colour: vec3<f32>,
+    luminosity: f32,
+};
+struct Lights {
+    lights: array<Light, 4>,
+    count: u32,
+    reference_distance: f32,
 };
 @group(1) @binding(0)
-var<uniform> light: Light;
+var<uniform> lights: Lights;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -23,8 +30,10 @@ struct VertexOutput {
 @vertex
 fn vs_main(
     model: VertexInput,
+    // Each instance draws a different light
+    @builtin(instance_index) light_index: u32,
 ) -> VertexOutput {
-    let scale = 1;
+    let light = lights.lights[light_index];
     var out: VertexOutput;
     
     // Calculate the position of the vertex, and output it
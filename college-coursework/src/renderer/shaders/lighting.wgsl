@@ -0,0 +1,22 @@
+// Put before the shaders that light bodies by `include_lit_wgsl!`, which each
+// bind the lights uniform in their own group
+
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+    colour: vec3<f32>,
+    luminosity: f32,
+};
+struct Lights {
+    // Must match MAX_LIGHTS in light.rs
+    lights: array<Light, 4>,
+    count: u32,
+    reference_distance: f32,
+};
+
+// The inverse square falloff of a light at a position, a star as luminous as
+// the Sun is at full intensity at the reference distance (1 AU)
+fn attenuation(light: Light, position: vec3<f32>, reference_distance: f32) -> f32 {
+    let distance = length(light.position - position) / reference_distance;
+    return light.luminosity / max(distance * distance, 0.0001);
+}
@@ -0,0 +1,122 @@
+struct Post {
+    texel_size: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+@group(0) @binding(1)
+var s_source: sampler;
+@group(0) @binding(2)
+var<uniform> post: Post;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// A single triangle covering the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn source(uv: vec2<f32>) -> vec3<f32> {
+    return textureSample(t_source, s_source, uv).rgb;
+}
+
+// Copies the image to the screen unchanged, after any effects
+@fragment
+fn fs_copy(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(source(in.uv), 1.0);
+}
+
+// Maps the high dynamic range of the scene onto the screen's, with an
+// approximation of the ACES filmic curve
+@fragment
+fn fs_tonemap(in: VertexOutput) -> @location(0) vec4<f32> {
+    let x = max(source(in.uv), vec3<f32>(0.0));
+    let mapped = (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14);
+    return vec4<f32>(clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+}
+
+// How much the corners are darkened
+let VIGNETTE_STRENGTH: f32 = 0.35;
+
+@fragment
+fn fs_vignette(in: VertexOutput) -> @location(0) vec4<f32> {
+    let from_centre = length(in.uv - vec2<f32>(0.5));
+    let shade = 1.0 - VIGNETTE_STRENGTH * smoothstep(0.3, 0.8, from_centre);
+    return vec4<f32>(source(in.uv) * shade, 1.0);
+}
+
+// Limits of how far FXAA blurs along an edge, in texels
+let FXAA_SPAN_MAX: f32 = 8.0;
+let FXAA_REDUCE_MUL: f32 = 0.125;
+let FXAA_REDUCE_MIN: f32 = 0.0078125;
+
+fn luma(colour: vec3<f32>) -> f32 {
+    // Perceived brightness, roughly gamma encoded so edges in dark areas
+    // aren't missed
+    return sqrt(dot(colour, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+// Smooths jagged edges by blurring along them, found from the difference in
+// brightness with the neighbouring pixels
+@fragment
+fn fs_fxaa(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = post.texel_size;
+
+    let rgb_m = source(in.uv);
+    let luma_nw = luma(source(in.uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(source(in.uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(source(in.uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(source(in.uv + vec2<f32>(1.0, 1.0) * texel));
+    let luma_m = luma(rgb_m);
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // The direction of the edge, across the change in brightness
+    var direction = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    let reduce = max(
+        (luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * FXAA_REDUCE_MUL,
+        FXAA_REDUCE_MIN
+    );
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(
+        direction * scale,
+        vec2<f32>(-FXAA_SPAN_MAX),
+        vec2<f32>(FXAA_SPAN_MAX)
+    ) * texel;
+
+    let rgb_a = 0.5 * (
+        source(in.uv + direction * (1.0 / 3.0 - 0.5)) +
+        source(in.uv + direction * (2.0 / 3.0 - 0.5))
+    );
+    let rgb_b = rgb_a * 0.5 + 0.25 * (
+        source(in.uv + direction * -0.5) +
+        source(in.uv + direction * 0.5)
+    );
+
+    // The wider blur is only used if it didn't pick up another edge
+    let luma_b = luma(rgb_b);
+    let outside = luma_b < luma_min || luma_b > luma_max;
+    return vec4<f32>(select(rgb_b, rgb_a, outside), 1.0);
+}
+
+let GAMMA: f32 = 2.2;
+
+// Encodes the linear colours for screens that don't do it themselves
+@fragment
+fn fs_gamma(in: VertexOutput) -> @location(0) vec4<f32> {
+    let colour = max(source(in.uv), vec3<f32>(0.0));
+    return vec4<f32>(pow(colour, vec3<f32>(1.0 / GAMMA)), 1.0);
+}
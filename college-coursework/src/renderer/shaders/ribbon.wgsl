@@ -0,0 +1,45 @@
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+// Opacity of the oldest point of a ribbon, must match TRAIL_TAIL_OPACITY in
+// trails.rs so ribbons fade like thin trails
+let TAIL_OPACITY: f32 = 0.1;
+
+struct VertexInput {
+    // The age is in the last component
+    @location(0) position_age: vec4<f32>,
+    @location(1) colour: vec4<f32>,
+    @location(2) edge: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) colour: vec4<f32>,
+    @location(1) age: f32,
+    @location(2) edge: f32,
+};
+
+@vertex
+fn vs_main(model: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(model.position_age.xyz, 1.0);
+    out.colour = model.colour;
+    out.age = model.position_age.w;
+    out.edge = model.edge;
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Ribbons fade in from their tail towards their head, and their edges
+    // are softened so they don't look jagged
+    let fade = mix(1.0, TAIL_OPACITY, clamp(in.age, 0.0, 1.0));
+    let edge = 1.0 - smoothstep(0.6, 1.0, abs(in.edge));
+    return vec4<f32>(in.colour.rgb, in.colour.a * fade * edge);
+}
@@ -0,0 +1,120 @@
+@group(0) @binding(0)
+var t_diffuse: texture_2d<f32>;
+@group(0) @binding(1)
+var s_diffuse: sampler;
+
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+};
+@group(1) @binding(0)
+var<uniform> camera: Camera;
+
+@group(2) @binding(0)
+var<uniform> lights: Lights;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+};
+struct RingsInput {
+    // The colour, with the opacity in the last component
+    @location(12) colour_opacity: vec4<f32>,
+    // The inner and outer radii, then the tilt (in radians)
+    @location(13) radii_tilt: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) tex_coords: vec2<f32>,
+    @location(3) colour_opacity: vec4<f32>,
+    // The centre and radius of the body the rings are around
+    @location(4) body: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+    rings: RingsInput,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+
+    // Every body's rings share a mesh, its edges are moved out to the rings'
+    // own radii (in multiples of the body's radius)
+    let radius = mix(rings.radii_tilt.x, rings.radii_tilt.y, model.tex_coords.y);
+    let in_plane = normalize(model.position.xy) * radius;
+
+    // Tilt the rings about the x axis, along with the body's equator
+    let s = sin(rings.radii_tilt.z);
+    let c = cos(rings.radii_tilt.z);
+    let position = vec3<f32>(in_plane.x, in_plane.y * c, in_plane.y * s);
+    let normal = vec3<f32>(0.0, -s, c);
+
+    let world_position = model_matrix * vec4<f32>(position, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * world_position;
+    out.world_position = world_position.xyz;
+    out.world_normal = normal;
+    out.tex_coords = model.tex_coords;
+    out.colour_opacity = rings.colour_opacity;
+    // The body's model is a unit sphere, scaled by the model matrix
+    out.body = vec4<f32>(model_matrix[3].xyz, length(model_matrix[0].xyz));
+    return out;
+}
+
+// Fragment shader
+
+// Whether the ray from a point towards a light passes through the body, so
+// the point is in the body's shadow
+fn in_shadow(position: vec3<f32>, light_dir: vec3<f32>, body: vec4<f32>) -> bool {
+    let to_centre = body.xyz - position;
+    let along = dot(to_centre, light_dir);
+    let closest = length(to_centre - light_dir * along);
+    return along > 0.0 && closest < body.w;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let object_colour = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let alpha = object_colour.a * in.colour_opacity.w;
+
+    let normal = normalize(in.world_normal);
+    let view_dir = normalize(camera.view_pos.xyz - in.world_position);
+
+    // The rings are lit a little on their night side, like the bodies
+    var lit = vec3<f32>(0.05, 0.05, 0.05);
+    for (var i = 0u; i < lights.count; i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - in.world_position);
+        if (in_shadow(in.world_position, light_dir, in.body)) {
+            continue;
+        }
+
+        // The rings are thin, so either side is lit, but light only reaches
+        // the side facing away from it through the gaps between the particles
+        var strength = abs(dot(normal, light_dir));
+        if (dot(normal, light_dir) * dot(normal, view_dir) < 0.0) {
+            strength = strength * (1.0 - alpha);
+        }
+
+        lit = lit + light.colour * light.intensity
+            * attenuation(light, in.world_position, lights.reference_distance) * strength;
+    }
+
+    return vec4<f32>(object_colour.rgb * in.colour_opacity.rgb * lit, alpha);
+}
@@ -11,12 +11,8 @@ struct CameraCenter {
 @group(1) @binding(1)
 var<uniform> camera_center: CameraCenter;
 
-struct Light {
-    position: vec3<f32>,
-    colour: vec3<f32>,
-};
 @group(2) @binding(0)
-var<uniform> light: Light;
+var<uniform> lights: Lights;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -38,9 +34,10 @@ struct InstanceInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
-    @location(1) tangent_position: vec3<f32>,
-    @location(2) tangent_light_position: vec3<f32>,
-    @location(3) tangent_view_position: vec3<f32>,
+    @location(1) world_position: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) world_tangent: vec3<f32>,
+    @location(4) world_bitangent: vec3<f32>,
 };
 
 @vertex
@@ -60,25 +57,18 @@ fn vs_main(
         instance.normal_matrix_2,
     );
 
-    // Construct the tangent matrix
-    let world_normal = normalize(normal_matrix * model.normal);
-    let world_tangent = normalize(normal_matrix * model.tangent);
-    let world_bitangent = normalize(normal_matrix * model.bitangent);
-    let tangent_matrix = transpose(mat3x3<f32>(
-        world_tangent,
-        world_bitangent,
-        world_normal,
-    ));
-
     let world_position = model_matrix * vec4<f32>(model.position, 1.0);
 
-    // Calculate the vertex location, and output it
+    // Calculate the vertex location, and output it along with the vectors
+    // used to construct the tangent matrix, lighting is done in world space as
+    // there can be multiple lights
     var out: VertexOutput;
     out.clip_position = camera.view_proj * world_position;
     out.tex_coords = model.tex_coords;
-    out.tangent_position = tangent_matrix * world_position.xyz;
-    out.tangent_view_position = tangent_matrix * camera.view_pos.xyz;
-    out.tangent_light_position = tangent_matrix * light.position;
+    out.world_position = world_position.xyz;
+    out.world_normal = normalize(normal_matrix * model.normal);
+    out.world_tangent = normalize(normal_matrix * model.tangent);
+    out.world_bitangent = normalize(normal_matrix * model.bitangent);
     return out;
 }
 
@@ -92,30 +82,95 @@ var s_diffuse: sampler;
 var t_normal: texture_2d<f32>;
 @group(0) @binding(3)
 var s_normal: sampler;
+@group(0) @binding(4)
+var t_specular: texture_2d<f32>;
+@group(0) @binding(5)
+var s_specular: sampler;
+@group(0) @binding(6)
+var t_roughness: texture_2d<f32>;
+@group(0) @binding(7)
+var s_roughness: sampler;
+@group(0) @binding(8)
+var t_metallic: texture_2d<f32>;
+@group(0) @binding(9)
+var s_metallic: sampler;
+
+// The stars and lights around the camera, blurrier at each mip level
+@group(3) @binding(0)
+var t_environment: texture_cube<f32>;
+@group(3) @binding(1)
+var s_environment: sampler;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let object_colour: vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
     let object_normal: vec4<f32> = textureSample(t_normal, s_normal, in.tex_coords);
-    
-    // Create ambient light
-    let ambient_strength = 0.5;
-    let ambient_colour = light.colour * ambient_strength;
-
-    // Create the lighting vectors
-    let tangent_normal = object_normal.xyz * 2.0 - 1.0;
-    let light_dir = normalize(in.tangent_light_position - in.tangent_position);
-    let view_dir = normalize(in.tangent_view_position - in.tangent_position);
-    let half_dir = normalize(view_dir + light_dir);
-
-    let diffuse_strength = max(dot(tangent_normal, light_dir), 0.0);
-
-    let diffuse_colour = vec3<f32>(0.0, 0.0, 0.0) * diffuse_strength;
-
-    let specular_strength = pow(max(dot(tangent_normal, half_dir), 0.0), 32.0);
-    let specular_colour = specular_strength * vec3<f32>(0.0, 0.0, 0.0);
+    let object_specular: f32 = textureSample(t_specular, s_specular, in.tex_coords).r;
+    let object_roughness: f32 = textureSample(t_roughness, s_roughness, in.tex_coords).r;
+    let object_metallic: f32 = textureSample(t_metallic, s_metallic, in.tex_coords).r;
+
+    // Create ambient light, so the night side of bodies is not completely black
+    let ambient_strength = 0.05;
+    let ambient_colour = vec3<f32>(1.0, 1.0, 1.0) * ambient_strength;
+
+    // Transform the normal from the normal map into world space
+    let tangent_matrix = mat3x3<f32>(
+        normalize(in.world_tangent),
+        normalize(in.world_bitangent),
+        normalize(in.world_normal),
+    );
+    let normal = normalize(tangent_matrix * (object_normal.xyz * 2.0 - 1.0));
+    let view_dir = normalize(camera.view_pos.xyz - in.world_position);
+
+    // Smooth surfaces (e.g. oceans) have small, sharp highlights while rough
+    // surfaces spread them out
+    let shininess = mix(256.0, 4.0, object_roughness);
+
+    var diffuse_colour = vec3<f32>(0.0, 0.0, 0.0);
+    var specular_colour = vec3<f32>(0.0, 0.0, 0.0);
+    for (var i = 0u; i < lights.count; i = i + 1u) {
+        let light = lights.lights[i];
+
+        // Create the lighting vectors
+        let light_dir = normalize(light.position - in.world_position);
+        let half_dir = normalize(view_dir + light_dir);
+        let light_colour = light.colour * light.intensity
+            * attenuation(light, in.world_position, lights.reference_distance);
+
+        let diffuse_strength = max(dot(normal, light_dir), 0.0);
+        diffuse_colour = diffuse_colour + light_colour * diffuse_strength;
+
+        let specular_strength = pow(max(dot(normal, half_dir), 0.0), shininess);
+        specular_colour = specular_colour + light_colour * specular_strength * object_specular;
+    }
+
+    // Reflect the environment, blurred more on rougher surfaces. Metals
+    // reflect in the colour of their surface and have no diffuse colour, other
+    // surfaces only reflect where they are specular
+    let max_lod = f32(textureNumLevels(t_environment) - 1);
+    let reflection = textureSampleLevel(
+        t_environment,
+        s_environment,
+        reflect(-view_dir, normal),
+        object_roughness * max_lod
+    ).rgb;
+    let reflectance = mix(
+        vec3<f32>(0.04, 0.04, 0.04) * object_specular,
+        object_colour.xyz,
+        object_metallic
+    );
+    let highlight_colour = mix(vec3<f32>(1.0, 1.0, 1.0), object_colour.xyz, object_metallic);
 
-    // Output the colour of the pixel
-    let result = (ambient_colour + diffuse_colour + specular_colour) * object_colour.xyz;
+    // Output the colour of the pixel, the specular highlight is the colour of
+    // the light rather than the surface, unless it is metallic
+    let result = (ambient_colour + diffuse_colour) * object_colour.xyz * (1.0 - object_metallic)
+        + specular_colour * highlight_colour
+        + reflection * reflectance;
     return vec4<f32>(result, object_colour.a);
-}
\ No newline at end of file
+}
+
+// Bodies that emit light, such as stars, are not lit by other lights
+@fragment
+fn fs_emissive(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+}
@@ -0,0 +1,61 @@
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct StarInput {
+    @location(0) direction: vec3<f32>,
+    @location(1) size: f32,
+    @location(2) colour: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+    @location(1) colour: vec4<f32>,
+};
+
+// The stars are infinitely far away, so are drawn on a sphere this far around
+// the camera, behind everything else
+let STAR_DISTANCE: f32 = 1000.0;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, star: StarInput) -> VertexOutput {
+    // Two triangles making a square facing the camera
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0)
+    );
+    let corner = corners[index];
+
+    // The square lies flat on the sky, unless the star is at the ecliptic's
+    // pole
+    var right = cross(star.direction, vec3<f32>(0.0, 0.0, 1.0));
+    if (length(right) < 0.001) {
+        right = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    right = normalize(right);
+    let up = cross(right, star.direction);
+
+    let direction = star.direction + (right * corner.x + up * corner.y) * star.size;
+    let position = camera.view_pos.xyz + direction * STAR_DISTANCE;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+    out.corner = corner;
+    out.colour = star.colour;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // A round point that fades out towards its edge
+    let falloff = 1.0 - smoothstep(0.0, 1.0, length(in.corner));
+    return vec4<f32>(in.colour.rgb * in.colour.a * falloff, 1.0);
+}
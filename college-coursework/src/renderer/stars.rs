@@ -0,0 +1,320 @@
+use std::num::ParseFloatError;
+
+use cgmath::{Deg, InnerSpace, Rad, Vector3};
+use thiserror::Error;
+use wgpu::util::DeviceExt;
+
+use super::{texture, vertex};
+
+/// The brightest stars, from the HYG catalogue
+const CATALOGUE: &str = include_str!("../../assets/stars/hyg-bright.csv");
+
+/// The tilt of the Earth's axis, between the celestial equator the catalogue
+/// is measured from and the ecliptic the scene is laid out on
+const OBLIQUITY: Deg<f64> = Deg(23.4393);
+
+/// The magnitude a star is drawn at full size and brightness
+const REFERENCE_MAGNITUDE: f32 = 1.0;
+/// The radius of a star at the reference magnitude (in radians)
+const STAR_SIZE: f32 = 0.003;
+/// Limits on how much a star is scaled by its brightness, so the faintest
+/// can still be seen and the brightest don't swamp their constellations
+const MIN_SCALE: f32 = 0.4;
+const MAX_SCALE: f32 = 2.5;
+
+/// A star's colour by its B-V colour index, from hot blue stars to cool red
+/// ones
+const COLOUR_INDICES: [(f32, [f32; 3]); 7] = [
+    (-0.4, [0.61, 0.71, 1.0]),
+    (0.0, [0.8, 0.86, 1.0]),
+    (0.4, [1.0, 0.97, 0.93]),
+    (0.8, [1.0, 0.89, 0.74]),
+    (1.2, [1.0, 0.8, 0.58]),
+    (1.6, [1.0, 0.71, 0.42]),
+    (2.0, [1.0, 0.6, 0.3]),
+];
+
+#[derive(Debug, Error)]
+pub enum ParseStarError {
+    #[error("Line {0} has {1} columns rather than 6")]
+    Columns(usize, usize),
+    #[error("Line {0} has an invalid number: {1}")]
+    Number(usize, ParseFloatError),
+}
+
+/// A star in the catalogue
+#[derive(Debug, Clone, PartialEq)]
+pub struct Star {
+    pub name: String,
+    /// Right ascension (in hours)
+    pub right_ascension: f64,
+    /// Declination (in degrees)
+    pub declination: f64,
+    /// Apparent magnitude, smaller is brighter
+    pub magnitude: f32,
+    /// B-V colour index, smaller is bluer
+    pub colour_index: f32,
+}
+impl Star {
+    pub fn direction(&self) -> Vector3<f64> {
+        //! The direction of the star from the solar system, with the ecliptic
+        //! in the xy plane and the vernal equinox along the x axis
+
+        let right_ascension: Rad<f64> = Deg(self.right_ascension * 15.0).into();
+        let declination: Rad<f64> = Deg(self.declination).into();
+        let equatorial = Vector3::new(
+            declination.0.cos() * right_ascension.0.cos(),
+            declination.0.cos() * right_ascension.0.sin(),
+            declination.0.sin(),
+        );
+
+        // Tilt the celestial equator onto the ecliptic about the equinox
+        let obliquity: Rad<f64> = OBLIQUITY.into();
+        let (sin, cos) = obliquity.0.sin_cos();
+        Vector3::new(
+            equatorial.x,
+            equatorial.y * cos + equatorial.z * sin,
+            -equatorial.y * sin + equatorial.z * cos,
+        )
+    }
+
+    fn to_raw(&self) -> StarRaw {
+        let scale = magnitude_scale(self.magnitude);
+        let [r, g, b] = colour(self.colour_index);
+        StarRaw {
+            direction: self.direction().normalize().map(|a| a as f32).into(),
+            size: STAR_SIZE * scale.clamp(MIN_SCALE, MAX_SCALE),
+            colour: [r, g, b, scale.min(1.0)],
+        }
+    }
+}
+
+pub fn parse_catalogue(catalogue: &str) -> Result<Vec<Star>, ParseStarError> {
+    //! Read the stars from a catalogue with HYG's hip, proper, ra, dec, mag
+    //! and ci columns, after a header line
+
+    catalogue
+        .lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let columns = line.split(',').map(str::trim).collect::<Vec<_>>();
+            if columns.len() != 6 {
+                return Err(ParseStarError::Columns(i + 1, columns.len()));
+            }
+            let number = |column: &str| {
+                column
+                    .parse::<f64>()
+                    .map_err(|err| ParseStarError::Number(i + 1, err))
+            };
+
+            Ok(Star {
+                name: columns[1].to_string(),
+                right_ascension: number(columns[2])?,
+                declination: number(columns[3])?,
+                magnitude: number(columns[4])? as f32,
+                colour_index: number(columns[5])? as f32,
+            })
+        })
+        .collect()
+}
+
+fn magnitude_scale(magnitude: f32) -> f32 {
+    //! How much bigger a star is drawn than one at the reference magnitude,
+    //! its area growing with its brightness
+    10f32.powf(-0.4 * (magnitude - REFERENCE_MAGNITUDE)).sqrt()
+}
+
+fn colour(colour_index: f32) -> [f32; 3] {
+    //! The colour of a star with a B-V colour index, interpolated between
+    //! the nearest known colours
+    let (first_index, first_colour) = COLOUR_INDICES[0];
+    if colour_index <= first_index {
+        return first_colour;
+    }
+    for window in COLOUR_INDICES.windows(2) {
+        let ((a, from), (b, to)) = (window[0], window[1]);
+        if colour_index <= b {
+            let t = (colour_index - a) / (b - a);
+            return [0, 1, 2].map(|i| from[i] + (to[i] - from[i]) * t);
+        }
+    }
+    COLOUR_INDICES[COLOUR_INDICES.len() - 1].1
+}
+
+/// The raw representation of a star, drawn as an instance of a square
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct StarRaw {
+    direction: [f32; 3],
+    /// Radius on the sky (in radians)
+    size: f32,
+    /// The alpha is the star's brightness
+    colour: [f32; 4],
+}
+impl vertex::Vertex for StarRaw {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<StarRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// The stars of the catalogue, drawn behind everything else at their real
+/// positions on the sky so the constellations can be recognised
+pub struct Starfield {
+    pipeline: wgpu::RenderPipeline,
+    buffer: wgpu::Buffer,
+    count: u32,
+}
+impl Starfield {
+    pub fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        colour_format: wgpu::TextureFormat,
+    ) -> Self {
+        //! Upload the stars of the bundled catalogue, using a pipeline layout
+        //! with the camera
+
+        let stars = parse_catalogue(CATALOGUE).expect("Failed to parse the star catalogue");
+        let stars_raw = stars.iter().map(Star::to_raw).collect::<Vec<_>>();
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Star Buffer"),
+            contents: bytemuck::cast_slice(&stars_raw),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        Self {
+            pipeline: create_render_pipeline(device, layout, colour_format),
+            buffer,
+            count: stars_raw.len() as u32,
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn draw<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        camera_bind_group: &'a wgpu::BindGroup,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.buffer.slice(..));
+        render_pass.draw(0..6, 0..self.count);
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    colour_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    //! Creates the render pipeline for stars, which add their light to the
+    //! sky and are hidden by anything drawn after them
+
+    use vertex::Vertex as _;
+
+    let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/stars.wgsl"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Star Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[StarRaw::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: colour_format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent::OVER,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: texture::Texture::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::InnerSpace;
+
+    use super::{colour, magnitude_scale, parse_catalogue, CATALOGUE};
+
+    #[test]
+    fn test_catalogue_parses() {
+        let stars = parse_catalogue(CATALOGUE).unwrap();
+        assert!(stars.len() > 100);
+
+        // Polaris is close to the celestial pole, which is tilted from the
+        // ecliptic's pole by the obliquity
+        let polaris = stars.iter().find(|star| star.name == "Polaris").unwrap();
+        let angle = polaris.direction().normalize().z.acos().to_degrees();
+        assert!((angle - 23.4).abs() < 1.0, "{}", angle);
+    }
+
+    #[test]
+    fn test_brighter_stars_are_bigger_and_bluer_stars_are_blue() {
+        assert!(magnitude_scale(-1.0) > magnitude_scale(1.0));
+        assert_eq!(1.0, magnitude_scale(super::REFERENCE_MAGNITUDE));
+
+        let blue = colour(-0.2);
+        let red = colour(1.8);
+        assert!(blue[2] > blue[0]);
+        assert!(red[0] > red[2]);
+    }
+}
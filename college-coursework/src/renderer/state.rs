@@ -2,6 +2,7 @@ use std::{rc::Rc, sync::Arc};
 
 use cgmath::{Euler, InnerSpace, Point3, Rotation3, Zero};
 use instant::Duration;
+use log::{info, warn};
 use specs::{Join, Read, ReadStorage, World, Write};
 use wgpu::{include_wgsl, util::DeviceExt};
 use winit::{
@@ -11,21 +12,74 @@ use winit::{
 };
 
 use crate::{
-    assets, models,
-    renderer::{instance::InstanceRaw, light::LightUniform, vertex::Vertex},
+    accessibility::AccessibilitySettings,
+    assets,
+    config::GraphicsConfig,
+    locale::tr,
+    models::{self, cache::ModelCache},
+    renderer::{
+        atmosphere::{self, Atmosphere, AtmosphereRaw},
+        instance::InstanceRaw,
+        light::{include_lit_wgsl, Light, LightUniform, LightsUniform},
+        rings::{self, Rings, RingsRaw},
+        settings::{AdapterReport, Backend, RenderSettings},
+        vertex::Vertex,
+    },
     setup::Dispatchers,
-    simulation::{DeltaTime, Identifier, Position},
+    simulation::{
+        Autosaver, DeltaTime, Identifier, Position, PositionScaleFactor, TimeJump, TimeScale,
+    },
+    stats::UsageStats,
+    toasts::Toasts,
+    util::AU,
 };
 
 use super::{
-    camera::{self, CameraPosition, CameraSpeed},
+    arrows,
+    attract::AttractMode,
+    camera::{self, CameraPosition, CameraRotation, CameraSpeed},
+    capture::FrameCapturer,
+    components,
     components::RenderModel,
-    instance,
+    cursor, debug,
+    environment::EnvironmentMap,
+    errors::{show_error_dialog, DeviceErrors, RenderError},
+    field::FieldOverlay,
+    follow,
+    fonts::FontSettings,
+    gamepad::GamepadHandler,
+    grid,
+    hud::{self, FrameStats, GpuTimer, RenderStats, Timestamp},
+    instance, labels,
     light::DrawLight,
+    lines,
     model::{self, DrawModel, Model},
-    texture,
+    path,
+    post::{self, PostProcessor},
+    ribbons, ruler,
+    screenshot::{ScreenshotCapture, ScreenshotRequests},
+    stars::Starfield,
+    texture, trails,
+    transparent::{self, Transparent},
 };
 
+/// The longest time between frames while power saving, so clocks such as the
+/// idle timeout of attract mode still run and a gamepad can wake the window
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The number of atmospheres the atmosphere buffer initially has room for
+const INITIAL_ATMOSPHERE_CAPACITY: usize = 8;
+
+/// The number of rings the rings buffer initially has room for
+const INITIAL_RINGS_CAPACITY: usize = 4;
+
+/// The number of points the line buffer initially has room for
+const INITIAL_LINE_CAPACITY: usize = 4096;
+
+/// The number of vertices the ribbon buffer initially has room for, two for
+/// each point of a trail
+const INITIAL_RIBBON_CAPACITY: usize = 8192;
+
 const NUM_INSTANCES_PER_ROW: u32 = 1;
 const INSTANCE_DISPLACEMENT: cgmath::Vector3<f32> = cgmath::Vector3::new(
     NUM_INSTANCES_PER_ROW as f32 * 0.5,
@@ -38,14 +92,21 @@ pub struct RenderPassContainer<'a>(wgpu::RenderPass<'a>);
 
 /// The state of the application render
 pub struct State {
-    surface: wgpu::Surface,
+    /// Only none while the device is being recreated, as a window can only
+    /// have one surface configured on it at a time
+    surface: Option<wgpu::Surface>,
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
     config: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
 
     render_pipeline: wgpu::RenderPipeline,
+    emissive_render_pipeline: wgpu::RenderPipeline,
     light_render_pipeline: wgpu::RenderPipeline,
+    atmosphere_render_pipeline: wgpu::RenderPipeline,
+    rings_render_pipeline: wgpu::RenderPipeline,
+    line_render_pipeline: wgpu::RenderPipeline,
+    ribbon_render_pipeline: wgpu::RenderPipeline,
 
     /*vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -59,7 +120,31 @@ pub struct State {
     camera_center_uniform: camera::CameraCenterUniform,
     camera_center_buffer: wgpu::Buffer,
     camera_bind_group: wgpu::BindGroup,
+    light_buffer: wgpu::Buffer,
     light_bind_group: wgpu::BindGroup,
+    environment_map: EnvironmentMap,
+    starfield: Starfield,
+    post_processor: PostProcessor,
+    atmosphere_buffer: wgpu::Buffer,
+    atmosphere_capacity: usize,
+    /// The annulus and texture every body's rings are drawn with
+    rings_model: Model,
+    rings_buffer: wgpu::Buffer,
+    rings_capacity: usize,
+    line_buffer: wgpu::Buffer,
+    line_capacity: usize,
+    ribbon_buffer: wgpu::Buffer,
+    ribbon_capacity: usize,
+    field_overlay: FieldOverlay,
+    frame_stats: FrameStats,
+    gpu_timer: Option<GpuTimer>,
+    frame_capturer: FrameCapturer,
+    gamepad: GamepadHandler,
+    autosaver: Autosaver,
+    device_errors: DeviceErrors,
+    /// Whether the user has been told about an error from the device, only
+    /// the first is shown as the same error is likely to happen every frame
+    error_reported: bool,
     pub camera_controller: Box<dyn camera::CameraController>,
 
     depth_texture: texture::Texture,
@@ -68,54 +153,116 @@ pub struct State {
     egui_ctx: egui::Context,
     egui_render_pass: egui_wgpu::renderer::RenderPass,
     ui_handler: crate::panel::UiHandler,
+    /// The render settings last applied to the surface
+    render_settings: RenderSettings,
+    adapter_report: AdapterReport,
+    attract_mode: AttractMode,
+    /// Whether the camera moved in the last update, which needs another frame
+    camera_moved: bool,
+    /// How long until the UI next has to be redrawn, zero if it is animating
+    repaint_after: Duration,
+    safe_mode: bool,
+    /// Render settings from the config, used again if the device is lost
+    graphics_config: GraphicsConfig,
 }
 impl State {
     pub async fn new(
         window: &Window,
         event_loop_window_target: &EventLoopWindowTarget<()>,
-    ) -> Self {
+        safe_mode: bool,
+        graphics_config: &GraphicsConfig,
+    ) -> Result<Self, RenderError> {
         //! Create a new application state and render pipeline
 
         let size = window.inner_size();
 
-        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
-        let surface = unsafe { instance.create_surface(window) };
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
+        // Safe mode ignores any saved settings, in case they caused the crash
+        let mut render_settings = if safe_mode {
+            RenderSettings::default()
+        } else {
+            RenderSettings::load()
+        };
+        graphics_config.apply(&mut render_settings);
+
+        // Fall back to the primary backends if the preferred one isn't
+        // available
+        let (surface, adapter) = match Self::request_adapter(window, render_settings.backend).await
+        {
+            Some(found) => found,
+            None => {
+                warn!(
+                    "No adapter found for the {:?} backend, falling back to the default",
+                    render_settings.backend
+                );
+                Self::request_adapter(window, Backend::Auto)
+                    .await
+                    .ok_or(RenderError::NoAdapter)?
+            }
+        };
 
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
+                    // Timestamps are only used to time frames, so are left
+                    // out in safe mode
+                    features: if safe_mode {
+                        wgpu::Features::empty()
+                    } else {
+                        adapter.features()
+                            & (wgpu::Features::TIMESTAMP_QUERY
+                                | wgpu::Features::WRITE_TIMESTAMP_INSIDE_PASSES)
+                    },
 
                     limits: wgpu::Limits::default(),
                     label: None,
                 },
                 None,
             )
-            .await
-            .unwrap();
+            .await?;
+        let device_errors = DeviceErrors::watch(&device);
+
+        let gpu_timer = GpuTimer::new(&device, &queue);
+
+        let formats = surface.get_supported_formats(&adapter);
+        let adapter_report = AdapterReport {
+            info: adapter.get_info(),
+            limits: adapter.limits(),
+            present_modes: surface.get_supported_modes(&adapter),
+            format: render_settings.choose_format(&formats),
+            formats,
+        };
+        info!(
+            "Rendering with {} ({:?})",
+            adapter_report.info.name, adapter_report.info.backend
+        );
 
-        let surface_format = surface.get_supported_formats(&adapter)[0];
+        let surface_format = adapter_report.format;
 
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            // Frames are copied out of the surface for screenshots
+            usage: if adapter_report.supports_screenshots() {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            },
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: wgpu::PresentMode::AutoVsync,
+            // Fifo is the only present mode every platform has to support
+            present_mode: if safe_mode {
+                wgpu::PresentMode::Fifo
+            } else if adapter_report.supports(render_settings.present_mode) {
+                render_settings.present_mode.to_wgpu()
+            } else {
+                wgpu::PresentMode::AutoVsync
+            },
         };
         surface.configure(&device, &config);
 
         let diffuse_bytes = include_bytes!("assets/happy-tree.png");
         let diffuse_texture =
-            texture::Texture::from_bytes(&device, &queue, diffuse_bytes, "happy-tree.png").unwrap();
+            texture::Texture::from_bytes(&device, &queue, diffuse_bytes, "happy-tree.png", false)
+                .unwrap();
 
         let texture_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -154,15 +301,67 @@ impl State {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    // Specular map
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 5,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    // Roughness map
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 7,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    // Metallic map
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 8,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 9,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
                 ],
                 label: Some("texture_bind_group_layout"),
             });
 
-        let light_uniform = LightUniform::new([0.0, 4.0, 0.0], [1.0, 1.0, 1.0]);
+        // The lights are updated from the Entity Component System each frame
+        let lights_uniform = LightsUniform::default();
 
         let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Light"),
-            contents: bytemuck::cast_slice(&[light_uniform]),
+            contents: bytemuck::cast_slice(&[lights_uniform]),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
@@ -201,7 +400,12 @@ impl State {
         let camera_projection =
             camera::Projection::new(config.width, config.height, cgmath::Deg(45.0), 0.1, 4000.0);
 
-        let camera_controller = Box::new(camera::FreeCameraController::new(20.0, 200.0, 1.0, 1.0));
+        let camera_controller = Box::new(camera::FreeCameraController::new(
+            camera::DEFAULT_CAMERA_SPEED,
+            200.0,
+            1.0,
+            1.0,
+        ));
 
         let mut camera_uniform = camera::CameraUniform::new();
 
@@ -265,6 +469,9 @@ impl State {
             label: Some("camera_bind_group"),
         });
 
+        // Reflective materials sample the sky captured around the camera
+        let environment_map = EnvironmentMap::new(&device, &light_bind_group_layout);
+
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
@@ -272,6 +479,7 @@ impl State {
                     &texture_bind_group_layout,
                     &camera_bind_group_layout,
                     &light_bind_group_layout,
+                    &environment_map.bind_group_layout,
                 ],
                 push_constant_ranges: &[],
             });
@@ -279,15 +487,33 @@ impl State {
         let depth_texture =
             texture::Texture::create_depth_texture(&device, &config, "depth_texture");
 
-        let shader = include_wgsl!("shaders/shader.wgsl");
+        // The scene is drawn offscreen, then the effects are applied on the
+        // way to the surface
+        let post_processor = PostProcessor::new(&device, &queue, &config);
+
+        let shader = include_lit_wgsl!("shaders/shader.wgsl");
 
         let render_pipeline = Self::create_render_pipeline(
             &device,
             &render_pipeline_layout,
-            config.format,
+            post::SCENE_FORMAT,
             Some(texture::Texture::DEPTH_FORMAT),
             &[model::ModelVertex::desc(), instance::InstanceRaw::desc()],
             shader,
+            "fs_main",
+        );
+
+        // Bodies that emit light are drawn with the same shader, but are not lit
+        let shader = include_lit_wgsl!("shaders/shader.wgsl");
+
+        let emissive_render_pipeline = Self::create_render_pipeline(
+            &device,
+            &render_pipeline_layout,
+            post::SCENE_FORMAT,
+            Some(texture::Texture::DEPTH_FORMAT),
+            &[model::ModelVertex::desc(), instance::InstanceRaw::desc()],
+            shader,
+            "fs_emissive",
         );
 
         let light_render_pipeline_layout =
@@ -302,38 +528,105 @@ impl State {
         let light_render_pipeline = Self::create_render_pipeline(
             &device,
             &light_render_pipeline_layout,
-            config.format,
+            post::SCENE_FORMAT,
             Some(texture::Texture::DEPTH_FORMAT),
             &[model::ModelVertex::desc()],
             shader,
+            "fs_main",
         );
 
-        let egui_state = egui_winit::State::new(event_loop_window_target);
-        let egui_ctx = egui::Context::default();
-        let egui_render_pass = egui_wgpu::renderer::RenderPass::new(&device, surface_format, 1);
+        // Atmospheres are drawn with the camera and lights, like the lights
+        let atmosphere_render_pipeline = atmosphere::create_render_pipeline(
+            &device,
+            &light_render_pipeline_layout,
+            post::SCENE_FORMAT,
+            &[
+                model::ModelVertex::desc(),
+                instance::InstanceRaw::desc(),
+                AtmosphereRaw::desc(),
+            ],
+        );
+        let atmosphere_buffer =
+            atmosphere::create_instance_buffer(&device, INITIAL_ATMOSPHERE_CAPACITY);
 
-        let mut egui_fonts = egui::FontDefinitions::default();
+        // Rings have a texture and are lit, but don't reflect anything
+        let rings_render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Rings Pipeline Layout"),
+                bind_group_layouts: &[
+                    &texture_bind_group_layout,
+                    &camera_bind_group_layout,
+                    &light_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
 
-        egui_fonts.font_data.insert(
-            "keycap".to_owned(),
-            egui::FontData::from_static(include_bytes!("../../assets/fonts/BkcapRegular.ttf")),
+        let rings_render_pipeline = rings::create_render_pipeline(
+            &device,
+            &rings_render_pipeline_layout,
+            post::SCENE_FORMAT,
+            &[
+                model::ModelVertex::desc(),
+                instance::InstanceRaw::desc(),
+                RingsRaw::desc(),
+            ],
         );
+        let rings_model = rings::create_model(&device, &queue, &texture_bind_group_layout);
+        let rings_buffer = rings::create_instance_buffer(&device, INITIAL_RINGS_CAPACITY);
 
-        egui_fonts.families.insert(
-            egui::FontFamily::Name("keycap".into()),
-            vec!["keycap".to_owned()],
+        // Lines only need the camera
+        let line_render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Line Pipeline Layout"),
+                bind_group_layouts: &[&camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let line_render_pipeline = lines::create_render_pipeline(
+            &device,
+            &line_render_pipeline_layout,
+            post::SCENE_FORMAT,
         );
+        let line_buffer = lines::create_vertex_buffer(&device, INITIAL_LINE_CAPACITY);
 
-        egui_ctx.set_fonts(egui_fonts);
+        // As do ribbons, which are extruded to face the camera as they are
+        // built
+        let ribbon_render_pipeline = ribbons::create_render_pipeline(
+            &device,
+            &line_render_pipeline_layout,
+            post::SCENE_FORMAT,
+        );
+        let ribbon_buffer = ribbons::create_vertex_buffer(&device, INITIAL_RIBBON_CAPACITY);
 
-        Self {
-            surface,
+        // The stars also only need the camera
+        let starfield = Starfield::new(&device, &line_render_pipeline_layout, post::SCENE_FORMAT);
+
+        let egui_state = egui_winit::State::new(event_loop_window_target);
+        let egui_ctx = egui::Context::default();
+        let egui_render_pass = egui_wgpu::renderer::RenderPass::new(&device, surface_format, 1);
+
+        // The fonts are set before the first frame, so the keycap family
+        // exists by the time the help window is drawn
+        let font_settings = if safe_mode {
+            FontSettings::default()
+        } else {
+            FontSettings::load()
+        };
+        egui_ctx.set_fonts(font_settings.definitions());
+
+        Ok(Self {
+            surface: Some(surface),
             device: Arc::new(device),
             queue: Arc::new(queue),
             config,
             size,
             render_pipeline,
+            emissive_render_pipeline,
             light_render_pipeline,
+            atmosphere_render_pipeline,
+            rings_render_pipeline,
+            line_render_pipeline,
+            ribbon_render_pipeline,
             /*vertex_buffer,
             index_buffer,
             num_vertices,*/
@@ -346,15 +639,43 @@ impl State {
             camera_center_uniform,
             camera_center_buffer,
             camera_bind_group,
+            light_buffer,
             light_bind_group,
+            environment_map,
+            starfield,
+            post_processor,
+            atmosphere_buffer,
+            atmosphere_capacity: INITIAL_ATMOSPHERE_CAPACITY,
+            rings_model,
+            rings_buffer,
+            rings_capacity: INITIAL_RINGS_CAPACITY,
+            line_buffer,
+            line_capacity: INITIAL_LINE_CAPACITY,
+            ribbon_buffer,
+            ribbon_capacity: INITIAL_RIBBON_CAPACITY,
+            field_overlay: FieldOverlay::new(),
+            frame_stats: FrameStats::new(),
+            gpu_timer,
+            frame_capturer: FrameCapturer::new(),
+            gamepad: GamepadHandler::new(),
+            autosaver: Autosaver::new(),
+            device_errors,
+            error_reported: false,
             camera_controller,
             depth_texture,
             texture_bind_group_layout: Arc::new(texture_bind_group_layout),
             egui_state,
             egui_ctx,
             egui_render_pass,
-            ui_handler: crate::panel::UiHandler::default(),
-        }
+            ui_handler: crate::panel::UiHandler::new(safe_mode),
+            render_settings,
+            adapter_report,
+            attract_mode: AttractMode::new(),
+            camera_moved: false,
+            repaint_after: Duration::ZERO,
+            safe_mode,
+            graphics_config: graphics_config.clone(),
+        })
     }
 
     fn create_render_pipeline(
@@ -364,6 +685,7 @@ impl State {
         depth_format: Option<wgpu::TextureFormat>,
         vertex_layouts: &[wgpu::VertexBufferLayout],
         shader: wgpu::ShaderModuleDescriptor,
+        fragment_entry_point: &str,
     ) -> wgpu::RenderPipeline {
         //! Creates a render pipeline
 
@@ -379,7 +701,7 @@ impl State {
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
-                entry_point: "fs_main",
+                entry_point: fragment_entry_point,
                 targets: &[Some(wgpu::ColorTargetState {
                     format: colour_format,
                     blend: Some(wgpu::BlendState::REPLACE),
@@ -398,7 +720,7 @@ impl State {
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: texture::Texture::DEPTH_FORMAT,
                 depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_compare: texture::Texture::DEPTH_COMPARE,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
@@ -418,11 +740,15 @@ impl State {
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
 
             // Update the depth texture to match the size of the window
             self.depth_texture =
                 texture::Texture::create_depth_texture(&self.device, &self.config, "depth_texture");
+            self.post_processor
+                .resize(&self.device, &self.queue, &self.config);
 
             // Update the camera projection
             self.camera_projection
@@ -435,11 +761,191 @@ impl State {
         self.egui_state.on_event(&self.egui_ctx, event)
     }
 
+    pub fn redraw_after(&self, world: &World) -> Option<Duration> {
+        //! How long the window can wait for input before drawing the next
+        //! frame, None if it should be drawn straight away. Frames are drawn
+        //! continuously unless power saving is on and nothing is moving
+
+        let (time_scale, jump): (Read<TimeScale>, Read<TimeJump>) = world.system_data();
+        let animating = !self.render_settings.power_saving
+            || self.camera_moved
+            || self.repaint_after.is_zero()
+            || !time_scale.paused
+            || jump.target().is_some();
+
+        (!animating).then_some(self.repaint_after.min(IDLE_REDRAW_INTERVAL))
+    }
+
+    pub fn register_input(&mut self) -> bool {
+        //! Record user input, returns whether it was used to leave attract mode
+        self.attract_mode.register_input(&mut self.camera)
+    }
+
+    async fn request_adapter(
+        window: &Window,
+        backend: Backend,
+    ) -> Option<(wgpu::Surface, wgpu::Adapter)> {
+        //! Find an adapter that can render to the window with a backend
+
+        let instance = wgpu::Instance::new(backend.backends());
+        let surface = unsafe { instance.create_surface(window) };
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await?;
+
+        Some((surface, adapter))
+    }
+
+    pub fn insert_render_settings(&self, world: &mut World) {
+        //! Add the render settings and adapter to the world, so they can be
+        //! shown and changed in the UI
+
+        world.insert(self.render_settings.clone());
+        world.insert(self.adapter_report.clone());
+    }
+
+    fn apply_render_settings(&mut self, world: &World) {
+        //! Reconfigure the surface if the present mode has been changed, and
+        //! save any changes for later sessions
+
+        let settings = world.read_resource::<RenderSettings>();
+        if *settings == self.render_settings {
+            return;
+        }
+
+        if settings.present_mode != self.render_settings.present_mode
+            && self.adapter_report.supports(settings.present_mode)
+        {
+            self.config.present_mode = settings.present_mode.to_wgpu();
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+        }
+
+        settings.save();
+        self.render_settings = settings.clone();
+    }
+
+    pub fn recover(
+        &mut self,
+        window: &Window,
+        event_loop_window_target: &EventLoopWindowTarget<()>,
+        world: &mut World,
+    ) -> Result<(), RenderError> {
+        //! Create a new device after the last one was lost, along with
+        //! everything made with it, keeping the camera and UI as they were
+
+        warn!("Recreating the graphics device");
+
+        // Configuring a second swapchain on the window while the old one is
+        // alive can fail on Vulkan and DX12, so the old surface goes first,
+        // along with the cached models and handles to the old device in the
+        // world. The old device itself goes with the last of what was made
+        // with it when this state is replaced
+        drop(self.surface.take());
+        world.insert(ModelCache::default());
+        world.remove::<Arc<wgpu::Device>>();
+        world.remove::<Arc<wgpu::Queue>>();
+
+        // Waiting for the device doesn't need a full runtime
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let mut recovered = runtime.block_on(Self::new(
+            window,
+            event_loop_window_target,
+            self.safe_mode,
+            &self.graphics_config,
+        ))?;
+
+        std::mem::swap(&mut recovered.camera, &mut self.camera);
+        std::mem::swap(
+            &mut recovered.camera_controller,
+            &mut self.camera_controller,
+        );
+        std::mem::swap(&mut recovered.ui_handler, &mut self.ui_handler);
+        std::mem::swap(&mut recovered.attract_mode, &mut self.attract_mode);
+        std::mem::swap(&mut recovered.frame_stats, &mut self.frame_stats);
+        std::mem::swap(&mut recovered.frame_capturer, &mut self.frame_capturer);
+        std::mem::swap(&mut recovered.gamepad, &mut self.gamepad);
+        std::mem::swap(&mut recovered.autosaver, &mut self.autosaver);
+        recovered.error_reported = self.error_reported;
+        // The textures egui had uploaded went with the old device, so only
+        // the layout of the windows is kept
+        *recovered.egui_ctx.memory() = self.egui_ctx.memory().clone();
+        *self = recovered;
+
+        // Make the models of the bodies again with the new device
+        world.insert(self.device.clone());
+        world.insert(self.queue.clone());
+        world.insert(self.texture_bind_group_layout.clone());
+        world.insert(self.adapter_report.clone());
+        components::rebuild_body_models(world);
+        world
+            .read_resource::<Toasts>()
+            .warn(tr!("device-recreated"));
+
+        Ok(())
+    }
+
+    fn check_device_errors(&mut self) -> Result<(), RenderError> {
+        //! Handle the errors the device has reported, the device has to be
+        //! recreated if it ran out of memory
+
+        for err in self.device_errors.take() {
+            match err {
+                wgpu::Error::OutOfMemory { .. } => return Err(RenderError::DeviceLost),
+                wgpu::Error::Validation { description, .. } => {
+                    if !self.error_reported {
+                        self.error_reported = true;
+                        show_error_dialog(
+                            "Graphics Error",
+                            &format!(
+                                "Something went wrong while rendering, so some things may not \
+                                be drawn correctly.\n\n{}",
+                                description
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save_session(&self, world: &World) {
+        //! Save the layout of the UI for the next launch
+        self.ui_handler.save_session(&self.egui_ctx, world);
+    }
+
     pub fn update(&mut self, dt: Duration, world: &mut World, dispatchers: &mut Dispatchers) {
         //! Update the state
 
-        // Move the camera with the camera controller
-        self.camera_controller.update_camera(&mut self.camera, dt);
+        self.frame_stats.begin_frame(dt);
+
+        // Moving the gamepad counts as input, so it leaves attract mode
+        let gamepad_input = self.gamepad.poll(world);
+        if !gamepad_input.is_idle() {
+            self.attract_mode.register_input(&mut self.camera);
+        }
+        self.camera_controller.process_gamepad_input(gamepad_input);
+
+        let camera_before = (self.camera.position, self.camera.rotation);
+
+        // Move the camera with the camera controller, unless attract mode, a
+        // flight to a body or a camera path has taken over the camera
+        if !self.attract_mode.update(dt, &mut self.camera, world)
+            && !follow::fly_to_body(&mut self.camera, dt, world)
+            && !path::play_path(&mut self.camera, dt, world)
+        {
+            let before = self.camera.position;
+            self.camera_controller.update_camera(&mut self.camera, dt);
+            follow::follow_body(&mut self.camera, self.camera.position - before, dt, world);
+        }
+        self.camera_moved = (self.camera.position, self.camera.rotation) != camera_before;
         self.camera_uniform
             .update_view_proj(&self.camera, &self.camera_projection);
 
@@ -450,50 +956,208 @@ impl State {
         );
 
         // Add the new delta time to Entity Component System
-        world.exec(|(mut delta,): (Write<DeltaTime>,)| {
-            delta.0 = dt;
-        });
+        world.exec(
+            |(mut delta, mut usage_stats): (Write<DeltaTime>, Write<UsageStats>)| {
+                delta.0 = dt;
+                usage_stats.time_run += dt.as_secs_f64();
+            },
+        );
 
         // Run the simulation
         dispatchers.simulation_dispatcher.dispatch(world);
+
+        // Remove any bodies that were absorbed in a merge
+        world.maintain();
+
+        self.autosaver.update(world);
+
+        // Carry out any commands from remote clients
+        crate::remote::update(world);
     }
 
-    pub fn render(&mut self, world: &mut World, window: &Window) -> Result<(), wgpu::SurfaceError> {
+    pub fn render(&mut self, world: &mut World, window: &Window) -> Result<(), RenderError> {
         //! Render the next frame
-        let output = self.surface.get_current_texture()?;
-
-        // Update the camera position and speed in the entity component system
+        self.check_device_errors()?;
+        self.apply_render_settings(world);
+        self.frame_capturer.update(world);
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.poll(&self.device);
+        }
+        let output = self
+            .surface
+            .as_ref()
+            .ok_or(RenderError::DeviceLost)?
+            .get_current_texture()?;
+        let screenshots = std::mem::take(&mut world.write_resource::<ScreenshotRequests>().0);
+        let toasts = world.read_resource::<Toasts>().sender();
+
+        // Update the camera position, rotation and speed in the entity component system
         world.exec(
-            |(mut camera_position, mut camera_speed): (
+            |(mut camera_position, mut camera_rotation, mut camera_speed): (
                 Write<CameraPosition>,
+                Write<CameraRotation>,
                 Write<CameraSpeed>,
             )| {
                 camera_position.0 = self.camera.position;
+                camera_rotation.0 = self.camera.rotation;
                 camera_speed.0 = self.camera_controller.get_speed();
             },
         );
 
+        // Scale the UI by the chosen amount on top of the display's scale
+        // factor, including after the window moves to another display
+        let pixels_per_point = world
+            .read_resource::<AccessibilitySettings>()
+            .pixels_per_point(window.scale_factor());
+        if pixels_per_point != self.egui_state.pixels_per_point() {
+            self.egui_state.set_pixels_per_point(pixels_per_point);
+        }
+
         let input = self.egui_state.take_egui_input(window);
         let full_output = self.egui_ctx.run(input, |ctx| {
-            self.ui_handler.show(ctx, world);
+            // The UI is hidden while attract mode is active
+            if !self.attract_mode.is_active() {
+                self.ui_handler.show(ctx, world);
+                trails::draw_legend(ctx, world);
+                hud::draw_hud(ctx, world, &self.frame_stats, self.gpu_timer.as_ref());
+                cursor::draw_cursor(ctx, world);
+                follow::pick_body(ctx, world, &self.camera, &self.camera_projection);
+                ruler::pick(ctx, world, &self.camera, &self.camera_projection);
+                ruler::draw_label(ctx, world, &self.camera, &self.camera_projection);
+            }
+
+            labels::draw_body_labels(ctx, world, &self.camera, &self.camera_projection);
+            debug::draw_text(ctx, world, &self.camera, &self.camera_projection);
 
             //puffin_egui::profiler_window(ctx);
         });
+        self.repaint_after = full_output.repaint_after;
 
         world.exec(
-            |(camera_position, camera_speed): (Read<CameraPosition>, Read<CameraSpeed>)| {
+            |(camera_position, camera_rotation, camera_speed): (
+                Read<CameraPosition>,
+                Read<CameraRotation>,
+                Read<CameraSpeed>,
+            )| {
                 self.camera.position = camera_position.0;
+                self.camera.rotation = camera_rotation.0;
                 self.camera_controller.set_speed(camera_speed.0);
             },
         );
 
+        // Upload the trails, grid, gravitational field, ruler, arrows and debug lines, growing the buffer if
+        // there are more points than it can hold
+        let mut line_vertices = Vec::new();
+        let mut line_ranges = Vec::new();
+        grid::build_vertices(world, &mut line_vertices, &mut line_ranges);
+        self.field_overlay
+            .build_vertices(world, &mut line_vertices, &mut line_ranges);
+        trails::build_vertices(world, &mut line_vertices, &mut line_ranges);
+        ruler::build_vertices(world, &mut line_vertices, &mut line_ranges);
+        arrows::build_vertices(world, &mut line_vertices, &mut line_ranges);
+        debug::build_vertices(world, &mut line_vertices, &mut line_ranges);
+        if line_vertices.len() > self.line_capacity {
+            self.line_capacity = line_vertices.len().next_power_of_two();
+            self.line_buffer = lines::create_vertex_buffer(&self.device, self.line_capacity);
+        }
+        if !line_vertices.is_empty() {
+            self.queue
+                .write_buffer(&self.line_buffer, 0, bytemuck::cast_slice(&line_vertices));
+        }
+
+        // Upload the trails drawn as ribbons in the same way
+        let mut ribbon_vertices = Vec::new();
+        let mut ribbon_ranges = Vec::new();
+        trails::build_ribbons(
+            world,
+            self.camera.position,
+            self.camera_projection.pixel_size(self.size.height),
+            &mut ribbon_vertices,
+            &mut ribbon_ranges,
+        );
+        if ribbon_vertices.len() > self.ribbon_capacity {
+            self.ribbon_capacity = ribbon_vertices.len().next_power_of_two();
+            self.ribbon_buffer = ribbons::create_vertex_buffer(&self.device, self.ribbon_capacity);
+        }
+        if !ribbon_vertices.is_empty() {
+            self.queue.write_buffer(
+                &self.ribbon_buffer,
+                0,
+                bytemuck::cast_slice(&ribbon_vertices),
+            );
+        }
+
+        let mut render_stats = RenderStats::default();
+
+        // Bodies entirely outside the camera's view aren't drawn
+        let frustum = camera::Frustum::new(&self.camera, &self.camera_projection);
+        let in_view = |model: &RenderModel| {
+            let (centre, radius) = model.bounding_sphere();
+            frustum.intersects_sphere(centre, radius)
+        };
+
         // Get all models from the entity component system
         world.exec(
-            |(ids, positions, models): (
+            |(ids, positions, models, lights, atmospheres, rings, scale): (
                 ReadStorage<Identifier>,
                 ReadStorage<Position>,
                 ReadStorage<RenderModel>,
+                ReadStorage<Light>,
+                ReadStorage<Atmosphere>,
+                ReadStorage<Rings>,
+                Read<PositionScaleFactor>,
             )| {
+                // Move the lights to the bodies that emit them, the light
+                // falls off from 1 AU in the scaled down scene
+                let lights_uniform = LightsUniform::new(
+                    (&models, &lights).join().map(|(model, light)| {
+                        LightUniform::new(model.instance.position.into(), light)
+                    }),
+                    (AU / scale.0) as f32,
+                );
+                self.queue.write_buffer(
+                    &self.light_buffer,
+                    0,
+                    bytemuck::cast_slice(&[lights_uniform]),
+                );
+
+                // Upload the atmospheres, growing the buffer if there are
+                // more than it can hold
+                let atmospheres_raw = (&models, &atmospheres)
+                    .join()
+                    .map(|(_, atmosphere)| atmosphere.to_raw())
+                    .collect::<Vec<_>>();
+                if atmospheres_raw.len() > self.atmosphere_capacity {
+                    self.atmosphere_capacity = atmospheres_raw.len().next_power_of_two();
+                    self.atmosphere_buffer =
+                        atmosphere::create_instance_buffer(&self.device, self.atmosphere_capacity);
+                }
+                if !atmospheres_raw.is_empty() {
+                    self.queue.write_buffer(
+                        &self.atmosphere_buffer,
+                        0,
+                        bytemuck::cast_slice(&atmospheres_raw),
+                    );
+                }
+
+                // Upload the rings in the same way
+                let rings_raw = (&models, &rings)
+                    .join()
+                    .map(|(_, rings)| rings.to_raw())
+                    .collect::<Vec<_>>();
+                if rings_raw.len() > self.rings_capacity {
+                    self.rings_capacity = rings_raw.len().next_power_of_two();
+                    self.rings_buffer =
+                        rings::create_instance_buffer(&self.device, self.rings_capacity);
+                }
+                if !rings_raw.is_empty() {
+                    self.queue.write_buffer(
+                        &self.rings_buffer,
+                        0,
+                        bytemuck::cast_slice(&rings_raw),
+                    );
+                }
+
                 let view = output
                     .texture
                     .create_view(&wgpu::TextureViewDescriptor::default());
@@ -503,13 +1167,27 @@ impl State {
                         .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                             label: Some("Render Encoder"),
                         });
+                if let Some(gpu_timer) = &mut self.gpu_timer {
+                    gpu_timer.begin(&mut encoder);
+                }
+
+                self.environment_map.capture(
+                    &self.queue,
+                    &mut encoder,
+                    &self.light_bind_group,
+                    self.camera.position,
+                    self.render_settings.reflections,
+                );
+                if let Some(gpu_timer) = &self.gpu_timer {
+                    gpu_timer.write(&mut encoder, Timestamp::EnvironmentEnd);
+                }
 
                 {
                     // Create a new render pass
                     let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: Some("Render Pass"),
                         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                            view: &view,
+                            view: self.post_processor.scene_view(),
                             resolve_target: None,
                             ops: wgpu::Operations {
                                 load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
@@ -519,24 +1197,59 @@ impl State {
                         depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                             view: &self.depth_texture.view,
                             depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(1.0),
+                                load: wgpu::LoadOp::Clear(texture::Texture::DEPTH_CLEAR),
                                 store: true,
                             }),
                             stencil_ops: None,
                         }),
                     });
 
-                    // Set the render pipeline
+                    // The stars are behind everything, so are drawn first
+                    if self.render_settings.stars {
+                        self.starfield
+                            .draw(&mut render_pass, &self.camera_bind_group);
+                        render_stats.count_stars(self.starfield.count());
+                    }
+
+                    // Set the render pipeline, the environment map is the
+                    // same for every model
                     render_pass.set_pipeline(&self.render_pipeline);
+                    render_pass.set_bind_group(3, &self.environment_map.bind_group, &[]);
 
                     // Render each model
-                    (&models).join().for_each(|model| {
+                    (&models, !&lights).join().for_each(|(model, _)| {
+                        if !in_view(model) {
+                            render_stats.culled += 1;
+                            return;
+                        }
+
                         render_pass.set_vertex_buffer(1, model.instance_buffer.slice(..));
                         render_pass.draw_model(
                             &model.model,
                             &self.camera_bind_group,
                             &self.light_bind_group,
                         );
+                        render_stats.count_model(&model.model);
+                    });
+                    if let Some(gpu_timer) = &self.gpu_timer {
+                        gpu_timer.write_in_pass(&mut render_pass, Timestamp::BodiesEnd);
+                    }
+
+                    // Render the models that emit light
+                    render_pass.set_pipeline(&self.emissive_render_pipeline);
+                    (&models, &lights).join().for_each(|(model, _)| {
+                        if !in_view(model) {
+                            render_stats.culled += 1;
+                            return;
+                        }
+
+                        render_pass.set_vertex_buffer(1, model.instance_buffer.slice(..));
+                        render_pass.draw_model(
+                            &model.model,
+                            &self.camera_bind_group,
+                            &self.light_bind_group,
+                        );
+                        render_stats.count_model(&model.model);
                     });
 
                     (&ids, &models)
@@ -555,8 +1268,160 @@ impl State {
                         });
                 }
 
+                if let Some(gpu_timer) = &self.gpu_timer {
+                    gpu_timer.write(&mut encoder, Timestamp::OpaquePassEnd);
+                }
+
+                // Blended objects are drawn over the opaque ones, furthest
+                // from the camera first so nearer ones are blended over them.
+                // They are hidden behind the bodies but don't write depth, so
+                // don't hide anything themselves
+                let line_items = line_ranges.iter().map(|range| {
+                    let vertices = &line_vertices[range.start as usize..range.end as usize];
+                    (
+                        transparent::centre(vertices.iter().map(|vertex| vertex.position())),
+                        Transparent::Lines(range.clone()),
+                    )
+                });
+                let ribbon_items = ribbon_ranges.iter().map(|range| {
+                    let vertices = &ribbon_vertices[range.start as usize..range.end as usize];
+                    (
+                        transparent::centre(vertices.iter().map(|vertex| vertex.position())),
+                        Transparent::Ribbon(range.clone()),
+                    )
+                });
+                let atmosphere_items =
+                    (&models, &atmospheres)
+                        .join()
+                        .enumerate()
+                        .map(|(index, (model, _))| {
+                            (
+                                model.instance.position,
+                                Transparent::Atmosphere { model, index },
+                            )
+                        });
+                let ring_items = (&models, &rings)
+                    .join()
+                    .enumerate()
+                    .map(|(index, (model, _))| {
+                        (model.instance.position, Transparent::Rings { model, index })
+                    });
+                let transparent = transparent::back_to_front(
+                    line_items
+                        .chain(ribbon_items)
+                        .chain(atmosphere_items)
+                        .chain(ring_items),
+                    self.camera.position,
+                );
+                render_stats.count_lines(&line_ranges);
+                render_stats.count_ribbons(&ribbon_ranges);
+
+                {
+                    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("Transparent Render Pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: self.post_processor.scene_view(),
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Load,
+                                store: true,
+                            },
+                        })],
+                        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                            view: &self.depth_texture.view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Load,
+                                store: false,
+                            }),
+                            stencil_ops: None,
+                        }),
+                    });
+
+                    // Only switch pipelines when the kind of object changes
+                    let mut drawing = None;
+                    for item in transparent.iter() {
+                        let kind = std::mem::discriminant(item);
+                        let switched = drawing != Some(kind);
+                        drawing = Some(kind);
+
+                        match item {
+                            Transparent::Lines(range) => {
+                                if switched {
+                                    render_pass.set_pipeline(&self.line_render_pipeline);
+                                    render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+                                    render_pass.set_vertex_buffer(0, self.line_buffer.slice(..));
+                                }
+                                render_pass.draw(range.clone(), 0..1);
+                            }
+                            Transparent::Ribbon(range) => {
+                                if switched {
+                                    render_pass.set_pipeline(&self.ribbon_render_pipeline);
+                                    render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+                                    render_pass.set_vertex_buffer(0, self.ribbon_buffer.slice(..));
+                                }
+                                render_pass.draw(range.clone(), 0..1);
+                            }
+                            Transparent::Atmosphere { model, index } => {
+                                if switched {
+                                    render_pass.set_pipeline(&self.atmosphere_render_pipeline);
+                                }
+
+                                let size =
+                                    std::mem::size_of::<AtmosphereRaw>() as wgpu::BufferAddress;
+                                let offset = *index as wgpu::BufferAddress * size;
+
+                                render_pass.set_vertex_buffer(1, model.instance_buffer.slice(..));
+                                render_pass.set_vertex_buffer(
+                                    2,
+                                    self.atmosphere_buffer.slice(offset..offset + size),
+                                );
+                                render_pass.draw_light_model(
+                                    &model.model,
+                                    &self.camera_bind_group,
+                                    &self.light_bind_group,
+                                );
+                                render_stats.count_model(&model.model);
+                            }
+                            Transparent::Rings { model, index } => {
+                                if switched {
+                                    render_pass.set_pipeline(&self.rings_render_pipeline);
+                                }
+
+                                let size = std::mem::size_of::<RingsRaw>() as wgpu::BufferAddress;
+                                let offset = *index as wgpu::BufferAddress * size;
+
+                                // The rings are placed by their body's instance
+                                render_pass.set_vertex_buffer(1, model.instance_buffer.slice(..));
+                                render_pass.set_vertex_buffer(
+                                    2,
+                                    self.rings_buffer.slice(offset..offset + size),
+                                );
+                                render_pass.draw_model(
+                                    &self.rings_model,
+                                    &self.camera_bind_group,
+                                    &self.light_bind_group,
+                                );
+                                render_stats.count_model(&self.rings_model);
+                            }
+                        }
+                    }
+                }
+
+                if let Some(gpu_timer) = &self.gpu_timer {
+                    gpu_timer.write(&mut encoder, Timestamp::TransparentPassEnd);
+                }
+
+                let passes =
+                    self.post_processor
+                        .apply(&mut encoder, &self.render_settings.post, &view);
+                render_stats.count_post(passes);
+                if let Some(gpu_timer) = &self.gpu_timer {
+                    gpu_timer.write(&mut encoder, Timestamp::PostEnd);
+                }
+
                 {
                     let paint_jobs = self.egui_ctx.tessellate(full_output.shapes);
+                    render_stats.count_ui(&paint_jobs);
 
                     let screen_descriptor = egui_wgpu::renderer::ScreenDescriptor {
                         size_in_pixels: [self.size.width, self.size.height],
@@ -587,17 +1452,49 @@ impl State {
                         None,
                     );
                 }
+                if let Some(gpu_timer) = &self.gpu_timer {
+                    gpu_timer.write(&mut encoder, Timestamp::UiEnd);
+                }
+
+                let capture = ScreenshotCapture::begin(
+                    &self.device,
+                    &mut encoder,
+                    &output.texture,
+                    &self.config,
+                    screenshots,
+                    toasts,
+                );
 
                 // Render the frame
+                if let Some(gpu_timer) = &mut self.gpu_timer {
+                    gpu_timer.end(&mut encoder);
+                }
                 self.queue.submit(std::iter::once(encoder.finish()));
+                if let Some(gpu_timer) = &mut self.gpu_timer {
+                    gpu_timer.after_submit();
+                }
+                self.frame_stats.end_frame();
                 output.present();
 
+                if let Some(capture) = capture {
+                    capture.finish(&self.device);
+                }
+
                 for id in &full_output.textures_delta.free {
                     self.egui_render_pass.free_texture(id);
                 }
             },
         );
 
+        // The timings are from the last frame whose timestamps have been read
+        if let Some(gpu_timer) = &self.gpu_timer {
+            render_stats.timed = true;
+            render_stats.subpasses_timed = gpu_timer.times_subpasses();
+            render_stats.gpu_time = gpu_timer.gpu_time;
+            render_stats.pass_times = gpu_timer.pass_times.clone();
+        }
+        *world.write_resource::<RenderStats>() = render_stats;
+
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use image::{GenericImageView, ImageResult};
+use image::{DynamicImage, GenericImageView, ImageResult, Rgba, Rgba32FImage};
 
 /// Data structure representing a texture
 pub struct Texture {
@@ -13,11 +13,37 @@ impl Texture {
         queue: &wgpu::Queue,
         bytes: &[u8],
         label: &str,
+        is_linear: bool,
     ) -> ImageResult<Self> {
         //! Creates a texture from bytes of an image
 
         let img = image::load_from_memory(bytes)?;
-        Ok(Self::from_image(device, queue, &img, Some(label)))
+        Ok(Self::from_image(
+            device,
+            queue,
+            &img,
+            Some(label),
+            is_linear,
+        ))
+    }
+
+    pub fn from_colour(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        colour: [f32; 4],
+        label: Option<&str>,
+        is_linear: bool,
+    ) -> Self {
+        //! Creates a single pixel texture of the specified colour
+
+        let img = Rgba32FImage::from_pixel(1, 1, Rgba(colour));
+        Self::from_image(
+            device,
+            queue,
+            &DynamicImage::ImageRgba32F(img),
+            label,
+            is_linear,
+        )
     }
 
     pub fn from_image(
@@ -25,8 +51,10 @@ impl Texture {
         queue: &wgpu::Queue,
         img: &image::DynamicImage,
         label: Option<&str>,
+        is_linear: bool,
     ) -> Self {
-        //! Creates a texture from and image::Image
+        //! Creates a texture from and image::Image, data textures such as normal
+        //! and specular maps should be linear rather than sRGB
 
         let rgba = img.to_rgba8();
         let dimensions = img.dimensions();
@@ -42,7 +70,11 @@ impl Texture {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format: if is_linear {
+                wgpu::TextureFormat::Rgba8Unorm
+            } else {
+                wgpu::TextureFormat::Rgba8UnormSrgb
+            },
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
         });
 
@@ -65,8 +97,10 @@ impl Texture {
 
         // Create a texture view and texture sampler for rendering
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Textures repeat horizontally so that equirectangular textures wrap
+        // around the seam of a sphere
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_u: wgpu::AddressMode::Repeat,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
             mag_filter: wgpu::FilterMode::Linear,
@@ -84,6 +118,10 @@ impl Texture {
 
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
+    // The depth buffer is reversed, nearer fragments have a greater depth
+    pub const DEPTH_COMPARE: wgpu::CompareFunction = wgpu::CompareFunction::Greater;
+    pub const DEPTH_CLEAR: f32 = 0.0;
+
     pub fn create_depth_texture(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
@@ -116,7 +154,7 @@ impl Texture {
             mag_filter: wgpu::FilterMode::Linear,
             min_filter: wgpu::FilterMode::Linear,
             mipmap_filter: wgpu::FilterMode::Nearest,
-            compare: Some(wgpu::CompareFunction::LessEqual),
+            compare: Some(wgpu::CompareFunction::GreaterEqual),
             lod_min_clamp: -100.0,
             lod_max_clamp: 100.0,
             ..Default::default()
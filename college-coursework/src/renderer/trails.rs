@@ -0,0 +1,406 @@
+use std::ops::Range;
+
+use cgmath::{Point3, Vector3};
+use specs::{Entities, Join, Read, ReadStorage, World};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    locale::tr,
+    simulation::{PositionScaleFactor, Trail, TrailPoint},
+};
+
+use super::{
+    components::{BodyStyle, PlanetColour},
+    lines::LineVertex,
+    ribbons::{self, RibbonPoint, RibbonVertex},
+};
+
+/// Opacity of the oldest point of a trail, trails fade in from this towards
+/// their body. Must match TAIL_OPACITY in ribbon.wgsl
+const TRAIL_TAIL_OPACITY: f32 = 0.1;
+
+/// Trails this wide (in pixels) or thinner are drawn as lines rather than
+/// ribbons
+const LINE_WIDTH: f32 = 1.0;
+
+/// Number of steps the gradient of the legend is drawn with
+const LEGEND_STEPS: usize = 32;
+
+/// The quantity the points of trails are coloured by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailColouring {
+    BodyColour,
+    Speed,
+    SpecificEnergy,
+    Acceleration,
+}
+impl TrailColouring {
+    pub const ALL: [TrailColouring; 4] = [
+        TrailColouring::BodyColour,
+        TrailColouring::Speed,
+        TrailColouring::SpecificEnergy,
+        TrailColouring::Acceleration,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            TrailColouring::BodyColour => tr!("trail-colouring-body-colour"),
+            TrailColouring::Speed => tr!("trail-colouring-speed"),
+            TrailColouring::SpecificEnergy => tr!("trail-colouring-specific-energy"),
+            TrailColouring::Acceleration => tr!("trail-colouring-acceleration"),
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            TrailColouring::BodyColour => "",
+            TrailColouring::Speed => "m/s",
+            TrailColouring::SpecificEnergy => "J/kg",
+            TrailColouring::Acceleration => "m/s\u{b2}",
+        }
+    }
+
+    pub fn value(&self, point: &TrailPoint) -> Option<f64> {
+        //! The quantity of a point that is mapped to a colour, None if points
+        //! are coloured by their body instead
+
+        match self {
+            TrailColouring::BodyColour => None,
+            TrailColouring::Speed => Some(point.speed),
+            TrailColouring::SpecificEnergy => Some(point.specific_energy),
+            TrailColouring::Acceleration => Some(point.acceleration),
+        }
+    }
+}
+
+/// A gradient that maps values between 0 and 1 to colours
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Colourmap {
+    Viridis,
+    Inferno,
+    Coolwarm,
+    Greyscale,
+}
+impl Colourmap {
+    pub const ALL: [Colourmap; 4] = [
+        Colourmap::Viridis,
+        Colourmap::Inferno,
+        Colourmap::Coolwarm,
+        Colourmap::Greyscale,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Colourmap::Viridis => "Viridis",
+            Colourmap::Inferno => "Inferno",
+            Colourmap::Coolwarm => "Cool-warm",
+            Colourmap::Greyscale => "Greyscale",
+        }
+    }
+
+    fn stops(&self) -> &'static [[f32; 3]] {
+        //! Evenly spaced colours along the gradient
+
+        match self {
+            Colourmap::Viridis => &[
+                [0.267, 0.005, 0.329],
+                [0.254, 0.265, 0.530],
+                [0.164, 0.471, 0.558],
+                [0.135, 0.659, 0.518],
+                [0.478, 0.821, 0.318],
+                [0.993, 0.906, 0.144],
+            ],
+            Colourmap::Inferno => &[
+                [0.001, 0.000, 0.014],
+                [0.258, 0.039, 0.406],
+                [0.576, 0.149, 0.404],
+                [0.865, 0.317, 0.226],
+                [0.988, 0.645, 0.040],
+                [0.988, 0.998, 0.645],
+            ],
+            Colourmap::Coolwarm => &[
+                [0.230, 0.299, 0.754],
+                [0.552, 0.690, 0.996],
+                [0.866, 0.866, 0.866],
+                [0.956, 0.604, 0.486],
+                [0.706, 0.016, 0.150],
+            ],
+            Colourmap::Greyscale => &[[0.05, 0.05, 0.05], [1.0, 1.0, 1.0]],
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> [f32; 3] {
+        //! The colour at `t` along the gradient, clamped between 0 and 1
+
+        let stops = self.stops();
+        let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let fraction = position - index as f32;
+
+        let (from, to) = (stops[index], stops[index + 1]);
+        [
+            from[0] + (to[0] - from[0]) * fraction,
+            from[1] + (to[1] - from[1]) * fraction,
+            from[2] + (to[2] - from[2]) * fraction,
+        ]
+    }
+}
+
+/// Options for drawing the trails bodies leave behind them
+#[derive(Debug, Clone, Copy)]
+pub struct TrailStyle {
+    pub shown: bool,
+    pub colouring: TrailColouring,
+    pub colourmap: Colourmap,
+    /// How wide trails are drawn (in pixels)
+    pub width: f32,
+}
+impl Default for TrailStyle {
+    fn default() -> Self {
+        Self {
+            shown: true,
+            colouring: TrailColouring::BodyColour,
+            colourmap: Colourmap::Viridis,
+            width: 2.0,
+        }
+    }
+}
+impl TrailStyle {
+    pub fn scaled_width(&self, accessibility_settings: &AccessibilitySettings) -> f32 {
+        //! How wide trails are drawn (in pixels), thicker in high-contrast
+        //! mode
+        self.width * accessibility_settings.line_width_scale()
+    }
+
+    pub fn ribbons(&self, accessibility_settings: &AccessibilitySettings) -> bool {
+        //! Whether trails are wide enough to be drawn as ribbons
+        self.scaled_width(accessibility_settings) > LINE_WIDTH
+    }
+
+    pub fn value_range(&self, trails: &ReadStorage<Trail>) -> Option<(f64, f64)> {
+        //! The smallest and largest values the trails are coloured by, None if
+        //! they are coloured by body or there are no points
+
+        trails
+            .join()
+            .flat_map(|trail| trail.points.iter())
+            .filter_map(|point| self.colouring.value(point))
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+}
+
+fn for_each_trail(world: &World, mut draw: impl FnMut(&[(Vector3<f32>, [f32; 3])])) {
+    //! Call `draw` with the position and colour of every point of each trail
+    //! long enough to be drawn, oldest first
+
+    let (entities, trails, colours, body_styles, style, scale_factor, accessibility_settings): (
+        Entities,
+        ReadStorage<Trail>,
+        ReadStorage<PlanetColour>,
+        ReadStorage<BodyStyle>,
+        Read<TrailStyle>,
+        Read<PositionScaleFactor>,
+        Read<AccessibilitySettings>,
+    ) = world.system_data();
+
+    if !style.shown {
+        return;
+    }
+
+    let (min, max) = style.value_range(&trails).unwrap_or((0.0, 0.0));
+    let mut points = Vec::new();
+
+    for (entity, trail, colour, body_style) in (
+        &entities,
+        &trails,
+        (&colours).maybe(),
+        (&body_styles).maybe(),
+    )
+        .join()
+    {
+        // A line needs at least two points
+        if trail.points.len() < 2 {
+            continue;
+        }
+
+        // A trail colour picked for the body is kept whatever the palette
+        let trail_colour = body_style.and_then(|body_style| body_style.trail_colour);
+        let body_colour = trail_colour.unwrap_or_else(|| {
+            colour.map_or([1.0; 3], |colour| {
+                let [r, g, b, _] = accessibility_settings.palette.body_colour(entity, colour.0);
+                [r, g, b]
+            })
+        });
+
+        points.clear();
+        points.extend(trail.points.iter().map(|point| {
+            let colour = match style.colouring.value(point) {
+                Some(value) if max > min => {
+                    style.colourmap.sample(((value - min) / (max - min)) as f32)
+                }
+                Some(_) => style.colourmap.sample(0.5),
+                None => body_colour,
+            };
+
+            ((point.position / scale_factor.0).map(|a| a as f32), colour)
+        }));
+
+        draw(&points);
+    }
+}
+
+pub fn build_vertices(world: &World, vertices: &mut Vec<LineVertex>, ranges: &mut Vec<Range<u32>>) {
+    //! Add the vertices of every trail, along with the range of vertices each
+    //! trail is drawn from, unless the trails are drawn as ribbons
+
+    if world
+        .read_resource::<TrailStyle>()
+        .ribbons(&world.read_resource::<AccessibilitySettings>())
+    {
+        return;
+    }
+
+    for_each_trail(world, |points| {
+        let start = vertices.len() as u32;
+        let last = (points.len() - 1) as f32;
+
+        vertices.extend(
+            points
+                .iter()
+                .enumerate()
+                .map(|(i, &(position, [r, g, b]))| {
+                    let opacity = TRAIL_TAIL_OPACITY + (1.0 - TRAIL_TAIL_OPACITY) * i as f32 / last;
+                    LineVertex::new(position.into(), [r, g, b, opacity])
+                }),
+        );
+
+        ranges.push(start..vertices.len() as u32);
+    });
+}
+
+pub fn build_ribbons(
+    world: &World,
+    camera: Point3<f32>,
+    pixel_size: f32,
+    vertices: &mut Vec<RibbonVertex>,
+    ranges: &mut Vec<Range<u32>>,
+) {
+    //! Add the vertices of every trail as a ribbon facing the camera, along
+    //! with the range of vertices each ribbon is drawn from, if the trails
+    //! are wide enough to be drawn as ribbons
+
+    let style = *world.read_resource::<TrailStyle>();
+    let accessibility_settings = *world.read_resource::<AccessibilitySettings>();
+    if !style.ribbons(&accessibility_settings) {
+        return;
+    }
+    let width = style.scaled_width(&accessibility_settings);
+
+    let mut ribbon = Vec::new();
+    for_each_trail(world, |points| {
+        let last = (points.len() - 1) as f32;
+
+        // The newest point is at the head of the ribbon
+        ribbon.clear();
+        ribbon.extend(
+            points
+                .iter()
+                .enumerate()
+                .map(|(i, &(position, [r, g, b]))| RibbonPoint {
+                    position,
+                    colour: [r, g, b, 1.0],
+                    age: 1.0 - i as f32 / last,
+                }),
+        );
+
+        let start = vertices.len() as u32;
+        ribbons::extrude(&ribbon, camera, width, pixel_size, vertices);
+        ranges.push(start..vertices.len() as u32);
+    });
+}
+
+pub fn draw_legend(ctx: &egui::Context, world: &World) {
+    //! Show the colourmap the trails are coloured with and the values at
+    //! either end of it
+
+    let (trails, style, accessibility_settings): (
+        ReadStorage<Trail>,
+        Read<TrailStyle>,
+        Read<AccessibilitySettings>,
+    ) = world.system_data();
+
+    if !style.shown {
+        return;
+    }
+    let (min, max) = match style.value_range(&trails) {
+        Some(range) => range,
+        None => return,
+    };
+
+    let width = 200.0 * accessibility_settings.label_scale();
+    let height = 12.0 * accessibility_settings.label_scale();
+    let unit = style.colouring.unit();
+
+    egui::Area::new("trail_legend")
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(format!("{} ({})", style.colouring.name(), unit));
+
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+                let step = width / LEGEND_STEPS as f32;
+                for i in 0..LEGEND_STEPS {
+                    let [r, g, b] = style
+                        .colourmap
+                        .sample((i as f32 + 0.5) / LEGEND_STEPS as f32);
+                    ui.painter().rect_filled(
+                        egui::Rect::from_min_size(
+                            rect.min + egui::vec2(i as f32 * step, 0.0),
+                            egui::vec2(step + 0.5, height),
+                        ),
+                        0.0,
+                        egui::Rgba::from_rgb(r, g, b),
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("{:.3e}", min));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{:.3e}", max));
+                    });
+                });
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrailStyle;
+    use crate::accessibility::AccessibilitySettings;
+
+    #[test]
+    fn test_high_contrast_widens_trails() {
+        let settings = AccessibilitySettings::default();
+        let high_contrast = AccessibilitySettings {
+            high_contrast: true,
+            ..AccessibilitySettings::default()
+        };
+
+        let style = TrailStyle::default();
+        assert_eq!(style.width, style.scaled_width(&settings));
+        assert_eq!(2.0 * style.width, style.scaled_width(&high_contrast));
+
+        // Trails thin enough to be lines become ribbons once widened
+        let thin = TrailStyle {
+            width: 1.0,
+            ..TrailStyle::default()
+        };
+        assert!(!thin.ribbons(&settings));
+        assert!(thin.ribbons(&high_contrast));
+    }
+}
@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3, Zero};
+
+use super::components::RenderModel;
+
+/// Something blended over the opaque bodies, which has to be drawn after
+/// anything it is in front of
+pub enum Transparent<'a> {
+    /// A range of the line buffer, drawn as a strip
+    Lines(Range<u32>),
+    /// A range of the ribbon buffer, drawn as a triangle strip
+    Ribbon(Range<u32>),
+    /// A body's atmosphere, along with its index in the atmosphere buffer
+    Atmosphere {
+        model: &'a RenderModel,
+        index: usize,
+    },
+    /// A body's rings, along with their index in the rings buffer
+    Rings {
+        model: &'a RenderModel,
+        index: usize,
+    },
+}
+
+pub fn centre(positions: impl ExactSizeIterator<Item = [f32; 3]>) -> Vector3<f32> {
+    //! The average position of the points of a line or ribbon, which it is
+    //! sorted by
+    let count = positions.len();
+    if count == 0 {
+        return Vector3::zero();
+    }
+    positions.fold(Vector3::zero(), |sum, position| {
+        sum + Vector3::from(position)
+    }) / count as f32
+}
+
+pub fn back_to_front<T>(
+    items: impl Iterator<Item = (Vector3<f32>, T)>,
+    camera: Point3<f32>,
+) -> Vec<T> {
+    //! Sort items by the distance of their position from the camera, furthest
+    //! first, so nearer ones are blended over them
+    let mut items = items
+        .map(|(position, item)| ((position - camera.to_vec()).magnitude2(), item))
+        .collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Point3, Vector3};
+
+    use super::back_to_front;
+
+    #[test]
+    fn test_furthest_drawn_first() {
+        let items = [
+            (Vector3::new(1.0, 0.0, 0.0), "near"),
+            (Vector3::new(10.0, 0.0, 0.0), "far"),
+            (Vector3::new(0.0, -5.0, 0.0), "middle"),
+        ];
+        assert_eq!(
+            vec!["far", "middle", "near"],
+            back_to_front(items.into_iter(), Point3::new(0.0, 0.0, 0.0))
+        );
+    }
+}
@@ -1,36 +1,68 @@
-use std::{
-    fs::File,
-    io::{BufReader, Cursor},
-    thread,
-};
-
-use log::error;
-use rodio::{decoder::DecoderError, Decoder, OutputStream, Sink};
-use specs::World;
+use log::{error, warn};
+use specs::{World, WorldExt};
 use winit::{event_loop::EventLoop, window::WindowBuilder};
 
-use crate::{renderer::state::State, setup::Dispatchers};
+use crate::{
+    audio::MusicPlayer,
+    config::StartupConfig,
+    crash,
+    panel::{Tutorial, TutorialAction},
+    preferences::{AudioPreferences, PreferenceSaver, Preferences},
+    renderer::{
+        capture::FrameCaptureRequest,
+        errors::{show_error_dialog, RenderError},
+        hud::HudSettings,
+        state::State,
+    },
+    setup::Dispatchers,
+    simulation::{DroppedFile, QuickSlotRequest},
+    stats::UsageStats,
+};
+
+/// The number of frames in a row the surface can be lost, even after being
+/// reconfigured, before the device is assumed to be lost
+const MAX_LOST_FRAMES: u32 = 10;
 
 /// Data structure representing the program window
 pub struct Window {
     pub event_loop: EventLoop<()>,
     pub window: winit::window::Window,
     pub state: State,
+    safe_mode: bool,
+    /// The music settings to start playing with
+    audio: AudioPreferences,
 }
 impl Window {
-    pub async fn new() -> Self {
-        //! Create a new window
+    pub async fn new(
+        safe_mode: bool,
+        config: &StartupConfig,
+        preferences: &Preferences,
+    ) -> Result<Self, RenderError> {
+        //! Create a new window, with fewer features enabled in safe mode. It
+        //! is the size it was when the program was last closed
         let event_loop = EventLoop::new();
-        let window = WindowBuilder::new().build(&event_loop).unwrap();
+        let mut window_builder = WindowBuilder::new();
+        if let Some(size) = preferences.window_size() {
+            window_builder = window_builder.with_inner_size(size);
+        }
+        let window = window_builder.build(&event_loop).unwrap();
 
         // Initialise the program state
-        let state = State::new(&window, &event_loop).await;
+        let mut state = State::new(&window, &event_loop, safe_mode, &config.graphics).await?;
+        state.camera_controller.set_speed(preferences.camera_speed);
 
-        Self {
+        let mut audio = preferences.audio;
+        if let Some(volume) = config.volume {
+            audio.volume = volume.clamp(0.0, 1.0);
+        }
+
+        Ok(Self {
             event_loop,
             window,
             state,
-        }
+            safe_mode,
+            audio,
+        })
     }
 
     pub fn run(self, mut world: World, mut dispatchers: Dispatchers<'static, 'static>) -> ! {
@@ -40,134 +72,257 @@ impl Window {
             event_loop,
             window,
             mut state,
+            safe_mode,
+            audio,
         } = self;
 
-        // Register music
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-
-        const CITY_OF_GHOSTS_MUSIC: &[u8] =
-            include_bytes!("../../assets/music/background/City of Ghosts.mp3");
-        const DUST_TO_DUST_MUSIC: &[u8] =
-            include_bytes!("../../assets/music/background/Dust to Dust.mp3");
-        const NORTHWARD_MUSIC: &[u8] =
-            include_bytes!("../../assets/music/background/Northward.mp3");
-        const SLEEPING_LIGHTLY_MUSIC: &[u8] =
-            include_bytes!("../../assets/music/background/Sleeping Lightly.mp3");
-        const STRATUS_MUSIC: &[u8] = include_bytes!("../../assets/music/background/Stratus.mp3");
-
-        // Spawn a thread to play music
-        thread::spawn(move || {
-            let files = [
-                CITY_OF_GHOSTS_MUSIC,
-                DUST_TO_DUST_MUSIC,
-                NORTHWARD_MUSIC,
-                SLEEPING_LIGHTLY_MUSIC,
-                STRATUS_MUSIC,
-            ];
-
-            // Create a new music sink
-            let sink = Sink::try_new(&stream_handle).unwrap();
-
-            let mut song_num = 0;
-
-            loop {
-                // Decode a file, the file is picked from the list of songs and it will
-                // repeat after there are no new songs to play
-                let file = BufReader::new(Cursor::new(files[song_num % files.len()]));
-                let source = Decoder::new(file).unwrap();
-
-                // Play the file
-                sink.append(source);
-
-                // Wait until the file has finished playing
-                sink.sleep_until_end();
-                song_num += 1;
+        // Play music on another thread, unless in safe mode where audio is
+        // turned off. The stream has to be kept alive for the music to play
+        let _stream = if safe_mode {
+            None
+        } else {
+            match MusicPlayer::start(audio.volume) {
+                Ok((stream, player)) => {
+                    world.insert(player);
+                    Some(stream)
+                }
+                Err(err) => {
+                    warn!("Failed to start playing music: {}", err);
+                    None
+                }
             }
-        });
+        };
+
+        // Set even when there is no music, so they aren't lost the next time
+        // the preferences are saved
+        audio.apply(&mut world.write_resource::<MusicPlayer>());
+
+        // Save changes to the preferences from now on
+        let mut preference_saver = PreferenceSaver::new(&world, state.size);
 
         // Create the start time for delta time
         let mut last_render_time = instant::Instant::now();
+        let mut lost_frames = 0;
 
         use winit::{event::*, event_loop::ControlFlow};
 
         // Start the event loop
-        event_loop.run(move |event, _, control_flow| match event {
-            Event::DeviceEvent {
-                event: DeviceEvent::MouseMotion { delta },
-                ..
-            } => state
-                .camera_controller
-                .process_mouse_move_event(delta.0, delta.1),
-            Event::WindowEvent {
-                ref event,
-                window_id,
-            } if window_id == window.id() => {
-                if !state.on_event(event) {
-                    match event {
-                        WindowEvent::CloseRequested
-                        | WindowEvent::KeyboardInput {
-                            input:
-                                KeyboardInput {
-                                    state: ElementState::Pressed,
-                                    virtual_keycode: Some(VirtualKeyCode::Escape),
-                                    ..
-                                },
-                            ..
-                        } => *control_flow = ControlFlow::Exit,
-                        WindowEvent::KeyboardInput {
-                            input:
-                                KeyboardInput {
-                                    state: keyboard_state,
-                                    virtual_keycode: Some(virtual_keycode),
-                                    ..
-                                },
-                            ..
-                        } => state
-                            .camera_controller
-                            .process_keyboard_event(*virtual_keycode, *keyboard_state),
-                        WindowEvent::MouseInput {
-                            state: keyboard_state,
-                            button,
-                            ..
-                        } => state
+        event_loop.run(
+            move |event, event_loop_window_target, control_flow| match event {
+                Event::DeviceEvent {
+                    event: DeviceEvent::MouseMotion { delta },
+                    ..
+                } => {
+                    if !state.register_input() {
+                        state
                             .camera_controller
-                            .process_mouse_button_event(*button, *keyboard_state),
-                        WindowEvent::MouseWheel { delta, .. } => {
-                            state.camera_controller.process_mouse_scroll_event(*delta)
+                            .process_mouse_move_event(delta.0, delta.1)
+                    }
+                }
+                Event::WindowEvent {
+                    ref event,
+                    window_id,
+                } if window_id == window.id() => {
+                    // Anything that happens to the window may change what is
+                    // shown, even while power saving
+                    window.request_redraw();
+
+                    // Any input leaves attract mode, without being passed on
+                    let left_attract_mode = match event {
+                        WindowEvent::KeyboardInput { .. }
+                        | WindowEvent::MouseInput { .. }
+                        | WindowEvent::MouseWheel { .. }
+                        | WindowEvent::CursorMoved { .. } => state.register_input(),
+                        _ => false,
+                    };
+
+                    if !left_attract_mode && !state.on_event(event) {
+                        match event {
+                            WindowEvent::CloseRequested
+                            | WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::Escape),
+                                        ..
+                                    },
+                                ..
+                            } => *control_flow = ControlFlow::Exit,
+                            // Toggle the HUD
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::F3),
+                                        ..
+                                    },
+                                ..
+                            } => {
+                                let mut hud_settings = world.write_resource::<HudSettings>();
+                                hud_settings.shown = !hud_settings.shown;
+                            }
+                            // Quick save to the selected slot
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::F5),
+                                        ..
+                                    },
+                                ..
+                            } => world.write_resource::<QuickSlotRequest>().save = true,
+                            // Quick load from the selected slot
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::F9),
+                                        ..
+                                    },
+                                ..
+                            } => world.write_resource::<QuickSlotRequest>().load = true,
+                            // Capture the next frame with RenderDoc
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::F10),
+                                        ..
+                                    },
+                                ..
+                            } => world.write_resource::<FrameCaptureRequest>().request(),
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: keyboard_state,
+                                        virtual_keycode: Some(virtual_keycode),
+                                        ..
+                                    },
+                                ..
+                            } => {
+                                if *keyboard_state == ElementState::Pressed {
+                                    world
+                                        .write_resource::<Tutorial>()
+                                        .record(TutorialAction::MoveCamera);
+                                }
+                                state
+                                    .camera_controller
+                                    .process_keyboard_event(*virtual_keycode, *keyboard_state)
+                            }
+                            WindowEvent::MouseInput {
+                                state: keyboard_state,
+                                button,
+                                ..
+                            } => state
+                                .camera_controller
+                                .process_mouse_button_event(*button, *keyboard_state),
+                            WindowEvent::MouseWheel { delta, .. } => {
+                                world
+                                    .write_resource::<Tutorial>()
+                                    .record(TutorialAction::MoveCamera);
+                                state.camera_controller.process_mouse_scroll_event(*delta)
+                            }
+                            // Loaded once the user confirms it in the UI
+                            WindowEvent::DroppedFile(path) => {
+                                world.write_resource::<DroppedFile>().0 = Some(path.clone())
+                            }
+                            WindowEvent::Resized(physical_size) => {
+                                state.resize(*physical_size);
+                            }
+                            WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                                state.resize(**new_inner_size);
+                            }
+                            _ => {}
                         }
-                        WindowEvent::Resized(physical_size) => {
-                            state.resize(*physical_size);
+                    }
+                }
+                Event::RedrawRequested(window_id) if window_id == window.id() => {
+                    // Calculate delta time
+                    let now = instant::Instant::now();
+                    let dt = now - last_render_time;
+                    last_render_time = now;
+
+                    // Update the program state using delta time
+                    state.update(dt, &mut world, &mut dispatchers);
+                    preference_saver.update(&world, state.size);
+
+                    // Render the next frame
+                    let device_lost = match state.render(&mut world, &window) {
+                        Ok(_) => {
+                            lost_frames = 0;
+                            false
+                        }
+                        Err(RenderError::SurfaceError(
+                            wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated,
+                        )) => {
+                            lost_frames += 1;
+                            state.resize(state.size);
+                            lost_frames > MAX_LOST_FRAMES
                         }
-                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                            state.resize(**new_inner_size);
+                        Err(
+                            RenderError::SurfaceError(wgpu::SurfaceError::OutOfMemory)
+                            | RenderError::DeviceLost,
+                        ) => true,
+                        Err(RenderError::SurfaceError(wgpu::SurfaceError::Timeout)) => {
+                            warn!("Timed out waiting for the next frame");
+                            false
+                        }
+                        Err(e) => {
+                            error!("{:?}", e);
+                            false
+                        }
+                    };
+
+                    // Try to carry on with a new device, only closing if there
+                    // isn't one to be had
+                    if device_lost {
+                        lost_frames = 0;
+                        if let Err(err) =
+                            state.recover(&window, event_loop_window_target, &mut world)
+                        {
+                            error!(
+                                "Failed to recover from losing the graphics device: {:?}",
+                                err
+                            );
+                            show_error_dialog(
+                                "Graphics Device Lost",
+                                &format!(
+                                "The graphics device stopped working and could not be restarted, \
+                                so the program has to close.\n\n{}",
+                                err
+                            ),
+                            );
+                            *control_flow = ControlFlow::Exit;
                         }
-                        _ => {}
                     }
                 }
-            }
-            Event::RedrawRequested(window_id) if window_id == window.id() => {
-                // Calculate delta time
-                let now = instant::Instant::now();
-                let dt = now - last_render_time;
-                last_render_time = now;
-
-                // Update the program state using delta time
-                state.update(dt, &mut world, &mut dispatchers);
-
-                // Render the next frame
-                match state.render(&mut world, &window) {
-                    Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
-                        state.resize(state.size)
+                // Waited long enough while power saving
+                Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                    window.request_redraw();
+                }
+                Event::MainEventsCleared => {
+                    if let ControlFlow::ExitWithCode(_) = *control_flow {
+                        return;
+                    }
+
+                    match state.redraw_after(&world) {
+                        Some(wait) => {
+                            *control_flow = ControlFlow::WaitUntil(instant::Instant::now() + wait);
+                        }
+                        None => {
+                            *control_flow = ControlFlow::Poll;
+                            window.request_redraw();
+                        }
                     }
-                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
-                    Err(e) => error!("{:?}", e),
                 }
-            }
-            Event::MainEventsCleared => {
-                window.request_redraw();
-            }
-            _ => {}
-        })
+                Event::LoopDestroyed => {
+                    world.read_resource::<UsageStats>().save();
+                    state.save_session(&world);
+                    preference_saver.save_changes(&world, state.size);
+                    crash::mark_stopped();
+                }
+                _ => {}
+            },
+        )
     }
 }
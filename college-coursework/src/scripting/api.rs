@@ -0,0 +1,202 @@
+use std::sync::{Arc, Mutex};
+
+use cgmath::Vector3;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FnPtr};
+
+use super::{ScriptBody, ScriptWorld};
+use crate::{
+    locale,
+    panel::validation::{self, Validation},
+};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_array(vector: [f64; 3]) -> Array {
+    vector.into_iter().map(Dynamic::from_float).collect()
+}
+
+fn from_array(array: Array) -> ScriptResult<[f64; 3]> {
+    //! Read a vector given as an array of three numbers, which can be written
+    //! as integers
+
+    if array.len() != 3 {
+        return Err(format!("Expected a vector of 3 numbers, found {}", array.len()).into());
+    }
+
+    let mut vector = [0.0; 3];
+    for (component, value) in vector.iter_mut().zip(array) {
+        *component = value
+            .as_float()
+            .or_else(|_| value.as_int().map(|value| value as f64))
+            .map_err(|type_name| format!("Expected a number, found {}", type_name))?;
+    }
+
+    Ok(vector)
+}
+
+fn validate(name: &str, validation: Validation) -> ScriptResult<()> {
+    //! Stop a script setting an invalid value, with the same reason the
+    //! panels give for it
+
+    validation
+        .map_err(|message| format!("Invalid {}: {}", name, locale::text(message, None)).into())
+}
+
+impl ScriptWorld {
+    fn body(&mut self, id: &str) -> ScriptResult<&mut ScriptBody> {
+        self.bodies
+            .iter_mut()
+            .find(|body| body.id == id)
+            .ok_or_else(|| format!("Unknown body: {}", id).into())
+    }
+}
+
+pub(super) fn register(engine: &mut Engine, world: &Arc<Mutex<ScriptWorld>>) {
+    //! Add the functions scripts use to query and change the simulation.
+    //! Positions are in m, velocities in m/s, masses in kg and times in
+    //! seconds
+
+    // Bodies
+
+    let shared = world.clone();
+    engine.register_fn("bodies", move || -> Array {
+        let world = shared.lock().unwrap();
+        world
+            .bodies
+            .iter()
+            .map(|body| Dynamic::from(body.id.clone()))
+            .collect()
+    });
+
+    let shared = world.clone();
+    engine.register_fn("name", move |id: &str| -> ScriptResult<String> {
+        Ok(shared.lock().unwrap().body(id)?.name.clone())
+    });
+
+    let shared = world.clone();
+    engine.register_fn("position", move |id: &str| -> ScriptResult<Array> {
+        Ok(to_array(shared.lock().unwrap().body(id)?.position))
+    });
+
+    let shared = world.clone();
+    engine.register_fn(
+        "set_position",
+        move |id: &str, position: Array| -> ScriptResult<()> {
+            let position = from_array(position)?;
+            validate(
+                "position",
+                validation::finite_vector(Vector3::from(position)),
+            )?;
+            shared.lock().unwrap().body(id)?.position = position;
+            Ok(())
+        },
+    );
+
+    let shared = world.clone();
+    engine.register_fn("velocity", move |id: &str| -> ScriptResult<Array> {
+        Ok(to_array(shared.lock().unwrap().body(id)?.velocity))
+    });
+
+    let shared = world.clone();
+    engine.register_fn(
+        "set_velocity",
+        move |id: &str, velocity: Array| -> ScriptResult<()> {
+            let velocity = from_array(velocity)?;
+            validate(
+                "velocity",
+                validation::below_light_speed(Vector3::from(velocity)),
+            )?;
+            shared.lock().unwrap().body(id)?.velocity = velocity;
+            Ok(())
+        },
+    );
+
+    let shared = world.clone();
+    engine.register_fn("mass", move |id: &str| -> ScriptResult<f64> {
+        Ok(shared.lock().unwrap().body(id)?.mass)
+    });
+
+    let shared = world.clone();
+    engine.register_fn("set_mass", move |id: &str, mass: f64| -> ScriptResult<()> {
+        validate("mass", validation::positive(mass))?;
+        shared.lock().unwrap().body(id)?.mass = mass;
+        Ok(())
+    });
+
+    let shared = world.clone();
+    engine.register_fn("radius", move |id: &str| -> ScriptResult<f64> {
+        Ok(shared.lock().unwrap().body(id)?.radius)
+    });
+
+    let shared = world.clone();
+    engine.register_fn(
+        "set_radius",
+        move |id: &str, radius: f64| -> ScriptResult<()> {
+            validate("radius", validation::positive(radius))?;
+            shared.lock().unwrap().body(id)?.radius = radius;
+            Ok(())
+        },
+    );
+
+    // Changes the velocity of a body by an impulse (in N s)
+    let shared = world.clone();
+    engine.register_fn(
+        "impulse",
+        move |id: &str, impulse: Array| -> ScriptResult<()> {
+            let impulse = from_array(impulse)?;
+            validate("impulse", validation::finite_vector(Vector3::from(impulse)))?;
+            let mut world = shared.lock().unwrap();
+            let body = world.body(id)?;
+            if body.mass <= 0.0 {
+                return Err(format!("{} has no mass to push", id).into());
+            }
+
+            for (velocity, impulse) in body.velocity.iter_mut().zip(impulse) {
+                *velocity += impulse / body.mass;
+            }
+            Ok(())
+        },
+    );
+
+    // Time
+
+    let shared = world.clone();
+    engine.register_fn("time", move || -> f64 { shared.lock().unwrap().time });
+
+    let shared = world.clone();
+    engine.register_fn("time_scale", move || -> f64 {
+        shared.lock().unwrap().time_scale
+    });
+
+    let shared = world.clone();
+    engine.register_fn(
+        "set_time_scale",
+        move |time_scale: f64| -> ScriptResult<()> {
+            validate("time scale", validation::non_negative(time_scale))?;
+            shared.lock().unwrap().time_scale = time_scale;
+            Ok(())
+        },
+    );
+
+    let shared = world.clone();
+    engine.register_fn("paused", move || -> bool { shared.lock().unwrap().paused });
+
+    let shared = world.clone();
+    engine.register_fn("pause", move || shared.lock().unwrap().paused = true);
+
+    let shared = world.clone();
+    engine.register_fn("resume", move || shared.lock().unwrap().paused = false);
+
+    // Callbacks
+
+    // Call a function with the simulated time after every step
+    let shared = world.clone();
+    engine.register_fn("on_step", move |callback: FnPtr| {
+        shared.lock().unwrap().callbacks.push(callback);
+    });
+
+    let shared = world.clone();
+    engine.register_fn("clear_callbacks", move || {
+        shared.lock().unwrap().callbacks.clear();
+    });
+}
@@ -0,0 +1,406 @@
+mod api;
+
+use std::{
+    fs, io, mem,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use log::warn;
+use rhai::{Dynamic, Engine, FnPtr, Scope, AST};
+use specs::{Join, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::simulation::{Identifier, Mass, Position, Radius, SimulationTime, TimeScale, Velocity};
+
+/// The most lines kept in the console, the oldest are removed first
+const MAX_CONSOLE_LINES: usize = 500;
+
+/// Stops a script that runs for too long, such as one stuck in a loop, so
+/// it can't freeze the program
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// A line in the script console
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleLine {
+    /// A script that was run
+    Input(String),
+    /// Something printed by a script
+    Output(String),
+    Error(String),
+}
+
+/// The state of a body, as seen and changed by scripts
+#[derive(Debug, Clone, Default)]
+struct ScriptBody {
+    id: String,
+    name: String,
+    position: [f64; 3],
+    velocity: [f64; 3],
+    mass: f64,
+    radius: f64,
+}
+
+/// The part of the simulation scripts can see, copied from the world before
+/// they run and back afterwards
+#[derive(Default)]
+struct ScriptWorld {
+    bodies: Vec<ScriptBody>,
+    /// Simulated time since the simulation started
+    time: f64,
+    time_scale: f64,
+    paused: bool,
+    /// Functions registered by scripts to be called after every step
+    callbacks: Vec<FnPtr>,
+    /// Lines printed by the scripts since they were last shown
+    printed: Vec<String>,
+}
+
+/// Runs scripts written in Rhai against the simulation, from the console or
+/// the command line
+pub struct Scripts {
+    engine: Engine,
+    /// Variables kept between the scripts run from the console
+    scope: Scope<'static>,
+    /// Every function defined by the scripts run so far, which is needed to
+    /// call them back
+    functions: AST,
+    world: Arc<Mutex<ScriptWorld>>,
+    /// Scripts waiting for the next step to run
+    pending: Vec<String>,
+    console: Vec<ConsoleLine>,
+}
+impl Default for Scripts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Scripts {
+    pub fn new() -> Self {
+        let world = Arc::new(Mutex::new(ScriptWorld::default()));
+
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        api::register(&mut engine, &world);
+
+        let shared = world.clone();
+        engine.on_print(move |text| shared.lock().unwrap().printed.push(text.to_string()));
+        let shared = world.clone();
+        engine.on_debug(move |text, _, _| shared.lock().unwrap().printed.push(text.to_string()));
+
+        Self {
+            engine,
+            scope: Self::new_scope(),
+            functions: AST::empty(),
+            world,
+            pending: Vec::new(),
+            console: Vec::new(),
+        }
+    }
+
+    fn new_scope() -> Scope<'static> {
+        //! The variables every script starts with, to help write times
+
+        let mut scope = Scope::new();
+        scope.push_constant("DAY", 86400.0);
+        scope.push_constant("YEAR", 31557600.0);
+        scope
+    }
+
+    pub fn queue(&mut self, script: String) {
+        //! Run a script at the end of the next step
+        self.pending.push(script);
+    }
+
+    pub fn queue_file(&mut self, path: &Path) -> io::Result<()> {
+        self.queue(fs::read_to_string(path)?);
+        Ok(())
+    }
+
+    pub fn console(&self) -> &[ConsoleLine] {
+        &self.console
+    }
+
+    pub fn clear_console(&mut self) {
+        self.console.clear();
+    }
+
+    pub fn callbacks(&self) -> usize {
+        self.world.lock().unwrap().callbacks.len()
+    }
+
+    pub fn reset(&mut self) {
+        //! Forget the variables, functions and callbacks of every script run
+        //! so far
+
+        self.scope = Self::new_scope();
+        self.functions = AST::empty();
+        self.world.lock().unwrap().callbacks.clear();
+    }
+
+    fn has_work(&self, stepped: bool) -> bool {
+        !self.pending.is_empty() || (stepped && self.callbacks() > 0)
+    }
+
+    fn run(&mut self, stepped: bool) {
+        //! Run the queued scripts, then call back the scripts waiting on a
+        //! step if the simulation has moved on
+
+        for script in mem::take(&mut self.pending) {
+            self.log(ConsoleLine::Input(script.clone()));
+
+            let result = self
+                .engine
+                .compile_with_scope(&self.scope, &script)
+                .map_err(|err| err.to_string())
+                .and_then(|ast| {
+                    // Keep the functions defined, so later scripts and
+                    // callbacks can use them
+                    self.functions = self.functions.merge(&ast.clone_functions_only());
+                    self.engine
+                        .run_ast_with_scope(&mut self.scope, &self.functions.merge(&ast))
+                        .map_err(|err| err.to_string())
+                });
+
+            self.show_printed();
+            if let Err(err) = result {
+                self.log(ConsoleLine::Error(err));
+            }
+        }
+
+        if !stepped {
+            return;
+        }
+
+        let (callbacks, time) = {
+            let mut world = self.world.lock().unwrap();
+            (mem::take(&mut world.callbacks), world.time)
+        };
+
+        let mut kept = Vec::with_capacity(callbacks.len());
+        for callback in callbacks {
+            let result = callback.call::<Dynamic>(&self.engine, &self.functions, (time,));
+
+            self.show_printed();
+            match result {
+                Ok(_) => kept.push(callback),
+                // A callback that fails would fail again every step
+                Err(err) => self.log(ConsoleLine::Error(format!(
+                    "Removed the callback {}: {}",
+                    callback.fn_name(),
+                    err
+                ))),
+            }
+        }
+
+        // Callbacks can register more callbacks
+        let mut world = self.world.lock().unwrap();
+        kept.append(&mut world.callbacks);
+        world.callbacks = kept;
+    }
+
+    fn show_printed(&mut self) {
+        let printed = mem::take(&mut self.world.lock().unwrap().printed);
+        for line in printed {
+            self.log(ConsoleLine::Output(line));
+        }
+    }
+
+    fn log(&mut self, line: ConsoleLine) {
+        if let ConsoleLine::Error(err) = &line {
+            warn!("Script error: {}", err);
+        }
+
+        self.console.push(line);
+        if self.console.len() > MAX_CONSOLE_LINES {
+            self.console.remove(0);
+        }
+    }
+}
+
+/// Runs the scripts queued since the last step, and calls back those
+/// waiting on each step
+pub struct ScriptRunner;
+impl ScriptRunner {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for ScriptRunner {
+    type SystemData = (
+        ReadStorage<'a, Identifier>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, Mass>,
+        WriteStorage<'a, Radius>,
+        Read<'a, SimulationTime>,
+        Write<'a, TimeScale>,
+        Write<'a, Scripts>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            ids,
+            mut positions,
+            mut velocities,
+            mut masses,
+            mut radii,
+            simulation_time,
+            mut time_scale,
+            mut scripts,
+        ): Self::SystemData,
+    ) {
+        let stepped = !time_scale.paused;
+        if !scripts.has_work(stepped) {
+            return;
+        }
+
+        {
+            let mut world = scripts.world.lock().unwrap();
+            world.bodies = (&ids, &positions, &velocities, &masses, (&radii).maybe())
+                .join()
+                .map(|(id, position, velocity, mass, radius)| ScriptBody {
+                    id: id.get_id().to_string(),
+                    name: id.get_name().to_string(),
+                    position: position.0.into(),
+                    velocity: velocity.0.into(),
+                    mass: mass.0,
+                    radius: radius.map_or(0.0, |radius| radius.0),
+                })
+                .collect();
+            world.time = simulation_time.0;
+            world.time_scale = time_scale.total_time_elapsed;
+            world.paused = time_scale.paused;
+        }
+
+        scripts.run(stepped);
+
+        let world = scripts.world.lock().unwrap();
+        for (id, position, velocity, mass, radius) in (
+            &ids,
+            &mut positions,
+            &mut velocities,
+            &mut masses,
+            (&mut radii).maybe(),
+        )
+            .join()
+        {
+            if let Some(body) = world.bodies.iter().find(|body| body.id == id.get_id()) {
+                position.0 = body.position.into();
+                velocity.0 = body.velocity.into();
+                mass.0 = body.mass;
+                if let Some(radius) = radius {
+                    radius.0 = body.radius;
+                }
+            }
+        }
+
+        // Choose the iterations again as the UI does when the time scale is
+        // changed
+        if world.time_scale != time_scale.total_time_elapsed {
+            *time_scale = TimeScale::from_max_time_per_iteration(world.time_scale, 86400.0);
+        }
+        time_scale.paused = world.paused;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConsoleLine, ScriptBody, Scripts};
+
+    #[test]
+    fn test_impulse_at_day() {
+        let mut scripts = Scripts::new();
+        scripts.world.lock().unwrap().bodies.push(ScriptBody {
+            id: "earth".to_string(),
+            name: "Earth".to_string(),
+            mass: 2.0,
+            ..Default::default()
+        });
+
+        scripts.queue(
+            r#"
+            let kicked = false;
+            on_step(|t| {
+                if !kicked && t >= 100 * DAY {
+                    impulse("earth", [0, 4, 0]);
+                    kicked = true;
+                    print("Kicked");
+                }
+            });
+            "#
+            .to_string(),
+        );
+        scripts.run(true);
+        assert_eq!(1, scripts.callbacks());
+
+        for day in [99.0, 100.0, 101.0] {
+            scripts.world.lock().unwrap().time = day * 86400.0;
+            scripts.run(true);
+        }
+
+        // Only kicked once, changing the velocity by the impulse over the mass
+        assert_eq!(
+            [0.0, 2.0, 0.0],
+            scripts.world.lock().unwrap().bodies[0].velocity
+        );
+        assert_eq!(
+            1,
+            scripts
+                .console()
+                .iter()
+                .filter(|line| **line == ConsoleLine::Output("Kicked".to_string()))
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_failing_callback_removed() {
+        let mut scripts = Scripts::new();
+        scripts.queue(r#"on_step(|t| mass("pluto"));"#.to_string());
+        scripts.run(true);
+
+        assert_eq!(0, scripts.callbacks());
+        assert!(matches!(
+            scripts.console().last(),
+            Some(ConsoleLine::Error(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        let mut scripts = Scripts::new();
+        scripts.world.lock().unwrap().bodies.push(ScriptBody {
+            id: "earth".to_string(),
+            name: "Earth".to_string(),
+            mass: 2.0,
+            ..Default::default()
+        });
+        let time_scale = scripts.world.lock().unwrap().time_scale;
+
+        for script in [
+            r#"set_position("earth", [parse_float("inf"), 0, 0]);"#,
+            r#"set_velocity("earth", [0, parse_float("NaN"), 0]);"#,
+            r#"set_velocity("earth", [3e8, 0, 0]);"#,
+            r#"set_mass("earth", 0.0);"#,
+            r#"set_mass("earth", parse_float("inf"));"#,
+            r#"set_time_scale(parse_float("NaN"));"#,
+            r#"set_time_scale(-1.0);"#,
+        ] {
+            scripts.queue(script.to_string());
+            scripts.run(false);
+            assert!(
+                matches!(scripts.console().last(), Some(ConsoleLine::Error(_))),
+                "{} wasn't rejected",
+                script
+            );
+        }
+
+        // None of the values were changed
+        let world = scripts.world.lock().unwrap();
+        assert_eq!([0.0; 3], world.bodies[0].position);
+        assert_eq!([0.0; 3], world.bodies[0].velocity);
+        assert_eq!(2.0, world.bodies[0].mass);
+        assert_eq!(time_scale, world.time_scale);
+    }
+}
@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use cgmath::InnerSpace;
+use rodio::{source::Zero, OutputStream, Sink, Source};
+use specs::{Builder, Join, RunNow, World, WorldExt};
+
+use crate::{
+    panel::PlanetWindowShown,
+    renderer::{
+        atmosphere::Atmosphere,
+        camera::{CameraControllerType, CameraPosition, CameraRotation, CameraSpeed},
+        components::{BodyStyle, CameraCenter, PlanetColour, PlanetTexture, SphereDetail},
+        rings::Rings,
+    },
+    simulation::{
+        DeltaTime, GravitationalConstant, GravityOverrides, GroupTags, Identifier,
+        InteractionFlags, InteractionHandler, Lineage, Mass, MassRate, Position, Radius, Satellite,
+        SimulationState, SimulationTime, Simulator, TimeScale, Trail, TrailPoint, Velocity,
+        PLANET_EARTH, SUN,
+    },
+    util::BIG_G,
+};
+
+/// The result of a check, with a description of what was found
+type CheckResult = std::result::Result<String, String>;
+
+pub async fn run() -> bool {
+    //! Exercise each part of the program that depends on the machine it is
+    //! running on, printing a pass/fail report. Returns whether every check
+    //! passed
+
+    let checks = [
+        ("GPU", check_gpu().await),
+        ("Audio", check_audio()),
+        ("File dialogs", check_dialogs()),
+        ("Save round trip", check_save_round_trip()),
+        ("Physics", check_physics()),
+    ];
+
+    println!("Self-test report");
+    for (name, result) in checks.iter() {
+        match result {
+            Ok(details) => println!("  [PASS] {}: {}", name, details),
+            Err(details) => println!("  [FAIL] {}: {}", name, details),
+        }
+    }
+
+    let failed = checks.iter().filter(|(_, result)| result.is_err()).count();
+    if failed == 0 {
+        println!("All checks passed");
+    } else {
+        println!("{} of {} checks failed", failed, checks.len());
+    }
+
+    failed == 0
+}
+
+async fn check_gpu() -> CheckResult {
+    //! Check a GPU can be found and a device created on it, without a window
+
+    let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or("No suitable GPU adapter found")?;
+
+    adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                features: wgpu::Features::empty(),
+                limits: wgpu::Limits::default(),
+                label: None,
+            },
+            None,
+        )
+        .await
+        .map_err(|err| format!("Failed to create a device: {}", err))?;
+
+    let info = adapter.get_info();
+    Ok(format!("{} ({:?})", info.name, info.backend))
+}
+
+fn check_audio() -> CheckResult {
+    //! Check an audio output device can be opened and played to
+
+    let (_stream, stream_handle) = OutputStream::try_default()
+        .map_err(|err| format!("Failed to open an audio output: {}", err))?;
+    let sink = Sink::try_new(&stream_handle)
+        .map_err(|err| format!("Failed to create an audio sink: {}", err))?;
+
+    // Play a moment of silence
+    sink.append(Zero::<f32>::new(2, 44100).take_duration(Duration::from_millis(50)));
+    sink.sleep_until_end();
+
+    Ok("Played to the default output device".to_string())
+}
+
+fn check_dialogs() -> CheckResult {
+    //! Check the file dialogs have somewhere to be shown, without showing
+    //! one. rfd draws them with GTK 3 on Linux, which needs a display
+
+    #[cfg(target_family = "windows")]
+    return Ok("Using Windows common dialogs".to_string());
+
+    #[cfg(target_os = "macos")]
+    return Ok("Using AppKit panels".to_string());
+
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    {
+        let is_set =
+            |name: &str| matches!(std::env::var_os(name), Some(value) if !value.is_empty());
+
+        if is_set("WAYLAND_DISPLAY") {
+            Ok("Using GTK 3 on Wayland".to_string())
+        } else if is_set("DISPLAY") {
+            Ok("Using GTK 3 on X11".to_string())
+        } else {
+            Err("Neither $WAYLAND_DISPLAY nor $DISPLAY is set, GTK 3 has no display to show file dialogs on".to_string())
+        }
+    }
+}
+
+fn create_world() -> World {
+    //! Create a world with the Sun and the Earth, without a renderer
+
+    let mut world = World::new();
+    world.register::<Identifier>();
+    world.register::<PlanetColour>();
+    world.register::<BodyStyle>();
+    world.register::<PlanetTexture>();
+    world.register::<Position>();
+    world.register::<Velocity>();
+    world.register::<Mass>();
+    world.register::<MassRate>();
+    world.register::<Radius>();
+    world.register::<Lineage>();
+    world.register::<Satellite>();
+    world.register::<InteractionHandler>();
+    world.register::<GroupTags>();
+    world.register::<SphereDetail>();
+    world.register::<PlanetWindowShown>();
+    world.register::<Atmosphere>();
+    world.register::<Rings>();
+    world.register::<Trail>();
+
+    world.insert(CameraPosition::default());
+    world.insert(CameraRotation::default());
+    world.insert(CameraSpeed::default());
+    world.insert(CameraControllerType::default());
+    world.insert(CameraCenter::new(SUN.get_identifier()));
+    world.insert(GravitationalConstant(BIG_G));
+    world.insert(GravityOverrides::default());
+    world.insert(TimeScale::new(86400.0, 24));
+    world.insert(DeltaTime(Duration::from_secs(1)));
+    world.insert(SimulationTime::default());
+
+    for (body, flags) in [
+        (SUN, InteractionFlags::STAR),
+        (PLANET_EARTH, InteractionFlags::all()),
+    ] {
+        world
+            .create_entity()
+            .with(body.get_identifier())
+            .with(PlanetColour(body.get_colour()))
+            .with(BodyStyle::default())
+            .with(body.get_pos())
+            .with(body.get_vel())
+            .with(body.get_mass())
+            .with(body.get_radius())
+            .with(Lineage::default())
+            .with(SphereDetail(3))
+            .with(PlanetWindowShown::default())
+            .maybe_with(Atmosphere::for_body(body.get_identifier().get_id()))
+            .maybe_with(Rings::for_body(body.get_identifier().get_id()))
+            .with(Trail::from_points([TrailPoint::at(body.get_pos().0)]))
+            .with(InteractionHandler::new(flags, body.get_body_type()))
+            .build();
+    }
+
+    world
+}
+
+fn check_save_round_trip() -> CheckResult {
+    //! Check a simulation survives being saved and loaded in every format
+
+    let state = SimulationState::serialize_from_world(&mut create_world());
+
+    let toml = toml::to_string_pretty(&state).map_err(|err| err.to_string())?;
+    let reloaded = toml::from_str::<SimulationState>(&toml).map_err(|err| err.to_string())?;
+    if toml::to_string_pretty(&reloaded).map_err(|err| err.to_string())? != toml {
+        return Err("The TOML save changed after being loaded".to_string());
+    }
+
+    let json = serde_json::to_string_pretty(&state).map_err(|err| err.to_string())?;
+    let reloaded = serde_json::from_str::<SimulationState>(&json).map_err(|err| err.to_string())?;
+    if serde_json::to_string_pretty(&reloaded).map_err(|err| err.to_string())? != json {
+        return Err("The JSON save changed after being loaded".to_string());
+    }
+
+    let binary = rmp_serde::to_vec_named(&state).map_err(|err| err.to_string())?;
+    let reloaded =
+        rmp_serde::from_slice::<SimulationState>(&binary).map_err(|err| err.to_string())?;
+    if rmp_serde::to_vec_named(&reloaded).map_err(|err| err.to_string())? != binary {
+        return Err("The binary save changed after being loaded".to_string());
+    }
+
+    Ok("TOML, JSON and binary saves are unchanged after loading".to_string())
+}
+
+fn check_physics() -> CheckResult {
+    //! Check the Earth completes an orbit of the Sun in a year
+
+    let world = create_world();
+
+    // Simulate a year, a day per frame
+    let mut simulator = Simulator::new();
+    for _ in 0..365 {
+        simulator.run_now(&world);
+    }
+
+    let positions = world.read_storage::<Position>();
+    let ids = world.read_storage::<Identifier>();
+    let earth = (&ids, &positions)
+        .join()
+        .find(|(id, _)| id.get_id() == PLANET_EARTH.get_identifier().get_id())
+        .map(|(_, position)| position.0)
+        .ok_or("The Earth is missing")?;
+
+    // After a year the Earth should be back within 1% of its orbit of where
+    // it started
+    let start = PLANET_EARTH.get_pos().0;
+    let error = (earth - start).magnitude() / start.magnitude();
+    if error < 0.01 {
+        Ok(format!(
+            "The Earth returned to within {:.3}% of its start",
+            error * 100.0
+        ))
+    } else {
+        Err(format!(
+            "The Earth ended {:.3}% of its orbit away from its start",
+            error * 100.0
+        ))
+    }
+}
@@ -3,25 +3,54 @@ use std::sync::Arc;
 use cgmath::{Quaternion, Vector3, Zero};
 use crossbeam::channel::Receiver;
 use error_stack::Result;
+use rayon::ThreadPool;
 use specs::{
     Builder, Dispatcher, DispatcherBuilder, Join, Read, ReadExpect, ReadStorage, World, WorldExt,
 };
 use thiserror::Error;
 
 use crate::{
-    models::sphere::Icosphere,
-    panel::PlanetWindowShown,
+    accessibility::{AccessibilitySettings, Announcements, Narrator},
+    audio::MusicPlayer,
+    models::cache::{ModelCache, Surface},
+    panel::{PlanetWindowShown, Tutorial},
     renderer::{
+        arrows::ArrowSettings,
+        atmosphere::Atmosphere,
+        attract::AttractSettings,
+        capture::FrameCaptureRequest,
         components::{
-            CameraCenter, PlanetColour, RenderModel, UpdateCameraDisplacement, UpdateCameraPosition,
+            BodyStyle, CameraCenter, PlanetColour, PlanetTexture, RenderModel, SphereDetail,
+            UpdateCameraDisplacement, UpdateCameraPosition,
         },
+        cursor::CursorSettings,
+        debug::DebugDraw,
+        field::FieldSettings,
+        follow::FlyTo,
+        fonts::FontSettings,
+        gamepad::GamepadSettings,
+        grid::GridSettings,
+        hud::{HudSettings, RenderStats},
         instance::Instance,
+        labels::LabelSettings,
+        light::Light,
+        path::CameraPath,
+        rings::Rings,
+        ruler::Ruler,
+        screenshot::ScreenshotRequests,
+        trails::TrailStyle,
     },
+    scripting::{ScriptRunner, Scripts},
     simulation::{
-        self, BodyType, GravitationalConstant, Identifier, InstanceUpdater, InteractionFlags,
-        InteractionHandler, Mass, Position, PositionScaleFactor, Simulator, TimeScale, Velocity,
-        SUN,
+        self, AutosaveSettings, BodyScale, BodyType, CloseEncounterSettings, Collider, DroppedFile,
+        GravitationalConstant, GravityOverrides, GroupTags, Identifier, InstanceUpdater,
+        InteractionFlags, InteractionHandler, Lineage, Mass, MassChanger, MassRate, MemoryBudget,
+        OrbitClosureDetector, OrbitTracker, Position, PositionScaleFactor, PruneReport,
+        QuickSlotRequest, Radius, Satellite, SimulationTime, Simulator, Stopwatch, TimeJump,
+        TimeJumper, TimeScale, Trail, TrailRecorder, TrailSettings, Velocity, SUN,
     },
+    stats::UsageStats,
+    toasts::Toasts,
     util::BIG_G,
 };
 
@@ -36,6 +65,9 @@ pub async fn setup<'a, 'b>(
     device: Arc<wgpu::Device>,
     queue: Arc<wgpu::Queue>,
     texture_bind_group_layout: Arc<wgpu::BindGroupLayout>,
+    thread_pool: Arc<ThreadPool>,
+    safe_mode: bool,
+    solar_system: bool,
 ) -> Result<(World, Dispatchers<'a, 'b>), SetupError> {
     //! Setup the Enityt Component System
     let mut world = World::new();
@@ -46,9 +78,26 @@ pub async fn setup<'a, 'b>(
     world.register::<Position>();
     world.register::<Velocity>();
     world.register::<Mass>();
+    world.register::<MassRate>();
+    world.register::<Radius>();
+    world.register::<Lineage>();
     world.register::<PlanetColour>();
+    world.register::<BodyStyle>();
+    world.register::<PlanetTexture>();
+    world.register::<Light>();
+    world.register::<Atmosphere>();
+    world.register::<Rings>();
+    world.register::<Trail>();
+    world.register::<OrbitTracker>();
     world.register::<RenderModel>();
+    world.register::<SphereDetail>();
     world.register::<InteractionHandler>();
+    world.register::<Satellite>();
+    world.register::<GroupTags>();
+
+    // Bodies made from the same sphere and colour share their mesh and
+    // material
+    let mut model_cache = ModelCache::default();
 
     // Create the Sun entity
     world
@@ -58,14 +107,30 @@ pub async fn setup<'a, 'b>(
         .with(SUN.get_pos())
         .with(SUN.get_vel())
         .with(SUN.get_mass())
+        .with(MassRate::default())
+        .with(SUN.get_radius())
+        .with(Lineage::default())
         .with(PlanetColour(SUN.get_colour()))
+        .with(BodyStyle::default())
+        .maybe_with(
+            SUN.get_texture()
+                .map(|texture| PlanetTexture(texture.to_string())),
+        )
+        .with(SphereDetail(4))
         .with(RenderModel::new(
             &device,
-            Icosphere::new(8.0, 4).into_model(
+            model_cache.body_model(
                 &device,
                 &queue,
-                "The Sun".into(),
+                1.0,
+                4,
                 SUN.get_colour(),
+                Surface::for_body(
+                    SUN.get_identifier().get_id(),
+                    SUN.get_texture(),
+                    SUN.get_mass().0,
+                    true,
+                ),
                 &texture_bind_group_layout,
             ),
             Instance::new([0.0; 3].into(), Quaternion::zero()),
@@ -76,10 +141,19 @@ pub async fn setup<'a, 'b>(
             InteractionFlags::STAR,
             BodyType::Star,
         ))
+        .with(Light::default())
         .build();
 
+    // Only the Sun is kept when a saved simulation is loaded, so the rest of
+    // the solar system isn't made when starting from one
+    let (planets, moons) = if solar_system {
+        (simulation::planets(), simulation::moons())
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
     // Create the planets
-    for planet in simulation::planets() {
+    for planet in planets {
         world
             .create_entity()
             .with(planet.get_identifier())
@@ -87,14 +161,33 @@ pub async fn setup<'a, 'b>(
             .with(planet.get_pos())
             .with(planet.get_vel())
             .with(planet.get_mass())
+            .with(MassRate::default())
+            .with(planet.get_radius())
+            .with(Lineage::default())
+            .with(Trail::default())
+            .with(OrbitTracker::default())
             .with(PlanetColour(planet.get_colour()))
+            .with(BodyStyle::default())
+            .maybe_with(
+                planet
+                    .get_texture()
+                    .map(|texture| PlanetTexture(texture.to_string())),
+            )
+            .with(SphereDetail(3))
             .with(RenderModel::new(
                 &device,
-                Icosphere::new(2.5, 3).into_model(
+                model_cache.body_model(
                     &device,
                     &queue,
-                    planet.get_identifier().get_id().to_string(),
+                    1.0,
+                    3,
                     planet.get_colour(),
+                    Surface::for_body(
+                        planet.get_identifier().get_id(),
+                        planet.get_texture(),
+                        planet.get_mass().0,
+                        false,
+                    ),
                     &texture_bind_group_layout,
                 ),
                 Instance::new(
@@ -104,6 +197,8 @@ pub async fn setup<'a, 'b>(
                 wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
                 Some(planet.get_identifier().get_id()),
             ))
+            .maybe_with(Atmosphere::for_body(planet.get_identifier().get_id()))
+            .maybe_with(Rings::for_body(planet.get_identifier().get_id()))
             .with(InteractionHandler::new(
                 InteractionFlags::all(),
                 BodyType::Planet,
@@ -111,34 +206,163 @@ pub async fn setup<'a, 'b>(
             .build();
     }
 
+    // Create the moons as satellites of their planets
+    for (parent_id, moon) in moons {
+        let parent = (&world.entities(), &world.read_storage::<Identifier>())
+            .join()
+            .find(|(_, id)| id.get_id() == parent_id)
+            .map(|(entity, _)| entity)
+            .expect("Moon orbits a body that does not exist");
+
+        world
+            .create_entity()
+            .with(moon.get_identifier())
+            .with(PlanetWindowShown::default())
+            .with(moon.get_pos())
+            .with(moon.get_vel())
+            .with(moon.get_mass())
+            .with(MassRate::default())
+            .with(moon.get_radius())
+            .with(Lineage::default())
+            .with(Trail::default())
+            .with(OrbitTracker::default())
+            .with(PlanetColour(moon.get_colour()))
+            .with(BodyStyle::default())
+            .maybe_with(
+                moon.get_texture()
+                    .map(|texture| PlanetTexture(texture.to_string())),
+            )
+            .with(SphereDetail(3))
+            .with(RenderModel::new(
+                &device,
+                model_cache.body_model(
+                    &device,
+                    &queue,
+                    1.0,
+                    3,
+                    moon.get_colour(),
+                    Surface::for_body(
+                        moon.get_identifier().get_id(),
+                        moon.get_texture(),
+                        moon.get_mass().0,
+                        false,
+                    ),
+                    &texture_bind_group_layout,
+                ),
+                Instance::new(
+                    moon.get_pos().0.map(|a| a as f32) / 4_000_000_000.0,
+                    Quaternion::zero(),
+                ),
+                wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                Some(moon.get_identifier().get_id()),
+            ))
+            .with(InteractionHandler::new(
+                InteractionFlags::all(),
+                moon.get_body_type(),
+            ))
+            .with(Satellite::new(parent))
+            .build();
+    }
+
     // Add the global states to thje Entity Component System
     world.insert(device);
     world.insert(queue);
     world.insert(texture_bind_group_layout);
+    world.insert(model_cache);
     world.insert(TimeScale::new(3155760.0, 20));
+    world.insert(SimulationTime::default());
+    world.insert(Stopwatch::default());
+    world.insert(TimeJump::default());
     world.insert(GravitationalConstant(BIG_G));
+    world.insert(GravityOverrides::default());
+    world.insert(CloseEncounterSettings::default());
+    world.insert(TrailSettings::default());
+    world.insert(MemoryBudget::default());
+    world.insert(AutosaveSettings::default());
+    world.insert(PruneReport::default());
     world.insert(PositionScaleFactor(4_000_000_000.0));
+    world.insert(BodyScale::default());
     world.insert(CameraCenter::new(SUN.get_identifier()));
+    // Safe mode ignores saved settings in case they caused a crash
+    world.insert(if safe_mode {
+        AccessibilitySettings::default()
+    } else {
+        AccessibilitySettings::load()
+    });
+    world.insert(if safe_mode {
+        FontSettings::default()
+    } else {
+        FontSettings::load()
+    });
+
+    // Count this session in the usage statistics
+    let mut usage_stats = UsageStats::load();
+    usage_stats.sessions += 1;
+    world.insert(usage_stats);
+    world.insert(Announcements::default());
+    world.insert(Toasts::default());
+    world.insert(Tutorial::default());
+    world.insert(LabelSettings::default());
+    world.insert(CursorSettings::default());
+    world.insert(AttractSettings::default());
+    world.insert(TrailStyle::default());
+    world.insert(GridSettings::default());
+    world.insert(Ruler::default());
+    world.insert(ArrowSettings::default());
+    world.insert(FieldSettings::default());
+    world.insert(HudSettings::default());
+    world.insert(RenderStats::default());
+    world.insert(CameraPath::default());
+    world.insert(DebugDraw::default());
+    world.insert(FlyTo::default());
+    world.insert(GamepadSettings::default());
+    world.insert(FrameCaptureRequest::default());
+    world.insert(ScreenshotRequests::default());
+    world.insert(QuickSlotRequest::default());
+    world.insert(DroppedFile::default());
+    world.insert(Scripts::new());
+    // Replaced by the window once the music has started
+    world.insert(MusicPlayer::default());
 
     // Register the systems
     let simulation_dispatcher = DispatcherBuilder::new()
+        .with_pool(thread_pool)
         // .with(
         //     UpdateCameraDisplacement {},
         //     "sys_update_camera_displacement",
         //     &[],
         // )
+        .with(TimeJumper::new(), "sys_time_jumper", &[])
         .with(
             Simulator::new(),
             "sys_simulator",
             // &["sys_update_camera_displacement"],
-            &[],
+            &["sys_time_jumper"],
+        )
+        .with(Collider::new(), "sys_collider", &["sys_simulator"])
+        .with(MassChanger::new(), "sys_mass_changer", &["sys_collider"])
+        .with(
+            TrailRecorder::new(),
+            "sys_trail_recorder",
+            &["sys_collider"],
+        )
+        .with(
+            OrbitClosureDetector::new(),
+            "sys_orbit_closure_detector",
+            &["sys_collider"],
+        )
+        .with(
+            ScriptRunner::new(),
+            "sys_script_runner",
+            &["sys_mass_changer"],
         )
         .with(
             InstanceUpdater::new(),
             "sys_instance_updater",
-            &["sys_simulator"],
+            &["sys_collider", "sys_script_runner"],
         )
         // .with(UpdateCameraPosition {}, "sys_update_camera_position", &[])
+        .with(Narrator::new(), "sys_narrator", &[])
         .build();
 
     Ok((
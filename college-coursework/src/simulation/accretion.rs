@@ -0,0 +1,114 @@
+use specs::{Component, Entities, Join, Read, ReadStorage, System, VecStorage, WriteStorage};
+
+use super::{DeltaTime, Mass, Radius, TimeScale};
+
+/// How fast a body gains or loses mass, e.g. a protoplanet accreting matter
+/// or a comet evaporating (in kg per second of simulated time, negative for
+/// mass loss)
+#[derive(Debug, Clone, Copy, Default, Component)]
+#[storage(VecStorage)]
+pub struct MassRate(pub f64);
+
+/// Changes the mass of each body by its mass rate. Matter is gained at rest
+/// relative to the body and lost evenly in every direction, so the velocity
+/// of the body is unchanged while its momentum changes with its mass. Bodies
+/// that lose all of their mass are removed
+pub struct MassChanger;
+impl MassChanger {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for MassChanger {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Mass>,
+        WriteStorage<'a, Radius>,
+        ReadStorage<'a, MassRate>,
+        Read<'a, DeltaTime>,
+        Read<'a, TimeScale>,
+    );
+
+    fn run(&mut self, (entities, mut masses, mut radii, rates, dt, time_scale): Self::SystemData) {
+        if time_scale.paused {
+            return;
+        }
+        let elapsed = time_scale.total_time_elapsed * dt.0.as_secs_f64();
+
+        for (entity, mass, radius, rate) in
+            (&entities, &mut masses, (&mut radii).maybe(), &rates).join()
+        {
+            let changed = change_mass(mass.0, rate.0, elapsed);
+
+            // Nothing is left of a body that has lost all of its mass
+            if changed <= 0.0 {
+                entities.delete(entity).unwrap();
+                continue;
+            }
+
+            // Keep the density the same, as when bodies merge
+            if let Some(radius) = radius {
+                if mass.0 > 0.0 {
+                    radius.0 *= (changed / mass.0).cbrt();
+                }
+            }
+            mass.0 = changed;
+        }
+    }
+}
+
+fn change_mass(mass: f64, rate: f64, elapsed: f64) -> f64 {
+    //! The mass of a body after `elapsed` seconds, a body can't lose more
+    //! mass than it has
+    (mass + rate * elapsed).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use specs::{Builder, RunNow, World, WorldExt};
+
+    use super::{change_mass, MassChanger, MassRate};
+    use crate::simulation::{DeltaTime, Mass, Radius, TimeScale};
+
+    #[test]
+    fn test_mass_change() {
+        assert_eq!(1.5e20, change_mass(1e20, 5e13, 1e6));
+        assert_eq!(0.5e20, change_mass(1e20, -5e13, 1e6));
+
+        // A comet that has completely evaporated has no mass left
+        assert_eq!(0.0, change_mass(1e20, -5e13, 1e9));
+    }
+
+    #[test]
+    fn test_evaporated_body_is_removed() {
+        let mut world = World::new();
+        world.register::<Mass>();
+        world.register::<Radius>();
+        world.register::<MassRate>();
+        world.insert(DeltaTime(Duration::from_secs(1)));
+        world.insert(TimeScale::new(1e6, 1));
+
+        let comet = world
+            .create_entity()
+            .with(Mass(1e20))
+            .with(Radius(1e4))
+            .with(MassRate(-5e13))
+            .build();
+
+        // Half of the comet is left after the first million seconds
+        let mut mass_changer = MassChanger::new();
+        mass_changer.run_now(&world);
+        world.maintain();
+        assert_eq!(0.5e20, world.read_storage::<Mass>().get(comet).unwrap().0);
+        assert!(
+            (world.read_storage::<Radius>().get(comet).unwrap().0 - 1e4 * 0.5f64.cbrt()).abs()
+                < 1e-6
+        );
+
+        mass_changer.run_now(&world);
+        world.maintain();
+        assert!(!world.is_alive(comet));
+    }
+}
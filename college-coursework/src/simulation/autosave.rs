@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use instant::{Duration, Instant};
+use log::{info, warn};
+use specs::{World, WorldExt};
+
+use crate::{locale::tr, log::LOG_DIR, toasts::Toasts};
+
+use super::{SimulationState, SimulationTime};
+
+/// Start of the file name of every autosave, so other files in the
+/// directory are never removed when rotating
+const AUTOSAVE_PREFIX: &'static str = "autosave-";
+
+/// Shortest real time between autosaves, so fast simulated time doesn't
+/// write a save every frame
+const MIN_AUTOSAVE_GAP: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    /// Where autosaves are written, next to the logs
+    pub static ref AUTOSAVE_DIR: PathBuf = LOG_DIR
+        .parent()
+        .map_or_else(|| LOG_DIR.join("autosaves"), |dir| dir.join("autosaves"));
+}
+
+/// Which clock the autosave interval is measured with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutosaveClock {
+    Real,
+    Simulated,
+}
+impl AutosaveClock {
+    pub const ALL: [Self; 2] = [Self::Real, Self::Simulated];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Real => tr!("autosave-clock-real"),
+            Self::Simulated => tr!("autosave-clock-simulated"),
+        }
+    }
+}
+
+/// Options for saving the simulation automatically, changed from the UI
+#[derive(Debug, Clone, Copy)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    /// Minutes between autosaves
+    pub interval: f32,
+    pub clock: AutosaveClock,
+    /// How many of the latest autosaves are kept, older ones are removed
+    pub kept: usize,
+}
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 5.0,
+            clock: AutosaveClock::Real,
+            kept: 5,
+        }
+    }
+}
+
+/// Saves the simulation to the autosave directory every interval, keeping
+/// only the latest few saves
+pub struct Autosaver {
+    last_real: Instant,
+    last_simulated: f64,
+}
+impl Autosaver {
+    pub fn new() -> Self {
+        Self {
+            last_real: Instant::now(),
+            last_simulated: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, world: &mut World) {
+        //! Autosave the simulation if the interval has passed since the last
+        //! autosave
+
+        let settings = *world.read_resource::<AutosaveSettings>();
+        let simulation_time = world.read_resource::<SimulationTime>().0;
+
+        // Count the interval from when autosaving is enabled
+        if !settings.enabled {
+            self.last_real = Instant::now();
+            self.last_simulated = simulation_time;
+            return;
+        }
+
+        let interval = settings.interval as f64 * 60.0;
+        let elapsed = match settings.clock {
+            AutosaveClock::Real => self.last_real.elapsed().as_secs_f64(),
+            // Loading a save can move the simulated time backwards
+            AutosaveClock::Simulated => (simulation_time - self.last_simulated).abs(),
+        };
+        if elapsed < interval || self.last_real.elapsed() < MIN_AUTOSAVE_GAP {
+            return;
+        }
+
+        self.last_real = Instant::now();
+        self.last_simulated = simulation_time;
+
+        let toasts = world.read_resource::<Toasts>().sender();
+        let contents = match toml::to_string_pretty(&SimulationState::serialize_from_world(world)) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize the autosave: {}", err);
+                toasts.error(tr!("autosave-failed", error = err.to_string()));
+                return;
+            }
+        };
+
+        // Written on another thread, so a large simulation doesn't stall the
+        // frame
+        std::thread::spawn(move || {
+            let path = AUTOSAVE_DIR.join(format!(
+                "{}{}.toml",
+                AUTOSAVE_PREFIX,
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ));
+
+            match fs::create_dir_all(AUTOSAVE_DIR.as_path())
+                .and_then(|_| fs::write(&path, contents))
+            {
+                Ok(()) => info!("Autosaved to {:?}", path),
+                Err(err) => {
+                    warn!("Failed to write the autosave to {:?}: {}", path, err);
+                    toasts.error(tr!("autosave-failed", error = err.to_string()));
+                    return;
+                }
+            }
+
+            remove_old_autosaves(AUTOSAVE_DIR.as_path(), settings.kept);
+        });
+    }
+}
+
+fn remove_old_autosaves(dir: &Path, kept: usize) {
+    //! Remove all but the latest autosaves in a directory
+
+    let mut autosaves = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| name.starts_with(AUTOSAVE_PREFIX))
+            })
+            .collect::<Vec<_>>(),
+        Err(err) => {
+            warn!("Failed to list the autosaves in {:?}: {}", dir, err);
+            return;
+        }
+    };
+
+    // The file names start with the date and time they were saved, so they
+    // sort oldest first
+    autosaves.sort();
+
+    let removed = autosaves.len().saturating_sub(kept);
+    for path in autosaves.drain(..removed) {
+        if let Err(err) = fs::remove_file(&path) {
+            warn!("Failed to remove the old autosave {:?}: {}", path, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::remove_old_autosaves;
+
+    #[test]
+    fn test_old_autosaves_removed() {
+        let dir = std::env::temp_dir().join(format!("autosave-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for name in [
+            "autosave-20221001-120000.toml",
+            "autosave-20221001-120500.toml",
+            "autosave-20221001-121000.toml",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        remove_old_autosaves(&dir, 2);
+
+        let mut remaining = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            vec![
+                "autosave-20221001-120500.toml",
+                "autosave-20221001-121000.toml",
+                "notes.txt",
+            ],
+            remaining
+        );
+    }
+}
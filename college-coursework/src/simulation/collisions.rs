@@ -0,0 +1,220 @@
+use cgmath::{InnerSpace, Vector3};
+use serde::{Deserialize, Serialize};
+use specs::{
+    Component, Entities, Entity, Join, Read, ReadStorage, System, VecStorage, Write, WriteStorage,
+};
+
+use crate::{
+    accessibility::Announcements,
+    locale::{self, tr},
+};
+
+use super::{DeltaTime, Identifier, Mass, Position, Radius, SimulationTime, TimeScale, Velocity};
+
+/// One of the bodies that combined to form a merged body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ancestor {
+    pub id: String,
+    pub name: String,
+    pub mass: f64,
+    pub lineage: Lineage,
+}
+
+/// A merge of bodies that happened during the simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Merge {
+    /// Simulated time of the merge (in seconds)
+    pub time: f64,
+    pub bodies: Vec<Ancestor>,
+}
+
+/// The merge that formed a body, if it was formed by one. Each of the merged
+/// bodies has its own lineage, making up a tree of every merge
+#[derive(Debug, Clone, Default, Component, Serialize, Deserialize)]
+#[storage(VecStorage)]
+pub struct Lineage(pub Option<Merge>);
+
+/// Merges bodies that have collided, conserving their mass and momentum.
+/// Bodies are swept along their path over the frame, so fast bodies can't
+/// pass through each other between frames at high time scales
+pub struct Collider;
+impl Collider {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for Collider {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Identifier>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, Mass>,
+        WriteStorage<'a, Radius>,
+        WriteStorage<'a, Lineage>,
+        Read<'a, SimulationTime>,
+        Read<'a, TimeScale>,
+        Read<'a, DeltaTime>,
+        Write<'a, Announcements>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            ids,
+            mut positions,
+            mut velocities,
+            mut masses,
+            mut radii,
+            mut lineages,
+            simulation_time,
+            time_scale,
+            dt,
+            mut announcements,
+        ): Self::SystemData,
+    ) {
+        // The simulated time that passed this frame, across every iteration
+        let frame_time = if time_scale.paused {
+            0.0
+        } else {
+            time_scale.total_time_elapsed * dt.0.as_secs_f64()
+        };
+
+        let bodies = (&entities, &positions, &velocities, &masses, &radii)
+            .join()
+            .map(|(entity, position, velocity, mass, radius)| {
+                (entity, position.0, velocity.0, mass.0, radius.0)
+            })
+            .collect::<Vec<_>>();
+
+        // Find the pairs of bodies that overlap, each body can only be merged
+        // once per frame
+        let mut merged: Vec<Entity> = Vec::new();
+        let mut collisions: Vec<(Entity, Entity)> = Vec::new();
+        for (i, (a, a_position, a_velocity, a_mass, a_radius)) in bodies.iter().enumerate() {
+            for (b, b_position, b_velocity, b_mass, b_radius) in bodies.iter().skip(i + 1) {
+                if merged.contains(a) || merged.contains(b) {
+                    continue;
+                }
+
+                let distance = closest_approach(
+                    *a_position - *b_position,
+                    *a_velocity - *b_velocity,
+                    frame_time,
+                );
+                if distance < a_radius + b_radius {
+                    // The larger body absorbs the smaller one
+                    let (survivor, absorbed) = if a_mass >= b_mass { (*a, *b) } else { (*b, *a) };
+                    collisions.push((survivor, absorbed));
+                    merged.push(*a);
+                    merged.push(*b);
+                }
+            }
+        }
+
+        for (survivor, absorbed) in collisions {
+            let ancestor = |entity: Entity| {
+                let id = ids.get(entity);
+                Ancestor {
+                    id: id.map_or(String::new(), |id| id.get_id().to_string()),
+                    name: id.map_or(String::new(), |id| id.get_name().to_string()),
+                    mass: masses.get(entity).map_or(0.0, |mass| mass.0),
+                    lineage: lineages.get(entity).cloned().unwrap_or_default(),
+                }
+            };
+            let lineage = Lineage(Some(Merge {
+                time: simulation_time.0,
+                bodies: vec![ancestor(survivor), ancestor(absorbed)],
+            }));
+
+            let (m1, m2) = (
+                masses.get(survivor).unwrap().0,
+                masses.get(absorbed).unwrap().0,
+            );
+            let total_mass = m1 + m2;
+
+            // Combine the bodies at their center of mass, conserving momentum
+            let position = (positions.get(survivor).unwrap().0 * m1
+                + positions.get(absorbed).unwrap().0 * m2)
+                / total_mass;
+            let velocity: Vector3<f64> = (velocities.get(survivor).unwrap().0 * m1
+                + velocities.get(absorbed).unwrap().0 * m2)
+                / total_mass;
+
+            // Keep the density the same by combining the volumes of the bodies
+            let radius = (radii.get(survivor).unwrap().0.powi(3)
+                + radii.get(absorbed).unwrap().0.powi(3))
+            .cbrt();
+
+            positions.get_mut(survivor).unwrap().0 = position;
+            velocities.get_mut(survivor).unwrap().0 = velocity;
+            masses.get_mut(survivor).unwrap().0 = total_mass;
+            radii.get_mut(survivor).unwrap().0 = radius;
+            lineages.insert(survivor, lineage).unwrap();
+
+            if let (Some(survivor), Some(absorbed)) = (ids.get(survivor), ids.get(absorbed)) {
+                announcements.push(tr!(
+                    "bodies-merged",
+                    absorbed = locale::body_name(absorbed),
+                    survivor = locale::body_name(survivor),
+                ));
+            }
+
+            entities.delete(absorbed).unwrap();
+        }
+    }
+}
+
+fn closest_approach(separation: Vector3<f64>, relative_velocity: Vector3<f64>, time: f64) -> f64 {
+    //! The closest two bodies came over the last `time` seconds, given how
+    //! far apart they are now and their relative velocity. Their paths are
+    //! taken to be straight over the frame
+
+    let displacement = relative_velocity * time;
+    let start = separation - displacement;
+    if displacement.magnitude2() == 0.0 {
+        return separation.magnitude();
+    }
+
+    // How far along the frame the bodies were closest, from 0 at its start
+    // to 1 now
+    let t = (-start.dot(displacement) / displacement.magnitude2()).clamp(0.0, 1.0);
+    (start + displacement * t).magnitude()
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::closest_approach;
+
+    #[test]
+    fn test_closest_approach_sweeps_the_frame() {
+        // Passed straight through each other, 10m apart either side
+        let distance = closest_approach(
+            Vector3::new(10.0, 1.0, 0.0),
+            Vector3::new(20.0, 0.0, 0.0),
+            1.0,
+        );
+        assert!((distance - 1.0).abs() < 1e-9);
+
+        // Still approaching, so closest now
+        let distance = closest_approach(
+            Vector3::new(-10.0, 0.0, 0.0),
+            Vector3::new(5.0, 0.0, 0.0),
+            1.0,
+        );
+        assert!((distance - 10.0).abs() < 1e-9);
+
+        // Without time passing only the current separation counts
+        assert_eq!(
+            5.0,
+            closest_approach(
+                Vector3::new(3.0, 4.0, 0.0),
+                Vector3::new(100.0, 0.0, 0.0),
+                0.0
+            )
+        );
+    }
+}
@@ -1,138 +1,52 @@
-use cgmath::Vector3;
-use instant::Duration;
-use log::{debug, info};
-use specs::{Component, Join, Read, ReadStorage, System, VecStorage};
+use serde::{Deserialize, Serialize};
 
-use crate::renderer::camera::{CameraPosition, CameraSpeed};
+use super::BodyType;
 
-// The position of an entity
-#[derive(Debug, Clone, Copy)]
-pub struct Position(pub Vector3<f64>);
-impl From<Vector3<f64>> for Position {
-    fn from(v: Vector3<f64>) -> Self {
-        Self(v)
-    }
-}
-impl Component for Position {
-    type Storage = VecStorage<Self>;
-}
+#[derive(Default, Copy, Clone)]
+pub struct PositionScaleFactor(pub f64);
 
-// The velocity of an entity
-#[derive(Debug, Clone, Copy)]
-pub struct Velocity(pub Vector3<f64>);
-impl From<Vector3<f64>> for Velocity {
-    fn from(v: Vector3<f64>) -> Self {
-        Self(v)
-    }
-}
-impl Component for Velocity {
-    type Storage = VecStorage<Self>;
-}
+/// The size bodies are drawn at, in scene units, when their radius is unknown
+const FALLBACK_DISPLAY_RADIUS: f64 = 1.0;
 
-// The mass of an entity
-#[derive(Debug, Clone, Copy)]
-pub struct Mass(pub f64);
-impl From<f64> for Mass {
-    fn from(m: f64) -> Self {
-        Self(m)
-    }
-}
-impl Component for Mass {
-    type Storage = VecStorage<Self>;
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScaleMode {
+    /// Bodies are drawn at their real size relative to their orbits
+    True,
+    /// Bodies are enlarged so they can be seen from a distance
+    Exaggerated,
 }
 
-// The Identifier and name of an entity
-#[derive(Debug, Clone)]
-pub struct Identifier {
-    id: String,
-    name: String,
+/// How the size of bodies is scaled in the render
+#[derive(Debug, Copy, Clone)]
+pub struct BodyScale {
+    pub mode: ScaleMode,
+    /// How many times larger than their real size bodies are drawn when
+    /// exaggerated
+    pub multiplier: f64,
 }
-impl Identifier {
-    pub fn new(id: String, name: String) -> Self {
-        Self { id, name }
-    }
-
-    pub fn get_id(&self) -> &str {
-        self.id.as_str()
-    }
-
-    pub fn get_name(&self) -> &str {
-        self.name.as_str()
-    }
-}
-impl Component for Identifier {
-    type Storage = VecStorage<Self>;
-}
-
-// The delta time container struct
-#[derive(Default, Copy, Clone)]
-pub struct DeltaTime(pub Duration);
-
-#[derive(Default, Copy, Clone)]
-pub struct TimeScale {
-    pub time_scale: f64,
-    pub total_time_elapsed: f64,
-    pub iterations: usize,
-}
-impl TimeScale {
-    pub fn new(total_time_elapsed: f64, iterations: usize) -> Self {
-        if iterations < 1 {
-            panic!("Iterations cannot be less than 1");
-        }
-
+impl Default for BodyScale {
+    fn default() -> Self {
         Self {
-            time_scale: total_time_elapsed / iterations as f64,
-            total_time_elapsed,
-            iterations,
+            mode: ScaleMode::Exaggerated,
+            multiplier: 1000.0,
         }
     }
-
-    pub fn from_max_time_per_iteration(
-        total_time_elapsed: f64,
-        max_time_per_iteration: f64,
-    ) -> Self {
-        let ierations = (total_time_elapsed / max_time_per_iteration).ceil() as usize;
-
-        Self::new(total_time_elapsed, ierations)
-    }
-}
-
-#[derive(Default, Copy, Clone)]
-pub struct GravitationalConstant(pub f64);
-
-#[derive(Default, Copy, Clone)]
-pub struct PositionScaleFactor(pub f64);
-
-pub struct Printer;
-impl Printer {
-    pub fn new() -> Self {
-        Self {}
-    }
 }
-impl<'a> System<'a> for Printer {
-    type SystemData = (
-        ReadStorage<'a, Identifier>,
-        ReadStorage<'a, Position>,
-        ReadStorage<'a, Velocity>,
-        ReadStorage<'a, Mass>,
-    );
+impl BodyScale {
+    pub fn display_radius(&self, radius: f64, body_type: BodyType, scale_factor: f64) -> f64 {
+        //! Returns the radius a body is drawn, and should be picked, at in
+        //! scene units
 
-    fn run(&mut self, (id, positions, velocities, mass): Self::SystemData) {
-        // Iterate over every entity
-        (&id, &positions, &velocities, &mass)
-            .join()
-            .for_each(|(id, pos, vel, mass)| {
-                // Print the entity's id, name, pos, vel and mass as an informational log
-                info!(
-                    "body{{id:{},name:{},pos:{:?},vel:{:?},mass:{:?}}}",
-                    id.id, id.name, pos.0, vel.0, mass.0,
-                );
+        if radius <= 0.0 {
+            return FALLBACK_DISPLAY_RADIUS;
+        }
 
-                // Print the entity's name, pos, vel and mass as a debug log
-                debug!(
-                    "{} {{\n\tpos: {:?},\n\tvel: {:?},\n\tmass: {:?}\n}}",
-                    id.name, pos.0, vel.0, mass.0,
-                );
-            });
+        let radius = radius / scale_factor;
+        match (self.mode, body_type) {
+            (ScaleMode::True, _) => radius,
+            // Stars are exaggerated less so they do not engulf the inner planets
+            (ScaleMode::Exaggerated, BodyType::Star) => radius * self.multiplier.sqrt(),
+            (ScaleMode::Exaggerated, _) => radius * self.multiplier,
+        }
     }
 }
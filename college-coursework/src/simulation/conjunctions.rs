@@ -0,0 +1,239 @@
+use std::{
+    f64::consts::{PI, TAU},
+    sync::mpsc,
+    thread,
+};
+
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::locale::tr;
+
+use super::{BodyType, InteractionFlags};
+
+/// Simulated time between steps of the search (in seconds)
+const SEARCH_STEP: f64 = 86400.0;
+
+/// How far ahead the search looks before giving up (in seconds)
+const MAX_SEARCH_TIME: f64 = 200.0 * 365.25 * 86400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignmentKind {
+    /// Both bodies are in the same direction from the Sun
+    Conjunction,
+    /// The bodies are on opposite sides of the Sun
+    Opposition,
+}
+impl AlignmentKind {
+    pub fn name(&self) -> String {
+        match self {
+            AlignmentKind::Conjunction => tr!("alignment-conjunction"),
+            AlignmentKind::Opposition => tr!("alignment-opposition"),
+        }
+    }
+
+    fn angle(&self) -> f64 {
+        //! The difference in heliocentric longitude of the bodies
+        match self {
+            AlignmentKind::Conjunction => 0.0,
+            AlignmentKind::Opposition => PI,
+        }
+    }
+}
+
+/// The state of a body, propagated without the Entity Component System
+#[derive(Debug, Clone, Copy)]
+pub struct SearchBody {
+    pub position: Vector3<f64>,
+    pub velocity: Vector3<f64>,
+    pub mass: f64,
+    pub body_type: BodyType,
+    pub flags: InteractionFlags,
+}
+
+/// The next time two bodies line up with the Sun
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    pub kind: AlignmentKind,
+    /// Time after the start of the search (in seconds)
+    pub time: f64,
+    /// The state of every body at the alignment, in the order they were given
+    pub bodies: Vec<SearchBody>,
+}
+
+/// A search for the next alignment, run on its own thread so the UI doesn't
+/// stall while it propagates
+pub struct AlignmentSearch {
+    receiver: mpsc::Receiver<Option<Alignment>>,
+}
+impl AlignmentSearch {
+    pub fn start(
+        bodies: Vec<SearchBody>,
+        first: usize,
+        second: usize,
+        gravitational_constant: f64,
+    ) -> Self {
+        //! Start searching for the next conjunction or opposition of the first
+        //! and second bodies
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(find_next_alignment(
+                bodies,
+                first,
+                second,
+                gravitational_constant,
+            ));
+        });
+
+        Self { receiver }
+    }
+
+    pub fn try_result(&self) -> Result<Option<Alignment>, mpsc::TryRecvError> {
+        //! The result of the search once it has finished, None if there is no
+        //! alignment within the search time
+        self.receiver.try_recv()
+    }
+}
+
+pub fn find_next_alignment(
+    mut bodies: Vec<SearchBody>,
+    first: usize,
+    second: usize,
+    gravitational_constant: f64,
+) -> Option<Alignment> {
+    //! Propagate the bodies forward until the first and second line up with
+    //! the Sun (the first star), returning when and how they line up
+
+    let sun = bodies
+        .iter()
+        .position(|body| matches!(body.body_type, BodyType::Star))?;
+
+    if first == second || first == sun || second == sun {
+        return None;
+    }
+
+    // How far past each alignment the bodies are, between -π and π
+    let offsets = |bodies: &[SearchBody]| {
+        let longitude = |body: &SearchBody| {
+            let relative = body.position - bodies[sun].position;
+            relative.y.atan2(relative.x)
+        };
+        let difference = longitude(&bodies[first]) - longitude(&bodies[second]);
+
+        [AlignmentKind::Conjunction, AlignmentKind::Opposition]
+            .map(|kind| (kind, (difference - kind.angle() + PI).rem_euclid(TAU) - PI))
+    };
+
+    let mut time = 0.0;
+    let mut previous_offsets = offsets(&bodies);
+    while time < MAX_SEARCH_TIME {
+        let previous_bodies = bodies.clone();
+        step(&mut bodies, gravitational_constant, SEARCH_STEP);
+        time += SEARCH_STEP;
+
+        let current_offsets = offsets(&bodies);
+        for ((kind, before), (_, after)) in previous_offsets.iter().zip(current_offsets.iter()) {
+            // The offset wraps around at ±π, which isn't an alignment. Bodies
+            // that start lined up aren't counted either
+            let crossed = before * after < 0.0 && (before - after).abs() < PI;
+            if !crossed {
+                continue;
+            }
+
+            // Estimate when the alignment happened between the steps and
+            // propagate the previous state to then
+            let fraction = before / (before - after);
+            let mut bodies = previous_bodies;
+            step(&mut bodies, gravitational_constant, SEARCH_STEP * fraction);
+
+            return Some(Alignment {
+                kind: *kind,
+                time: time - SEARCH_STEP * (1.0 - fraction),
+                bodies,
+            });
+        }
+
+        previous_offsets = current_offsets;
+    }
+
+    None
+}
+
+pub(super) fn step(bodies: &mut [SearchBody], gravitational_constant: f64, dt: f64) {
+    //! Advance the bodies by dt, in the same way the simulator does
+
+    let accelerations = bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| {
+            bodies
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i != *j)
+                .filter(|(_, other)| {
+                    let other_flags: InteractionFlags = other.body_type.into();
+                    body.flags.contains(other_flags)
+                })
+                .map(|(_, other)| {
+                    let r = other.position - body.position;
+                    gravitational_constant * other.mass / r.magnitude2() * r.normalize()
+                })
+                .fold(Vector3::zero(), |a, b| a + b)
+        })
+        .collect::<Vec<_>>();
+
+    for (body, acceleration) in bodies.iter_mut().zip(accelerations) {
+        body.velocity += acceleration * dt;
+        body.position += body.velocity * dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use cgmath::Vector3;
+
+    use super::{find_next_alignment, AlignmentKind, SearchBody};
+    use crate::{
+        simulation::{BodyType, InteractionFlags},
+        util::{AU, BIG_G},
+    };
+
+    #[test]
+    fn test_opposition_after_half_a_synodic_period() {
+        let sun_mass = 1.989e30;
+        let planet = |radius: f64| {
+            let speed = (BIG_G * sun_mass / radius).sqrt();
+            SearchBody {
+                position: Vector3::new(radius, 0.0, 0.0),
+                velocity: Vector3::new(0.0, speed, 0.0),
+                mass: 1.0,
+                body_type: BodyType::Planet,
+                flags: InteractionFlags::STAR,
+            }
+        };
+        let bodies = vec![
+            SearchBody {
+                position: Vector3::new(0.0, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 0.0, 0.0),
+                mass: sun_mass,
+                body_type: BodyType::Star,
+                flags: InteractionFlags::empty(),
+            },
+            planet(AU),
+            planet(1.5 * AU),
+        ];
+
+        // Both planets start lined up, so the next alignment is when the
+        // inner planet has gained half a lap
+        let angular_speed = |radius: f64| (BIG_G * sun_mass / radius.powi(3)).sqrt();
+        let expected = PI / (angular_speed(AU) - angular_speed(1.5 * AU));
+
+        let alignment = find_next_alignment(bodies, 1, 2, BIG_G).unwrap();
+
+        assert_eq!(AlignmentKind::Opposition, alignment.kind);
+        // The search steps a day at a time, so only has to be within a few days
+        assert!((alignment.time - expected).abs() < 3.0 * 86400.0);
+    }
+}
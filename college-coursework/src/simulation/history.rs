@@ -0,0 +1,286 @@
+use std::{collections::VecDeque, mem};
+
+use cgmath::{InnerSpace, Vector3};
+use log::debug;
+use specs::{
+    Component, Entities, Join, Read, ReadStorage, System, VecStorage, Write, WriteStorage,
+};
+
+use super::{GravitationalConstant, Mass, Position, Satellite, SimulationTime, Velocity};
+
+const MEBIBYTE: usize = 1024 * 1024;
+
+/// A point a body has passed through, with the state of its orbit there
+#[derive(Debug, Clone, Copy)]
+pub struct TrailPoint {
+    pub position: Vector3<f64>,
+    /// (in m/s)
+    pub speed: f64,
+    /// Orbital energy per unit mass relative to the body being orbited
+    /// (in J/kg)
+    pub specific_energy: f64,
+    /// Average acceleration since the previous point (in m/s^2)
+    pub acceleration: f64,
+}
+impl TrailPoint {
+    pub fn at(position: Vector3<f64>) -> Self {
+        //! Create a point with no orbital state recorded
+        Self {
+            position,
+            speed: 0.0,
+            specific_energy: 0.0,
+            acceleration: 0.0,
+        }
+    }
+}
+
+/// The points a body has passed through, oldest first
+#[derive(Debug, Clone, Default, Component)]
+#[storage(VecStorage)]
+pub struct Trail {
+    pub points: VecDeque<TrailPoint>,
+    // Simulated time the last point was recorded at
+    last_sample: Option<f64>,
+    // Velocity at the last point, used to find the acceleration
+    last_velocity: Option<Vector3<f64>>,
+}
+impl Trail {
+    pub fn from_points(points: impl IntoIterator<Item = TrailPoint>) -> Self {
+        //! Create a trail the body has already passed through, such as one
+        //! loaded from a save
+
+        Self {
+            points: points.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn clear(&mut self) {
+        //! Remove every point, starting the trail again from the body
+        self.points.clear();
+        self.last_sample = None;
+        self.last_velocity = None;
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        //! The number of bytes used by the points of the trail
+        self.points.len() * mem::size_of::<TrailPoint>()
+    }
+
+    fn decimate(&mut self) -> usize {
+        //! Remove every other point from the oldest half of the trail, so that
+        //! recent history stays detailed. Returns the number of points removed
+
+        let half = self.points.len() / 2;
+        let newer = self.points.split_off(half);
+        let older = mem::take(&mut self.points);
+
+        self.points = older.into_iter().step_by(2).chain(newer).collect();
+
+        half / 2
+    }
+}
+
+/// How often trails record the position of their body
+#[derive(Debug, Clone, Copy)]
+pub struct TrailSettings {
+    /// Simulated time between points (in seconds)
+    pub sample_interval: f64,
+}
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self {
+            sample_interval: 86400.0,
+        }
+    }
+}
+
+/// The most memory recorded history is allowed to use before the oldest data
+/// is thinned out
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryBudget {
+    /// Limit in bytes
+    pub limit: usize,
+}
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            limit: 64 * MEBIBYTE,
+        }
+    }
+}
+
+/// What has been pruned to stay within the memory budget, for diagnostics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PruneReport {
+    /// Bytes currently used by recorded history
+    pub used: usize,
+    /// Total number of trail points removed
+    pub trail_points_pruned: usize,
+    /// Number of times history has been pruned
+    pub prunes: usize,
+    /// Simulated time of the most recent prune (in seconds)
+    pub last_prune: Option<f64>,
+}
+
+/// Records the trail of each body and prunes the trails when they use more
+/// than the memory budget
+pub struct TrailRecorder;
+impl TrailRecorder {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for TrailRecorder {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Mass>,
+        ReadStorage<'a, Satellite>,
+        WriteStorage<'a, Trail>,
+        Read<'a, GravitationalConstant>,
+        Read<'a, SimulationTime>,
+        Read<'a, TrailSettings>,
+        Read<'a, MemoryBudget>,
+        Write<'a, PruneReport>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            positions,
+            velocities,
+            masses,
+            satellites,
+            mut trails,
+            gravitational_constant,
+            simulation_time,
+            settings,
+            budget,
+            mut report,
+        ): Self::SystemData,
+    ) {
+        // Bodies that aren't satellites are taken to orbit the most massive
+        // body, normally the Sun
+        let primary = (&entities, &masses)
+            .join()
+            .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(entity, _)| entity);
+
+        for (entity, position, velocity, satellite, trail) in (
+            &entities,
+            &positions,
+            &velocities,
+            (&satellites).maybe(),
+            &mut trails,
+        )
+            .join()
+        {
+            let elapsed = match trail.last_sample {
+                // Time has gone backwards, e.g. a save has been loaded, so the
+                // trail no longer leads to the body
+                Some(last_sample) if simulation_time.0 < last_sample => {
+                    trail.clear();
+                    None
+                }
+                Some(last_sample) if simulation_time.0 - last_sample < settings.sample_interval => {
+                    continue;
+                }
+                Some(last_sample) => Some(simulation_time.0 - last_sample),
+                None => None,
+            };
+
+            let acceleration = match (trail.last_velocity, elapsed) {
+                (Some(last_velocity), Some(elapsed)) if elapsed > 0.0 => {
+                    (velocity.0 - last_velocity).magnitude() / elapsed
+                }
+                _ => 0.0,
+            };
+
+            // Energy relative to the body being orbited, ignoring every other
+            // body
+            let orbited = satellite.map(|satellite| satellite.parent).or(primary);
+            let specific_energy =
+                match orbited
+                    .filter(|&orbited| orbited != entity)
+                    .and_then(|orbited| {
+                        Some((
+                            positions.get(orbited)?,
+                            velocities.get(orbited)?,
+                            masses.get(orbited)?,
+                        ))
+                    }) {
+                    Some((orbited_position, orbited_velocity, orbited_mass)) => {
+                        (velocity.0 - orbited_velocity.0).magnitude2() / 2.0
+                            - gravitational_constant.0 * orbited_mass.0
+                                / (position.0 - orbited_position.0).magnitude()
+                    }
+                    None => velocity.0.magnitude2() / 2.0,
+                };
+
+            trail.points.push_back(TrailPoint {
+                position: position.0,
+                speed: velocity.0.magnitude(),
+                specific_energy,
+                acceleration,
+            });
+            trail.last_sample = Some(simulation_time.0);
+            trail.last_velocity = Some(velocity.0);
+        }
+
+        let memory_usage =
+            |trails: &WriteStorage<Trail>| trails.join().map(Trail::memory_usage).sum::<usize>();
+
+        report.used = memory_usage(&trails);
+        if report.used <= budget.limit {
+            return;
+        }
+
+        // Thin out the oldest points of every trail until within the budget
+        let mut pruned = 0;
+        while report.used > budget.limit {
+            let removed = (&mut trails).join().map(Trail::decimate).sum::<usize>();
+            if removed == 0 {
+                break;
+            }
+
+            pruned += removed;
+            report.used = memory_usage(&trails);
+        }
+
+        debug!(
+            "Pruned {} trail points to stay within the memory budget",
+            pruned
+        );
+        report.trail_points_pruned += pruned;
+        report.prunes += 1;
+        report.last_prune = Some(simulation_time.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{Trail, TrailPoint};
+
+    #[test]
+    fn test_trail_decimation_keeps_recent_points() {
+        let mut trail = Trail::default();
+        trail
+            .points
+            .extend((0..10).map(|i| TrailPoint::at(Vector3::new(i as f64, 0.0, 0.0))));
+
+        assert_eq!(2, trail.decimate());
+        assert_eq!(
+            vec![0.0, 2.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0],
+            trail
+                .points
+                .iter()
+                .map(|point| point.position.x)
+                .collect::<Vec<_>>()
+        );
+    }
+}
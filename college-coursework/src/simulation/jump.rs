@@ -0,0 +1,95 @@
+use specs::{Read, System, Write};
+
+use super::{DeltaTime, SimulationTime, TimeScale};
+
+/// The longest simulated time a single iteration covers while jumping (in
+/// seconds), the same as when the time scale is set from the UI
+const MAX_TIME_PER_ITERATION: f64 = 86400.0;
+
+/// Real time it would take to cover the rest of a jump at its current speed
+/// (in seconds), so jumps slow down as they near the target
+const JUMP_TIME_CONSTANT: f64 = 0.5;
+
+/// Slowest and fastest a jump moves through simulated time (in seconds per
+/// second), 30 days and 50 years a second
+const MIN_JUMP_RATE: f64 = 30.0 * 86400.0;
+const MAX_JUMP_RATE: f64 = 50.0 * 365.25 * 86400.0;
+
+/// How close to the target a jump finishes (in seconds), as rounding can
+/// leave the last step just short of it
+const JUMP_TOLERANCE: f64 = 1.0;
+
+/// A simulated time the simulation is being fast-forwarded or rewound to
+#[derive(Default)]
+pub struct TimeJump {
+    /// (in seconds since the simulation started)
+    target: Option<f64>,
+    /// The time scale to go back to once the target is reached
+    resume: Option<TimeScale>,
+}
+impl TimeJump {
+    pub fn start(&mut self, target: f64, time_scale: &TimeScale) {
+        //! Start moving the simulation to a simulated time, going back to the
+        //! current time scale once it is there
+
+        if self.resume.is_none() {
+            self.resume = Some(*time_scale);
+        }
+        self.target = Some(target);
+    }
+
+    pub fn cancel(&mut self) -> Option<TimeScale> {
+        //! Stop jumping, returning the time scale from before the jump
+
+        self.target = None;
+        self.resume.take()
+    }
+
+    pub fn target(&self) -> Option<f64> {
+        self.target
+    }
+}
+
+/// Sets the time scale each step to move the simulation towards the target of
+/// a `TimeJump`, landing exactly on it. Runs backwards to rewind
+pub struct TimeJumper;
+impl TimeJumper {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for TimeJumper {
+    type SystemData = (
+        Write<'a, TimeJump>,
+        Write<'a, TimeScale>,
+        Read<'a, SimulationTime>,
+        Read<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, (mut jump, mut time_scale, simulation_time, dt): Self::SystemData) {
+        let target = match jump.target {
+            Some(target) => target,
+            None => return,
+        };
+
+        let remaining = target - simulation_time.0;
+        let dt = dt.0.as_secs_f64();
+        if dt <= 0.0 {
+            return;
+        }
+        if remaining.abs() < JUMP_TOLERANCE {
+            if let Some(resume) = jump.cancel() {
+                *time_scale = resume;
+            }
+            return;
+        }
+
+        // Never step past the target, so the last step lands on it
+        let rate = (remaining.abs() / JUMP_TIME_CONSTANT)
+            .clamp(MIN_JUMP_RATE, MAX_JUMP_RATE)
+            .min(remaining.abs() / dt);
+        let iterations = (rate * dt / MAX_TIME_PER_ITERATION).ceil().max(1.0) as usize;
+
+        *time_scale = TimeScale::new(rate.copysign(remaining), iterations);
+    }
+}
@@ -1,14 +1,51 @@
+mod accretion;
+mod autosave;
+mod collisions;
 mod components;
+mod conjunctions;
+mod history;
+mod jump;
+mod parallelism;
 mod planets;
+mod sandbox;
 mod saves;
+mod scenario;
 mod simulator;
-pub mod util;
+mod stopwatch;
 
-pub use saves::{SaveHandler, SimulationState};
+// The physics is shared with other frontends through the core library
+use solar_sim_core::kepler;
+pub use solar_sim_core::{
+    semi_major_axis, sidereal_period, synodic_period, util, BodyType, CloseEncounterSettings,
+    DeltaTime, DeterministicMode, GravitationalConstant, GravityOverride, GravityOverrides,
+    GroupTags, Identifier, InteractionFlags, InteractionHandler, Mass, OrbitClosureDetector,
+    OrbitTracker, Position, Printer, Radius, Satellite, SimulationTime, Simulator, TimeScale,
+    Velocity,
+};
 
-pub use components::{
-    DeltaTime, GravitationalConstant, Identifier, Mass, Position, PositionScaleFactor, Printer,
-    TimeScale, Velocity,
+pub use saves::{
+    quick_slot_time, DroppedFile, QuickSlotRequest, SaveError, SaveFormat, SaveHandler,
+    SimulationState, QUICK_SLOTS,
 };
+
+pub use scenario::{available_scenarios, Scenario, ScenarioError};
+
+pub use autosave::{AutosaveClock, AutosaveSettings, Autosaver, AUTOSAVE_DIR};
+
+pub use accretion::{MassChanger, MassRate};
+
+pub use collisions::{Ancestor, Collider, Lineage, Merge};
+
+pub use conjunctions::{Alignment, AlignmentKind, AlignmentSearch, SearchBody};
+
+pub use jump::{TimeJump, TimeJumper};
+
+pub use sandbox::{BranchRun, DivergenceSample, Perturbation};
+
+pub use history::{MemoryBudget, PruneReport, Trail, TrailPoint, TrailRecorder, TrailSettings};
+
+pub use components::{BodyScale, PositionScaleFactor, ScaleMode};
+pub use parallelism::ParallelismSettings;
 pub use planets::*;
-pub use simulator::{InstanceUpdater, Simulator};
+pub use simulator::InstanceUpdater;
+pub use stopwatch::Stopwatch;
@@ -0,0 +1,81 @@
+use std::{fs, num::NonZeroUsize, sync::Arc, thread};
+
+use log::{info, warn};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{args::Args, config::CONFIG_DIR};
+
+const PARALLELISM_FILE: &'static str = "parallelism.toml";
+
+/// How many threads the simulation is allowed to use
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParallelismSettings {
+    /// The number of threads in the thread pool, every core if not set
+    pub threads: Option<usize>,
+    /// Leave one core free for rendering and the UI
+    pub reserve_core: bool,
+}
+impl Default for ParallelismSettings {
+    fn default() -> Self {
+        Self {
+            threads: None,
+            reserve_core: false,
+        }
+    }
+}
+impl ParallelismSettings {
+    pub fn load(args: &Args) -> Self {
+        //! Load the parallelism settings from the config directory, with any
+        //! options given on the command line taking priority
+
+        let path = CONFIG_DIR.join(PARALLELISM_FILE);
+
+        let mut settings: Self = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid parallelism settings in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+
+        if args.threads.is_some() {
+            settings.threads = args.threads;
+        }
+        settings.reserve_core |= args.reserve_core;
+
+        settings
+    }
+
+    pub fn thread_count(&self) -> usize {
+        //! Returns the number of threads the thread pool should have, always
+        //! at least one
+
+        let threads = self.threads.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(NonZeroUsize::get)
+                .unwrap_or(1)
+        });
+
+        if self.reserve_core {
+            threads.saturating_sub(1).max(1)
+        } else {
+            threads.max(1)
+        }
+    }
+
+    pub fn build_thread_pool(&self) -> Result<Arc<ThreadPool>, ThreadPoolBuildError> {
+        //! Build the thread pool the systems, and the parallel joins inside
+        //! them, are run on
+
+        let threads = self.thread_count();
+        info!("Using {} simulation threads", threads);
+
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("simulation-{}", i))
+            .build()
+            .map(Arc::new)
+    }
+}
@@ -1,46 +1,11 @@
 use cgmath::Vector3;
-use specs::{Builder, Component, Entity, VecStorage, World, WorldExt};
+use specs::{Builder, Entity, World, WorldExt};
 use serde::Deserialize;
 use std::collections::HashMap;
 
-use super::{Identifier, Mass, Position, Velocity};
+use super::{BodyType, Identifier, Mass, Position, Radius, Velocity};
 use crate::util::Vec3;
 
-use bitflags::bitflags;
-
-#[derive(Debug, Copy, Clone)]
-pub enum BodyType {
-    Star,
-    Planet,
-}
-
-bitflags! {
-    pub struct InteractionFlags: u32 {
-        const STAR = 1 << BodyType::Star as u32;
-        const PLANET = 1 << BodyType::Planet as u32;
-    }
-}
-impl From<BodyType> for InteractionFlags {
-    fn from(body_type: BodyType) -> Self {
-        InteractionFlags::from_bits(1 << body_type as u32).unwrap()
-    }
-}
-
-/// Contains information about what body type an entity is
-// And what body types affect it (For optimisation)
-#[derive(Component)]
-#[storage(VecStorage)]
-pub struct InteractionHandler {
-    pub flags: InteractionFlags,
-    pub body_type: BodyType,
-}
-impl InteractionHandler {
-    pub fn new(flags: InteractionFlags, body_type: BodyType) -> Self {
-        //! Create a new interaction handler
-        Self { flags, body_type }
-    }
-}
-
 // A structure to contain the information about an orbital body
 pub struct OrbitalBody {
     id: &'static str,
@@ -48,7 +13,11 @@ pub struct OrbitalBody {
     initial_pos: [f64; 3],
     initial_vel: [f64; 3],
     colour: [f32; 4],
+    // None until the textures listed in assets/textures/README.md are added,
+    // so the body is given a generated surface rather than failing to load
+    texture: Option<&'static str>,
     mass: f64,
+    radius: f64,
     body_type: BodyType,
 }
 impl OrbitalBody {
@@ -64,6 +33,10 @@ impl OrbitalBody {
         Mass::from(self.mass)
     }
 
+    pub fn get_radius(&self) -> Radius {
+        Radius(self.radius)
+    }
+
     pub fn get_identifier(&self) -> Identifier {
         Identifier::new(self.id.to_string(), self.name.to_string())
     }
@@ -72,6 +45,14 @@ impl OrbitalBody {
         self.colour
     }
 
+    pub fn get_texture(&self) -> Option<&'static str> {
+        self.texture
+    }
+
+    pub fn get_body_type(&self) -> BodyType {
+        self.body_type
+    }
+
     pub fn register_entity(&self, world: &mut World) -> Entity {
         // Register the entity into the ECS world
         world
@@ -80,6 +61,7 @@ impl OrbitalBody {
             .with(self.get_pos())
             .with(self.get_vel())
             .with(self.get_mass())
+            .with(self.get_radius())
             .build()
     }
 }
@@ -93,7 +75,9 @@ pub const SUN: OrbitalBody = OrbitalBody {
     initial_pos: [0.0, 0.0, 0.0],
     initial_vel: [0.0, 0.0, 0.0],
     colour: [252.0 / 255.0, 229.0 / 255.0, 112.0 / 255.0, 1.0],
+    texture: None,
     mass: 1.989e30,
+    radius: 695.700e6,
     body_type: BodyType::Star,
 };
 
@@ -103,7 +87,9 @@ pub const PLANET_MERCURY: OrbitalBody = OrbitalBody {
     initial_pos: [57.909e9, 0.0, 0.0],
     initial_vel: [0.0, 47.36e3, 0.0],
     colour: [0.7, 0.7, 0.7, 1.0],
+    texture: None,
     mass: 0.33011e24,
+    radius: 2.4397e6,
     body_type: BodyType::Planet,
 };
 
@@ -113,7 +99,9 @@ pub const PLANET_VENUS: OrbitalBody = OrbitalBody {
     initial_pos: [108.209e9, 0.0, 0.0],
     initial_vel: [0.0, 35.02e3, 0.0],
     colour: [0.9, 0.9, 0.9, 1.0],
+    texture: None,
     mass: 4.8675e24,
+    radius: 6.0518e6,
     body_type: BodyType::Planet,
 };
 
@@ -123,7 +111,9 @@ pub const PLANET_EARTH: OrbitalBody = OrbitalBody {
     initial_pos: [149.596e9, 0.0, 0.0],
     initial_vel: [0.0, 29.78e3, 0.0],
     colour: [0.0, 1.0, 0.0, 1.0],
+    texture: None,
     mass: 5.9724e24,
+    radius: 6.371e6,
     body_type: BodyType::Planet,
 };
 
@@ -133,7 +123,9 @@ pub const PLANET_MARS: OrbitalBody = OrbitalBody {
     initial_pos: [227.923e9, 0.0, 0.0],
     initial_vel: [0.0, 24.07e3, 0.0],
     colour: [1.0, 0.0, 0.0, 1.0],
+    texture: None,
     mass: 0.64171e24,
+    radius: 3.3895e6,
     body_type: BodyType::Planet,
 };
 
@@ -143,7 +135,9 @@ pub const PLANET_JUPITER: OrbitalBody = OrbitalBody {
     initial_pos: [778.570e9, 0.0, 0.0],
     initial_vel: [0.0, 13e3, 0.0],
     colour: [0.605, 0.428, 0.299, 1.0],
+    texture: None,
     mass: 1898.19e24,
+    radius: 69.911e6,
     body_type: BodyType::Planet,
 };
 
@@ -153,7 +147,9 @@ pub const PLANET_SATURN: OrbitalBody = OrbitalBody {
     initial_pos: [1433.529e9, 0.0, 0.0],
     initial_vel: [0.0, 9.68e3, 0.0],
     colour: [0.605, 0.428, 0.399, 1.0],
+    texture: None,
     mass: 568.34e24,
+    radius: 58.232e6,
     body_type: BodyType::Planet,
 };
 
@@ -163,7 +159,9 @@ pub const PLANET_URANUS: OrbitalBody = OrbitalBody {
     initial_pos: [2872.463e9, 0.0, 0.0],
     initial_vel: [0.0, 6.80e3, 0.0],
     colour: [0.0, 0.5, 1.0, 1.0],
+    texture: None,
     mass: 86.813e24,
+    radius: 25.362e6,
     body_type: BodyType::Planet,
 };
 
@@ -173,10 +171,26 @@ pub const PLANET_NEPTUNE: OrbitalBody = OrbitalBody {
     initial_pos: [4495.060e9, 0.0, 0.0],
     initial_vel: [0.0, 5.43e3, 0.0],
     colour: [0.0, 0.0, 1.0, 1.0],
+    texture: None,
     mass: 102.413e24,
+    radius: 24.622e6,
     body_type: BodyType::Planet,
 };
 
+// The Moon is placed relative to the Earth, with the Earth's velocity plus its
+// orbital velocity around the Earth
+pub const MOON: OrbitalBody = OrbitalBody {
+    id: "moon",
+    name: "Moon",
+    initial_pos: [149.596e9 + 384.4e6, 0.0, 0.0],
+    initial_vel: [0.0, 29.78e3 + 1.022e3, 0.0],
+    colour: [0.8, 0.8, 0.8, 1.0],
+    texture: None,
+    mass: 0.07346e24,
+    radius: 1.7374e6,
+    body_type: BodyType::Moon,
+};
+
 pub fn planets() -> Vec<OrbitalBody> {
     vec![
         PLANET_MERCURY,
@@ -190,6 +204,11 @@ pub fn planets() -> Vec<OrbitalBody> {
     ]
 }
 
+// Each moon paired with the id of the planet it orbits
+pub fn moons() -> Vec<(&'static str, OrbitalBody)> {
+    vec![("earth", MOON)]
+}
+
 const PLANETS_TOML: &'static str = include_str!("../../assets/planets/planets.toml");
 
 pub struct RawOribitalBody {
@@ -0,0 +1,209 @@
+use std::{sync::mpsc, thread};
+
+use cgmath::InnerSpace;
+
+use super::conjunctions::{step, SearchBody};
+
+/// Simulated time between steps of both branches (in seconds)
+const BRANCH_STEP: f64 = 86400.0;
+
+/// Number of steps between each sample of how far the branches have drifted
+/// apart
+const STEPS_PER_SAMPLE: usize = 5;
+
+/// A change made to a body in the sandbox branch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perturbation {
+    /// Whether the body is left out of the sandbox entirely
+    pub removed: bool,
+    pub mass_multiplier: f64,
+}
+impl Default for Perturbation {
+    fn default() -> Self {
+        Self {
+            removed: false,
+            mass_multiplier: 1.0,
+        }
+    }
+}
+
+/// How far apart the tracked bodies are in the two branches at a time
+#[derive(Debug, Clone)]
+pub struct DivergenceSample {
+    /// Time after the branches were made (in seconds)
+    pub time: f64,
+    /// Distance between the positions of each tracked body in the two
+    /// branches (in metres), in the order they were tracked
+    pub distances: Vec<f64>,
+}
+
+/// The simulation and a perturbed sandbox copy of it, propagated side by side
+/// on their own thread. Dropping the run stops it
+pub struct BranchRun {
+    receiver: mpsc::Receiver<DivergenceSample>,
+}
+impl BranchRun {
+    pub fn start(
+        bodies: Vec<SearchBody>,
+        perturbations: &[Perturbation],
+        tracked: Vec<usize>,
+        gravitational_constant: f64,
+        duration: f64,
+    ) -> Self {
+        //! Branch the bodies, applying a perturbation to each, and start
+        //! propagating both branches for a simulated duration (in seconds)
+
+        let sandbox = perturb(&bodies, perturbations);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            run_branches(
+                bodies,
+                sandbox,
+                &tracked,
+                gravitational_constant,
+                duration,
+                // Stop once the run has been dropped
+                |sample| sender.send(sample).is_ok(),
+            );
+        });
+
+        Self { receiver }
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = DivergenceSample> + '_ {
+        //! The samples taken since this was last called
+        self.receiver.try_iter()
+    }
+}
+
+fn perturb(bodies: &[SearchBody], perturbations: &[Perturbation]) -> Vec<Option<SearchBody>> {
+    //! The sandbox copy of the bodies, None where a body has been removed
+
+    bodies
+        .iter()
+        .zip(perturbations)
+        .map(|(body, perturbation)| {
+            (!perturbation.removed).then_some(SearchBody {
+                mass: body.mass * perturbation.mass_multiplier,
+                ..*body
+            })
+        })
+        .collect()
+}
+
+fn run_branches(
+    mut baseline: Vec<SearchBody>,
+    sandbox: Vec<Option<SearchBody>>,
+    tracked: &[usize],
+    gravitational_constant: f64,
+    duration: f64,
+    mut on_sample: impl FnMut(DivergenceSample) -> bool,
+) {
+    //! Propagate both branches, passing a sample to `on_sample` every few
+    //! steps until the duration is reached or it returns false. Tracked
+    //! bodies that were removed from the sandbox are left out of the samples
+
+    // Where each body of the baseline is in the sandbox, which has none of
+    // the removed bodies
+    let mut sandbox_indices = Vec::with_capacity(sandbox.len());
+    let mut sandbox_bodies = Vec::with_capacity(sandbox.len());
+    for body in sandbox {
+        sandbox_indices.push(body.map(|_| sandbox_bodies.len()));
+        sandbox_bodies.extend(body);
+    }
+    let tracked = tracked
+        .iter()
+        .filter_map(|&i| Some((i, sandbox_indices.get(i).copied().flatten()?)))
+        .collect::<Vec<_>>();
+
+    let mut time = 0.0;
+    let mut steps = 0;
+    loop {
+        if steps % STEPS_PER_SAMPLE == 0 || time >= duration {
+            let sample = DivergenceSample {
+                time,
+                distances: tracked
+                    .iter()
+                    .map(|&(baseline_index, sandbox_index)| {
+                        (baseline[baseline_index].position - sandbox_bodies[sandbox_index].position)
+                            .magnitude()
+                    })
+                    .collect(),
+            };
+            if !on_sample(sample) || time >= duration {
+                return;
+            }
+        }
+
+        // The last step is shortened to end exactly on the duration
+        let next = (time + BRANCH_STEP).min(duration);
+        step(&mut baseline, gravitational_constant, next - time);
+        step(&mut sandbox_bodies, gravitational_constant, next - time);
+        time = next;
+        steps += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{perturb, run_branches, Perturbation, SearchBody};
+    use crate::{
+        simulation::{BodyType, InteractionFlags},
+        util::{AU, BIG_G},
+    };
+
+    #[test]
+    fn test_branches_diverge_only_when_perturbed() {
+        let sun_mass = 1.989e30;
+        let bodies = vec![
+            SearchBody {
+                position: Vector3::new(0.0, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 0.0, 0.0),
+                mass: sun_mass,
+                body_type: BodyType::Star,
+                flags: InteractionFlags::empty(),
+            },
+            SearchBody {
+                position: Vector3::new(AU, 0.0, 0.0),
+                velocity: Vector3::new(0.0, (BIG_G * sun_mass / AU).sqrt(), 0.0),
+                mass: 5.972e24,
+                body_type: BodyType::Planet,
+                flags: InteractionFlags::STAR,
+            },
+        ];
+        let year = 365.25 * 86400.0;
+
+        let final_distance = |perturbations: &[Perturbation]| {
+            let mut last = None;
+            run_branches(
+                bodies.clone(),
+                perturb(&bodies, perturbations),
+                &[1],
+                BIG_G,
+                year,
+                |sample| {
+                    last = Some(sample);
+                    true
+                },
+            );
+
+            let last = last.unwrap();
+            assert_eq!(year, last.time);
+            last.distances[0]
+        };
+
+        assert_eq!(0.0, final_distance(&[Perturbation::default(); 2]));
+
+        // Without the Sun the planet carries on in a straight line
+        let without_sun = [
+            Perturbation {
+                removed: true,
+                ..Perturbation::default()
+            },
+            Perturbation::default(),
+        ];
+        assert!(final_distance(&without_sun) > AU);
+    }
+}
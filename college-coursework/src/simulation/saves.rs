@@ -1,81 +1,300 @@
 use std::{
     fs,
-    io::{Read as _, Write as _},
+    path::{Path, PathBuf},
     sync::{mpsc, Arc},
 };
 
 use cgmath::{Quaternion, Vector3, Zero};
-use chrono::Utc;
-use dialog::DialogBox;
+use chrono::{DateTime, Local, Utc};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use specs::{
-    Builder, Entities, Join, Read, ReadExpect, ReadStorage, World, WorldExt, Write, WriteStorage,
+    Builder, Entities, Join, Read, ReadExpect, ReadStorage, World, WorldExt, Write, WriteExpect,
+    WriteStorage,
 };
+use tokio::runtime::Handle;
 
 use crate::{
-    models::sphere::Icosphere,
+    accessibility::{AccessibilitySettings, ColourPalette},
+    locale::{self, tr},
+    log::LOG_DIR,
+    models::cache::{ModelCache, Surface},
     panel::PlanetWindowShown,
     renderer::{
-        camera::{CameraPosition, CameraSpeed},
-        components::{PlanetColour, RenderModel},
+        atmosphere::Atmosphere,
+        camera::{CameraControllerType, CameraPosition, CameraRotation, CameraSpeed},
+        components::{
+            self, BodyStyle, CameraCenter, PlanetColour, PlanetTexture, RenderModel, SphereDetail,
+        },
         instance::Instance,
+        rings::Rings,
     },
+    stats::UsageStats,
+    toasts::Toasts,
 };
 
 use super::{
-    BodyType, GravitationalConstant, Identifier, InteractionFlags, InteractionHandler, Mass,
-    Position, TimeScale, Velocity,
+    scenario::{Scenario, ScenarioError},
+    BodyType, GravitationalConstant, Identifier, InteractionFlags, InteractionHandler, Lineage,
+    Mass, MassRate, OrbitTracker, Position, Radius, Satellite, TimeScale, Trail, TrailPoint,
+    Velocity,
 };
 
+/// How detailed the sphere of a body is when the save doesn't say
+pub(super) const DEFAULT_SPHERE_DETAIL: usize = 3;
+
+/// Number of quick save slots
+pub const QUICK_SLOTS: usize = 4;
+
+lazy_static! {
+    /// Where the quick save slots are written, next to the logs
+    static ref QUICK_SAVE_DIR: PathBuf = LOG_DIR
+        .parent()
+        .map_or_else(|| LOG_DIR.join("quicksaves"), |dir| dir.join("quicksaves"));
+}
+
+/// Set to quick save or quick load the selected slot, from the hotkeys or
+/// anywhere else with access to the world
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuickSlotRequest {
+    pub save: bool,
+    pub load: bool,
+}
+
+/// A save or scenario dropped onto the window, waiting for the user to
+/// confirm loading it
+#[derive(Debug, Clone, Default)]
+pub struct DroppedFile(pub Option<PathBuf>);
+
+fn quick_slot_path(slot: usize) -> PathBuf {
+    //! The file a quick save slot is kept in, numbered from 1
+
+    QUICK_SAVE_DIR.join(format!("slot-{}.{}", slot, SaveFormat::Toml.extension()))
+}
+
+pub fn quick_slot_time(slot: usize) -> Option<DateTime<Local>> {
+    //! When a quick save slot was last saved to, if it has been
+
+    fs::metadata(quick_slot_path(slot))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(DateTime::from)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct TimeState {
-    date_time: String,
-    time_scale: f64,
+    pub(super) date_time: String,
+    pub(super) time_scale: f64,
+    // Older saves don't have the number of iterations, which is then chosen
+    // from the time scale
+    #[serde(default)]
+    pub(super) iterations: Option<usize>,
+    #[serde(default)]
+    pub(super) paused: bool,
+}
+impl TimeState {
+    fn new(time_scale: &TimeScale) -> Self {
+        Self {
+            date_time: Utc::now().to_rfc3339(),
+            time_scale: time_scale.total_time_elapsed,
+            iterations: Some(time_scale.iterations),
+            paused: time_scale.paused,
+        }
+    }
+
+    fn to_time_scale(&self) -> TimeScale {
+        //! The time scale the state was saved with
+
+        let mut time_scale = match self.iterations {
+            Some(iterations) if iterations >= 1 => TimeScale::new(self.time_scale, iterations),
+            _ => TimeScale::from_max_time_per_iteration(self.time_scale, 86400.0),
+        };
+        time_scale.paused = self.paused;
+
+        time_scale
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ConstantState {
-    gravitational_constant: f64,
+    pub(super) gravitational_constant: f64,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CameraState {
     #[serde(rename = "position")]
-    camera_position: [f32; 3],
+    pub(super) camera_position: [f32; 3],
     #[serde(rename = "speed")]
-    camera_speed: f32,
+    pub(super) camera_speed: f32,
+    // Older saves don't have the rotation or controller, which are then left
+    // as they are. The rotation is a quaternion, scalar part last
+    #[serde(default)]
+    pub(super) rotation: Option<[f32; 4]>,
+    #[serde(default)]
+    pub(super) controller: Option<CameraControllerType>,
+    // Tables have to come after values in TOML, so this must be last
+    #[serde(default)]
+    pub(super) center: Option<CameraCenterState>,
 }
 
+/// The body the camera is centred on, and how it follows it
 #[derive(Serialize, Deserialize)]
-pub struct PlanetState {
-    id: String,
-    name: String,
+pub struct CameraCenterState {
+    body: String,
+    following: bool,
+    offset: [f32; 3],
+    smoothing: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AtmosphereState {
+    colour: [f32; 3],
+    thickness: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RingsState {
+    inner_radius: f32,
+    outer_radius: f32,
+    tilt: f32,
+    colour: [f32; 3],
+    opacity: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TrailPointState {
     position: [f64; 3],
-    velocity: [f64; 3],
-    mass: f64,
-    colour: [f32; 4],
+    speed: f64,
+    specific_energy: f64,
+    acceleration: f64,
+}
+impl From<&TrailPoint> for TrailPointState {
+    fn from(point: &TrailPoint) -> Self {
+        Self {
+            position: point.position.into(),
+            speed: point.speed,
+            specific_energy: point.specific_energy,
+            acceleration: point.acceleration,
+        }
+    }
+}
+impl From<&TrailPointState> for TrailPoint {
+    fn from(state: &TrailPointState) -> Self {
+        Self {
+            position: state.position.into(),
+            speed: state.speed,
+            specific_energy: state.specific_energy,
+            acceleration: state.acceleration,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlanetState {
+    pub(super) id: String,
+    pub(super) name: String,
+    pub(super) position: [f64; 3],
+    pub(super) velocity: [f64; 3],
+    pub(super) mass: f64,
+    // The rate the body gains or loses mass (in kg/s)
+    #[serde(default)]
+    pub(super) mass_rate: f64,
+    pub(super) colour: [f32; 4],
+    // The asset path of the body's surface texture
+    #[serde(default)]
+    pub(super) texture: Option<String>,
+    #[serde(default)]
+    pub(super) radius: f64,
+    // The id of the body this body is a satellite of
+    #[serde(default)]
+    pub(super) parent: Option<String>,
+    // The number of times the sphere of the body is subdivided
+    #[serde(default = "default_sphere_detail")]
+    pub(super) detail: usize,
+    // Whether the window of the body is open
+    #[serde(default)]
+    pub(super) window_shown: bool,
+    // The colour of the body's trail, the body's own colour if not set
+    #[serde(default)]
+    pub(super) trail_colour: Option<[f32; 3]>,
+    #[serde(default = "default_shown")]
+    pub(super) label_shown: bool,
+    #[serde(default = "default_shown")]
+    pub(super) arrows_shown: bool,
+    // Tables have to come after values in TOML, so these must be last
+    #[serde(default)]
+    pub(super) lineage: Lineage,
+    // Older saves don't have the atmosphere, which is then the one the body
+    // starts with
+    #[serde(default)]
+    pub(super) atmosphere: Option<AtmosphereState>,
+    // Older saves don't have the rings either
+    #[serde(default)]
+    pub(super) rings: Option<RingsState>,
+    // An empty trail would be written as a value, which can't follow the
+    // tables above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) trail: Vec<TrailPointState>,
+}
+
+fn default_sphere_detail() -> usize {
+    DEFAULT_SPHERE_DETAIL
+}
+
+fn default_shown() -> bool {
+    true
 }
 
 pub type PlanetsState = Vec<PlanetState>;
 
+#[derive(Serialize, Deserialize)]
+pub struct DisplayState {
+    pub(super) palette: ColourPalette,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SimulationState {
     #[serde(rename = "time")]
-    time_state: TimeState,
+    pub(super) time_state: TimeState,
     #[serde(rename = "constants")]
-    constant_state: ConstantState,
+    pub(super) constant_state: ConstantState,
     #[serde(rename = "camera")]
-    camera_state: CameraState,
+    pub(super) camera_state: CameraState,
+    // Older saves and scenarios don't have the display, which is then left
+    // as it is
+    #[serde(rename = "display", default)]
+    pub(super) display_state: Option<DisplayState>,
 
     #[serde(rename = "planet")]
-    planet_state: PlanetsState,
+    pub(super) planet_state: PlanetsState,
 }
 impl SimulationState {
+    pub fn load_file(path: &Path) -> Result<Self, SaveError> {
+        //! Read a saved simulation, in the format of its extension
+
+        let format =
+            SaveFormat::from_path(path).ok_or_else(|| SaveError::FormatError(path.to_owned()))?;
+
+        format.deserialize(&fs::read(path)?)
+    }
+
+    pub fn save_file(&self, path: &Path) -> Result<(), SaveError> {
+        //! Write the simulation to a file, in the format of its extension
+
+        let format =
+            SaveFormat::from_path(path).ok_or_else(|| SaveError::FormatError(path.to_owned()))?;
+
+        Ok(fs::write(path, format.serialize(self)?)?)
+    }
+
     pub fn serialize_from_world(world: &mut World) -> Self {
         world.exec(
             |(
                 camera_position,
+                camera_rotation,
                 camera_speed,
+                camera_controller,
+                camera_center,
                 gravitational_constant,
                 time_scale,
                 planet_ids,
@@ -83,9 +302,24 @@ impl SimulationState {
                 planet_positions,
                 planet_velocities,
                 planet_masses,
+                planet_mass_rates,
+                planet_radii,
+                planet_lineages,
+                planet_satellites,
+                planet_textures,
+                planet_details,
+                planet_window_shown,
+                planet_atmospheres,
+                planet_rings,
+                planet_trails,
+                planet_styles,
+                accessibility_settings,
             ): (
                 Read<CameraPosition>,
+                Read<CameraRotation>,
                 Read<CameraSpeed>,
+                Read<CameraControllerType>,
+                ReadExpect<CameraCenter>,
                 Read<GravitationalConstant>,
                 Read<TimeScale>,
                 ReadStorage<Identifier>,
@@ -93,6 +327,18 @@ impl SimulationState {
                 ReadStorage<Position>,
                 ReadStorage<Velocity>,
                 ReadStorage<Mass>,
+                ReadStorage<MassRate>,
+                ReadStorage<Radius>,
+                ReadStorage<Lineage>,
+                ReadStorage<Satellite>,
+                ReadStorage<PlanetTexture>,
+                ReadStorage<SphereDetail>,
+                ReadStorage<PlanetWindowShown>,
+                ReadStorage<Atmosphere>,
+                ReadStorage<Rings>,
+                ReadStorage<Trail>,
+                ReadStorage<BodyStyle>,
+                Read<AccessibilitySettings>,
             )| {
                 let planet_state = (
                     &planet_ids,
@@ -100,30 +346,98 @@ impl SimulationState {
                     &planet_positions,
                     &planet_velocities,
                     &planet_masses,
+                    (&planet_mass_rates).maybe(),
+                    (&planet_radii).maybe(),
+                    (&planet_lineages).maybe(),
+                    (&planet_satellites).maybe(),
+                    (&planet_textures).maybe(),
+                    (&planet_details).maybe(),
+                    (&planet_window_shown).maybe(),
+                    (&planet_atmospheres).maybe(),
+                    (&planet_rings).maybe(),
+                    (&planet_trails).maybe(),
+                    (&planet_styles).maybe(),
                 )
                     .join()
-                    .map(|(id, colour, position, velocity, mass)| PlanetState {
-                        id: id.get_id().to_string(),
-                        name: id.get_name().to_string(),
-                        position: position.0.into(),
-                        velocity: velocity.0.into(),
-                        mass: mass.0,
-                        colour: colour.0,
-                    })
+                    .map(
+                        |(
+                            id,
+                            colour,
+                            position,
+                            velocity,
+                            mass,
+                            mass_rate,
+                            radius,
+                            lineage,
+                            satellite,
+                            texture,
+                            detail,
+                            window_shown,
+                            atmosphere,
+                            rings,
+                            trail,
+                            style,
+                        )| {
+                            let style = style.copied().unwrap_or_default();
+                            PlanetState {
+                                id: id.get_id().to_string(),
+                                name: id.get_name().to_string(),
+                                position: position.0.into(),
+                                velocity: velocity.0.into(),
+                                mass: mass.0,
+                                mass_rate: mass_rate.map_or(0.0, |mass_rate| mass_rate.0),
+                                colour: colour.0,
+                                texture: texture.map(|texture| texture.0.clone()),
+                                radius: radius.map_or(0.0, |radius| radius.0),
+                                parent: satellite
+                                    .and_then(|satellite| planet_ids.get(satellite.parent))
+                                    .map(|parent| parent.get_id().to_string()),
+                                detail: detail.map_or(DEFAULT_SPHERE_DETAIL, |detail| detail.0),
+                                window_shown: window_shown
+                                    .map_or(false, |window_shown| window_shown.0),
+                                trail_colour: style.trail_colour,
+                                label_shown: style.label_shown,
+                                arrows_shown: style.arrows_shown,
+                                lineage: lineage.cloned().unwrap_or_default(),
+                                atmosphere: atmosphere.map(|atmosphere| AtmosphereState {
+                                    colour: atmosphere.colour,
+                                    thickness: atmosphere.thickness,
+                                }),
+                                rings: rings.map(|rings| RingsState {
+                                    inner_radius: rings.inner_radius,
+                                    outer_radius: rings.outer_radius,
+                                    tilt: rings.tilt,
+                                    colour: rings.colour,
+                                    opacity: rings.opacity,
+                                }),
+                                trail: trail.map_or_else(Vec::new, |trail| {
+                                    trail.points.iter().map(TrailPointState::from).collect()
+                                }),
+                            }
+                        },
+                    )
                     .collect();
 
                 Self {
-                    time_state: TimeState {
-                        date_time: Utc::now().to_rfc3339(),
-                        time_scale: time_scale.total_time_elapsed,
-                    },
+                    time_state: TimeState::new(&time_scale),
                     constant_state: ConstantState {
                         gravitational_constant: gravitational_constant.0,
                     },
                     camera_state: CameraState {
                         camera_position: camera_position.0.into(),
                         camera_speed: camera_speed.0,
+                        rotation: Some(camera_rotation.0.into()),
+                        controller: Some(*camera_controller),
+                        center: Some(CameraCenterState {
+                            body: camera_center.get_body().get_id().to_string(),
+                            following: camera_center.following,
+                            offset: camera_center.offset.into(),
+                            smoothing: camera_center.smoothing,
+                        }),
                     },
+                    display_state: Some(DisplayState {
+                        palette: accessibility_settings.palette,
+                    }),
                     planet_state,
                 }
             },
@@ -131,21 +445,33 @@ impl SimulationState {
     }
 
     pub fn deserialize_to_world(self, world: &mut World) {
-        world.exec(
+        // The sun, if it was saved with a different colour and so needs its
+        // model making again
+        let recoloured_sun = world.exec(
             |(
                 mut camera_position,
+                mut camera_rotation,
                 mut camera_speed,
+                mut camera_controller,
                 mut gravitational_constant,
                 mut time_scale,
                 planet_ids,
-                planet_colours,
-                planet_positions,
-                planet_velocities,
-                planet_masses,
+                mut planet_colours,
+                mut planet_positions,
+                mut planet_velocities,
+                mut planet_masses,
+                mut planet_mass_rates,
+                mut planet_radii,
+                mut planet_window_shown,
+                mut planet_trails,
+                mut planet_styles,
+                mut accessibility_settings,
                 entities,
             ): (
                 Write<CameraPosition>,
+                Write<CameraRotation>,
                 Write<CameraSpeed>,
+                Write<CameraControllerType>,
                 Write<GravitationalConstant>,
                 Write<TimeScale>,
                 WriteStorage<Identifier>,
@@ -153,15 +479,30 @@ impl SimulationState {
                 WriteStorage<Position>,
                 WriteStorage<Velocity>,
                 WriteStorage<Mass>,
+                WriteStorage<MassRate>,
+                WriteStorage<Radius>,
+                WriteStorage<PlanetWindowShown>,
+                WriteStorage<Trail>,
+                WriteStorage<BodyStyle>,
+                Write<AccessibilitySettings>,
                 Entities,
             )| {
                 camera_position.0 = self.camera_state.camera_position.into();
                 camera_speed.0 = self.camera_state.camera_speed.into();
+                if let Some(rotation) = self.camera_state.rotation {
+                    camera_rotation.0 = rotation.into();
+                }
+                if let Some(controller) = self.camera_state.controller {
+                    *camera_controller = controller;
+                }
 
                 gravitational_constant.0 = self.constant_state.gravitational_constant;
 
-                *time_scale =
-                    TimeScale::from_max_time_per_iteration(self.time_state.time_scale, 86400.0);
+                *time_scale = self.time_state.to_time_scale();
+
+                if let Some(display_state) = &self.display_state {
+                    accessibility_settings.palette = display_state.palette;
+                }
 
                 (
                     &planet_ids,
@@ -176,6 +517,48 @@ impl SimulationState {
                     .for_each(|(_id, _col, _pos, _vel, _mass, entity)| {
                         entities.delete(entity).unwrap();
                     });
+
+                // The sun is kept rather than made again, so the state it was
+                // saved with is put back onto it
+                let sun = (&planet_ids, &entities)
+                    .join()
+                    .find(|(id, _)| id.get_id() == "sun")
+                    .map(|(_, entity)| entity);
+                let sun_state = self.planet_state.iter().find(|state| state.id == "sun");
+                if let (Some(sun), Some(state)) = (sun, sun_state) {
+                    let _ = planet_positions.insert(sun, Position(state.position.into()));
+                    let _ = planet_velocities.insert(sun, Velocity(state.velocity.into()));
+                    let _ = planet_masses.insert(sun, Mass(state.mass));
+                    let _ = planet_mass_rates.insert(sun, MassRate(state.mass_rate));
+                    // Older saves don't have the radius
+                    if state.radius > 0.0 {
+                        let _ = planet_radii.insert(sun, Radius(state.radius));
+                    }
+                    let _ = planet_window_shown.insert(sun, PlanetWindowShown(state.window_shown));
+                    let _ = planet_styles.insert(
+                        sun,
+                        BodyStyle {
+                            trail_colour: state.trail_colour,
+                            label_shown: state.label_shown,
+                            arrows_shown: state.arrows_shown,
+                        },
+                    );
+                    // The sun doesn't usually have a trail
+                    if !state.trail.is_empty() {
+                        let _ = planet_trails.insert(
+                            sun,
+                            Trail::from_points(state.trail.iter().map(TrailPoint::from)),
+                        );
+                    }
+
+                    let recoloured = planet_colours
+                        .get(sun)
+                        .map_or(true, |colour| colour.0 != state.colour);
+                    let _ = planet_colours.insert(sun, PlanetColour(state.colour));
+                    return recoloured.then(|| sun);
+                }
+
+                None
             },
         );
 
@@ -186,26 +569,59 @@ impl SimulationState {
 
             (device, queue, texture_bind_group_layout)
         };
+        // Taken out of the world while the bodies are being created
+        let mut model_cache = std::mem::take(&mut *world.write_resource::<ModelCache>());
+
+        world.write_resource::<UsageStats>().bodies_created += self
+            .planet_state
+            .iter()
+            .filter(|state| state.id != "sun")
+            .count() as u64;
 
-        self.planet_state
+        let satellites = self
+            .planet_state
             .into_iter()
             .filter(|state| state.id != "sun")
-            .for_each(|state| {
-                world
+            .filter_map(|state| {
+                let body_type = match state.parent {
+                    Some(_) => BodyType::Moon,
+                    None => BodyType::Planet,
+                };
+
+                let entity = world
                     .create_entity()
                     .with(Identifier::new(state.id.clone(), state.name))
-                    .with(PlanetWindowShown::default())
+                    .with(PlanetWindowShown(state.window_shown))
                     .with(Position(state.position.into()))
                     .with(Velocity(state.velocity.into()))
                     .with(Mass(state.mass))
+                    .with(MassRate(state.mass_rate))
+                    .with(Radius(state.radius))
+                    .with(state.lineage)
+                    .with(Trail::from_points(state.trail.iter().map(TrailPoint::from)))
+                    .with(OrbitTracker::default())
                     .with(PlanetColour(state.colour))
+                    .with(BodyStyle {
+                        trail_colour: state.trail_colour,
+                        label_shown: state.label_shown,
+                        arrows_shown: state.arrows_shown,
+                    })
+                    .maybe_with(state.texture.clone().map(PlanetTexture))
+                    .with(SphereDetail(state.detail))
                     .with(RenderModel::new(
                         &device,
-                        Icosphere::new(2.5, 3).into_model(
+                        model_cache.body_model(
                             &device,
                             &queue,
-                            state.id.clone(),
+                            1.0,
+                            state.detail,
                             state.colour,
+                            Surface::for_body(
+                                &state.id,
+                                state.texture.as_deref(),
+                                state.mass,
+                                false,
+                            ),
                             &texture_bind_group_layout,
                         ),
                         Instance::new(
@@ -215,148 +631,418 @@ impl SimulationState {
                         wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
                         Some(&state.id),
                     ))
-                    .with(InteractionHandler::new(
-                        InteractionFlags::all(),
-                        BodyType::Planet,
-                    ))
+                    .maybe_with(match &state.atmosphere {
+                        Some(atmosphere) => {
+                            Some(Atmosphere::new(atmosphere.colour, atmosphere.thickness))
+                        }
+                        None => Atmosphere::for_body(&state.id),
+                    })
+                    .maybe_with(match &state.rings {
+                        Some(rings) => Some(Rings {
+                            inner_radius: rings.inner_radius,
+                            outer_radius: rings.outer_radius,
+                            tilt: rings.tilt,
+                            colour: rings.colour,
+                            opacity: rings.opacity,
+                        }),
+                        None => Rings::for_body(&state.id),
+                    })
+                    .with(InteractionHandler::new(InteractionFlags::all(), body_type))
                     .build();
-            });
+
+                state.parent.map(|parent| (entity, parent))
+            })
+            .collect::<Vec<_>>();
+
+        world.insert(model_cache);
 
         world.maintain();
-    }
+        // The bodies that were replaced no longer need their models
+        world.write_resource::<ModelCache>().prune();
 
-    fn save_string(contents: String) {
-        std::thread::spawn(move || {
-            let file_location = dialog::FileSelection::new("Save Simulation")
-                .title("Save Simulation")
-                .mode(dialog::FileSelectionMode::Save)
-                .show()
-                .expect("Could not display dialog box");
-
-            if let Some(file_location) = file_location {
-                let file = fs::File::options()
-                    .write(true)
-                    .create(true)
-                    .open(file_location);
-
-                match file {
-                    Ok(mut file) => match file.write_all(contents.as_bytes()) {
-                        Err(err) => dialog::Message::new(format!("{:?}", err))
-                            .title("Failed to write to file.")
-                            .show()
-                            .expect("Could not display dialog box"),
-                        _ => {}
-                    },
-                    Err(err) => dialog::Message::new(format!("{:?}", err))
-                        .title("Failed to save file.")
-                        .show()
-                        .expect("Could not display dialog box"),
+        // Link the satellites to their parents, now that every body exists
+        world.exec(
+            |(entities, ids, mut planet_satellites): (
+                Entities,
+                ReadStorage<Identifier>,
+                WriteStorage<Satellite>,
+            )| {
+                for (entity, parent_id) in satellites {
+                    let parent = (&entities, &ids)
+                        .join()
+                        .find(|(_, id)| id.get_id() == parent_id)
+                        .map(|(parent, _)| parent);
+
+                    match parent {
+                        Some(parent) => {
+                            planet_satellites
+                                .insert(entity, Satellite::new(parent))
+                                .unwrap();
+                        }
+                        None => warn!("Satellite's parent {} does not exist", parent_id),
+                    }
                 }
-            }
-        });
+            },
+        );
+
+        // Centre the camera on the body it was saved centred on
+        if let Some(center) = self.camera_state.center {
+            world.exec(
+                |(ids, mut camera_center): (ReadStorage<Identifier>, WriteExpect<CameraCenter>)| {
+                    match (&ids).join().find(|id| id.get_id() == center.body) {
+                        Some(body) => camera_center.set_body(body.clone()),
+                        None => warn!("Camera's centre {} does not exist", center.body),
+                    }
+                    camera_center.following = center.following;
+                    camera_center.offset = center.offset.into();
+                    camera_center.smoothing = center.smoothing;
+                },
+            );
+        }
+
+        // The bodies were made in their natural colours
+        if world.read_resource::<AccessibilitySettings>().palette != ColourPalette::Natural {
+            components::rebuild_body_models(world);
+        } else if let Some(sun) = recoloured_sun {
+            components::rebuild_body_model(world, sun);
+        }
     }
+}
 
-    pub fn save_json(self) -> Result<(), serde_json::Error> {
-        let contents = serde_json::to_string_pretty(&self)?;
+/// The file types a simulation can be saved as
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SaveFormat {
+    Json,
+    Toml,
+    /// MessagePack, which is smaller and faster to read than the text
+    /// formats, for simulations with long trails
+    Binary,
+}
+impl SaveFormat {
+    pub const ALL: [Self; 3] = [Self::Json, Self::Toml, Self::Binary];
 
-        Self::save_string(contents);
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+            Self::Binary => "Binary",
+        }
+    }
 
-        Ok(())
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Binary => "msgpack",
+        }
     }
 
-    pub fn save_toml(self) -> Result<(), toml::ser::Error> {
-        let contents = toml::to_string_pretty(&self)?;
+    pub fn from_path(path: &Path) -> Option<Self> {
+        //! The format of a save, from the extension of its file
+
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
 
-        Self::save_string(contents);
+    fn serialize(&self, state: &SimulationState) -> Result<Vec<u8>, SaveError> {
+        Ok(match self {
+            Self::Json => serde_json::to_vec_pretty(state)?,
+            Self::Toml => toml::to_string_pretty(state)?.into_bytes(),
+            // Fields are written with their names, so saves missing the newer
+            // ones can still be read
+            Self::Binary => rmp_serde::to_vec_named(state)?,
+        })
+    }
 
-        Ok(())
+    fn deserialize(&self, contents: &[u8]) -> Result<SimulationState, SaveError> {
+        Ok(match self {
+            Self::Json => serde_json::from_slice(contents)?,
+            Self::Toml => {
+                let contents = std::str::from_utf8(contents).map_err(|err| {
+                    SaveError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+                })?;
+                toml::from_str(contents)?
+            }
+            Self::Binary => rmp_serde::from_slice(contents)?,
+        })
     }
 }
 
+/// Possible errors when saving or loading a simulation
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Failed to write TOML: {0}")]
+    TomlSerializeError(#[from] toml::ser::Error),
+
+    #[error("Invalid TOML: {0}")]
+    TomlDeserializeError(#[from] toml::de::Error),
+
+    #[error("Failed to write binary: {0}")]
+    BinarySerializeError(#[from] rmp_serde::encode::Error),
+
+    #[error("Invalid binary: {0}")]
+    BinaryDeserializeError(#[from] rmp_serde::decode::Error),
+
+    #[error("Unknown save format: {0:?}")]
+    FormatError(PathBuf),
+
+    #[error(transparent)]
+    ScenarioError(#[from] ScenarioError),
+}
+
+/// The result of a save or load, sent back from the task showing the dialog
+enum SaveEvent {
+    Saved(PathBuf),
+    Loaded(SimulationState),
+    /// The id of the message describing what failed, with why
+    Failed {
+        message: &'static str,
+        err: SaveError,
+    },
+}
+
+/// Shows the dialogs to pick where a simulation is saved to or loaded from,
+/// without blocking the UI, and reports the results in toasts
 pub struct SaveHandler {
-    load_receiver: mpsc::Receiver<SimulationState>,
-    load_sender: mpsc::Sender<SimulationState>,
+    sender: mpsc::Sender<SaveEvent>,
+    receiver: mpsc::Receiver<SaveEvent>,
 }
 impl SaveHandler {
     pub fn new() -> Self {
-        let (load_sender, load_receiver) = mpsc::channel();
+        let (sender, receiver) = mpsc::channel();
 
-        Self {
-            load_sender,
-            load_receiver,
-        }
+        Self { sender, receiver }
     }
 
-    fn load_string() -> Option<String> {
-        let file_location = dialog::FileSelection::new("Load Simulation")
-            .title("Load Simulation")
-            .mode(dialog::FileSelectionMode::Open)
-            .show()
-            .expect("Could not display dialog box");
-
-        if let Some(file_location) = file_location {
-            let file = fs::File::open(file_location);
-
-            match file {
-                Ok(mut file) => {
-                    let mut contents = String::new();
-                    match file.read_to_string(&mut contents) {
-                        Err(err) => dialog::Message::new(format!("{:?}", err))
-                            .title("Failed to load file.")
-                            .show()
-                            .expect("Could not display dialog box"),
-                        Ok(len) => return Some(contents[..len].to_string()),
-                    }
-                }
-                Err(err) => dialog::Message::new(format!("{:?}", err))
-                    .title("Failed to load file.")
-                    .show()
-                    .expect("Could not display dialog box"),
+    pub fn save(&self, runtime: &Handle, state: SimulationState, format: SaveFormat) {
+        //! Ask where to save the simulation, then write it there
+
+        let sender = self.sender.clone();
+        runtime.spawn(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(&tr!("save-simulation-title"))
+                .add_filter(format.name(), &[format.extension()])
+                .set_file_name(&format!("simulation.{}", format.extension()))
+                .save_file()
+                .await;
+
+            if let Some(file) = file {
+                let result = format
+                    .serialize(&state)
+                    .and_then(|contents| Ok(fs::write(file.path(), contents)?));
+
+                let _ = sender.send(match result {
+                    Ok(()) => SaveEvent::Saved(file.path().to_path_buf()),
+                    Err(err) => SaveEvent::Failed {
+                        message: "save-failed",
+                        err,
+                    },
+                });
             }
-        }
+        });
+    }
+
+    pub fn load(&self, runtime: &Handle, format: SaveFormat) {
+        //! Ask which simulation to load, then read it
+
+        let sender = self.sender.clone();
+        runtime.spawn(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(&tr!("load-simulation-title"))
+                .add_filter(format.name(), &[format.extension()])
+                .pick_file()
+                .await;
+
+            if let Some(file) = file {
+                let result = format.deserialize(&file.read().await);
+
+                let _ = sender.send(match result {
+                    Ok(state) => SaveEvent::Loaded(state),
+                    Err(err) => SaveEvent::Failed {
+                        message: "load-failed",
+                        err,
+                    },
+                });
+            }
+        });
+    }
+
+    pub fn quick_save(&self, state: SimulationState, slot: usize) {
+        //! Write the simulation to a quick save slot, without asking where
+
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let path = quick_slot_path(slot);
+            let result = SaveFormat::Toml.serialize(&state).and_then(|contents| {
+                fs::create_dir_all(QUICK_SAVE_DIR.as_path())?;
+                Ok(fs::write(&path, contents)?)
+            });
 
-        None
+            let _ = sender.send(match result {
+                Ok(()) => SaveEvent::Saved(path),
+                Err(err) => SaveEvent::Failed {
+                    message: "quick-save-failed",
+                    err,
+                },
+            });
+        });
     }
 
-    pub fn load_toml(&self) {
-        let sender = self.load_sender.clone();
+    pub fn quick_load(&self, slot: usize) {
+        //! Read the simulation in a quick save slot, without asking which
+
+        let sender = self.sender.clone();
         std::thread::spawn(move || {
-            let contents = Self::load_string();
+            let result = fs::read(quick_slot_path(slot))
+                .map_err(SaveError::from)
+                .and_then(|contents| SaveFormat::Toml.deserialize(&contents));
+
+            let _ = sender.send(match result {
+                Ok(state) => SaveEvent::Loaded(state),
+                Err(err) => SaveEvent::Failed {
+                    message: "quick-load-failed",
+                    err,
+                },
+            });
+        });
+    }
 
-            if let Some(contents) = contents {
-                let state = toml::from_str::<SimulationState>(&contents);
+    pub fn start_scenario(&self, path: PathBuf) {
+        //! Read a scenario, and start a new simulation from it
 
-                match state {
-                    Ok(state) => sender.send(state).unwrap(),
-                    Err(err) => dialog::Message::new(format!("{:?}", err))
-                        .title("Invalid file format.")
-                        .show()
-                        .expect("Could not display dialog box"),
-                }
-            }
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let result = Scenario::load_file(&path).and_then(Scenario::into_state);
+
+            let _ = sender.send(match result {
+                Ok(state) => SaveEvent::Loaded(state),
+                Err(err) => SaveEvent::Failed {
+                    message: "scenario-failed",
+                    err: err.into(),
+                },
+            });
         });
     }
 
-    pub fn load_json(&self) {
-        let sender = self.load_sender.clone();
+    pub fn load_dropped(&self, path: PathBuf) {
+        //! Read a save or scenario dropped onto the window. TOML files can be
+        //! either, so are started as a scenario if they aren't a save
+
+        let sender = self.sender.clone();
         std::thread::spawn(move || {
-            let contents = Self::load_string();
+            let result = SimulationState::load_file(&path).or_else(|save_err| {
+                Scenario::load_file(&path)
+                    .and_then(Scenario::into_state)
+                    .map_err(|err| match err {
+                        // Not a scenario either, so why it isn't a save is
+                        // more useful
+                        ScenarioError::FormatError(_) => save_err,
+                        err => err.into(),
+                    })
+            });
 
-            if let Some(contents) = contents {
-                let state = serde_json::from_str::<SimulationState>(&contents);
+            let _ = sender.send(match result {
+                Ok(state) => SaveEvent::Loaded(state),
+                Err(err) => SaveEvent::Failed {
+                    message: "load-failed",
+                    err,
+                },
+            });
+        });
+    }
 
-                match state {
-                    Ok(state) => sender.send(state).unwrap(),
-                    Err(err) => dialog::Message::new(format!("{:?}", err))
-                        .title("Invalid file format.")
-                        .show()
-                        .expect("Could not display dialog box"),
-                }
+    pub fn open_scenario(&self, runtime: &Handle) {
+        //! Ask which scenario to start, for those not in a scenarios folder
+
+        let sender = self.sender.clone();
+        runtime.spawn(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(&tr!("open-scenario-title"))
+                .add_filter("Scenario", &["toml", "ron"])
+                .pick_file()
+                .await;
+
+            if let Some(file) = file {
+                let result = Scenario::load_file(file.path()).and_then(Scenario::into_state);
+
+                let _ = sender.send(match result {
+                    Ok(state) => SaveEvent::Loaded(state),
+                    Err(err) => SaveEvent::Failed {
+                        message: "scenario-failed",
+                        err: err.into(),
+                    },
+                });
             }
         });
     }
 
-    pub fn try_load_state(&mut self) -> Result<SimulationState, mpsc::TryRecvError> {
-        self.load_receiver.try_recv()
+    pub fn try_load_state(&mut self, toasts: &Toasts) -> Option<SimulationState> {
+        //! Handle the saves and loads finished since the last frame, returning
+        //! the simulation that was loaded, if any
+
+        let mut loaded = None;
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                SaveEvent::Saved(path) => {
+                    info!("Saved the simulation to {:?}", path);
+                    toasts.info(tr!("simulation-saved", path = path.display().to_string()));
+                }
+                SaveEvent::Loaded(state) => loaded = Some(state),
+                SaveEvent::Failed { message, err } => {
+                    let message = locale::text(message, None);
+                    warn!("{}: {}", message, err);
+                    toasts.error(format!("{}: {}", message, err));
+                }
+            }
+        }
+
+        loaded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimeScale, TimeState};
+
+    #[test]
+    fn test_time_scale_round_trip() {
+        let mut time_scale = TimeScale::new(3155760.0, 7);
+        time_scale.paused = true;
+        let state = TimeState::new(&time_scale);
+
+        let toml = toml::to_string(&state).unwrap();
+        let loaded = toml::from_str::<TimeState>(&toml).unwrap().to_time_scale();
+        assert_eq!(time_scale.total_time_elapsed, loaded.total_time_elapsed);
+        assert_eq!(time_scale.time_scale, loaded.time_scale);
+        assert_eq!(7, loaded.iterations);
+        assert!(loaded.paused);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded = serde_json::from_str::<TimeState>(&json)
+            .unwrap()
+            .to_time_scale();
+        assert_eq!(7, loaded.iterations);
+        assert!(loaded.paused);
+    }
+
+    #[test]
+    fn test_time_scale_from_older_saves() {
+        // Saves from before the iterations and paused state were stored
+        let state = toml::from_str::<TimeState>(
+            "date_time = \"2022-09-01T12:00:00+00:00\"\ntime_scale = 3155760.0\n",
+        )
+        .unwrap();
+        let loaded = state.to_time_scale();
+
+        assert_eq!(37, loaded.iterations);
+        assert!(!loaded.paused);
     }
 }
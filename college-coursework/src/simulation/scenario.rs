@@ -0,0 +1,395 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use cgmath::{One, Quaternion, Vector3};
+use chrono::Utc;
+use log::warn;
+use serde::Deserialize;
+
+use crate::{assets, config::CONFIG_DIR, renderer::camera::Camera, util::BIG_G};
+
+use super::{
+    kepler::OrbitalElements,
+    saves::{
+        CameraState, ConstantState, PlanetState, SimulationState, TimeState, DEFAULT_SPHERE_DETAIL,
+    },
+    SUN,
+};
+
+/// Folder the scenarios are kept in, both in the assets and the config
+/// directory
+const SCENARIOS_FOLDER: &'static str = "scenarios";
+
+/// An experiment to start the simulation with, written by hand and shared as
+/// a TOML or RON file
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub constants: ScenarioConstants,
+    #[serde(default)]
+    pub camera: ScenarioCamera,
+    #[serde(rename = "body")]
+    pub bodies: Vec<ScenarioBody>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ScenarioConstants {
+    /// (in m^3 kg^-1 s^-2)
+    pub gravitational_constant: f64,
+    /// Simulated seconds for every real second
+    pub time_scale: f64,
+}
+impl Default for ScenarioConstants {
+    fn default() -> Self {
+        Self {
+            gravitational_constant: BIG_G,
+            time_scale: 3155760.0,
+        }
+    }
+}
+
+/// Where the camera starts, in scene units
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ScenarioCamera {
+    pub position: [f32; 3],
+    /// The point the camera starts facing
+    pub look_at: [f32; 3],
+    pub speed: f32,
+}
+impl Default for ScenarioCamera {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 5.0, 10.0],
+            look_at: [0.0, 0.0, 0.0],
+            speed: 20.0,
+        }
+    }
+}
+
+/// A body in a scenario. Its state is either given as a position and
+/// velocity, or as an orbit around its parent
+#[derive(Debug, Deserialize)]
+pub struct ScenarioBody {
+    pub id: String,
+    pub name: String,
+    /// (in kg)
+    pub mass: f64,
+    /// (in m)
+    pub radius: f64,
+    #[serde(default = "default_colour")]
+    pub colour: [f32; 4],
+    /// The asset path of the body's surface texture
+    #[serde(default)]
+    pub texture: Option<String>,
+    /// The id of the body this body is a satellite of, which has to come
+    /// before it. Orbits are around the Sun if there is no parent
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// (in m)
+    #[serde(default)]
+    pub position: Option<[f64; 3]>,
+    /// (in m/s)
+    #[serde(default)]
+    pub velocity: Option<[f64; 3]>,
+    #[serde(default)]
+    pub orbit: Option<ScenarioOrbit>,
+}
+
+fn default_colour() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+/// The orbit of a body around its parent. Angles are in degrees
+#[derive(Debug, Deserialize)]
+pub struct ScenarioOrbit {
+    /// (in m)
+    pub semi_major_axis: f64,
+    #[serde(default)]
+    pub eccentricity: f64,
+    #[serde(default)]
+    pub inclination: f64,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f64,
+    #[serde(default)]
+    pub argument_of_periapsis: f64,
+    #[serde(default)]
+    pub true_anomaly: f64,
+}
+impl ScenarioOrbit {
+    fn to_elements(&self) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis: self.semi_major_axis,
+            eccentricity: self.eccentricity,
+            inclination: self.inclination.to_radians(),
+            longitude_of_ascending_node: self.longitude_of_ascending_node.to_radians(),
+            argument_of_periapsis: self.argument_of_periapsis.to_radians(),
+            true_anomaly: self.true_anomaly.to_radians(),
+        }
+    }
+}
+
+/// Possible errors when reading a scenario
+#[derive(thiserror::Error, Debug)]
+pub enum ScenarioError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid TOML: {0}")]
+    TomlError(#[from] toml::de::Error),
+
+    #[error("Invalid RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+
+    #[error("Unknown scenario format: {0:?}")]
+    FormatError(PathBuf),
+
+    #[error("{body} needs either a position and velocity, or an orbit")]
+    StateError { body: String },
+
+    #[error("The parent of {body}, {parent}, has to come before it")]
+    ParentError { body: String, parent: String },
+}
+
+impl Scenario {
+    pub fn load_file(path: &Path) -> Result<Self, ScenarioError> {
+        //! Read a scenario, as TOML or RON depending on its extension
+
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(toml::from_str(&contents)?),
+            Some("ron") => Ok(ron::from_str(&contents)?),
+            _ => Err(ScenarioError::FormatError(path.to_owned())),
+        }
+    }
+
+    pub fn into_state(self) -> Result<SimulationState, ScenarioError> {
+        //! Work out where each body starts, giving a simulation that can be
+        //! loaded like a save. The Sun is added as it usually is if the
+        //! scenario doesn't have it
+
+        let big_g = self.constants.gravitational_constant;
+        let sun = SUN.get_identifier();
+
+        let mut planet_state: Vec<PlanetState> = Vec::new();
+        if !self.bodies.iter().any(|body| body.id == sun.get_id()) {
+            planet_state.push(PlanetState {
+                id: sun.get_id().to_string(),
+                name: sun.get_name().to_string(),
+                position: SUN.get_pos().0.into(),
+                velocity: SUN.get_vel().0.into(),
+                mass: SUN.get_mass().0,
+                mass_rate: 0.0,
+                colour: SUN.get_colour(),
+                texture: SUN.get_texture().map(str::to_string),
+                radius: SUN.get_radius().0,
+                parent: None,
+                detail: DEFAULT_SPHERE_DETAIL,
+                window_shown: false,
+                trail_colour: None,
+                label_shown: true,
+                arrows_shown: true,
+                lineage: Default::default(),
+                atmosphere: None,
+                rings: None,
+                trail: Vec::new(),
+            });
+        }
+
+        for body in self.bodies {
+            let (position, velocity) = match (body.position, body.velocity, &body.orbit) {
+                (Some(position), Some(velocity), None) => (position, velocity),
+                (None, None, Some(orbit)) => {
+                    let parent_id = body
+                        .parent
+                        .clone()
+                        .unwrap_or_else(|| sun.get_id().to_string());
+                    let parent = planet_state
+                        .iter()
+                        .find(|state| state.id == parent_id)
+                        .ok_or_else(|| ScenarioError::ParentError {
+                            body: body.id.clone(),
+                            parent: parent_id.clone(),
+                        })?;
+
+                    let mu = big_g * (parent.mass + body.mass);
+                    let (position, velocity) = orbit.to_elements().to_state(mu);
+
+                    (
+                        (Vector3::from(parent.position) + position).into(),
+                        (Vector3::from(parent.velocity) + velocity).into(),
+                    )
+                }
+                _ => return Err(ScenarioError::StateError { body: body.id }),
+            };
+
+            if let Some(parent) = &body.parent {
+                if !planet_state.iter().any(|state| &state.id == parent) {
+                    return Err(ScenarioError::ParentError {
+                        body: body.id,
+                        parent: parent.clone(),
+                    });
+                }
+            }
+
+            planet_state.push(PlanetState {
+                id: body.id,
+                name: body.name,
+                position,
+                velocity,
+                mass: body.mass,
+                mass_rate: 0.0,
+                colour: body.colour,
+                texture: body.texture,
+                radius: body.radius,
+                parent: body.parent,
+                detail: DEFAULT_SPHERE_DETAIL,
+                window_shown: false,
+                trail_colour: None,
+                label_shown: true,
+                arrows_shown: true,
+                lineage: Default::default(),
+                atmosphere: None,
+                rings: None,
+                trail: Vec::new(),
+            });
+        }
+
+        let mut camera = Camera::new(self.camera.position, Quaternion::one());
+        camera.look_at(self.camera.look_at.into());
+
+        Ok(SimulationState {
+            time_state: TimeState {
+                date_time: Utc::now().to_rfc3339(),
+                time_scale: self.constants.time_scale,
+                iterations: None,
+                paused: false,
+            },
+            constant_state: ConstantState {
+                gravitational_constant: big_g,
+            },
+            camera_state: CameraState {
+                camera_position: self.camera.position,
+                camera_speed: self.camera.speed,
+                rotation: Some(camera.rotation.into()),
+                controller: None,
+                center: None,
+            },
+            display_state: None,
+            planet_state,
+        })
+    }
+}
+
+pub fn available_scenarios() -> Vec<PathBuf> {
+    //! Returns the paths of the bundled scenarios, followed by those in the
+    //! config directory, each in alphabetical order
+
+    [
+        assets::asset_path(SCENARIOS_FOLDER),
+        CONFIG_DIR.join(SCENARIOS_FOLDER),
+    ]
+    .into_iter()
+    .flat_map(|folder| {
+        let mut scenarios = match fs::read_dir(&folder) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    matches!(
+                        path.extension().and_then(|extension| extension.to_str()),
+                        Some("toml" | "ron")
+                    )
+                })
+                .collect::<Vec<_>>(),
+            // There are no scenarios in the config directory until some are
+            // added
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                warn!("Failed to find the scenarios in {:?}: {}", folder, err);
+                Vec::new()
+            }
+        };
+
+        scenarios.sort();
+        scenarios
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scenario, ScenarioError};
+
+    #[test]
+    fn test_orbits_around_parents() {
+        let scenario: Scenario = toml::from_str(
+            r#"
+            name = "Test"
+
+            [[body]]
+            id = "planet"
+            name = "Planet"
+            mass = 6.0e24
+            radius = 6.4e6
+            [body.orbit]
+            semi_major_axis = 1.5e11
+
+            [[body]]
+            id = "moon"
+            name = "Moon"
+            mass = 7.3e22
+            radius = 1.7e6
+            parent = "planet"
+            [body.orbit]
+            semi_major_axis = 3.8e8
+            true_anomaly = 90.0
+            "#,
+        )
+        .unwrap();
+
+        let state = scenario.into_state().unwrap();
+        let ids = state
+            .planet_state
+            .iter()
+            .map(|body| body.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["sun", "planet", "moon"], ids);
+
+        // The moon is a quarter of the way around the planet
+        let planet = &state.planet_state[1];
+        let moon = &state.planet_state[2];
+        assert_eq!(planet.position[0], moon.position[0]);
+        assert!((moon.position[1] - 3.8e8).abs() < 1.0);
+        assert_eq!(Some("planet"), moon.parent.as_deref());
+    }
+
+    #[test]
+    fn test_parent_has_to_come_first() {
+        let scenario: Scenario = ron::from_str(
+            r#"(
+                name: "Test",
+                body: [(
+                    id: "moon",
+                    name: "Moon",
+                    mass: 7.3e22,
+                    radius: 1.7e6,
+                    parent: Some("planet"),
+                    orbit: Some((semi_major_axis: 3.8e8)),
+                )],
+            )"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            scenario.into_state(),
+            Err(ScenarioError::ParentError { .. })
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+/// Stopwatch measuring elapsed simulated time, with lap markers
+#[derive(Debug, Default, Clone)]
+pub struct Stopwatch {
+    start: Option<f64>,
+    stopped_at: Option<f64>,
+    laps: Vec<f64>,
+}
+impl Stopwatch {
+    pub fn is_running(&self) -> bool {
+        self.start.is_some() && self.stopped_at.is_none()
+    }
+
+    pub fn start(&mut self, now: f64) {
+        //! Start the stopwatch, or resume it if it was stopped
+
+        match (self.start, self.stopped_at.take()) {
+            // Shift the start time and laps so the time spent stopped is not counted
+            (Some(start), Some(stopped_at)) => {
+                let paused = now - stopped_at;
+                self.start = Some(start + paused);
+                self.laps.iter_mut().for_each(|lap| *lap += paused);
+            }
+            (Some(_), None) => {}
+            (None, _) => self.start = Some(now),
+        }
+    }
+
+    pub fn stop(&mut self, now: f64) {
+        if self.is_running() {
+            self.stopped_at = Some(now);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn lap(&mut self, now: f64) {
+        //! Mark the end of a lap
+
+        if self.is_running() {
+            self.laps.push(now);
+        }
+    }
+
+    pub fn elapsed(&self, now: f64) -> f64 {
+        //! Returns the simulated time elapsed since the stopwatch was started
+
+        match self.start {
+            Some(start) => self.stopped_at.unwrap_or(now) - start,
+            None => 0.0,
+        }
+    }
+
+    pub fn laps(&self) -> Vec<f64> {
+        //! Returns the duration of each completed lap
+
+        let mut previous = match self.start {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+
+        self.laps
+            .iter()
+            .map(|&lap| {
+                let duration = lap - previous;
+                previous = lap;
+                duration
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stopwatch;
+
+    #[test]
+    fn test_stopwatch_laps() {
+        let mut stopwatch = Stopwatch::default();
+        stopwatch.start(100.0);
+        stopwatch.lap(150.0);
+        stopwatch.lap(175.0);
+
+        assert_eq!(vec![50.0, 25.0], stopwatch.laps());
+        assert_eq!(100.0, stopwatch.elapsed(200.0));
+    }
+
+    #[test]
+    fn test_stopwatch_stopped_time_not_counted() {
+        let mut stopwatch = Stopwatch::default();
+        stopwatch.start(0.0);
+        stopwatch.lap(10.0);
+        stopwatch.stop(20.0);
+
+        // Laps cannot be marked while stopped
+        stopwatch.lap(25.0);
+        assert_eq!(20.0, stopwatch.elapsed(1000.0));
+
+        stopwatch.start(100.0);
+        stopwatch.lap(130.0);
+
+        assert_eq!(vec![10.0, 40.0], stopwatch.laps());
+        assert_eq!(60.0, stopwatch.elapsed(140.0));
+    }
+}
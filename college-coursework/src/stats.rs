@@ -0,0 +1,63 @@
+use std::{collections::BTreeMap, fs};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::data::DATA_DIR;
+
+const STATS_FILE: &'static str = "stats.toml";
+
+/// Statistics about how the program has been used, kept only on this machine
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// Total time the program has been running (in seconds)
+    pub time_run: f64,
+    pub sessions: u64,
+    /// Bodies added to the simulation after it started, such as by loading a
+    /// save
+    pub bodies_created: u64,
+    pub saves_made: u64,
+    // Tables have to come after values in TOML, so this must be last
+    /// The number of times each feature has been used
+    pub features_used: BTreeMap<String, u64>,
+}
+impl UsageStats {
+    pub fn load() -> Self {
+        //! Load the statistics of previous sessions, starting from nothing if
+        //! there are none
+
+        let path = DATA_DIR.join(STATS_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Invalid usage statistics in {:?}: {}", path, err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        //! Save the statistics for use by later sessions
+
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to serialize usage statistics: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = fs::create_dir_all(DATA_DIR.as_path())
+            .and_then(|_| fs::write(DATA_DIR.join(STATS_FILE), contents))
+        {
+            warn!("Failed to save usage statistics: {}", err);
+        }
+    }
+
+    pub fn use_feature(&mut self, feature: &str) {
+        //! Record that a feature has been used
+        *self.features_used.entry(feature.to_string()).or_default() += 1;
+    }
+}
@@ -71,3 +71,234 @@ fn test_keplerian_conversion_moon() {
         moon_vel.magnitude(),
     );
 }
+
+// Determinism tests
+//
+// Each scenario is run for a year of simulated time, one day per frame in
+// hourly steps, both with the deterministic mode on and off.
+//
+// Tolerance policy:
+// - The deterministic and parallel runs must produce bit identical states on
+//   the same machine, this is checked by comparing checksums of the bits.
+//   Any optimisation that makes the result depend on how work is split
+//   between threads will fail this check
+// - Across platforms the results are compared against stored references,
+//   with a relative tolerance of `DETERMINISM_TOLERANCE`. Differences in
+//   `sin`, `cos` and `cosh` between platforms are at most a few ulp, which the
+//   close encounter scenario amplifies to around 1e-8, so there is a margin
+//   of 100 times. A change that moves any body by more than this is a change
+//   in behaviour and the references must be updated deliberately, using the
+//   states printed by the failing test
+const DETERMINISM_TOLERANCE: f64 = 1e-6;
+
+/// A body in a determinism scenario
+struct ScenarioBody {
+    position: [f64; 3],
+    velocity: [f64; 3],
+    mass: f64,
+    body_type: crate::simulation::BodyType,
+    // Index of the body this is a satellite of
+    parent: Option<usize>,
+}
+
+/// The final position and velocity of a body
+type BodyState = ([f64; 3], [f64; 3]);
+
+const SCENARIO_SUN: ScenarioBody = ScenarioBody {
+    position: [0.0, 0.0, 0.0],
+    velocity: [0.0, 0.0, 0.0],
+    mass: 1.989e30,
+    body_type: crate::simulation::BodyType::Star,
+    parent: None,
+};
+
+const SCENARIO_EARTH: ScenarioBody = ScenarioBody {
+    position: [149.596e9, 0.0, 0.0],
+    velocity: [0.0, 29.78e3, 0.0],
+    mass: 5.9724e24,
+    body_type: crate::simulation::BodyType::Planet,
+    parent: None,
+};
+
+fn run_scenario(bodies: &[ScenarioBody], deterministic: bool) -> Vec<BodyState> {
+    //! Runs a scenario through the simulator without a renderer and returns
+    //! the final state of each body, in the order they were given
+    use crate::simulation::{
+        CloseEncounterSettings, DeltaTime, DeterministicMode, GravitationalConstant,
+        GravityOverrides, GroupTags, Identifier, InteractionFlags, InteractionHandler, Mass,
+        Position, Satellite, SimulationTime, Simulator, TimeScale, Velocity,
+    };
+    use crate::util::BIG_G;
+    use cgmath::Vector3;
+    use specs::{Builder, RunNow, World, WorldExt};
+    use std::time::Duration;
+
+    let mut world = World::new();
+    world.register::<Position>();
+    world.register::<Velocity>();
+    world.register::<Mass>();
+    world.register::<InteractionHandler>();
+    world.register::<Satellite>();
+    world.register::<Identifier>();
+    world.register::<GroupTags>();
+
+    world.insert(DeltaTime(Duration::from_secs(1)));
+    world.insert(TimeScale::new(86400.0, 24));
+    world.insert(GravitationalConstant(BIG_G));
+    world.insert(GravityOverrides::default());
+    world.insert(CloseEncounterSettings::default());
+    world.insert(SimulationTime::default());
+    world.insert(DeterministicMode(deterministic));
+
+    let mut entities = Vec::new();
+    for body in bodies {
+        let flags = match body.body_type {
+            crate::simulation::BodyType::Star => InteractionFlags::STAR,
+            _ => InteractionFlags::all(),
+        };
+
+        let mut builder = world
+            .create_entity()
+            .with(Position(Vector3::from(body.position)))
+            .with(Velocity(Vector3::from(body.velocity)))
+            .with(Mass(body.mass))
+            .with(InteractionHandler::new(flags, body.body_type));
+        if let Some(parent) = body.parent {
+            builder = builder.with(Satellite::new(entities[parent]));
+        }
+        entities.push(builder.build());
+    }
+
+    let mut simulator = Simulator::new();
+    for _ in 0..365 {
+        simulator.run_now(&world);
+    }
+
+    let positions = world.read_storage::<Position>();
+    let velocities = world.read_storage::<Velocity>();
+    entities
+        .iter()
+        .map(|entity| {
+            (
+                positions.get(*entity).unwrap().0.into(),
+                velocities.get(*entity).unwrap().0.into(),
+            )
+        })
+        .collect()
+}
+
+fn checksum(states: &[BodyState]) -> u64 {
+    //! A FNV-1a hash of the bits of every state
+    states
+        .iter()
+        .flat_map(|(position, velocity)| position.iter().chain(velocity.iter()))
+        .fold(0xcbf29ce484222325, |hash, value| {
+            (hash ^ value.to_bits()).wrapping_mul(0x100000001b3)
+        })
+}
+
+fn check_scenario(bodies: &[ScenarioBody], references: &[BodyState]) {
+    //! Checks a scenario is the same in deterministic and parallel mode, and
+    //! that it matches its references within the tolerance
+    let deterministic = run_scenario(bodies, true);
+    let parallel = run_scenario(bodies, false);
+
+    assert_eq!(
+        checksum(&deterministic),
+        checksum(&parallel),
+        "Deterministic and parallel runs differ\ndeterministic: {:?}\nparallel: {:?}",
+        deterministic,
+        parallel,
+    );
+
+    use cgmath::Vector3;
+
+    let within_tolerance = |actual: [f64; 3], reference: [f64; 3]| {
+        let (actual, reference) = (Vector3::from(actual), Vector3::from(reference));
+        (actual - reference).magnitude() <= DETERMINISM_TOLERANCE * reference.magnitude().max(1.0)
+    };
+
+    for (i, (state, reference)) in deterministic.iter().zip(references).enumerate() {
+        assert!(
+            within_tolerance(state.0, reference.0) && within_tolerance(state.1, reference.1),
+            "Body {} is outside of the tolerance of its reference\nexpected: {:?}\nactual: {:?}\nall states: {:?}",
+            i,
+            reference,
+            state,
+            deterministic,
+        );
+    }
+}
+
+#[test]
+fn test_determinism_sun_earth() {
+    check_scenario(
+        &[SCENARIO_SUN, SCENARIO_EARTH],
+        &[
+            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+            (
+                [149595394802.54553, 375135114.1759562, 0.0],
+                [-74.72469871937892, 29779.93309233747, 0.0],
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_determinism_earth_moon() {
+    // The Moon is integrated in the Earth's frame
+    check_scenario(
+        &[
+            SCENARIO_SUN,
+            SCENARIO_EARTH,
+            ScenarioBody {
+                position: [149.596e9 + 384.4e6, 0.0, 0.0],
+                velocity: [0.0, 29.78e3 + 1.022e3, 0.0],
+                mass: 0.07346e24,
+                body_type: crate::simulation::BodyType::Moon,
+                parent: Some(1),
+            },
+        ],
+        &[
+            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+            (
+                [149595394802.54553, 375135114.1759562, 0.0],
+                [-74.72469871937892, 29779.93309233747, 0.0],
+            ),
+            (
+                [149292614623.1407, 158601010.0059081, 0.0],
+                [517.2083506404143, 28910.324708572523, 0.0],
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_determinism_close_encounter() {
+    // A small body orbiting close enough to the Earth that the pair is
+    // stepped analytically
+    check_scenario(
+        &[
+            SCENARIO_SUN,
+            SCENARIO_EARTH,
+            ScenarioBody {
+                position: [149.596e9 + 50e6, 0.0, 0.0],
+                velocity: [0.0, 29.78e3 + 2.8e3, 0.0],
+                mass: 1.0e22,
+                body_type: crate::simulation::BodyType::Planet,
+                parent: None,
+            },
+        ],
+        &[
+            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+            (
+                [149596171535.35352, -68745938.69422738, 0.0],
+                [14.589141434528532, 29789.440771096484, 0.0],
+            ),
+            (
+                [149548818041.2307, -59620959.81729202, 0.0],
+                [-524.1819603094534, 26936.78304842275, 0.0],
+            ),
+        ],
+    );
+}
@@ -0,0 +1,154 @@
+use crossbeam::channel::{self, Receiver, Sender};
+use instant::{Duration, Instant};
+
+use crate::locale::tr;
+
+/// The most toasts shown at once, the rest wait until one is dismissed
+const MAX_SHOWN: usize = 5;
+
+/// How important a toast is, which sets its colour and how long it stays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+impl ToastLevel {
+    fn duration(&self) -> Duration {
+        match self {
+            Self::Info => Duration::from_secs(4),
+            Self::Warning => Duration::from_secs(8),
+            Self::Error => Duration::from_secs(15),
+        }
+    }
+
+    fn colour(&self, ui: &egui::Ui) -> egui::Color32 {
+        match self {
+            Self::Info => ui.visuals().text_color(),
+            Self::Warning => egui::Color32::YELLOW,
+            Self::Error => egui::Color32::RED,
+        }
+    }
+}
+
+struct Toast {
+    level: ToastLevel,
+    text: String,
+}
+
+/// Pushes toasts from other threads, such as those saving files
+#[derive(Clone)]
+pub struct ToastSender(Sender<Toast>);
+impl ToastSender {
+    pub fn push(&self, level: ToastLevel, text: impl Into<String>) {
+        // The toasts are never dropped while the program is running
+        let _ = self.0.send(Toast {
+            level,
+            text: text.into(),
+        });
+    }
+
+    pub fn info(&self, text: impl Into<String>) {
+        self.push(ToastLevel::Info, text);
+    }
+
+    pub fn warn(&self, text: impl Into<String>) {
+        self.push(ToastLevel::Warning, text);
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.push(ToastLevel::Error, text);
+    }
+}
+
+/// Short notifications shown in the corner of the window, which dismiss
+/// themselves after a while
+pub struct Toasts {
+    sender: ToastSender,
+    receiver: Receiver<Toast>,
+    /// The toasts being shown, with when they were first shown
+    shown: Vec<(Toast, Instant)>,
+}
+impl Default for Toasts {
+    fn default() -> Self {
+        let (sender, receiver) = channel::unbounded();
+
+        Self {
+            sender: ToastSender(sender),
+            receiver,
+            shown: Vec::new(),
+        }
+    }
+}
+impl Toasts {
+    pub fn sender(&self) -> ToastSender {
+        //! A sender for pushing toasts from another thread
+
+        self.sender.clone()
+    }
+
+    pub fn info(&self, text: impl Into<String>) {
+        self.sender.info(text);
+    }
+
+    pub fn warn(&self, text: impl Into<String>) {
+        self.sender.warn(text);
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.sender.error(text);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        //! Show the toasts in the bottom right corner, newest at the bottom,
+        //! removing those that have been shown for long enough. A toast
+        //! stays while the mouse is over it
+
+        while self.shown.len() < MAX_SHOWN {
+            match self.receiver.try_recv() {
+                Ok(toast) => self.shown.push((toast, Instant::now())),
+                Err(_) => break,
+            }
+        }
+
+        if self.shown.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Max), |ui| {
+                    for (i, (toast, shown_at)) in self.shown.iter_mut().enumerate().rev() {
+                        let response = egui::Frame::popup(ui.style())
+                            .show(ui, |ui| {
+                                ui.set_max_width(300.0);
+                                ui.horizontal(|ui| {
+                                    ui.colored_label(toast.level.colour(ui), &toast.text);
+                                    if ui
+                                        .small_button("\u{d7}")
+                                        .on_hover_text(tr!("dismiss"))
+                                        .clicked()
+                                    {
+                                        dismissed = Some(i);
+                                    }
+                                });
+                            })
+                            .response;
+
+                        if response.hovered() {
+                            *shown_at = Instant::now();
+                        }
+                    }
+                });
+            });
+
+        if let Some(i) = dismissed {
+            self.shown.remove(i);
+        }
+        self.shown
+            .retain(|(toast, shown_at)| shown_at.elapsed() < toast.level.duration());
+    }
+}
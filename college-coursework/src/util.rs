@@ -1,13 +1,47 @@
+use std::{env, path::PathBuf};
+
 use chrono::{Date, DateTime, Duration, Utc};
 use nalgebra::{Vector2, Vector3};
 
-pub const BIG_G: f64 = 6.6743015e-11;
-pub const AU: f64 = 1.495978707e11;
+use crate::APPLICATION_NAME;
+
+pub use solar_sim_core::constants::{AU, BIG_G, J2000};
 
 pub type Vec2 = Vector2<f64>;
 pub type Vec3 = Vector3<f64>;
 
 #[rustfmt::skip]
+#[cfg_attr(target_family = "windows", allow(unused_variables))]
+pub fn app_dir(override_var: &str, xdg_var: &str, home_default: &str, dir: &str) -> PathBuf {
+    //! A directory for the program's files, set by the environment variable
+    //! `override_var`. Otherwise it is `dir` in the program's directory, which
+    //! on Unix is under `$xdg_var`, or `home_default` in the home directory if
+    //! that isn't set, and on Windows is under `%AppData%`
+
+    if let Ok(dir) = env::var(override_var) {
+        return PathBuf::from(dir);
+    }
+
+    #[cfg(target_family = "unix")]
+    let app_dir = env::var(xdg_var)
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| PathBuf::from(home).join(home_default)))
+        .expect("Failed to find the home directory, no $HOME set")
+        .join(APPLICATION_NAME);
+
+    #[cfg(target_family = "windows")]
+    let app_dir = env::var("appdata")
+        .map(PathBuf::from)
+        .expect("Failed to find the application data directory, no %AppData% set")
+        .join(APPLICATION_NAME);
+
+    if dir.is_empty() {
+        app_dir
+    } else {
+        app_dir.join(dir)
+    }
+}
+
 pub fn convert_date_to_julian_day(date: &Date<Utc>) -> i64 {
     use chrono::Datelike as _;
     (1461 * (date.year() as i64 + 4800 + (date.month() as i64 - 14) / 12)) / 4
@@ -82,12 +116,52 @@ pub fn convert_julian_date_to_datetime(julian_date: f64) -> DateTime<Utc> {
     date.and_hms(hour, minute, second as u32)
 }
 
+pub fn fuzzy_match(query: &str, text: &str) -> Option<u32> {
+    //! Whether every character of the query appears in the text in order,
+    //! ignoring case. Returns how well it matches, higher when the
+    //! characters are next to each other or start words, or None when it
+    //! doesn't match
+
+    let mut text = text.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        loop {
+            let c = text.next()?;
+            let word_start = previous.map_or(true, |previous| !previous.is_alphanumeric());
+            previous = Some(c);
+
+            if c == wanted {
+                score += 1 + if consecutive { 2 } else { 0 } + if word_start { 3 } else { 0 };
+                consecutive = true;
+                break;
+            }
+            consecutive = false;
+        }
+    }
+
+    Some(score)
+}
+
 mod tests {
     use crate::util::{
         convert_date_to_julian_day, convert_datetime_to_julian_date,
-        convert_julian_date_to_datetime, convert_julian_day_to_date,
+        convert_julian_date_to_datetime, convert_julian_day_to_date, fuzzy_match,
     };
 
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(Some(0), fuzzy_match("", "Jupiter"));
+        assert_eq!(None, fuzzy_match("jx", "Jupiter"));
+        assert!(fuzzy_match("jptr", "Jupiter").is_some());
+
+        // Letters together and at the start of words score higher
+        assert!(fuzzy_match("mar", "Mars") > fuzzy_match("mar", "Ganymede Arc"));
+        assert!(fuzzy_match("io", "Io") > fuzzy_match("io", "Callisto"));
+    }
+
     #[test]
     fn test_julian_day_conversion() {
         use chrono::{TimeZone as _, Utc};
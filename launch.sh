@@ -22,6 +22,7 @@ case $subcmd in
   # Compile and run the program
   run)
     SS_LOG_DIR="$root_dir/env/logs" \
+    SS_CONFIG_DIR="$root_dir/env/config" \
     cargo run \
     $cargo_args \
     $@
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{
+    fields::{parse_f64, parse_vec3, unquote},
+    Log, LogParseError,
+};
+
+/// The state of a body, as logged by the simulator's `Printer` system with
+/// messages like `body{id:earth,name:Earth,pos:Vector3 [..],vel:..,mass:..}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyLogRecord {
+    /// When the body was logged
+    pub time: DateTime<FixedOffset>,
+    pub id: String,
+    pub name: Option<String>,
+    /// (in m)
+    pub position: [f64; 3],
+    /// (in m/s)
+    pub velocity: Option<[f64; 3]>,
+    /// (in kg)
+    pub mass: Option<f64>,
+}
+impl BodyLogRecord {
+    /// The columns of `to_csv`
+    pub const CSV_HEADER: &'static str = "time,elapsed,x,y,z,vx,vy,vz,mass";
+
+    pub fn from_log(log: &Log) -> Result<Option<Self>, LogParseError> {
+        //! The body logged, or none if the log isn't about a body
+
+        let msg = unquote(log.msg());
+        let payload = match msg.trim().strip_prefix("body{") {
+            Some(payload) => payload,
+            None => return Ok(None),
+        };
+        let payload = payload
+            .strip_suffix('}')
+            .ok_or(LogParseError::PatternError { expected: "}" })?;
+
+        let mut fields = HashMap::new();
+        for field in split_fields(payload) {
+            let (name, value) = field
+                .split_once(':')
+                .ok_or(LogParseError::PatternError { expected: ":" })?;
+            fields.insert(name.trim(), value.trim());
+        }
+
+        let get = |field: &'static str| {
+            fields
+                .get(field)
+                .copied()
+                .ok_or(LogParseError::MissingFieldError(field))
+        };
+
+        Ok(Some(Self {
+            time: log.time(),
+            id: get("id")?.to_string(),
+            name: get("name").ok().map(str::to_string),
+            position: parse_vec3("pos", get("pos")?)?,
+            velocity: get("vel")
+                .ok()
+                .map(|vel| parse_vec3("vel", vel))
+                .transpose()?,
+            mass: get("mass")
+                .ok()
+                .map(|mass| parse_f64("mass", mass))
+                .transpose()?,
+        }))
+    }
+
+    pub fn to_csv(&self, start: DateTime<FixedOffset>) -> String {
+        //! The record as a row of a CSV file, with the time it was logged
+        //! and the seconds since `start`. Numbers are written as they are
+        //! logged, and the velocity and mass are left empty if they weren't
+
+        let elapsed = (self.time - start)
+            .num_microseconds()
+            .map_or(f64::NAN, |microseconds| microseconds as f64 / 1e6);
+        let [x, y, z] = self.position.map(|v| format!("{:?}", v));
+        let [vx, vy, vz] = self
+            .velocity
+            .map_or([(); 3].map(|_| String::new()), |velocity| {
+                velocity.map(|v| format!("{:?}", v))
+            });
+        let mass = self
+            .mass
+            .map_or(String::new(), |mass| format!("{:?}", mass));
+
+        [
+            self.time.to_rfc3339(),
+            format!("{:?}", elapsed),
+            x,
+            y,
+            z,
+            vx,
+            vy,
+            vz,
+            mass,
+        ]
+        .join(",")
+    }
+}
+
+fn split_fields(payload: &str) -> Vec<&str> {
+    //! Split a payload at the commas between its fields, leaving the commas
+    //! between the components of vectors
+
+    let mut fields = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in payload.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                fields.push(&payload[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&payload[start..]);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BodyLogRecord;
+    use crate::Log;
+
+    #[test]
+    fn test_body_record_from_printer_log() {
+        let log = Log::from_str(
+            "time=2022-08-14T15:02:11+01:00 target=solar_sim_core::components level=INFO msg=\"body{id:earth,name:Earth,pos:Vector3 [149600000000.0, 0.0, -1.5],vel:Vector3 [0.0, 29780.0, 0.0],mass:5.972e24}\"",
+        )
+        .unwrap();
+
+        let body = BodyLogRecord::from_log(&log).unwrap().unwrap();
+        assert_eq!("earth", body.id);
+        assert_eq!(Some("Earth"), body.name.as_deref());
+        assert_eq!([149600000000.0, 0.0, -1.5], body.position);
+        assert_eq!(Some([0.0, 29780.0, 0.0]), body.velocity);
+        assert_eq!(Some(5.972e24), body.mass);
+        assert_eq!(log.time(), body.time);
+        assert_eq!(
+            "2022-08-14T15:02:11+01:00,1.0,149600000000.0,0.0,-1.5,0.0,29780.0,0.0,5.972e24",
+            body.to_csv(log.time() - chrono::Duration::seconds(1))
+        );
+
+        let other = Log::from_str(
+            "time=2022-08-14T15:02:11+01:00 target=college_coursework level=INFO msg=Started",
+        )
+        .unwrap();
+        assert_eq!(None, BodyLogRecord::from_log(&other).unwrap());
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::Display;
+
+/// Something noticed while parsing a log, which didn't stop it being parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    /// The format a log was found to be written in
+    FormatDetected(&'static str),
+    /// The fields found in a log, before they are read
+    Fields(Vec<String>),
+    /// A field which isn't one of the known ones, kept in `Log::other`
+    UnknownField { name: String, value: String },
+    /// A value which was changed to fit its field
+    Coerced {
+        field: String,
+        from: String,
+        to: String,
+    },
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FormatDetected(format) => write!(f, "Detected the {} format", format),
+            Self::Fields(fields) => write!(f, "Found the fields: {}", fields.join(", ")),
+            Self::UnknownField { name, value } => {
+                write!(f, "Unknown field: `{}` with the value {:?}", name, value)
+            }
+            Self::Coerced { field, from, to } => {
+                write!(
+                    f,
+                    "Coerced the field: `{}` from {:?} to {:?}",
+                    field, from, to
+                )
+            }
+        }
+    }
+}
+
+/// Where diagnostics are reported while parsing, which ignores them unless
+/// a consumer subscribes with a callback
+#[derive(Default)]
+pub struct Diagnostics<'a> {
+    callback: Option<Box<dyn FnMut(Diagnostic) + Send + 'a>>,
+}
+impl<'a> Diagnostics<'a> {
+    pub fn new(callback: impl FnMut(Diagnostic) + Send + 'a) -> Self {
+        Self {
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn report(&mut self, diagnostic: impl FnOnce() -> Diagnostic) {
+        //! Report a diagnostic, which is only built if anything is subscribed
+
+        if let Some(callback) = &mut self.callback {
+            callback(diagnostic());
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use crate::{Log, LogParseError};
+
+impl Log {
+    fn get(&self, field: &str) -> Option<String> {
+        //! The text of a field which isn't one of the known ones
+        Some(unquote(self.other.get(field)?))
+    }
+
+    pub fn get_f64(&self, field: &str) -> Result<Option<f64>, LogParseError> {
+        //! A field read as a number, or none if the log doesn't have it
+        self.get(field)
+            .map(|value| parse_f64(field, &value))
+            .transpose()
+    }
+
+    pub fn get_vec3(&self, field: &str) -> Result<Option<[f64; 3]>, LogParseError> {
+        //! A field read as a vector, written like `[1.0, 2.0, 3.0]` or as the
+        //! `Debug` output of a cgmath `Vector3`
+        self.get(field)
+            .map(|value| parse_vec3(field, &value))
+            .transpose()
+    }
+
+    pub fn get_duration(&self, field: &str) -> Result<Option<Duration>, LogParseError> {
+        //! A field read as a duration, written like the `Debug` output of a
+        //! `Duration` such as `1.5s` or `250ms`, or as a number of seconds
+        self.get(field)
+            .map(|value| parse_duration(field, &value))
+            .transpose()
+    }
+}
+
+pub(crate) fn unquote(value: &str) -> String {
+    //! The text of a value read from logfmt, without the quotes around it and
+    //! with its escapes decoded if it was escaped. Only the first line is
+    //! quoted, any lines continuing it such as a backtrace are kept as written
+
+    let (first, rest) = match value.split_once('\n') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (value, None),
+    };
+
+    let mut text = match first
+        .strip_prefix('"')
+        .and_then(|first| first.strip_suffix('"'))
+    {
+        Some(inner) => unescape(inner),
+        None => first.to_string(),
+    };
+    if let Some(rest) = rest {
+        text.push('\n');
+        text.push_str(rest);
+    }
+
+    text
+}
+
+fn unescape(value: &str) -> String {
+    //! Decode the escapes `Debug` writes in a string, keeping any backslash
+    //! which doesn't start one
+
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('0') => text.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => text.push(c),
+            // Written like `\u{1b}`
+            Some('u') => {
+                let rest = chars.as_str();
+                let escaped = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .and_then(|(code, rest)| {
+                        let c = char::from_u32(u32::from_str_radix(code, 16).ok()?)?;
+                        Some((c, rest))
+                    });
+                match escaped {
+                    Some((c, rest)) => {
+                        text.push(c);
+                        chars = rest.chars();
+                    }
+                    None => text.push_str("\\u"),
+                }
+            }
+            Some(c) => {
+                text.push('\\');
+                text.push(c);
+            }
+            None => text.push('\\'),
+        }
+    }
+
+    text
+}
+
+pub(crate) fn parse_f64(field: &str, value: &str) -> Result<f64, LogParseError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|err| LogParseError::FloatParseError {
+            field: field.to_string(),
+            source: err,
+        })
+}
+
+pub(crate) fn parse_vec3(field: &str, value: &str) -> Result<[f64; 3], LogParseError> {
+    //! Parse three numbers separated by commas, optionally in brackets
+    //! and named as a `Vector3`
+
+    let error = || LogParseError::VectorParseError {
+        field: field.to_string(),
+        value: value.to_string(),
+    };
+
+    let inner = value.trim();
+    let inner = inner.strip_prefix("Vector3").unwrap_or(inner).trim();
+    let inner = inner
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .or_else(|| {
+            inner
+                .strip_prefix('(')
+                .and_then(|inner| inner.strip_suffix(')'))
+        })
+        .unwrap_or(inner);
+
+    let mut components = inner.split(',');
+    let mut vector = [0.0; 3];
+    for component in vector.iter_mut() {
+        *component = components
+            .next()
+            .and_then(|component| component.trim().parse().ok())
+            .ok_or_else(error)?;
+    }
+    if components.next().is_some() {
+        return Err(error());
+    }
+
+    Ok(vector)
+}
+
+pub(crate) fn parse_duration(field: &str, value: &str) -> Result<Duration, LogParseError> {
+    let error = || LogParseError::DurationParseError {
+        field: field.to_string(),
+        value: value.to_string(),
+    };
+
+    let value = value.trim();
+    let unit_start = value
+        .find(|c: char| c.is_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+
+    let seconds_per_unit = match unit {
+        "ns" => 1e-9,
+        "µs" | "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" | "" => 1.0,
+        _ => return Err(error()),
+    };
+    let seconds = number.parse::<f64>().map_err(|_| error())? * seconds_per_unit;
+
+    // Durations can't be negative, or longer than a u64 of seconds
+    if !(0.0..u64::MAX as f64).contains(&seconds) {
+        return Err(error());
+    }
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::unquote;
+    use crate::{Log, LogParseError};
+
+    #[test]
+    fn test_typed_getters() {
+        let log = Log::from_str(
+            "time=2022-08-14T15:02:11+01:00 target=college_coursework level=INFO msg=Step dt=250ms scale=86400 pos=\"Vector3 [1.5, -2.0, 3e11]\" bad=fast",
+        )
+        .unwrap();
+
+        assert_eq!(Some(86400.0), log.get_f64("scale").unwrap());
+        assert_eq!(Some([1.5, -2.0, 3e11]), log.get_vec3("pos").unwrap());
+        assert_eq!(
+            Some(Duration::from_millis(250)),
+            log.get_duration("dt").unwrap()
+        );
+        assert_eq!(None, log.get_f64("missing").unwrap());
+
+        assert!(matches!(
+            log.get_f64("bad"),
+            Err(LogParseError::FloatParseError { .. })
+        ));
+        assert!(matches!(
+            log.get_vec3("scale"),
+            Err(LogParseError::VectorParseError { .. })
+        ));
+        assert!(matches!(
+            log.get_duration("bad"),
+            Err(LogParseError::DurationParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unquote_decodes_debug_escapes() {
+        assert_eq!(
+            "a \\ \"b\"\n\tc\u{1b}[0m 'd'",
+            unquote(r#""a \\ \"b\"\n\tc\u{1b}[0m \'d\'""#)
+        );
+        assert_eq!(r"C:\Users", unquote(r"C:\Users"));
+        assert_eq!(r"\q \u{zz}", unquote(r#""\q \u{zz}""#));
+
+        // Only the first line is quoted, the rest are kept as they are
+        assert_eq!(
+            "Slow \"frame\"\n\tat \"frame\" 12",
+            unquote("\"Slow \\\"frame\\\"\"\n\tat \"frame\" 12")
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, FixedOffset};
+
+use crate::Log;
+
+/// Which logs are kept, every log is kept unless a part of the filter is set
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// The least severe level kept
+    pub level: Option<log::Level>,
+    /// A pattern the target must match, where `*` matches any text and `?`
+    /// any one character
+    pub target: Option<String>,
+    /// The earliest time kept
+    pub since: Option<DateTime<FixedOffset>>,
+    /// The latest time kept
+    pub until: Option<DateTime<FixedOffset>>,
+}
+impl Filter {
+    pub fn matches(&self, log: &Log) -> bool {
+        //! Whether a log passes every part of the filter
+
+        // More severe levels compare as less than others
+        self.level.into_iter().all(|level| log.level() <= level)
+            && self
+                .target
+                .iter()
+                .all(|pattern| glob_matches(pattern, log.target()))
+            && self.since.into_iter().all(|since| log.time() >= since)
+            && self.until.into_iter().all(|until| log.time() <= until)
+    }
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    //! Whether the whole of some text matches a glob pattern
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // Where to go back to if the rest doesn't match, letting the last `*`
+    // seen match one more character
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    // Any stars left can match nothing
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_matches, Filter};
+    use crate::Log;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches(
+            "college_coursework::*",
+            "college_coursework::simulation"
+        ));
+        assert!(glob_matches(
+            "*simulation*",
+            "college_coursework::simulation::nbody"
+        ));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("college_coursework::*", "wgpu_core::device"));
+        assert!(!glob_matches("a?c", "ac"));
+    }
+
+    #[test]
+    fn test_filter_by_level_target_and_time() {
+        let log = |time: &str, target: &str, level: &str| {
+            Log::from_str(&format!(
+                "time={} target={} level={} msg=Test",
+                time, target, level
+            ))
+            .unwrap()
+        };
+
+        let filter = Filter {
+            level: Some(log::Level::Warn),
+            target: Some("college_coursework::simulation*".to_string()),
+            since: Some("2022-08-14T15:00:00+01:00".parse().unwrap()),
+            until: Some("2022-08-14T16:00:00+01:00".parse().unwrap()),
+        };
+
+        let simulation = "college_coursework::simulation";
+        assert!(filter.matches(&log("2022-08-14T15:30:00+01:00", simulation, "ERROR")));
+        assert!(filter.matches(&log("2022-08-14T15:30:00+01:00", simulation, "WARN")));
+        assert!(!filter.matches(&log("2022-08-14T15:30:00+01:00", simulation, "INFO")));
+        assert!(!filter.matches(&log("2022-08-14T15:30:00+01:00", "wgpu_core", "WARN")));
+        assert!(!filter.matches(&log("2022-08-14T14:59:59+01:00", simulation, "WARN")));
+        assert!(!filter.matches(&log("2022-08-14T16:00:01+01:00", simulation, "WARN")));
+
+        assert!(Filter::default().matches(&log("2022-08-14T15:30:00+01:00", "a", "TRACE")));
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+};
+
+/// The name the simulator's logs are written under
+const APPLICATION_NAME: &str = "college-coursework";
+
+/// How long to wait before checking for more logs once the end of the file
+/// has been reached
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn log_dir() -> Option<PathBuf> {
+    //! The directory the simulator writes its logs to, found in the same
+    //! way as the simulator does
+
+    let log_dir = env::var("SS_LOG_DIR").map(PathBuf::from);
+
+    #[cfg(target_family = "unix")]
+    let log_dir = log_dir
+        .or_else(|_| {
+            env::var("XDG_STATE_HOME").map(|v| PathBuf::from(v).join(APPLICATION_NAME).join("logs"))
+        })
+        .or_else(|_| {
+            env::var("HOME").map(|v| {
+                PathBuf::from(v)
+                    .join(".local")
+                    .join("state")
+                    .join(APPLICATION_NAME)
+                    .join("logs")
+            })
+        });
+
+    #[cfg(target_family = "windows")]
+    let log_dir = log_dir.or_else(|_| {
+        env::var("appdata").map(|v| PathBuf::from(v).join(APPLICATION_NAME).join("logs"))
+    });
+
+    log_dir.ok()
+}
+
+fn is_log_file(path: &Path) -> bool {
+    //! Whether a file is one of the simulator's daily logs, named
+    //! `%Y-%m-%d.college-coursework.log`
+
+    path.to_string_lossy()
+        .ends_with(&format!(".{}.log", APPLICATION_NAME))
+}
+
+/// Reads the lines of a log file as they are written, like `tail -f`
+pub struct Follow {
+    /// The directory of daily log files being followed, if not following a
+    /// single file
+    dir: Option<PathBuf>,
+    path: Option<PathBuf>,
+    reader: Option<BufReader<File>>,
+    /// The start of a line which hasn't been finished yet
+    partial: String,
+}
+impl Follow {
+    pub async fn file(path: PathBuf) -> io::Result<Self> {
+        //! Follow a single file, from its start
+
+        Ok(Self {
+            dir: None,
+            reader: Some(BufReader::new(File::open(&path).await?)),
+            path: Some(path),
+            partial: String::new(),
+        })
+    }
+
+    pub async fn dir(dir: PathBuf) -> io::Result<Self> {
+        //! Follow the simulator's daily log files in a directory, from the
+        //! start of the latest one, moving on to each new day's file once it
+        //! is created
+
+        let mut follow = Self {
+            dir: Some(dir),
+            path: None,
+            reader: None,
+            partial: String::new(),
+        };
+        if let Some(latest) = follow.newer_file().await? {
+            follow.open(latest).await?;
+        }
+
+        Ok(follow)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        //! The file currently being read, if there is one yet
+        self.path.as_deref()
+    }
+
+    async fn open(&mut self, path: PathBuf) -> io::Result<()> {
+        self.reader = Some(BufReader::new(File::open(&path).await?));
+        self.path = Some(path);
+        Ok(())
+    }
+
+    async fn newer_file(&self) -> io::Result<Option<PathBuf>> {
+        //! The latest daily log file in the directory, if it is newer than
+        //! the one being read. The files are named by date, so the latest
+        //! sorts last
+
+        let dir = match &self.dir {
+            Some(dir) => dir,
+            None => return Ok(None),
+        };
+
+        let mut latest: Option<PathBuf> = None;
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if is_log_file(&path) && latest.iter().all(|latest| &path > latest) {
+                latest = Some(path);
+            }
+        }
+
+        Ok(latest.filter(|latest| self.path.iter().all(|path| latest > path)))
+    }
+
+    async fn read_whole_line(&mut self, buf: &mut String) -> io::Result<Option<usize>> {
+        //! Read a line if the whole of it has been written, keeping the start
+        //! of it for next time if not
+
+        if let Some(reader) = &mut self.reader {
+            reader.read_line(&mut self.partial).await?;
+            if self.partial.ends_with('\n') {
+                buf.push_str(&self.partial);
+                let read = self.partial.len();
+                self.partial.clear();
+                return Ok(Some(read));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub async fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        //! Read the next line into `buf`, returning its length. If nothing
+        //! more has been written this waits a short while and returns 0, so
+        //! the caller can finish what it has read so far
+
+        if let Some(read) = self.read_whole_line(buf).await? {
+            return Ok(read);
+        }
+
+        // The simulator may have moved on to the next day's file
+        if let Some(newer) = self.newer_file().await? {
+            // Anything written to the old file before the new one was
+            // created is read first
+            if let Some(read) = self.read_whole_line(buf).await? {
+                return Ok(read);
+            }
+            self.open(newer).await?;
+
+            // The last line of the old file will never be finished
+            if !self.partial.is_empty() {
+                self.partial.push('\n');
+                buf.push_str(&self.partial);
+                let read = self.partial.len();
+                self.partial.clear();
+                return Ok(read);
+            }
+            return Ok(0);
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::Follow;
+
+    #[tokio::test]
+    async fn test_follow_moves_on_to_the_next_day() {
+        let dir = std::env::temp_dir().join(format!("log-parser-follow-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Named the way the simulator's logger names them, which adds the
+        // date onto the end of the directory with a trailing separator
+        let log_file = |date: &str| {
+            let mut path = dir.join("").into_os_string();
+            path.push(format!("{}.college-coursework.log", date));
+            std::path::PathBuf::from(path)
+        };
+        let first = log_file("2022-08-14");
+        let second = log_file("2022-08-15");
+        assert_eq!(Some(dir.as_path()), first.parent());
+        std::fs::write(&first, "first\nunfini").unwrap();
+        std::fs::write(dir.join("unrelated.txt"), "ignored\n").unwrap();
+
+        let mut follow = Follow::dir(dir.clone()).await.unwrap();
+        let mut buf = String::new();
+        assert_eq!(6, follow.read_line(&mut buf).await.unwrap());
+        assert_eq!("first\n", buf);
+
+        // Half a line is kept until the rest of it is written
+        buf.clear();
+        assert_eq!(0, follow.read_line(&mut buf).await.unwrap());
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&first)
+            .unwrap()
+            .write_all(b"shed\n")
+            .unwrap();
+        follow.read_line(&mut buf).await.unwrap();
+        assert_eq!("unfinished\n", buf);
+
+        std::fs::write(&second, "second\n").unwrap();
+        buf.clear();
+        assert_eq!(0, follow.read_line(&mut buf).await.unwrap());
+        assert_eq!(Some(second.as_path()), follow.path());
+        follow.read_line(&mut buf).await.unwrap();
+        assert_eq!("second\n", buf);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
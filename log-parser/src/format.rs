@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use chrono::DateTime;
+
+use crate::{parse_log_to_map, Diagnostic, Diagnostics, Log, LogParseError};
+
+/// A way logs can be written, parsed into the same `Log` whichever is used
+pub trait InputFormat {
+    fn name(&self) -> &'static str;
+
+    /// Whether a log looks like it is written in this format
+    fn detect(&self, log: &str) -> bool;
+
+    /// Parse a single log, along with any lines continuing it
+    fn parse_log(&self, log: &str) -> Result<Log, LogParseError> {
+        self.parse_log_with(log, &mut Diagnostics::none())
+    }
+
+    /// Parse a single log, reporting anything noticed along the way
+    fn parse_log_with(
+        &self,
+        log: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Log, LogParseError>;
+
+    /// Parse every log in a file
+    fn parse_file(&self, file: &str) -> Result<Vec<Log>, LogParseError> {
+        split_logs(file)
+            .into_iter()
+            .map(|log| parse_folded(self, &log, &mut Diagnostics::none()))
+            .collect()
+    }
+}
+
+/// `key=value` pairs, as written by the simulator
+pub struct Logfmt;
+impl InputFormat for Logfmt {
+    fn name(&self) -> &'static str {
+        "logfmt"
+    }
+
+    fn detect(&self, log: &str) -> bool {
+        parse_log_to_map(log).is_ok()
+    }
+
+    fn parse_log_with(
+        &self,
+        log: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Log, LogParseError> {
+        Log::from_map_with(parse_log_to_map(log)?, diagnostics)
+    }
+}
+
+/// A JSON object on each line, with the same fields as logfmt
+pub struct JsonLines;
+impl InputFormat for JsonLines {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn detect(&self, log: &str) -> bool {
+        log.trim_start().starts_with('{')
+    }
+
+    fn parse_log_with(
+        &self,
+        log: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Log, LogParseError> {
+        let object: HashMap<String, serde_json::Value> = serde_json::from_str(log)?;
+
+        // Numbers and other values are kept as they were written
+        let map = object
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => {
+                    let text = value.to_string();
+                    diagnostics.report(|| Diagnostic::Coerced {
+                        field: key.clone(),
+                        from: json_type(&value).to_string(),
+                        to: text.clone(),
+                    });
+                    (key, text)
+                }
+            })
+            .collect();
+
+        Log::from_map_with(map, diagnostics)
+    }
+}
+
+/// The default pattern of log4rs, `{d} {l} {t} - {m}{n}`
+pub struct Log4rs;
+impl InputFormat for Log4rs {
+    fn name(&self) -> &'static str {
+        "log4rs"
+    }
+
+    fn detect(&self, log: &str) -> bool {
+        log.contains(" - ")
+            && log
+                .split_whitespace()
+                .next()
+                .is_some_and(|time| DateTime::parse_from_rfc3339(time).is_ok())
+    }
+
+    fn parse_log_with(
+        &self,
+        log: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Log, LogParseError> {
+        let (head, msg) = log
+            .split_once(" - ")
+            .ok_or(LogParseError::PatternError { expected: " - " })?;
+
+        let mut fields = head.split_whitespace();
+        let mut map = HashMap::new();
+        for field in ["time", "level", "target"] {
+            let value = fields
+                .next()
+                .ok_or(LogParseError::MissingFieldError(field))?;
+            map.insert(field.to_string(), value.to_string());
+        }
+        map.insert("msg".to_string(), msg.trim_end().to_string());
+
+        Log::from_map_with(map, diagnostics)
+    }
+}
+
+/// The formats that can be read, chosen by name or detected from the logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Logfmt,
+    Json,
+    Log4rs,
+}
+impl Format {
+    /// Every format, in the order they are tried when detecting one. Logfmt
+    /// is last as it is the least strict
+    pub const ALL: [Self; 3] = [Self::Json, Self::Log4rs, Self::Logfmt];
+
+    pub fn input(&self) -> &'static dyn InputFormat {
+        match self {
+            Self::Logfmt => &Logfmt,
+            Self::Json => &JsonLines,
+            Self::Log4rs => &Log4rs,
+        }
+    }
+
+    pub fn detect(log: &str) -> Self {
+        Self::detect_with(log, &mut Diagnostics::none())
+    }
+
+    pub fn detect_with(log: &str, diagnostics: &mut Diagnostics) -> Self {
+        //! Find the format a log is written in, assuming logfmt if it doesn't
+        //! look like any of them
+
+        let format = Self::ALL
+            .into_iter()
+            .find(|format| format.input().detect(log))
+            .unwrap_or(Self::Logfmt);
+        diagnostics.report(|| Diagnostic::FormatDetected(format.input().name()));
+
+        format
+    }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    //! The name of the type of a JSON value
+
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+pub fn split_logs(file: &str) -> Vec<String> {
+    //! Split a file into its logs, keeping the lines continuing a log with
+    //! it
+
+    let mut logs: Vec<String> = Vec::new();
+    let mut folding = Folding::default();
+
+    for line in file.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match logs.last_mut() {
+            Some(log) if folding.continues(line) => {
+                log.push('\n');
+                log.push_str(line);
+            }
+            _ => logs.push(line.to_string()),
+        }
+    }
+
+    logs
+}
+
+/// Decides which lines continue the log before them rather than starting a
+/// new one
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Folding {
+    /// Whether the lines since the last log were written by a panic
+    panicking: bool,
+}
+impl Folding {
+    pub fn continues(&mut self, line: &str) -> bool {
+        //! Whether a line continues the log before it. Indented lines, such
+        //! as the frames of a backtrace, always do. A panic isn't indented,
+        //! so every line from `thread '...' panicked at` does until the next
+        //! log starts
+
+        if line.starts_with(&[' ', '\t'][..]) {
+            return true;
+        }
+        if line.starts_with("thread '") && line.contains(" panicked at ") {
+            self.panicking = true;
+            return true;
+        }
+        if self.panicking && !starts_log(line) {
+            return true;
+        }
+
+        self.panicking = false;
+        false
+    }
+}
+
+fn starts_log(line: &str) -> bool {
+    //! Whether a line starts a log, even if it is coloured. Lines starting
+    //! with a time do even if the rest of them can't be read, so a log the
+    //! grammar can't parse isn't hidden in the panic before it
+
+    let line = strip_ansi_escapes::strip(line).map_or_else(
+        |_| line.to_string(),
+        |line| String::from_utf8_lossy(&line).into_owned(),
+    );
+    let starts_with_time = line
+        .split_whitespace()
+        .next()
+        .is_some_and(|time| DateTime::parse_from_rfc3339(time).is_ok());
+
+    line.starts_with("time=")
+        || starts_with_time
+        || Format::ALL
+            .into_iter()
+            .any(|format| format.input().detect(&line))
+}
+
+pub(crate) fn parse_folded<F: InputFormat + ?Sized>(
+    format: &F,
+    log: &str,
+    diagnostics: &mut Diagnostics,
+) -> Result<Log, LogParseError> {
+    //! Parse the first line of a log, adding the lines continuing it to its
+    //! message, so the formats only ever read a single line
+
+    let mut lines = log.lines();
+    let mut parsed = format.parse_log_with(lines.next().unwrap_or(""), diagnostics)?;
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        parsed.msg.push('\n');
+        parsed.msg.push_str(line.trim_end());
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, InputFormat as _, JsonLines};
+    use crate::{Diagnostic, Diagnostics};
+
+    #[test]
+    fn test_formats_parse_the_same_log() {
+        let logs = [
+            (
+                Format::Logfmt,
+                "time=2022-08-14T15:02:11.417962+01:00 target=college_coursework level=WARN msg=Slow",
+            ),
+            (
+                Format::Json,
+                r#"{"time":"2022-08-14T15:02:11.417962+01:00","target":"college_coursework","level":"WARN","msg":"Slow"}"#,
+            ),
+            (
+                Format::Log4rs,
+                "2022-08-14T15:02:11.417962+01:00 WARN college_coursework - Slow",
+            ),
+        ];
+
+        for (format, text) in logs {
+            assert_eq!(format, Format::detect(text));
+
+            let log = format.input().parse_log(text).unwrap();
+            assert_eq!("college_coursework", log.target());
+            assert_eq!(log::Level::Warn, log.level());
+            assert_eq!("Slow", log.msg());
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_reported() {
+        let mut reported = Vec::new();
+        JsonLines
+            .parse_log_with(
+                r#"{"time":"2022-08-14T15:02:11+01:00","target":"a","level":"warn","msg":"Slow","frame":12}"#,
+                &mut Diagnostics::new(|diagnostic| reported.push(diagnostic)),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                Diagnostic::Coerced {
+                    field: "frame".to_string(),
+                    from: "number".to_string(),
+                    to: "12".to_string(),
+                },
+                Diagnostic::Fields(
+                    ["frame", "level", "msg", "target", "time"]
+                        .map(String::from)
+                        .to_vec()
+                ),
+                Diagnostic::Coerced {
+                    field: "level".to_string(),
+                    from: "warn".to_string(),
+                    to: "WARN".to_string(),
+                },
+                Diagnostic::UnknownField {
+                    name: "frame".to_string(),
+                    value: "12".to_string(),
+                },
+            ],
+            reported
+        );
+    }
+}
@@ -3,9 +3,30 @@ extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
+mod body;
+mod diagnostics;
+mod fields;
+mod filter;
+mod follow;
+mod format;
+mod output;
+mod pretty;
+mod reader;
+mod stats;
+
+pub use body::BodyLogRecord;
+pub use diagnostics::{Diagnostic, Diagnostics};
+pub use filter::Filter;
+pub use follow::{log_dir, Follow};
+pub use format::{split_logs, Format, InputFormat, JsonLines, Log4rs, Logfmt};
+pub use output::Output;
+pub use pretty::{PrettyPrinter, Theme};
+pub use reader::{LineParser, LogReader};
+pub use stats::{Bucket, Stats};
+
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::num::ParseIntError;
+use std::num::{ParseFloatError, ParseIntError};
 
 use chrono::{DateTime, FixedOffset};
 use pest::Parser;
@@ -31,12 +52,35 @@ pub enum LogParseError {
     TimestampParseError(#[from] chrono::format::ParseError),
     #[error("Unknown option: `{option}` for field: `{field}`")]
     UnknownOptionError { option: String, field: &'static str },
+    #[error("Failed to parse JSON log: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Expected `{expected}` in the log")]
+    PatternError { expected: &'static str },
     #[error("Failed to parse int for field: {field}: {source}")]
     IntegerParseError {
         field: &'static str,
         #[source]
         source: ParseIntError,
     },
+    #[error("Failed to parse float for field: {field}: {source}")]
+    FloatParseError {
+        field: String,
+        #[source]
+        source: ParseFloatError,
+    },
+    #[error("Failed to parse vector for field: {field}: `{value}`")]
+    VectorParseError { field: String, value: String },
+    #[error("Failed to parse duration for field: {field}: `{value}`")]
+    DurationParseError { field: String, value: String },
+    #[error("Failed to read logs: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to parse the log on line {line}: {source}\n{text}")]
+    LineError {
+        line: usize,
+        text: String,
+        #[source]
+        source: Box<LogParseError>,
+    },
 }
 
 #[derive(Debug)]
@@ -56,14 +100,28 @@ pub struct Log {
 }
 impl Log {
     pub fn from_str(s: &str) -> Result<Self, LogParseError> {
-        let map = parse_log_to_map(s)?;
+        Self::from_str_with(s, &mut Diagnostics::none())
+    }
 
-        println!("map: {:?}", map);
+    pub fn from_str_with(s: &str, diagnostics: &mut Diagnostics) -> Result<Self, LogParseError> {
+        //! Parse a log in logfmt, along with any lines continuing it
+        format::parse_folded(&Logfmt, s, diagnostics)
+    }
 
-        Self::from_map(map)
+    pub fn from_map(map: HashMap<String, String>) -> Result<Self, LogParseError> {
+        Self::from_map_with(map, &mut Diagnostics::none())
     }
 
-    pub fn from_map(mut map: HashMap<String, String>) -> Result<Self, LogParseError> {
+    pub fn from_map_with(
+        mut map: HashMap<String, String>,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Self, LogParseError> {
+        diagnostics.report(|| {
+            let mut fields = map.keys().cloned().collect::<Vec<_>>();
+            fields.sort();
+            Diagnostic::Fields(fields)
+        });
+
         #[inline(always)]
         fn get(
             map: &mut HashMap<String, String>,
@@ -73,7 +131,7 @@ impl Log {
                 .ok_or(LogParseError::MissingFieldError(field))
         }
 
-        Ok(Self {
+        let log = Self {
             time: chrono::DateTime::<FixedOffset>::parse_from_rfc3339(
                 get(&mut map, "time")?.as_str(),
             )?,
@@ -106,7 +164,7 @@ impl Log {
             },
             level: {
                 let level = get(&mut map, "level")?;
-                match level.to_lowercase().as_str() {
+                let parsed = match level.to_lowercase().as_str() {
                     "error" => log::Level::Error,
                     "warn" => log::Level::Warn,
                     "info" => log::Level::Info,
@@ -118,11 +176,30 @@ impl Log {
                             field: "level",
                         })
                     }
+                };
+                if level != parsed.as_str() {
+                    diagnostics.report(|| Diagnostic::Coerced {
+                        field: "level".to_string(),
+                        from: level,
+                        to: parsed.as_str().to_string(),
+                    });
                 }
+                parsed
             },
             msg: get(&mut map, "msg")?,
             other: map,
-        })
+        };
+
+        let mut unknown = log.other.iter().collect::<Vec<_>>();
+        unknown.sort();
+        for (name, value) in unknown {
+            diagnostics.report(|| Diagnostic::UnknownField {
+                name: name.clone(),
+                value: value.clone(),
+            });
+        }
+
+        Ok(log)
     }
 
     pub fn time(&self) -> DateTime<FixedOffset> {
@@ -190,10 +267,11 @@ impl Display for Log {
 }
 
 pub fn parse_log_file(file: &str) -> Result<Vec<Log>, LogParseError> {
-    parse_log_file_to_map(file)?
-        .into_iter()
-        .map(|map| Log::from_map(map))
-        .collect()
+    //! Parse every log in a file already read into memory, in the format of
+    //! its first log. Larger files can be read a log at a time with a
+    //! `LogReader`
+
+    LogReader::new(file.as_bytes()).collect()
 }
 
 fn parse_parameter(mut pairs: Pairs<Rule>) -> (String, String) {
@@ -205,17 +283,9 @@ fn parse_parameter(mut pairs: Pairs<Rule>) -> (String, String) {
 
 fn parse_line(pairs: Pairs<Rule>) -> HashMap<String, String> {
     pairs
-        .map(|pair| match pair.as_rule() {
-            Rule::Parameter => parse_parameter(pair.into_inner()),
-            _ => unreachable!(),
-        })
-        .collect()
-}
-
-fn parse_logs(pairs: Pairs<Rule>) -> Vec<HashMap<String, String>> {
-    pairs
-        .map(|pair| match pair.as_rule() {
-            Rule::Line => parse_line(pair.into_inner()),
+        .filter_map(|pair| match pair.as_rule() {
+            Rule::Parameter => Some(parse_parameter(pair.into_inner())),
+            Rule::EOI => None,
             _ => unreachable!(),
         })
         .collect()
@@ -227,8 +297,79 @@ fn parse_log_to_map(log: &str) -> Result<HashMap<String, String>, Error<Rule>> {
     Ok(parse_line(log.into_inner()))
 }
 
-fn parse_log_file_to_map(file: &str) -> Result<Vec<HashMap<String, String>>, Error<Rule>> {
-    let logs = LogParser::parse(Rule::File, file)?.next().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::{parse_log_file, Log, LogReader};
+
+    /// A log written just before a panic, followed by what std wrote to
+    /// stderr for the panic with `RUST_BACKTRACE=1`
+    const PANIC_LOG: &str = "\
+time=2022-08-14T15:02:11.417962+01:00 target=college_coursework::renderer::state level=ERROR msg=\"Failed to render frame\"
+
+thread 'main' (6873) panicked at main.rs:3:11:
+Failed to render frame
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/std/src/panicking.rs:689:5
+   1: core::panicking::panic_fmt
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:80:14
+   2: core::panicking::panic_display::<&str>
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:259:5
+   3: core::option::expect_failed
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/option.rs:2245:5
+   4: core::option::Option<T>::expect
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/option.rs:971:21
+   5: main::main
+             at ./main.rs:3:11
+   6: core::ops::function::FnOnce::call_once
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/ops/function.rs:250:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+time=2022-08-14T15:02:11.418204+01:00 target=college_coursework level=INFO msg=\"Exiting\"
+";
+
+    #[test]
+    fn test_backtrace_folded_into_message() {
+        let logs = parse_log_file(PANIC_LOG).unwrap();
 
-    Ok(parse_logs(logs.into_inner()))
+        assert_eq!(2, logs.len());
+        let msg = logs[0].msg();
+        assert!(msg.starts_with(
+            "\"Failed to render frame\"
+thread 'main' (6873) panicked at main.rs:3:11:
+Failed to render frame
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+"
+        ));
+        assert!(msg.ends_with("\nnote: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace."));
+        assert_eq!(19, msg.lines().count());
+        assert_eq!("\"Exiting\"", logs[1].msg());
+
+        // The next log ends the panic even if its message has escaped quotes
+        let escaped = PANIC_LOG.replace(r#"msg="Exiting""#, r#"msg="Exiting \"now\"""#);
+        let logs = parse_log_file(&escaped).unwrap();
+        assert_eq!(2, logs.len());
+        assert_eq!(r#""Exiting \"now\"""#, logs[1].msg());
+
+        // or if it can't be read at all
+        let broken = PANIC_LOG.replace(r#"msg="Exiting""#, r#"msg="Exiting now"#);
+        let results = LogReader::new(broken.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(2, results.len());
+        assert!(results[1].is_err());
+
+        // Panics written in the older format, with the message on the first
+        // line, are folded in the same way
+        let old = "time=2022-08-14T15:02:11+01:00 target=a level=ERROR msg=Failed\nthread 'main' panicked at 'Failed', src/main.rs:3:11\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        assert_eq!(1, parse_log_file(old).unwrap().len());
+    }
+
+    #[test]
+    fn test_single_multiline_log() {
+        let log = Log::from_str(
+            "time=2022-08-14T15:02:11.417962+01:00 target=college_coursework level=WARN msg=\"Slow frame\"\r\n\tat frame 12\r\n",
+        )
+        .unwrap();
+
+        assert_eq!("\"Slow frame\"\n\tat frame 12", log.msg());
+    }
 }
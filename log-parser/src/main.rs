@@ -1,42 +1,427 @@
-use log_parser::{Log, LogParseError};
-use tokio::io::{self, AsyncBufReadExt, BufReader};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, FixedOffset};
+use log_parser::{
+    BodyLogRecord, Diagnostics, Filter, Follow, Format, LineParser, Log, LogParseError, Output,
+    PrettyPrinter, Stats, Theme,
+};
+use tokio::io::{self, AsyncBufRead, AsyncBufReadExt, BufReader};
 
 use clap::Parser;
 
+/// The format of the logs read, as given on the command line
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum FormatArg {
+    /// Detect the format from the first log
+    Auto,
+    Logfmt,
+    Json,
+    Log4rs,
+}
+impl FormatArg {
+    fn format(&self) -> Option<Format> {
+        match self {
+            Self::Auto => None,
+            Self::Logfmt => Some(Format::Logfmt),
+            Self::Json => Some(Format::Json),
+            Self::Log4rs => Some(Format::Log4rs),
+        }
+    }
+}
+
+/// How the parsed logs are written, as given on the command line
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum OutputArg {
+    Pretty,
+    /// A JSON object on each line
+    Json,
+    Logfmt,
+}
+impl OutputArg {
+    fn output(&self) -> Output {
+        match self {
+            Self::Pretty => Output::Pretty,
+            Self::Json => Output::Json,
+            Self::Logfmt => Output::Logfmt,
+        }
+    }
+}
+
+/// The colours pretty printed logs are written in, as given on the command
+/// line
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ThemeArg {
+    /// For terminals with a dark background
+    Dark,
+    /// For terminals with a light background
+    Light,
+    /// Without colours
+    Plain,
+}
+impl ThemeArg {
+    fn theme(&self) -> Theme {
+        match self {
+            Self::Dark => Theme::Dark,
+            Self::Light => Theme::Light,
+            Self::Plain => Theme::Plain,
+        }
+    }
+}
+
+/// When pretty printed logs are coloured, as given on the command line
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ColourArg {
+    /// When printing to a terminal and `NO_COLOR` isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+fn parse_level(level: &str) -> Result<log::Level, String> {
+    level
+        .parse()
+        .map_err(|_| "expected one of error, warn, info, debug or trace".to_string())
+}
+
+fn parse_time(time: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(time).map_err(|err| format!("{} (expected RFC 3339)", err))
+}
+
+/// Where the logs are read from
+enum Input {
+    Reader(Box<dyn AsyncBufRead + Unpin + Send>),
+    Follow(Follow),
+}
+impl Input {
+    async fn open(args: &InputArgs, follow: bool) -> Result<Self, LogParseError> {
+        //! Open the file given, or the simulator's log directory if following
+        //! without one
+
+        let stdin = args.file.as_os_str() == "-";
+
+        Ok(match (follow, stdin) {
+            (false, true) => Self::Reader(Box::new(BufReader::new(io::stdin()))),
+            (false, false) => Self::Reader(Box::new(BufReader::new(
+                tokio::fs::File::open(&args.file).await?,
+            ))),
+            (true, true) => Self::Follow(
+                Follow::dir(log_parser::log_dir().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "No log directory could be found")
+                })?)
+                .await?,
+            ),
+            (true, false) if args.file.is_dir() => {
+                Self::Follow(Follow::dir(args.file.clone()).await?)
+            }
+            (true, false) => Self::Follow(Follow::file(args.file.clone()).await?),
+        })
+    }
+
+    async fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        //! Read a line into `buf`, returning 0 at the end of the logs, or when
+        //! following if nothing new has been written for a while
+        match self {
+            Self::Reader(reader) => reader.read_line(buf).await,
+            Self::Follow(follow) => follow.read_line(buf).await,
+        }
+    }
+}
+
+// Where the logs are read from and which of them are used, shared by every
+// command. Not a doc comment, as clap would use it to describe the program
+#[derive(clap::Args, Debug)]
+struct InputArgs {
+    /// The file to read, or `-` to read stdin
+    #[clap(default_value = "-")]
+    file: PathBuf,
+    /// The format the logs are written in
+    #[clap(long, value_enum, default_value = "auto")]
+    format: FormatArg,
+    /// Only use logs at this level or more severe
+    #[clap(long, value_parser = parse_level)]
+    level: Option<log::Level>,
+    /// Only use logs whose target matches this pattern, where `*` matches
+    /// any text and `?` any one character
+    #[clap(long)]
+    target: Option<String>,
+    /// Only use logs from this time on, such as 2022-08-14T15:00:00+01:00
+    #[clap(long, value_parser = parse_time)]
+    since: Option<DateTime<FixedOffset>>,
+    /// Only use logs up to this time
+    #[clap(long, value_parser = parse_time)]
+    until: Option<DateTime<FixedOffset>>,
+    /// Print what was noticed while parsing each log to stderr
+    #[clap(long)]
+    diagnostics: bool,
+    /// Stop at the first log which can't be parsed, rather than reporting it
+    /// and carrying on
+    #[clap(long)]
+    strict: bool,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Summarise the logs: how many there are at each level and from each
+    /// target, how often errors happened and the most frequent messages
+    Stats {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// The length of the spans of time the error rate is counted over (in
+        /// seconds)
+        #[clap(long, default_value = "60")]
+        bucket: i64,
+        /// The number of the most frequent messages shown
+        #[clap(long, default_value = "10")]
+        top: usize,
+        /// Print the summary as JSON rather than tables
+        #[clap(long)]
+        json: bool,
+    },
+    /// Write the state of each body logged by the simulator to a CSV file
+    /// for each body, named by its id
+    ExtractBodies {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// The directory to write the files to
+        #[clap(long, short, default_value = ".")]
+        out_dir: PathBuf,
+        /// Only write the bodies with these ids
+        #[clap(long = "body")]
+        bodies: Vec<String>,
+    },
+}
+
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
-    #[clap(default_value = "-")]
-    file: std::path::PathBuf,
+    #[clap(flatten)]
+    input: InputArgs,
+    /// Keep reading logs as they are written. If the file is a directory, or
+    /// isn't given, the simulator's daily logs in it, or in its log
+    /// directory, are followed from one day to the next
+    #[clap(long, short)]
+    follow: bool,
+    /// The format the logs are written out in
+    #[clap(long, value_enum, default_value = "pretty")]
+    output: OutputArg,
+    /// The colours pretty printed logs are written in
+    #[clap(long, value_enum, default_value = "dark")]
+    theme: ThemeArg,
+    /// When to colour pretty printed logs
+    #[clap(long, value_enum, default_value = "auto")]
+    colour: ColourArg,
+    /// Show the time of each pretty printed log from the first, rather than
+    /// as a date
+    #[clap(long)]
+    relative: bool,
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
 #[tokio::main]
-async fn main() -> Result<(), LogParseError> {
-    let stdin = io::stdin();
-    let mut reader = BufReader::new(stdin);
+async fn main() {
+    let args = Args::parse();
 
-    let mut buffer = String::new();
+    if let Err(err) = run(args).await {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
 
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+async fn run(args: Args) -> Result<(), LogParseError> {
+    match args.command {
+        None => {
+            let output = args.output.output();
+            let colour = match args.colour {
+                ColourArg::Auto => PrettyPrinter::colour_enabled(atty::Stream::Stdout),
+                ColourArg::Always => true,
+                ColourArg::Never => false,
+            };
+            let mut pretty = PrettyPrinter::new(if colour {
+                args.theme.theme()
+            } else {
+                Theme::Plain
+            })
+            .with_relative_time(args.relative);
 
-    let task = tokio::spawn(async move {
-        while let Some(log) = rx.recv().await {
-            let log = Log::from_str(
-                &String::from_utf8(strip_ansi_escapes::strip(&log).unwrap()).unwrap(),
-            )
-            .unwrap();
+            read_logs(&args.input, args.follow, |log| {
+                match output {
+                    Output::Pretty => println!("{}", pretty.write(&log)),
+                    output => println!("{}", output.write(&log)),
+                }
+                Ok(())
+            })
+            .await
+        }
+        Some(Command::Stats {
+            input,
+            bucket,
+            top,
+            json,
+        }) => {
+            let mut stats = Stats::new(chrono::Duration::seconds(bucket), top);
+            read_logs(&input, false, |log| {
+                stats.add(&log);
+                Ok(())
+            })
+            .await?;
 
-            println!("{}", log);
+            if json {
+                println!("{}", stats.to_json());
+            } else {
+                print!("{}", stats);
+            }
+            Ok(())
         }
-    });
+        Some(Command::ExtractBodies {
+            input,
+            out_dir,
+            bodies,
+        }) => extract_bodies(&input, &out_dir, &bodies).await,
+    }
+}
 
-    loop {
-        match reader.read_line(&mut buffer).await {
-            Ok(0) => return Ok(()),
-            Ok(_) => tx.send(buffer.clone()).unwrap(),
-            Err(_) => break,
+async fn extract_bodies(
+    args: &InputArgs,
+    out_dir: &Path,
+    bodies: &[String],
+) -> Result<(), LogParseError> {
+    //! Write the trajectory of each body to `<id>.csv` in a directory
+
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut files = HashMap::new();
+    let mut start = None;
+    let mut records = 0;
+
+    read_logs(args, false, |log| {
+        let record = match BodyLogRecord::from_log(&log) {
+            Ok(Some(record)) => record,
+            Ok(None) => return Ok(()),
+            Err(err) if args.strict => return Err(err),
+            Err(err) => {
+                eprintln!("Failed to read the body logged at {}: {}", log.time(), err);
+                return Ok(());
+            }
+        };
+        if !bodies.is_empty() && !bodies.contains(&record.id) {
+            return Ok(());
+        }
+
+        let file = match files.entry(record.id.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                // Ids are written by the simulator, but shouldn't be able to
+                // escape the directory
+                let name = record
+                    .id
+                    .chars()
+                    .map(|c| {
+                        if c.is_alphanumeric() || c == '-' || c == '_' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>();
+                let mut file = BufWriter::new(File::create(out_dir.join(format!("{}.csv", name)))?);
+                writeln!(file, "{}", BodyLogRecord::CSV_HEADER)?;
+                entry.insert(file)
+            }
+        };
+
+        // Times are from the first body in the logs, so every file shares them
+        let start = *start.get_or_insert(record.time);
+        writeln!(file, "{}", record.to_csv(start))?;
+        records += 1;
+
+        Ok(())
+    })
+    .await?;
+
+    for file in files.values_mut() {
+        file.flush()?;
+    }
+    eprintln!(
+        "Wrote {} records of {} bodies to {}",
+        records,
+        files.len(),
+        out_dir.display()
+    );
+
+    Ok(())
+}
+
+async fn read_logs(
+    args: &InputArgs,
+    follow: bool,
+    mut use_log: impl FnMut(Log) -> Result<(), LogParseError>,
+) -> Result<(), LogParseError> {
+    //! Read every log, passing on those which pass the filter given and
+    //! reporting those which can't be parsed. Reading stops at the first
+    //! error returned for a log
+
+    let mut input = Input::open(args, follow).await?;
+
+    let filter = Filter {
+        level: args.level,
+        target: args.target.clone(),
+        since: args.since,
+        until: args.until,
+    };
+    let diagnostics = if args.diagnostics {
+        Diagnostics::new(|diagnostic| eprintln!("{}", diagnostic))
+    } else {
+        Diagnostics::none()
+    };
+    let mut parser = LineParser::new()
+        .with_format(args.format.format())
+        .with_diagnostics(diagnostics);
+
+    let mut skipped = 0;
+    let mut handle = |result: Result<Log, LogParseError>| match result {
+        Ok(log) => {
+            if filter.matches(&log) {
+                use_log(log)?;
+            }
+            Ok(())
+        }
+        // The rest of the logs are still read unless every one has to be
+        // parsed
+        Err(err) if args.strict => Err(err),
+        Err(err) => {
+            eprintln!("{}", err);
+            skipped += 1;
+            Ok(())
         }
+    };
+
+    let mut buffer = String::new();
+    loop {
+        let result = match input.read_line(&mut buffer).await? {
+            // Nothing more has been written yet, so the log being read is
+            // finished
+            0 if follow => parser.finish(),
+            0 => break,
+            _ => parser.push_line(&buffer),
+        };
         buffer.clear();
+
+        if let Some(result) = result {
+            handle(result)?;
+        }
+    }
+    if let Some(result) = parser.finish() {
+        handle(result)?;
+    }
+
+    if skipped > 0 {
+        eprintln!("Skipped {} logs which couldn't be parsed", skipped);
     }
 
     Ok(())
@@ -0,0 +1,159 @@
+use crate::{fields::unquote, Log, PrettyPrinter, Theme};
+
+/// The ways logs can be written back out once parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// For reading in a terminal, without colours, use a `PrettyPrinter`
+    /// to line logs up with each other and colour them
+    Pretty,
+    /// A JSON object on each line, for piping into jq
+    Json,
+    /// `key=value` pairs, as written by the simulator
+    Logfmt,
+}
+impl Output {
+    pub fn write(&self, log: &Log) -> String {
+        //! Write a log in this format, without a trailing newline
+
+        match self {
+            Self::Pretty => PrettyPrinter::new(Theme::Plain).write(log),
+            Self::Json => log.to_json().to_string(),
+            Self::Logfmt => log.to_logfmt(),
+        }
+    }
+}
+
+impl Log {
+    fn fields(&self) -> Vec<(&str, String)> {
+        //! Every field of the log as text, the known ones first in the order
+        //! the simulator writes them and the others sorted after them
+
+        let mut fields = vec![
+            ("time", self.time.to_rfc3339()),
+            ("target", self.target.clone()),
+            ("level", self.level.to_string()),
+        ];
+        if let Some(debug_info) = &self.debug_info {
+            fields.push(("file", debug_info.file.clone()));
+            fields.push(("line", debug_info.line.to_string()));
+        }
+        fields.push(("msg", unquote(&self.msg)));
+
+        let mut other = self
+            .other
+            .iter()
+            .map(|(name, value)| (name.as_str(), unquote(value)))
+            .collect::<Vec<_>>();
+        other.sort();
+        fields.extend(other);
+
+        fields
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        //! The log as a JSON object, with the same fields it was read with.
+        //! The line number is kept as a number, everything else is a string
+
+        self.fields()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match (name, &self.debug_info) {
+                    ("line", Some(debug_info)) => debug_info.line.into(),
+                    _ => value.into(),
+                };
+                (name.to_string(), value)
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    pub fn to_logfmt(&self) -> String {
+        //! The log as `key=value` pairs, quoting values that need it
+
+        self.fields()
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, quote(&value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn quote(value: &str) -> String {
+    //! A value written for logfmt, quoted if it is empty or would otherwise
+    //! be split up when read back. Quoted values are escaped the way `Debug`
+    //! escapes them, so a message spanning lines is written on one
+
+    if !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '=')
+    {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Output;
+    use crate::{Format, Log};
+
+    const LOG: &str = "time=2022-08-14T15:02:11.417962+01:00 target=college_coursework level=INFO file=src/main.rs line=42 msg=\"Loaded bodies\" frame=12";
+
+    #[test]
+    fn test_structured_outputs_read_back() {
+        let log = Log::from_str(LOG).unwrap();
+
+        let json = Output::Json.write(&log);
+        assert_eq!(
+            r#"{"file":"src/main.rs","frame":"12","level":"INFO","line":42,"msg":"Loaded bodies","target":"college_coursework","time":"2022-08-14T15:02:11.417962+01:00"}"#,
+            json
+        );
+
+        let logfmt = Output::Logfmt.write(&log);
+        assert_eq!(
+            "time=2022-08-14T15:02:11.417962+01:00 target=college_coursework level=INFO file=src/main.rs line=42 msg=\"Loaded bodies\" frame=12",
+            logfmt
+        );
+
+        for (format, text) in [(Format::Json, json), (Format::Logfmt, logfmt)] {
+            let read = format.input().parse_log(&text).unwrap();
+            assert_eq!(log.time(), read.time());
+            assert_eq!(log.line(), read.line());
+            assert_eq!("12", read.other()["frame"]);
+        }
+    }
+
+    #[test]
+    fn test_multiline_logfmt_reads_back() {
+        let log = Log::from_str(
+            "time=2022-08-14T15:02:11+01:00 target=college_coursework level=ERROR msg=\"Failed to load \\\"C:\\\\sim\\\"\"\n\tat load_scenario\n\tat main",
+        )
+        .unwrap();
+
+        let logfmt = Output::Logfmt.write(&log);
+        assert_eq!(
+            r#"time=2022-08-14T15:02:11+01:00 target=college_coursework level=ERROR msg="Failed to load \"C:\\sim\"\n\tat load_scenario\n\tat main""#,
+            logfmt
+        );
+
+        let read = Log::from_str(&logfmt).unwrap();
+        assert_eq!(Output::Logfmt.write(&log), Output::Logfmt.write(&read));
+        assert_eq!(Output::Pretty.write(&log), Output::Pretty.write(&read));
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, FixedOffset};
+
+use crate::{fields::unquote, Log};
+
+/// Targets longer than this don't widen the target column, so one long
+/// target doesn't push every message across
+const MAX_TARGET_WIDTH: usize = 40;
+
+/// The colours logs are printed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// For terminals with a dark background
+    Dark,
+    /// For terminals with a light background
+    Light,
+    /// No colours at all
+    Plain,
+}
+impl Theme {
+    fn palette(&self) -> Option<Palette> {
+        match self {
+            Self::Dark => Some(Palette {
+                error: "1;31",
+                warn: "33",
+                info: "32",
+                debug: "34",
+                trace: "35",
+                time: "2",
+                target: "36",
+                fields: "2",
+            }),
+            Self::Light => Some(Palette {
+                error: "1;31",
+                warn: "38;5;166",
+                info: "38;5;28",
+                debug: "38;5;25",
+                trace: "38;5;90",
+                time: "38;5;244",
+                target: "38;5;30",
+                fields: "38;5;244",
+            }),
+            Self::Plain => None,
+        }
+    }
+}
+
+/// The ANSI graphics codes each part of a log is coloured with
+struct Palette {
+    error: &'static str,
+    warn: &'static str,
+    info: &'static str,
+    debug: &'static str,
+    trace: &'static str,
+    time: &'static str,
+    target: &'static str,
+    fields: &'static str,
+}
+impl Palette {
+    fn level(&self, level: log::Level) -> &'static str {
+        match level {
+            log::Level::Error => self.error,
+            log::Level::Warn => self.warn,
+            log::Level::Info => self.info,
+            log::Level::Debug => self.debug,
+            log::Level::Trace => self.trace,
+        }
+    }
+}
+
+/// Writes logs for reading in a terminal, in columns which line up from one
+/// log to the next
+pub struct PrettyPrinter {
+    palette: Option<Palette>,
+    /// Whether times are shown from the first log rather than as dates
+    relative: bool,
+    start: Option<DateTime<FixedOffset>>,
+    /// The width of the longest target so far
+    target_width: usize,
+}
+impl PrettyPrinter {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            palette: theme.palette(),
+            relative: false,
+            start: None,
+            target_width: 0,
+        }
+    }
+
+    pub fn with_relative_time(mut self, relative: bool) -> Self {
+        self.relative = relative;
+        self
+    }
+
+    pub fn colour_enabled(stream: atty::Stream) -> bool {
+        //! Whether logs written to a stream should be coloured, which they
+        //! are only when it is a terminal and `NO_COLOR` isn't set
+
+        let no_colour = matches!(std::env::var_os("NO_COLOR"), Some(value) if !value.is_empty());
+        !no_colour && atty::is(stream)
+    }
+
+    fn paint(&self, colour: impl Fn(&Palette) -> &'static str, text: &str) -> String {
+        match &self.palette {
+            Some(palette) => format!("\u{1b}[{}m{}\u{1b}[0m", colour(palette), text),
+            None => text.to_string(),
+        }
+    }
+
+    pub fn write(&mut self, log: &Log) -> String {
+        //! Write a log as a line, or more if its message is, without a
+        //! trailing newline
+
+        let time = if self.relative {
+            let start = *self.start.get_or_insert(log.time());
+            let seconds = (log.time() - start)
+                .num_microseconds()
+                .map_or(f64::NAN, |microseconds| microseconds as f64 / 1e6);
+            format!("+{:>11.3}s", seconds)
+        } else {
+            log.time().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+        };
+
+        let target_length = log.target().chars().count();
+        if target_length <= MAX_TARGET_WIDTH {
+            self.target_width = self.target_width.max(target_length);
+        }
+
+        let mut line = format!(
+            "{} {} {}  ",
+            self.paint(|palette| palette.time, &time),
+            self.paint(
+                |palette| palette.level(log.level()),
+                &format!("{:<5}", log.level())
+            ),
+            self.paint(
+                |palette| palette.target,
+                &format!("{:<width$}", log.target(), width = self.target_width)
+            ),
+        );
+        // Lines continuing the message are lined up under its first line
+        let indent =
+            " ".repeat(time.chars().count() + 1 + 5 + 1 + self.target_width.max(target_length) + 2);
+
+        let msg = unquote(log.msg());
+        let mut lines = msg.lines();
+        line.push_str(lines.next().unwrap_or(""));
+
+        let mut fields = log
+            .other()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>();
+        fields.sort();
+        if let (Some(file), Some(number)) = (log.file(), log.line()) {
+            fields.push(format!("at {}:{}", file, number));
+        }
+        if !fields.is_empty() {
+            line.push_str("  ");
+            line.push_str(&self.paint(|palette| palette.fields, &fields.join(" ")));
+        }
+
+        for continuation in lines {
+            line.push('\n');
+            line.push_str(&indent);
+            line.push_str(continuation);
+        }
+
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrettyPrinter, Theme};
+    use crate::Log;
+
+    #[test]
+    fn test_columns_line_up() {
+        let mut printer = PrettyPrinter::new(Theme::Plain).with_relative_time(true);
+        let lines = [
+            "time=2022-08-14T15:02:11+01:00 target=college_coursework::renderer level=WARN msg=\"Slow frame\" frame=12",
+            "time=2022-08-14T15:02:12.5+01:00 target=a level=ERROR msg=\"Failed\"\n   0: rust_begin_unwind",
+        ]
+        .map(|text| printer.write(&Log::from_str(text).unwrap()));
+
+        assert_eq!(
+            "+      0.000s WARN  college_coursework::renderer  Slow frame  frame=12",
+            lines[0]
+        );
+        // The backtrace keeps its own indentation after the message's
+        assert_eq!(
+            format!(
+                "+      1.500s ERROR a                             Failed\n{}   0: rust_begin_unwind",
+                " ".repeat(50)
+            ),
+            lines[1]
+        );
+
+        let mut printer = PrettyPrinter::new(Theme::Dark);
+        let coloured = printer.write(
+            &Log::from_str("time=2022-08-14T15:02:11+01:00 target=a level=ERROR msg=Failed")
+                .unwrap(),
+        );
+        assert!(coloured.contains("\u{1b}[1;31mERROR\u{1b}[0m"));
+    }
+}
@@ -0,0 +1,199 @@
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use crate::{
+    format::{parse_folded, Folding},
+    Diagnostics, Format, Log, LogParseError,
+};
+
+/// Parses logs from the lines of a file as they are read, so only the log
+/// being read is kept in memory
+#[derive(Default)]
+pub struct LineParser<'a> {
+    /// The format of the logs, detected from the first if it isn't given
+    format: Option<Format>,
+    diagnostics: Diagnostics<'a>,
+    /// The lines of the log being read
+    entry: String,
+    /// The number of the line the log being read starts on
+    entry_line: usize,
+    /// The number of lines read so far
+    line: usize,
+    folding: Folding,
+}
+impl<'a> LineParser<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_format(mut self, format: Option<Format>) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_diagnostics(mut self, diagnostics: Diagnostics<'a>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    pub fn push_line(&mut self, line: &str) -> Option<Result<Log, LogParseError>> {
+        //! Add the next line, returning the log before it if the line starts
+        //! a new one. Indented lines and panics continue the log before them,
+        //! so a log is only finished once the next one starts
+
+        self.line += 1;
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+
+        if line.trim().is_empty() {
+            return None;
+        }
+        if !self.entry.is_empty() && self.folding.continues(line) {
+            self.entry.push_str(line);
+            self.entry.push('\n');
+            return None;
+        }
+
+        let finished = self.finish();
+        self.entry_line = self.line;
+        self.entry.push_str(line);
+        self.entry.push('\n');
+
+        finished
+    }
+
+    pub fn finish(&mut self) -> Option<Result<Log, LogParseError>> {
+        //! Parse the log being read, such as at the end of a file, or when
+        //! nothing more has been written to it for a while
+
+        if self.entry.is_empty() {
+            return None;
+        }
+
+        let text = std::mem::take(&mut self.entry);
+        Some(self.parse(&text).map_err(|err| LogParseError::LineError {
+            line: self.entry_line,
+            text: text.trim_end().to_string(),
+            source: Box::new(err),
+        }))
+    }
+
+    fn parse(&mut self, text: &str) -> Result<Log, LogParseError> {
+        // Logs copied from a terminal may still be coloured
+        let text = String::from_utf8_lossy(&strip_ansi_escapes::strip(text)?).into_owned();
+
+        let diagnostics = &mut self.diagnostics;
+        let format = self.format.get_or_insert_with(|| {
+            Format::detect_with(text.lines().next().unwrap_or(""), diagnostics)
+        });
+        parse_folded(format.input(), &text, diagnostics)
+    }
+}
+
+/// Reads logs one at a time from a file, or anything else which can be read
+/// line by line, either as an iterator or asynchronously with `next_log`
+pub struct LogReader<'a, R> {
+    reader: R,
+    parser: LineParser<'a>,
+    buffer: String,
+}
+impl<'a, R> LogReader<'a, R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, LineParser::new())
+    }
+
+    pub fn with_parser(reader: R, parser: LineParser<'a>) -> Self {
+        Self {
+            reader,
+            parser,
+            buffer: String::new(),
+        }
+    }
+
+    pub fn format(&self) -> Option<Format> {
+        self.parser.format()
+    }
+}
+impl<'a, R: std::io::BufRead> Iterator for LogReader<'a, R> {
+    type Item = Result<Log, LogParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return self.parser.finish(),
+                Ok(_) => {
+                    if let Some(log) = self.parser.push_line(&self.buffer) {
+                        return Some(log);
+                    }
+                }
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+impl<'a, R: AsyncBufRead + Unpin> LogReader<'a, R> {
+    pub async fn next_log(&mut self) -> Option<Result<Log, LogParseError>> {
+        //! Read the next log, or none at the end of the file
+
+        loop {
+            self.buffer.clear();
+            match AsyncBufReadExt::read_line(&mut self.reader, &mut self.buffer).await {
+                Ok(0) => return self.parser.finish(),
+                Ok(_) => {
+                    if let Some(log) = self.parser.push_line(&self.buffer) {
+                        return Some(log);
+                    }
+                }
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogReader;
+    use crate::{Format, LogParseError};
+
+    const LOGS: &str = "\
+time=2022-08-14T15:02:11+01:00 target=college_coursework level=ERROR msg=\"Failed\"
+   0: rust_begin_unwind
+
+Not a log
+time=2022-08-14T15:02:12+01:00 target=college_coursework level=INFO msg=\"Exiting\"";
+
+    #[test]
+    fn test_reader_yields_each_log() {
+        let mut reader = LogReader::new(LOGS.as_bytes());
+
+        let log = reader.next().unwrap().unwrap();
+        assert_eq!("\"Failed\"\n   0: rust_begin_unwind", log.msg());
+        assert_eq!(Some(Format::Logfmt), reader.format());
+
+        // A log which can't be parsed doesn't stop the rest being read
+        match reader.next().unwrap() {
+            Err(LogParseError::LineError { line, text, .. }) => {
+                assert_eq!(4, line);
+                assert_eq!("Not a log", text);
+            }
+            result => panic!("Expected a line error, got {:?}", result),
+        }
+
+        assert_eq!("\"Exiting\"", reader.next().unwrap().unwrap().msg());
+        assert!(reader.next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_async_reader_yields_each_log() {
+        let mut reader = LogReader::new(LOGS.as_bytes());
+
+        let mut results = Vec::new();
+        while let Some(result) = reader.next_log().await {
+            results.push(result.is_ok());
+        }
+        assert_eq!(vec![true, false, true], results);
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+use chrono::{DateTime, Duration, FixedOffset, TimeZone};
+use serde_json::json;
+
+use crate::{fields::unquote, Log};
+
+/// The number of logs and errors in a span of time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bucket {
+    pub count: usize,
+    pub errors: usize,
+}
+impl Bucket {
+    pub fn error_rate(&self) -> f64 {
+        self.errors as f64 / self.count.max(1) as f64
+    }
+}
+
+/// A summary of a stream of logs, built up a log at a time
+#[derive(Debug, Clone)]
+pub struct Stats {
+    /// The length of the spans of time the error rate is counted over
+    bucket_length: Duration,
+    /// The number of the most frequent messages kept
+    top: usize,
+    count: usize,
+    levels: BTreeMap<log::Level, usize>,
+    targets: BTreeMap<String, usize>,
+    first: Option<DateTime<FixedOffset>>,
+    last: Option<DateTime<FixedOffset>>,
+    /// By the timestamp each span of time starts at
+    buckets: BTreeMap<i64, Bucket>,
+    messages: HashMap<String, usize>,
+}
+impl Stats {
+    pub fn new(bucket_length: Duration, top: usize) -> Self {
+        Self {
+            // A bucket of no length would divide by zero
+            bucket_length: bucket_length.max(Duration::seconds(1)),
+            top,
+            count: 0,
+            levels: BTreeMap::new(),
+            targets: BTreeMap::new(),
+            first: None,
+            last: None,
+            buckets: BTreeMap::new(),
+            messages: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, log: &Log) {
+        self.count += 1;
+        *self.levels.entry(log.level()).or_default() += 1;
+        *self.targets.entry(log.target().to_string()).or_default() += 1;
+
+        let time = log.time();
+        self.first = Some(self.first.map_or(time, |first| first.min(time)));
+        self.last = Some(self.last.map_or(time, |last| last.max(time)));
+
+        let length = self.bucket_length.num_seconds();
+        let bucket = self
+            .buckets
+            .entry(time.timestamp().div_euclid(length) * length)
+            .or_default();
+        bucket.count += 1;
+        if log.level() == log::Level::Error {
+            bucket.errors += 1;
+        }
+
+        *self.messages.entry(message_pattern(log.msg())).or_default() += 1;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn levels(&self) -> &BTreeMap<log::Level, usize> {
+        &self.levels
+    }
+
+    pub fn targets(&self) -> &BTreeMap<String, usize> {
+        &self.targets
+    }
+
+    pub fn first(&self) -> Option<DateTime<FixedOffset>> {
+        self.first
+    }
+
+    pub fn last(&self) -> Option<DateTime<FixedOffset>> {
+        self.last
+    }
+
+    pub fn buckets(&self) -> impl Iterator<Item = (DateTime<FixedOffset>, Bucket)> + '_ {
+        //! Each span of time with logs in it, in order, by the time it starts
+        //! in the time zone of the first log
+
+        let offset = self
+            .first
+            .map_or(FixedOffset::east(0), |first| *first.offset());
+        self.buckets
+            .iter()
+            .map(move |(&start, &bucket)| (offset.timestamp(start, 0), bucket))
+    }
+
+    pub fn top_messages(&self) -> Vec<(&str, usize)> {
+        //! The most frequent messages, most frequent first
+
+        let mut messages = self
+            .messages
+            .iter()
+            .map(|(message, &count)| (message.as_str(), count))
+            .collect::<Vec<_>>();
+        // Ties are sorted by message so the order is the same every time
+        messages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        messages.truncate(self.top);
+
+        messages
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "count": self.count,
+            "first": self.first.map(|time| time.to_rfc3339()),
+            "last": self.last.map(|time| time.to_rfc3339()),
+            "levels": self
+                .levels
+                .iter()
+                .map(|(level, &count)| (level.to_string(), count.into()))
+                .collect::<serde_json::Map<_, _>>(),
+            "targets": self.targets,
+            "buckets": self
+                .buckets()
+                .map(|(start, bucket)| json!({
+                    "start": start.to_rfc3339(),
+                    "count": bucket.count,
+                    "errors": bucket.errors,
+                    "error_rate": bucket.error_rate(),
+                }))
+                .collect::<Vec<_>>(),
+            "messages": self
+                .top_messages()
+                .into_iter()
+                .map(|(message, count)| json!({ "message": message, "count": count }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //! The stats as a table for each part of them
+
+        match (self.first, self.last) {
+            (Some(first), Some(last)) => writeln!(
+                f,
+                "{} logs from {} to {}",
+                self.count,
+                first.to_rfc3339(),
+                last.to_rfc3339()
+            )?,
+            _ => writeln!(f, "No logs")?,
+        }
+
+        let tables = [
+            (
+                ["Level", "Count", ""],
+                self.levels
+                    .iter()
+                    .rev()
+                    .map(|(level, count)| [level.to_string(), count.to_string(), String::new()])
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                ["Target", "Count", ""],
+                self.targets
+                    .iter()
+                    .map(|(target, count)| [target.clone(), count.to_string(), String::new()])
+                    .collect(),
+            ),
+            (
+                ["Time", "Count", "Error rate"],
+                self.buckets()
+                    .map(|(start, bucket)| {
+                        [
+                            start.to_rfc3339(),
+                            bucket.count.to_string(),
+                            format!("{:.1}%", bucket.error_rate() * 100.0),
+                        ]
+                    })
+                    .collect(),
+            ),
+            (
+                ["Count", "Message", ""],
+                self.top_messages()
+                    .into_iter()
+                    .map(|(message, count)| [count.to_string(), message.to_string(), String::new()])
+                    .collect(),
+            ),
+        ];
+
+        for (headings, rows) in tables {
+            writeln!(f)?;
+            write_table(f, headings, &rows)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_table(
+    f: &mut std::fmt::Formatter<'_>,
+    headings: [&str; 3],
+    rows: &[[String; 3]],
+) -> std::fmt::Result {
+    //! Write rows of up to three columns, each as wide as its widest cell
+
+    let mut widths = headings.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let headings = headings.map(str::to_string);
+    for row in std::iter::once(&headings).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(f, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+}
+
+fn message_pattern(msg: &str) -> String {
+    //! The first line of a message with each number in it replaced by `#`,
+    //! so messages only differing by their values, such as the state of a
+    //! body each frame, are counted together
+
+    let msg = unquote(msg);
+    let msg = msg.lines().next().unwrap_or("");
+    let chars = msg.chars().collect::<Vec<_>>();
+    let digit_at = |i: usize| matches!(chars.get(i), Some(c) if c.is_ascii_digit());
+
+    let mut pattern = String::with_capacity(msg.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_number = digit_at(i) || (chars[i] == '-' && digit_at(i + 1));
+        // Digits inside words, like the 3 in Vector3, aren't numbers
+        let in_word = i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+
+        if !starts_number || in_word {
+            pattern.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // Take the rest of the number, including any decimal point and
+        // exponent
+        i += 1;
+        while digit_at(i) || (chars.get(i) == Some(&'.') && digit_at(i + 1)) {
+            i += 1;
+        }
+        if matches!(chars.get(i), Some('e' | 'E')) {
+            let sign = matches!(chars.get(i + 1), Some('-' | '+')) as usize;
+            if digit_at(i + 1 + sign) {
+                i += 1 + sign;
+                while digit_at(i) {
+                    i += 1;
+                }
+            }
+        }
+        pattern.push('#');
+    }
+
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::{message_pattern, Stats};
+    use crate::Log;
+
+    #[test]
+    fn test_message_pattern() {
+        assert_eq!(
+            "body{id:earth,pos:Vector3 [#, #, #],mass:#}",
+            message_pattern("\"body{id:earth,pos:Vector3 [1.5e11, -2.0, 0.0],mass:5.972e24}\"")
+        );
+        assert_eq!("Frame # took #ms", message_pattern("Frame 12 took 16.6ms"));
+    }
+
+    #[test]
+    fn test_stats_counts_logs() {
+        let mut stats = Stats::new(Duration::minutes(1), 2);
+        for (time, level, msg) in [
+            ("15:00:10", "INFO", "Frame 1"),
+            ("15:00:50", "ERROR", "Failed"),
+            ("15:01:20", "INFO", "Frame 2"),
+            ("15:00:00", "WARN", "Slow"),
+        ] {
+            stats.add(
+                &Log::from_str(&format!(
+                    "time=2022-08-14T{}+01:00 target=college_coursework level={} msg=\"{}\"",
+                    time, level, msg
+                ))
+                .unwrap(),
+            );
+        }
+
+        assert_eq!(4, stats.count());
+        assert_eq!(Some(&2), stats.levels().get(&log::Level::Info));
+        assert_eq!(
+            "15:00:00",
+            stats.first().unwrap().format("%H:%M:%S").to_string()
+        );
+        assert_eq!(
+            "15:01:20",
+            stats.last().unwrap().format("%H:%M:%S").to_string()
+        );
+
+        let buckets = stats.buckets().collect::<Vec<_>>();
+        assert_eq!(2, buckets.len());
+        assert_eq!("15:00:00", buckets[0].0.format("%H:%M:%S").to_string());
+        assert_eq!((3, 1), (buckets[0].1.count, buckets[0].1.errors));
+        assert_eq!((1, 0), (buckets[1].1.count, buckets[1].1.errors));
+
+        assert_eq!(vec![("Frame #", 2), ("Failed", 1)], stats.top_messages());
+    }
+}
@@ -0,0 +1,163 @@
+use bitflags::bitflags;
+use specs::{Component, Entity, VecStorage};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BodyType {
+    Star,
+    Planet,
+    Moon,
+}
+impl BodyType {
+    pub const ALL: [BodyType; 3] = [BodyType::Star, BodyType::Planet, BodyType::Moon];
+}
+
+bitflags! {
+    pub struct InteractionFlags: u32 {
+        const STAR = 1 << BodyType::Star as u32;
+        const PLANET = 1 << BodyType::Planet as u32;
+        const MOON = 1 << BodyType::Moon as u32;
+        // Groups bodies can be tagged with for experiments, not used to
+        // decide which bodies interact
+        const GROUP_A = 1 << 8;
+        const GROUP_B = 1 << 9;
+    }
+}
+impl From<BodyType> for InteractionFlags {
+    fn from(body_type: BodyType) -> Self {
+        InteractionFlags::from_bits(1 << body_type as u32).unwrap()
+    }
+}
+
+/// Contains information about what body type an entity is
+// And what body types affect it (For optimisation)
+#[derive(Component)]
+#[storage(VecStorage)]
+pub struct InteractionHandler {
+    pub flags: InteractionFlags,
+    pub body_type: BodyType,
+}
+impl InteractionHandler {
+    pub fn new(flags: InteractionFlags, body_type: BodyType) -> Self {
+        //! Create a new interaction handler
+        Self { flags, body_type }
+    }
+
+    pub fn interacts_with(&self, other: BodyType) -> bool {
+        //! Whether this body is pulled by the gravity of bodies of another
+        //! type, which is when its flags contain that type. Only the body
+        //! type flags are used, group tags have no effect. With the flags the
+        //! bodies are created with:
+        //!
+        //! | Pulled body | by Star | by Planet | by Moon |
+        //! |-------------|---------|-----------|---------|
+        //! | Star        | yes     | no        | no      |
+        //! | Planet      | yes     | yes       | yes     |
+        //! | Moon        | yes     | yes       | yes     |
+        //!
+        //! The pull is one way, a planet is pulled by a star without pulling
+        //! it back
+        self.flags.contains(other.into())
+    }
+}
+
+/// Marks a body as a satellite of another body, so that it can be integrated
+/// in its parent's frame of reference
+#[derive(Component)]
+#[storage(VecStorage)]
+pub struct Satellite {
+    pub parent: Entity,
+}
+impl Satellite {
+    pub fn new(parent: Entity) -> Self {
+        //! Create a new satellite of the parent entity
+        Self { parent }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BodyType, InteractionFlags, InteractionHandler};
+
+    fn index(body_type: BodyType) -> usize {
+        // Adding a body type fails to compile here until the tests cover it
+        match body_type {
+            BodyType::Star => 0,
+            BodyType::Planet => 1,
+            BodyType::Moon => 2,
+        }
+    }
+
+    #[test]
+    fn test_all_body_types_listed() {
+        for (i, body_type) in BodyType::ALL.into_iter().enumerate() {
+            assert_eq!(i, index(body_type));
+        }
+    }
+
+    #[test]
+    fn test_interacts_with_own_flags() {
+        // Each body type flag lets bodies be pulled by only that type
+        for pulled in BodyType::ALL {
+            for flagged in BodyType::ALL {
+                let handler = InteractionHandler::new(flagged.into(), pulled);
+                for other in BodyType::ALL {
+                    assert_eq!(
+                        index(other) == index(flagged),
+                        handler.interacts_with(other),
+                        "{:?} flagged with {:?}, pulled by {:?}",
+                        pulled,
+                        flagged,
+                        other
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_interacts_with_all_or_nothing() {
+        for pulled in BodyType::ALL {
+            let everything = InteractionHandler::new(InteractionFlags::all(), pulled);
+            let nothing = InteractionHandler::new(InteractionFlags::empty(), pulled);
+            for other in BodyType::ALL {
+                assert!(everything.interacts_with(other));
+                assert!(!nothing.interacts_with(other));
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_tags_do_not_interact() {
+        let handler = InteractionHandler::new(
+            InteractionFlags::GROUP_A | InteractionFlags::GROUP_B,
+            BodyType::Planet,
+        );
+        for other in BodyType::ALL {
+            assert!(!handler.interacts_with(other));
+        }
+    }
+
+    #[test]
+    fn test_default_truth_table() {
+        // The flags bodies are created with, stars are only pulled by other
+        // stars so the Sun isn't pulled around by the planets
+        let flags = |body_type| match body_type {
+            BodyType::Star => InteractionFlags::STAR,
+            BodyType::Planet | BodyType::Moon => InteractionFlags::all(),
+        };
+        let expected = [[true, false, false], [true, true, true], [true, true, true]];
+
+        for pulled in BodyType::ALL {
+            let handler = InteractionHandler::new(flags(pulled), pulled);
+            for other in BodyType::ALL {
+                assert_eq!(
+                    expected[index(pulled)][index(other)],
+                    handler.interacts_with(other),
+                    "{:?} pulled by {:?}",
+                    pulled,
+                    other
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use cgmath::Vector3;
+use instant::Duration;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use specs::{Component, Join, ReadStorage, System, VecStorage};
+
+/// The position of a body (in m)
+#[derive(Debug, Clone, Copy)]
+pub struct Position(pub Vector3<f64>);
+impl From<Vector3<f64>> for Position {
+    fn from(v: Vector3<f64>) -> Self {
+        Self(v)
+    }
+}
+impl Component for Position {
+    type Storage = VecStorage<Self>;
+}
+
+/// The velocity of a body (in m/s)
+#[derive(Debug, Clone, Copy)]
+pub struct Velocity(pub Vector3<f64>);
+impl From<Vector3<f64>> for Velocity {
+    fn from(v: Vector3<f64>) -> Self {
+        Self(v)
+    }
+}
+impl Component for Velocity {
+    type Storage = VecStorage<Self>;
+}
+
+/// The mass of a body (in kg)
+#[derive(Debug, Clone, Copy)]
+pub struct Mass(pub f64);
+impl From<f64> for Mass {
+    fn from(m: f64) -> Self {
+        Self(m)
+    }
+}
+impl Component for Mass {
+    type Storage = VecStorage<Self>;
+}
+
+/// The physical radius of a body (in m)
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Radius(pub f64);
+impl Component for Radius {
+    type Storage = VecStorage<Self>;
+}
+
+/// The unique id of a body, and the name it is shown with
+#[derive(Debug, Clone)]
+pub struct Identifier {
+    id: String,
+    name: String,
+}
+impl Identifier {
+    pub fn new(id: String, name: String) -> Self {
+        Self { id, name }
+    }
+
+    pub fn get_id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+impl Component for Identifier {
+    type Storage = VecStorage<Self>;
+}
+
+/// The real time the last frame took, which the simulation is advanced by
+#[derive(Default, Copy, Clone)]
+pub struct DeltaTime(pub Duration);
+
+/// How quickly the simulation runs, split into iterations so that each step
+/// stays small enough to be accurate
+#[derive(Default, Copy, Clone)]
+pub struct TimeScale {
+    /// Simulated seconds each iteration, for every real second
+    pub time_scale: f64,
+    /// Simulated seconds for every real second
+    pub total_time_elapsed: f64,
+    pub iterations: usize,
+    /// The simulation doesn't advance while paused
+    pub paused: bool,
+}
+impl TimeScale {
+    pub fn new(total_time_elapsed: f64, iterations: usize) -> Self {
+        if iterations < 1 {
+            panic!("Iterations cannot be less than 1");
+        }
+
+        Self {
+            time_scale: total_time_elapsed / iterations as f64,
+            total_time_elapsed,
+            iterations,
+            paused: false,
+        }
+    }
+
+    pub fn from_max_time_per_iteration(
+        total_time_elapsed: f64,
+        max_time_per_iteration: f64,
+    ) -> Self {
+        // There is always at least one iteration, even with no time elapsing
+        let ierations = (total_time_elapsed / max_time_per_iteration)
+            .ceil()
+            .max(1.0) as usize;
+
+        Self::new(total_time_elapsed, ierations)
+    }
+}
+
+/// Total simulated time elapsed since the simulation started (in seconds)
+#[derive(Default, Copy, Clone)]
+pub struct SimulationTime(pub f64);
+
+/// (in m^3 kg^-1 s^-2)
+#[derive(Default, Copy, Clone)]
+pub struct GravitationalConstant(pub f64);
+
+/// Runs the simulation on a single thread so that results are exactly
+/// reproducible, regardless of how work is split between threads
+#[derive(Default, Copy, Clone)]
+pub struct DeterministicMode(pub bool);
+
+/// How pairs of bodies in close encounters are integrated
+#[derive(Copy, Clone)]
+pub struct CloseEncounterSettings {
+    /// Step close pairs analytically as a two body orbit
+    pub analytic: bool,
+    // A pair is in a close encounter when its dynamical time (the time scale
+    // of its orbit around itself) is less than this many steps
+    pub min_steps: f64,
+}
+impl Default for CloseEncounterSettings {
+    fn default() -> Self {
+        Self {
+            analytic: true,
+            min_steps: 20.0,
+        }
+    }
+}
+
+/// Logs the state of every body each time it runs
+pub struct Printer;
+impl Printer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for Printer {
+    type SystemData = (
+        ReadStorage<'a, Identifier>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Mass>,
+    );
+
+    fn run(&mut self, (id, positions, velocities, mass): Self::SystemData) {
+        // Iterate over every entity
+        (&id, &positions, &velocities, &mass)
+            .join()
+            .for_each(|(id, pos, vel, mass)| {
+                // Print the entity's id, name, pos, vel and mass as an informational log
+                info!(
+                    "body{{id:{},name:{},pos:{:?},vel:{:?},mass:{:?}}}",
+                    id.id, id.name, pos.0, vel.0, mass.0,
+                );
+
+                // Print the entity's name, pos, vel and mass as a debug log
+                debug!(
+                    "{} {{\n\tpos: {:?},\n\tvel: {:?},\n\tmass: {:?}\n}}",
+                    id.name, pos.0, vel.0, mass.0,
+                );
+            });
+    }
+}
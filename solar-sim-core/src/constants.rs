@@ -0,0 +1,7 @@
+/// The gravitational constant (in m^3 kg^-1 s^-2)
+pub const BIG_G: f64 = 6.6743015e-11;
+/// The astronomical unit (in m)
+pub const AU: f64 = 1.495978707e11;
+/// Julian date of the J2000 epoch, which the starting positions of the
+/// bodies are for
+pub const J2000: f64 = 2451545.0;
@@ -0,0 +1,94 @@
+use specs::{Component, VecStorage};
+
+use super::{InteractionFlags, InteractionHandler};
+
+/// The experiment groups a body has been tagged with
+#[derive(Debug, Clone, Copy, Component)]
+#[storage(VecStorage)]
+pub struct GroupTags(pub InteractionFlags);
+
+/// A different gravitational constant for the pull between two groups of
+/// bodies, e.g. to see what happens if only Jupiter pulled harder on the
+/// asteroids
+#[derive(Debug, Clone, Copy)]
+pub struct GravityOverride {
+    pub enabled: bool,
+    pub first: InteractionFlags,
+    pub second: InteractionFlags,
+    /// The gravitational constant between the groups, as a multiple of the
+    /// global one
+    pub multiplier: f64,
+}
+impl Default for GravityOverride {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            first: InteractionFlags::GROUP_A,
+            second: InteractionFlags::GROUP_B,
+            multiplier: 1.0,
+        }
+    }
+}
+impl GravityOverride {
+    fn applies(&self, a: InteractionFlags, b: InteractionFlags) -> bool {
+        //! Whether the override affects the pull between bodies in groups `a`
+        //! and `b`, in either direction so momentum is still conserved
+
+        self.enabled
+            && ((self.first.intersects(a) && self.second.intersects(b))
+                || (self.first.intersects(b) && self.second.intersects(a)))
+    }
+}
+
+/// The gravitational constant overrides used for "what if" experiments, the
+/// last override that applies to a pair of bodies is used
+#[derive(Debug, Clone, Default)]
+pub struct GravityOverrides(pub Vec<GravityOverride>);
+impl GravityOverrides {
+    pub fn constant(&self, global: f64, a: InteractionFlags, b: InteractionFlags) -> f64 {
+        //! The gravitational constant between bodies in groups `a` and `b`
+
+        self.0
+            .iter()
+            .rev()
+            .find(|gravity_override| gravity_override.applies(a, b))
+            .map_or(global, |gravity_override| {
+                global * gravity_override.multiplier
+            })
+    }
+}
+
+pub fn groups(handler: Option<&InteractionHandler>, tags: Option<&GroupTags>) -> InteractionFlags {
+    //! The groups a body is in, its body type along with any tags
+
+    handler.map_or(InteractionFlags::empty(), |handler| {
+        handler.body_type.into()
+    }) | tags.map_or(InteractionFlags::empty(), |tags| tags.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GravityOverride, GravityOverrides};
+    use crate::InteractionFlags;
+
+    #[test]
+    fn test_override_only_between_groups() {
+        let overrides = GravityOverrides(vec![GravityOverride {
+            enabled: true,
+            first: InteractionFlags::PLANET | InteractionFlags::GROUP_A,
+            second: InteractionFlags::GROUP_B,
+            multiplier: 2.0,
+        }]);
+
+        let jupiter = InteractionFlags::PLANET | InteractionFlags::GROUP_A;
+        let asteroid = InteractionFlags::MOON | InteractionFlags::GROUP_B;
+
+        assert_eq!(2.0, overrides.constant(1.0, jupiter, asteroid));
+        assert_eq!(2.0, overrides.constant(1.0, asteroid, jupiter));
+        assert_eq!(
+            1.0,
+            overrides.constant(1.0, jupiter, InteractionFlags::STAR)
+        );
+        assert_eq!(1.0, overrides.constant(1.0, asteroid, asteroid));
+    }
+}
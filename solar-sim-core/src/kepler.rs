@@ -0,0 +1,185 @@
+use cgmath::{InnerSpace, Matrix3, Rad, Vector3};
+
+/// Tolerance on the universal anomaly when solving Kepler's equation
+const TOLERANCE: f64 = 1e-10;
+
+/// Maximum number of Newton iterations when solving Kepler's equation
+const MAX_ITERATIONS: usize = 100;
+
+fn stumpff(z: f64) -> (f64, f64) {
+    //! Calculates the Stumpff functions C(z) and S(z), using their series for
+    //! small z to avoid cancellation errors
+
+    if z.abs() < 1e-6 {
+        (
+            1.0 / 2.0 - z / 24.0 + z * z / 720.0,
+            1.0 / 6.0 - z / 120.0 + z * z / 5040.0,
+        )
+    } else if z > 0.0 {
+        let root = z.sqrt();
+        ((1.0 - root.cos()) / z, (root - root.sin()) / root.powi(3))
+    } else {
+        let root = (-z).sqrt();
+        (
+            (root.cosh() - 1.0) / -z,
+            (root.sinh() - root) / root.powi(3),
+        )
+    }
+}
+
+pub fn propagate(
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    mu: f64,
+    dt: f64,
+) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    //! Advances a two body orbit analytically by dt using universal variables,
+    //! which works for elliptic, parabolic and hyperbolic orbits alike.
+    //! `position` and `velocity` are relative to the other body and `mu` is
+    //! G * (m1 + m2). Returns None if Kepler's equation could not be solved
+
+    let r0 = position.magnitude();
+    let radial_velocity = position.dot(velocity) / r0;
+    let sqrt_mu = mu.sqrt();
+
+    // Reciprocal of the semi-major axis, negative for hyperbolic orbits
+    let alpha = 2.0 / r0 - velocity.magnitude2() / mu;
+
+    // Solve the universal Kepler's equation for the universal anomaly using
+    // Newton's method
+    let mut chi = sqrt_mu * alpha.abs() * dt;
+    let mut converged = false;
+    for _ in 0..MAX_ITERATIONS {
+        let (c, s) = stumpff(alpha * chi * chi);
+
+        let f = r0 * radial_velocity / sqrt_mu * chi * chi * c
+            + (1.0 - alpha * r0) * chi.powi(3) * s
+            + r0 * chi
+            - sqrt_mu * dt;
+        let df = r0 * radial_velocity / sqrt_mu * chi * (1.0 - alpha * chi * chi * s)
+            + (1.0 - alpha * r0) * chi * chi * c
+            + r0;
+
+        let ratio = f / df;
+        chi -= ratio;
+
+        if !chi.is_finite() {
+            return None;
+        }
+
+        if ratio.abs() <= TOLERANCE * chi.abs().max(1.0) {
+            converged = true;
+            break;
+        }
+    }
+
+    if !converged {
+        return None;
+    }
+
+    // Use the Lagrange coefficients to find the new state
+    let (c, s) = stumpff(alpha * chi * chi);
+    let f = 1.0 - chi * chi / r0 * c;
+    let g = dt - chi.powi(3) * s / sqrt_mu;
+    let new_position = f * position + g * velocity;
+
+    let r = new_position.magnitude();
+    let f_dot = sqrt_mu / (r * r0) * (alpha * chi.powi(3) * s - chi);
+    let g_dot = 1.0 - chi * chi / r * c;
+    let new_velocity = f_dot * position + g_dot * velocity;
+
+    Some((new_position, new_velocity))
+}
+
+/// The shape and orientation of an orbit, with where the body is along it.
+/// Angles are in radians, measured from the x-axis in the x-y plane
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    /// (in m), negative for hyperbolic orbits
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub true_anomaly: f64,
+}
+impl OrbitalElements {
+    pub fn to_state(&self, mu: f64) -> (Vector3<f64>, Vector3<f64>) {
+        //! Returns the position and velocity of the body relative to the one
+        //! it orbits, where `mu` is G * (m1 + m2)
+
+        let semi_latus_rectum = self.semi_major_axis * (1.0 - self.eccentricity.powi(2));
+        let (sin_anomaly, cos_anomaly) = self.true_anomaly.sin_cos();
+        let r = semi_latus_rectum / (1.0 + self.eccentricity * cos_anomaly);
+
+        // In the plane of the orbit, with the periapsis along the x-axis
+        let position = Vector3::new(r * cos_anomaly, r * sin_anomaly, 0.0);
+        let velocity = (mu / semi_latus_rectum).sqrt()
+            * Vector3::new(-sin_anomaly, self.eccentricity + cos_anomaly, 0.0);
+
+        let rotation = Matrix3::from_angle_z(Rad(self.longitude_of_ascending_node))
+            * Matrix3::from_angle_x(Rad(self.inclination))
+            * Matrix3::from_angle_z(Rad(self.argument_of_periapsis));
+
+        (rotation * position, rotation * velocity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use super::{propagate, OrbitalElements};
+
+    const MU: f64 = 3.986004418e14;
+
+    #[test]
+    fn test_circular_orbit_returns_after_one_period() {
+        let r = 7.0e6;
+        let position = Vector3::new(r, 0.0, 0.0);
+        let velocity = Vector3::new(0.0, (MU / r).sqrt(), 0.0);
+        let period = std::f64::consts::TAU * (r.powi(3) / MU).sqrt();
+
+        let (new_position, new_velocity) = propagate(position, velocity, MU, period).unwrap();
+
+        assert!((new_position - position).magnitude() < 1e-3);
+        assert!((new_velocity - velocity).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn test_hyperbolic_flyby_conserves_energy() {
+        let position = Vector3::new(-1.0e8, 1.0e7, 0.0);
+        let velocity = Vector3::new(1.0e4, 0.0, 0.0);
+        let energy = |p: Vector3<f64>, v: Vector3<f64>| v.magnitude2() / 2.0 - MU / p.magnitude();
+
+        let (new_position, new_velocity) = propagate(position, velocity, MU, 2.0e4).unwrap();
+
+        // The body passes the other and comes out the other side
+        assert!(new_position.x > 0.0);
+        assert!(
+            (energy(new_position, new_velocity) - energy(position, velocity)).abs()
+                < 1e-6 * energy(position, velocity).abs()
+        );
+    }
+
+    #[test]
+    fn test_elements_give_the_orbit_described() {
+        let elements = OrbitalElements {
+            semi_major_axis: 1.0e7,
+            eccentricity: 0.5,
+            inclination: std::f64::consts::FRAC_PI_2,
+            longitude_of_ascending_node: 0.0,
+            argument_of_periapsis: 0.0,
+            true_anomaly: 0.0,
+        };
+        let (position, velocity) = elements.to_state(MU);
+
+        // At periapsis, moving straight up out of the x-y plane
+        assert!((position - Vector3::new(5.0e6, 0.0, 0.0)).magnitude() < 1e-6);
+        assert!(velocity.x.abs() < 1e-9 && velocity.y.abs() < 1e-9);
+
+        // The vis-viva equation holds
+        let expected_speed = (MU * (2.0 / 5.0e6 - 1.0 / 1.0e7)).sqrt();
+        assert!((velocity.magnitude() - expected_speed).abs() < 1e-6);
+    }
+}
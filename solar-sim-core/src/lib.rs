@@ -0,0 +1,81 @@
+//! The physics of the solar system simulation, with no rendering or windowing,
+//! so it can be shared by the program, its tests and any other frontend.
+//!
+//! Bodies are [specs] entities with a [`Position`], [`Velocity`] and
+//! [`Mass`], along with an [`InteractionHandler`] deciding which bodies pull
+//! on them. Each time the [`Simulator`] system runs it advances them by the
+//! [`TimeScale`] for every second of [`DeltaTime`].
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use cgmath::{InnerSpace, Vector3};
+//! use solar_sim_core::{
+//!     constants::{AU, BIG_G},
+//!     BodyType, DeltaTime, GravitationalConstant, InteractionFlags, InteractionHandler, Mass,
+//!     Position, Simulator, TimeScale, Velocity,
+//! };
+//! use specs::{Builder, DispatcherBuilder, ReadStorage, World, WorldExt};
+//!
+//! let mut world = World::new();
+//! let mut dispatcher = DispatcherBuilder::new()
+//!     .with(Simulator::new(), "simulator", &[])
+//!     .build();
+//! // Registers the components and resources the simulator uses
+//! dispatcher.setup(&mut world);
+//!
+//! world.insert(GravitationalConstant(BIG_G));
+//! // A day every time the simulator runs, in hour long steps
+//! world.insert(TimeScale::new(86400.0, 24));
+//! world.insert(DeltaTime(Duration::from_secs(1)));
+//!
+//! let sun_mass = 1.9885e30;
+//! world
+//!     .create_entity()
+//!     .with(Position(Vector3::new(0.0, 0.0, 0.0)))
+//!     .with(Velocity(Vector3::new(0.0, 0.0, 0.0)))
+//!     .with(Mass(sun_mass))
+//!     .with(InteractionHandler::new(InteractionFlags::STAR, BodyType::Star))
+//!     .build();
+//! let earth = world
+//!     .create_entity()
+//!     .with(Position(Vector3::new(AU, 0.0, 0.0)))
+//!     .with(Velocity(Vector3::new(0.0, (BIG_G * sun_mass / AU).sqrt(), 0.0)))
+//!     .with(Mass(5.972e24))
+//!     .with(InteractionHandler::new(InteractionFlags::all(), BodyType::Planet))
+//!     .build();
+//!
+//! // Half a year later the Earth is on the other side of the Sun
+//! for _ in 0..183 {
+//!     dispatcher.dispatch(&world);
+//! }
+//!
+//! let positions: ReadStorage<Position> = world.system_data();
+//! let position = positions.get(earth).unwrap().0;
+//! assert!(position.x < 0.0);
+//! assert!((position.magnitude() / AU - 1.0).abs() < 0.01);
+//! ```
+
+mod bodies;
+mod components;
+pub mod constants;
+mod experiments;
+pub mod kepler;
+mod orbits;
+mod simulator;
+pub mod util;
+
+pub use bodies::{BodyType, InteractionFlags, InteractionHandler, Satellite};
+
+pub use components::{
+    CloseEncounterSettings, DeltaTime, DeterministicMode, GravitationalConstant, Identifier, Mass,
+    Position, Printer, Radius, SimulationTime, TimeScale, Velocity,
+};
+
+pub use experiments::{GravityOverride, GravityOverrides, GroupTags};
+
+pub use orbits::{
+    semi_major_axis, sidereal_period, synodic_period, OrbitClosureDetector, OrbitTracker,
+};
+
+pub use simulator::Simulator;
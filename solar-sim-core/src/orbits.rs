@@ -0,0 +1,175 @@
+use std::f64::consts::TAU;
+
+use cgmath::{InnerSpace, Vector3};
+use specs::{Component, Entities, Join, Read, ReadStorage, System, VecStorage, WriteStorage};
+
+use super::{Mass, Position, Satellite, SimulationTime, Velocity};
+
+/// Tracks how far a body has travelled around the body it orbits, to measure
+/// the time each orbit actually takes
+#[derive(Debug, Clone, Default, Component)]
+#[storage(VecStorage)]
+pub struct OrbitTracker {
+    /// Time taken by the last complete orbit (in seconds)
+    pub measured_period: Option<f64>,
+    // Angle swept since the current orbit started (in radians)
+    swept: f64,
+    // Simulated time the current orbit started at
+    start: Option<f64>,
+    // Position relative to the orbited body and simulated time when last run
+    last: Option<(Vector3<f64>, f64)>,
+}
+impl OrbitTracker {
+    fn reset(&mut self) {
+        //! Start measuring again from scratch
+        *self = Self::default();
+    }
+}
+
+/// Measures the period of each body's orbit by detecting when it has swept a
+/// full turn around the body it orbits
+pub struct OrbitClosureDetector;
+impl OrbitClosureDetector {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl<'a> System<'a> for OrbitClosureDetector {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Mass>,
+        ReadStorage<'a, Satellite>,
+        WriteStorage<'a, OrbitTracker>,
+        Read<'a, SimulationTime>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, positions, velocities, masses, satellites, mut trackers, simulation_time): Self::SystemData,
+    ) {
+        let primary = (&entities, &masses)
+            .join()
+            .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(entity, _)| entity);
+
+        for (entity, position, velocity, satellite, tracker) in (
+            &entities,
+            &positions,
+            &velocities,
+            (&satellites).maybe(),
+            &mut trackers,
+        )
+            .join()
+        {
+            let orbited = satellite.map(|satellite| satellite.parent).or(primary);
+            let relative = match orbited
+                .filter(|&orbited| orbited != entity)
+                .and_then(|orbited| Some((positions.get(orbited)?, velocities.get(orbited)?)))
+            {
+                Some((orbited_position, orbited_velocity)) => (
+                    position.0 - orbited_position.0,
+                    velocity.0 - orbited_velocity.0,
+                ),
+                None => continue,
+            };
+            let (relative_position, relative_velocity) = relative;
+
+            let (last_position, last_time) = match tracker.last {
+                // Time has gone backwards, e.g. a save has been loaded, so the
+                // orbit being measured is no longer the same
+                Some((_, last_time)) if simulation_time.0 < last_time => {
+                    tracker.reset();
+                    tracker.last = Some((relative_position, simulation_time.0));
+                    continue;
+                }
+                Some(last) => last,
+                None => {
+                    tracker.last = Some((relative_position, simulation_time.0));
+                    continue;
+                }
+            };
+            if simulation_time.0 == last_time {
+                continue;
+            }
+
+            // The angle swept since the last run, measured in the direction
+            // of the orbit. This is only correct for less than half a turn
+            // per frame
+            let normal = relative_position.cross(relative_velocity);
+            let swept = last_position
+                .cross(relative_position)
+                .dot(normal.normalize())
+                .atan2(last_position.dot(relative_position));
+
+            let start = *tracker.start.get_or_insert(last_time);
+            tracker.swept += swept;
+            if tracker.swept >= TAU {
+                // Estimate when the turn was completed between the runs
+                let overshoot = (tracker.swept - TAU) / swept;
+                let completed = simulation_time.0 - overshoot * (simulation_time.0 - last_time);
+
+                tracker.measured_period = Some(completed - start);
+                tracker.swept -= TAU;
+                tracker.start = Some(completed);
+            }
+
+            tracker.last = Some((relative_position, simulation_time.0));
+        }
+    }
+}
+
+pub fn semi_major_axis(position: Vector3<f64>, velocity: Vector3<f64>, mu: f64) -> f64 {
+    //! The semi-major axis of an orbit from the vis-viva equation, negative
+    //! for hyperbolic orbits. `position` and `velocity` are relative to the
+    //! body being orbited and `mu` is G * (m1 + m2)
+
+    1.0 / (2.0 / position.magnitude() - velocity.magnitude2() / mu)
+}
+
+pub fn sidereal_period(semi_major_axis: f64, mu: f64) -> Option<f64> {
+    //! The time taken for one orbit from Kepler's third law, None if the
+    //! orbit isn't closed
+
+    (semi_major_axis > 0.0).then(|| TAU * (semi_major_axis.powi(3) / mu).sqrt())
+}
+
+pub fn synodic_period(first: f64, second: f64) -> Option<f64> {
+    //! The time between successive alignments of two bodies orbiting the
+    //! same body with the given sidereal periods, None if they never come
+    //! back into line
+
+    let difference = (1.0 / first - 1.0 / second).abs();
+    (difference > 0.0).then(|| 1.0 / difference)
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{semi_major_axis, sidereal_period, synodic_period};
+    use crate::constants::{AU, BIG_G};
+
+    #[test]
+    fn test_earth_mars_synodic_period() {
+        let mu = BIG_G * 1.989e30;
+        let period = |radius: f64| {
+            let speed = (mu / radius).sqrt();
+            let a = semi_major_axis(
+                Vector3::new(radius, 0.0, 0.0),
+                Vector3::new(0.0, speed, 0.0),
+                mu,
+            );
+            sidereal_period(a, mu).unwrap()
+        };
+
+        let earth = period(AU);
+        let mars = period(1.524 * AU);
+
+        // Around 365 and 687 days, which come back into line every 780 days
+        assert!((earth / 86400.0 - 365.25).abs() < 1.0);
+        assert!((synodic_period(earth, mars).unwrap() / 86400.0 - 780.0).abs() < 5.0);
+        assert_eq!(None, synodic_period(earth, earth));
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3, Zero};
+use log::warn;
+use rayon::prelude::*;
+use specs::{
+    BitSet, Entities, Entity, Join, ParJoin, Read, ReadStorage, System, Write, WriteStorage,
+};
+
+use super::{
+    components::{CloseEncounterSettings, DeltaTime, DeterministicMode, SimulationTime, TimeScale},
+    experiments::{self, GravityOverrides, GroupTags},
+    kepler, GravitationalConstant, Identifier, InteractionHandler, Mass, Position, Satellite,
+    Velocity,
+};
+
+/// The largest mass a satellite can have relative to its parent for it to be
+/// integrated in its parent's frame. The pair pull on each other, but other
+/// bodies are only pulled by the parent, so above this the two bodies orbit a
+/// shared barycenter and treating one as a satellite would be inaccurate
+const MAX_SATELLITE_MASS_RATIO: f64 = 0.1;
+
+/// The minimum number of sub-steps a satellite takes per orbit of its parent
+const MIN_STEPS_PER_ORBIT: f64 = 200.0;
+
+/// The maximum number of sub-steps a satellite can take per global step
+const MAX_SUB_STEPS: usize = 10_000;
+
+/// Steps every body forward by the time scale, pulling each one towards the
+/// bodies its `InteractionHandler` lets it interact with
+pub struct Simulator {
+    // Satellites that have already been warned about failing the mass ratio check
+    warned_satellites: BitSet,
+}
+impl Simulator {
+    pub fn new() -> Self {
+        Self {
+            warned_satellites: BitSet::new(),
+        }
+    }
+}
+impl<'a> System<'a> for Simulator {
+    type SystemData = (
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Mass>,
+        ReadStorage<'a, InteractionHandler>,
+        ReadStorage<'a, Satellite>,
+        ReadStorage<'a, Identifier>,
+        ReadStorage<'a, GroupTags>,
+        Read<'a, DeltaTime>,
+        Read<'a, TimeScale>,
+        Read<'a, GravitationalConstant>,
+        Read<'a, GravityOverrides>,
+        Read<'a, CloseEncounterSettings>,
+        Read<'a, DeterministicMode>,
+        Write<'a, SimulationTime>,
+        Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut positions,
+            mut velocities,
+            mass,
+            interaction_handlers,
+            satellites,
+            ids,
+            tags,
+            dt,
+            time_scale,
+            gravitational_constant,
+            gravity_overrides,
+            close_encounter_settings,
+            deterministic,
+            mut simulation_time,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if time_scale.paused {
+            return;
+        }
+        simulation_time.0 += time_scale.total_time_elapsed * dt.0.as_secs_f64();
+
+        // The gravitational constant between two bodies, which experiments
+        // can change for some groups of bodies
+        let groups = |entity: Entity| {
+            experiments::groups(interaction_handlers.get(entity), tags.get(entity))
+        };
+        let constant_between = |a: Entity, b: Entity| {
+            gravity_overrides.constant(gravitational_constant.0, groups(a), groups(b))
+        };
+
+        // Find the satellites that can be integrated in their parent's frame
+        let mut hierarchical = BitSet::new();
+        let mut satellite_pairs = Vec::new();
+        for (entity, satellite, satellite_mass) in (&entities, &satellites, &mass).join() {
+            let parent_mass = match mass.get(satellite.parent) {
+                // Nested satellites are integrated as normal bodies
+                Some(parent_mass) if !satellites.contains(satellite.parent) => parent_mass.0,
+                _ => continue,
+            };
+
+            if satellite_mass.0 / parent_mass > MAX_SATELLITE_MASS_RATIO {
+                if !self.warned_satellites.add(entity.id()) {
+                    warn!(
+                        "{} is too massive to be a satellite of its parent (mass ratio {:.3}), integrating it as a normal body",
+                        ids.get(entity).map_or("Unknown", |id| id.get_name()),
+                        satellite_mass.0 / parent_mass
+                    );
+                }
+                continue;
+            }
+
+            hierarchical.add(entity.id());
+            satellite_pairs.push((
+                entity,
+                satellite.parent,
+                constant_between(entity, satellite.parent) * (satellite_mass.0 + parent_mass),
+                // The fraction of the pair's mass in the satellite, which is
+                // how much of their pull on each other moves the parent
+                satellite_mass.0 / (satellite_mass.0 + parent_mass),
+            ));
+        }
+
+        let step = time_scale.time_scale * dt.0.as_secs_f64();
+
+        for _ in 0..time_scale.iterations {
+            // Get the state of each satellite relative to its parent, along with
+            // the difference in the pull of every other body on the satellite
+            // and its parent
+            let relative_states = satellite_pairs
+                .iter()
+                .map(|(satellite, parent, mu, _)| {
+                    let satellite_position = positions.get(*satellite).unwrap().0;
+                    let parent_position = positions.get(*parent).unwrap().0;
+
+                    let external_acceleration = |body: Entity, position: Vector3<f64>| {
+                        (&entities, &positions, &mass)
+                            .join()
+                            .filter(|(o, _pos, _mass)| {
+                                o != satellite && o != parent && !hierarchical.contains(o.id())
+                            })
+                            .map(|(o, other, mass)| {
+                                let r = other.0 - position;
+                                constant_between(body, o) * mass.0 / r.magnitude2() * r.normalize()
+                            })
+                            .fold(Vector3::zero(), |a, b| a + b)
+                    };
+
+                    (
+                        satellite_position - parent_position,
+                        velocities.get(*satellite).unwrap().0 - velocities.get(*parent).unwrap().0,
+                        external_acceleration(*satellite, satellite_position)
+                            - external_acceleration(*parent, parent_position),
+                        *mu,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // Find pairs of bodies so close that their orbit around each other
+            // can't be resolved by the step, these are stepped analytically
+            let mut encounters = Vec::new();
+            let mut partners = HashMap::new();
+            if close_encounter_settings.analytic {
+                let bodies = (&entities, &positions, &mass, &interaction_handlers)
+                    .join()
+                    .filter(|(e, _pos, _mass, _interaction_handler)| !hierarchical.contains(e.id()))
+                    .collect::<Vec<_>>();
+
+                let mut candidates = Vec::new();
+                for (i, (a, a_pos, a_mass, a_handler)) in bodies.iter().enumerate() {
+                    for (b, b_pos, b_mass, b_handler) in bodies.iter().skip(i + 1) {
+                        // Only bodies that attract each other can be treated as
+                        // a two body problem
+                        if !a_handler.interacts_with(b_handler.body_type)
+                            || !b_handler.interacts_with(a_handler.body_type)
+                        {
+                            continue;
+                        }
+
+                        let mu = constant_between(*a, *b) * (a_mass.0 + b_mass.0);
+                        let dynamical_time = ((b_pos.0 - a_pos.0).magnitude().powi(3) / mu).sqrt();
+                        if dynamical_time < step * close_encounter_settings.min_steps {
+                            candidates.push((dynamical_time, *a, *b));
+                        }
+                    }
+                }
+
+                // Each body can only be in one encounter, the closest first
+                candidates.sort_by(|(a, _, _), (b, _, _)| a.total_cmp(b));
+                for (_, a, b) in candidates {
+                    if !partners.contains_key(&a) && !partners.contains_key(&b) {
+                        partners.insert(a, b);
+                        partners.insert(b, a);
+                        encounters.push((a, b));
+                    }
+                }
+            }
+
+            let update_velocity = |(e, pos, vel, interaction_handler): (
+                Entity,
+                &Position,
+                &mut Velocity,
+                &InteractionHandler,
+            )| {
+                // Get a resultant acceleration using iterators
+                let resultant = (&entities, &positions, &mass, &interaction_handlers)
+                    .join()
+                    // Make sure the body does not try to interact with itself
+                    .filter(|(o, _pos, _mass, _interaction_handler)| e.id() != o.id())
+                    .filter(|(o, _pos, _mass, _interaction_handler)| !hierarchical.contains(o.id()))
+                    // Bodies in a close encounter are stepped analytically
+                    // around each other
+                    .filter(|(o, _pos, _mass, _interaction_handler)| partners.get(&e) != Some(o))
+                    // Stop different types of bodys interacting if it will have negligable effect
+                    // e.g. (planet effecting the sun)
+                    .filter(|(_, _pos, _mass, other_interaction_handler)| {
+                        interaction_handler.interacts_with(other_interaction_handler.body_type)
+                    })
+                    .map(|(o, other, mass, _interaction_handler)| {
+                        // Displacement from one body to the other
+                        let r = other.0 - pos.0;
+
+                        // Apply Newton's equation for universal gravitation
+                        // The equation has been manipulated
+                        // F = m1 * a
+                        // F = G * m1 * m2 / |r|^2
+                        // m1 * a = G * m1 * m2 / |r|^2
+                        // a = G * m2 / |r|^2
+                        let a = constant_between(e, o) * mass.0 / r.magnitude2();
+
+                        // Get the direction of the other body from this
+                        // And project the acceleration into that direction
+                        a * r.normalize()
+                    })
+                    .reduce(|a, b| a + b);
+
+                // Apply the resultant acceleration to the velocity
+                if let Some(resultant) = resultant {
+                    vel.0 += resultant * step;
+                }
+            };
+
+            // Iterate over every entity, in parallel unless the simulation
+            // has to be deterministic
+            // (Satellites are integrated separately in their parent's frame)
+            let velocity_join = (
+                &entities,
+                &positions,
+                &mut velocities,
+                &interaction_handlers,
+            );
+            if deterministic.0 {
+                velocity_join
+                    .join()
+                    .filter(|(e, _pos, _vel, _interaction_handler)| !hierarchical.contains(e.id()))
+                    .for_each(update_velocity);
+            } else {
+                velocity_join
+                    .par_join()
+                    .filter(|(e, _pos, _vel, _interaction_handler)| !hierarchical.contains(e.id()))
+                    .for_each(update_velocity);
+            }
+
+            // Apply the velocity to the position
+            (&entities, &mut positions, &velocities)
+                .par_join()
+                .filter(|(e, _pos, _vel)| !hierarchical.contains(e.id()))
+                .filter(|(e, _pos, _vel)| !partners.contains_key(e))
+                .for_each(|(_, mut pos, vel)| {
+                    pos.0 += vel.0 * step;
+                });
+
+            // Step each close encounter as a two body orbit around the pair's
+            // center of mass, which moves with the rest of the simulation
+            for (a, b) in encounters {
+                let (a_mass, b_mass) = (mass.get(a).unwrap().0, mass.get(b).unwrap().0);
+                let total_mass = a_mass + b_mass;
+                let (a_pos, b_pos) = (positions.get(a).unwrap().0, positions.get(b).unwrap().0);
+                let (a_vel, b_vel) = (velocities.get(a).unwrap().0, velocities.get(b).unwrap().0);
+
+                let center_velocity = (a_vel * a_mass + b_vel * b_mass) / total_mass;
+                let center_position =
+                    (a_pos * a_mass + b_pos * b_mass) / total_mass + center_velocity * step;
+
+                let mu = constant_between(a, b) * total_mass;
+                let (r, v) = kepler::propagate(b_pos - a_pos, b_vel - a_vel, mu, step)
+                    .unwrap_or_else(|| {
+                        // Fall back to the normal integrator if Kepler's
+                        // equation can't be solved
+                        let r = b_pos - a_pos;
+                        let v = b_vel - a_vel - mu / r.magnitude2() * r.normalize() * step;
+                        (r + v * step, v)
+                    });
+
+                positions.get_mut(a).unwrap().0 = center_position - r * (b_mass / total_mass);
+                positions.get_mut(b).unwrap().0 = center_position + r * (a_mass / total_mass);
+                velocities.get_mut(a).unwrap().0 = center_velocity - v * (b_mass / total_mass);
+                velocities.get_mut(b).unwrap().0 = center_velocity + v * (a_mass / total_mass);
+            }
+
+            // Sub-step each satellite around its parent, so that tight orbits
+            // stay stable without shrinking the global step. The parent is
+            // pulled back towards the satellite, so the pair's momentum is kept
+            let mut parent_changes = HashMap::new();
+            let mut sub_stepped = Vec::with_capacity(satellite_pairs.len());
+            for ((satellite, parent, _, mass_fraction), (mut r, mut v, tidal, mu)) in
+                satellite_pairs.iter().zip(relative_states)
+            {
+                let period = std::f64::consts::TAU * (r.magnitude().powi(3) / mu).sqrt();
+                let sub_steps =
+                    ((step * MIN_STEPS_PER_ORBIT / period).ceil() as usize).clamp(1, MAX_SUB_STEPS);
+                let sub_step = step / sub_steps as f64;
+
+                let mut reaction_displacement = Vector3::zero();
+                let mut reaction_velocity = Vector3::zero();
+                for _ in 0..sub_steps {
+                    // The two body acceleration plus the tidal pull of everything else
+                    let pull = -mu / r.magnitude2() * r.normalize();
+                    v += (pull + tidal) * sub_step;
+                    r += v * sub_step;
+
+                    reaction_velocity -= pull * *mass_fraction * sub_step;
+                    reaction_displacement += reaction_velocity * sub_step;
+                }
+
+                let (displacement, velocity_change) = parent_changes
+                    .entry(*parent)
+                    .or_insert((Vector3::zero(), Vector3::zero()));
+                *displacement += reaction_displacement;
+                *velocity_change += reaction_velocity;
+
+                sub_stepped.push((*satellite, *parent, r, v));
+            }
+
+            // Move every parent before placing its satellites, as a parent can
+            // have more than one
+            for (parent, (displacement, velocity_change)) in parent_changes {
+                positions.get_mut(parent).unwrap().0 += displacement;
+                velocities.get_mut(parent).unwrap().0 += velocity_change;
+            }
+            for (satellite, parent, r, v) in sub_stepped {
+                let parent_position = positions.get(parent).unwrap().0;
+                let parent_velocity = velocities.get(parent).unwrap().0;
+                positions.get_mut(satellite).unwrap().0 = parent_position + r;
+                velocities.get_mut(satellite).unwrap().0 = parent_velocity + v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cgmath::{InnerSpace, Vector3};
+    use specs::{Builder, Join, ReadStorage, RunNow, System, World, WorldExt};
+
+    use super::Simulator;
+    use crate::{
+        constants::BIG_G, BodyType, DeltaTime, GravitationalConstant, InteractionFlags,
+        InteractionHandler, Mass, Position, Satellite, TimeScale, Velocity,
+    };
+
+    #[test]
+    fn test_satellite_keeps_momentum() {
+        let mut world = World::new();
+        let mut simulator = Simulator::new();
+        System::setup(&mut simulator, &mut world);
+
+        world.insert(GravitationalConstant(BIG_G));
+        // A day in hour long steps, each sub-stepped for the Moon
+        world.insert(TimeScale::new(86400.0, 24));
+        world.insert(DeltaTime(Duration::from_secs(1)));
+
+        let (earth_mass, moon_mass) = (5.972e24, 7.342e22);
+        let distance = 3.844e8;
+        let speed = (BIG_G * (earth_mass + moon_mass) / distance).sqrt();
+        let earth = world
+            .create_entity()
+            .with(Position(Vector3::new(0.0, 0.0, 0.0)))
+            .with(Velocity(Vector3::new(0.0, 0.0, 0.0)))
+            .with(Mass(earth_mass))
+            .with(InteractionHandler::new(
+                InteractionFlags::all(),
+                BodyType::Planet,
+            ))
+            .build();
+        world
+            .create_entity()
+            .with(Position(Vector3::new(distance, 0.0, 0.0)))
+            .with(Velocity(Vector3::new(0.0, speed, 0.0)))
+            .with(Mass(moon_mass))
+            .with(InteractionHandler::new(
+                InteractionFlags::all(),
+                BodyType::Moon,
+            ))
+            .with(Satellite::new(earth))
+            .build();
+
+        let momentum = |world: &World| {
+            let (velocities, masses): (ReadStorage<Velocity>, ReadStorage<Mass>) =
+                world.system_data();
+            (&velocities, &masses)
+                .join()
+                .fold(Vector3::new(0.0, 0.0, 0.0), |p, (v, m)| p + v.0 * m.0)
+        };
+        let start = momentum(&world);
+
+        // A week, a quarter of an orbit, so the Earth is pulled well off its
+        // starting velocity
+        for _ in 0..7 {
+            simulator.run_now(&world);
+        }
+
+        let drift = (momentum(&world) - start).magnitude();
+        assert!(
+            drift < 1e-9 * moon_mass * speed,
+            "momentum drifted by {} kg m/s",
+            drift
+        );
+    }
+}